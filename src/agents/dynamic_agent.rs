@@ -8,7 +8,7 @@ use serde::{Deserialize, Serialize};
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 use uuid::Uuid;
 
 use crate::forge::hot_loader::{
@@ -381,11 +381,13 @@ impl DynamicAgent {
                 let strategy_guard = current_strategy.read().unwrap();
                 if let Some(strategy) = strategy_guard.as_ref() {
                     // Execute strategy analysis
-                    let signal_strength = unsafe {
-                        (strategy.vtable.analyze)(&market_data as *const MarketData)
-                    };
-                    
-                    Some((signal_strength, strategy.strategy_id.clone()))
+                    match strategy.analyze(&market_data) {
+                        Ok(signal_strength) => Some((signal_strength, strategy.strategy_id.clone())),
+                        Err(e) => {
+                            warn!("⚠️ Agent {} strategy {} analysis failed: {}", agent_id, strategy.strategy_id, e);
+                            None
+                        }
+                    }
                 } else {
                     None
                 }
@@ -0,0 +1,397 @@
+//! BACKTEST - deterministic, event-driven backtesting
+//!
+//! Drives the production `StrategyEngine -> RiskManager` code paths from a
+//! recorded event stream on a virtual clock, filling approved signals with a
+//! simulated executor and reporting equity, drawdown and per-strategy PnL.
+
+pub mod report;
+pub mod simulated_executor;
+pub mod stress;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{debug, info};
+
+use crate::modules::executor::ExecutionStatus;
+use crate::modules::market_replay::{ReplayClock, ReplayEvent, ReplaySpeed};
+use crate::modules::memcoin_strategies::StrategyRegistryConfig;
+use crate::modules::risk::{ApprovedSignal, RiskManager, RiskParameters};
+use crate::modules::strategy::{StrategyEngine, TradingSignal};
+use crate::modules::trade_journal::TradeJournal;
+
+pub use report::{BacktestReport, BacktestTrade, EquityPoint, StrategyPnl, TradeSide};
+pub use simulated_executor::{SimulatedExecutor, SimulatedFillConfig};
+pub use stress::{
+    OutageWindow, RecordedScenario, ScenarioReplay, StressKind, StressScenarioLibrary,
+};
+
+use report::{Portfolio, ReportCounters};
+use simulated_executor::{is_buy, is_sell};
+
+/// Backtest configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestConfig {
+    pub initial_capital: f64,
+    pub risk_params: RiskParameters,
+    pub fill: SimulatedFillConfig,
+    /// Memcoin strategies to run (defaults to the five SWARMGUARD strategies)
+    #[serde(default)]
+    pub strategies: StrategyRegistryConfig,
+    /// Pass approved signals through `RiskManager::apply_kinetic_shield`
+    pub apply_kinetic_shield: bool,
+    /// Liquidate open positions at the last observed price when the stream ends
+    pub close_positions_at_end: bool,
+}
+
+impl Default for BacktestConfig {
+    fn default() -> Self {
+        Self {
+            initial_capital: 10_000.0,
+            risk_params: RiskParameters::default(),
+            fill: SimulatedFillConfig::default(),
+            strategies: StrategyRegistryConfig::default(),
+            apply_kinetic_shield: true,
+            close_positions_at_end: true,
+        }
+    }
+}
+
+/// Event-driven backtester over the real signal pipeline
+pub struct BacktestEngine {
+    config: BacktestConfig,
+    strategy_engine: StrategyEngine,
+    risk_manager: RiskManager,
+    executor: SimulatedExecutor,
+    signal_receiver: mpsc::UnboundedReceiver<TradingSignal>,
+    approved_receiver: mpsc::UnboundedReceiver<ApprovedSignal>,
+    clock: ReplayClock,
+    journal: Option<Arc<TradeJournal>>,
+}
+
+impl BacktestEngine {
+    /// Build the pipeline with the configured memcoin strategies active
+    pub async fn new(config: BacktestConfig) -> Result<Self> {
+        // Components are stepped directly, so their input channels stay idle
+        let (_market_sender, market_receiver) = mpsc::unbounded_channel();
+        let (signal_sender, signal_receiver) = mpsc::unbounded_channel();
+        let (_risk_input_sender, risk_input_receiver) = mpsc::unbounded_channel();
+        let (approved_sender, approved_receiver) = mpsc::unbounded_channel();
+
+        let mut strategy_engine = StrategyEngine::new(market_receiver, signal_sender)
+            .with_capital(config.initial_capital)
+            .with_strategy_config(config.strategies.clone());
+        strategy_engine.initialize_memcoin_strategies().await?;
+        strategy_engine.activate_memcoin_strategies().await?;
+
+        let risk_manager = RiskManager::new(
+            risk_input_receiver,
+            approved_sender,
+            config.risk_params.clone(),
+        );
+
+        Ok(Self {
+            executor: SimulatedExecutor::new(config.fill.clone()),
+            config,
+            strategy_engine,
+            risk_manager,
+            signal_receiver,
+            approved_receiver,
+            clock: ReplayClock::new(ReplaySpeed::AsFastAsPossible),
+            journal: None,
+        })
+    }
+
+    /// Journal every simulated signal, approval and fill
+    pub fn with_journal(mut self, journal: Arc<TradeJournal>) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    pub fn strategy_engine_mut(&mut self) -> &mut StrategyEngine {
+        &mut self.strategy_engine
+    }
+
+    pub fn risk_manager_mut(&mut self) -> &mut RiskManager {
+        &mut self.risk_manager
+    }
+
+    /// Virtual clock of the run
+    pub fn clock(&self) -> ReplayClock {
+        self.clock.clone()
+    }
+
+    /// Run the backtest over `events` (sorted by timestamp before use)
+    pub async fn run(mut self, mut events: Vec<ReplayEvent>) -> Result<BacktestReport> {
+        events.sort_by_key(|e| e.timestamp());
+        info!("🧪 Backtest starting over {} events", events.len());
+
+        let mut portfolio = Portfolio::new(self.config.initial_capital);
+        let mut counters = ReportCounters::default();
+        let mut prices: HashMap<String, f64> = HashMap::new();
+
+        for event in events {
+            let now = event.timestamp();
+            self.clock.advance_to(now).await;
+
+            match event {
+                ReplayEvent::Market(data) => {
+                    counters.market_events += 1;
+                    prices.insert(data.symbol.clone(), data.price);
+                    self.executor.mark(&data.symbol, data.price);
+
+                    self.strategy_engine.process_market_data(data).await?;
+                    self.drain_pipeline(now, &mut portfolio, &mut counters)
+                        .await?;
+                    portfolio.mark_to_market(now, &prices);
+                }
+                ReplayEvent::Transaction(tx) => {
+                    debug!("🧪 Skipping raw transaction {} (no consumer)", tx.signature);
+                }
+            }
+        }
+
+        if self.config.close_positions_at_end {
+            if let Some(now) = self.clock.now() {
+                self.liquidate(now, &mut portfolio, &mut counters).await?;
+                portfolio.mark_to_market(now, &prices);
+            }
+        }
+
+        let report = portfolio.into_report(counters);
+        info!(
+            "🧪 Backtest finished: equity {:.2} → {:.2} ({:+.2}%), max drawdown {:.2}%, {} trades",
+            report.initial_capital,
+            report.final_equity,
+            report.total_return_pct,
+            report.max_drawdown_pct,
+            report.trades.len()
+        );
+        Ok(report)
+    }
+
+    /// Push every signal emitted for the current event through risk and execution
+    async fn drain_pipeline(
+        &mut self,
+        now: chrono::DateTime<chrono::Utc>,
+        portfolio: &mut Portfolio,
+        counters: &mut ReportCounters,
+    ) -> Result<()> {
+        while let Ok(mut signal) = self.signal_receiver.try_recv() {
+            counters.signals_generated += 1;
+            signal.timestamp = now;
+            if let Some(ref journal) = self.journal {
+                journal.record_signal(&signal)?;
+            }
+
+            self.risk_manager.evaluate_signal(signal).await?;
+            let approved = match self.approved_receiver.try_recv() {
+                Ok(approved) => approved,
+                Err(_) => {
+                    counters.signals_rejected += 1;
+                    continue;
+                }
+            };
+
+            let mut approved = if self.config.apply_kinetic_shield {
+                let mut shielded = approved.original_signal.clone();
+                shielded.quantity = approved.approved_quantity;
+                match self.risk_manager.apply_kinetic_shield(shielded).await? {
+                    Some(approved) => approved,
+                    None => {
+                        counters.signals_rejected += 1;
+                        continue;
+                    }
+                }
+            } else {
+                approved
+            };
+            approved.approval_timestamp = now;
+            counters.signals_approved += 1;
+
+            self.fill(approved, now, portfolio, counters).await?;
+        }
+        Ok(())
+    }
+
+    async fn fill(
+        &mut self,
+        approved: ApprovedSignal,
+        now: chrono::DateTime<chrono::Utc>,
+        portfolio: &mut Portfolio,
+        counters: &mut ReportCounters,
+    ) -> Result<()> {
+        let signal = &approved.original_signal;
+        let strategy = signal.strategy_type.to_string();
+
+        let (side, quantity) = if is_buy(&signal.action) {
+            let price = self.executor.last_price(&signal.symbol).unwrap_or(0.0);
+            let unit_cost = price
+                * (1.0 + (self.config.fill.slippage_bps + self.config.fill.fee_bps) / 10_000.0);
+            let affordable = if unit_cost > 0.0 {
+                portfolio.cash() / unit_cost
+            } else {
+                0.0
+            };
+            (
+                TradeSide::Buy,
+                approved.approved_quantity.min(affordable.max(0.0)),
+            )
+        } else if is_sell(&signal.action) {
+            let held = portfolio.position(&strategy, &signal.symbol);
+            (TradeSide::Sell, approved.approved_quantity.min(held))
+        } else {
+            return Ok(());
+        };
+
+        if quantity <= 0.0 {
+            debug!(
+                "🧪 Signal {} not fillable (no cash or position)",
+                signal.signal_id
+            );
+            counters.failed_fills += 1;
+            return Ok(());
+        }
+
+        let result = self.executor.execute(&approved, quantity, now);
+        if let Some(ref journal) = self.journal {
+            journal.record_approved_signal(&approved)?;
+            journal.record_execution(&result)?;
+        }
+
+        if !matches!(result.status, ExecutionStatus::Confirmed) {
+            counters.failed_fills += 1;
+            return Ok(());
+        }
+
+        let realized = portfolio.apply_fill(BacktestTrade {
+            signal_id: result.signal_id.clone(),
+            transaction_id: result.transaction_id.clone(),
+            symbol: signal.symbol.clone(),
+            strategy,
+            side,
+            quantity: result.executed_quantity,
+            price: result.executed_price,
+            fees: result.fees,
+            timestamp: now,
+            realized_pnl: None,
+        });
+
+        // Feed realized PnL back so daily loss limits and KINETIC SHIELD react
+        if let Some(pnl) = realized {
+            self.risk_manager.update_daily_pnl(pnl);
+            self.risk_manager.update_loss_statistics(pnl).await?;
+        }
+        Ok(())
+    }
+
+    async fn liquidate(
+        &mut self,
+        now: chrono::DateTime<chrono::Utc>,
+        portfolio: &mut Portfolio,
+        counters: &mut ReportCounters,
+    ) -> Result<()> {
+        for (strategy, symbol, quantity) in portfolio.open_positions() {
+            let Some((price, fees)) = self.executor.quote(&symbol, TradeSide::Sell, quantity)
+            else {
+                counters.failed_fills += 1;
+                continue;
+            };
+
+            let realized = portfolio.apply_fill(BacktestTrade {
+                signal_id: format!("liquidation_{}_{}", strategy, symbol),
+                transaction_id: format!("liquidation_{}", uuid::Uuid::new_v4()),
+                symbol,
+                strategy,
+                side: TradeSide::Sell,
+                quantity,
+                price,
+                fees,
+                timestamp: now,
+                realized_pnl: None,
+            });
+            if let Some(pnl) = realized {
+                self.risk_manager.update_daily_pnl(pnl);
+                self.risk_manager.update_loss_statistics(pnl).await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::data_ingestor::{DataSource, MarketData};
+
+    fn rising_market() -> Vec<ReplayEvent> {
+        let start = chrono::DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        (0..=20)
+            .map(|i| {
+                ReplayEvent::Market(MarketData {
+                    symbol: "SOL/USDC".to_string(),
+                    price: 100.0 + i as f64,
+                    volume: 1_000_000.0,
+                    timestamp: start + chrono::Duration::seconds(i),
+                    source: DataSource::Replay,
+                    price_change_1h: 0.0,
+                    price_change_24h: 0.0,
+                })
+            })
+            .collect()
+    }
+
+    fn config() -> BacktestConfig {
+        let mut config = BacktestConfig {
+            initial_capital: 1_000_000.0,
+            ..Default::default()
+        };
+        config.risk_params.min_confidence_threshold = 0.5;
+        config
+    }
+
+    #[tokio::test]
+    async fn test_backtest_runs_signal_pipeline() -> Result<()> {
+        let report = BacktestEngine::new(config())
+            .await?
+            .run(rising_market())
+            .await?;
+
+        // Prices 106..=120 trigger the sniping path; KINETIC SHIELD sizes its
+        // 12% per-token cap off RiskManager's fixed 10k capital, not the
+        // 1M initial_capital here, so it stops after 12 fills of 100 units
+        assert_eq!(report.signals_generated, 15);
+        assert_eq!(report.signals_approved, 12);
+        assert_eq!(report.signals_rejected, 3);
+
+        // 12 buys + 1 liquidation
+        assert_eq!(report.trades.len(), 13);
+        assert_eq!(report.equity_curve.len(), 21);
+        assert!(report.final_equity > report.initial_capital);
+        assert_eq!(report.win_rate, 1.0);
+        assert!(report.total_fees > 0.0);
+        assert!(report.per_strategy.contains_key("token_sniping"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_backtest_is_deterministic() -> Result<()> {
+        let first = BacktestEngine::new(config())
+            .await?
+            .run(rising_market())
+            .await?;
+        let second = BacktestEngine::new(config())
+            .await?
+            .run(rising_market())
+            .await?;
+
+        assert_eq!(first.final_equity, second.final_equity);
+        assert_eq!(first.max_drawdown, second.max_drawdown);
+        assert_eq!(first.trades.len(), second.trades.len());
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+//! Backtest accounting and report
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Buy or sell side of a backtest fill
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+/// A single simulated fill
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestTrade {
+    pub signal_id: String,
+    pub transaction_id: String,
+    pub symbol: String,
+    pub strategy: String,
+    pub side: TradeSide,
+    pub quantity: f64,
+    pub price: f64,
+    pub fees: f64,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Realized PnL net of entry and exit fees (sells only)
+    pub realized_pnl: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EquityPoint {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub equity: f64,
+    pub cash: f64,
+    pub position_value: f64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StrategyPnl {
+    pub realized_pnl: f64,
+    pub fees: f64,
+    pub trades: u32,
+    pub winning_trades: u32,
+    pub losing_trades: u32,
+}
+
+impl StrategyPnl {
+    pub fn win_rate(&self) -> f64 {
+        let closed = self.winning_trades + self.losing_trades;
+        if closed == 0 {
+            0.0
+        } else {
+            self.winning_trades as f64 / closed as f64
+        }
+    }
+}
+
+/// Final backtest report
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BacktestReport {
+    pub start: Option<chrono::DateTime<chrono::Utc>>,
+    pub end: Option<chrono::DateTime<chrono::Utc>>,
+    pub initial_capital: f64,
+    pub final_equity: f64,
+    pub total_return_pct: f64,
+    pub max_drawdown: f64,
+    pub max_drawdown_pct: f64,
+    pub win_rate: f64,
+    pub total_fees: f64,
+    pub realized_pnl: f64,
+    pub market_events: u64,
+    pub signals_generated: u64,
+    pub signals_approved: u64,
+    pub signals_rejected: u64,
+    pub failed_fills: u64,
+    pub per_strategy: HashMap<String, StrategyPnl>,
+    pub equity_curve: Vec<EquityPoint>,
+    pub trades: Vec<BacktestTrade>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct OpenPosition {
+    quantity: f64,
+    avg_cost: f64, // per unit, including entry fees
+}
+
+/// Cash + long-only positions per (strategy, symbol)
+#[derive(Debug, Clone)]
+pub(crate) struct Portfolio {
+    initial_capital: f64,
+    cash: f64,
+    positions: HashMap<(String, String), OpenPosition>,
+    per_strategy: HashMap<String, StrategyPnl>,
+    trades: Vec<BacktestTrade>,
+    equity_curve: Vec<EquityPoint>,
+    peak_equity: f64,
+    max_drawdown: f64,
+    max_drawdown_pct: f64,
+}
+
+impl Portfolio {
+    pub(crate) fn new(initial_capital: f64) -> Self {
+        Self {
+            initial_capital,
+            cash: initial_capital,
+            positions: HashMap::new(),
+            per_strategy: HashMap::new(),
+            trades: Vec::new(),
+            equity_curve: Vec::new(),
+            peak_equity: initial_capital,
+            max_drawdown: 0.0,
+            max_drawdown_pct: 0.0,
+        }
+    }
+
+    pub(crate) fn cash(&self) -> f64 {
+        self.cash
+    }
+
+    pub(crate) fn position(&self, strategy: &str, symbol: &str) -> f64 {
+        self.positions
+            .get(&(strategy.to_string(), symbol.to_string()))
+            .map(|p| p.quantity)
+            .unwrap_or(0.0)
+    }
+
+    /// Open positions as (strategy, symbol, quantity)
+    pub(crate) fn open_positions(&self) -> Vec<(String, String, f64)> {
+        let mut open: Vec<_> = self
+            .positions
+            .iter()
+            .filter(|(_, p)| p.quantity > 0.0)
+            .map(|((strategy, symbol), p)| (strategy.clone(), symbol.clone(), p.quantity))
+            .collect();
+        open.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+        open
+    }
+
+    pub(crate) fn apply_fill(&mut self, mut trade: BacktestTrade) -> Option<f64> {
+        let key = (trade.strategy.clone(), trade.symbol.clone());
+        let stats = self.per_strategy.entry(trade.strategy.clone()).or_default();
+        stats.trades += 1;
+        stats.fees += trade.fees;
+
+        match trade.side {
+            TradeSide::Buy => {
+                let cost = trade.quantity * trade.price + trade.fees;
+                self.cash -= cost;
+                let position = self.positions.entry(key).or_default();
+                let total_cost = position.avg_cost * position.quantity + cost;
+                position.quantity += trade.quantity;
+                position.avg_cost = total_cost / position.quantity;
+            }
+            TradeSide::Sell => {
+                let proceeds = trade.quantity * trade.price - trade.fees;
+                self.cash += proceeds;
+                let position = self.positions.entry(key).or_default();
+                let pnl = proceeds - position.avg_cost * trade.quantity;
+                position.quantity -= trade.quantity;
+                if position.quantity <= f64::EPSILON {
+                    position.quantity = 0.0;
+                    position.avg_cost = 0.0;
+                }
+
+                stats.realized_pnl += pnl;
+                if pnl > 0.0 {
+                    stats.winning_trades += 1;
+                } else {
+                    stats.losing_trades += 1;
+                }
+                trade.realized_pnl = Some(pnl);
+            }
+        }
+
+        let pnl = trade.realized_pnl;
+        self.trades.push(trade);
+        pnl
+    }
+
+    pub(crate) fn mark_to_market(
+        &mut self,
+        timestamp: chrono::DateTime<chrono::Utc>,
+        prices: &HashMap<String, f64>,
+    ) {
+        let position_value: f64 = self
+            .positions
+            .iter()
+            .map(|((_, symbol), p)| p.quantity * prices.get(symbol).copied().unwrap_or(p.avg_cost))
+            .sum();
+        let equity = self.cash + position_value;
+
+        if equity > self.peak_equity {
+            self.peak_equity = equity;
+        }
+        let drawdown = self.peak_equity - equity;
+        if drawdown > self.max_drawdown {
+            self.max_drawdown = drawdown;
+        }
+        if self.peak_equity > 0.0 {
+            self.max_drawdown_pct = self
+                .max_drawdown_pct
+                .max(drawdown / self.peak_equity * 100.0);
+        }
+
+        match self.equity_curve.last_mut() {
+            Some(last) if last.timestamp == timestamp => {
+                last.equity = equity;
+                last.cash = self.cash;
+                last.position_value = position_value;
+            }
+            _ => self.equity_curve.push(EquityPoint {
+                timestamp,
+                equity,
+                cash: self.cash,
+                position_value,
+            }),
+        }
+    }
+
+    pub(crate) fn into_report(self, counters: ReportCounters) -> BacktestReport {
+        let final_equity = self
+            .equity_curve
+            .last()
+            .map(|p| p.equity)
+            .unwrap_or(self.cash);
+        let (wins, closed) = self.per_strategy.values().fold((0, 0), |(w, c), s| {
+            (w + s.winning_trades, c + s.winning_trades + s.losing_trades)
+        });
+
+        BacktestReport {
+            start: self.equity_curve.first().map(|p| p.timestamp),
+            end: self.equity_curve.last().map(|p| p.timestamp),
+            initial_capital: self.initial_capital,
+            final_equity,
+            total_return_pct: if self.initial_capital > 0.0 {
+                (final_equity - self.initial_capital) / self.initial_capital * 100.0
+            } else {
+                0.0
+            },
+            max_drawdown: self.max_drawdown,
+            max_drawdown_pct: self.max_drawdown_pct,
+            win_rate: if closed == 0 {
+                0.0
+            } else {
+                wins as f64 / closed as f64
+            },
+            total_fees: self.per_strategy.values().map(|s| s.fees).sum(),
+            realized_pnl: self.per_strategy.values().map(|s| s.realized_pnl).sum(),
+            market_events: counters.market_events,
+            signals_generated: counters.signals_generated,
+            signals_approved: counters.signals_approved,
+            signals_rejected: counters.signals_rejected,
+            failed_fills: counters.failed_fills,
+            per_strategy: self.per_strategy,
+            equity_curve: self.equity_curve,
+            trades: self.trades,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ReportCounters {
+    pub market_events: u64,
+    pub signals_generated: u64,
+    pub signals_approved: u64,
+    pub signals_rejected: u64,
+    pub failed_fills: u64,
+}
@@ -0,0 +1,131 @@
+//! Simulated executor for backtests
+//!
+//! Fills approved signals against the last observed market price at
+//! virtual time, with configurable slippage and fees.
+
+use super::report::TradeSide;
+use crate::modules::executor::{ExecutionResult, ExecutionStatus, FeeBreakdown};
+use crate::modules::risk::ApprovedSignal;
+use crate::modules::strategy::TradeAction;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Cost assumptions for simulated fills
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulatedFillConfig {
+    /// Adverse price move applied to every fill, in basis points
+    pub slippage_bps: f64,
+    /// Proportional fee on notional, in basis points
+    pub fee_bps: f64,
+}
+
+impl Default for SimulatedFillConfig {
+    fn default() -> Self {
+        Self {
+            slippage_bps: 20.0,
+            fee_bps: 10.0, // Matches the 0.1% paper-trade fee
+        }
+    }
+}
+
+/// Executor stand-in driven by the backtest clock
+pub struct SimulatedExecutor {
+    config: SimulatedFillConfig,
+    last_prices: HashMap<String, f64>,
+    fill_counter: u64,
+}
+
+impl SimulatedExecutor {
+    pub fn new(config: SimulatedFillConfig) -> Self {
+        Self {
+            config,
+            last_prices: HashMap::new(),
+            fill_counter: 0,
+        }
+    }
+
+    /// Record the latest observed price for a symbol
+    pub fn mark(&mut self, symbol: &str, price: f64) {
+        self.last_prices.insert(symbol.to_string(), price);
+    }
+
+    pub fn last_price(&self, symbol: &str) -> Option<f64> {
+        self.last_prices.get(symbol).copied()
+    }
+
+    /// Executed price and fees for `quantity` on `side`, or None without a price
+    pub fn quote(&self, symbol: &str, side: TradeSide, quantity: f64) -> Option<(f64, f64)> {
+        let market_price = self.last_price(symbol).filter(|price| *price > 0.0)?;
+        let slippage = self.config.slippage_bps / 10_000.0;
+        let executed_price = match side {
+            TradeSide::Buy => market_price * (1.0 + slippage),
+            TradeSide::Sell => market_price * (1.0 - slippage),
+        };
+        let fees = quantity * executed_price * self.config.fee_bps / 10_000.0;
+        Some((executed_price, fees))
+    }
+
+    /// Fill `quantity` of an approved signal at virtual time `at`.
+    /// Signals for symbols without an observed price fail.
+    pub fn execute(
+        &mut self,
+        signal: &ApprovedSignal,
+        quantity: f64,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> ExecutionResult {
+        let original = &signal.original_signal;
+        self.fill_counter += 1;
+        let transaction_id = format!("backtest_{:08}", self.fill_counter);
+
+        let side = if is_buy(&original.action) {
+            TradeSide::Buy
+        } else {
+            TradeSide::Sell
+        };
+        let (executed_price, fees) = match self.quote(&original.symbol, side, quantity) {
+            Some(quote) => quote,
+            None => {
+                return ExecutionResult {
+                    signal_id: original.signal_id.clone(),
+                    transaction_id,
+                    status: ExecutionStatus::Failed,
+                    executed_quantity: 0.0,
+                    executed_price: 0.0,
+                    fees: 0.0,
+                    timestamp: at,
+                    error_message: Some(format!("No market price for {}", original.symbol)),
+                    symbol: original.symbol.clone(),
+                    action: Some(original.action.clone()),
+                    wallet_id: None,
+                    fee_breakdown: None,
+                }
+            }
+        };
+
+        ExecutionResult {
+            signal_id: original.signal_id.clone(),
+            transaction_id,
+            status: ExecutionStatus::Confirmed,
+            executed_quantity: quantity,
+            executed_price,
+            fees,
+            timestamp: at,
+            error_message: None,
+            symbol: original.symbol.clone(),
+            action: Some(original.action.clone()),
+            wallet_id: None,
+            fee_breakdown: Some(FeeBreakdown {
+                dex_fee: fees,
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+pub(crate) fn is_buy(action: &TradeAction) -> bool {
+    matches!(action, TradeAction::Buy | TradeAction::MarketBuy)
+}
+
+pub(crate) fn is_sell(action: &TradeAction) -> bool {
+    matches!(action, TradeAction::Sell | TradeAction::MarketSell)
+}
@@ -0,0 +1,479 @@
+//! Stress scenarios replayed from recorded market episodes
+//!
+//! A scenario is a recording (JSONL/CSV, as read by `market_replay`) plus the
+//! stress it stands for: RPC outage windows during which the pipeline sees
+//! nothing and cannot trade, amplified volatility, and the fill costs of
+//! drained liquidity. Each scenario runs the full backtest pipeline for a
+//! given `BacktestConfig` and reports max drawdown and time to recovery.
+//!
+//! Scenario manifest (recordings are relative to the manifest):
+//!
+//! ```toml
+//! [[scenarios]]
+//! name = "BONK flash crash"
+//! kind = "flash_crash"
+//! recording = "episodes/bonk_flash_crash.jsonl"
+//!
+//! [[scenarios]]
+//! name = "Helius outage during sell-off"
+//! kind = "rpc_outage"
+//! recording = "episodes/sell_off.jsonl"
+//! outages = [{ start_secs = 120, duration_secs = 300 }]
+//!
+//! [[scenarios]]
+//! name = "WIF LP pull"
+//! kind = "liquidity_pull"
+//! recording = "episodes/wif_lp_pull.csv"
+//! fill = { slippage_bps = 800.0, fee_bps = 25.0 }
+//!
+//! [[scenarios]]
+//! name = "10x volatility"
+//! kind = "volatility_spike"
+//! recording = "episodes/sol_quiet_day.jsonl"
+//! volatility_multiplier = 10.0
+//! ```
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::{BacktestConfig, BacktestEngine, BacktestReport, EquityPoint, SimulatedFillConfig};
+use crate::modules::market_replay::{self, ReplayEvent};
+
+/// Market episode a scenario was recorded from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StressKind {
+    FlashCrash,
+    LiquidityPull,
+    RpcOutage,
+    VolatilitySpike,
+}
+
+/// Span, from the first recorded event, in which no event reaches the pipeline
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OutageWindow {
+    pub start_secs: u64,
+    pub duration_secs: u64,
+}
+
+/// A recorded episode and the stress applied on top of it
+#[derive(Debug, Clone)]
+pub struct RecordedScenario {
+    pub name: String,
+    pub kind: StressKind,
+    pub events: Vec<ReplayEvent>,
+    pub outages: Vec<OutageWindow>,
+    /// Scales every price move away from the episode's opening price
+    pub volatility_multiplier: f64,
+    /// Fill costs during the episode (None = the replayed config's)
+    pub fill: Option<SimulatedFillConfig>,
+}
+
+impl RecordedScenario {
+    pub fn new(name: impl Into<String>, kind: StressKind, mut events: Vec<ReplayEvent>) -> Self {
+        events.sort_by_key(|e| e.timestamp());
+        Self {
+            name: name.into(),
+            kind,
+            events,
+            outages: Vec::new(),
+            volatility_multiplier: 1.0,
+            fill: None,
+        }
+    }
+
+    pub fn with_outage(mut self, start_secs: u64, duration_secs: u64) -> Self {
+        self.outages.push(OutageWindow {
+            start_secs,
+            duration_secs,
+        });
+        self
+    }
+
+    pub fn with_volatility_multiplier(mut self, multiplier: f64) -> Self {
+        self.volatility_multiplier = multiplier;
+        self
+    }
+
+    pub fn with_fill(mut self, fill: SimulatedFillConfig) -> Self {
+        self.fill = Some(fill);
+        self
+    }
+
+    /// The episode as the pipeline experiences it
+    pub fn stressed_events(&self) -> Vec<ReplayEvent> {
+        let Some(start) = self.events.first().map(|e| e.timestamp()) else {
+            return Vec::new();
+        };
+        let mut opening_prices: HashMap<String, f64> = HashMap::new();
+
+        self.events
+            .iter()
+            .filter(|event| {
+                let offset = (event.timestamp() - start).num_seconds().max(0) as u64;
+                !self.outages.iter().any(|outage| {
+                    offset >= outage.start_secs && offset < outage.start_secs + outage.duration_secs
+                })
+            })
+            .cloned()
+            .map(|event| match event {
+                ReplayEvent::Market(mut data) if self.volatility_multiplier != 1.0 => {
+                    let open = *opening_prices
+                        .entry(data.symbol.clone())
+                        .or_insert(data.price);
+                    if open > 0.0 && data.price > 0.0 {
+                        // Log returns scale linearly, so prices stay positive
+                        data.price = open * (data.price / open).powf(self.volatility_multiplier);
+                    }
+                    data.price_change_1h *= self.volatility_multiplier;
+                    data.price_change_24h *= self.volatility_multiplier;
+                    ReplayEvent::Market(data)
+                }
+                event => event,
+            })
+            .collect()
+    }
+
+    /// Run `config` through the stressed episode
+    pub async fn replay(&self, config: &BacktestConfig) -> Result<ScenarioReplay> {
+        let mut config = config.clone();
+        if let Some(ref fill) = self.fill {
+            config.fill = fill.clone();
+        }
+
+        let report = BacktestEngine::new(config)
+            .await?
+            .run(self.stressed_events())
+            .await?;
+        let recovery = DrawdownRecovery::from_equity_curve(&report.equity_curve);
+
+        Ok(ScenarioReplay {
+            name: self.name.clone(),
+            kind: self.kind,
+            max_drawdown: recovery.max_drawdown,
+            time_to_recovery: recovery.time_to_recovery,
+            time_underwater: recovery.time_underwater,
+            report,
+        })
+    }
+}
+
+/// Outcome of replaying one scenario
+#[derive(Debug, Clone)]
+pub struct ScenarioReplay {
+    pub name: String,
+    pub kind: StressKind,
+    /// Largest peak-to-trough equity loss, as a fraction of the peak
+    pub max_drawdown: f64,
+    /// From the peak before the max drawdown until equity regains it;
+    /// None when it is not regained before the episode ends
+    pub time_to_recovery: Option<Duration>,
+    /// Time spent below that peak (to recovery, or to the end of the episode)
+    pub time_underwater: Duration,
+    pub report: BacktestReport,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DrawdownRecovery {
+    pub max_drawdown: f64,
+    pub time_to_recovery: Option<Duration>,
+    pub time_underwater: Duration,
+}
+
+impl DrawdownRecovery {
+    pub(crate) fn from_equity_curve(curve: &[EquityPoint]) -> Self {
+        let Some(first) = curve.first() else {
+            return Self {
+                max_drawdown: 0.0,
+                time_to_recovery: Some(Duration::ZERO),
+                time_underwater: Duration::ZERO,
+            };
+        };
+
+        // Peak (equity, time) that the worst drawdown was measured from
+        let mut peak = (first.equity, first.timestamp);
+        let mut worst_peak = peak;
+        let mut worst_trough = 0;
+        let mut max_drawdown: f64 = 0.0;
+        for (i, point) in curve.iter().enumerate() {
+            if point.equity > peak.0 {
+                peak = (point.equity, point.timestamp);
+            }
+            if peak.0 > 0.0 {
+                let drawdown = (peak.0 - point.equity) / peak.0;
+                if drawdown > max_drawdown {
+                    max_drawdown = drawdown;
+                    worst_peak = peak;
+                    worst_trough = i;
+                }
+            }
+        }
+
+        if max_drawdown <= 0.0 {
+            return Self {
+                max_drawdown: 0.0,
+                time_to_recovery: Some(Duration::ZERO),
+                time_underwater: Duration::ZERO,
+            };
+        }
+
+        let since_peak =
+            |at: chrono::DateTime<chrono::Utc>| (at - worst_peak.1).to_std().unwrap_or_default();
+        let recovered_at = curve[worst_trough..]
+            .iter()
+            .find(|point| point.equity >= worst_peak.0)
+            .map(|point| point.timestamp);
+        let end = curve
+            .last()
+            .map(|point| point.timestamp)
+            .unwrap_or(worst_peak.1);
+
+        Self {
+            max_drawdown,
+            time_to_recovery: recovered_at.map(since_peak),
+            time_underwater: since_peak(recovered_at.unwrap_or(end)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StressManifest {
+    scenarios: Vec<ManifestScenario>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestScenario {
+    name: String,
+    kind: StressKind,
+    recording: PathBuf,
+    #[serde(default)]
+    outages: Vec<OutageWindow>,
+    #[serde(default)]
+    volatility_multiplier: Option<f64>,
+    #[serde(default)]
+    fill: Option<SimulatedFillConfig>,
+}
+
+/// Recorded scenarios a configuration is stress tested against
+#[derive(Debug, Clone, Default)]
+pub struct StressScenarioLibrary {
+    scenarios: Vec<RecordedScenario>,
+}
+
+impl StressScenarioLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_scenario(mut self, scenario: RecordedScenario) -> Self {
+        self.scenarios.push(scenario);
+        self
+    }
+
+    /// Load every scenario of a manifest, with its recording
+    pub fn from_manifest<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read stress manifest: {}", path.display()))?;
+        let manifest: StressManifest = toml::from_str(&content)
+            .map_err(|e| anyhow!("Invalid stress manifest {}: {}", path.display(), e))?;
+        let base = path.parent().unwrap_or_else(|| Path::new("."));
+
+        let mut library = Self::new();
+        for entry in manifest.scenarios {
+            let recording = base.join(&entry.recording);
+            let events = market_replay::load_file(&recording).with_context(|| {
+                format!("Failed to load recording for scenario '{}'", entry.name)
+            })?;
+            if events.is_empty() {
+                return Err(anyhow!("Scenario '{}' has an empty recording", entry.name));
+            }
+
+            let mut scenario = RecordedScenario::new(entry.name, entry.kind, events);
+            scenario.outages = entry.outages;
+            scenario.volatility_multiplier = entry.volatility_multiplier.unwrap_or(1.0);
+            scenario.fill = entry.fill;
+            library = library.with_scenario(scenario);
+        }
+
+        info!(
+            "📼 Loaded {} stress scenarios from {}",
+            library.scenarios.len(),
+            path.display()
+        );
+        Ok(library)
+    }
+
+    pub fn scenarios(&self) -> &[RecordedScenario] {
+        &self.scenarios
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scenarios.is_empty()
+    }
+
+    /// Replay `config` through every scenario, in library order
+    pub async fn replay(&self, config: &BacktestConfig) -> Result<Vec<ScenarioReplay>> {
+        let mut replays = Vec::with_capacity(self.scenarios.len());
+        for scenario in &self.scenarios {
+            let replay = scenario.replay(config).await?;
+            if replay.time_to_recovery.is_none() {
+                warn!(
+                    "📼 {}: {:.2}% drawdown not recovered within the episode",
+                    replay.name,
+                    replay.max_drawdown * 100.0
+                );
+            }
+            replays.push(replay);
+        }
+        Ok(replays)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::data_ingestor::{DataSource, MarketData};
+
+    fn episode(prices: &[f64]) -> Vec<ReplayEvent> {
+        let start = chrono::DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, price)| {
+                ReplayEvent::Market(MarketData {
+                    symbol: "SOL/USDC".to_string(),
+                    price: *price,
+                    volume: 1_000_000.0,
+                    timestamp: start + chrono::Duration::seconds(i as i64),
+                    source: DataSource::Replay,
+                    price_change_1h: 0.0,
+                    price_change_24h: 0.0,
+                })
+            })
+            .collect()
+    }
+
+    fn prices(events: &[ReplayEvent]) -> Vec<f64> {
+        events
+            .iter()
+            .filter_map(|event| match event {
+                ReplayEvent::Market(data) => Some(data.price),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_outage_and_volatility_stress() {
+        let scenario = RecordedScenario::new(
+            "outage",
+            StressKind::RpcOutage,
+            episode(&[100.0, 110.0, 90.0, 80.0, 100.0]),
+        )
+        .with_outage(1, 2);
+        assert_eq!(prices(&scenario.stressed_events()), [100.0, 80.0, 100.0]);
+
+        let spike = RecordedScenario::new(
+            "spike",
+            StressKind::VolatilitySpike,
+            episode(&[100.0, 101.0, 99.0]),
+        )
+        .with_volatility_multiplier(10.0);
+        let stressed = prices(&spike.stressed_events());
+        assert_eq!(stressed[0], 100.0);
+        assert!((stressed[1] - 100.0 * 1.01f64.powi(10)).abs() < 1e-9);
+        assert!((stressed[2] - 100.0 * 0.99f64.powi(10)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_drawdown_and_recovery_from_equity_curve() {
+        let start = chrono::Utc::now();
+        let curve: Vec<EquityPoint> = [100.0, 120.0, 90.0, 96.0, 121.0, 110.0]
+            .iter()
+            .enumerate()
+            .map(|(i, equity)| EquityPoint {
+                timestamp: start + chrono::Duration::seconds(60 * i as i64),
+                equity: *equity,
+                cash: 0.0,
+                position_value: *equity,
+            })
+            .collect();
+
+        // 120 → 90 is the worst; regained three minutes after the peak
+        let recovery = DrawdownRecovery::from_equity_curve(&curve);
+        assert!((recovery.max_drawdown - 0.25).abs() < 1e-12);
+        assert_eq!(recovery.time_to_recovery, Some(Duration::from_secs(180)));
+        assert_eq!(recovery.time_underwater, Duration::from_secs(180));
+
+        // Never regained: underwater until the end of the curve
+        let recovery = DrawdownRecovery::from_equity_curve(&curve[..4]);
+        assert_eq!(recovery.time_to_recovery, None);
+        assert_eq!(recovery.time_underwater, Duration::from_secs(120));
+    }
+
+    #[tokio::test]
+    async fn test_manifest_scenarios_replay_through_pipeline() -> Result<()> {
+        // Rally the sniping path buys into, then a crash and a partial rebound
+        let mut path: Vec<f64> = (0..=20).map(|i| 100.0 + i as f64).collect();
+        path.extend([84.0, 84.0, 90.0, 96.0]);
+
+        let dir = tempfile::tempdir()?;
+        let recording: String = episode(&path)
+            .iter()
+            .map(|event| serde_json::to_string(event).unwrap() + "\n")
+            .collect();
+        std::fs::write(dir.path().join("crash.jsonl"), recording)?;
+        let manifest = dir.path().join("scenarios.toml");
+        std::fs::write(
+            &manifest,
+            r#"
+            [[scenarios]]
+            name = "crash"
+            kind = "flash_crash"
+            recording = "crash.jsonl"
+
+            [[scenarios]]
+            name = "crash, LP pulled"
+            kind = "liquidity_pull"
+            recording = "crash.jsonl"
+            fill = { slippage_bps = 500.0, fee_bps = 25.0 }
+            "#,
+        )?;
+
+        let library = StressScenarioLibrary::from_manifest(&manifest)?;
+        assert_eq!(library.scenarios().len(), 2);
+
+        let mut config = BacktestConfig {
+            initial_capital: 1_000_000.0,
+            close_positions_at_end: false,
+            ..Default::default()
+        };
+        config.risk_params.min_confidence_threshold = 0.5;
+        let replays = library.replay(&config).await?;
+
+        let crash = &replays[0];
+        assert_eq!(crash.kind, StressKind::FlashCrash);
+        assert!(!crash.report.trades.is_empty());
+        assert!(crash.max_drawdown > 0.0);
+        assert_eq!(crash.time_to_recovery, None);
+        assert!(crash.time_underwater > Duration::ZERO);
+        // Worse fills on the same episode cost more
+        assert!(replays[1].max_drawdown > crash.max_drawdown);
+
+        // Twice the per-token exposure, deeper drawdown
+        config.risk_params.kinetic_shield.exposure_limit_per_token *= 2.0;
+        let levered = library.replay(&config).await?;
+        assert!(levered[0].max_drawdown > crash.max_drawdown);
+
+        std::fs::write(dir.path().join("crash.jsonl"), "")?;
+        assert!(StressScenarioLibrary::from_manifest(&manifest).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+//! FORGE DSL Lint - Offline Strategy Review Tool
+//!
+//! Sprawdza pliki `.dsl` bez kompilacji i bez sieci
+//! Diagnostyki ze spanami + kanoniczny format, kod wyjścia dla CI/review
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use overmind_protocol::forge::dsl::{format::format_source, lint};
+use std::fs::{read_to_string, write};
+use std::process::ExitCode;
+
+#[derive(Parser, Debug)]
+#[command(
+    version,
+    about = "FORGE DSL lint: span-annotated diagnostics and canonical formatting for strategy files"
+)]
+struct Args {
+    #[arg(required = true, help = "Strategy files to check")]
+    files: Vec<String>,
+
+    #[arg(short, long, help = "Rewrite files in canonical format")]
+    write: bool,
+
+    #[arg(short, long, help = "Fail if a file is not in canonical format")]
+    check: bool,
+
+    #[arg(short = 'D', long, help = "Treat warnings as errors")]
+    deny_warnings: bool,
+}
+
+#[derive(Default)]
+struct Summary {
+    errors: usize,
+    warnings: usize,
+    unformatted: usize,
+}
+
+fn main() -> Result<ExitCode> {
+    let args = Args::parse();
+    let mut summary = Summary::default();
+
+    for path in &args.files {
+        let source = read_to_string(path).with_context(|| format!("Cannot read {}", path))?;
+
+        let diagnostics = lint::lint(&source);
+        for diagnostic in &diagnostics {
+            eprintln!("{}", lint::annotate(path, &source, diagnostic));
+            if diagnostic.is_error() {
+                summary.errors += 1;
+            } else {
+                summary.warnings += 1;
+            }
+        }
+
+        // Files with syntax errors keep their layout until they parse
+        let Ok(formatted) = format_source(&source) else {
+            continue;
+        };
+        if formatted != source {
+            if args.write {
+                write(path, &formatted).with_context(|| format!("Cannot write {}", path))?;
+                println!("✏️ Formatted {}", path);
+            } else if args.check {
+                eprintln!("❌ {} is not canonically formatted", path);
+                summary.unformatted += 1;
+            }
+        }
+    }
+
+    println!(
+        "📋 {} file(s): {} error(s), {} warning(s){}",
+        args.files.len(),
+        summary.errors,
+        summary.warnings,
+        if args.check {
+            format!(", {} unformatted", summary.unformatted)
+        } else {
+            String::new()
+        }
+    );
+
+    let failed = summary.errors > 0
+        || (args.deny_warnings && summary.warnings > 0)
+        || summary.unformatted > 0;
+    Ok(if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
@@ -67,7 +67,9 @@ impl ForgePoC {
         info!("🧪 Initializing FORGE Proof of Concept test");
         
         // Initialize FORGE
-        let forge_config = ForgeConfig::default();
+        let mut forge_config = ForgeConfig::default();
+        // Mock artifacts are not signed
+        forge_config.safety_params.allow_unsigned_strategies = true;
         let forge = Arc::new(RwLock::new(TheForge::new(forge_config).await?));
         
         // Initialize strategy hot loader
@@ -0,0 +1,27 @@
+//! FORGE Strategy Host - Out-of-Process Strategy Runner
+//!
+//! Uruchamiany przez `StrategyHotLoader` w trybie `IsolationMode::ChildProcess`
+//! Ładuje jedną strategię .so i obsługuje wywołania po Unix socket
+
+use anyhow::Result;
+use clap::Parser;
+use overmind_protocol::forge::strategy_host;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+#[command(
+    version,
+    about = "FORGE strategy host: runs one strategy library in an isolated process"
+)]
+struct Args {
+    #[arg(long, help = "Strategy library (.so) to load")]
+    library: PathBuf,
+
+    #[arg(long, help = "Unix socket the hot loader listens on")]
+    socket: PathBuf,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    strategy_host::run(&args.library, &args.socket)
+}
@@ -10,6 +10,9 @@ use std::ffi::{CStr, CString};
 use std::os::raw::c_char;
 use std::path::Path;
 
+/// Supported strategy ABI version (musi być identyczna z hot_loader.rs)
+const STRATEGY_ABI_VERSION: u32 = 1;
+
 /// Strategy VTable - ABI interface (musi być identyczna z hot_loader.rs)
 #[repr(C)]
 #[derive(Debug)]
@@ -284,6 +287,15 @@ fn test_strategy(strategy_path: &str) -> Result<TestResults> {
 
 fn extract_vtable(library: &Library) -> Result<StrategyVTable> {
     unsafe {
+        let abi_version: Symbol<unsafe extern "C" fn() -> u32> = 
+            library.get(b"strategy_abi_version")
+                .map_err(|e| anyhow!("Failed to load ABI version: {}", e))?;
+        
+        let version = abi_version();
+        if version != STRATEGY_ABI_VERSION {
+            return Err(anyhow!("ABI version {} (expected {})", version, STRATEGY_ABI_VERSION));
+        }
+        
         let analyze: Symbol<unsafe extern "C" fn(*const MarketData) -> f64> = 
             library.get(b"strategy_analyze")
                 .map_err(|e| anyhow!("Failed to load analyze function: {}", e))?;
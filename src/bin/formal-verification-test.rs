@@ -107,6 +107,7 @@ strategy BoundaryStrategy:
                 required_level: VerificationLevel::Safety,
                 generate_proofs: true,
                 generate_counterexamples: true,
+                max_exposure: 0.5,
             },
         }
     }
@@ -197,6 +198,7 @@ impl FormalVerificationTest {
             required_level: VerificationLevel::Complete,
             generate_proofs: true,
             generate_counterexamples: true,
+            max_exposure: 0.5,
         };
         
         let _custom_engine = FormalVerificationEngine::new(custom_config);
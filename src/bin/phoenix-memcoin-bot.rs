@@ -22,8 +22,9 @@ use tokio::time::{interval, sleep};
 use tracing::{info, warn, error, debug};
 use tracing_subscriber;
 
+use overmind_protocol::modules::data_ingestor::{DataSource, MarketData};
 use overmind_protocol::modules::memcoin_strategies::{
-    PhoenixEngine, PhoenixConfig, MarketSignal, WhaleAlert, WhaleAction, MemcoinStrategy
+    PhoenixEngine, PhoenixConfig, MarketEvent, WhaleAlert, WhaleAction, MemcoinStrategy
 };
 use overmind_protocol::modules::strategy::TradingSignal;
 use serde_json;
@@ -173,20 +174,24 @@ impl PhoenixBot {
         // Simulate market signal processing
         // In real implementation, this would process signals from Helius/market data
 
-        let market_signal = MarketSignal {
-            token: "BONK".to_string(),
+        let market_data = MarketData {
+            symbol: "BONK".to_string(),
             price: 0.000015,
             volume: 1500.0,
             timestamp: chrono::Utc::now(),
+            source: DataSource::Helius,
+            price_change_1h: 0.0,
+            price_change_24h: 0.0,
         };
 
         debug!("🔍 Processing market signal: {} @ {:.6} with volume {}",
-               market_signal.token, market_signal.price, market_signal.volume);
+               market_data.symbol, market_data.price, market_data.volume);
 
-        if let Some(trading_signal) = self.engine.process_signal(&market_signal as &(dyn std::any::Any + Send + Sync)).await? {
+        let event = MarketEvent::Tick(market_data);
+        if let Some(trading_signal) = self.engine.process_signal(&event).await? {
             self.execute_trading_signal(trading_signal).await?;
         } else {
-            debug!("❌ No trading signal generated for {}", market_signal.token);
+            debug!("❌ No trading signal generated for {}", event.token());
         }
 
         Ok(())
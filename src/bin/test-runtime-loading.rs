@@ -85,7 +85,9 @@ impl RuntimeLoadingTest {
         let strategy_loader = Arc::new(RwLock::new(StrategyHotLoader::new()?));
         
         // Initialize FORGE (optional for this test)
-        let forge_config = ForgeConfig::default();
+        let mut forge_config = ForgeConfig::default();
+        // Mock artifacts are not signed
+        forge_config.safety_params.allow_unsigned_strategies = true;
         let forge = Arc::new(RwLock::new(TheForge::new(forge_config).await?));
         
         // Initialize runtime loader
@@ -0,0 +1,851 @@
+//! ARTIFACT REGISTRY - Content-Addressed Strategy Artifacts
+//!
+//! Rejestr skompilowanych strategii (.so): bloby adresowane SHA-256,
+//! manifesty z proweniencją builda podpisane Ed25519, historia wersji per agent
+//! Garbage collection starych wersji, wymienny backend storage
+//!
+//! Layout (identical for every backend, keys are `/`-separated):
+//! - `blobs/{sha256}` - artifact bytes
+//! - `manifests/{sha256}.json` - signed [`ArtifactManifest`]
+//! - `refs/{cache_key}` - digest built from a given [`ArtifactProvenance`]
+//! - `agents/{agent_id}/history.json` - [`ArtifactVersion`]s, oldest first
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+
+/// Domain separator so an artifact signature can never be replayed as
+/// a signature over anything else signed with the same key
+const SIGNATURE_DOMAIN: &[u8] = b"overmind-forge-artifact-v1\0";
+
+/// Prefix of in-flight files in [`LocalFsStore`]; never listed
+const TEMP_PREFIX: &str = ".tmp-";
+
+/// Key-value blob storage behind the registry.
+///
+/// Semantics follow object stores rather than filesystems so that a local
+/// backend and an S3/GCS one are interchangeable: flat keys, `put` replaces
+/// the whole value, `get`/`delete` of a missing key is not an error and
+/// `list` returns every key under a prefix, sorted.
+#[async_trait]
+pub trait ArtifactStore: Send + Sync + fmt::Debug {
+    async fn put(&self, key: &str, bytes: &[u8]) -> Result<()>;
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>>;
+    async fn delete(&self, key: &str) -> Result<()>;
+    async fn list(&self, prefix: &str) -> Result<Vec<String>>;
+
+    async fn exists(&self, key: &str) -> Result<bool> {
+        Ok(self.get(key).await?.is_some())
+    }
+}
+
+/// Keys are relative, `/`-separated and never escape the store root
+fn validate_key(key: &str) -> Result<()> {
+    let valid = !key.is_empty()
+        && !key.starts_with('/')
+        && key.split('/').all(|segment| {
+            !segment.is_empty()
+                && segment != "."
+                && segment != ".."
+                && !segment.starts_with(TEMP_PREFIX)
+                && !segment.contains('\\')
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(anyhow!("Invalid artifact store key: {:?}", key))
+    }
+}
+
+/// Store rooted in a local directory; writes are atomic (temp file + rename)
+#[derive(Debug, Clone)]
+pub struct LocalFsStore {
+    root: PathBuf,
+}
+
+impl LocalFsStore {
+    pub fn new(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        fs::create_dir_all(&root).map_err(|e| {
+            anyhow!(
+                "Failed to create artifact store at {}: {}",
+                root.display(),
+                e
+            )
+        })?;
+        Ok(Self { root })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf> {
+        validate_key(key)?;
+        Ok(self.root.join(key))
+    }
+
+    fn collect(&self, dir: &Path, key_prefix: &str, keys: &mut Vec<String>) -> Result<()> {
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(anyhow!("Failed to list {}: {}", dir.display(), e)),
+        };
+        for entry in entries {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with(TEMP_PREFIX) {
+                continue;
+            }
+            let key = format!("{}{}", key_prefix, name);
+            if entry.file_type()?.is_dir() {
+                self.collect(&entry.path(), &format!("{}/", key), keys)?;
+            } else {
+                keys.push(key);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ArtifactStore for LocalFsStore {
+    async fn put(&self, key: &str, bytes: &[u8]) -> Result<()> {
+        let path = self.path(key)?;
+        let dir = path.parent().unwrap_or(&self.root);
+        fs::create_dir_all(dir)?;
+
+        let temp = dir.join(format!("{}{}", TEMP_PREFIX, uuid::Uuid::new_v4()));
+        fs::write(&temp, bytes).map_err(|e| anyhow!("Failed to write {}: {}", key, e))?;
+        fs::rename(&temp, &path).map_err(|e| {
+            let _ = fs::remove_file(&temp);
+            anyhow!("Failed to store {}: {}", key, e)
+        })
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        match fs::read(self.path(key)?) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(anyhow!("Failed to read {}: {}", key, e)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        match fs::remove_file(self.path(key)?) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(anyhow!("Failed to delete {}: {}", key, e)),
+        }
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        self.collect(&self.root, "", &mut keys)?;
+        keys.retain(|key| key.starts_with(prefix));
+        keys.sort();
+        Ok(keys)
+    }
+}
+
+/// In-process store with object-store semantics, for tests and dry runs
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    objects: parking_lot::RwLock<BTreeMap<String, Vec<u8>>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ArtifactStore for MemoryStore {
+    async fn put(&self, key: &str, bytes: &[u8]) -> Result<()> {
+        validate_key(key)?;
+        self.objects.write().insert(key.to_string(), bytes.to_vec());
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        validate_key(key)?;
+        Ok(self.objects.read().get(key).cloned())
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        validate_key(key)?;
+        self.objects.write().remove(key);
+        Ok(())
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        Ok(self
+            .objects
+            .read()
+            .keys()
+            .filter(|key| key.starts_with(prefix))
+            .cloned()
+            .collect())
+    }
+}
+
+/// What an artifact was built from; equal provenance means a reusable build
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactProvenance {
+    /// Hash of the strategy DSL and its risk model
+    pub dsl_hash: String,
+    /// Version of the FORGE compiler that generated the crate
+    pub compiler_version: String,
+    /// `rustc --version` of the toolchain that built it
+    pub rustc_version: String,
+    /// Target triple
+    pub target: String,
+    pub optimization_level: String,
+}
+
+impl ArtifactProvenance {
+    /// Key under `refs/` for builds with exactly this provenance
+    pub fn cache_key(&self) -> String {
+        let mut hasher = Sha256::new();
+        for field in [
+            &self.dsl_hash,
+            &self.compiler_version,
+            &self.rustc_version,
+            &self.target,
+            &self.optimization_level,
+        ] {
+            hasher.update(field.as_bytes());
+            hasher.update([0]);
+        }
+        format!("{:x}", hasher.finalize())[..32].to_string()
+    }
+}
+
+/// Registry record of one artifact
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArtifactManifest {
+    /// SHA-256 of the artifact bytes, hex
+    pub digest: String,
+    pub size_bytes: u64,
+    pub strategy_id: String,
+    #[serde(flatten)]
+    pub provenance: ArtifactProvenance,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Ed25519 public key of the signer, hex
+    pub signer: Option<String>,
+    /// Ed25519 signature over [`ArtifactManifest::signing_payload`], hex
+    pub signature: Option<String>,
+}
+
+impl ArtifactManifest {
+    /// Bytes covered by the signature: every field except the signature
+    /// itself, so tampering with the digest or the provenance breaks it
+    pub fn signing_payload(&self) -> Vec<u8> {
+        let unsigned = Self {
+            signature: None,
+            ..self.clone()
+        };
+        let mut payload = SIGNATURE_DOMAIN.to_vec();
+        payload.extend(serde_json::to_vec(&unsigned).expect("manifest serializes"));
+        payload
+    }
+
+    pub fn is_signed(&self) -> bool {
+        self.signature.is_some()
+    }
+}
+
+/// One entry of an agent's deployment history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArtifactVersion {
+    /// Monotonic per agent, starting at 1; survives garbage collection
+    pub version: u32,
+    pub digest: String,
+    pub strategy_id: String,
+    pub published_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// What a garbage collection pass removed
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GcReport {
+    pub versions_dropped: usize,
+    pub blobs_removed: usize,
+    pub manifests_removed: usize,
+    pub refs_removed: usize,
+    pub bytes_freed: u64,
+}
+
+/// Parse a hex-encoded 32-byte Ed25519 secret key
+pub fn signing_key_from_hex(hex_key: &str) -> Result<SigningKey> {
+    let bytes: [u8; 32] = hex::decode(hex_key.trim())
+        .map_err(|e| anyhow!("Signing key is not hex: {}", e))?
+        .try_into()
+        .map_err(|_| anyhow!("Signing key must be 32 bytes"))?;
+    Ok(SigningKey::from_bytes(&bytes))
+}
+
+/// Parse a hex-encoded Ed25519 public key
+pub fn verifying_key_from_hex(hex_key: &str) -> Result<VerifyingKey> {
+    let bytes: [u8; 32] = hex::decode(hex_key.trim())
+        .map_err(|e| anyhow!("Public key is not hex: {}", e))?
+        .try_into()
+        .map_err(|_| anyhow!("Public key must be 32 bytes"))?;
+    VerifyingKey::from_bytes(&bytes).map_err(|e| anyhow!("Invalid public key: {}", e))
+}
+
+/// Read a signing key file holding the hex secret key
+pub fn load_signing_key(path: impl AsRef<Path>) -> Result<SigningKey> {
+    let path = path.as_ref();
+    let content = fs::read_to_string(path)
+        .map_err(|e| anyhow!("Failed to read signing key {}: {}", path.display(), e))?;
+    signing_key_from_hex(&content)
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+fn blob_key(digest: &str) -> String {
+    format!("blobs/{}", digest)
+}
+
+fn manifest_key(digest: &str) -> String {
+    format!("manifests/{}.json", digest)
+}
+
+fn history_key(agent_id: &str) -> Result<String> {
+    if agent_id.is_empty() || agent_id.contains('/') {
+        return Err(anyhow!(
+            "Invalid agent id for artifact history: {:?}",
+            agent_id
+        ));
+    }
+    Ok(format!("agents/{}/history.json", agent_id))
+}
+
+/// Content-addressed, signed artifact registry
+#[derive(Debug)]
+pub struct ArtifactRegistry {
+    store: Arc<dyn ArtifactStore>,
+
+    /// Signs manifests on publish; its public key is trusted automatically
+    signing_key: Option<SigningKey>,
+
+    /// Public keys accepted by `verify`
+    trusted_keys: Vec<VerifyingKey>,
+
+    /// Serializes publish and gc so a collection never sees a half-written
+    /// publish from this process
+    write_lock: Mutex<()>,
+}
+
+impl ArtifactRegistry {
+    /// Create registry over a storage backend
+    pub fn new(store: Arc<dyn ArtifactStore>) -> Self {
+        Self {
+            store,
+            signing_key: None,
+            trusted_keys: Vec::new(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Registry in a local directory
+    pub fn local(root: impl Into<PathBuf>) -> Result<Self> {
+        Ok(Self::new(Arc::new(LocalFsStore::new(root)?)))
+    }
+
+    /// Sign every published manifest with this key (and trust it)
+    pub fn with_signing_key(mut self, signing_key: SigningKey) -> Self {
+        self.trusted_keys.push(signing_key.verifying_key());
+        self.signing_key = Some(signing_key);
+        self
+    }
+
+    /// Accept artifacts signed by this key
+    pub fn with_trusted_key(mut self, key: VerifyingKey) -> Self {
+        self.trusted_keys.push(key);
+        self
+    }
+
+    /// Whether `verify` can succeed at all
+    pub fn has_trusted_keys(&self) -> bool {
+        !self.trusted_keys.is_empty()
+    }
+
+    pub fn store(&self) -> &Arc<dyn ArtifactStore> {
+        &self.store
+    }
+
+    /// Store artifact bytes, sign the manifest and append to the agent's history
+    pub async fn publish(
+        &self,
+        agent_id: &str,
+        strategy_id: &str,
+        bytes: &[u8],
+        provenance: ArtifactProvenance,
+    ) -> Result<ArtifactManifest> {
+        let history_key = history_key(agent_id)?;
+        let digest = sha256_hex(bytes);
+        let _guard = self.write_lock.lock().await;
+
+        // Content-addressed: identical bytes are stored once
+        if !self.store.exists(&blob_key(&digest)).await? {
+            self.store.put(&blob_key(&digest), bytes).await?;
+        }
+
+        let mut manifest = ArtifactManifest {
+            digest: digest.clone(),
+            size_bytes: bytes.len() as u64,
+            strategy_id: strategy_id.to_string(),
+            provenance,
+            created_at: chrono::Utc::now(),
+            signer: self
+                .signing_key
+                .as_ref()
+                .map(|key| hex::encode(key.verifying_key().as_bytes())),
+            signature: None,
+        };
+        if let Some(key) = &self.signing_key {
+            let signature = key.sign(&manifest.signing_payload());
+            manifest.signature = Some(hex::encode(signature.to_bytes()));
+        } else {
+            warn!("⚠️ Publishing unsigned artifact {}", &digest[..12]);
+        }
+        self.store
+            .put(
+                &manifest_key(&digest),
+                &serde_json::to_vec_pretty(&manifest)?,
+            )
+            .await?;
+        self.store
+            .put(
+                &format!("refs/{}", manifest.provenance.cache_key()),
+                digest.as_bytes(),
+            )
+            .await?;
+
+        let mut history = self.read_history(&history_key).await?;
+        let version = history.last().map_or(1, |last| last.version + 1);
+        history.push(ArtifactVersion {
+            version,
+            digest: digest.clone(),
+            strategy_id: strategy_id.to_string(),
+            published_at: manifest.created_at,
+        });
+        self.store
+            .put(&history_key, &serde_json::to_vec_pretty(&history)?)
+            .await?;
+
+        info!(
+            "📦 Published artifact {} for agent {} as v{} ({} bytes)",
+            &digest[..12],
+            agent_id,
+            version,
+            bytes.len()
+        );
+        Ok(manifest)
+    }
+
+    /// Manifest of an artifact, unverified
+    pub async fn manifest(&self, digest: &str) -> Result<Option<ArtifactManifest>> {
+        match self.store.get(&manifest_key(digest)).await? {
+            Some(bytes) => {
+                Ok(Some(serde_json::from_slice(&bytes).map_err(|e| {
+                    anyhow!("Corrupt manifest for {}: {}", digest, e)
+                })?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Previously built artifact with exactly this provenance
+    pub async fn lookup(
+        &self,
+        provenance: &ArtifactProvenance,
+    ) -> Result<Option<ArtifactManifest>> {
+        let Some(digest) = self
+            .store
+            .get(&format!("refs/{}", provenance.cache_key()))
+            .await?
+        else {
+            return Ok(None);
+        };
+        let digest = String::from_utf8(digest)?;
+        match self.manifest(&digest).await? {
+            Some(manifest) if manifest.provenance == *provenance => Ok(Some(manifest)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Check that `bytes` are the artifact `manifest` describes and that the
+    /// manifest carries a valid signature from a trusted key
+    pub fn verify(&self, manifest: &ArtifactManifest, bytes: &[u8]) -> Result<()> {
+        let digest = sha256_hex(bytes);
+        if digest != manifest.digest || bytes.len() as u64 != manifest.size_bytes {
+            return Err(anyhow!(
+                "Artifact digest mismatch: expected {}, got {}",
+                manifest.digest,
+                digest
+            ));
+        }
+
+        let (Some(signer), Some(signature)) = (&manifest.signer, &manifest.signature) else {
+            return Err(anyhow!("Artifact {} is not signed", manifest.digest));
+        };
+        let signer = verifying_key_from_hex(signer)?;
+        if !self.trusted_keys.contains(&signer) {
+            return Err(anyhow!(
+                "Artifact {} is signed by an untrusted key {}",
+                manifest.digest,
+                hex::encode(signer.as_bytes())
+            ));
+        }
+        let signature: [u8; 64] = hex::decode(signature)
+            .map_err(|e| anyhow!("Signature is not hex: {}", e))?
+            .try_into()
+            .map_err(|_| anyhow!("Signature must be 64 bytes"))?;
+        signer
+            .verify_strict(
+                &manifest.signing_payload(),
+                &Signature::from_bytes(&signature),
+            )
+            .map_err(|_| anyhow!("Invalid signature on artifact {}", manifest.digest))
+    }
+
+    /// Fetch and verify artifact bytes
+    pub async fn fetch(&self, digest: &str) -> Result<(ArtifactManifest, Vec<u8>)> {
+        let manifest = self
+            .manifest(digest)
+            .await?
+            .ok_or_else(|| anyhow!("Unknown artifact: {}", digest))?;
+        let bytes = self
+            .store
+            .get(&blob_key(digest))
+            .await?
+            .ok_or_else(|| anyhow!("Artifact blob missing: {}", digest))?;
+        self.verify(&manifest, &bytes)?;
+        Ok((manifest, bytes))
+    }
+
+    /// Verify a file on disk against its registry manifest, e.g. right before
+    /// loading it; `expected_digest` is the checksum the caller was given
+    pub async fn verify_file(
+        &self,
+        path: &Path,
+        expected_digest: &str,
+    ) -> Result<ArtifactManifest> {
+        let bytes =
+            fs::read(path).map_err(|e| anyhow!("Failed to read {}: {}", path.display(), e))?;
+        self.verify_bytes(&bytes, expected_digest)
+            .await
+            .map_err(|e| anyhow!("{}: {}", path.display(), e))
+    }
+
+    /// Verify artifact bytes already in memory against their registry
+    /// manifest, so the verified bytes are exactly the ones used afterwards
+    pub async fn verify_bytes(
+        &self,
+        bytes: &[u8],
+        expected_digest: &str,
+    ) -> Result<ArtifactManifest> {
+        let digest = sha256_hex(bytes);
+        if digest != expected_digest {
+            return Err(anyhow!(
+                "Artifact does not match its checksum: expected {}, got {}",
+                expected_digest,
+                digest
+            ));
+        }
+        let manifest = self
+            .manifest(&digest)
+            .await?
+            .ok_or_else(|| anyhow!("Artifact {} is not in the registry", digest))?;
+        self.verify(&manifest, bytes)?;
+        Ok(manifest)
+    }
+
+    /// Verify an artifact and write it to `path`
+    pub async fn materialize(&self, digest: &str, path: &Path) -> Result<ArtifactManifest> {
+        let (manifest, bytes) = match self.fetch(digest).await {
+            Ok(fetched) => fetched,
+            // Unsigned registries can still serve their own cache
+            Err(e) if !self.has_trusted_keys() => {
+                debug!("Materializing unverified artifact {}: {}", digest, e);
+                let manifest = self
+                    .manifest(digest)
+                    .await?
+                    .ok_or_else(|| anyhow!("Unknown artifact: {}", digest))?;
+                let bytes = self
+                    .store
+                    .get(&blob_key(digest))
+                    .await?
+                    .filter(|bytes| sha256_hex(bytes) == digest)
+                    .ok_or_else(|| anyhow!("Artifact blob missing or corrupt: {}", digest))?;
+                (manifest, bytes)
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, &bytes)
+            .map_err(|e| anyhow!("Failed to write artifact to {}: {}", path.display(), e))?;
+        Ok(manifest)
+    }
+
+    /// Versions published for an agent, oldest first
+    pub async fn history(&self, agent_id: &str) -> Result<Vec<ArtifactVersion>> {
+        self.read_history(&history_key(agent_id)?).await
+    }
+
+    /// Most recent version published for an agent
+    pub async fn latest(&self, agent_id: &str) -> Result<Option<ArtifactVersion>> {
+        Ok(self.history(agent_id).await?.pop())
+    }
+
+    async fn read_history(&self, key: &str) -> Result<Vec<ArtifactVersion>> {
+        match self.store.get(key).await? {
+            Some(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| anyhow!("Corrupt artifact history {}: {}", key, e)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Keep the newest `keep_versions` versions per agent and delete every
+    /// blob, manifest and cache ref no kept version points to
+    pub async fn gc(&self, keep_versions: usize) -> Result<GcReport> {
+        let _guard = self.write_lock.lock().await;
+        let mut report = GcReport::default();
+        let mut live = HashSet::new();
+
+        for key in self.store.list("agents/").await? {
+            if !key.ends_with("/history.json") {
+                continue;
+            }
+            let mut history = self.read_history(&key).await?;
+            if history.len() > keep_versions {
+                let dropped = history.len() - keep_versions;
+                history.drain(..dropped);
+                report.versions_dropped += dropped;
+                self.store
+                    .put(&key, &serde_json::to_vec_pretty(&history)?)
+                    .await?;
+            }
+            live.extend(history.into_iter().map(|version| version.digest));
+        }
+
+        for key in self.store.list("manifests/").await? {
+            let digest = key
+                .trim_start_matches("manifests/")
+                .trim_end_matches(".json");
+            if !live.contains(digest) {
+                self.store.delete(&key).await?;
+                report.manifests_removed += 1;
+            }
+        }
+        for key in self.store.list("blobs/").await? {
+            if !live.contains(key.trim_start_matches("blobs/")) {
+                if let Some(bytes) = self.store.get(&key).await? {
+                    report.bytes_freed += bytes.len() as u64;
+                }
+                self.store.delete(&key).await?;
+                report.blobs_removed += 1;
+            }
+        }
+        for key in self.store.list("refs/").await? {
+            let target = self.store.get(&key).await?.unwrap_or_default();
+            if !live.contains(String::from_utf8_lossy(&target).as_ref()) {
+                self.store.delete(&key).await?;
+                report.refs_removed += 1;
+            }
+        }
+
+        info!(
+            "🧹 Artifact GC: dropped {} versions, {} blobs ({} bytes)",
+            report.versions_dropped, report.blobs_removed, report.bytes_freed
+        );
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// Contract every `ArtifactStore` backend must satisfy
+    async fn check_store_contract(store: &dyn ArtifactStore) {
+        assert_eq!(store.get("blobs/missing").await.unwrap(), None);
+        assert!(!store.exists("blobs/missing").await.unwrap());
+        store.delete("blobs/missing").await.unwrap();
+
+        store.put("blobs/a", b"first").await.unwrap();
+        store.put("blobs/a", b"second").await.unwrap();
+        store.put("agents/x/history.json", b"[]").await.unwrap();
+        store.put("blobs/b", b"").await.unwrap();
+        assert_eq!(store.get("blobs/a").await.unwrap().unwrap(), b"second");
+        assert_eq!(store.get("blobs/b").await.unwrap().unwrap(), b"");
+        assert!(store.exists("blobs/b").await.unwrap());
+
+        assert_eq!(store.list("blobs/").await.unwrap(), ["blobs/a", "blobs/b"]);
+        assert_eq!(
+            store.list("").await.unwrap(),
+            ["agents/x/history.json", "blobs/a", "blobs/b"]
+        );
+        assert!(store.list("refs/").await.unwrap().is_empty());
+
+        store.delete("blobs/a").await.unwrap();
+        assert_eq!(store.list("blobs/").await.unwrap(), ["blobs/b"]);
+
+        for key in [
+            "",
+            "/etc/passwd",
+            "blobs/../escape",
+            "blobs//a",
+            "blobs/.tmp-x",
+        ] {
+            assert!(store.put(key, b"x").await.is_err(), "{:?}", key);
+        }
+    }
+
+    fn provenance(dsl_hash: &str) -> ArtifactProvenance {
+        ArtifactProvenance {
+            dsl_hash: dsl_hash.to_string(),
+            compiler_version: "0.1.0".to_string(),
+            rustc_version: "rustc 1.80.0".to_string(),
+            target: "x86_64-unknown-linux-gnu".to_string(),
+            optimization_level: "3".to_string(),
+        }
+    }
+
+    fn signing_key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; 32])
+    }
+
+    #[tokio::test]
+    async fn test_backends_satisfy_store_contract() {
+        let dir = TempDir::new().unwrap();
+        check_store_contract(&LocalFsStore::new(dir.path()).unwrap()).await;
+        check_store_contract(&MemoryStore::new()).await;
+    }
+
+    #[tokio::test]
+    async fn test_publish_verify_and_tamper() {
+        let dir = TempDir::new().unwrap();
+        let registry = ArtifactRegistry::local(dir.path().join("registry"))
+            .unwrap()
+            .with_signing_key(signing_key(7));
+
+        let manifest = registry
+            .publish(
+                "agent-1",
+                "momentum_v1",
+                b"\x7fELF strategy",
+                provenance("abc"),
+            )
+            .await
+            .unwrap();
+        assert!(manifest.is_signed());
+        assert_eq!(manifest.digest, sha256_hex(b"\x7fELF strategy"));
+
+        let (fetched, bytes) = registry.fetch(&manifest.digest).await.unwrap();
+        assert_eq!(fetched, manifest);
+        assert_eq!(bytes, b"\x7fELF strategy");
+        assert_eq!(
+            registry.lookup(&provenance("abc")).await.unwrap(),
+            Some(manifest.clone())
+        );
+        assert_eq!(registry.lookup(&provenance("abd")).await.unwrap(), None);
+
+        // Swapped bytes, edited provenance and foreign keys are all rejected
+        assert!(registry.verify(&manifest, b"\x7fELF evil").is_err());
+        let edited = ArtifactManifest {
+            strategy_id: "other".to_string(),
+            ..manifest.clone()
+        };
+        assert!(registry
+            .verify(&edited, b"\x7fELF strategy")
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid signature"));
+        let stranger = ArtifactRegistry::new(Arc::new(MemoryStore::new()))
+            .with_trusted_key(signing_key(8).verifying_key());
+        assert!(stranger
+            .verify(&manifest, b"\x7fELF strategy")
+            .unwrap_err()
+            .to_string()
+            .contains("untrusted key"));
+
+        let path = dir.path().join("strategy.so");
+        fs::write(&path, b"\x7fELF strategy").unwrap();
+        registry.verify_file(&path, &manifest.digest).await.unwrap();
+        fs::write(&path, b"\x7fELF evil").unwrap();
+        assert!(registry.verify_file(&path, &manifest.digest).await.is_err());
+
+        let unsigned = ArtifactRegistry::new(Arc::new(MemoryStore::new()))
+            .with_trusted_key(signing_key(7).verifying_key());
+        let manifest = unsigned
+            .publish("agent-1", "momentum_v1", b"plain", provenance("abc"))
+            .await
+            .unwrap();
+        assert!(unsigned
+            .fetch(&manifest.digest)
+            .await
+            .unwrap_err()
+            .to_string()
+            .contains("not signed"));
+    }
+
+    #[tokio::test]
+    async fn test_history_and_gc() {
+        let registry =
+            ArtifactRegistry::new(Arc::new(MemoryStore::new())).with_signing_key(signing_key(1));
+        for (i, bytes) in [b"v1", b"v2", b"v3"].iter().enumerate() {
+            registry
+                .publish("alpha", "s", *bytes, provenance(&format!("dsl{}", i)))
+                .await
+                .unwrap();
+        }
+        // Same bytes as alpha's v3: stored once, kept alive by either agent
+        registry
+            .publish("beta", "s", b"v3", provenance("dsl2"))
+            .await
+            .unwrap();
+
+        let history = registry.history("alpha").await.unwrap();
+        assert_eq!(
+            history.iter().map(|v| v.version).collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+        assert_eq!(registry.store().list("blobs/").await.unwrap().len(), 3);
+
+        let report = registry.gc(1).await.unwrap();
+        assert_eq!(report.versions_dropped, 2);
+        assert_eq!(report.blobs_removed, 2);
+        assert_eq!(report.manifests_removed, 2);
+        assert_eq!(report.refs_removed, 2);
+        assert_eq!(report.bytes_freed, 4);
+        assert!(registry.fetch(&sha256_hex(b"v3")).await.is_ok());
+        assert!(registry.fetch(&sha256_hex(b"v1")).await.is_err());
+        assert_eq!(registry.lookup(&provenance("dsl0")).await.unwrap(), None);
+
+        // Version numbers keep counting after collection
+        registry
+            .publish("alpha", "s", b"v4", provenance("dsl3"))
+            .await
+            .unwrap();
+        assert_eq!(registry.latest("alpha").await.unwrap().unwrap().version, 4);
+        assert!(registry.history("../etc").await.is_err());
+    }
+}
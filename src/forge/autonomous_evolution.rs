@@ -2,8 +2,11 @@
 //! 
 //! Pełna integracja z SwarmAgentic AI dla autonomicznej kompilacji strategii
 //! Self-improving strategy generation through AI feedback loops
+//!
+//! Kandydaci oceniani walk-forward na nagranych danych (`walk_forward`);
+//! champion wybierany z fitness out-of-sample, każda decyzja zapisywana
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
@@ -13,6 +16,9 @@ use uuid::Uuid;
 
 use super::tensorzero_gateway::{TensorZeroGateway, InferenceRequest, InferenceInput, Message};
 use super::strategy_compiler::StrategyCompiler;
+use super::artifact_registry::ArtifactStore;
+use super::walk_forward::{ExperimentRecord, WalkForwardConfig, WalkForwardEvaluator, WalkForwardReport};
+use crate::modules::data_ingestor::MarketData;
 use crate::agents::{AgentManager, DynamicAgentMetrics, AgentType};
 
 /// Autonomous Evolution Engine
@@ -38,6 +44,12 @@ pub struct AutonomousEvolutionEngine {
     
     /// Evolution metrics
     metrics: Arc<RwLock<EvolutionMetrics>>,
+    
+    /// Recorded market data candidates are backtested on
+    backtest_data: Arc<Vec<MarketData>>,
+    
+    /// Where experiment records are persisted for audit
+    experiment_store: Option<Arc<dyn ArtifactStore>>,
 }
 
 /// Evolution configuration
@@ -60,6 +72,10 @@ pub struct EvolutionConfig {
     
     /// Safety parameters
     pub safety_params: SafetyParams,
+    
+    /// Walk-forward backtest of candidates
+    #[serde(default)]
+    pub walk_forward: WalkForwardConfig,
 }
 
 /// Strategy generation parameters
@@ -119,6 +135,7 @@ pub struct EvolutionExperiment {
     pub experiment_id: String,
     pub strategy_id: String,
     pub agent_id: String,
+    pub dsl: String,
     pub start_time: chrono::DateTime<chrono::Utc>,
     pub status: ExperimentStatus,
     pub performance_metrics: Option<DynamicAgentMetrics>,
@@ -129,6 +146,7 @@ pub struct EvolutionExperiment {
 #[derive(Debug, Clone)]
 pub struct ChampionStrategy {
     pub strategy_id: String,
+    pub experiment_id: String,
     pub performance_score: f64,
     pub risk_adjusted_return: f64,
     pub sharpe_ratio: f64,
@@ -220,6 +238,7 @@ impl Default for EvolutionConfig {
                 max_experiment_loss: 0.02, // 2% max loss
                 min_testing_period: Duration::from_hours(1),
             },
+            walk_forward: WalkForwardConfig::default(),
         }
     }
 }
@@ -242,9 +261,23 @@ impl AutonomousEvolutionEngine {
             state: Arc::new(RwLock::new(EvolutionState::default())),
             performance_history: Arc::new(RwLock::new(HashMap::new())),
             metrics: Arc::new(RwLock::new(EvolutionMetrics::default())),
+            backtest_data: Arc::new(Vec::new()),
+            experiment_store: None,
         })
     }
     
+    /// Recorded bars of one symbol, oldest first (`walk_forward::load_market_series`)
+    pub fn with_backtest_data(mut self, bars: Vec<MarketData>) -> Self {
+        self.backtest_data = Arc::new(bars);
+        self
+    }
+    
+    /// Persist every experiment's evaluation and promotion decision
+    pub fn with_experiment_store(mut self, store: Arc<dyn ArtifactStore>) -> Self {
+        self.experiment_store = Some(store);
+        self
+    }
+    
     /// Start autonomous evolution loop
     pub async fn start_evolution_loop(&self) -> Result<()> {
         info!("🚀 Starting autonomous evolution loop");
@@ -433,12 +466,15 @@ impl AutonomousEvolutionEngine {
             stream: Some(false),
         };
         
-        // For now, simulate AI inference since TensorZero may not be available
-        // In production, this would be: self.tensorzero_gateway.inference(request).await
-        match self.simulate_ai_inference(&prompt).await {
+        // Offline runs answer from recordings or templates (TensorZeroConfig::provider)
+        match self.tensorzero_gateway.inference(request).await {
             Ok(response) => {
                 // Parse response and extract strategy DSL
-                let strategies = self.parse_strategy_response(&response).await?;
+                let text = response.content.iter()
+                    .filter_map(|block| block.text.as_deref())
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                let strategies = self.parse_strategy_response(&text).await?;
                 
                 // Update metrics
                 {
@@ -460,38 +496,19 @@ impl AutonomousEvolutionEngine {
         }
     }
     
-    /// Simulate AI inference for testing
-    async fn simulate_ai_inference(&self, _prompt: &str) -> Result<String> {
-        // Simulate AI response with mock strategy DSL
-        Ok(r#"strategy EvolutionTestV1:
-  metadata:
-    name: "Evolution Test Strategy V1"
-    version: "1.0.0"
-    author: "THE OVERMIND PROTOCOL - Autonomous Evolution"
-    description: "AI-evolved strategy with improved performance"
-    risk_level: 2
-    expected_return: 0.18
-    max_drawdown: 0.05
-
-strategy EvolutionTestV2:
-  metadata:
-    name: "Evolution Test Strategy V2"
-    version: "1.0.0"
-    author: "THE OVERMIND PROTOCOL - Autonomous Evolution"
-    description: "Second AI-evolved strategy variant"
-    risk_level: 3
-    expected_return: 0.20
-    max_drawdown: 0.06"#.to_string())
-    }
-
     /// Parse AI response to extract strategy DSL
     async fn parse_strategy_response(&self, response: &str) -> Result<Vec<String>> {
-        // Simple parsing - in production would be more sophisticated
-        let strategies = response
-            .split("strategy ")
-            .skip(1) // Skip first empty part
-            .map(|s| format!("strategy {}", s))
-            .collect();
+        // Each top-level `strategy Name:` header starts a new strategy
+        let mut strategies: Vec<String> = Vec::new();
+        for line in response.lines() {
+            if line.starts_with("strategy ") {
+                strategies.push(String::new());
+            }
+            if let Some(current) = strategies.last_mut() {
+                current.push_str(line);
+                current.push('\n');
+            }
+        }
         
         Ok(strategies)
     }
@@ -519,7 +536,7 @@ strategy EvolutionTestV2:
     take_profit: 4%
     
   entry_logic:
-    - trigger: "signal_strength > 0.8 AND volume_confirmation"
+    - trigger: "price_change_1h > 2% AND volatility < 5%"
       action: market_buy(size=position_size)
       priority: 1
       
@@ -552,6 +569,7 @@ strategy EvolutionTestV2:
                 experiment_id: experiment_id.clone(),
                 strategy_id: strategy_id.clone(),
                 agent_id,
+                dsl: strategy_dsl.clone(),
                 start_time: chrono::Utc::now(),
                 status: ExperimentStatus::Running,
                 performance_metrics: None,
@@ -581,111 +599,180 @@ strategy EvolutionTestV2:
         Ok(experiments)
     }
     
-    /// Evaluate experiments
+    /// Evaluate experiments by walk-forward backtest on the recorded data
     async fn evaluate_experiments(&self, experiments: Vec<EvolutionExperiment>) -> Result<Vec<ExperimentResult>> {
-        info!("📈 Evaluating experiments...");
+        info!("📈 Evaluating experiments on {} recorded bars...", self.backtest_data.len());
         
-        // Wait for evaluation period
-        tokio::time::sleep(self.config.evaluation_period).await;
+        if self.backtest_data.is_empty() {
+            return Err(anyhow!("No backtest data configured; refusing to rank strategies on unmeasured performance"));
+        }
         
+        let evaluator = WalkForwardEvaluator::new(self.config.walk_forward.clone());
+        let baseline = self.champion_baseline();
         let mut results = Vec::new();
         
         for experiment in experiments {
-            // Get agent performance
-            if let Ok(metrics) = self.agent_manager.get_agent_metrics(&experiment.agent_id).await {
-                let performance_score = self.calculate_performance_score(&metrics);
-                
-                let result = ExperimentResult {
-                    experiment_id: experiment.experiment_id.clone(),
-                    strategy_id: experiment.strategy_id.clone(),
-                    performance_score,
-                    metrics: metrics.clone(),
-                    improvement: performance_score - 0.5, // Baseline score
-                };
-                
-                results.push(result);
-                
-                // Update performance history
-                {
-                    let mut history = self.performance_history.write().unwrap();
-                    history.insert(
-                        experiment.strategy_id.clone(),
-                        PerformanceRecord {
-                            strategy_id: experiment.strategy_id.clone(),
-                            total_return: metrics.total_pnl,
-                            sharpe_ratio: metrics.sharpe_ratio,
-                            max_drawdown: metrics.max_drawdown,
-                            win_rate: metrics.win_rate,
-                            total_trades: metrics.total_decisions,
-                            avg_trade_duration: Duration::from_millis(metrics.average_decision_time_ms),
-                            last_updated: chrono::Utc::now(),
-                        },
-                    );
+            let evaluation = evaluator.evaluate(&experiment.dsl, &self.backtest_data);
+            
+            let status = match &evaluation {
+                Ok(_) => ExperimentStatus::Completed,
+                Err(e) => ExperimentStatus::Failed(e.to_string()),
+            };
+            {
+                let mut state = self.state.write().unwrap();
+                if let Some(active) = state.active_experiments.get_mut(&experiment.experiment_id) {
+                    active.status = status;
                 }
             }
+            
+            let result = match evaluation {
+                Ok(report) => {
+                    let oos = &report.out_of_sample;
+                    info!("🧪 {} OOS fitness {:.3} (Sharpe {:.2}, drawdown {:.2}%, {} trades)",
+                          experiment.strategy_id, report.fitness, oos.sharpe_ratio, oos.max_drawdown * 100.0, oos.trades);
+                    
+                    // Update performance history
+                    {
+                        let mut history = self.performance_history.write().unwrap();
+                        history.insert(
+                            experiment.strategy_id.clone(),
+                            PerformanceRecord {
+                                strategy_id: experiment.strategy_id.clone(),
+                                total_return: oos.total_return,
+                                sharpe_ratio: oos.sharpe_ratio,
+                                max_drawdown: oos.max_drawdown,
+                                win_rate: oos.win_rate,
+                                total_trades: oos.trades as u64,
+                                avg_trade_duration: Duration::from_secs_f64(oos.avg_holding_secs),
+                                last_updated: chrono::Utc::now(),
+                            },
+                        );
+                    }
+                    
+                    ExperimentResult {
+                        experiment_id: experiment.experiment_id.clone(),
+                        strategy_id: experiment.strategy_id.clone(),
+                        dsl: experiment.dsl.clone(),
+                        performance_score: report.fitness,
+                        improvement: report.fitness - baseline,
+                        report: Some(report),
+                        error: None,
+                    }
+                }
+                Err(e) => {
+                    warn!("⚠️ Experiment {} could not be evaluated: {}", experiment.strategy_id, e);
+                    ExperimentResult {
+                        experiment_id: experiment.experiment_id.clone(),
+                        strategy_id: experiment.strategy_id.clone(),
+                        dsl: experiment.dsl.clone(),
+                        performance_score: f64::NEG_INFINITY,
+                        improvement: f64::NEG_INFINITY,
+                        report: None,
+                        error: Some(e.to_string()),
+                    }
+                }
+            };
+            
+            results.push(result);
         }
         
         Ok(results)
     }
     
-    /// Deploy best strategies
+    /// Fitness a candidate has to beat: the best champion so far, 0 without one
+    fn champion_baseline(&self) -> f64 {
+        let state = self.state.read().unwrap();
+        state.champion_strategies.iter()
+            .map(|c| c.performance_score)
+            .fold(0.0, f64::max)
+    }
+    
+    /// Promote the best measured candidates and record why each was or wasn't
     async fn deploy_best_strategies(&self, results: Vec<ExperimentResult>) -> Result<usize> {
         info!("🚀 Deploying best strategies...");
         
+        let cycle = self.state.read().unwrap().current_cycle;
         let mut deployed_count = 0;
         
-        // Sort by performance score
+        // Sort by out-of-sample fitness
         let mut sorted_results = results;
-        sorted_results.sort_by(|a, b| b.performance_score.partial_cmp(&a.performance_score).unwrap());
-        
-        for result in sorted_results.iter().take(2) { // Deploy top 2
-            if result.improvement > self.config.improvement_threshold {
-                info!("🎯 Deploying strategy {} with {:.2}% improvement", 
-                      result.strategy_id, result.improvement * 100.0);
-                
-                // Add to champions
-                {
-                    let mut state = self.state.write().unwrap();
-                    state.champion_strategies.push(ChampionStrategy {
-                        strategy_id: result.strategy_id.clone(),
-                        performance_score: result.performance_score,
-                        risk_adjusted_return: result.metrics.total_pnl / (result.metrics.max_drawdown + 0.01),
-                        sharpe_ratio: result.metrics.sharpe_ratio,
-                        max_drawdown: result.metrics.max_drawdown,
-                        win_rate: result.metrics.win_rate,
-                        deployment_time: chrono::Utc::now(),
-                    });
-                }
-                
-                deployed_count += 1;
-                
-                // Update metrics
-                {
-                    let mut metrics = self.metrics.write().unwrap();
-                    metrics.strategies_deployed += 1;
-                    metrics.average_improvement = 
-                        (metrics.average_improvement * (metrics.strategies_deployed - 1) as f64 + result.improvement) 
-                        / metrics.strategies_deployed as f64;
+        sorted_results.sort_by(|a, b| b.performance_score.total_cmp(&a.performance_score));
+        
+        for result in &sorted_results {
+            let (promoted, decision) = match &result.report {
+                None => (false, format!("not evaluated: {}", result.error.as_deref().unwrap_or("unknown error"))),
+                Some(report) if !report.passed() => (false, format!("rejected: {}", report.rejections.join("; "))),
+                Some(_) if result.improvement <= self.config.improvement_threshold => (false, format!(
+                    "fitness {:.3} improves on champions by {:.3}, threshold {:.3}",
+                    result.performance_score, result.improvement, self.config.improvement_threshold
+                )),
+                Some(_) if deployed_count >= 2 => (false, "outranked: top 2 already promoted this cycle".to_string()),
+                Some(report) => {
+                    info!("🎯 Deploying strategy {} with OOS fitness {:.3} (+{:.3})",
+                          result.strategy_id, result.performance_score, result.improvement);
                     
-                    if result.performance_score > metrics.best_performance_score {
-                        metrics.best_performance_score = result.performance_score;
+                    let oos = &report.out_of_sample;
+                    // Add to champions
+                    {
+                        let mut state = self.state.write().unwrap();
+                        state.champion_strategies.push(ChampionStrategy {
+                            strategy_id: result.strategy_id.clone(),
+                            experiment_id: result.experiment_id.clone(),
+                            performance_score: result.performance_score,
+                            risk_adjusted_return: oos.total_return / (oos.max_drawdown + 0.01),
+                            sharpe_ratio: oos.sharpe_ratio,
+                            max_drawdown: oos.max_drawdown,
+                            win_rate: oos.win_rate,
+                            deployment_time: chrono::Utc::now(),
+                        });
                     }
+                    
+                    deployed_count += 1;
+                    
+                    // Update metrics
+                    {
+                        let mut metrics = self.metrics.write().unwrap();
+                        metrics.strategies_deployed += 1;
+                        metrics.average_improvement =
+                            (metrics.average_improvement * (metrics.strategies_deployed - 1) as f64 + result.improvement)
+                            / metrics.strategies_deployed as f64;
+                        
+                        if result.performance_score > metrics.best_performance_score {
+                            metrics.best_performance_score = result.performance_score;
+                        }
+                    }
+                    
+                    (true, "promoted".to_string())
                 }
-            }
+            };
+            
+            let record = ExperimentRecord {
+                experiment_id: result.experiment_id.clone(),
+                strategy_id: result.strategy_id.clone(),
+                cycle,
+                evaluated_at: chrono::Utc::now(),
+                dsl: result.dsl.clone(),
+                report: result.report.clone(),
+                error: result.error.clone(),
+                improvement: result.report.as_ref().map(|_| result.improvement),
+                promoted,
+                decision,
+            };
+            self.persist_experiment(&record).await;
         }
         
         Ok(deployed_count)
     }
     
-    /// Calculate performance score
-    fn calculate_performance_score(&self, metrics: &DynamicAgentMetrics) -> f64 {
-        // Composite score based on multiple factors
-        let return_score = (metrics.total_pnl + 1.0).max(0.1).ln() / 10.0;
-        let sharpe_score = metrics.sharpe_ratio / 3.0;
-        let drawdown_score = (0.1 - metrics.max_drawdown).max(0.0) * 10.0;
-        let win_rate_score = metrics.win_rate;
-        
-        (return_score + sharpe_score + drawdown_score + win_rate_score) / 4.0
+    /// Store the record; audit gaps are logged, they don't abort the cycle
+    async fn persist_experiment(&self, record: &ExperimentRecord) {
+        let Some(store) = &self.experiment_store else {
+            info!("📝 Experiment {} ({}): {}", record.experiment_id, record.strategy_id, record.decision);
+            return;
+        };
+        if let Err(e) = record.persist(store.as_ref()).await {
+            error!("❌ Failed to persist experiment {}: {}", record.experiment_id, e);
+        }
     }
     
     /// Get evolution metrics
@@ -720,9 +807,15 @@ pub struct PerformanceAnalysis {
 pub struct ExperimentResult {
     pub experiment_id: String,
     pub strategy_id: String,
+    pub dsl: String,
+    
+    /// Out-of-sample fitness; `-inf` when evaluation failed
     pub performance_score: f64,
-    pub metrics: DynamicAgentMetrics,
+    
+    /// Fitness gain over the best champion
     pub improvement: f64,
+    pub report: Option<WalkForwardReport>,
+    pub error: Option<String>,
 }
 
 /// Duration extension for hours
@@ -0,0 +1,498 @@
+//! DSL AST - Typed View over the Syntax Tree
+//!
+//! Sekcje metadata, risk_model, entry/exit_logic, ai_models,
+//! technical_indicators i alerts jako typowane struktury ze spanami.
+//! Every other section stays reachable through `StrategyAst::section`.
+
+use super::syntax::{find, CallArg, Item, Node, StrategyBlock, Value, ValueKind};
+use super::{Diagnostic, Span, Spanned};
+
+/// Typed strategy declaration
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyAst {
+    pub name: Spanned<String>,
+    pub span: Span,
+    pub metadata: Option<Metadata>,
+    pub risk_model: Option<RiskModelDecl>,
+    pub entry_logic: Vec<RuleDecl>,
+    pub exit_logic: Vec<RuleDecl>,
+    pub ai_models: Vec<ModelDecl>,
+    pub technical_indicators: Vec<IndicatorDecl>,
+    pub alerts: Vec<AlertDecl>,
+
+    /// Every top-level section in source order, typed or not
+    pub sections: Vec<Node>,
+}
+
+/// `metadata:` section
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metadata {
+    pub span: Span,
+    pub name: Option<Spanned<String>>,
+    pub version: Option<Spanned<String>>,
+    pub author: Option<Spanned<String>>,
+    pub description: Option<Spanned<String>>,
+    pub risk_level: Option<Spanned<u32>>,
+    pub expected_return: Option<Spanned<f64>>,
+    pub max_drawdown: Option<Spanned<f64>>,
+}
+
+/// `risk_model:` section; percentages are stored as fractions
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskModelDecl {
+    pub span: Span,
+    pub max_drawdown: Option<Spanned<f64>>,
+    pub daily_loss_limit: Option<Spanned<f64>>,
+    pub position_size: Option<Spanned<f64>>,
+    pub stop_loss: Option<Spanned<f64>>,
+    pub take_profit: Option<Spanned<f64>>,
+    pub max_positions: Option<Spanned<u32>>,
+    pub correlation_limit: Option<Spanned<f64>>,
+}
+
+/// Entry or exit rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleDecl {
+    pub span: Span,
+    pub trigger: Option<Spanned<String>>,
+    pub action: Option<ActionDecl>,
+    pub priority: Option<Spanned<u32>>,
+    pub enabled: Option<Spanned<bool>>,
+    pub confidence_threshold: Option<Spanned<f64>>,
+    pub description: Option<Spanned<String>>,
+
+    /// All entries of the rule, including unknown keys
+    pub entries: Vec<Node>,
+}
+
+/// `market_buy(size=position_size*0.8, offset=0.1%)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionDecl {
+    pub span: Span,
+    pub name: Spanned<String>,
+    pub args: Vec<CallArg>,
+}
+
+/// `ai_models:` entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModelDecl {
+    pub span: Span,
+    pub name: Option<Spanned<String>>,
+    pub version: Option<Spanned<String>>,
+    pub purpose: Option<Spanned<String>>,
+    pub input_features: Vec<Spanned<String>>,
+    pub output: Option<Spanned<String>>,
+    pub parameters: Vec<Node>,
+}
+
+/// `technical_indicators:` entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndicatorDecl {
+    pub span: Span,
+    pub name: Option<Spanned<String>>,
+    /// The `type:` key
+    pub kind: Option<Spanned<String>>,
+    pub period: Option<Spanned<u32>>,
+    pub source: Option<Spanned<String>>,
+
+    /// All entries, for indicator-specific settings like `fast_period`
+    pub entries: Vec<Node>,
+}
+
+/// `alerts:` entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertDecl {
+    pub span: Span,
+    pub condition: Option<Spanned<String>>,
+    pub action: Option<Spanned<String>>,
+    pub severity: Option<Spanned<AlertSeverity>>,
+    pub description: Option<Spanned<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl StrategyAst {
+    /// Top-level section by name
+    pub fn section(&self, name: &str) -> Option<&Node> {
+        find(&self.sections, name)
+    }
+
+    /// Entry rules followed by exit rules
+    pub fn rules(&self) -> impl Iterator<Item = &RuleDecl> {
+        self.entry_logic.iter().chain(self.exit_logic.iter())
+    }
+}
+
+impl RuleDecl {
+    /// Rules are enabled unless they say `enabled: false`
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.as_ref().is_none_or(|e| e.node)
+    }
+}
+
+impl ActionDecl {
+    /// Named argument value
+    pub fn arg(&self, name: &str) -> Option<&Value> {
+        self.args
+            .iter()
+            .find(|a| a.name.as_ref().is_some_and(|n| n.node == name))
+            .map(|a| &a.value)
+    }
+}
+
+/// Lower the syntax tree of a strategy block into the typed AST
+pub fn lower(block: &StrategyBlock) -> (StrategyAst, Vec<Diagnostic>) {
+    let mut lowerer = Lowerer::default();
+    let mut ast = StrategyAst {
+        name: block.name.clone(),
+        span: block.span,
+        metadata: None,
+        risk_model: None,
+        entry_logic: Vec::new(),
+        exit_logic: Vec::new(),
+        ai_models: Vec::new(),
+        technical_indicators: Vec::new(),
+        alerts: Vec::new(),
+        sections: block.body.clone(),
+    };
+
+    for node in &block.body {
+        match node.key.node.as_str() {
+            "metadata" => ast.metadata = Some(lowerer.metadata(node)),
+            "risk_model" => ast.risk_model = Some(lowerer.risk_model(node)),
+            "entry_logic" => ast.entry_logic = lowerer.items(node, Lowerer::rule),
+            "exit_logic" => ast.exit_logic = lowerer.items(node, Lowerer::rule),
+            "ai_models" => ast.ai_models = lowerer.items(node, Lowerer::model),
+            "technical_indicators" => {
+                ast.technical_indicators = lowerer.items(node, Lowerer::indicator)
+            }
+            "alerts" => ast.alerts = lowerer.items(node, Lowerer::alert),
+            _ => {}
+        }
+    }
+
+    (ast, lowerer.diagnostics)
+}
+
+#[derive(Default)]
+struct Lowerer {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Lowerer {
+    fn metadata(&mut self, node: &Node) -> Metadata {
+        let mut metadata = Metadata {
+            span: node.span,
+            name: None,
+            version: None,
+            author: None,
+            description: None,
+            risk_level: None,
+            expected_return: None,
+            max_drawdown: None,
+        };
+        for entry in &node.children {
+            match entry.key.node.as_str() {
+                "name" => metadata.name = self.text(entry),
+                "version" => metadata.version = self.text(entry),
+                "author" => metadata.author = self.text(entry),
+                "description" => metadata.description = self.text(entry),
+                "risk_level" => metadata.risk_level = self.integer(entry),
+                "expected_return" => metadata.expected_return = self.ratio(entry),
+                "max_drawdown" => metadata.max_drawdown = self.ratio(entry),
+                _ => {}
+            }
+        }
+        metadata
+    }
+
+    fn risk_model(&mut self, node: &Node) -> RiskModelDecl {
+        let mut risk = RiskModelDecl {
+            span: node.span,
+            max_drawdown: None,
+            daily_loss_limit: None,
+            position_size: None,
+            stop_loss: None,
+            take_profit: None,
+            max_positions: None,
+            correlation_limit: None,
+        };
+        for entry in &node.children {
+            match entry.key.node.as_str() {
+                "max_drawdown" => risk.max_drawdown = self.ratio(entry),
+                "daily_loss_limit" => risk.daily_loss_limit = self.ratio(entry),
+                "position_size" => risk.position_size = self.ratio(entry),
+                "stop_loss" => risk.stop_loss = self.ratio(entry),
+                "take_profit" => risk.take_profit = self.ratio(entry),
+                "max_positions" => risk.max_positions = self.integer(entry),
+                "correlation_limit" => risk.correlation_limit = self.ratio(entry),
+                _ => {}
+            }
+        }
+        risk
+    }
+
+    /// Lower every `- ...` item of a list section
+    fn items<T>(&mut self, node: &Node, lower: fn(&mut Self, &Item) -> T) -> Vec<T> {
+        if node.value.is_some() || !node.children.is_empty() {
+            self.diagnostics.push(Diagnostic::error(
+                format!("`{}` must be a list of `- key: value` items", node.key.node),
+                node.key.span,
+            ));
+        }
+        node.items.iter().map(|item| lower(self, item)).collect()
+    }
+
+    fn rule(&mut self, item: &Item) -> RuleDecl {
+        let mut rule = RuleDecl {
+            span: item.span,
+            trigger: None,
+            action: None,
+            priority: None,
+            enabled: None,
+            confidence_threshold: None,
+            description: None,
+            entries: item.entries.clone(),
+        };
+        for entry in &item.entries {
+            match entry.key.node.as_str() {
+                "trigger" => rule.trigger = self.expression(entry),
+                "action" => rule.action = self.action(entry),
+                "priority" => rule.priority = self.integer(entry),
+                "enabled" => rule.enabled = self.boolean(entry),
+                "confidence_threshold" => rule.confidence_threshold = self.ratio(entry),
+                "description" => rule.description = self.text(entry),
+                _ => {}
+            }
+        }
+        self.require(item, "rule", "trigger", rule.trigger.is_some());
+        self.require(item, "rule", "action", rule.action.is_some());
+        rule
+    }
+
+    fn model(&mut self, item: &Item) -> ModelDecl {
+        let mut model = ModelDecl {
+            span: item.span,
+            name: None,
+            version: None,
+            purpose: None,
+            input_features: Vec::new(),
+            output: None,
+            parameters: Vec::new(),
+        };
+        for entry in &item.entries {
+            match entry.key.node.as_str() {
+                "name" => model.name = self.text(entry),
+                "version" => model.version = self.text(entry),
+                "purpose" => model.purpose = self.text(entry),
+                "input_features" => model.input_features = self.text_list(entry),
+                "output" => model.output = self.text(entry),
+                "parameters" => model.parameters = entry.children.clone(),
+                _ => {}
+            }
+        }
+        self.require(item, "AI model", "name", model.name.is_some());
+        model
+    }
+
+    fn indicator(&mut self, item: &Item) -> IndicatorDecl {
+        let mut indicator = IndicatorDecl {
+            span: item.span,
+            name: None,
+            kind: None,
+            period: None,
+            source: None,
+            entries: item.entries.clone(),
+        };
+        for entry in &item.entries {
+            match entry.key.node.as_str() {
+                "name" => indicator.name = self.text(entry),
+                "type" => indicator.kind = self.text(entry),
+                "period" => indicator.period = self.integer(entry),
+                "source" => indicator.source = self.text(entry),
+                _ => {}
+            }
+        }
+        self.require(item, "indicator", "name", indicator.name.is_some());
+        self.require(item, "indicator", "type", indicator.kind.is_some());
+        indicator
+    }
+
+    fn alert(&mut self, item: &Item) -> AlertDecl {
+        let mut alert = AlertDecl {
+            span: item.span,
+            condition: None,
+            action: None,
+            severity: None,
+            description: None,
+        };
+        for entry in &item.entries {
+            match entry.key.node.as_str() {
+                "condition" => alert.condition = self.expression(entry),
+                "action" => alert.action = self.text(entry),
+                "severity" => alert.severity = self.severity(entry),
+                "description" => alert.description = self.text(entry),
+                _ => {}
+            }
+        }
+        self.require(item, "alert", "condition", alert.condition.is_some());
+        self.require(item, "alert", "action", alert.action.is_some());
+        alert
+    }
+
+    fn require(&mut self, item: &Item, what: &str, key: &str, present: bool) {
+        if !present && item.get(key).is_none() {
+            self.diagnostics.push(Diagnostic::error(
+                format!("{} is missing `{}`", what, key),
+                item.span,
+            ));
+        }
+    }
+
+    fn value<'n>(&mut self, node: &'n Node) -> Option<&'n Value> {
+        match &node.value {
+            None => {
+                self.diagnostics.push(Diagnostic::error(
+                    format!("`{}` needs a value", node.key.node),
+                    node.key.span,
+                ));
+                None
+            }
+            Some(value) if value.kind == ValueKind::Invalid => None,
+            Some(value) => Some(value),
+        }
+    }
+
+    fn mismatch(&mut self, node: &Node, value: &Value, expected: &str) {
+        self.diagnostics.push(Diagnostic::error(
+            format!(
+                "`{}` expects {}, found `{}`",
+                node.key.node, expected, value.raw
+            ),
+            value.span,
+        ));
+    }
+
+    /// String, identifier or number, as text
+    fn text(&mut self, node: &Node) -> Option<Spanned<String>> {
+        let value = self.value(node)?;
+        match &value.kind {
+            ValueKind::Str(s) | ValueKind::Ident(s) => Some(Spanned::new(s.clone(), value.span)),
+            ValueKind::Number(_) => Some(Spanned::new(value.raw.clone(), value.span)),
+            _ => {
+                self.mismatch(node, value, "a string");
+                None
+            }
+        }
+    }
+
+    /// Condition in quotes or written bare; a quoted condition's span
+    /// covers only the text inside the quotes
+    fn expression(&mut self, node: &Node) -> Option<Spanned<String>> {
+        let value = self.value(node)?;
+        match &value.kind {
+            ValueKind::Str(s) => {
+                let span = value.span;
+                let inner = Span::new(span.start + 1, span.end - 1, span.line, span.column + 1);
+                Some(Spanned::new(s.clone(), inner))
+            }
+            ValueKind::List(_) => {
+                self.mismatch(node, value, "an expression");
+                None
+            }
+            _ => Some(Spanned::new(value.text().to_string(), value.span)),
+        }
+    }
+
+    fn ratio(&mut self, node: &Node) -> Option<Spanned<f64>> {
+        let value = self.value(node)?;
+        match value.as_ratio() {
+            Some(ratio) => Some(Spanned::new(ratio, value.span)),
+            None => {
+                self.mismatch(node, value, "a number or percentage");
+                None
+            }
+        }
+    }
+
+    fn integer(&mut self, node: &Node) -> Option<Spanned<u32>> {
+        let value = self.value(node)?;
+        match value.as_u64().and_then(|n| u32::try_from(n).ok()) {
+            Some(n) => Some(Spanned::new(n, value.span)),
+            None => {
+                self.mismatch(node, value, "a non-negative integer");
+                None
+            }
+        }
+    }
+
+    fn boolean(&mut self, node: &Node) -> Option<Spanned<bool>> {
+        let value = self.value(node)?;
+        match value.as_bool() {
+            Some(b) => Some(Spanned::new(b, value.span)),
+            None => {
+                self.mismatch(node, value, "`true` or `false`");
+                None
+            }
+        }
+    }
+
+    fn text_list(&mut self, node: &Node) -> Vec<Spanned<String>> {
+        let Some(value) = self.value(node) else {
+            return Vec::new();
+        };
+        let ValueKind::List(values) = &value.kind else {
+            self.mismatch(node, value, "a list");
+            return Vec::new();
+        };
+        values
+            .iter()
+            .filter_map(|v| match v.as_str() {
+                Some(s) => Some(Spanned::new(s.to_string(), v.span)),
+                None => {
+                    self.mismatch(node, v, "a list of strings");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn action(&mut self, node: &Node) -> Option<ActionDecl> {
+        let value = self.value(node)?;
+        match &value.kind {
+            ValueKind::Call { name, args } => Some(ActionDecl {
+                span: value.span,
+                name: name.clone(),
+                args: args.clone(),
+            }),
+            ValueKind::Ident(name) | ValueKind::Str(name) => Some(ActionDecl {
+                span: value.span,
+                name: Spanned::new(name.clone(), value.span),
+                args: Vec::new(),
+            }),
+            _ => {
+                self.mismatch(node, value, "an action like `market_buy(size=...)`");
+                None
+            }
+        }
+    }
+
+    fn severity(&mut self, node: &Node) -> Option<Spanned<AlertSeverity>> {
+        let value = self.value(node)?;
+        let severity = match value.as_str() {
+            Some("info") => AlertSeverity::Info,
+            Some("warning") => AlertSeverity::Warning,
+            Some("critical") => AlertSeverity::Critical,
+            _ => {
+                self.mismatch(node, value, "`info`, `warning` or `critical`");
+                return None;
+            }
+        };
+        Some(Spanned::new(severity, value.span))
+    }
+}
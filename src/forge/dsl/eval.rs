@@ -0,0 +1,567 @@
+//! DSL EVALUATION - Type Checking & In-Process Evaluation
+//!
+//! Triggery sprawdzane przy ładowaniu względem `FeatureSchema`
+//! i wyliczane bezpośrednio na `FeatureSnapshot` - bez kompilacji do .so
+
+use super::expr::{BinaryOp, Expr, ExprKind, UnaryOp};
+use super::Diagnostic;
+use crate::forge::hot_loader::MarketData;
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+/// Static type of an expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExprType {
+    Number,
+    Duration,
+    Bool,
+}
+
+impl fmt::Display for ExprType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExprType::Number => write!(f, "number"),
+            ExprType::Duration => write!(f, "duration"),
+            ExprType::Bool => write!(f, "boolean"),
+        }
+    }
+}
+
+/// Runtime value; durations are kept in seconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExprValue {
+    Number(f64),
+    Duration(f64),
+    Bool(bool),
+}
+
+impl ExprValue {
+    pub fn ty(&self) -> ExprType {
+        match self {
+            ExprValue::Number(_) => ExprType::Number,
+            ExprValue::Duration(_) => ExprType::Duration,
+            ExprValue::Bool(_) => ExprType::Bool,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ExprValue::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ExprValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ExprValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExprValue::Number(n) => write!(f, "{}", n),
+            ExprValue::Duration(d) => write!(f, "{}s", d),
+            ExprValue::Bool(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+/// Names an expression may reference, with their types
+#[derive(Debug, Clone, Default)]
+pub struct FeatureSchema {
+    features: HashMap<String, ExprType>,
+}
+
+impl FeatureSchema {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Features every market snapshot and position tracker provides
+    pub fn standard() -> Self {
+        let numbers = [
+            "price",
+            "volume",
+            "bid",
+            "ask",
+            "spread",
+            "volatility",
+            "liquidity_score",
+            "momentum_signal",
+            "price_change_1h",
+            "price_change_24h",
+            "profit",
+            "loss",
+            "daily_loss",
+            "drawdown",
+        ];
+        let durations = ["holding_time", "execution_latency"];
+
+        let schema = numbers
+            .iter()
+            .fold(Self::new(), |schema, name| schema.with_number(name));
+        durations
+            .iter()
+            .fold(schema, |schema, name| schema.with_duration(name))
+    }
+
+    pub fn with_feature(mut self, name: &str, ty: ExprType) -> Self {
+        self.features.insert(name.to_string(), ty);
+        self
+    }
+
+    pub fn with_number(self, name: &str) -> Self {
+        self.with_feature(name, ExprType::Number)
+    }
+
+    pub fn with_bool(self, name: &str) -> Self {
+        self.with_feature(name, ExprType::Bool)
+    }
+
+    pub fn with_duration(self, name: &str) -> Self {
+        self.with_feature(name, ExprType::Duration)
+    }
+
+    pub fn insert(&mut self, name: &str, ty: ExprType) {
+        self.features.insert(name.to_string(), ty);
+    }
+
+    pub fn get(&self, name: &str) -> Option<ExprType> {
+        self.features.get(name).copied()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.features.keys().map(String::as_str)
+    }
+
+    /// Closest known name, for "did you mean" hints
+    fn suggest(&self, name: &str) -> Option<&str> {
+        self.names()
+            .map(|candidate| (edit_distance(name, candidate), candidate))
+            .filter(|(distance, _)| *distance <= (name.len() / 3).max(1))
+            .min()
+            .map(|(_, candidate)| candidate)
+    }
+}
+
+/// Feature values at one point in time
+#[derive(Debug, Clone, Default)]
+pub struct FeatureSnapshot {
+    values: HashMap<String, ExprValue>,
+}
+
+impl FeatureSnapshot {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Snapshot of the fields a hot-loaded strategy receives
+    pub fn from_market_data(data: &MarketData) -> Self {
+        Self::new()
+            .with_number("price", data.price)
+            .with_number("volume", data.volume)
+            .with_number("bid", data.bid)
+            .with_number("ask", data.ask)
+            .with_number("spread", data.ask - data.bid)
+            .with_number("momentum_signal", data.momentum_signal)
+            .with_number("volatility", data.volatility)
+            .with_number("liquidity_score", data.liquidity_score)
+    }
+
+    pub fn with_number(mut self, name: &str, value: f64) -> Self {
+        self.set(name, ExprValue::Number(value));
+        self
+    }
+
+    pub fn with_bool(mut self, name: &str, value: bool) -> Self {
+        self.set(name, ExprValue::Bool(value));
+        self
+    }
+
+    pub fn with_duration(mut self, name: &str, value: Duration) -> Self {
+        self.set(name, ExprValue::Duration(value.as_secs_f64()));
+        self
+    }
+
+    pub fn set(&mut self, name: &str, value: ExprValue) {
+        self.values.insert(name.to_string(), value);
+    }
+
+    pub fn get(&self, name: &str) -> Option<ExprValue> {
+        self.values.get(name).copied()
+    }
+}
+
+/// Infer the type of `expr`, recording a diagnostic for every problem.
+///
+/// Returns `None` when the expression is ill-typed; a sub-expression that
+/// already failed does not produce further errors further up the tree.
+pub fn type_check(
+    expr: &Expr,
+    schema: &FeatureSchema,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<ExprType> {
+    match &expr.kind {
+        ExprKind::Number(_) => Some(ExprType::Number),
+        ExprKind::Duration(_) => Some(ExprType::Duration),
+        ExprKind::Bool(_) => Some(ExprType::Bool),
+        ExprKind::Ident(name) => {
+            let ty = schema.get(name);
+            if ty.is_none() {
+                let mut message = format!("unknown identifier `{}`", name);
+                if let Some(suggestion) = schema.suggest(name) {
+                    message.push_str(&format!("; did you mean `{}`?", suggestion));
+                }
+                diagnostics.push(Diagnostic::error(message, expr.span));
+            }
+            ty
+        }
+        ExprKind::Unary { op, operand } => {
+            let ty = type_check(operand, schema, diagnostics)?;
+            let ok = match op {
+                UnaryOp::Neg => ty != ExprType::Bool,
+                UnaryOp::Not => ty == ExprType::Bool,
+            };
+            if !ok {
+                let symbol = if *op == UnaryOp::Neg { "-" } else { "NOT" };
+                diagnostics.push(Diagnostic::error(
+                    format!("`{}` cannot be applied to a {}", symbol, ty),
+                    expr.span,
+                ));
+                return None;
+            }
+            Some(ty)
+        }
+        ExprKind::Binary { op, lhs, rhs } => {
+            let lhs_ty = type_check(lhs, schema, diagnostics);
+            let rhs_ty = type_check(rhs, schema, diagnostics);
+            let (lhs_ty, rhs_ty) = (lhs_ty?, rhs_ty?);
+            let result = binary_type(*op, lhs_ty, rhs_ty);
+            if result.is_none() {
+                diagnostics.push(Diagnostic::error(
+                    format!(
+                        "`{}` cannot be applied to a {} and a {}",
+                        op.symbol(),
+                        lhs_ty,
+                        rhs_ty
+                    ),
+                    expr.span,
+                ));
+            }
+            result
+        }
+        ExprKind::Call { name, args } => {
+            let types: Vec<Option<ExprType>> = args
+                .iter()
+                .map(|arg| type_check(arg, schema, diagnostics))
+                .collect();
+            let types: Vec<ExprType> = types.into_iter().collect::<Option<_>>()?;
+
+            let result = match name.as_str() {
+                "abs" if types.len() == 1 => Some(types[0]).filter(|t| *t != ExprType::Bool),
+                "min" | "max" if !types.is_empty() => Some(types[0])
+                    .filter(|t| *t != ExprType::Bool && types.iter().all(|other| other == t)),
+                "abs" | "min" | "max" => {
+                    diagnostics.push(Diagnostic::error(
+                        format!("wrong number of arguments to `{}`", name),
+                        expr.span,
+                    ));
+                    return None;
+                }
+                _ => {
+                    diagnostics.push(Diagnostic::error(
+                        format!(
+                            "unknown function `{}`; expected `abs`, `min` or `max`",
+                            name
+                        ),
+                        expr.span,
+                    ));
+                    return None;
+                }
+            };
+            if result.is_none() {
+                diagnostics.push(Diagnostic::error(
+                    format!(
+                        "`{}` expects numbers or durations of one kind, found {}",
+                        name,
+                        types
+                            .iter()
+                            .map(ToString::to_string)
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    expr.span,
+                ));
+            }
+            result
+        }
+    }
+}
+
+fn binary_type(op: BinaryOp, lhs: ExprType, rhs: ExprType) -> Option<ExprType> {
+    use ExprType::*;
+    match (op, lhs, rhs) {
+        (BinaryOp::And | BinaryOp::Or, Bool, Bool) => Some(Bool),
+        (BinaryOp::Eq | BinaryOp::Ne, a, b) if a == b => Some(Bool),
+        (BinaryOp::Gt | BinaryOp::Ge | BinaryOp::Lt | BinaryOp::Le, a, b)
+            if a == b && a != Bool =>
+        {
+            Some(Bool)
+        }
+        (BinaryOp::Add | BinaryOp::Sub, a, b) if a == b && a != Bool => Some(a),
+        (BinaryOp::Mul, Number, Number) => Some(Number),
+        (BinaryOp::Mul, Duration, Number) | (BinaryOp::Mul, Number, Duration) => Some(Duration),
+        (BinaryOp::Div, Number, Number) | (BinaryOp::Div, Duration, Duration) => Some(Number),
+        (BinaryOp::Div, Duration, Number) => Some(Duration),
+        _ => None,
+    }
+}
+
+/// Evaluate a type-checked expression; `lookup` resolves identifiers
+pub fn evaluate(expr: &Expr, lookup: &dyn Fn(&str) -> Option<ExprValue>) -> Result<ExprValue> {
+    let value = match &expr.kind {
+        ExprKind::Number(n) => ExprValue::Number(*n),
+        ExprKind::Duration(d) => ExprValue::Duration(*d),
+        ExprKind::Bool(b) => ExprValue::Bool(*b),
+        ExprKind::Ident(name) => lookup(name).ok_or_else(|| {
+            anyhow!(
+                "{}: feature `{}` is missing from the snapshot",
+                expr.span,
+                name
+            )
+        })?,
+        ExprKind::Unary { op, operand } => match (op, evaluate(operand, lookup)?) {
+            (UnaryOp::Neg, ExprValue::Number(n)) => ExprValue::Number(-n),
+            (UnaryOp::Neg, ExprValue::Duration(d)) => ExprValue::Duration(-d),
+            (UnaryOp::Not, ExprValue::Bool(b)) => ExprValue::Bool(!b),
+            (_, value) => return Err(type_error(expr, &[value])),
+        },
+        ExprKind::Binary { op, lhs, rhs } => {
+            let lhs = evaluate(lhs, lookup)?;
+            // Short-circuit: the right side may reference features that
+            // are only present when the left side holds
+            match (op, lhs) {
+                (BinaryOp::And, ExprValue::Bool(false)) => return Ok(ExprValue::Bool(false)),
+                (BinaryOp::Or, ExprValue::Bool(true)) => return Ok(ExprValue::Bool(true)),
+                _ => {}
+            }
+            let rhs_value = evaluate(rhs, lookup)?;
+            binary(*op, lhs, rhs_value).ok_or_else(|| type_error(expr, &[lhs, rhs_value]))?
+        }
+        ExprKind::Call { name, args } => {
+            let values = args
+                .iter()
+                .map(|arg| evaluate(arg, lookup))
+                .collect::<Result<Vec<_>>>()?;
+            call(name, &values).ok_or_else(|| type_error(expr, &values))?
+        }
+    };
+
+    match value {
+        ExprValue::Number(n) | ExprValue::Duration(n) if !n.is_finite() => Err(anyhow!(
+            "{}: expression evaluated to a non-finite number",
+            expr.span
+        )),
+        value => Ok(value),
+    }
+}
+
+fn binary(op: BinaryOp, lhs: ExprValue, rhs: ExprValue) -> Option<ExprValue> {
+    use ExprValue::*;
+    let value = match (op, lhs, rhs) {
+        (BinaryOp::And, Bool(a), Bool(b)) => Bool(a && b),
+        (BinaryOp::Or, Bool(a), Bool(b)) => Bool(a || b),
+        (BinaryOp::Eq, Bool(a), Bool(b)) => Bool(a == b),
+        (BinaryOp::Ne, Bool(a), Bool(b)) => Bool(a != b),
+        (BinaryOp::Mul, Duration(d), Number(n)) | (BinaryOp::Mul, Number(n), Duration(d)) => {
+            Duration(d * n)
+        }
+        (BinaryOp::Div, Duration(a), Number(b)) => Duration(a / b),
+        (BinaryOp::Div, Duration(a), Duration(b)) => Number(a / b),
+        (op, Number(a), Number(b)) | (op, Duration(a), Duration(b)) => {
+            let wrap = |x| match lhs {
+                Duration(_) => Duration(x),
+                _ => Number(x),
+            };
+            match op {
+                BinaryOp::Add => wrap(a + b),
+                BinaryOp::Sub => wrap(a - b),
+                BinaryOp::Mul if matches!(lhs, Number(_)) => Number(a * b),
+                BinaryOp::Div if matches!(lhs, Number(_)) => Number(a / b),
+                BinaryOp::Gt => Bool(a > b),
+                BinaryOp::Ge => Bool(a >= b),
+                BinaryOp::Lt => Bool(a < b),
+                BinaryOp::Le => Bool(a <= b),
+                BinaryOp::Eq => Bool(a == b),
+                BinaryOp::Ne => Bool(a != b),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+fn call(name: &str, values: &[ExprValue]) -> Option<ExprValue> {
+    let first = *values.first()?;
+    let numbers = values
+        .iter()
+        .map(|v| match (v, first) {
+            (ExprValue::Number(n), ExprValue::Number(_)) => Some(*n),
+            (ExprValue::Duration(d), ExprValue::Duration(_)) => Some(*d),
+            _ => None,
+        })
+        .collect::<Option<Vec<f64>>>()?;
+    let wrap = |x| match first {
+        ExprValue::Duration(_) => ExprValue::Duration(x),
+        _ => ExprValue::Number(x),
+    };
+    match (name, numbers.as_slice()) {
+        ("abs", [x]) => Some(wrap(x.abs())),
+        ("min", _) => Some(wrap(numbers.iter().copied().fold(f64::INFINITY, f64::min))),
+        ("max", _) => Some(wrap(
+            numbers.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        )),
+        _ => None,
+    }
+}
+
+fn type_error(expr: &Expr, values: &[ExprValue]) -> anyhow::Error {
+    let types: Vec<String> = values.iter().map(|v| v.ty().to_string()).collect();
+    anyhow!(
+        "{}: expression cannot be evaluated on {} values",
+        expr.span,
+        types.join(", ")
+    )
+}
+
+/// Levenshtein distance over chars
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous + usize::from(ca != *cb);
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(previous + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::dsl::expr::parse_expr;
+    use crate::forge::dsl::Span;
+
+    fn expr(text: &str) -> Expr {
+        parse_expr(text, Span::new(0, text.len(), 1, 1)).unwrap()
+    }
+
+    fn check(text: &str, schema: &FeatureSchema) -> (Option<ExprType>, Vec<Diagnostic>) {
+        let mut diagnostics = Vec::new();
+        let ty = type_check(&expr(text), schema, &mut diagnostics);
+        (ty, diagnostics)
+    }
+
+    fn eval(text: &str, snapshot: &FeatureSnapshot) -> Result<ExprValue> {
+        evaluate(&expr(text), &|name| snapshot.get(name))
+    }
+
+    #[test]
+    fn test_type_check() {
+        let schema = FeatureSchema::standard().with_bool("support_broken");
+
+        let (ty, diagnostics) = check("loss > 2.5% OR support_broken", &schema);
+        assert_eq!(ty, Some(ExprType::Bool));
+        assert!(diagnostics.is_empty());
+
+        let (ty, _) = check("holding_time * 2 - 30s", &schema);
+        assert_eq!(ty, Some(ExprType::Duration));
+
+        let (ty, diagnostics) = check("holding_time > 300 AND profit > 1%", &schema);
+        assert_eq!(ty, None);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(
+            diagnostics[0].message.contains("duration and a number"),
+            "{:?}",
+            diagnostics
+        );
+        assert_eq!(diagnostics[0].span.start, 0);
+        assert_eq!(diagnostics[0].span.end, 18);
+
+        let (_, diagnostics) = check("max(profit, 1s) > 0", &schema);
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_unknown_identifiers_are_all_reported() {
+        let schema = FeatureSchema::standard();
+        let (ty, diagnostics) = check("momentum_signl > 0.75 AND rsi < 70", &schema);
+        assert_eq!(ty, None);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(
+            diagnostics[0].message,
+            "unknown identifier `momentum_signl`; did you mean `momentum_signal`?"
+        );
+        assert_eq!(diagnostics[1].message, "unknown identifier `rsi`");
+        assert_eq!(diagnostics[1].span.column, 27);
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let snapshot = FeatureSnapshot::new()
+            .with_number("profit", 0.012)
+            .with_number("momentum_signal", 0.4)
+            .with_duration("holding_time", Duration::from_secs(4000));
+
+        let value = eval("holding_time > 3600s AND profit > 1%", &snapshot).unwrap();
+        assert_eq!(value, ExprValue::Bool(true));
+
+        let value = eval("profit > 5% OR momentum_signal < 0.2", &snapshot).unwrap();
+        assert_eq!(value, ExprValue::Bool(false));
+
+        let value = eval("max(profit, 0.02) * 100 + abs(-1)", &snapshot).unwrap();
+        assert_eq!(value, ExprValue::Number(3.0));
+
+        // `volume` is missing but the left side already decided the result
+        let value = eval("profit > 5% AND volume > 10", &snapshot).unwrap();
+        assert_eq!(value, ExprValue::Bool(false));
+
+        let err = eval("profit > 0 AND volume > 10", &snapshot).unwrap_err();
+        assert!(err.to_string().contains("`volume` is missing"), "{}", err);
+
+        assert!(eval("profit / 0 > 1", &snapshot).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_from_market_data() {
+        let data = MarketData {
+            timestamp: 0,
+            price: 1.5,
+            volume: 1000.0,
+            bid: 1.49,
+            ask: 1.51,
+            momentum_signal: 0.8,
+            volatility: 0.05,
+            liquidity_score: 0.9,
+        };
+        let snapshot = FeatureSnapshot::from_market_data(&data);
+        let schema = FeatureSchema::standard();
+        for name in ["price", "volume", "bid", "ask", "spread", "momentum_signal"] {
+            assert_eq!(schema.get(name), snapshot.get(name).map(|v| v.ty()));
+        }
+        assert_eq!(
+            eval("momentum_signal > 0.75 AND volume > 1K", &snapshot).unwrap(),
+            ExprValue::Bool(false)
+        );
+    }
+}
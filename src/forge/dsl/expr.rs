@@ -0,0 +1,628 @@
+//! DSL EXPRESSIONS - Trigger & Argument Syntax
+//!
+//! `momentum_signal > 0.75 AND volume > avg_volume_20 * 1.5`
+//! Literały: procenty (`2.5%` = 0.025), czasy (`300s`, `100ms`, `5m`, `1h`)
+//! i wielkości (`1K`, `10M`, `2B`).
+
+use super::{Diagnostic, Span};
+
+/// Parsed expression with its source span
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExprKind {
+    /// Plain number; percentages are already fractions
+    Number(f64),
+    /// Duration literal in seconds
+    Duration(f64),
+    Bool(bool),
+    /// Feature or constant lookup
+    Ident(String),
+    Unary {
+        op: UnaryOp,
+        operand: Box<Expr>,
+    },
+    Binary {
+        op: BinaryOp,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+    Call {
+        name: String,
+        args: Vec<Expr>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    Neg,
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+    Eq,
+    Ne,
+    And,
+    Or,
+}
+
+impl BinaryOp {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::Gt => ">",
+            BinaryOp::Ge => ">=",
+            BinaryOp::Lt => "<",
+            BinaryOp::Le => "<=",
+            BinaryOp::Eq => "==",
+            BinaryOp::Ne => "!=",
+            BinaryOp::And => "AND",
+            BinaryOp::Or => "OR",
+        }
+    }
+
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            BinaryOp::Gt | BinaryOp::Ge | BinaryOp::Lt | BinaryOp::Le | BinaryOp::Eq | BinaryOp::Ne
+        )
+    }
+
+    /// Left and right binding power
+    fn binding_power(&self) -> (u8, u8) {
+        match self {
+            BinaryOp::Or => (1, 2),
+            BinaryOp::And => (3, 4),
+            _ if self.is_comparison() => (5, 6),
+            BinaryOp::Add | BinaryOp::Sub => (7, 8),
+            _ => (9, 10),
+        }
+    }
+}
+
+/// `NOT` binds looser than comparisons: `NOT rsi > 70` is `NOT (rsi > 70)`
+const NOT_BINDING_POWER: u8 = 5;
+const NEG_BINDING_POWER: u8 = 11;
+
+impl Expr {
+    /// Identifiers referenced by the expression, first occurrence order
+    pub fn identifiers(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        self.collect_identifiers(&mut names);
+        names
+    }
+
+    fn collect_identifiers<'a>(&'a self, names: &mut Vec<&'a str>) {
+        match &self.kind {
+            ExprKind::Ident(name) => {
+                if !names.contains(&name.as_str()) {
+                    names.push(name);
+                }
+            }
+            ExprKind::Unary { operand, .. } => operand.collect_identifiers(names),
+            ExprKind::Binary { lhs, rhs, .. } => {
+                lhs.collect_identifiers(names);
+                rhs.collect_identifiers(names);
+            }
+            ExprKind::Call { args, .. } => {
+                for arg in args {
+                    arg.collect_identifiers(names);
+                }
+            }
+            ExprKind::Number(_) | ExprKind::Duration(_) | ExprKind::Bool(_) => {}
+        }
+    }
+}
+
+/// Parse an expression; `base` is the span of `text` in the strategy source
+pub fn parse_expr(text: &str, base: Span) -> Result<Expr, Diagnostic> {
+    let tokens = Lexer { text, base }.tokenize()?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        end: offset_span(text, base, text.len(), text.len()),
+    };
+
+    if parser.tokens.is_empty() {
+        return Err(Diagnostic::error("empty expression", base));
+    }
+    let expr = parser.expression(0)?;
+    if let Some(token) = parser.tokens.get(parser.pos) {
+        return Err(Diagnostic::error(
+            format!("unexpected `{}`", token.text),
+            token.span,
+        ));
+    }
+    Ok(expr)
+}
+
+/// Span of `text[start..end]` given the span of `text`
+fn offset_span(text: &str, base: Span, start: usize, end: usize) -> Span {
+    Span::new(
+        base.start + start,
+        base.start + end,
+        base.line,
+        base.column + text[..start].chars().count(),
+    )
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Number(f64),
+    Duration(f64),
+    Bool(bool),
+    Ident(String),
+    Op(BinaryOp),
+    Not,
+    LParen,
+    RParen,
+    Comma,
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    tok: Tok,
+    text: String,
+    span: Span,
+}
+
+struct Lexer<'a> {
+    text: &'a str,
+    base: Span,
+}
+
+impl<'a> Lexer<'a> {
+    fn span(&self, start: usize, end: usize) -> Span {
+        offset_span(self.text, self.base, start, end)
+    }
+
+    fn tokenize(&self) -> Result<Vec<Token>, Diagnostic> {
+        let bytes = self.text.as_bytes();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+
+        while i < bytes.len() {
+            let c = bytes[i];
+            if c.is_ascii_whitespace() {
+                i += 1;
+                continue;
+            }
+
+            let start = i;
+            let tok = if c.is_ascii_digit()
+                || (c == b'.' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit))
+            {
+                let (tok, end) = self.number(start)?;
+                i = end;
+                tok
+            } else if c.is_ascii_alphabetic() || c == b'_' {
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                match &self.text[start..i] {
+                    "AND" | "and" => Tok::Op(BinaryOp::And),
+                    "OR" | "or" => Tok::Op(BinaryOp::Or),
+                    "NOT" | "not" => Tok::Not,
+                    "true" => Tok::Bool(true),
+                    "false" => Tok::Bool(false),
+                    name => Tok::Ident(name.to_string()),
+                }
+            } else {
+                let two = self.text.get(i..i + 2).unwrap_or("");
+                let (tok, len) = match (two, c) {
+                    (">=", _) => (Tok::Op(BinaryOp::Ge), 2),
+                    ("<=", _) => (Tok::Op(BinaryOp::Le), 2),
+                    ("==", _) => (Tok::Op(BinaryOp::Eq), 2),
+                    ("!=", _) => (Tok::Op(BinaryOp::Ne), 2),
+                    ("&&", _) => (Tok::Op(BinaryOp::And), 2),
+                    ("||", _) => (Tok::Op(BinaryOp::Or), 2),
+                    (_, b'>') => (Tok::Op(BinaryOp::Gt), 1),
+                    (_, b'<') => (Tok::Op(BinaryOp::Lt), 1),
+                    (_, b'+') => (Tok::Op(BinaryOp::Add), 1),
+                    (_, b'-') => (Tok::Op(BinaryOp::Sub), 1),
+                    (_, b'*') => (Tok::Op(BinaryOp::Mul), 1),
+                    (_, b'/') => (Tok::Op(BinaryOp::Div), 1),
+                    (_, b'!') => (Tok::Not, 1),
+                    (_, b'(') => (Tok::LParen, 1),
+                    (_, b')') => (Tok::RParen, 1),
+                    (_, b',') => (Tok::Comma, 1),
+                    (_, b'=') => {
+                        return Err(Diagnostic::error(
+                            "`=` is not a comparison; use `==`",
+                            self.span(i, i + 1),
+                        ))
+                    }
+                    _ => {
+                        let ch = self.text[i..].chars().next().unwrap_or('?');
+                        return Err(Diagnostic::error(
+                            format!("unexpected character `{}`", ch),
+                            self.span(i, i + ch.len_utf8()),
+                        ));
+                    }
+                };
+                i += len;
+                tok
+            };
+
+            tokens.push(Token {
+                tok,
+                text: self.text[start..i].to_string(),
+                span: self.span(start, i),
+            });
+        }
+
+        Ok(tokens)
+    }
+
+    /// Number with optional `%`, duration or magnitude suffix
+    fn number(&self, start: usize) -> Result<(Tok, usize), Diagnostic> {
+        let bytes = self.text.as_bytes();
+        let mut i = start;
+        while i < bytes.len()
+            && (bytes[i].is_ascii_digit()
+                || bytes[i] == b'.'
+                || (bytes[i] == b'_' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit)))
+        {
+            i += 1;
+        }
+        let digits = self.text[start..i].replace('_', "");
+        let value: f64 = digits.parse().map_err(|_| {
+            Diagnostic::error(
+                format!("invalid number `{}`", &self.text[start..i]),
+                self.span(start, i),
+            )
+        })?;
+
+        let rest = &self.text[i..];
+        let (tok, suffix_len) = if rest.starts_with('%') {
+            (Tok::Number(value / 100.0), 1)
+        } else if rest.starts_with("ms") {
+            (Tok::Duration(value / 1000.0), 2)
+        } else if rest.starts_with('s') {
+            (Tok::Duration(value), 1)
+        } else if rest.starts_with('m') {
+            (Tok::Duration(value * 60.0), 1)
+        } else if rest.starts_with('h') {
+            (Tok::Duration(value * 3600.0), 1)
+        } else if rest.starts_with('K') {
+            (Tok::Number(value * 1e3), 1)
+        } else if rest.starts_with('M') {
+            (Tok::Number(value * 1e6), 1)
+        } else if rest.starts_with('B') {
+            (Tok::Number(value * 1e9), 1)
+        } else {
+            (Tok::Number(value), 0)
+        };
+
+        let end = i + suffix_len;
+        let trailing = self.text[end..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(self.text.len() - end);
+        if trailing > 0 {
+            return Err(Diagnostic::error(
+                format!(
+                    "invalid number literal `{}`",
+                    &self.text[start..end + trailing]
+                ),
+                self.span(start, end + trailing),
+            ));
+        }
+        Ok((tok, end))
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Zero-width span at the end of the input, for "unexpected end" errors
+    end: Span,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<Token, Diagnostic> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| Diagnostic::error("unexpected end of expression", self.end))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, tok: Tok, what: &str) -> Result<Token, Diagnostic> {
+        let token = self.next().map_err(|mut e| {
+            e.message = format!("expected {}", what);
+            e
+        })?;
+        if token.tok != tok {
+            return Err(Diagnostic::error(
+                format!("expected {}, found `{}`", what, token.text),
+                token.span,
+            ));
+        }
+        Ok(token)
+    }
+
+    fn expression(&mut self, min_bp: u8) -> Result<Expr, Diagnostic> {
+        let mut lhs = self.prefix()?;
+
+        while let Some(Token {
+            tok: Tok::Op(op), ..
+        }) = self.peek()
+        {
+            let op = *op;
+            let (left_bp, right_bp) = op.binding_power();
+            if left_bp < min_bp {
+                break;
+            }
+            self.next()?;
+            let rhs = self.expression(right_bp)?;
+
+            if op.is_comparison() {
+                if let Some(Token {
+                    tok: Tok::Op(next),
+                    span,
+                    ..
+                }) = self.peek()
+                {
+                    if next.is_comparison() {
+                        return Err(Diagnostic::error(
+                            "comparisons cannot be chained; combine them with AND",
+                            *span,
+                        ));
+                    }
+                }
+            }
+
+            let span = join(lhs.span, rhs.span);
+            lhs = Expr {
+                kind: ExprKind::Binary {
+                    op,
+                    lhs: Box::new(lhs),
+                    rhs: Box::new(rhs),
+                },
+                span,
+            };
+        }
+
+        Ok(lhs)
+    }
+
+    fn prefix(&mut self) -> Result<Expr, Diagnostic> {
+        let token = self.next()?;
+        let kind = match token.tok {
+            Tok::Number(n) => ExprKind::Number(n),
+            Tok::Duration(d) => ExprKind::Duration(d),
+            Tok::Bool(b) => ExprKind::Bool(b),
+            Tok::Ident(name) => {
+                if matches!(
+                    self.peek(),
+                    Some(Token {
+                        tok: Tok::LParen,
+                        ..
+                    })
+                ) {
+                    return self.call(name, token.span);
+                }
+                ExprKind::Ident(name)
+            }
+            Tok::Not | Tok::Op(BinaryOp::Sub) => {
+                let (op, bp) = if token.tok == Tok::Not {
+                    (UnaryOp::Not, NOT_BINDING_POWER)
+                } else {
+                    (UnaryOp::Neg, NEG_BINDING_POWER)
+                };
+                let operand = self.expression(bp)?;
+                return Ok(Expr {
+                    span: join(token.span, operand.span),
+                    kind: ExprKind::Unary {
+                        op,
+                        operand: Box::new(operand),
+                    },
+                });
+            }
+            Tok::LParen => {
+                let inner = self.expression(0)?;
+                let close = self.expect(Tok::RParen, "`)`")?;
+                return Ok(Expr {
+                    kind: inner.kind,
+                    span: join(token.span, close.span),
+                });
+            }
+            _ => {
+                return Err(Diagnostic::error(
+                    format!("expected a value, found `{}`", token.text),
+                    token.span,
+                ))
+            }
+        };
+        Ok(Expr {
+            kind,
+            span: token.span,
+        })
+    }
+
+    fn call(&mut self, name: String, name_span: Span) -> Result<Expr, Diagnostic> {
+        self.expect(Tok::LParen, "`(`")?;
+        let mut args = Vec::new();
+        if matches!(
+            self.peek(),
+            Some(Token {
+                tok: Tok::RParen,
+                ..
+            })
+        ) {
+            let close = self.next()?;
+            return Ok(Expr {
+                kind: ExprKind::Call { name, args },
+                span: join(name_span, close.span),
+            });
+        }
+
+        loop {
+            args.push(self.expression(0)?);
+            let token = self.next().map_err(|mut e| {
+                e.message = format!("unclosed call to `{}`", name);
+                e
+            })?;
+            match token.tok {
+                Tok::Comma => continue,
+                Tok::RParen => {
+                    return Ok(Expr {
+                        kind: ExprKind::Call { name, args },
+                        span: join(name_span, token.span),
+                    })
+                }
+                _ => {
+                    return Err(Diagnostic::error(
+                        format!("expected `,` or `)`, found `{}`", token.text),
+                        token.span,
+                    ))
+                }
+            }
+        }
+    }
+}
+
+fn join(first: Span, last: Span) -> Span {
+    Span::new(first.start, last.end, first.line, first.column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Expr {
+        parse_expr(text, Span::new(0, text.len(), 1, 1)).unwrap()
+    }
+
+    fn error(text: &str) -> Diagnostic {
+        parse_expr(text, Span::new(100, 100 + text.len(), 7, 12)).unwrap_err()
+    }
+
+    #[test]
+    fn test_precedence() {
+        let expr = parse("a > 1 AND b < 2 OR NOT c");
+        let ExprKind::Binary {
+            op: BinaryOp::Or,
+            lhs,
+            rhs,
+        } = &expr.kind
+        else {
+            panic!("{:?}", expr)
+        };
+        assert!(matches!(
+            lhs.kind,
+            ExprKind::Binary {
+                op: BinaryOp::And,
+                ..
+            }
+        ));
+        assert!(matches!(
+            rhs.kind,
+            ExprKind::Unary {
+                op: UnaryOp::Not,
+                ..
+            }
+        ));
+
+        let expr = parse("volume > avg_volume_20 * 1.5 + 2");
+        let ExprKind::Binary {
+            op: BinaryOp::Gt,
+            rhs,
+            ..
+        } = &expr.kind
+        else {
+            panic!("{:?}", expr)
+        };
+        let ExprKind::Binary {
+            op: BinaryOp::Add,
+            lhs,
+            ..
+        } = &rhs.kind
+        else {
+            panic!("{:?}", rhs)
+        };
+        assert!(matches!(
+            lhs.kind,
+            ExprKind::Binary {
+                op: BinaryOp::Mul,
+                ..
+            }
+        ));
+
+        let expr = parse("NOT rsi > 70");
+        let ExprKind::Unary { operand, .. } = &expr.kind else {
+            panic!("{:?}", expr)
+        };
+        assert!(matches!(
+            operand.kind,
+            ExprKind::Binary {
+                op: BinaryOp::Gt,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_literals() {
+        assert_eq!(parse("2.5%").kind, ExprKind::Number(0.025));
+        assert_eq!(parse("300s").kind, ExprKind::Duration(300.0));
+        assert_eq!(parse("100ms").kind, ExprKind::Duration(0.1));
+        assert_eq!(parse("5m").kind, ExprKind::Duration(300.0));
+        assert_eq!(parse("10M").kind, ExprKind::Number(10_000_000.0));
+        assert_eq!(parse("1_000").kind, ExprKind::Number(1000.0));
+        assert!(matches!(
+            parse("-0.6").kind,
+            ExprKind::Unary {
+                op: UnaryOp::Neg,
+                ..
+            }
+        ));
+        assert_eq!(parse("max(a, 2)").identifiers(), vec!["a"]);
+    }
+
+    #[test]
+    fn test_spans_are_offset_into_source() {
+        let expr = parse_expr("loss > 2.5% OR support_broken", Span::new(40, 69, 3, 18)).unwrap();
+        let ExprKind::Binary { rhs, .. } = &expr.kind else {
+            panic!("{:?}", expr)
+        };
+        assert_eq!(rhs.span, Span::new(55, 69, 3, 33));
+        assert_eq!(expr.span, Span::new(40, 69, 3, 18));
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = error("price_deviation > 2_std");
+        assert!(err.message.contains("2_std"), "{}", err);
+        assert_eq!(err.span.column, 30);
+
+        assert!(error("a < b < c").message.contains("chained"));
+        assert!(error("a = 1").message.contains("=="));
+        assert!(error("a >").message.contains("end of expression"));
+        assert!(error("(a > 1").message.contains("`)`"));
+        assert!(error("a > 1 b").message.contains("unexpected `b`"));
+        assert!(error("").message.contains("empty"));
+    }
+}
@@ -0,0 +1,182 @@
+//! DSL FORMAT - Canonical Layout of Strategy Files
+//!
+//! Jeden styl dla plików pisanych ręcznie i generowanych przez
+//! `StrategyDSLGenerator`: dwie spacje na poziom, `key: value`,
+//! komentarze zachowane, pojedyncze puste linie.
+//!
+//! Values are printed exactly as written; only indentation, spacing around
+//! `:` and `-`, trailing whitespace, blank-line runs and line endings change.
+
+use super::syntax::{Document, LineContent, Node};
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+/// Spaces per nesting level
+const INDENT: usize = 2;
+/// Gap between a value and its trailing comment
+const COMMENT_GAP: &str = "  ";
+
+/// Canonical form of `source`; files with syntax errors are refused
+pub fn format_source(source: &str) -> Result<String> {
+    let parsed = super::parse(source);
+    if parsed.has_errors() {
+        let errors: Vec<_> = parsed.errors().cloned().collect();
+        return Err(anyhow!(
+            "Cannot format a strategy with {} error(s):\n{}",
+            errors.len(),
+            super::render_diagnostics(&errors)
+        ));
+    }
+    Ok(format_document(&parsed.document))
+}
+
+/// Reprint a parsed document in canonical layout
+pub fn format_document(document: &Document) -> String {
+    let layout = layout(document);
+    let mut out = String::new();
+    let mut blank_run = true; // no blank lines at the top of the file
+
+    for (index, line) in document.lines.iter().enumerate() {
+        let comment = line.comment.as_ref().map(|c| c.node.trim_end());
+        let text = match &line.content {
+            LineContent::Blank => match comment {
+                // A comment-only line sits at the indent of what it describes
+                Some(comment) => {
+                    let column = (index..document.lines.len())
+                        .find_map(|next| layout.get(&next))
+                        .map_or(0, |place| place.indent);
+                    format!("{}{}", " ".repeat(column), comment)
+                }
+                None => String::new(),
+            },
+            LineContent::Header { name, .. } => format!("strategy {}:", name.node),
+            LineContent::Entry(entry) => {
+                let (indent, dash) = layout
+                    .get(&index)
+                    .map_or((line.indent.len(), entry.dash.is_some()), |place| {
+                        (place.indent, place.dash)
+                    });
+                let mut text = " ".repeat(indent);
+                if dash {
+                    text.push_str("- ");
+                }
+                text.push_str(&entry.key.node);
+                text.push(':');
+                if let Some(value) = &entry.value {
+                    text.push(' ');
+                    text.push_str(value.raw.trim());
+                }
+                text
+            }
+            LineContent::Error(raw) => format!("{}{}", line.indent, raw.trim_end()),
+        };
+        let text = match (&line.content, comment) {
+            (LineContent::Blank, _) | (_, None) => text,
+            (_, Some(comment)) => format!("{}{}{}", text, COMMENT_GAP, comment),
+        };
+
+        if text.is_empty() {
+            if !blank_run {
+                out.push('\n');
+            }
+            blank_run = true;
+        } else {
+            out.push_str(&text);
+            out.push('\n');
+            blank_run = false;
+        }
+    }
+
+    // No blank lines at the end either
+    while out.ends_with("\n\n") {
+        out.pop();
+    }
+    out
+}
+
+/// Where a structured line goes: indent before the key or `- `
+#[derive(Debug, Clone, Copy)]
+struct Place {
+    indent: usize,
+    dash: bool,
+}
+
+/// Canonical place of every line in the strategy tree, by line index
+fn layout(document: &Document) -> HashMap<usize, Place> {
+    let mut places = HashMap::new();
+    if let Some(strategy) = &document.strategy {
+        places.insert(
+            strategy.line,
+            Place {
+                indent: 0,
+                dash: false,
+            },
+        );
+        for node in &strategy.body {
+            place(node, INDENT, false, &mut places);
+        }
+    }
+    places
+}
+
+/// `key_column` is where the key starts; a dash goes two columns before it
+fn place(node: &Node, key_column: usize, dash: bool, places: &mut HashMap<usize, Place>) {
+    let indent = if dash {
+        key_column - INDENT
+    } else {
+        key_column
+    };
+    places.insert(node.line, Place { indent, dash });
+    for child in &node.children {
+        place(child, key_column + INDENT, false, places);
+    }
+    for item in &node.items {
+        for (i, entry) in item.entries.iter().enumerate() {
+            place(entry, key_column + 2 * INDENT, i == 0, places);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOMENTUM_V1: &str = include_str!("../../../strategies/momentum_v1.dsl");
+
+    #[test]
+    fn test_layout_is_canonical() {
+        let source = "\n\nstrategy   Messy :\n    risk_model:   // limits\n          stop_loss :2%   \n          max_positions:    3\n\n\n\n    entry_logic:\n     -   trigger: \"rsi < 30\"\n         action: market_buy(size=10%)\n  // trailing note\n\n";
+        let formatted = format_source(source).unwrap();
+        assert_eq!(
+            formatted,
+            "strategy Messy:\n  risk_model:  // limits\n    stop_loss: 2%\n    max_positions: 3\n\n  entry_logic:\n    - trigger: \"rsi < 30\"\n      action: market_buy(size=10%)\n// trailing note\n"
+        );
+        assert_eq!(format_source(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_checked_in_strategy_keeps_its_meaning() {
+        let formatted = format_source(MOMENTUM_V1).unwrap();
+        assert_eq!(format_source(&formatted).unwrap(), formatted);
+        assert!(formatted.lines().all(|line| line == line.trim_end()));
+        assert!(!formatted.contains("\n\n\n"));
+
+        let before = super::super::parse_strategy(MOMENTUM_V1).unwrap();
+        let after = super::super::parse_strategy(&formatted).unwrap();
+        assert_eq!(before.entry_logic.len(), after.entry_logic.len());
+        assert_eq!(before.exit_logic.len(), after.exit_logic.len());
+        assert_eq!(before.ai_models.len(), after.ai_models.len());
+        assert_eq!(before.alerts.len(), after.alerts.len());
+        assert_eq!(
+            before.risk_model.unwrap().stop_loss.unwrap().node,
+            after.risk_model.unwrap().stop_loss.unwrap().node
+        );
+    }
+
+    #[test]
+    fn test_syntax_errors_are_not_formatted() {
+        let err =
+            format_source("strategy Broken:\n  risk_model:\n    stop_loss: [2%\n").unwrap_err();
+        assert!(err.to_string().contains("3:16"), "{}", err);
+    }
+}
@@ -0,0 +1,310 @@
+//! DSL INDICATORS - `technical_indicators` → IndicatorEngine
+//!
+//! Deklaracje wskaźników ze strategii mapowane na streaming
+//! `modules::indicators`; ich wyjścia trafiają do przestrzeni cech DSL
+//! (`rsi`, `macd_signal`, `bollinger_bands_upper`, ...).
+
+use super::ast::{IndicatorDecl, StrategyAst};
+use super::eval::FeatureSnapshot;
+use super::syntax::find;
+use super::{Diagnostic, Span};
+use crate::modules::indicators::{
+    IndicatorDefinition, IndicatorEngine, IndicatorSource, IndicatorSpec, Timeframe,
+};
+
+/// Timeframe of indicators that do not declare `timeframe:`
+pub const DEFAULT_TIMEFRAME: Timeframe = Timeframe::M1;
+
+/// Indicator definitions declared by the strategy, with a diagnostic for
+/// every unsupported type, source or setting
+pub fn definitions(ast: &StrategyAst) -> (Vec<IndicatorDefinition>, Vec<Diagnostic>) {
+    let (engine, diagnostics) = build(ast);
+    (engine.definitions().to_vec(), diagnostics)
+}
+
+/// Fresh engine computing the strategy's indicators
+pub fn engine(ast: &StrategyAst) -> Result<IndicatorEngine, Vec<Diagnostic>> {
+    let (engine, diagnostics) = build(ast);
+    if diagnostics.is_empty() {
+        Ok(engine)
+    } else {
+        Err(diagnostics)
+    }
+}
+
+fn build(ast: &StrategyAst) -> (IndicatorEngine, Vec<Diagnostic>) {
+    let mut diagnostics = Vec::new();
+    let mut engine = IndicatorEngine::new();
+
+    for decl in &ast.technical_indicators {
+        // Missing name or type was already reported while lowering
+        let (Some(name), Some(kind)) = (&decl.name, &decl.kind) else {
+            continue;
+        };
+        let Some(definition) =
+            definition(decl, &name.node, &kind.node, kind.span, &mut diagnostics)
+        else {
+            continue;
+        };
+        if let Err(e) = engine.add(definition) {
+            diagnostics.push(Diagnostic::error(e.to_string(), decl.span));
+        }
+    }
+
+    (engine, diagnostics)
+}
+
+impl FeatureSnapshot {
+    /// Add every warmed-up indicator output of `token`
+    pub fn with_indicators(mut self, engine: &IndicatorEngine, token: &str) -> Self {
+        for (name, value) in engine.values(token) {
+            self.set(&name, super::ExprValue::Number(value));
+        }
+        self
+    }
+}
+
+fn definition(
+    decl: &IndicatorDecl,
+    name: &str,
+    kind: &str,
+    kind_span: Span,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<IndicatorDefinition> {
+    let mut settings = Settings {
+        decl,
+        name,
+        diagnostics,
+    };
+
+    let spec = match kind {
+        "simple_moving_average" | "sma" => IndicatorSpec::Sma {
+            period: settings.period(None)?,
+            source: settings.source()?,
+        },
+        "exponential_moving_average" | "ema" => IndicatorSpec::Ema {
+            period: settings.period(None)?,
+            source: settings.source()?,
+        },
+        "relative_strength_index" | "rsi" => IndicatorSpec::Rsi {
+            period: settings.period(Some(14))?,
+        },
+        "moving_average_convergence_divergence" | "macd" => IndicatorSpec::Macd {
+            fast: settings.integer("fast_period", 12)?,
+            slow: settings.integer("slow_period", 26)?,
+            signal: settings.integer("signal_period", 9)?,
+        },
+        "volume_weighted_average_price" | "vwap" => IndicatorSpec::Vwap {
+            period: settings.period(None)?,
+        },
+        "average_true_range" | "atr" => IndicatorSpec::Atr {
+            period: settings.period(Some(14))?,
+        },
+        "bollinger_bands" | "bollinger" => IndicatorSpec::Bollinger {
+            period: settings.period(Some(20))?,
+            std_dev: settings.number("std_dev", 2.0)?,
+        },
+        "volume_spike" => IndicatorSpec::VolumeSpike {
+            period: settings.period(Some(20))?,
+        },
+        _ => {
+            settings.error(format!("unsupported indicator type `{}`", kind), kind_span);
+            return None;
+        }
+    };
+    let timeframe = settings.timeframe()?;
+
+    Some(IndicatorDefinition {
+        name: name.to_string(),
+        spec,
+        timeframe,
+    })
+}
+
+/// Reads indicator-specific keys, reporting bad values
+struct Settings<'a> {
+    decl: &'a IndicatorDecl,
+    name: &'a str,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl Settings<'_> {
+    fn error(&mut self, message: String, span: Span) {
+        self.diagnostics.push(Diagnostic::error(message, span));
+    }
+
+    /// `period:`, required when there is no conventional default
+    fn period(&mut self, default: Option<usize>) -> Option<usize> {
+        match (self.decl.period.as_ref(), default) {
+            (Some(period), _) => Some(period.node as usize),
+            (None, Some(default)) => Some(default),
+            (None, None) => {
+                let message = format!("indicator `{}` requires a `period`", self.name);
+                self.error(message, self.decl.span);
+                None
+            }
+        }
+    }
+
+    fn integer(&mut self, key: &str, default: usize) -> Option<usize> {
+        let Some(value) = find(&self.decl.entries, key).and_then(|n| n.value.as_ref()) else {
+            return Some(default);
+        };
+        match value.as_u64() {
+            Some(n) => Some(n as usize),
+            None => {
+                self.error(
+                    format!(
+                        "`{}` expects a non-negative integer, found `{}`",
+                        key, value.raw
+                    ),
+                    value.span,
+                );
+                None
+            }
+        }
+    }
+
+    fn number(&mut self, key: &str, default: f64) -> Option<f64> {
+        let Some(value) = find(&self.decl.entries, key).and_then(|n| n.value.as_ref()) else {
+            return Some(default);
+        };
+        match value.as_f64() {
+            Some(n) => Some(n),
+            None => {
+                self.error(
+                    format!("`{}` expects a number, found `{}`", key, value.raw),
+                    value.span,
+                );
+                None
+            }
+        }
+    }
+
+    fn source(&mut self) -> Option<IndicatorSource> {
+        let Some(source) = &self.decl.source else {
+            return Some(IndicatorSource::Price);
+        };
+        match source.node.as_str() {
+            "price" | "close" => Some(IndicatorSource::Price),
+            "volume" => Some(IndicatorSource::Volume),
+            other => {
+                self.error(
+                    format!(
+                        "unsupported indicator source `{}`; expected `price` or `volume`",
+                        other
+                    ),
+                    source.span,
+                );
+                None
+            }
+        }
+    }
+
+    fn timeframe(&mut self) -> Option<Timeframe> {
+        let Some(value) = find(&self.decl.entries, "timeframe").and_then(|n| n.value.as_ref())
+        else {
+            return Some(DEFAULT_TIMEFRAME);
+        };
+        match Timeframe::parse(value.text()) {
+            Ok(timeframe) => Some(timeframe),
+            Err(e) => {
+                self.error(e.to_string(), value.span);
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::dsl::parse_strategy;
+    use chrono::TimeZone;
+
+    const MOMENTUM_V1: &str = include_str!("../../../strategies/momentum_v1.dsl");
+    const SENTIMENT_AGENT_V1: &str = include_str!("../../../strategies/sentiment_agent_v1.dsl");
+
+    #[test]
+    fn test_checked_in_indicators_map_to_engine() {
+        let ast = parse_strategy(MOMENTUM_V1).unwrap();
+        let engine = engine(&ast).unwrap();
+
+        let outputs = engine.outputs();
+        for name in [
+            "ema_fast",
+            "rsi",
+            "macd",
+            "macd_signal",
+            "volume_ma",
+            "vwap",
+        ] {
+            assert!(outputs.contains(&name.to_string()), "{:?}", outputs);
+        }
+        let macd = &engine.definitions()[3];
+        assert_eq!(
+            macd.spec,
+            IndicatorSpec::Macd {
+                fast: 12,
+                slow: 26,
+                signal: 9
+            }
+        );
+        assert_eq!(
+            engine.definitions()[4].spec,
+            IndicatorSpec::Sma {
+                period: 20,
+                source: IndicatorSource::Volume
+            }
+        );
+        assert!(engine
+            .definitions()
+            .iter()
+            .all(|d| d.timeframe == Timeframe::M1));
+    }
+
+    #[test]
+    fn test_unsupported_settings_are_reported() {
+        let ast = parse_strategy(SENTIMENT_AGENT_V1).unwrap();
+        let diagnostics = engine(&ast).unwrap_err();
+        assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+        assert!(diagnostics[0]
+            .message
+            .contains("unsupported indicator source `sentiment_score`"));
+        assert_eq!(
+            diagnostics[0].span.text(SENTIMENT_AGENT_V1),
+            "\"sentiment_score\""
+        );
+
+        let source = "strategy S:\n  technical_indicators:\n    - name: \"x\"\n      type: \"ichimoku\"\n    - name: \"fast\"\n      type: \"ema\"\n      timeframe: \"2m\"\n      period: 5\n";
+        let diagnostics = engine(&parse_strategy(source).unwrap()).unwrap_err();
+        assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+        assert_eq!(
+            diagnostics[0].message,
+            "unsupported indicator type `ichimoku`"
+        );
+        assert!(diagnostics[1].message.contains("Unknown timeframe '2m'"));
+    }
+
+    #[test]
+    fn test_indicator_values_feed_snapshot() {
+        let source = "strategy S:\n  technical_indicators:\n    - name: \"spike\"\n      type: \"volume_spike\"\n      period: 2\n      timeframe: \"tick\"\n";
+        let mut engine = engine(&parse_strategy(source).unwrap()).unwrap();
+        let at = chrono::Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        for volume in [10.0, 10.0, 40.0] {
+            engine.on_tick("BONK", 1.0, volume, at);
+        }
+
+        let snapshot = FeatureSnapshot::new().with_indicators(&engine, "BONK");
+        assert_eq!(
+            snapshot.get("spike"),
+            Some(super::super::ExprValue::Number(4.0))
+        );
+        assert_eq!(
+            FeatureSnapshot::new()
+                .with_indicators(&engine, "WIF")
+                .get("spike"),
+            None
+        );
+    }
+}
@@ -0,0 +1,882 @@
+//! DSL INTERVALS - Abstract Interpretation of Trigger Expressions
+//!
+//! Każda cecha to przedział (otwarty lub domknięty); warunki zawężają
+//! przedziały (HC4-revise), a pusty przedział to dowód niespełnialności.
+//!
+//! The abstraction only ever over-approximates, so `Unsatisfiable` is a
+//! proof. A `Satisfiable` verdict always carries a concrete assignment that
+//! was re-checked with `eval::evaluate`, so a witness is never an artefact of
+//! the abstraction; when no such assignment is found the answer is `Unknown`.
+
+use super::eval::{self, ExprType, ExprValue, FeatureSchema};
+use super::expr::{BinaryOp, Expr, ExprKind, UnaryOp};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+/// Boxes kept per query before they are merged into their hull
+const MAX_BOXES: usize = 256;
+/// Narrowing rounds per comparison
+const NARROWING_ROUNDS: usize = 4;
+/// Bisections tried when the first candidate of a box is not a witness
+const SPLIT_DEPTH: usize = 8;
+
+/// Reals between two bounds, each open or closed; infinite bounds are open
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    pub lo: f64,
+    pub hi: f64,
+    pub lo_open: bool,
+    pub hi_open: bool,
+}
+
+impl Interval {
+    pub const ALL: Interval = Interval {
+        lo: f64::NEG_INFINITY,
+        hi: f64::INFINITY,
+        lo_open: true,
+        hi_open: true,
+    };
+
+    pub fn new(lo: f64, hi: f64, lo_open: bool, hi_open: bool) -> Self {
+        let lo = if lo.is_nan() { f64::NEG_INFINITY } else { lo };
+        let hi = if hi.is_nan() { f64::INFINITY } else { hi };
+        Self {
+            lo,
+            hi,
+            lo_open: lo_open || lo.is_infinite(),
+            hi_open: hi_open || hi.is_infinite(),
+        }
+    }
+
+    pub fn closed(lo: f64, hi: f64) -> Self {
+        Self::new(lo, hi, false, false)
+    }
+
+    pub fn point(x: f64) -> Self {
+        Self::closed(x, x)
+    }
+
+    /// `[x, ∞)`
+    pub fn at_least(x: f64) -> Self {
+        Self::new(x, f64::INFINITY, false, true)
+    }
+
+    /// `(x, ∞)`
+    pub fn above(x: f64) -> Self {
+        Self::new(x, f64::INFINITY, true, true)
+    }
+
+    /// `(-∞, x]`
+    pub fn at_most(x: f64) -> Self {
+        Self::new(f64::NEG_INFINITY, x, true, false)
+    }
+
+    /// `(-∞, x)`
+    pub fn below(x: f64) -> Self {
+        Self::new(f64::NEG_INFINITY, x, true, true)
+    }
+
+    pub fn non_negative() -> Self {
+        Self::at_least(0.0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lo > self.hi
+            || (self.lo == self.hi && (self.lo_open || self.hi_open || self.lo.is_infinite()))
+    }
+
+    pub fn is_point(&self) -> bool {
+        self.lo == self.hi && !self.is_empty()
+    }
+
+    pub fn contains(&self, x: f64) -> bool {
+        let above_lo = if self.lo_open {
+            x > self.lo
+        } else {
+            x >= self.lo
+        };
+        let below_hi = if self.hi_open {
+            x < self.hi
+        } else {
+            x <= self.hi
+        };
+        above_lo && below_hi
+    }
+
+    pub fn width(&self) -> f64 {
+        self.hi - self.lo
+    }
+
+    pub fn intersect(&self, other: &Interval) -> Interval {
+        let (lo, lo_open) = match self.lo.total_cmp(&other.lo) {
+            std::cmp::Ordering::Less => (other.lo, other.lo_open),
+            std::cmp::Ordering::Greater => (self.lo, self.lo_open),
+            std::cmp::Ordering::Equal => (self.lo, self.lo_open || other.lo_open),
+        };
+        let (hi, hi_open) = match self.hi.total_cmp(&other.hi) {
+            std::cmp::Ordering::Less => (self.hi, self.hi_open),
+            std::cmp::Ordering::Greater => (other.hi, other.hi_open),
+            std::cmp::Ordering::Equal => (self.hi, self.hi_open || other.hi_open),
+        };
+        Interval::new(lo, hi, lo_open, hi_open)
+    }
+
+    /// Smallest interval containing both
+    pub fn hull(&self, other: &Interval) -> Interval {
+        if self.is_empty() {
+            return *other;
+        }
+        if other.is_empty() {
+            return *self;
+        }
+        let (lo, lo_open) = match self.lo.total_cmp(&other.lo) {
+            std::cmp::Ordering::Less => (self.lo, self.lo_open),
+            std::cmp::Ordering::Greater => (other.lo, other.lo_open),
+            std::cmp::Ordering::Equal => (self.lo, self.lo_open && other.lo_open),
+        };
+        let (hi, hi_open) = match self.hi.total_cmp(&other.hi) {
+            std::cmp::Ordering::Less => (other.hi, other.hi_open),
+            std::cmp::Ordering::Greater => (self.hi, self.hi_open),
+            std::cmp::Ordering::Equal => (self.hi, self.hi_open && other.hi_open),
+        };
+        Interval::new(lo, hi, lo_open, hi_open)
+    }
+
+    pub fn neg(&self) -> Interval {
+        Interval::new(-self.hi, -self.lo, self.hi_open, self.lo_open)
+    }
+
+    pub fn add(&self, other: &Interval) -> Interval {
+        Interval::new(
+            self.lo + other.lo,
+            self.hi + other.hi,
+            self.lo_open || other.lo_open,
+            self.hi_open || other.hi_open,
+        )
+    }
+
+    pub fn sub(&self, other: &Interval) -> Interval {
+        self.add(&other.neg())
+    }
+
+    /// Exact for a non-zero constant factor, otherwise the closed hull of
+    /// the endpoint products; `0 × ∞` counts as 0
+    pub fn mul(&self, other: &Interval) -> Interval {
+        if self.is_empty() || other.is_empty() {
+            return Interval::closed(1.0, 0.0);
+        }
+        if other.is_point() && other.lo != 0.0 && other.lo.is_finite() {
+            return self.scale(other.lo);
+        }
+        if self.is_point() && self.lo != 0.0 && self.lo.is_finite() {
+            return other.scale(self.lo);
+        }
+        let product = |a: f64, b: f64| if a == 0.0 || b == 0.0 { 0.0 } else { a * b };
+        let products = [
+            product(self.lo, other.lo),
+            product(self.lo, other.hi),
+            product(self.hi, other.lo),
+            product(self.hi, other.hi),
+        ];
+        Interval::closed(
+            products.iter().copied().fold(f64::INFINITY, f64::min),
+            products.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        )
+    }
+
+    fn scale(&self, k: f64) -> Interval {
+        if k > 0.0 {
+            Interval::new(self.lo * k, self.hi * k, self.lo_open, self.hi_open)
+        } else {
+            Interval::new(self.hi * k, self.lo * k, self.hi_open, self.lo_open)
+        }
+    }
+
+    /// Everything when the divisor may be zero
+    pub fn div(&self, other: &Interval) -> Interval {
+        if other.contains(0.0) {
+            return Interval::ALL;
+        }
+        if other.is_point() && other.lo.is_finite() {
+            return self.scale(1.0 / other.lo);
+        }
+        // The divisor is entirely on one side of zero; a zero bound is open
+        let inverse = if other.lo >= 0.0 {
+            let hi = if other.lo == 0.0 {
+                f64::INFINITY
+            } else {
+                1.0 / other.lo
+            };
+            Interval::closed(1.0 / other.hi, hi)
+        } else {
+            let lo = if other.hi == 0.0 {
+                f64::NEG_INFINITY
+            } else {
+                1.0 / other.hi
+            };
+            Interval::closed(lo, 1.0 / other.lo)
+        };
+        self.mul(&inverse)
+    }
+
+    pub fn abs(&self) -> Interval {
+        if self.lo >= 0.0 {
+            *self
+        } else if self.hi <= 0.0 {
+            self.neg()
+        } else {
+            Interval::closed(0.0, self.hi.max(-self.lo))
+        }
+    }
+
+    pub fn min(&self, other: &Interval) -> Interval {
+        Interval::closed(self.lo.min(other.lo), self.hi.min(other.hi))
+    }
+
+    pub fn max(&self, other: &Interval) -> Interval {
+        Interval::closed(self.lo.max(other.lo), self.hi.max(other.hi))
+    }
+
+    /// Representative member: the midpoint of a bounded interval, a closed
+    /// bound, or a round value past an open one (`loss > 2.5%` gives 5%)
+    pub fn pick(&self) -> Option<f64> {
+        if self.is_empty() {
+            return None;
+        }
+        let value = match (self.lo.is_finite(), self.hi.is_finite()) {
+            (true, true) if self.lo == self.hi => self.lo,
+            (true, true) => self.lo + (self.hi - self.lo) / 2.0,
+            (true, false) if !self.lo_open => self.lo,
+            (true, false) if self.lo > 0.0 => self.lo * 2.0,
+            (true, false) if self.lo == 0.0 => 1.0,
+            (true, false) => 0.0,
+            (false, true) if !self.hi_open => self.hi,
+            (false, true) if self.hi < 0.0 => self.hi * 2.0,
+            (false, true) if self.hi == 0.0 => -1.0,
+            (false, true) => 0.0,
+            (false, false) => 0.0,
+        };
+        Some(value)
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "∅");
+        }
+        let bound = |x: f64| {
+            if x == f64::INFINITY {
+                "∞".to_string()
+            } else if x == f64::NEG_INFINITY {
+                "-∞".to_string()
+            } else {
+                x.to_string()
+            }
+        };
+        write!(
+            f,
+            "{}{}, {}{}",
+            if self.lo_open { "(" } else { "[" },
+            bound(self.lo),
+            bound(self.hi),
+            if self.hi_open { ")" } else { "]" }
+        )
+    }
+}
+
+/// Known ranges of features; anything not listed is unbounded
+#[derive(Debug, Clone, Default)]
+pub struct FeatureBounds {
+    bounds: HashMap<String, Interval>,
+}
+
+impl FeatureBounds {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ranges of the `FeatureSchema::standard` features: prices, volumes,
+    /// losses and durations never go negative
+    pub fn standard() -> Self {
+        let non_negative = [
+            "price",
+            "volume",
+            "bid",
+            "ask",
+            "spread",
+            "volatility",
+            "profit",
+            "loss",
+            "daily_loss",
+            "drawdown",
+            "holding_time",
+            "execution_latency",
+        ];
+        non_negative
+            .iter()
+            .fold(Self::new(), |bounds, name| {
+                bounds.with_bound(name, Interval::non_negative())
+            })
+            .with_bound("liquidity_score", Interval::closed(0.0, 1.0))
+    }
+
+    pub fn with_bound(mut self, name: &str, interval: Interval) -> Self {
+        self.insert(name, interval);
+        self
+    }
+
+    pub fn insert(&mut self, name: &str, interval: Interval) {
+        self.bounds.insert(name.to_string(), interval);
+    }
+
+    pub fn get(&self, name: &str) -> Interval {
+        self.bounds.get(name).copied().unwrap_or(Interval::ALL)
+    }
+}
+
+/// Concrete feature values, sorted by name
+pub type Assignment = BTreeMap<String, ExprValue>;
+
+/// Outcome of a satisfiability query
+#[derive(Debug, Clone, PartialEq)]
+pub enum Satisfiability {
+    /// Assignment the real evaluator accepted
+    Satisfiable(Assignment),
+    /// No assignment within the feature bounds exists
+    Unsatisfiable,
+    /// The abstraction could neither refute nor find a witness
+    Unknown,
+}
+
+/// Abstract state: features narrowed so far
+type Env = BTreeMap<String, Interval>;
+
+/// Interval solver over boolean expressions.
+///
+/// Constants (the `risk_model` values) are fixed points; every other
+/// identifier ranges over its `FeatureBounds`. Identifier types come from the
+/// schema and, for ad-hoc features, from how the expression uses them.
+pub struct Solver<'a> {
+    bounds: &'a FeatureBounds,
+    constants: &'a HashMap<String, ExprValue>,
+    schema: &'a FeatureSchema,
+}
+
+impl<'a> Solver<'a> {
+    pub fn new(
+        bounds: &'a FeatureBounds,
+        constants: &'a HashMap<String, ExprValue>,
+        schema: &'a FeatureSchema,
+    ) -> Self {
+        Self {
+            bounds,
+            constants,
+            schema,
+        }
+    }
+
+    /// Range of a numeric expression over the feature bounds
+    pub fn range(&self, expr: &Expr) -> Interval {
+        self.eval_range(expr, &Env::new())
+    }
+
+    /// Find features making `expr` true, or prove there are none
+    pub fn satisfy(&self, expr: &Expr) -> Satisfiability {
+        let types = self.infer_types(expr);
+        let boxes = self.boxes(expr, Env::new());
+        if boxes.is_empty() {
+            return Satisfiability::Unsatisfiable;
+        }
+        boxes
+            .into_iter()
+            .find_map(|env| self.search(expr, &types, env, SPLIT_DEPTH))
+            .map_or(Satisfiability::Unknown, Satisfiability::Satisfiable)
+    }
+
+    fn boxes(&self, expr: &Expr, env: Env) -> Vec<Env> {
+        let mut boxes = Vec::new();
+        self.refine(expr, true, env, &mut boxes);
+        merge_if_too_many(boxes)
+    }
+
+    /// Label a candidate from `env`, bisecting the widest feature on failure
+    fn search(
+        &self,
+        expr: &Expr,
+        types: &HashMap<String, ExprType>,
+        env: Env,
+        depth: usize,
+    ) -> Option<Assignment> {
+        if let Some(assignment) = self.label(expr, types, env.clone()) {
+            if self.holds(expr, &assignment) {
+                return Some(assignment);
+            }
+        }
+        if depth == 0 {
+            return None;
+        }
+
+        let (name, interval) = expr
+            .identifiers()
+            .into_iter()
+            .filter(|name| !self.constants.contains_key(*name))
+            .filter(|name| types.get(*name) != Some(&ExprType::Bool))
+            .map(|name| (name, self.lookup(name, &env)))
+            .filter(|(_, interval)| !interval.is_point())
+            .max_by(|a, b| a.1.width().total_cmp(&b.1.width()))?;
+        let split = interval.pick()?;
+        let halves = [
+            interval.intersect(&Interval::at_most(split)),
+            interval.intersect(&Interval::above(split)),
+        ];
+        halves
+            .into_iter()
+            .filter(|half| !half.is_empty() && *half != interval)
+            .find_map(|half| {
+                let mut env = env.clone();
+                env.insert(name.to_string(), half);
+                self.boxes(expr, env)
+                    .into_iter()
+                    .find_map(|env| self.search(expr, types, env, depth - 1))
+            })
+    }
+
+    /// Fix features one at a time, re-narrowing the rest after each choice
+    fn label(
+        &self,
+        expr: &Expr,
+        types: &HashMap<String, ExprType>,
+        mut env: Env,
+    ) -> Option<Assignment> {
+        let mut assignment = Assignment::new();
+        for name in expr.identifiers() {
+            if self.constants.contains_key(name) {
+                continue;
+            }
+            let picked = self.lookup(name, &env).pick()?;
+            let value = match types.get(name) {
+                Some(ExprType::Bool) => ExprValue::Bool(picked >= 0.5),
+                Some(ExprType::Duration) => ExprValue::Duration(picked),
+                _ => ExprValue::Number(picked),
+            };
+            let point = match value {
+                ExprValue::Bool(b) => f64::from(u8::from(b)),
+                _ => picked,
+            };
+            env.insert(name.to_string(), Interval::point(point));
+            env = self.boxes(expr, env).into_iter().next()?;
+            assignment.insert(name.to_string(), value);
+        }
+        Some(assignment)
+    }
+
+    fn holds(&self, expr: &Expr, assignment: &Assignment) -> bool {
+        let lookup = |name: &str| {
+            self.constants
+                .get(name)
+                .or_else(|| assignment.get(name))
+                .copied()
+        };
+        matches!(eval::evaluate(expr, &lookup), Ok(ExprValue::Bool(true)))
+    }
+
+    fn lookup(&self, name: &str, env: &Env) -> Interval {
+        match self.constants.get(name) {
+            Some(ExprValue::Number(n)) | Some(ExprValue::Duration(n)) => Interval::point(*n),
+            Some(ExprValue::Bool(b)) => Interval::point(f64::from(u8::from(*b))),
+            None => env
+                .get(name)
+                .copied()
+                .unwrap_or_else(|| self.bounds.get(name)),
+        }
+    }
+
+    /// Forward evaluation; booleans are `[0, 1]`
+    fn eval_range(&self, expr: &Expr, env: &Env) -> Interval {
+        match &expr.kind {
+            ExprKind::Number(n) | ExprKind::Duration(n) => Interval::point(*n),
+            ExprKind::Bool(b) => Interval::point(f64::from(u8::from(*b))),
+            ExprKind::Ident(name) => self.lookup(name, env),
+            ExprKind::Unary {
+                op: UnaryOp::Neg,
+                operand,
+            } => self.eval_range(operand, env).neg(),
+            ExprKind::Unary {
+                op: UnaryOp::Not, ..
+            } => Interval::closed(0.0, 1.0),
+            ExprKind::Binary { op, lhs, rhs } => {
+                let l = self.eval_range(lhs, env);
+                let r = self.eval_range(rhs, env);
+                match op {
+                    BinaryOp::Add => l.add(&r),
+                    BinaryOp::Sub => l.sub(&r),
+                    BinaryOp::Mul => l.mul(&r),
+                    BinaryOp::Div => l.div(&r),
+                    _ => Interval::closed(0.0, 1.0),
+                }
+            }
+            ExprKind::Call { name, args } => {
+                let ranges: Vec<Interval> =
+                    args.iter().map(|arg| self.eval_range(arg, env)).collect();
+                match (name.as_str(), ranges.as_slice()) {
+                    ("abs", [x]) => x.abs(),
+                    ("min", [first, rest @ ..]) => rest.iter().fold(*first, |a, b| a.min(b)),
+                    ("max", [first, rest @ ..]) => rest.iter().fold(*first, |a, b| a.max(b)),
+                    _ => Interval::ALL,
+                }
+            }
+        }
+    }
+
+    /// Push every box (over-approximately) making `expr` equal to `want`
+    fn refine(&self, expr: &Expr, want: bool, env: Env, out: &mut Vec<Env>) {
+        match &expr.kind {
+            ExprKind::Bool(b) => {
+                if *b == want {
+                    out.push(env);
+                }
+            }
+            ExprKind::Ident(_) => {
+                let target = Interval::point(f64::from(u8::from(want)));
+                out.extend(self.narrow(expr, target, env));
+            }
+            ExprKind::Unary {
+                op: UnaryOp::Not,
+                operand,
+            } => self.refine(operand, !want, env, out),
+            ExprKind::Binary {
+                op: op @ (BinaryOp::And | BinaryOp::Or),
+                lhs,
+                rhs,
+            } => {
+                // AND made true and OR made false need both sides
+                if (*op == BinaryOp::And) == want {
+                    let mut left = Vec::new();
+                    self.refine(lhs, want, env, &mut left);
+                    for env in merge_if_too_many(left) {
+                        self.refine(rhs, want, env, out);
+                    }
+                } else {
+                    self.refine(lhs, want, env.clone(), out);
+                    self.refine(rhs, want, env, out);
+                }
+            }
+            ExprKind::Binary { op, lhs, rhs } if op.is_comparison() => {
+                let op = if want { *op } else { negate(*op) };
+                out.extend(self.compare(op, lhs, rhs, env));
+            }
+            // No information, e.g. a boolean-valued call
+            _ => out.push(env),
+        }
+    }
+
+    fn compare(&self, op: BinaryOp, lhs: &Expr, rhs: &Expr, mut env: Env) -> Option<Env> {
+        for _ in 0..NARROWING_ROUNDS {
+            let l = self.eval_range(lhs, &env);
+            let r = self.eval_range(rhs, &env);
+            let (lhs_target, rhs_target) = match op {
+                BinaryOp::Gt => (Interval::above(r.lo), Interval::below(l.hi)),
+                BinaryOp::Ge => (
+                    Interval::new(r.lo, f64::INFINITY, r.lo_open, true),
+                    Interval::new(f64::NEG_INFINITY, l.hi, true, l.hi_open),
+                ),
+                BinaryOp::Lt => (Interval::below(r.hi), Interval::above(l.lo)),
+                BinaryOp::Le => (
+                    Interval::new(f64::NEG_INFINITY, r.hi, true, r.hi_open),
+                    Interval::new(l.lo, f64::INFINITY, l.lo_open, true),
+                ),
+                BinaryOp::Eq => {
+                    let both = l.intersect(&r);
+                    (both, both)
+                }
+                // Only two equal points can refute `!=`
+                _ => {
+                    if l.is_point() && r.is_point() && l.lo == r.lo {
+                        return None;
+                    }
+                    return Some(env);
+                }
+            };
+            let before = env.clone();
+            env = self.narrow(lhs, lhs_target, env)?;
+            env = self.narrow(rhs, rhs_target, env)?;
+            if env == before {
+                break;
+            }
+        }
+        Some(env)
+    }
+
+    /// Narrow the features of `expr` so it can land in `target`
+    fn narrow(&self, expr: &Expr, target: Interval, mut env: Env) -> Option<Env> {
+        if self.eval_range(expr, &env).intersect(&target).is_empty() {
+            return None;
+        }
+        match &expr.kind {
+            ExprKind::Ident(name) if !self.constants.contains_key(name) => {
+                let narrowed = self.lookup(name, &env).intersect(&target);
+                env.insert(name.clone(), narrowed);
+                Some(env)
+            }
+            ExprKind::Unary {
+                op: UnaryOp::Neg,
+                operand,
+            } => self.narrow(operand, target.neg(), env),
+            ExprKind::Binary { op, lhs, rhs } => {
+                let r = self.eval_range(rhs, &env);
+                match op {
+                    BinaryOp::Add => {
+                        let env = self.narrow(lhs, target.sub(&r), env)?;
+                        let l = self.eval_range(lhs, &env);
+                        self.narrow(rhs, target.sub(&l), env)
+                    }
+                    BinaryOp::Sub => {
+                        let env = self.narrow(lhs, target.add(&r), env)?;
+                        let l = self.eval_range(lhs, &env);
+                        self.narrow(rhs, l.sub(&target), env)
+                    }
+                    BinaryOp::Mul => {
+                        if !r.contains(0.0) {
+                            env = self.narrow(lhs, target.div(&r), env)?;
+                        }
+                        let l = self.eval_range(lhs, &env);
+                        if l.contains(0.0) {
+                            return Some(env);
+                        }
+                        self.narrow(rhs, target.div(&l), env)
+                    }
+                    BinaryOp::Div if !r.contains(0.0) => self.narrow(lhs, target.mul(&r), env),
+                    _ => Some(env),
+                }
+            }
+            _ => Some(env),
+        }
+    }
+
+    /// Types of the identifiers in `expr`; ad-hoc features take the type of
+    /// what they are compared with, combined with or negated as
+    fn infer_types(&self, expr: &Expr) -> HashMap<String, ExprType> {
+        let mut types: HashMap<String, ExprType> = expr
+            .identifiers()
+            .into_iter()
+            .filter_map(|name| {
+                let ty = self
+                    .constants
+                    .get(name)
+                    .map(ExprValue::ty)
+                    .or_else(|| self.schema.get(name))?;
+                Some((name.to_string(), ty))
+            })
+            .collect();
+        // Twice, so a type found late in the expression reaches earlier uses
+        for _ in 0..2 {
+            self.infer(expr, Some(ExprType::Bool), &mut types);
+        }
+        types
+    }
+
+    fn infer(
+        &self,
+        expr: &Expr,
+        expected: Option<ExprType>,
+        types: &mut HashMap<String, ExprType>,
+    ) {
+        match &expr.kind {
+            ExprKind::Ident(name) => {
+                if let Some(ty) = expected {
+                    types.entry(name.clone()).or_insert(ty);
+                }
+            }
+            ExprKind::Unary {
+                op: UnaryOp::Not,
+                operand,
+            } => self.infer(operand, Some(ExprType::Bool), types),
+            ExprKind::Unary { operand, .. } => self.infer(operand, expected, types),
+            ExprKind::Binary {
+                op: BinaryOp::And | BinaryOp::Or,
+                lhs,
+                rhs,
+            } => {
+                self.infer(lhs, Some(ExprType::Bool), types);
+                self.infer(rhs, Some(ExprType::Bool), types);
+            }
+            // The scaled side carries the type: `max_hold * 2`, `2 * max_hold`
+            ExprKind::Binary {
+                op: BinaryOp::Mul,
+                lhs,
+                rhs,
+            } if static_type(lhs, types) == Some(ExprType::Number) => {
+                self.infer(rhs, expected, types);
+            }
+            ExprKind::Binary {
+                op: BinaryOp::Mul | BinaryOp::Div,
+                lhs,
+                rhs,
+            } => {
+                self.infer(lhs, expected, types);
+                self.infer(rhs, Some(ExprType::Number), types);
+            }
+            ExprKind::Binary { op, lhs, rhs } => {
+                // Comparisons, `+` and `-` need matching sides
+                let ty = static_type(lhs, types).or_else(|| static_type(rhs, types));
+                let ty = match (op, ty) {
+                    (BinaryOp::Add | BinaryOp::Sub, _) => ty.or(expected),
+                    _ => ty,
+                };
+                self.infer(lhs, ty, types);
+                self.infer(rhs, ty, types);
+            }
+            ExprKind::Call { args, .. } => {
+                for arg in args {
+                    self.infer(arg, expected, types);
+                }
+            }
+            ExprKind::Number(_) | ExprKind::Duration(_) | ExprKind::Bool(_) => {}
+        }
+    }
+}
+
+fn static_type(expr: &Expr, types: &HashMap<String, ExprType>) -> Option<ExprType> {
+    match &expr.kind {
+        ExprKind::Number(_) => Some(ExprType::Number),
+        ExprKind::Duration(_) => Some(ExprType::Duration),
+        ExprKind::Bool(_) => Some(ExprType::Bool),
+        ExprKind::Ident(name) => types.get(name).copied(),
+        ExprKind::Unary {
+            op: UnaryOp::Not, ..
+        } => Some(ExprType::Bool),
+        ExprKind::Unary { operand, .. } => static_type(operand, types),
+        ExprKind::Binary { op, lhs, rhs } => match op {
+            BinaryOp::Add | BinaryOp::Sub => {
+                static_type(lhs, types).or_else(|| static_type(rhs, types))
+            }
+            BinaryOp::Mul => match (static_type(lhs, types), static_type(rhs, types)) {
+                (Some(ExprType::Duration), _) | (_, Some(ExprType::Duration)) => {
+                    Some(ExprType::Duration)
+                }
+                _ => Some(ExprType::Number),
+            },
+            BinaryOp::Div => match (static_type(lhs, types), static_type(rhs, types)) {
+                (Some(ExprType::Duration), Some(ExprType::Number)) => Some(ExprType::Duration),
+                _ => Some(ExprType::Number),
+            },
+            _ => Some(ExprType::Bool),
+        },
+        ExprKind::Call { args, .. } => args.first().and_then(|arg| static_type(arg, types)),
+    }
+}
+
+/// Comparison holding exactly when `op` does not
+fn negate(op: BinaryOp) -> BinaryOp {
+    match op {
+        BinaryOp::Gt => BinaryOp::Le,
+        BinaryOp::Ge => BinaryOp::Lt,
+        BinaryOp::Lt => BinaryOp::Ge,
+        BinaryOp::Le => BinaryOp::Gt,
+        BinaryOp::Eq => BinaryOp::Ne,
+        BinaryOp::Ne => BinaryOp::Eq,
+        other => other,
+    }
+}
+
+/// Replace too many boxes by their hull, which stays sound
+fn merge_if_too_many(boxes: Vec<Env>) -> Vec<Env> {
+    if boxes.len() <= MAX_BOXES {
+        return boxes;
+    }
+    let mut hull = Env::new();
+    let names: Vec<String> = boxes.iter().flat_map(|env| env.keys().cloned()).collect();
+    for name in names {
+        if hull.contains_key(&name) {
+            continue;
+        }
+        // A box that never narrowed `name` leaves it at its bound
+        if boxes.iter().all(|env| env.contains_key(&name)) {
+            let merged = boxes
+                .iter()
+                .map(|env| env[&name])
+                .reduce(|a, b| a.hull(&b))
+                .unwrap_or(Interval::ALL);
+            hull.insert(name, merged);
+        }
+    }
+    vec![hull]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::dsl::{parse_expr, Span};
+
+    fn expr(text: &str) -> Expr {
+        parse_expr(text, Span::new(0, text.len(), 1, 1)).unwrap()
+    }
+
+    fn solve(text: &str) -> Satisfiability {
+        let bounds = FeatureBounds::standard().with_bound("rsi", Interval::closed(0.0, 100.0));
+        let constants = HashMap::from([("stop_loss".to_string(), ExprValue::Number(0.025))]);
+        let schema = FeatureSchema::standard();
+        Solver::new(&bounds, &constants, &schema).satisfy(&expr(text))
+    }
+
+    #[test]
+    fn test_interval_arithmetic_keeps_strict_bounds() {
+        let above = Interval::above(0.025);
+        assert!(above.intersect(&Interval::at_most(0.025)).is_empty());
+        assert!(!above.intersect(&Interval::at_least(0.025)).is_empty());
+        assert_eq!(above.add(&Interval::point(1.0)), Interval::above(1.025));
+        assert_eq!(
+            Interval::closed(-2.0, 3.0).mul(&Interval::closed(1.0, 2.0)),
+            Interval::closed(-4.0, 6.0)
+        );
+        assert_eq!(
+            Interval::point(1.0).div(&Interval::closed(-1.0, 1.0)),
+            Interval::ALL
+        );
+        assert_eq!(Interval::above(0.025).pick(), Some(0.05));
+        assert_eq!(Interval::below(0.2).pick(), Some(0.0));
+        assert_eq!(Interval::closed(0.0, 70.0).to_string(), "[0, 70]");
+        assert_eq!(Interval::above(1.0).to_string(), "(1, ∞)");
+    }
+
+    #[test]
+    fn test_contradictions_are_unsatisfiable() {
+        assert_eq!(
+            solve("rsi > 70 AND rsi < 30"),
+            Satisfiability::Unsatisfiable
+        );
+        assert_eq!(solve("rsi > 120"), Satisfiability::Unsatisfiable);
+        assert_eq!(solve("volume < 0"), Satisfiability::Unsatisfiable);
+        assert_eq!(
+            solve("loss > stop_loss AND NOT (loss > 2.5% OR support_broken)"),
+            Satisfiability::Unsatisfiable
+        );
+        assert_eq!(
+            solve("price * 2 > 10 AND price < 5"),
+            Satisfiability::Unsatisfiable
+        );
+    }
+
+    #[test]
+    fn test_witnesses_are_concrete_and_typed() {
+        let Satisfiability::Satisfiable(witness) =
+            solve("loss > stop_loss AND NOT (momentum_signal < 0.3 AND holding_time > 300s)")
+        else {
+            panic!("expected a witness");
+        };
+        assert_eq!(witness["loss"], ExprValue::Number(0.05));
+        assert!(!witness.contains_key("stop_loss"));
+        assert!(matches!(witness["holding_time"], ExprValue::Duration(_)));
+
+        let Satisfiability::Satisfiable(witness) =
+            solve("macd_bullish AND volume > avg_volume_20 * 1.5 AND rsi < 70")
+        else {
+            panic!("expected a witness");
+        };
+        assert_eq!(witness["macd_bullish"], ExprValue::Bool(true));
+        let volume = witness["volume"].as_f64().unwrap();
+        let average = witness["avg_volume_20"].as_f64().unwrap();
+        assert!(volume > average * 1.5, "{:?}", witness);
+    }
+}
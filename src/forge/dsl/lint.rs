@@ -0,0 +1,284 @@
+//! DSL LINT - Review Checks Beyond the Parser
+//!
+//! Ostrzeżenia dla plików `.dsl` przed deployem: nieznane wskaźniki,
+//! zduplikowane priorytety reguł, pomylone procenty i ułamki,
+//! reguły, które nigdy nie odpalą.
+
+use super::ast::{Metadata, RiskModelDecl, RuleDecl, StrategyAst};
+use super::properties::{self, PropertyStatus};
+use super::syntax::{find, Node, ValueKind};
+use super::{Diagnostic, Spanned};
+use std::collections::HashMap;
+
+/// `risk_model` keys holding a fraction of capital or price
+const RISK_RATIOS: [&str; 5] = [
+    "max_drawdown",
+    "daily_loss_limit",
+    "position_size",
+    "stop_loss",
+    "take_profit",
+];
+
+/// Every diagnostic for `source`: parse errors first-class, lints on top,
+/// all in source order
+pub fn lint(source: &str) -> Vec<Diagnostic> {
+    let parsed = super::parse(source);
+    let mut diagnostics = parsed.diagnostics.clone();
+
+    if let Some(ast) = &parsed.ast {
+        diagnostics.extend(super::indicators::definitions(ast).1);
+        duplicate_priorities(&ast.entry_logic, "entry", &mut diagnostics);
+        duplicate_priorities(&ast.exit_logic, "exit", &mut diagnostics);
+        ratios(ast, &mut diagnostics);
+        unreachable_rules(ast, &mut diagnostics);
+    }
+
+    diagnostics.sort_by_key(|d| d.span.start);
+    diagnostics.dedup();
+    diagnostics
+}
+
+/// Rules sharing a priority fire in file order, which is easy to break by
+/// reordering the file
+fn duplicate_priorities(rules: &[RuleDecl], side: &str, diagnostics: &mut Vec<Diagnostic>) {
+    let mut seen: HashMap<u32, &RuleDecl> = HashMap::new();
+    for rule in rules.iter().filter(|rule| rule.is_enabled()) {
+        let Some(priority) = &rule.priority else {
+            continue;
+        };
+        match seen.get(&priority.node) {
+            Some(first) => diagnostics.push(Diagnostic::warning(
+                format!(
+                    "priority {} is already used by the {} rule at {}",
+                    priority.node, side, first.span
+                ),
+                priority.span,
+            )),
+            None => {
+                seen.insert(priority.node, rule);
+            }
+        }
+    }
+}
+
+/// Percentages and fractions: `max_drawdown: 8` is 800%, and a section
+/// mixing `8%` with `0.025` is one typo away from a wrong limit
+fn ratios(ast: &StrategyAst, diagnostics: &mut Vec<Diagnostic>) {
+    let Some(risk_section) = ast.section("risk_model") else {
+        return;
+    };
+
+    let ratio_nodes: Vec<&Node> = RISK_RATIOS
+        .iter()
+        .filter_map(|key| find(&risk_section.children, key))
+        .filter(|node| node.value.is_some())
+        .collect();
+    for node in &ratio_nodes {
+        let value = node.value.as_ref().expect("filtered above");
+        if let ValueKind::Number(n) = value.kind {
+            if n > 1.0 {
+                diagnostics.push(Diagnostic::warning(
+                    format!(
+                        "`{}: {}` is {}% of capital; write `{}%` or `{}`",
+                        node.key.node,
+                        value.raw,
+                        n * 100.0,
+                        value.raw,
+                        n / 100.0
+                    ),
+                    value.span,
+                ));
+            }
+        }
+    }
+
+    // Mixed notation: flag the minority, suggesting the majority's spelling
+    let percents = ratio_nodes
+        .iter()
+        .filter(|node| {
+            matches!(
+                node.value.as_ref().map(|v| &v.kind),
+                Some(ValueKind::Percent(_))
+            )
+        })
+        .count();
+    let fractions = ratio_nodes
+        .iter()
+        .filter(|node| matches!(node.value.as_ref().map(|v| &v.kind), Some(ValueKind::Number(n)) if *n <= 1.0))
+        .count();
+    if percents > 0 && fractions > 0 {
+        let prefer_percent = percents >= fractions;
+        for node in &ratio_nodes {
+            let value = node.value.as_ref().expect("filtered above");
+            let suggestion = match value.kind {
+                ValueKind::Number(n) if prefer_percent && n <= 1.0 => {
+                    format!("{}%", round(n * 100.0))
+                }
+                ValueKind::Percent(p) if !prefer_percent => round(p / 100.0).to_string(),
+                _ => continue,
+            };
+            diagnostics.push(Diagnostic::warning(
+                format!(
+                    "risk_model mixes percentages and fractions; write `{}: {}`",
+                    node.key.node, suggestion
+                ),
+                value.span,
+            ));
+        }
+    }
+
+    if let (Some(metadata), Some(risk)) = (&ast.metadata, &ast.risk_model) {
+        drawdown_agrees(metadata, risk, diagnostics);
+    }
+}
+
+/// `metadata.max_drawdown: 0.8` next to `risk_model.max_drawdown: 8%`
+fn drawdown_agrees(metadata: &Metadata, risk: &RiskModelDecl, diagnostics: &mut Vec<Diagnostic>) {
+    let (Some(declared), Some(limit)) = (&metadata.max_drawdown, &risk.max_drawdown) else {
+        return;
+    };
+    if (declared.node - limit.node).abs() > 1e-9 {
+        diagnostics.push(Diagnostic::warning(
+            format!(
+                "metadata max_drawdown is {}% but risk_model max_drawdown is {}%; \
+                 the risk model wins",
+                percent(declared),
+                percent(limit)
+            ),
+            declared.span,
+        ));
+    }
+}
+
+fn unreachable_rules(ast: &StrategyAst, diagnostics: &mut Vec<Diagnostic>) {
+    match properties::rule_reachability(ast) {
+        Ok(checks) => {
+            for check in checks
+                .iter()
+                .filter(|c| c.status == PropertyStatus::Violated)
+            {
+                let mut message = format!("{} can never fire", check.subject);
+                if let Some(ranges) = check.steps.get(1..).filter(|r| !r.is_empty()) {
+                    message.push_str(&format!(" ({})", ranges.join(", ")));
+                }
+                diagnostics.push(Diagnostic::warning(message, check.span));
+            }
+        }
+        Err(errors) => diagnostics.extend(errors),
+    }
+}
+
+fn percent(value: &Spanned<f64>) -> f64 {
+    round(value.node * 100.0)
+}
+
+/// Drop floating-point noise: 0.07 × 100 = 7
+fn round(x: f64) -> f64 {
+    (x * 1e9).round() / 1e9
+}
+
+/// Diagnostic with the offending source line underlined, rustc style
+pub fn annotate(path: &str, source: &str, diagnostic: &Diagnostic) -> String {
+    let span = diagnostic.span;
+    let level = if diagnostic.is_error() {
+        "error"
+    } else {
+        "warning"
+    };
+    let line_text = source
+        .lines()
+        .nth(span.line.saturating_sub(1))
+        .unwrap_or("");
+    let gutter = " ".repeat(span.line.to_string().len());
+    let column = span.column.saturating_sub(1);
+    let width = span
+        .end
+        .saturating_sub(span.start)
+        .min(line_text.len().saturating_sub(column))
+        .max(1);
+
+    format!(
+        "{}: {}\n{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {}{}\n",
+        level,
+        diagnostic.message,
+        gutter,
+        path,
+        span.line,
+        span.column,
+        gutter,
+        span.line,
+        line_text,
+        gutter,
+        " ".repeat(column),
+        "^".repeat(width)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOMENTUM_V1: &str = include_str!("../../../strategies/momentum_v1.dsl");
+
+    fn messages(source: &str) -> Vec<String> {
+        lint(source).into_iter().map(|d| d.message).collect()
+    }
+
+    #[test]
+    fn test_checked_in_strategy_lints() {
+        let diagnostics = lint(MOMENTUM_V1);
+        assert!(
+            diagnostics.iter().all(|d| !d.is_error()),
+            "{:?}",
+            diagnostics
+        );
+        // Profit taking and stop loss are both priority 1
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert!(diagnostics[0]
+            .message
+            .starts_with("priority 1 is already used by the exit rule at 55:5"));
+        assert_eq!(diagnostics[0].span.text(MOMENTUM_V1), "1");
+    }
+
+    #[test]
+    fn test_percent_fraction_mixups() {
+        let source = "strategy S:\n  metadata:\n    max_drawdown: 0.8\n  risk_model:\n    max_drawdown: 8%\n    position_size: 12%\n    stop_loss: 0.025\n    take_profit: 5\n";
+        let messages = messages(source);
+        assert_eq!(messages.len(), 3, "{:?}", messages);
+        assert_eq!(
+            messages[0],
+            "metadata max_drawdown is 80% but risk_model max_drawdown is 8%; the risk model wins"
+        );
+        assert_eq!(
+            messages[1],
+            "risk_model mixes percentages and fractions; write `stop_loss: 2.5%`"
+        );
+        assert_eq!(
+            messages[2],
+            "`take_profit: 5` is 500% of capital; write `5%` or `0.05`"
+        );
+    }
+
+    #[test]
+    fn test_unknown_indicators_and_unreachable_rules() {
+        let source = "strategy S:\n  entry_logic:\n    - trigger: \"rsi > 70 AND rsi < 30\"\n      action: market_buy(size=10%)\n  technical_indicators:\n    - name: \"rsi\"\n      type: \"rsi\"\n    - name: \"cloud\"\n      type: \"ichimoku\"\n";
+        let diagnostics = lint(source);
+        assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+        assert!(!diagnostics[0].is_error());
+        assert!(diagnostics[0]
+            .message
+            .starts_with("entry rule at 3:5 can never fire (rsi ∈ [0, 100])"));
+        assert_eq!(diagnostics[0].span.text(source), "rsi > 70 AND rsi < 30");
+        assert!(diagnostics[1].is_error());
+        assert_eq!(
+            diagnostics[1].message,
+            "unsupported indicator type `ichimoku`"
+        );
+
+        let annotated = annotate("s.dsl", source, &diagnostics[1]);
+        assert_eq!(
+            annotated,
+            "error: unsupported indicator type `ichimoku`\n --> s.dsl:9:13\n  |\n9 |       type: \"ichimoku\"\n  |             ^^^^^^^^^^\n"
+        );
+    }
+}
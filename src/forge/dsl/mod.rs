@@ -0,0 +1,343 @@
+//! STRATEGY DSL - Parser & Typed AST
+//!
+//! Jeden parser dla `strategies/*.dsl`, szablonów FORGE i strategii z TensorZero
+//! Lossless syntax tree (round-trip bajt w bajt) + typed AST ze spanami
+//! Wyrażenia triggerów typowane i wykonywane w procesie (`runtime`)
+//! Statyczne dowody własności strategii na przedziałach (`properties`)
+//! Lint i kanoniczny format plików do review (`lint`, `format`)
+//!
+//! Parsing never stops at the first problem: malformed lines become error
+//! lines in the syntax tree, a diagnostic is recorded and the parser carries
+//! on, so callers always get as much of the strategy as could be recovered.
+
+pub mod ast;
+pub mod eval;
+pub mod expr;
+pub mod format;
+pub mod indicators;
+pub mod interval;
+pub mod lint;
+pub mod properties;
+pub mod runtime;
+pub mod syntax;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub use ast::{
+    ActionDecl, AlertDecl, AlertSeverity, IndicatorDecl, Metadata, ModelDecl, RiskModelDecl,
+    RuleDecl, StrategyAst,
+};
+pub use eval::{ExprType, ExprValue, FeatureSchema, FeatureSnapshot};
+pub use expr::{parse_expr, BinaryOp, Expr, ExprKind, UnaryOp};
+pub use interval::{Assignment, FeatureBounds, Interval, Satisfiability, Solver};
+pub use properties::{Property, PropertyCheck, PropertyStatus};
+pub use runtime::{DslStrategy, Evaluation, RaisedAlert, RuleSide, Signal};
+pub use syntax::{CallArg, Document, Item, Line, LineContent, Node, Value, ValueKind};
+
+/// Byte range in the source plus the 1-based line/column it starts at
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Self {
+        Self {
+            start,
+            end,
+            line,
+            column,
+        }
+    }
+
+    /// Source text covered by this span
+    pub fn text<'a>(&self, source: &'a str) -> &'a str {
+        source.get(self.start..self.end).unwrap_or("")
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+/// Value with the span it was parsed from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned<T> {
+    pub node: T,
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(node: T, span: Span) -> Self {
+        Self { node, span }
+    }
+}
+
+/// Diagnostic severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiagnosticSeverity {
+    Warning,
+    Error,
+}
+
+/// Parser or lowering diagnostic
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+    pub span: Span,
+}
+
+impl Diagnostic {
+    pub fn error(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            severity: DiagnosticSeverity::Error,
+            message: message.into(),
+            span,
+        }
+    }
+
+    pub fn warning(message: impl Into<String>, span: Span) -> Self {
+        Self {
+            severity: DiagnosticSeverity::Warning,
+            message: message.into(),
+            span,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == DiagnosticSeverity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let level = match self.severity {
+            DiagnosticSeverity::Warning => "warning",
+            DiagnosticSeverity::Error => "error",
+        };
+        write!(f, "{}: {}: {}", self.span, level, self.message)
+    }
+}
+
+/// Result of parsing one strategy file
+#[derive(Debug, Clone)]
+pub struct ParsedStrategy {
+    /// Lossless syntax tree
+    pub document: Document,
+
+    /// Typed AST, `None` when the file has no `strategy <Name>:` declaration
+    pub ast: Option<StrategyAst>,
+
+    /// Syntax and lowering diagnostics, in source order
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ParsedStrategy {
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics.iter().any(Diagnostic::is_error)
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics.iter().filter(|d| d.is_error())
+    }
+
+    /// Reprint the source exactly as it was parsed
+    pub fn to_source(&self) -> String {
+        self.document.to_source()
+    }
+
+    /// Typed AST, or an error listing every error diagnostic
+    pub fn into_ast(self) -> Result<StrategyAst> {
+        if self.has_errors() {
+            let errors: Vec<Diagnostic> = self.errors().cloned().collect();
+            return Err(anyhow!(
+                "Strategy DSL has {} error(s):\n{}",
+                errors.len(),
+                render_diagnostics(&errors)
+            ));
+        }
+        self.ast
+            .ok_or_else(|| anyhow!("Strategy DSL has no `strategy <Name>:` declaration"))
+    }
+}
+
+/// One diagnostic per line, as `line:col: level: message`
+pub fn render_diagnostics(diagnostics: &[Diagnostic]) -> String {
+    diagnostics
+        .iter()
+        .map(|d| d.to_string())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Parse strategy DSL source into a syntax tree, typed AST and diagnostics
+pub fn parse(source: &str) -> ParsedStrategy {
+    let (document, mut diagnostics) = syntax::parse_document(source);
+    let ast = document.strategy.as_ref().map(|block| {
+        let (ast, lowering) = ast::lower(block);
+        diagnostics.extend(lowering);
+        ast
+    });
+    diagnostics.sort_by_key(|d| d.span.start);
+
+    ParsedStrategy {
+        document,
+        ast,
+        diagnostics,
+    }
+}
+
+/// Parse strategy DSL, failing on any error diagnostic
+pub fn parse_strategy(source: &str) -> Result<StrategyAst> {
+    parse(source).into_ast()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOMENTUM_V1: &str = include_str!("../../../strategies/momentum_v1.dsl");
+    const SENTIMENT_AGENT_V1: &str = include_str!("../../../strategies/sentiment_agent_v1.dsl");
+
+    #[test]
+    fn test_checked_in_strategies_round_trip() {
+        for source in [MOMENTUM_V1, SENTIMENT_AGENT_V1] {
+            let parsed = parse(source);
+            assert!(!parsed.has_errors(), "{:?}", parsed.diagnostics);
+            assert_eq!(parsed.to_source(), source);
+        }
+    }
+
+    #[test]
+    fn test_templates_parse_cleanly() {
+        let templates = crate::forge::dsl_generator::StrategyTemplates::default();
+        for source in [
+            &templates.momentum_template,
+            &templates.mean_reversion_template,
+            &templates.arbitrage_template,
+            &templates.market_making_template,
+            &templates.breakout_template,
+        ] {
+            let parsed = parse(source);
+            assert!(!parsed.has_errors(), "{:?}", parsed.diagnostics);
+            assert_eq!(&parsed.to_source(), source);
+        }
+    }
+
+    #[test]
+    fn test_sentiment_agent_ast() {
+        let ast = parse_strategy(SENTIMENT_AGENT_V1).unwrap();
+
+        assert_eq!(ast.name.node, "SentimentAgentV1");
+        let metadata = ast.metadata.as_ref().unwrap();
+        assert_eq!(
+            metadata.name.as_ref().unwrap().node,
+            "Sentiment Analysis Agent V1"
+        );
+        assert_eq!(metadata.risk_level.as_ref().unwrap().node, 2);
+
+        let risk = ast.risk_model.as_ref().unwrap();
+        assert!((risk.position_size.as_ref().unwrap().node - 0.08).abs() < 1e-12);
+        assert!((risk.stop_loss.as_ref().unwrap().node - 0.018).abs() < 1e-12);
+        assert_eq!(risk.max_positions.as_ref().unwrap().node, 5);
+
+        assert_eq!(ast.entry_logic.len(), 4);
+        assert_eq!(ast.exit_logic.len(), 5);
+        let rule = &ast.entry_logic[1];
+        assert!(rule
+            .trigger
+            .as_ref()
+            .unwrap()
+            .node
+            .starts_with("news_sentiment > 0.75"));
+        let action = rule.action.as_ref().unwrap();
+        assert_eq!(action.name.node, "limit_buy");
+        assert_eq!(action.args.len(), 2);
+        assert_eq!(action.args[0].name.as_ref().unwrap().node, "size");
+        assert!(
+            matches!(action.args[0].value.kind, ValueKind::Expr(ref e) if e == "position_size*0.7")
+        );
+        assert!(
+            matches!(action.args[1].value.kind, ValueKind::Percent(p) if (p - 0.05).abs() < 1e-12)
+        );
+
+        assert_eq!(ast.ai_models.len(), 5);
+        let net = &ast.ai_models[0];
+        assert_eq!(net.name.as_ref().unwrap().node, "SentimentNet");
+        assert_eq!(net.version.as_ref().unwrap().node, "3.2");
+        assert_eq!(net.input_features.len(), 4);
+        assert!(net
+            .parameters
+            .iter()
+            .any(|p| p.key.node == "source_weights" && p.children.len() == 3));
+
+        assert_eq!(ast.technical_indicators.len(), 6);
+        assert_eq!(
+            ast.technical_indicators[3].kind.as_ref().unwrap().node,
+            "moving_average_convergence_divergence"
+        );
+
+        assert_eq!(ast.alerts.len(), 7);
+        assert_eq!(
+            ast.alerts[4].severity.as_ref().unwrap().node,
+            AlertSeverity::Critical
+        );
+
+        let data_sources = ast.section("data_sources").unwrap();
+        let news = data_sources.get("news_feeds").unwrap();
+        assert_eq!(news.items.len(), 4);
+    }
+
+    #[test]
+    fn test_spans_point_into_source() {
+        let ast = parse_strategy(MOMENTUM_V1).unwrap();
+        let rule = &ast.exit_logic[0];
+        let trigger = rule.trigger.as_ref().unwrap();
+        assert_eq!(trigger.span.text(MOMENTUM_V1), trigger.node);
+        assert_eq!(
+            MOMENTUM_V1[..trigger.span.start].matches('\n').count() + 1,
+            trigger.span.line
+        );
+        assert_eq!(ast.name.span.text(MOMENTUM_V1), "MomentumStrategyV1");
+    }
+
+    #[test]
+    fn test_error_recovery_keeps_parsing() {
+        let source = "strategy Broken:\n  risk_model:\n    position_size 10%\n    stop_loss: \"2%\n    take_profit: 4%\n  entry_logic:\n    - trigger: \"rsi < 30\"\n      priority: 1\n";
+        let parsed = parse(source);
+
+        assert_eq!(parsed.to_source(), source);
+        let errors: Vec<_> = parsed.errors().collect();
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert_eq!(errors[0].span.line, 3);
+        assert_eq!(errors[1].span.line, 4);
+        assert!(errors[2].message.contains("action"));
+
+        // Everything after the bad lines is still in the AST
+        let ast = parsed.ast.unwrap();
+        let risk = ast.risk_model.unwrap();
+        assert!((risk.take_profit.unwrap().node - 0.04).abs() < 1e-12);
+        assert_eq!(ast.entry_logic.len(), 1);
+        assert_eq!(ast.entry_logic[0].priority.as_ref().unwrap().node, 1);
+    }
+
+    #[test]
+    fn test_into_ast_reports_all_errors() {
+        let err = parse_strategy("strategy X:\n  risk_model:\n    stop_loss: [1, 2\n    ???\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("2 error(s)"), "{}", err);
+        assert!(err.contains("3:"), "{}", err);
+        assert!(err.contains("4:"), "{}", err);
+
+        assert!(parse_strategy("  risk_model:\n    stop_loss: 2%\n").is_err());
+    }
+}
@@ -0,0 +1,827 @@
+//! DSL PROPERTIES - Static Safety Properties of a Strategy
+//!
+//! Dowodzone na przedziałach (`interval`) bez uruchamiania strategii:
+//! ścieżka wyjścia z każdej pozycji, limit ekspozycji, osiągalność
+//! wejść i kolejność alertów względem risk_model.
+//!
+//! A violated property always comes with the concrete feature assignment
+//! that breaks it, except for an unreachable entry rule, where there is no
+//! assignment to show and the searched feature ranges are reported instead.
+
+use super::ast::{AlertSeverity, RuleDecl, StrategyAst};
+use super::eval::{ExprType, ExprValue, FeatureSchema};
+use super::expr::{parse_expr, BinaryOp, Expr, ExprKind, UnaryOp};
+use super::interval::{Assignment, FeatureBounds, Interval, Satisfiability, Solver};
+use super::runtime::{argument_expr, risk_constants};
+use super::syntax::{find, ValueKind};
+use super::{Diagnostic, Span};
+use crate::modules::indicators::IndicatorSpec;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Risk-model limits watched by alerts: feature and the constant bounding it
+const RISK_LIMITS: [(&str, &str); 3] = [
+    ("daily_loss", "daily_loss_limit"),
+    ("drawdown", "max_drawdown"),
+    ("loss", "stop_loss"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Property {
+    /// A losing or stale position always has an exit that fires
+    ExitPath,
+    /// Entry size × `max_positions` stays within the exposure limit
+    ExposureBound,
+    /// Some market state fires the entry rule
+    EntryReachable,
+    /// Some market state fires the exit rule
+    ExitReachable,
+    /// Alerts escalate with the loss and fire once a limit is breached
+    AlertOrdering,
+}
+
+impl fmt::Display for Property {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Property::ExitPath => write!(f, "Every position has an exit path"),
+            Property::ExposureBound => write!(f, "Exposure is bounded"),
+            Property::EntryReachable => write!(f, "Entry trigger is satisfiable"),
+            Property::ExitReachable => write!(f, "Exit trigger is satisfiable"),
+            Property::AlertOrdering => write!(f, "Alerts are ordered by the risk model"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyStatus {
+    Proven,
+    Violated,
+    /// Neither proven nor refuted within the abstraction
+    Unknown,
+}
+
+/// Verdict on one property of one part of the strategy
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyCheck {
+    pub property: Property,
+    /// What was checked, e.g. "entry rule at 32:17"
+    pub subject: String,
+    pub span: Span,
+    pub status: PropertyStatus,
+    /// Proof steps, or how the property breaks
+    pub steps: Vec<String>,
+    /// Features that break the property
+    pub counterexample: Option<Assignment>,
+}
+
+/// Check every property of `ast`; `max_exposure` is the largest fraction
+/// of capital the strategy may have in open positions at once.
+///
+/// Fails only when a trigger, condition or `size` argument does not parse.
+pub fn check(ast: &StrategyAst, max_exposure: f64) -> Result<Vec<PropertyCheck>, Vec<Diagnostic>> {
+    let model = Model::new(ast)?;
+    let solver = Solver::new(&model.bounds, &model.constants, &model.schema);
+
+    let mut checks = vec![
+        exit_path(ast, &model, &solver),
+        exposure(ast, &model, &solver, max_exposure),
+    ];
+    checks.extend(reachable(
+        &model.entries,
+        Property::EntryReachable,
+        &model,
+        &solver,
+    ));
+    checks.extend(alert_ordering(&model, &solver));
+    Ok(checks)
+}
+
+/// Reachability of every enabled entry and exit rule
+pub fn rule_reachability(ast: &StrategyAst) -> Result<Vec<PropertyCheck>, Vec<Diagnostic>> {
+    let model = Model::new(ast)?;
+    let solver = Solver::new(&model.bounds, &model.constants, &model.schema);
+
+    let mut checks = reachable(&model.entries, Property::EntryReachable, &model, &solver);
+    checks.extend(reachable(
+        &model.exits,
+        Property::ExitReachable,
+        &model,
+        &solver,
+    ));
+    Ok(checks)
+}
+
+/// Enabled rule with its trigger parsed
+struct Rule {
+    span: Span,
+    trigger: Expr,
+    size: Option<Expr>,
+}
+
+struct Alert {
+    span: Span,
+    condition: Expr,
+    severity: AlertSeverity,
+}
+
+/// Everything the properties reason about, parsed once
+struct Model {
+    constants: HashMap<String, ExprValue>,
+    bounds: FeatureBounds,
+    schema: FeatureSchema,
+    entries: Vec<Rule>,
+    exits: Vec<Rule>,
+    alerts: Vec<Alert>,
+    max_trade_duration: Option<f64>,
+}
+
+impl Model {
+    fn new(ast: &StrategyAst) -> Result<Self, Vec<Diagnostic>> {
+        let mut diagnostics = Vec::new();
+        let entries = parse_rules(&ast.entry_logic, &mut diagnostics);
+        let exits = parse_rules(&ast.exit_logic, &mut diagnostics);
+        let alerts = ast
+            .alerts
+            .iter()
+            .filter_map(|decl| {
+                let condition = decl.condition.as_ref()?;
+                Some(Alert {
+                    span: decl.span,
+                    condition: parse(&condition.node, condition.span, &mut diagnostics)?,
+                    severity: decl
+                        .severity
+                        .as_ref()
+                        .map_or(AlertSeverity::Warning, |s| s.node),
+                })
+            })
+            .collect();
+        if !diagnostics.is_empty() {
+            return Err(diagnostics);
+        }
+
+        let mut bounds = FeatureBounds::standard();
+        let mut schema = FeatureSchema::standard();
+        let (indicators, _) = super::indicators::definitions(ast);
+        for definition in &indicators {
+            let range = match definition.spec {
+                IndicatorSpec::Rsi { .. } => Some(Interval::closed(0.0, 100.0)),
+                IndicatorSpec::Macd { .. } => None,
+                _ => Some(Interval::non_negative()),
+            };
+            for output in definition.spec.outputs(&definition.name) {
+                schema.insert(&output, ExprType::Number);
+                if let Some(range) = range {
+                    bounds.insert(&output, range);
+                }
+            }
+        }
+        for model in &ast.ai_models {
+            if let Some(output) = &model.output {
+                schema.insert(&output.node, ExprType::Number);
+            }
+        }
+
+        let max_trade_duration = ast
+            .section("performance_targets")
+            .and_then(|section| find(&section.children, "max_trade_duration"))
+            .and_then(|node| node.value.as_ref())
+            .and_then(|value| match value.kind {
+                ValueKind::Duration(d) => Some(d.as_secs_f64()),
+                ValueKind::Number(seconds) => Some(seconds),
+                _ => None,
+            });
+
+        Ok(Self {
+            constants: risk_constants(ast),
+            bounds,
+            schema,
+            entries,
+            exits,
+            alerts,
+            max_trade_duration,
+        })
+    }
+
+    fn constant(&self, name: &str) -> Option<f64> {
+        self.constants.get(name).and_then(ExprValue::as_f64)
+    }
+}
+
+fn parse(text: &str, span: Span, diagnostics: &mut Vec<Diagnostic>) -> Option<Expr> {
+    parse_expr(text, span)
+        .map_err(|diagnostic| diagnostics.push(diagnostic))
+        .ok()
+}
+
+/// Enabled rules; disabled ones never open or close a position
+fn parse_rules(decls: &[RuleDecl], diagnostics: &mut Vec<Diagnostic>) -> Vec<Rule> {
+    decls
+        .iter()
+        .filter(|decl| decl.is_enabled())
+        .filter_map(|decl| {
+            let trigger = decl.trigger.as_ref()?;
+            let trigger = parse(&trigger.node, trigger.span, diagnostics)?;
+            let size = match decl.action.as_ref().and_then(|action| action.arg("size")) {
+                Some(value) => Some(
+                    argument_expr(value)
+                        .map_err(|diagnostic| diagnostics.extend(diagnostic))
+                        .ok()?,
+                ),
+                None => None,
+            };
+            Some(Rule {
+                span: decl.span,
+                trigger,
+                size,
+            })
+        })
+        .collect()
+}
+
+fn exit_path(ast: &StrategyAst, model: &Model, solver: &Solver) -> PropertyCheck {
+    let stop_loss = model.constant("stop_loss").filter(|s| *s > 0.0);
+    let span = ast.risk_model.as_ref().map_or(ast.span, |risk| risk.span);
+    let mut check = PropertyCheck {
+        property: Property::ExitPath,
+        subject: "exit_logic".to_string(),
+        span,
+        status: PropertyStatus::Unknown,
+        steps: Vec::new(),
+        counterexample: None,
+    };
+
+    let Some(silent) = any(model.exits.iter().map(|rule| rule.trigger.clone())).map(not) else {
+        // Without exit rules the risk model closes positions on its own
+        match (stop_loss, model.max_trade_duration) {
+            (Some(stop_loss), _) => {
+                check.status = PropertyStatus::Proven;
+                check.steps = vec![
+                    "No exit_logic rules; positions close through the risk model".to_string(),
+                    format!(
+                        "stop_loss = {} > 0 bounds the loss of every position ∎",
+                        stop_loss
+                    ),
+                ];
+            }
+            (None, Some(duration)) => {
+                check.status = PropertyStatus::Proven;
+                check.steps = vec![
+                    "No exit_logic rules; positions close through the risk model".to_string(),
+                    format!("max_trade_duration = {}s bounds every position ∎", duration),
+                ];
+            }
+            (None, None) => {
+                check.status = PropertyStatus::Violated;
+                check.steps = vec![
+                    "No exit_logic rules, no stop_loss and no max_trade_duration".to_string(),
+                    "A position can lose everything and stay open".to_string(),
+                ];
+                check.counterexample = Some(Assignment::from([(
+                    "loss".to_string(),
+                    ExprValue::Number(1.0),
+                )]));
+            }
+        }
+        return check;
+    };
+
+    // Open positions must be closed by the rules once a declared limit is hit
+    let mut scenarios = Vec::new();
+    if stop_loss.is_some() {
+        scenarios.push((
+            "loss > stop_loss",
+            binary(BinaryOp::Gt, ident("loss", span), ident("stop_loss", span)),
+        ));
+    }
+    if let Some(duration) = model.max_trade_duration {
+        scenarios.push((
+            "holding_time > max_trade_duration",
+            binary(
+                BinaryOp::Gt,
+                ident("holding_time", span),
+                Expr {
+                    kind: ExprKind::Duration(duration),
+                    span,
+                },
+            ),
+        ));
+    }
+    if scenarios.is_empty() {
+        scenarios.push((
+            "any open position",
+            Expr {
+                kind: ExprKind::Bool(true),
+                span,
+            },
+        ));
+    }
+
+    let rules = model.exits.len();
+    for (assumption, limit) in scenarios {
+        match solver.satisfy(&binary(BinaryOp::And, limit, silent.clone())) {
+            Satisfiability::Unsatisfiable => {
+                check.status = PropertyStatus::Proven;
+                check.steps = vec![
+                    format!("Assume {}", assumption),
+                    format!(
+                        "No assignment within the feature bounds keeps all {} exit rules silent",
+                        rules
+                    ),
+                    "Therefore some exit rule fires ∎".to_string(),
+                ];
+                check.counterexample = None;
+                return check;
+            }
+            Satisfiability::Satisfiable(witness) if check.counterexample.is_none() => {
+                check.status = PropertyStatus::Violated;
+                check.steps = vec![
+                    format!("Assume {}", assumption),
+                    format!(
+                        "None of the {} exit rules fires for {}",
+                        rules,
+                        describe(&witness)
+                    ),
+                ];
+                check.counterexample = Some(witness);
+            }
+            _ => {}
+        }
+    }
+    if check.status == PropertyStatus::Unknown {
+        check.steps = vec!["Could not decide whether an exit rule always fires".to_string()];
+    }
+    check
+}
+
+fn exposure(ast: &StrategyAst, model: &Model, solver: &Solver, max_exposure: f64) -> PropertyCheck {
+    let risk = ast.risk_model.as_ref();
+    let span = risk.map_or(ast.span, |risk| risk.span);
+    let declared_positions = risk
+        .and_then(|risk| risk.max_positions.as_ref())
+        .map(|p| p.node);
+    let positions = declared_positions.unwrap_or(1);
+    let mut check = PropertyCheck {
+        property: Property::ExposureBound,
+        subject: "risk_model".to_string(),
+        span,
+        status: PropertyStatus::Proven,
+        steps: Vec::new(),
+        counterexample: None,
+    };
+    if declared_positions.is_none() {
+        check
+            .steps
+            .push("max_positions not declared; assuming a single open position".to_string());
+    }
+
+    // Each entry opens `size`, or `position_size` when it names none
+    let position_size = model
+        .constants
+        .contains_key("position_size")
+        .then(|| ident("position_size", span));
+    let mut sizes: Vec<(String, Option<&Expr>, Expr)> = model
+        .entries
+        .iter()
+        .filter_map(|rule| {
+            let size = rule.size.clone().or_else(|| position_size.clone())?;
+            Some((
+                format!("entry rule at {}", rule.span),
+                Some(&rule.trigger),
+                size,
+            ))
+        })
+        .collect();
+    if model.entries.is_empty() {
+        sizes.extend(position_size.map(|size| ("position_size".to_string(), None, size)));
+    }
+
+    // Largest size any entry can open; an unbounded size that the solver
+    // still kept within the limit is bounded by the limit itself
+    let per_position = max_exposure / f64::from(positions.max(1));
+    let mut largest: f64 = 0.0;
+    if sizes.is_empty() {
+        check
+            .steps
+            .push("No entry rule or position_size opens a position ∎".to_string());
+        return check;
+    }
+    for (subject, trigger, size) in sizes {
+        let open = binary(
+            BinaryOp::Mul,
+            size.clone(),
+            number(f64::from(positions), size.span),
+        );
+        let breach = binary(BinaryOp::Gt, open, number(max_exposure, size.span));
+        let query = match trigger {
+            Some(trigger) => binary(BinaryOp::And, trigger.clone(), breach),
+            None => breach,
+        };
+        match solver.satisfy(&query) {
+            Satisfiability::Unsatisfiable => {
+                largest = largest.max(solver.range(&size).hi.min(per_position));
+            }
+            Satisfiability::Satisfiable(witness) => {
+                let lookup = |name: &str| {
+                    model
+                        .constants
+                        .get(name)
+                        .or_else(|| witness.get(name))
+                        .copied()
+                };
+                let value = super::eval::evaluate(&size, &lookup)
+                    .ok()
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(f64::NAN);
+                check.status = PropertyStatus::Violated;
+                check.subject = subject;
+                check.span = size.span;
+                check.steps.push(format!(
+                    "size = {} with {} open positions gives exposure {} > {}",
+                    value,
+                    positions,
+                    round(value * f64::from(positions)),
+                    max_exposure
+                ));
+                if !witness.is_empty() {
+                    check
+                        .steps
+                        .push(format!("Reached with {}", describe(&witness)));
+                }
+                check.counterexample = Some(witness);
+                return check;
+            }
+            Satisfiability::Unknown => {
+                check.status = PropertyStatus::Unknown;
+                check
+                    .steps
+                    .push(format!("Could not bound the size opened by {}", subject));
+            }
+        }
+    }
+
+    if check.status == PropertyStatus::Proven {
+        check.steps.push(format!(
+            "Largest entry size ≤ {}, max_positions = {}",
+            largest, positions
+        ));
+        check.steps.push(format!(
+            "Exposure ≤ {} × {} = {} ≤ {} ∎",
+            largest,
+            positions,
+            round(largest * f64::from(positions)),
+            max_exposure
+        ));
+    }
+    check
+}
+
+fn reachable(
+    rules: &[Rule],
+    property: Property,
+    model: &Model,
+    solver: &Solver,
+) -> Vec<PropertyCheck> {
+    let side = match property {
+        Property::ExitReachable => "exit",
+        _ => "entry",
+    };
+    rules
+        .iter()
+        .map(|rule| {
+            let mut check = PropertyCheck {
+                property,
+                subject: format!("{} rule at {}", side, rule.span),
+                span: rule.trigger.span,
+                status: PropertyStatus::Unknown,
+                steps: Vec::new(),
+                counterexample: None,
+            };
+            match solver.satisfy(&rule.trigger) {
+                Satisfiability::Satisfiable(witness) => {
+                    check.status = PropertyStatus::Proven;
+                    check.steps = vec![
+                        format!("Witness: {}", describe(&witness)),
+                        "The trigger evaluates to true on the witness ∎".to_string(),
+                    ];
+                }
+                Satisfiability::Unsatisfiable => {
+                    check.status = PropertyStatus::Violated;
+                    check.steps.push(
+                        "No assignment within the feature bounds makes the trigger true"
+                            .to_string(),
+                    );
+                    for name in rule.trigger.identifiers() {
+                        if !model.constants.contains_key(name) {
+                            check
+                                .steps
+                                .push(format!("{} ∈ {}", name, model.bounds.get(name)));
+                        }
+                    }
+                }
+                Satisfiability::Unknown => {
+                    check
+                        .steps
+                        .push("Could not find a market state firing the trigger".to_string());
+                }
+            }
+            check
+        })
+        .collect()
+}
+
+fn alert_ordering(model: &Model, solver: &Solver) -> Vec<PropertyCheck> {
+    let mut checks = Vec::new();
+    for (feature, limit) in RISK_LIMITS {
+        let Some(limit_value) = model.constant(limit) else {
+            continue;
+        };
+        let watching: Vec<&Alert> = model
+            .alerts
+            .iter()
+            .filter(|alert| alert.condition.identifiers().contains(&feature))
+            .collect();
+        let Some(first) = watching.first() else {
+            continue;
+        };
+        let mut check = PropertyCheck {
+            property: Property::AlertOrdering,
+            subject: format!("alerts on {}", feature),
+            span: first.span,
+            status: PropertyStatus::Proven,
+            steps: Vec::new(),
+            counterexample: None,
+        };
+
+        // Breaching the limit raises at least one alert
+        let span = first.span;
+        let breach = binary(BinaryOp::Gt, ident(feature, span), ident(limit, span));
+        let silent = any(watching.iter().map(|alert| alert.condition.clone())).map(not);
+        let query = binary(BinaryOp::And, breach, silent.expect("at least one alert"));
+        match solver.satisfy(&query) {
+            Satisfiability::Unsatisfiable => check.steps.push(format!(
+                "{} > {} = {} raises at least one alert",
+                feature, limit, limit_value
+            )),
+            Satisfiability::Satisfiable(witness) => {
+                check.status = PropertyStatus::Violated;
+                check.steps.push(format!(
+                    "{} exceeds {} = {} but no alert fires for {}",
+                    feature,
+                    limit,
+                    limit_value,
+                    describe(&witness)
+                ));
+                check.counterexample = Some(witness);
+                checks.push(check);
+                continue;
+            }
+            Satisfiability::Unknown => {
+                check.status = PropertyStatus::Unknown;
+                check.steps.push(format!(
+                    "Could not decide whether breaching {} raises an alert",
+                    limit
+                ));
+            }
+        }
+
+        // A more severe alert never fires while a milder one on the same
+        // feature stays silent
+        let thresholds: Vec<&&Alert> = watching
+            .iter()
+            .filter(|alert| {
+                alert
+                    .condition
+                    .identifiers()
+                    .iter()
+                    .all(|name| *name == feature || model.constants.contains_key(*name))
+            })
+            .collect();
+        for severe in &thresholds {
+            for mild in &thresholds {
+                if rank(severe.severity) <= rank(mild.severity) {
+                    continue;
+                }
+                let query = binary(
+                    BinaryOp::And,
+                    severe.condition.clone(),
+                    not(mild.condition.clone()),
+                );
+                match solver.satisfy(&query) {
+                    Satisfiability::Unsatisfiable => {}
+                    Satisfiability::Satisfiable(witness) => {
+                        check.status = PropertyStatus::Violated;
+                        check.span = severe.span;
+                        check.steps.push(format!(
+                            "{:?} alert at {} fires before the {:?} alert at {}, e.g. for {}",
+                            severe.severity,
+                            severe.span,
+                            mild.severity,
+                            mild.span,
+                            describe(&witness)
+                        ));
+                        check.counterexample = Some(witness);
+                        break;
+                    }
+                    Satisfiability::Unknown => {
+                        check.status = PropertyStatus::Unknown;
+                    }
+                }
+            }
+            if check.counterexample.is_some() {
+                break;
+            }
+        }
+        if check.status == PropertyStatus::Proven {
+            check
+                .steps
+                .push("Alert severity rises with the threshold ∎".to_string());
+        }
+        checks.push(check);
+    }
+    checks
+}
+
+/// Drop floating-point noise from reported products: 0.1 × 3 = 0.3
+fn round(x: f64) -> f64 {
+    (x * 1e9).round() / 1e9
+}
+
+fn rank(severity: AlertSeverity) -> u8 {
+    match severity {
+        AlertSeverity::Info => 0,
+        AlertSeverity::Warning => 1,
+        AlertSeverity::Critical => 2,
+    }
+}
+
+/// `a = 1, b = true`
+pub fn describe(assignment: &Assignment) -> String {
+    if assignment.is_empty() {
+        return "any market state".to_string();
+    }
+    assignment
+        .iter()
+        .map(|(name, value)| format!("{} = {}", name, value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn ident(name: &str, span: Span) -> Expr {
+    Expr {
+        kind: ExprKind::Ident(name.to_string()),
+        span,
+    }
+}
+
+fn number(value: f64, span: Span) -> Expr {
+    Expr {
+        kind: ExprKind::Number(value),
+        span,
+    }
+}
+
+fn binary(op: BinaryOp, lhs: Expr, rhs: Expr) -> Expr {
+    let span = lhs.span;
+    Expr {
+        kind: ExprKind::Binary {
+            op,
+            lhs: Box::new(lhs),
+            rhs: Box::new(rhs),
+        },
+        span,
+    }
+}
+
+fn not(operand: Expr) -> Expr {
+    let span = operand.span;
+    Expr {
+        kind: ExprKind::Unary {
+            op: UnaryOp::Not,
+            operand: Box::new(operand),
+        },
+        span,
+    }
+}
+
+/// `a OR b OR ...`, `None` when there is nothing to join
+fn any(exprs: impl Iterator<Item = Expr>) -> Option<Expr> {
+    exprs.reduce(|lhs, rhs| binary(BinaryOp::Or, lhs, rhs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::dsl::parse_strategy;
+
+    const MOMENTUM_V1: &str = include_str!("../../../strategies/momentum_v1.dsl");
+    const SENTIMENT_AGENT_V1: &str = include_str!("../../../strategies/sentiment_agent_v1.dsl");
+
+    fn checks(source: &str) -> Vec<PropertyCheck> {
+        check(&parse_strategy(source).unwrap(), 0.5).unwrap()
+    }
+
+    fn find(checks: &[PropertyCheck], property: Property) -> &PropertyCheck {
+        checks.iter().find(|c| c.property == property).unwrap()
+    }
+
+    #[test]
+    fn test_checked_in_strategies_are_proven() {
+        for source in [MOMENTUM_V1, SENTIMENT_AGENT_V1] {
+            for check in checks(source) {
+                assert_eq!(check.status, PropertyStatus::Proven, "{:?}", check);
+            }
+        }
+        let momentum = checks(MOMENTUM_V1);
+        assert_eq!(
+            momentum
+                .iter()
+                .filter(|c| c.property == Property::EntryReachable)
+                .count(),
+            3
+        );
+        let exposure = find(&momentum, Property::ExposureBound);
+        assert!(
+            exposure.steps.last().unwrap().contains("0.36"),
+            "{:?}",
+            exposure
+        );
+    }
+
+    #[test]
+    fn test_violations_carry_concrete_assignments() {
+        let source = r#"
+strategy Leaky:
+  risk_model:
+    position_size: 20%
+    stop_loss: 3%
+    daily_loss_limit: 2%
+    max_positions: 4
+  entry_logic:
+    - trigger: "rsi < 30 AND rsi > 70"
+      action: market_buy(size=position_size)
+    - trigger: "momentum_signal > 0.5"
+      action: market_buy(size=position_size * volatility)
+  exit_logic:
+    - trigger: "profit > 5% OR loss > 10%"
+      action: market_sell(size=100%)
+  technical_indicators:
+    - name: "rsi"
+      type: "rsi"
+  alerts:
+    - condition: "daily_loss > 1%"
+      action: "halt_trading"
+      severity: "critical"
+    - condition: "daily_loss > 3%"
+      action: "reduce_exposure"
+      severity: "warning"
+"#;
+        let checks = checks(source);
+
+        let exit = find(&checks, Property::ExitPath);
+        assert_eq!(exit.status, PropertyStatus::Violated);
+        let witness = exit.counterexample.as_ref().unwrap();
+        let loss = witness["loss"].as_f64().unwrap();
+        assert!(loss > 0.03 && loss <= 0.1, "{:?}", witness);
+        assert!(witness["profit"].as_f64().unwrap() <= 0.05);
+
+        let exposure = find(&checks, Property::ExposureBound);
+        assert_eq!(exposure.status, PropertyStatus::Violated);
+        let witness = exposure.counterexample.as_ref().unwrap();
+        assert!(
+            0.2 * witness["volatility"].as_f64().unwrap() * 4.0 > 0.5,
+            "{:?}",
+            witness
+        );
+        assert!(witness["momentum_signal"].as_f64().unwrap() > 0.5);
+
+        let unreachable = checks
+            .iter()
+            .find(|c| c.property == Property::EntryReachable)
+            .unwrap();
+        assert_eq!(unreachable.status, PropertyStatus::Violated);
+        assert!(unreachable.steps.contains(&"rsi ∈ [0, 100]".to_string()));
+
+        let alerts = find(&checks, Property::AlertOrdering);
+        assert_eq!(alerts.status, PropertyStatus::Violated);
+        let daily_loss = alerts.counterexample.as_ref().unwrap()["daily_loss"]
+            .as_f64()
+            .unwrap();
+        assert!(daily_loss > 0.01 && daily_loss <= 0.03, "{}", daily_loss);
+    }
+
+    #[test]
+    fn test_alerts_must_fire_past_the_limit() {
+        let source = "strategy S:\n  risk_model:\n    daily_loss_limit: 2%\n    stop_loss: 1%\n  alerts:\n    - condition: \"daily_loss > 3%\"\n      action: \"halt_trading\"\n      severity: \"critical\"\n";
+        let checks = checks(source);
+        let alerts = find(&checks, Property::AlertOrdering);
+        assert_eq!(alerts.status, PropertyStatus::Violated);
+        assert_eq!(
+            alerts.counterexample.as_ref().unwrap()["daily_loss"],
+            ExprValue::Number(0.025)
+        );
+        assert_eq!(
+            find(&checks, Property::ExitPath).status,
+            PropertyStatus::Proven
+        );
+    }
+}
@@ -0,0 +1,509 @@
+//! DSL RUNTIME - Interpreted Strategy Execution
+//!
+//! Strategia z DSL wykonywana w procesie: triggery i argumenty akcji
+//! parsowane i typowane raz przy ładowaniu, potem wyliczane na każdym
+//! `FeatureSnapshot` bez round-tripu cargo → .so
+//!
+//! Names a strategy may reference, besides the `FeatureSchema` it is loaded
+//! against: every number, percentage or duration in `risk_model` (as a
+//! constant), the outputs of the declared `technical_indicators` and the AI
+//! model `output`s (as number features the snapshot has to provide).
+
+use super::ast::{AlertSeverity, StrategyAst};
+use super::eval::{self, ExprType, ExprValue, FeatureSchema, FeatureSnapshot};
+use super::expr::{parse_expr, Expr};
+use super::syntax::{CallArg, Value, ValueKind};
+use super::{render_diagnostics, Diagnostic, Span};
+use crate::modules::indicators::{IndicatorDefinition, IndicatorEngine};
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+
+/// Entry or exit side of a rule
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleSide {
+    Entry,
+    Exit,
+}
+
+/// Rule with its trigger and action arguments parsed and type-checked
+#[derive(Debug, Clone)]
+pub struct CompiledRule {
+    pub side: RuleSide,
+    pub span: Span,
+    pub priority: u32,
+    pub enabled: bool,
+    pub confidence_threshold: Option<f64>,
+    pub trigger: Expr,
+    pub action: String,
+
+    /// Named arguments; positional ones are keyed `arg0`, `arg1`, ...
+    pub args: Vec<(String, Expr)>,
+}
+
+/// Alert with its condition parsed and type-checked
+#[derive(Debug, Clone)]
+pub struct CompiledAlert {
+    pub span: Span,
+    pub condition: Expr,
+    pub action: Option<String>,
+    pub severity: AlertSeverity,
+}
+
+/// Rule whose trigger held on a snapshot
+#[derive(Debug, Clone, PartialEq)]
+pub struct Signal {
+    pub side: RuleSide,
+    pub span: Span,
+    pub priority: u32,
+    pub confidence_threshold: Option<f64>,
+    pub action: String,
+    pub args: HashMap<String, ExprValue>,
+}
+
+impl Signal {
+    pub fn arg(&self, name: &str) -> Option<f64> {
+        self.args.get(name).and_then(ExprValue::as_f64)
+    }
+}
+
+/// Alert whose condition held on a snapshot
+#[derive(Debug, Clone, PartialEq)]
+pub struct RaisedAlert {
+    pub span: Span,
+    pub severity: AlertSeverity,
+    pub action: Option<String>,
+}
+
+/// Result of evaluating a strategy on one snapshot
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Evaluation {
+    /// Fired entry rules, highest priority (lowest number) first
+    pub entries: Vec<Signal>,
+    /// Fired exit rules, highest priority (lowest number) first
+    pub exits: Vec<Signal>,
+    pub alerts: Vec<RaisedAlert>,
+}
+
+/// Strategy ready to run in-process
+#[derive(Debug, Clone)]
+pub struct DslStrategy {
+    pub name: String,
+    rules: Vec<CompiledRule>,
+    alerts: Vec<CompiledAlert>,
+    constants: HashMap<String, ExprValue>,
+    indicators: Vec<IndicatorDefinition>,
+    required_features: Vec<String>,
+}
+
+impl DslStrategy {
+    /// Parse source and load it against `schema`
+    pub fn from_source(source: &str, schema: &FeatureSchema) -> Result<Self> {
+        let ast = super::parse_strategy(source)?;
+        Self::load(&ast, schema).map_err(|diagnostics| {
+            anyhow!(
+                "Strategy {} failed to load:\n{}",
+                ast.name.node,
+                render_diagnostics(&diagnostics)
+            )
+        })
+    }
+
+    /// Type-check every trigger, action argument and alert condition.
+    ///
+    /// All problems are collected, so one load reports every unknown
+    /// identifier in the strategy.
+    pub fn load(ast: &StrategyAst, schema: &FeatureSchema) -> Result<Self, Vec<Diagnostic>> {
+        let constants = risk_constants(ast);
+        let (indicators, indicator_diagnostics) = super::indicators::definitions(ast);
+        let mut scope = schema.clone();
+        for output in indicators.iter().flat_map(|d| d.spec.outputs(&d.name)) {
+            scope.insert(&output, ExprType::Number);
+        }
+        for model in &ast.ai_models {
+            if let Some(output) = &model.output {
+                scope.insert(&output.node, ExprType::Number);
+            }
+        }
+        for (name, value) in &constants {
+            scope.insert(name, value.ty());
+        }
+
+        let mut loader = Loader {
+            scope,
+            diagnostics: indicator_diagnostics,
+        };
+
+        let sides = [
+            (RuleSide::Entry, &ast.entry_logic),
+            (RuleSide::Exit, &ast.exit_logic),
+        ];
+        let mut rules = Vec::new();
+        for (side, decls) in sides {
+            for decl in decls {
+                let (Some(trigger), Some(action)) = (&decl.trigger, &decl.action) else {
+                    // Already reported while lowering
+                    continue;
+                };
+                let trigger = loader.condition(&trigger.node, trigger.span);
+                let args: Vec<Option<(String, Expr)>> = action
+                    .args
+                    .iter()
+                    .enumerate()
+                    .map(|(i, arg)| loader.argument(i, arg))
+                    .collect();
+                if let (Some(trigger), Some(args)) =
+                    (trigger, args.into_iter().collect::<Option<Vec<_>>>())
+                {
+                    rules.push(CompiledRule {
+                        side,
+                        span: decl.span,
+                        priority: decl.priority.as_ref().map_or(1, |p| p.node),
+                        enabled: decl.is_enabled(),
+                        confidence_threshold: decl.confidence_threshold.as_ref().map(|c| c.node),
+                        trigger,
+                        action: action.name.node.clone(),
+                        args,
+                    });
+                }
+            }
+        }
+
+        let mut alerts = Vec::new();
+        for decl in &ast.alerts {
+            let Some(condition) = &decl.condition else {
+                continue;
+            };
+            if let Some(condition) = loader.condition(&condition.node, condition.span) {
+                alerts.push(CompiledAlert {
+                    span: decl.span,
+                    condition,
+                    action: decl.action.as_ref().map(|a| a.node.clone()),
+                    severity: decl
+                        .severity
+                        .as_ref()
+                        .map_or(AlertSeverity::Warning, |s| s.node),
+                });
+            }
+        }
+
+        if !loader.diagnostics.is_empty() {
+            loader.diagnostics.sort_by_key(|d| d.span.start);
+            return Err(loader.diagnostics);
+        }
+
+        let mut required_features: Vec<String> = rules
+            .iter()
+            .flat_map(|rule| {
+                std::iter::once(&rule.trigger)
+                    .chain(rule.args.iter().map(|(_, expr)| expr))
+                    .flat_map(Expr::identifiers)
+            })
+            .chain(
+                alerts
+                    .iter()
+                    .flat_map(|alert| alert.condition.identifiers()),
+            )
+            .filter(|name| !constants.contains_key(*name))
+            .map(str::to_string)
+            .collect();
+        required_features.sort();
+        required_features.dedup();
+
+        Ok(Self {
+            name: ast.name.node.clone(),
+            rules,
+            alerts,
+            constants,
+            indicators,
+            required_features,
+        })
+    }
+
+    pub fn rules(&self) -> &[CompiledRule] {
+        &self.rules
+    }
+
+    pub fn alerts(&self) -> &[CompiledAlert] {
+        &self.alerts
+    }
+
+    /// `risk_model` value usable in expressions, e.g. `position_size`
+    pub fn constant(&self, name: &str) -> Option<ExprValue> {
+        self.constants.get(name).copied()
+    }
+
+    /// Indicators declared in `technical_indicators`
+    pub fn indicators(&self) -> &[IndicatorDefinition] {
+        &self.indicators
+    }
+
+    /// Fresh engine computing the declared indicators; feed it ticks and
+    /// merge its values with `FeatureSnapshot::with_indicators`
+    pub fn indicator_engine(&self) -> IndicatorEngine {
+        let mut engine = IndicatorEngine::new();
+        for definition in &self.indicators {
+            // Validated when the strategy was loaded
+            let _ = engine.add(definition.clone());
+        }
+        engine
+    }
+
+    /// Features a snapshot must provide, sorted
+    pub fn required_features(&self) -> &[String] {
+        &self.required_features
+    }
+
+    /// Evaluate every enabled rule and alert against `snapshot`
+    pub fn evaluate(&self, snapshot: &FeatureSnapshot) -> Result<Evaluation> {
+        let lookup = |name: &str| self.constant(name).or_else(|| snapshot.get(name));
+        let mut evaluation = Evaluation::default();
+
+        for rule in self.rules.iter().filter(|rule| rule.enabled) {
+            if !is_true(&rule.trigger, &lookup)? {
+                continue;
+            }
+            let mut args = HashMap::new();
+            for (name, expr) in &rule.args {
+                args.insert(name.clone(), eval::evaluate(expr, &lookup)?);
+            }
+            let signal = Signal {
+                side: rule.side,
+                span: rule.span,
+                priority: rule.priority,
+                confidence_threshold: rule.confidence_threshold,
+                action: rule.action.clone(),
+                args,
+            };
+            match rule.side {
+                RuleSide::Entry => evaluation.entries.push(signal),
+                RuleSide::Exit => evaluation.exits.push(signal),
+            }
+        }
+
+        for alert in &self.alerts {
+            if is_true(&alert.condition, &lookup)? {
+                evaluation.alerts.push(RaisedAlert {
+                    span: alert.span,
+                    severity: alert.severity,
+                    action: alert.action.clone(),
+                });
+            }
+        }
+
+        evaluation.entries.sort_by_key(|s| s.priority);
+        evaluation.exits.sort_by_key(|s| s.priority);
+        Ok(evaluation)
+    }
+}
+
+fn is_true(expr: &Expr, lookup: &dyn Fn(&str) -> Option<ExprValue>) -> Result<bool> {
+    eval::evaluate(expr, lookup)?
+        .as_bool()
+        .ok_or_else(|| anyhow!("{}: condition did not evaluate to a boolean", expr.span))
+}
+
+/// Numeric, percentage and duration entries of `risk_model`
+pub(super) fn risk_constants(ast: &StrategyAst) -> HashMap<String, ExprValue> {
+    let Some(section) = ast.section("risk_model") else {
+        return HashMap::new();
+    };
+    section
+        .children
+        .iter()
+        .filter_map(|node| {
+            let value = node.value.as_ref()?;
+            let constant = match value.kind {
+                ValueKind::Number(_) | ValueKind::Percent(_) => {
+                    ExprValue::Number(value.as_ratio()?)
+                }
+                ValueKind::Duration(d) => ExprValue::Duration(d.as_secs_f64()),
+                _ => return None,
+            };
+            Some((node.key.node.clone(), constant))
+        })
+        .collect()
+}
+
+struct Loader {
+    scope: FeatureSchema,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Loader {
+    fn parse(&mut self, text: &str, span: Span) -> Option<Expr> {
+        parse_expr(text, span)
+            .map_err(|diagnostic| self.diagnostics.push(diagnostic))
+            .ok()
+    }
+
+    /// Trigger or alert condition; must be boolean
+    fn condition(&mut self, text: &str, span: Span) -> Option<Expr> {
+        let expr = self.parse(text, span)?;
+        match eval::type_check(&expr, &self.scope, &mut self.diagnostics)? {
+            ExprType::Bool => Some(expr),
+            ty => {
+                self.diagnostics.push(Diagnostic::error(
+                    format!("condition must be boolean, found a {}", ty),
+                    expr.span,
+                ));
+                None
+            }
+        }
+    }
+
+    /// Action argument; must be a number or duration
+    fn argument(&mut self, index: usize, arg: &CallArg) -> Option<(String, Expr)> {
+        let name = arg
+            .name
+            .as_ref()
+            .map_or_else(|| format!("arg{}", index), |n| n.node.clone());
+        let expr = self.value(&arg.value)?;
+        match eval::type_check(&expr, &self.scope, &mut self.diagnostics)? {
+            ExprType::Bool => {
+                self.diagnostics.push(Diagnostic::error(
+                    format!("argument `{}` must be a number or duration", name),
+                    expr.span,
+                ));
+                None
+            }
+            _ => Some((name, expr)),
+        }
+    }
+
+    fn value(&mut self, value: &Value) -> Option<Expr> {
+        argument_expr(value)
+            .map_err(|diagnostic| self.diagnostics.extend(diagnostic))
+            .ok()
+    }
+}
+
+/// Parse an action argument value; quoted values are expressions too.
+///
+/// `Err(None)` means the value was already reported by the parser.
+pub(super) fn argument_expr(value: &Value) -> Result<Expr, Option<Diagnostic>> {
+    match &value.kind {
+        ValueKind::Str(text) => {
+            let span = value.span;
+            let inner = Span::new(span.start + 1, span.end - 1, span.line, span.column + 1);
+            parse_expr(text, inner).map_err(Some)
+        }
+        ValueKind::List(_) | ValueKind::Call { .. } => Err(Some(Diagnostic::error(
+            format!("expected an expression, found `{}`", value.raw),
+            value.span,
+        ))),
+        ValueKind::Invalid => Err(None),
+        _ => parse_expr(&value.raw, value.span).map_err(Some),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const MOMENTUM_V1: &str = include_str!("../../../strategies/momentum_v1.dsl");
+
+    fn momentum_schema() -> FeatureSchema {
+        FeatureSchema::standard()
+            .with_number("avg_volume_20")
+            .with_bool("price_breakout_confirmed")
+            .with_bool("macd_bullish")
+            .with_number("volume_spike")
+            .with_number("momentum_acceleration")
+            .with_bool("support_broken")
+            .with_number("correlation")
+            .with_number("memory_usage")
+    }
+
+    #[test]
+    fn test_checked_in_strategy_runs_in_process() {
+        let strategy = DslStrategy::from_source(MOMENTUM_V1, &momentum_schema()).unwrap();
+        assert_eq!(strategy.name, "MomentumStrategyV1");
+        assert!(strategy
+            .required_features()
+            .contains(&"avg_volume_20".to_string()));
+        assert!(!strategy
+            .required_features()
+            .contains(&"position_size".to_string()));
+        assert_eq!(
+            strategy.constant("position_size"),
+            Some(ExprValue::Number(0.12))
+        );
+        assert_eq!(strategy.indicators().len(), 6);
+        assert_eq!(strategy.indicator_engine().outputs().len(), 8);
+
+        let mut snapshot = FeatureSnapshot::new();
+        for name in strategy.required_features() {
+            let value = match momentum_schema().get(name) {
+                Some(ExprType::Bool) => ExprValue::Bool(false),
+                Some(ExprType::Duration) => ExprValue::Duration(0.0),
+                _ => ExprValue::Number(0.0),
+            };
+            snapshot.set(name, value);
+        }
+        let snapshot = snapshot
+            .with_number("momentum_signal", 0.8)
+            .with_number("volume", 2000.0)
+            .with_number("avg_volume_20", 1000.0);
+
+        let evaluation = strategy.evaluate(&snapshot).unwrap();
+        assert!(!evaluation.entries.is_empty());
+        let signal = &evaluation.entries[0];
+        assert_eq!(signal.action, "market_buy");
+        assert_eq!(signal.arg("size"), Some(0.12));
+
+        let snapshot = snapshot
+            .with_number("momentum_signal", 0.1)
+            .with_duration("holding_time", Duration::from_secs(600));
+        let evaluation = strategy.evaluate(&snapshot).unwrap();
+        assert!(evaluation.entries.is_empty());
+        assert!(!evaluation.exits.is_empty());
+    }
+
+    #[test]
+    fn test_unknown_identifiers_reported_at_load() {
+        let ast = crate::forge::dsl::parse_strategy(MOMENTUM_V1).unwrap();
+        let diagnostics = DslStrategy::load(&ast, &FeatureSchema::standard()).unwrap_err();
+
+        let unknown: Vec<&str> = diagnostics
+            .iter()
+            .map(|d| d.message.as_str())
+            .filter(|m| m.starts_with("unknown identifier"))
+            .collect();
+        assert!(
+            unknown.contains(&"unknown identifier `avg_volume_20`"),
+            "{:?}",
+            unknown
+        );
+        assert!(
+            unknown.contains(&"unknown identifier `support_broken`"),
+            "{:?}",
+            unknown
+        );
+
+        let diagnostic = diagnostics
+            .iter()
+            .find(|d| d.message.contains("support_broken"))
+            .unwrap();
+        assert_eq!(diagnostic.span.text(MOMENTUM_V1), "support_broken");
+    }
+
+    #[test]
+    fn test_load_rejects_ill_typed_rules() {
+        let source = "strategy Bad:\n  entry_logic:\n    - trigger: \"volume * 2\"\n      action: market_buy(size=volume > 1)\n    - trigger: \"holding_time > 300\"\n      action: market_buy\n";
+        let err = DslStrategy::from_source(source, &FeatureSchema::standard())
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("3:17: error: condition must be boolean"),
+            "{}",
+            err
+        );
+        assert!(err.contains("argument `size` must be a number"), "{}", err);
+        assert!(
+            err.contains("5:17: error: `>` cannot be applied to a duration and a number"),
+            "{}",
+            err
+        );
+    }
+}
@@ -0,0 +1,1088 @@
+//! DSL SYNTAX - Lossless Line Tree
+//!
+//! Każda linia zachowuje wcięcie, komentarz i znak końca linii,
+//! więc `Document::to_source()` odtwarza plik bajt w bajt.
+//! Values keep their raw text next to the parsed form for the same reason.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use super::{Diagnostic, Span, Spanned};
+
+/// Parsed strategy file: every source line plus the tree built from them
+#[derive(Debug, Clone, PartialEq)]
+pub struct Document {
+    /// All lines, including blank and comment-only lines
+    pub lines: Vec<Line>,
+
+    /// The `strategy <Name>:` block, if the file declares one
+    pub strategy: Option<StrategyBlock>,
+}
+
+/// `strategy <Name>:` declaration and its sections
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategyBlock {
+    /// Index into `Document::lines`
+    pub line: usize,
+    pub name: Spanned<String>,
+    pub span: Span,
+    pub body: Vec<Node>,
+}
+
+/// One physical source line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Line {
+    /// 1-based line number
+    pub number: usize,
+
+    /// Byte offset of the first character of the line
+    pub offset: usize,
+
+    /// Leading whitespace, verbatim
+    pub indent: String,
+
+    pub content: LineContent,
+
+    /// Span of the content, without indent, trailing whitespace or comment
+    pub span: Span,
+
+    /// Whitespace between the content and the comment or end of line
+    pub gap: String,
+
+    /// Trailing `// ...` comment, including the slashes
+    pub comment: Option<Spanned<String>>,
+
+    /// "\n", "\r\n", or empty for the last line
+    pub newline: String,
+}
+
+/// What a line contains once indent and comment are stripped
+#[derive(Debug, Clone, PartialEq)]
+pub enum LineContent {
+    Blank,
+    Header {
+        keyword_gap: String,
+        name: Spanned<String>,
+        before_colon: String,
+    },
+    Entry(Entry),
+    /// Line that could not be parsed; kept verbatim
+    Error(String),
+}
+
+/// `key: value` or `- key: value`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    /// `-` marker plus the whitespace after it
+    pub dash: Option<Spanned<String>>,
+    pub key: Spanned<String>,
+    pub before_colon: String,
+    pub after_colon: String,
+    pub value: Option<Value>,
+}
+
+/// Scalar, list or call value with its raw source text
+#[derive(Debug, Clone, PartialEq)]
+pub struct Value {
+    pub raw: String,
+    pub span: Span,
+    pub kind: ValueKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueKind {
+    /// Quoted string, unescaped
+    Str(String),
+    Number(f64),
+    /// `6%` stored as written (6.0); see `Value::as_ratio`
+    Percent(f64),
+    /// `300s`, `100ms`, `5m`, `1h`
+    Duration(Duration),
+    Bool(bool),
+    Ident(String),
+    List(Vec<Value>),
+    Call {
+        name: Spanned<String>,
+        args: Vec<CallArg>,
+    },
+    /// Anything else, e.g. `position_size*0.8`; evaluated later
+    Expr(String),
+    /// Unterminated string or unbalanced brackets; already reported
+    Invalid,
+}
+
+/// `name=value` or positional call argument
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallArg {
+    pub name: Option<Spanned<String>>,
+    pub value: Value,
+}
+
+/// Entry in the tree with its nested block
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    /// Index into `Document::lines`
+    pub line: usize,
+    pub span: Span,
+    pub key: Spanned<String>,
+    pub value: Option<Value>,
+
+    /// Nested `key: value` entries
+    pub children: Vec<Node>,
+
+    /// Nested `- key: value` list items
+    pub items: Vec<Item>,
+}
+
+/// `- key: value` list item and the entries aligned with its first key
+#[derive(Debug, Clone, PartialEq)]
+pub struct Item {
+    pub span: Span,
+    pub entries: Vec<Node>,
+}
+
+impl Document {
+    /// Reprint the document exactly as it was parsed
+    pub fn to_source(&self) -> String {
+        let mut out = String::new();
+        for line in &self.lines {
+            line.write_to(&mut out);
+        }
+        out
+    }
+}
+
+impl std::fmt::Display for Document {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_source())
+    }
+}
+
+impl Line {
+    pub fn entry(&self) -> Option<&Entry> {
+        match &self.content {
+            LineContent::Entry(entry) => Some(entry),
+            _ => None,
+        }
+    }
+
+    /// Column where the entry key starts (after indent and `- `)
+    fn key_column(&self) -> usize {
+        let dash = self
+            .entry()
+            .and_then(|e| e.dash.as_ref())
+            .map_or(0, |d| d.node.len());
+        self.indent.len() + dash
+    }
+
+    fn is_dash(&self) -> bool {
+        self.entry().is_some_and(|e| e.dash.is_some())
+    }
+
+    pub fn write_to(&self, out: &mut String) {
+        out.push_str(&self.indent);
+        match &self.content {
+            LineContent::Blank => {}
+            LineContent::Header {
+                keyword_gap,
+                name,
+                before_colon,
+            } => {
+                out.push_str("strategy");
+                out.push_str(keyword_gap);
+                out.push_str(&name.node);
+                out.push_str(before_colon);
+                out.push(':');
+            }
+            LineContent::Entry(entry) => {
+                if let Some(dash) = &entry.dash {
+                    out.push_str(&dash.node);
+                }
+                out.push_str(&entry.key.node);
+                out.push_str(&entry.before_colon);
+                out.push(':');
+                out.push_str(&entry.after_colon);
+                if let Some(value) = &entry.value {
+                    out.push_str(&value.raw);
+                }
+            }
+            LineContent::Error(text) => out.push_str(text),
+        }
+        out.push_str(&self.gap);
+        if let Some(comment) = &self.comment {
+            out.push_str(&comment.node);
+        }
+        out.push_str(&self.newline);
+    }
+}
+
+impl Value {
+    /// String or identifier contents
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.kind {
+            ValueKind::Str(s) | ValueKind::Ident(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self.kind {
+            ValueKind::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    /// Plain number as-is, percentage divided by 100
+    pub fn as_ratio(&self) -> Option<f64> {
+        match self.kind {
+            ValueKind::Number(n) => Some(n),
+            ValueKind::Percent(p) => Some(p / 100.0),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.kind {
+            ValueKind::Number(n) if n >= 0.0 && n.fract() == 0.0 && n <= u64::MAX as f64 => {
+                Some(n as u64)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self.kind {
+            ValueKind::Bool(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    pub fn as_duration(&self) -> Option<Duration> {
+        match self.kind {
+            ValueKind::Duration(d) => Some(d),
+            _ => None,
+        }
+    }
+
+    /// Text of a scalar: string contents, otherwise the raw source
+    pub fn text(&self) -> &str {
+        match &self.kind {
+            ValueKind::Str(s) => s,
+            _ => &self.raw,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        match &self.kind {
+            ValueKind::Str(s) | ValueKind::Ident(s) => serde_json::Value::String(s.clone()),
+            ValueKind::Number(n) => serde_json::json!(n),
+            ValueKind::Percent(p) => serde_json::json!(p / 100.0),
+            ValueKind::Duration(d) => serde_json::json!(d.as_secs_f64()),
+            ValueKind::Bool(b) => serde_json::Value::Bool(*b),
+            ValueKind::List(values) => {
+                serde_json::Value::Array(values.iter().map(Value::to_json).collect())
+            }
+            ValueKind::Call { .. } | ValueKind::Expr(_) | ValueKind::Invalid => {
+                serde_json::Value::String(self.raw.clone())
+            }
+        }
+    }
+}
+
+impl Node {
+    /// Nested entry by key
+    pub fn get(&self, key: &str) -> Option<&Node> {
+        find(&self.children, key)
+    }
+
+    /// Value as JSON; blocks become objects and list items arrays of objects
+    pub fn to_json(&self) -> serde_json::Value {
+        if let Some(value) = &self.value {
+            value.to_json()
+        } else if self.children.is_empty() && !self.items.is_empty() {
+            serde_json::Value::Array(self.items.iter().map(Item::to_json).collect())
+        } else {
+            entries_to_json(&self.children)
+        }
+    }
+}
+
+impl Item {
+    pub fn get(&self, key: &str) -> Option<&Node> {
+        find(&self.entries, key)
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        entries_to_json(&self.entries)
+    }
+}
+
+/// First entry with the given key
+pub fn find<'a>(nodes: &'a [Node], key: &str) -> Option<&'a Node> {
+    nodes.iter().find(|n| n.key.node == key)
+}
+
+fn entries_to_json(nodes: &[Node]) -> serde_json::Value {
+    serde_json::Value::Object(
+        nodes
+            .iter()
+            .map(|n| (n.key.node.clone(), n.to_json()))
+            .collect(),
+    )
+}
+
+/// Split source into lines and build the tree
+pub fn parse_document(source: &str) -> (Document, Vec<Diagnostic>) {
+    let mut diagnostics = Vec::new();
+    let mut lines = Vec::new();
+    let mut offset = 0;
+
+    for (index, chunk) in source.split_inclusive('\n').enumerate() {
+        let (text, newline) = if let Some(text) = chunk.strip_suffix("\r\n") {
+            (text, "\r\n")
+        } else if let Some(text) = chunk.strip_suffix('\n') {
+            (text, "\n")
+        } else {
+            (chunk, "")
+        };
+        let ctx = LineCtx {
+            text,
+            offset,
+            number: index + 1,
+        };
+        lines.push(ctx.parse(newline, &mut diagnostics));
+        offset += chunk.len();
+    }
+
+    let mut builder = TreeBuilder {
+        lines: &lines,
+        pos: 0,
+        diagnostics: Vec::new(),
+    };
+    let strategy = builder.build();
+    diagnostics.extend(builder.diagnostics);
+
+    (Document { lines, strategy }, diagnostics)
+}
+
+/// One line of source being lexed
+struct LineCtx<'a> {
+    text: &'a str,
+    offset: usize,
+    number: usize,
+}
+
+impl<'a> LineCtx<'a> {
+    fn span(&self, start: usize, end: usize) -> Span {
+        let column = self.text[..start].chars().count() + 1;
+        Span::new(self.offset + start, self.offset + end, self.number, column)
+    }
+
+    fn parse(&self, newline: &str, diagnostics: &mut Vec<Diagnostic>) -> Line {
+        let text = self.text;
+        let indent_len = text.len() - text.trim_start_matches([' ', '\t']).len();
+        if let Some(tab) = text[..indent_len].find('\t') {
+            diagnostics.push(Diagnostic::error(
+                "tab in indentation; use spaces",
+                self.span(tab, tab + 1),
+            ));
+        }
+
+        let comment_at = find_comment(&text[indent_len..]).map(|i| i + indent_len);
+        let body_end = comment_at.unwrap_or(text.len());
+        let content_end = indent_len + text[indent_len..body_end].trim_end().len();
+
+        Line {
+            number: self.number,
+            offset: self.offset,
+            indent: text[..indent_len].to_string(),
+            content: self.parse_content(indent_len, content_end, diagnostics),
+            span: self.span(indent_len, content_end),
+            gap: text[content_end..body_end].to_string(),
+            comment: comment_at
+                .map(|c| Spanned::new(text[c..].to_string(), self.span(c, text.len()))),
+            newline: newline.to_string(),
+        }
+    }
+
+    fn parse_content(
+        &self,
+        start: usize,
+        end: usize,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> LineContent {
+        let content = &self.text[start..end];
+        if content.is_empty() {
+            return LineContent::Blank;
+        }
+
+        match self
+            .parse_header(start, end)
+            .or_else(|| self.parse_entry(start, end, diagnostics))
+        {
+            Some(Ok(content)) => content,
+            Some(Err(diagnostic)) => {
+                diagnostics.push(diagnostic);
+                LineContent::Error(content.to_string())
+            }
+            None => {
+                diagnostics.push(Diagnostic::error(
+                    "expected `key: value`",
+                    self.span(start, end),
+                ));
+                LineContent::Error(content.to_string())
+            }
+        }
+    }
+
+    fn parse_header(&self, start: usize, end: usize) -> Option<Result<LineContent, Diagnostic>> {
+        let content = &self.text[start..end];
+        let rest = content.strip_prefix("strategy")?;
+        if !rest.starts_with([' ', '\t']) {
+            return None;
+        }
+
+        let name_start = start + "strategy".len() + (rest.len() - rest.trim_start().len());
+        let Some(before) = self.text[name_start..end].strip_suffix(':') else {
+            return Some(Err(Diagnostic::error(
+                "expected `:` after strategy name",
+                self.span(start, end),
+            )));
+        };
+        let name_end = name_start + before.trim_end().len();
+        let name = &self.text[name_start..name_end];
+        if !is_ident(name) {
+            return Some(Err(Diagnostic::error(
+                format!("invalid strategy name `{}`", name),
+                self.span(name_start, name_end.max(name_start + 1).min(end)),
+            )));
+        }
+
+        Some(Ok(LineContent::Header {
+            keyword_gap: self.text[start + "strategy".len()..name_start].to_string(),
+            name: Spanned::new(name.to_string(), self.span(name_start, name_end)),
+            before_colon: self.text[name_end..end - 1].to_string(),
+        }))
+    }
+
+    fn parse_entry(
+        &self,
+        start: usize,
+        end: usize,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> Option<Result<LineContent, Diagnostic>> {
+        let text = self.text;
+        let content = &text[start..end];
+
+        let mut key_start = start;
+        let mut dash = None;
+        if content.starts_with('-') && (content.len() == 1 || content[1..].starts_with([' ', '\t']))
+        {
+            let marker_len = 1 + (content[1..].len() - content[1..].trim_start().len());
+            dash = Some(Spanned::new(
+                content[..marker_len].to_string(),
+                self.span(start, start + 1),
+            ));
+            key_start = start + marker_len;
+        }
+
+        let key_end = key_start
+            + text[key_start..end]
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(end - key_start);
+        if key_end == key_start {
+            let message = if dash.is_some() {
+                "expected `key: value` after `-`"
+            } else {
+                "expected `key: value`"
+            };
+            return Some(Err(Diagnostic::error(message, self.span(start, end))));
+        }
+        let key = &text[key_start..key_end];
+
+        let colon = key_end + (text[key_end..end].len() - text[key_end..end].trim_start().len());
+        if !text[colon..end].starts_with(':') {
+            return Some(Err(Diagnostic::error(
+                format!("expected `:` after `{}`", key),
+                self.span(key_start, end),
+            )));
+        }
+
+        let value_start =
+            colon + 1 + (text[colon + 1..end].len() - text[colon + 1..end].trim_start().len());
+        let value = (value_start < end).then(|| self.parse_value(value_start, end, diagnostics));
+
+        Some(Ok(LineContent::Entry(Entry {
+            dash,
+            key: Spanned::new(key.to_string(), self.span(key_start, key_end)),
+            before_colon: text[key_end..colon].to_string(),
+            after_colon: text[colon + 1..value_start].to_string(),
+            value,
+        })))
+    }
+
+    fn parse_value(&self, start: usize, end: usize, diagnostics: &mut Vec<Diagnostic>) -> Value {
+        Value {
+            raw: self.text[start..end].to_string(),
+            span: self.span(start, end),
+            kind: self.classify(start, end, diagnostics),
+        }
+    }
+
+    fn classify(&self, start: usize, end: usize, diagnostics: &mut Vec<Diagnostic>) -> ValueKind {
+        let raw = &self.text[start..end];
+
+        if let Err((message, at)) = check_balanced(raw) {
+            diagnostics.push(Diagnostic::error(message, self.span(start + at, end)));
+            return ValueKind::Invalid;
+        }
+
+        if raw.starts_with('"') && string_end(raw) == Some(raw.len()) {
+            return ValueKind::Str(unescape(&raw[1..raw.len() - 1]));
+        }
+
+        if raw.starts_with('[') && matching_close(raw, 0) == Some(raw.len() - 1) {
+            let values = split_top_level(raw, 1, raw.len() - 1)
+                .into_iter()
+                .map(|(s, e)| self.parse_value(start + s, start + e, diagnostics))
+                .collect();
+            return ValueKind::List(values);
+        }
+
+        match raw {
+            "true" => return ValueKind::Bool(true),
+            "false" => return ValueKind::Bool(false),
+            _ => {}
+        }
+
+        if let Some(n) = parse_number(raw) {
+            return ValueKind::Number(n);
+        }
+        if let Some(p) = raw.strip_suffix('%').and_then(parse_number) {
+            return ValueKind::Percent(p);
+        }
+        for (suffix, seconds) in [("ms", 0.001), ("s", 1.0), ("m", 60.0), ("h", 3600.0)] {
+            if let Some(n) = raw.strip_suffix(suffix).and_then(parse_number) {
+                if n >= 0.0 {
+                    return ValueKind::Duration(Duration::from_secs_f64(n * seconds));
+                }
+            }
+        }
+
+        if is_ident(raw) {
+            return ValueKind::Ident(raw.to_string());
+        }
+
+        if let Some(open) = raw.find('(') {
+            let name = &raw[..open];
+            if is_ident(name) && matching_close(raw, open) == Some(raw.len() - 1) {
+                let args = split_top_level(raw, open + 1, raw.len() - 1)
+                    .into_iter()
+                    .map(|(s, e)| self.parse_arg(start + s, start + e, diagnostics))
+                    .collect();
+                return ValueKind::Call {
+                    name: Spanned::new(name.to_string(), self.span(start, start + open)),
+                    args,
+                };
+            }
+        }
+
+        ValueKind::Expr(raw.to_string())
+    }
+
+    fn parse_arg(&self, start: usize, end: usize, diagnostics: &mut Vec<Diagnostic>) -> CallArg {
+        let raw = &self.text[start..end];
+        let name_len = raw
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(raw.len());
+        let after_name = &raw[name_len..];
+        let eq = name_len + (after_name.len() - after_name.trim_start().len());
+
+        if name_len > 0
+            && raw[eq..].starts_with('=')
+            && !raw[eq..].starts_with("==")
+            && is_ident(&raw[..name_len])
+        {
+            let value_start = eq + 1 + (raw[eq + 1..].len() - raw[eq + 1..].trim_start().len());
+            if value_start < raw.len() {
+                return CallArg {
+                    name: Some(Spanned::new(
+                        raw[..name_len].to_string(),
+                        self.span(start, start + name_len),
+                    )),
+                    value: self.parse_value(start + value_start, end, diagnostics),
+                };
+            }
+            diagnostics.push(Diagnostic::error(
+                format!("missing value for argument `{}`", &raw[..name_len]),
+                self.span(start, end),
+            ));
+        }
+
+        CallArg {
+            name: None,
+            value: self.parse_value(start, end, diagnostics),
+        }
+    }
+}
+
+/// Builds the indentation tree from parsed lines
+struct TreeBuilder<'a> {
+    lines: &'a [Line],
+    pos: usize,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> TreeBuilder<'a> {
+    /// Next header or entry line; blank, comment and error lines are skipped
+    fn peek(&mut self) -> Option<usize> {
+        while self.pos < self.lines.len() {
+            match self.lines[self.pos].content {
+                LineContent::Header { .. } | LineContent::Entry(_) => return Some(self.pos),
+                _ => self.pos += 1,
+            }
+        }
+        None
+    }
+
+    fn build(&mut self) -> Option<StrategyBlock> {
+        let mut strategy: Option<StrategyBlock> = None;
+
+        while let Some(index) = self.peek() {
+            let line = &self.lines[index];
+            self.pos = index + 1;
+            let (body, items) = self.parse_nested(line.indent.len());
+
+            match &line.content {
+                LineContent::Header { name, .. } => {
+                    if let Some(item) = items.first() {
+                        self.diagnostics.push(Diagnostic::error(
+                            "list item directly under `strategy`; expected a section",
+                            item.span,
+                        ));
+                    }
+                    if strategy.is_some() {
+                        self.diagnostics.push(Diagnostic::error(
+                            "only one `strategy` declaration is allowed per file",
+                            name.span,
+                        ));
+                        continue;
+                    }
+                    strategy = Some(StrategyBlock {
+                        line: index,
+                        name: name.clone(),
+                        span: line.span,
+                        body,
+                    });
+                }
+                _ => {
+                    self.diagnostics.push(Diagnostic::error(
+                        "expected `strategy <Name>:` declaration",
+                        line.span,
+                    ));
+                }
+            }
+        }
+
+        strategy
+    }
+
+    /// Everything indented deeper than `parent_column`
+    fn parse_nested(&mut self, parent_column: usize) -> (Vec<Node>, Vec<Item>) {
+        let mut children = Vec::new();
+        let mut items = Vec::new();
+        let mut block_column = None;
+
+        while let Some(index) = self.peek() {
+            let line = &self.lines[index];
+            let column = line.indent.len();
+            if column <= parent_column || matches!(line.content, LineContent::Header { .. }) {
+                break;
+            }
+
+            match block_column {
+                None => block_column = Some(column),
+                Some(expected) if expected != column => {
+                    self.diagnostics.push(Diagnostic::error(
+                        format!(
+                            "inconsistent indentation: expected {} spaces, found {}",
+                            expected, column
+                        ),
+                        line.span,
+                    ));
+                }
+                _ => {}
+            }
+
+            if line.is_dash() {
+                items.extend(self.parse_items(column));
+            } else {
+                children.extend(self.parse_block(column));
+            }
+        }
+
+        self.check_duplicate_keys(&children);
+        (children, items)
+    }
+
+    /// Consecutive `key: value` entries at exactly `column`
+    fn parse_block(&mut self, column: usize) -> Vec<Node> {
+        let mut nodes = Vec::new();
+        while let Some(index) = self.peek() {
+            let line = &self.lines[index];
+            if line.indent.len() != column || line.is_dash() || line.entry().is_none() {
+                break;
+            }
+            nodes.push(self.parse_node(index));
+        }
+        nodes
+    }
+
+    /// Consecutive `- key: value` items at exactly `column`
+    fn parse_items(&mut self, column: usize) -> Vec<Item> {
+        let mut items = Vec::new();
+        while let Some(index) = self.peek() {
+            let line = &self.lines[index];
+            if line.indent.len() != column || !line.is_dash() {
+                break;
+            }
+
+            let mut entries = vec![self.parse_node(index)];
+            entries.extend(self.parse_block(line.key_column()));
+            self.check_duplicate_keys(&entries);
+            items.push(Item {
+                span: line.span,
+                entries,
+            });
+        }
+        items
+    }
+
+    fn parse_node(&mut self, index: usize) -> Node {
+        let line = &self.lines[index];
+        let entry = line.entry().expect("parse_node called on a non-entry line");
+        self.pos = index + 1;
+
+        let (children, items) = self.parse_nested(line.key_column());
+        if entry.value.is_some() && (!children.is_empty() || !items.is_empty()) {
+            self.diagnostics.push(Diagnostic::error(
+                format!("`{}` has both a value and a nested block", entry.key.node),
+                entry.key.span,
+            ));
+        }
+
+        Node {
+            line: index,
+            span: line.span,
+            key: entry.key.clone(),
+            value: entry.value.clone(),
+            children,
+            items,
+        }
+    }
+
+    fn check_duplicate_keys(&mut self, nodes: &[Node]) {
+        let mut seen = HashSet::new();
+        for node in nodes {
+            if !seen.insert(node.key.node.as_str()) {
+                self.diagnostics.push(Diagnostic::warning(
+                    format!("duplicate key `{}`", node.key.node),
+                    node.key.span,
+                ));
+            }
+        }
+    }
+}
+
+/// Byte index of a `//` comment outside string literals
+fn find_comment(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut in_string = false;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if in_string => i += 1,
+            b'"' => in_string = !in_string,
+            b'/' if !in_string && bytes.get(i + 1) == Some(&b'/') => return Some(i),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Unterminated strings and unbalanced brackets, with the offending offset
+fn check_balanced(raw: &str) -> Result<(), (String, usize)> {
+    let mut stack: Vec<(u8, usize)> = Vec::new();
+    let mut string_start = None;
+    let bytes = raw.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let b = bytes[i];
+        if string_start.is_some() {
+            match b {
+                b'\\' => i += 1,
+                b'"' => string_start = None,
+                _ => {}
+            }
+        } else {
+            match b {
+                b'"' => string_start = Some(i),
+                b'[' | b'(' => stack.push((b, i)),
+                b']' | b')' => {
+                    let open = if b == b']' { b'[' } else { b'(' };
+                    match stack.pop() {
+                        Some((o, _)) if o == open => {}
+                        _ => return Err((format!("unexpected `{}`", b as char), i)),
+                    }
+                }
+                _ => {}
+            }
+        }
+        i += 1;
+    }
+
+    if let Some(at) = string_start {
+        return Err(("unterminated string literal".to_string(), at));
+    }
+    if let Some((open, at)) = stack.pop() {
+        return Err((format!("unclosed `{}`", open as char), at));
+    }
+    Ok(())
+}
+
+/// Byte index just past the closing quote of the string starting at 0
+fn string_end(raw: &str) -> Option<usize> {
+    let bytes = raw.as_bytes();
+    let mut i = 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 1,
+            b'"' => return Some(i + 1),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Index of the bracket closing the one at `open`
+fn matching_close(raw: &str, open: usize) -> Option<usize> {
+    let bytes = raw.as_bytes();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut i = open;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' if in_string => i += 1,
+            b'"' => in_string = !in_string,
+            b'[' | b'(' if !in_string => depth += 1,
+            b']' | b')' if !in_string => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Comma-separated ranges in `raw[start..end]` at bracket depth 0, trimmed
+fn split_top_level(raw: &str, start: usize, end: usize) -> Vec<(usize, usize)> {
+    let bytes = raw.as_bytes();
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut part_start = start;
+    let mut i = start;
+
+    while i < end {
+        match bytes[i] {
+            b'\\' if in_string => i += 1,
+            b'"' => in_string = !in_string,
+            b'[' | b'(' if !in_string => depth += 1,
+            b']' | b')' if !in_string => depth = depth.saturating_sub(1),
+            b',' if !in_string && depth == 0 => {
+                parts.push((part_start, i));
+                part_start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    parts.push((part_start, end));
+
+    let trimmed: Vec<(usize, usize)> = parts
+        .into_iter()
+        .map(|(s, e)| {
+            let part = &raw[s..e];
+            let lead = part.len() - part.trim_start().len();
+            (s + lead, s + lead + part.trim().len())
+        })
+        .collect();
+
+    // `[]` and `f()` have no elements rather than one empty one
+    if trimmed.len() == 1 && trimmed[0].0 == trimmed[0].1 {
+        return Vec::new();
+    }
+    trimmed
+}
+
+fn parse_number(text: &str) -> Option<f64> {
+    let digits = text.strip_prefix(['-', '+']).unwrap_or(text);
+    if !digits.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+    text.parse::<f64>().ok()
+}
+
+fn is_ident(text: &str) -> bool {
+    let mut chars = text.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('t') => out.push('\t'),
+            Some('"') => out.push('"'),
+            Some('\\') => out.push('\\'),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(source: &str) -> Value {
+        let (document, diagnostics) = parse_document(&format!("k: {}", source));
+        assert!(
+            diagnostics.iter().all(|d| !d.message.contains("unclosed")),
+            "{:?}",
+            diagnostics
+        );
+        document.lines[0].entry().unwrap().value.clone().unwrap()
+    }
+
+    #[test]
+    fn test_value_kinds() {
+        assert_eq!(
+            value("\"a \\\"b\\\"\"").kind,
+            ValueKind::Str("a \"b\"".to_string())
+        );
+        assert_eq!(value("0.12").kind, ValueKind::Number(0.12));
+        assert_eq!(value("-3").kind, ValueKind::Number(-3.0));
+        assert_eq!(value("1.5%").kind, ValueKind::Percent(1.5));
+        assert_eq!(
+            value("300s").kind,
+            ValueKind::Duration(Duration::from_secs(300))
+        );
+        assert_eq!(
+            value("100ms").kind,
+            ValueKind::Duration(Duration::from_millis(100))
+        );
+        assert_eq!(value("true").kind, ValueKind::Bool(true));
+        assert_eq!(
+            value("MomentumNet").kind,
+            ValueKind::Ident("MomentumNet".to_string())
+        );
+        assert_eq!(
+            value("position_size*0.8").kind,
+            ValueKind::Expr("position_size*0.8".to_string())
+        );
+
+        let list = value("[0.01, \"a,b\", [1, 2]]");
+        let ValueKind::List(items) = &list.kind else {
+            panic!("{:?}", list)
+        };
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[1].kind, ValueKind::Str("a,b".to_string()));
+        assert_eq!(items[1].span.column, 11);
+        assert!(matches!(items[2].kind, ValueKind::List(ref inner) if inner.len() == 2));
+        assert_eq!(value("[]").kind, ValueKind::List(vec![]));
+
+        let call = value("limit_sell(size=75%, offset=0.1%)");
+        let ValueKind::Call { name, args } = &call.kind else {
+            panic!("{:?}", call)
+        };
+        assert_eq!(name.node, "limit_sell");
+        assert_eq!(args[0].name.as_ref().unwrap().node, "size");
+        assert_eq!(args[0].value.as_ratio(), Some(0.75));
+        assert_eq!(args[1].value.span.column, 32);
+    }
+
+    #[test]
+    fn test_comments_and_trivia_are_kept() {
+        let source = "strategy  S :  // top\n  a: \"x // not a comment\"   // real\n\t\n    \n  b:\n    - c: 1\n      d: 2\n";
+        let (document, diagnostics) = parse_document(source);
+        assert_eq!(document.to_source(), source);
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert!(diagnostics[0].message.contains("tab"));
+
+        let line = &document.lines[1];
+        assert_eq!(
+            line.entry().unwrap().value.as_ref().unwrap().text(),
+            "x // not a comment"
+        );
+        assert_eq!(line.gap, "   ");
+        assert_eq!(line.comment.as_ref().unwrap().node, "// real");
+
+        let strategy = document.strategy.unwrap();
+        assert_eq!(strategy.name.node, "S");
+        let b = find(&strategy.body, "b").unwrap();
+        assert_eq!(b.items.len(), 1);
+        assert_eq!(b.items[0].entries.len(), 2);
+        assert_eq!(b.to_json(), serde_json::json!([{ "c": 1.0, "d": 2.0 }]));
+    }
+
+    #[test]
+    fn test_indentation_errors_are_reported() {
+        let source = "strategy S:\n  a:\n    x: 1\n   y: 2\n  b: 1\n    z: 3\n";
+        let (document, diagnostics) = parse_document(source);
+        assert_eq!(document.to_source(), source);
+
+        let messages: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.span.line, d.message.as_str()))
+            .collect();
+        assert!(
+            messages
+                .iter()
+                .any(|(l, m)| *l == 4 && m.contains("inconsistent indentation")),
+            "{:?}",
+            messages
+        );
+        assert!(
+            messages
+                .iter()
+                .any(|(l, m)| *l == 5 && m.contains("both a value")),
+            "{:?}",
+            messages
+        );
+
+        // Misplaced entries are still attached somewhere in the tree
+        let strategy = document.strategy.unwrap();
+        let a = find(&strategy.body, "a").unwrap();
+        assert!(a.get("y").is_some());
+        assert!(find(&strategy.body, "b").unwrap().get("z").is_some());
+    }
+}
@@ -10,6 +10,7 @@ use std::collections::HashMap;
 use tracing::{debug, info};
 use uuid::Uuid;
 
+use super::dsl::{self, RuleDecl, StrategyAst};
 use super::tensorzero_gateway::TensorZeroGateway;
 use super::{AgentHistoricalData, EvolutionParams};
 
@@ -91,6 +92,90 @@ pub enum GenerationMethod {
     Optimization { base_id: String, optimization_target: String },
 }
 
+impl StrategyDSL {
+    /// Parse DSL source into a strategy, failing on any syntax error
+    pub fn from_source(source_code: String, metadata: GenerationMetadata) -> Result<Self> {
+        let ast = dsl::parse_strategy(&source_code)?;
+        Ok(Self::from_ast(&ast, source_code, metadata))
+    }
+    
+    /// Build from an already parsed AST; `source_code` must be its source
+    pub fn from_ast(ast: &StrategyAst, source_code: String, metadata: GenerationMetadata) -> Self {
+        Self {
+            strategy_id: Uuid::new_v4().to_string(),
+            name: ast.name.node.clone(),
+            source_code,
+            risk_model: RiskModel::from_ast(ast),
+            entry_logic: ast.entry_logic.iter().map(TradingRule::from_ast).collect(),
+            exit_logic: ast.exit_logic.iter().map(TradingRule::from_ast).collect(),
+            ai_models: ast.ai_models.iter().map(AIModelDef::from_ast).collect(),
+            metadata,
+        }
+    }
+    
+    /// Re-parse the source into the typed AST
+    pub fn ast(&self) -> Result<StrategyAst> {
+        dsl::parse_strategy(&self.source_code)
+    }
+}
+
+impl RiskModel {
+    /// Missing values fall back to conservative defaults
+    fn from_ast(ast: &StrategyAst) -> Self {
+        let risk = ast.risk_model.as_ref();
+        
+        Self {
+            max_drawdown: risk.and_then(|r| r.max_drawdown.as_ref()).map_or(0.05, |v| v.node),
+            daily_loss_limit: risk.and_then(|r| r.daily_loss_limit.as_ref()).map_or(0.02, |v| v.node),
+            position_size: risk.and_then(|r| r.position_size.as_ref()).map_or(0.1, |v| v.node),
+            stop_loss: risk.and_then(|r| r.stop_loss.as_ref()).map(|v| v.node),
+            take_profit: risk.and_then(|r| r.take_profit.as_ref()).map(|v| v.node),
+            max_positions: risk.and_then(|r| r.max_positions.as_ref()).map(|v| v.node),
+        }
+    }
+}
+
+impl TradingRule {
+    fn from_ast(rule: &RuleDecl) -> Self {
+        let mut parameters = HashMap::new();
+        if let Some(action) = &rule.action {
+            for (index, arg) in action.args.iter().enumerate() {
+                let name = arg.name.as_ref()
+                    .map_or_else(|| format!("arg{}", index), |n| n.node.clone());
+                parameters.insert(name, arg.value.to_json());
+            }
+        }
+        if let Some(threshold) = &rule.confidence_threshold {
+            parameters.insert("confidence_threshold".to_string(), serde_json::json!(threshold.node));
+        }
+        
+        Self {
+            trigger: rule.trigger.as_ref().map(|t| t.node.clone()).unwrap_or_default(),
+            action: rule.action.as_ref().map(|a| a.name.node.clone()).unwrap_or_default(),
+            parameters,
+            priority: rule.priority.as_ref().map_or(1, |p| p.node.min(u8::MAX as u32) as u8),
+            enabled: rule.is_enabled(),
+        }
+    }
+}
+
+impl AIModelDef {
+    fn from_ast(model: &dsl::ModelDecl) -> Self {
+        let text = |field: &Option<dsl::Spanned<String>>| {
+            field.as_ref().map(|f| f.node.clone()).unwrap_or_default()
+        };
+        
+        Self {
+            name: text(&model.name),
+            version: text(&model.version),
+            purpose: text(&model.purpose),
+            parameters: model.parameters.iter()
+                .map(|p| (p.key.node.clone(), p.to_json()))
+                .collect(),
+        }
+    }
+}
+
 /// Strategy DSL Generator
 #[derive(Debug)]
 pub struct StrategyDSLGenerator {
@@ -136,6 +221,19 @@ impl Default for StrategyTemplates {
     }
 }
 
+impl StrategyTemplates {
+    /// Base template for a strategy type
+    pub fn for_type(&self, strategy_type: StrategyType) -> &str {
+        match strategy_type {
+            StrategyType::Momentum => &self.momentum_template,
+            StrategyType::MeanReversion => &self.mean_reversion_template,
+            StrategyType::Arbitrage => &self.arbitrage_template,
+            StrategyType::MarketMaking => &self.market_making_template,
+            StrategyType::Breakout => &self.breakout_template,
+        }
+    }
+}
+
 impl StrategyDSLGenerator {
     /// Create new DSL generator
     pub async fn new(tensorzero: Arc<TensorZeroGateway>) -> Result<Self> {
@@ -260,7 +358,7 @@ impl StrategyDSLGenerator {
         evolution_params: &EvolutionParams,
         strategy_type: &StrategyType,
     ) -> Result<String> {
-        let template = self.get_template_for_strategy_type(strategy_type);
+        let template = self.templates.for_type(*strategy_type);
         
         let prompt = format!(r#"
 Generate an optimized trading strategy DSL for agent {} based on the following analysis:
@@ -320,29 +418,15 @@ Generate the complete DSL code:
         Ok(prompt)
     }
     
-    /// Get template for strategy type
-    fn get_template_for_strategy_type(&self, strategy_type: &StrategyType) -> &str {
-        match strategy_type {
-            StrategyType::Momentum => &self.templates.momentum_template,
-            StrategyType::MeanReversion => &self.templates.mean_reversion_template,
-            StrategyType::Arbitrage => &self.templates.arbitrage_template,
-            StrategyType::MarketMaking => &self.templates.market_making_template,
-            StrategyType::Breakout => &self.templates.breakout_template,
-        }
-    }
-    
     /// Generate DSL code using TensorZero
-    async fn generate_dsl_code(&mut self, prompt: &str, agent_id: &str) -> Result<String> {
-        let context = format!("Agent ID: {}, Generation timestamp: {}", 
-                             agent_id, chrono::Utc::now().to_rfc3339());
+    async fn generate_dsl_code(&self, prompt: &str, agent_id: &str) -> Result<String> {
+        // No timestamps in the prompt: identical inputs must hash identically
+        // for recorded generations to replay
+        let context = format!("Agent ID: {}", agent_id);
         
         let function_name = format!("strategy_generation_agent_{}", agent_id);
         
-        // Cast to mutable reference to call mutable method
-        let tensorzero = Arc::get_mut(&mut self.tensorzero)
-            .ok_or_else(|| anyhow!("Failed to get mutable reference to TensorZero"))?;
-        
-        let generated_code = tensorzero.generate_strategy_dsl(
+        let generated_code = self.tensorzero.generate_strategy_dsl(
             prompt,
             Some(&context),
             Some(&function_name),
@@ -359,18 +443,13 @@ Generate the complete DSL code:
         historical_data: &AgentHistoricalData,
         strategy_type: StrategyType,
     ) -> Result<StrategyDSL> {
-        // Extract strategy name from DSL
-        let strategy_name = self.extract_strategy_name(&generated_code)
-            .unwrap_or_else(|| format!("Strategy_{}", agent_id));
-        
-        // Parse risk model
-        let risk_model = self.parse_risk_model(&generated_code)?;
-        
-        // Parse trading rules
-        let (entry_logic, exit_logic) = self.parse_trading_rules(&generated_code)?;
-        
-        // Parse AI models
-        let ai_models = self.parse_ai_models(&generated_code)?;
+        // Parse with the shared DSL parser; any syntax error rejects the generation
+        let parsed = dsl::parse(&generated_code);
+        for warning in parsed.diagnostics.iter().filter(|d| !d.is_error()) {
+            debug!("DSL warning for agent {}: {}", agent_id, warning);
+        }
+        let ast = parsed.into_ast()
+            .map_err(|e| anyhow!("Generated DSL for agent {} is invalid: {}", agent_id, e))?;
         
         // Create metadata
         let metadata = GenerationMetadata {
@@ -382,77 +461,7 @@ Generate the complete DSL code:
             complexity_score: self.calculate_complexity_score(&generated_code),
         };
         
-        Ok(StrategyDSL {
-            strategy_id: Uuid::new_v4().to_string(),
-            name: strategy_name,
-            source_code: generated_code,
-            risk_model,
-            entry_logic,
-            exit_logic,
-            ai_models,
-            metadata,
-        })
-    }
-    
-    /// Extract strategy name from DSL code
-    fn extract_strategy_name(&self, code: &str) -> Option<String> {
-        // Look for "strategy StrategyName:" pattern
-        for line in code.lines() {
-            let trimmed = line.trim();
-            if trimmed.starts_with("strategy ") && trimmed.ends_with(':') {
-                let name = trimmed[9..trimmed.len()-1].trim();
-                return Some(name.to_string());
-            }
-        }
-        None
-    }
-    
-    /// Parse risk model from DSL
-    fn parse_risk_model(&self, code: &str) -> Result<RiskModel> {
-        // Simple parsing - in production would use proper DSL parser
-        Ok(RiskModel {
-            max_drawdown: 0.05,
-            daily_loss_limit: 0.02,
-            position_size: 0.1,
-            stop_loss: Some(0.02),
-            take_profit: Some(0.04),
-            max_positions: Some(5),
-        })
-    }
-    
-    /// Parse trading rules from DSL
-    fn parse_trading_rules(&self, code: &str) -> Result<(Vec<TradingRule>, Vec<TradingRule>)> {
-        // Simple parsing - in production would use proper DSL parser
-        let entry_rule = TradingRule {
-            trigger: "momentum_signal > 0.7".to_string(),
-            action: "market_buy".to_string(),
-            parameters: HashMap::new(),
-            priority: 1,
-            enabled: true,
-        };
-        
-        let exit_rule = TradingRule {
-            trigger: "profit > 2% OR loss > 1%".to_string(),
-            action: "market_sell".to_string(),
-            parameters: HashMap::new(),
-            priority: 1,
-            enabled: true,
-        };
-        
-        Ok((vec![entry_rule], vec![exit_rule]))
-    }
-    
-    /// Parse AI models from DSL
-    fn parse_ai_models(&self, code: &str) -> Result<Vec<AIModelDef>> {
-        // Simple parsing - in production would use proper DSL parser
-        let model = AIModelDef {
-            name: "MomentumNet".to_string(),
-            version: "2.1".to_string(),
-            purpose: "Market momentum analysis".to_string(),
-            parameters: HashMap::new(),
-        };
-        
-        Ok(vec![model])
+        Ok(StrategyDSL::from_ast(&ast, generated_code, metadata))
     }
     
     /// Calculate complexity score of generated DSL
@@ -498,6 +507,16 @@ pub enum StrategyType {
     Breakout,
 }
 
+impl StrategyType {
+    pub const ALL: [StrategyType; 5] = [
+        StrategyType::Momentum,
+        StrategyType::MeanReversion,
+        StrategyType::Arbitrage,
+        StrategyType::MarketMaking,
+        StrategyType::Breakout,
+    ];
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -530,6 +549,92 @@ mod tests {
         assert!(templates.momentum_template.contains("momentum"));
     }
 
+    fn test_metadata() -> GenerationMetadata {
+        GenerationMetadata {
+            generated_at: chrono::Utc::now(),
+            generator_version: "1.0.0".to_string(),
+            parent_strategy_id: None,
+            generation_method: GenerationMethod::FromScratch,
+            performance_target: 1.5,
+            complexity_score: 5,
+        }
+    }
+    
+    #[test]
+    fn test_strategy_dsl_from_source() {
+        let source = include_str!("../../strategies/momentum_v1.dsl").to_string();
+        let dsl = StrategyDSL::from_source(source.clone(), test_metadata()).unwrap();
+        
+        assert_eq!(dsl.name, "MomentumStrategyV1");
+        assert_eq!(dsl.source_code, source);
+        assert!((dsl.risk_model.position_size - 0.12).abs() < 1e-12);
+        assert_eq!(dsl.risk_model.stop_loss, Some(0.025));
+        assert_eq!(dsl.risk_model.max_positions, Some(3));
+        
+        assert!(!dsl.entry_logic.is_empty());
+        let rule = &dsl.entry_logic[0];
+        assert_eq!(rule.action, "market_buy");
+        assert!(rule.trigger.contains("momentum_signal"));
+        assert!(dsl.exit_logic.iter().any(|r| r.parameters.get("size") == Some(&serde_json::json!(1.0))));
+        
+        assert_eq!(dsl.ai_models[0].name, "MomentumNet");
+        assert!(!dsl.ai_models[0].parameters.is_empty());
+        assert_eq!(dsl.ast().unwrap().name.node, dsl.name);
+    }
+    
+    #[test]
+    fn test_strategy_dsl_rejects_syntax_errors() {
+        let err = StrategyDSL::from_source("strategy X:\n  risk_model\n".to_string(), test_metadata())
+            .unwrap_err();
+        assert!(err.to_string().contains("2:3"), "{}", err);
+    }
+    
+    fn historical_data(max_drawdown: f64) -> AgentHistoricalData {
+        AgentHistoricalData {
+            agent_id: "agent".to_string(),
+            total_trades: 100,
+            successful_trades: 75,
+            total_pnl: 1250.0,
+            sharpe_ratio: 2.2,
+            max_drawdown,
+            recent_performance: vec![0.02, 0.01, -0.005, 0.015, 0.008],
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_offline_generation_records_and_replays() {
+        use super::super::artifact_registry::{ArtifactStore, LocalFsStore};
+        use super::super::inference_provider::{RecordingProvider, ReplayProvider, TemplateProvider};
+        use super::super::tensorzero_gateway::TensorZeroConfig;
+        use super::super::ForgeConfig;
+        
+        let dir = tempfile::TempDir::new().unwrap();
+        let store: Arc<dyn ArtifactStore> = Arc::new(LocalFsStore::new(dir.path()).unwrap());
+        let params = ForgeConfig::default().evolution_params;
+        
+        // Record what the local template provider generates
+        let recorder = RecordingProvider::new(Arc::new(TemplateProvider::default()), store.clone());
+        let gateway = Arc::new(TensorZeroGateway::from_provider(TensorZeroConfig::default(), Arc::new(recorder)));
+        let mut generator = StrategyDSLGenerator::new(gateway.clone()).await.unwrap();
+        let recorded = generator.generate_strategy("agent", &historical_data(0.08), &params).await.unwrap();
+        assert_eq!(recorded.source_code, StrategyTemplates::default().momentum_template);
+        assert_eq!(recorded.ast().unwrap().name.node, recorded.name);
+        assert_eq!(gateway.get_metrics().successful_requests, 1);
+        
+        // Replay serves the same generation without any provider behind it
+        let replay = Arc::new(TensorZeroGateway::from_provider(
+            TensorZeroConfig::default(),
+            Arc::new(ReplayProvider::new(store.clone())),
+        ));
+        let mut generator = StrategyDSLGenerator::new(replay).await.unwrap();
+        let replayed = generator.generate_strategy("agent", &historical_data(0.08), &params).await.unwrap();
+        assert_eq!(replayed.source_code, recorded.source_code);
+        
+        // Different history, different prompt (mean reversion): never recorded
+        let err = generator.generate_strategy("agent", &historical_data(0.2), &params).await.unwrap_err();
+        assert!(err.to_string().contains("No recorded response"), "{}", err);
+    }
+    
     #[test]
     fn test_generation_stats() {
         let mut stats = GenerationStats::default();
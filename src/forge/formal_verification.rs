@@ -3,12 +3,15 @@
 //! Matematyczna weryfikacja strategii przed deployment
 //! Proof-based validation dla critical trading logic
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::{Duration, Instant};
 use tracing::{debug, info};
 
+use super::dsl;
+use super::dsl::properties::{self, Property, PropertyCheck, PropertyStatus};
+
 /// Formal Verification Engine
 #[derive(Debug)]
 pub struct FormalVerificationEngine {
@@ -39,6 +42,14 @@ pub struct VerificationConfig {
     
     /// Enable counterexample generation
     pub generate_counterexamples: bool,
+    
+    /// Largest fraction of capital open across all positions
+    #[serde(default = "default_max_exposure")]
+    pub max_exposure: f64,
+}
+
+fn default_max_exposure() -> f64 {
+    0.5
 }
 
 /// Verification level
@@ -152,6 +163,7 @@ impl Default for VerificationConfig {
             required_level: VerificationLevel::Safety,
             generate_proofs: true,
             generate_counterexamples: true,
+            max_exposure: default_max_exposure(),
         }
     }
 }
@@ -201,6 +213,24 @@ impl FormalVerificationEngine {
                 condition: "total_risk <= max_risk_limit".to_string(),
                 enabled: true,
             },
+            VerificationRule {
+                rule_id: "SAFETY_004".to_string(),
+                name: "Exit Path".to_string(),
+                description: "Every position must have an exit that fires past stop_loss or max_trade_duration".to_string(),
+                rule_type: RuleType::Safety,
+                severity: Severity::Critical,
+                condition: "loss > stop_loss OR holding_time > max_trade_duration => some exit fires".to_string(),
+                enabled: true,
+            },
+            VerificationRule {
+                rule_id: "SAFETY_005".to_string(),
+                name: "Exposure Bound".to_string(),
+                description: "Entry size times max_positions must stay within the exposure limit".to_string(),
+                rule_type: RuleType::Safety,
+                severity: Severity::Critical,
+                condition: "entry_size * max_positions <= max_exposure".to_string(),
+                enabled: true,
+            },
             
             // Liveness Rules
             VerificationRule {
@@ -221,6 +251,15 @@ impl FormalVerificationEngine {
                 condition: "decisions_per_minute > min_decision_rate".to_string(),
                 enabled: true,
             },
+            VerificationRule {
+                rule_id: "LIVENESS_003".to_string(),
+                name: "Entry Reachability".to_string(),
+                description: "Every enabled entry trigger must be satisfiable".to_string(),
+                rule_type: RuleType::Liveness,
+                severity: Severity::Error,
+                condition: "exists features: entry_trigger = true".to_string(),
+                enabled: true,
+            },
             
             // Security Rules
             VerificationRule {
@@ -281,6 +320,15 @@ impl FormalVerificationEngine {
                 condition: "max_drawdown <= max_allowed_drawdown".to_string(),
                 enabled: true,
             },
+            VerificationRule {
+                rule_id: "BUSINESS_003".to_string(),
+                name: "Alert Ordering".to_string(),
+                description: "Alerts must escalate with the loss and fire once a risk limit is breached".to_string(),
+                rule_type: RuleType::Business,
+                severity: Severity::Error,
+                condition: "metric > limit => alert AND severity rises with threshold".to_string(),
+                enabled: true,
+            },
         ]
     }
     
@@ -389,44 +437,47 @@ impl FormalVerificationEngine {
     }
     
     /// Parse strategy DSL into AST
-    fn parse_strategy_dsl(&self, dsl: &str) -> Result<StrategyAST> {
-        // Simplified DSL parsing - in production would use proper parser
-        let mut ast = StrategyAST::default();
-        
-        // Extract metadata
-        if let Some(name_match) = dsl.lines().find(|line| line.trim().starts_with("name:")) {
-            ast.name = name_match.split(':').nth(1).unwrap_or("").trim().trim_matches('"').to_string();
-        }
+    fn parse_strategy_dsl(&self, source: &str) -> Result<StrategyAST> {
+        let strategy = dsl::parse_strategy(source)?;
+        
+        // Interval analysis of triggers, sizes and alerts
+        let properties = properties::check(&strategy, self.config.max_exposure).map_err(|diagnostics| {
+            anyhow!(
+                "Strategy {} cannot be analysed:\n{}",
+                strategy.name.node,
+                dsl::render_diagnostics(&diagnostics)
+            )
+        })?;
+        let mut ast = StrategyAST {
+            properties,
+            ..Default::default()
+        };
         
-        // Extract risk parameters
-        if let Some(risk_level_match) = dsl.lines().find(|line| line.trim().starts_with("risk_level:")) {
-            if let Ok(risk_level) = risk_level_match.split(':').nth(1).unwrap_or("0").trim().parse::<u8>() {
-                ast.risk_level = risk_level;
-            }
+        // Metadata: display name falls back to the declared strategy name
+        let metadata = strategy.metadata.as_ref();
+        ast.name = metadata
+            .and_then(|m| m.name.as_ref())
+            .map_or_else(|| strategy.name.node.clone(), |n| n.node.clone());
+        if let Some(risk_level) = metadata.and_then(|m| m.risk_level.as_ref()) {
+            ast.risk_level = risk_level.node.min(u8::MAX as u32) as u8;
         }
         
-        // Extract position size
-        if let Some(position_match) = dsl.lines().find(|line| line.trim().starts_with("position_size:")) {
-            let position_str = position_match.split(':').nth(1).unwrap_or("0%").trim().trim_end_matches('%');
-            if let Ok(position_size) = position_str.parse::<f64>() {
-                ast.max_position_size = position_size / 100.0; // Convert percentage to decimal
+        // Risk model (percentages are already fractions)
+        if let Some(risk) = &strategy.risk_model {
+            if let Some(position_size) = &risk.position_size {
+                ast.max_position_size = position_size.node;
             }
-        }
-        
-        // Extract stop loss
-        if let Some(stop_match) = dsl.lines().find(|line| line.trim().starts_with("stop_loss:")) {
-            let stop_str = stop_match.split(':').nth(1).unwrap_or("0%").trim().trim_end_matches('%');
-            if let Ok(stop_loss) = stop_str.parse::<f64>() {
-                ast.stop_loss = stop_loss / 100.0;
+            if let Some(stop_loss) = &risk.stop_loss {
+                ast.stop_loss = stop_loss.node;
             }
         }
         
-        // Extract max drawdown
-        if let Some(drawdown_match) = dsl.lines().find(|line| line.trim().starts_with("max_drawdown:")) {
-            let drawdown_str = drawdown_match.split(':').nth(1).unwrap_or("0%").trim().trim_end_matches('%');
-            if let Ok(max_drawdown) = drawdown_str.parse::<f64>() {
-                ast.max_drawdown = max_drawdown / 100.0;
-            }
+        // Max drawdown: risk_model wins over metadata
+        if let Some(max_drawdown) = strategy.risk_model.as_ref()
+            .and_then(|r| r.max_drawdown.as_ref())
+            .or_else(|| metadata.and_then(|m| m.max_drawdown.as_ref()))
+        {
+            ast.max_drawdown = max_drawdown.node;
         }
         
         // Set default values for verification
@@ -474,9 +525,30 @@ impl FormalVerificationEngine {
                     VerificationStatus::Failed
                 }
             }
-            _ => VerificationStatus::Passed, // Default to passed for other rules
+            rule_id => match Self::property_for(rule_id) {
+                Some(property) => {
+                    let checks: Vec<&PropertyCheck> = ast.checks(property).collect();
+                    if checks.iter().any(|c| c.status == PropertyStatus::Violated) {
+                        VerificationStatus::Failed
+                    } else if checks.iter().any(|c| c.status == PropertyStatus::Unknown) {
+                        VerificationStatus::Warning
+                    } else {
+                        VerificationStatus::Passed
+                    }
+                }
+                None => VerificationStatus::Passed, // Default to passed for other rules
+            },
         };
         
+        // Evidence: why property-backed rules did not pass
+        let evidence = Self::property_for(&rule.rule_id).and_then(|property| {
+            let lines: Vec<String> = ast.checks(property)
+                .filter(|c| c.status != PropertyStatus::Proven)
+                .map(|c| format!("{} ({}): {}", c.subject, c.span, c.steps.join("; ")))
+                .collect();
+            (!lines.is_empty()).then(|| lines.join("\n"))
+        });
+        
         let message = match status {
             VerificationStatus::Passed => format!("Rule {} passed", rule.rule_id),
             VerificationStatus::Failed => format!("Rule {} failed: {}", rule.rule_id, rule.description),
@@ -487,10 +559,21 @@ impl FormalVerificationEngine {
             rule_id: rule.rule_id.clone(),
             status,
             message,
-            evidence: None,
+            evidence,
         })
     }
     
+    /// Strategy property proven by interval analysis behind a rule
+    fn property_for(rule_id: &str) -> Option<Property> {
+        match rule_id {
+            "SAFETY_004" => Some(Property::ExitPath),
+            "SAFETY_005" => Some(Property::ExposureBound),
+            "LIVENESS_003" => Some(Property::EntryReachable),
+            "BUSINESS_003" => Some(Property::AlertOrdering),
+            _ => None,
+        }
+    }
+    
     /// Generate mathematical proofs
     async fn generate_proofs(&self, ast: &StrategyAST) -> Result<Vec<Proof>> {
         let mut proofs = Vec::new();
@@ -527,27 +610,78 @@ impl FormalVerificationEngine {
             });
         }
         
+        // Proofs 3+: strategy properties
+        for check in ast.properties.iter().filter(|c| c.status == PropertyStatus::Proven) {
+            let proof_method = match check.property {
+                Property::EntryReachable => "Witness construction",
+                _ => "Interval analysis",
+            };
+            proofs.push(Proof {
+                property: format!("{}: {}", check.property, check.subject),
+                proof_method: proof_method.to_string(),
+                proof_steps: check.steps.clone(),
+                confidence: 1.0,
+            });
+        }
+        
         Ok(proofs)
     }
     
     /// Generate counterexamples
-    async fn generate_counterexamples(&self, _ast: &StrategyAST, rule_results: &[RuleResult]) -> Result<Vec<Counterexample>> {
+    async fn generate_counterexamples(&self, ast: &StrategyAST, rule_results: &[RuleResult]) -> Result<Vec<Counterexample>> {
         let mut counterexamples = Vec::new();
         
         for rule_result in rule_results {
-            if rule_result.status == VerificationStatus::Failed {
-                // Generate counterexample for failed rule
-                counterexamples.push(Counterexample {
-                    property: format!("Rule {}", rule_result.rule_id),
-                    scenario: "Boundary condition violation".to_string(),
-                    inputs: HashMap::from([
-                        ("position_size".to_string(), "0.15".to_string()),
-                        ("stop_loss".to_string(), "0.05".to_string()),
-                    ]),
-                    expected_output: "Rule should pass".to_string(),
-                    actual_output: "Rule failed".to_string(),
-                });
+            if rule_result.status != VerificationStatus::Failed {
+                continue;
+            }
+            let condition = self.rules.iter()
+                .find(|r| r.rule_id == rule_result.rule_id)
+                .map_or_else(String::new, |r| r.condition.clone());
+            
+            // Property rules: the feature assignment breaking the property
+            if let Some(property) = Self::property_for(&rule_result.rule_id) {
+                for check in ast.checks(property).filter(|c| c.status == PropertyStatus::Violated) {
+                    let inputs = match &check.counterexample {
+                        Some(assignment) => assignment.iter()
+                            .map(|(name, value)| (name.clone(), value.to_string()))
+                            .collect(),
+                        // Unreachable entry: no assignment exists, the steps list the searched ranges
+                        None => HashMap::new(),
+                    };
+                    counterexamples.push(Counterexample {
+                        property: format!("Rule {}: {}", rule_result.rule_id, check.property),
+                        scenario: format!("{} at {}", check.subject, check.span),
+                        inputs,
+                        expected_output: condition.clone(),
+                        actual_output: check.steps.join("; "),
+                    });
+                }
+                continue;
             }
+            
+            // Risk-model rules: the declared values themselves
+            let total_risk = ast.max_position_size * ast.stop_loss;
+            let inputs: Vec<(&str, f64)> = match rule_result.rule_id.as_str() {
+                "SAFETY_001" => vec![("position_size", ast.max_position_size)],
+                "SAFETY_002" => vec![("stop_loss", ast.stop_loss), ("position_size", ast.max_position_size)],
+                "SAFETY_003" => vec![
+                    ("position_size", ast.max_position_size),
+                    ("stop_loss", ast.stop_loss),
+                    ("total_risk", total_risk),
+                ],
+                "BUSINESS_002" => vec![("max_drawdown", ast.max_drawdown)],
+                _ => vec![],
+            };
+            counterexamples.push(Counterexample {
+                property: format!("Rule {}", rule_result.rule_id),
+                scenario: "Declared risk_model values".to_string(),
+                inputs: inputs.into_iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+                expected_output: condition,
+                actual_output: rule_result.message.clone(),
+            });
         }
         
         Ok(counterexamples)
@@ -572,6 +706,10 @@ impl FormalVerificationEngine {
                     "SAFETY_002" => recommendations.push("Adjust stop loss to be positive and less than position value".to_string()),
                     "SAFETY_003" => recommendations.push("Reduce total risk exposure".to_string()),
                     "BUSINESS_002" => recommendations.push("Reduce maximum drawdown to acceptable levels".to_string()),
+                    "SAFETY_004" => recommendations.push("Add an exit rule that fires once loss exceeds stop_loss or holding_time exceeds max_trade_duration".to_string()),
+                    "SAFETY_005" => recommendations.push("Reduce entry sizes or max_positions to stay within the exposure limit".to_string()),
+                    "LIVENESS_003" => recommendations.push("Fix or disable entry triggers that can never fire".to_string()),
+                    "BUSINESS_003" => recommendations.push("Order alert thresholds so severity rises with the loss and alerts fire before risk limits".to_string()),
                     _ => recommendations.push(format!("Address rule failure: {}", rule_result.rule_id)),
                 }
             }
@@ -600,6 +738,14 @@ pub struct StrategyAST {
     pub max_latency: Duration,
     pub max_memory: usize,
     pub min_sharpe_ratio: f64,
+    pub properties: Vec<PropertyCheck>,
+}
+
+impl StrategyAST {
+    /// Checks of one property, e.g. every entry rule's reachability
+    pub fn checks(&self, property: Property) -> impl Iterator<Item = &PropertyCheck> {
+        self.properties.iter().filter(move |c| c.property == property)
+    }
 }
 
 #[cfg(test)]
@@ -628,4 +774,101 @@ strategy TestStrategy:
         assert!(matches!(result.overall_result, VerificationStatus::Passed | VerificationStatus::Warning));
         assert!(!result.rule_results.is_empty());
     }
+    
+    #[tokio::test]
+    async fn test_verifies_checked_in_strategy() {
+        let engine = FormalVerificationEngine::new(VerificationConfig::default());
+        let ast = engine.parse_strategy_dsl(include_str!("../../strategies/momentum_v1.dsl")).unwrap();
+        
+        assert_eq!(ast.name, "Momentum Strategy V1");
+        assert_eq!(ast.risk_level, 3);
+        assert!((ast.max_position_size - 0.12).abs() < 1e-12);
+        assert!((ast.stop_loss - 0.025).abs() < 1e-12);
+        assert!((ast.max_drawdown - 0.08).abs() < 1e-12);
+    }
+    
+    #[tokio::test]
+    async fn test_metadata_drawdown_is_a_fraction() {
+        let mut engine = FormalVerificationEngine::new(VerificationConfig::default());
+        
+        let test_dsl = r#"
+strategy HighRiskStrategy:
+  metadata:
+    max_drawdown: 0.15
+  risk_model:
+    position_size: 25%
+    stop_loss: 8%
+"#;
+        
+        let result = engine.verify_strategy(test_dsl, "high_risk").await.unwrap();
+        assert_eq!(result.overall_result, VerificationStatus::Failed);
+        assert!(result.rule_results.iter()
+            .any(|r| r.rule_id == "BUSINESS_002" && r.status == VerificationStatus::Failed));
+    }
+    
+    #[tokio::test]
+    async fn test_syntax_errors_fail_verification() {
+        let mut engine = FormalVerificationEngine::new(VerificationConfig::default());
+        
+        let err = engine.verify_strategy("strategy Broken:\n  risk_model:\n    stop_loss 2%\n", "broken")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("3:5"), "{}", err);
+    }
+    
+    #[tokio::test]
+    async fn test_checked_in_strategy_properties_are_proven() {
+        let mut engine = FormalVerificationEngine::new(VerificationConfig::default());
+        
+        let result = engine.verify_strategy(include_str!("../../strategies/momentum_v1.dsl"), "momentum_v1")
+            .await
+            .unwrap();
+        for rule_id in ["SAFETY_004", "SAFETY_005", "LIVENESS_003", "BUSINESS_003"] {
+            let rule_result = result.rule_results.iter().find(|r| r.rule_id == rule_id).unwrap();
+            assert_eq!(rule_result.status, VerificationStatus::Passed, "{:?}", rule_result);
+        }
+        assert!(result.proofs.iter().any(|p| p.proof_method == "Interval analysis"
+            && p.property.starts_with("Every position has an exit path")));
+        assert!(result.proofs.iter().any(|p| p.proof_method == "Witness construction"));
+    }
+    
+    #[tokio::test]
+    async fn test_counterexamples_are_concrete_assignments() {
+        let mut engine = FormalVerificationEngine::new(VerificationConfig::default());
+        
+        let test_dsl = r#"
+strategy NoStop:
+  risk_model:
+    position_size: 10%
+    max_positions: 3
+  entry_logic:
+    - trigger: "momentum_signal > 0.8"
+      action: market_buy(size=position_size * 2)
+  exit_logic:
+    - trigger: "profit > 5%"
+      action: market_sell(size=100%)
+"#;
+        
+        let result = engine.verify_strategy(test_dsl, "no_stop").await.unwrap();
+        assert_eq!(result.overall_result, VerificationStatus::Failed);
+        
+        let exit = result.counterexamples.iter()
+            .find(|c| c.property.starts_with("Rule SAFETY_004"))
+            .unwrap();
+        let profit: f64 = exit.inputs["profit"].parse().unwrap();
+        assert!(profit <= 0.05, "{:?}", exit.inputs);
+        
+        let exposure = result.counterexamples.iter()
+            .find(|c| c.property.starts_with("Rule SAFETY_005"))
+            .unwrap();
+        assert_eq!(exposure.inputs.get("momentum_signal").map(String::as_str), Some("1.6"));
+        assert!(exposure.actual_output.contains("with 3 open positions gives exposure 0.6 > 0.5"),
+                "{}", exposure.actual_output);
+        
+        let evidence = result.rule_results.iter()
+            .find(|r| r.rule_id == "SAFETY_004")
+            .and_then(|r| r.evidence.clone())
+            .unwrap();
+        assert!(evidence.contains("None of the 1 exit rules fires"), "{}", evidence);
+    }
 }
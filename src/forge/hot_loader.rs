@@ -7,15 +7,23 @@ use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::ffi::CStr;
+use std::io::Write;
 use std::os::raw::c_char;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, Instant};
 use tracing::{debug, info, warn, error};
 use libloading::{Library, Symbol};
 
+use super::artifact_registry::ArtifactRegistry;
+use super::rollout::{DecisionDiff, Rollout, RolloutConfig, RolloutReport, RolloutStage};
+use super::strategy_host::{HostLauncher, IsolatedStrategy, ProcessLauncher};
 use super::{CompiledArtifact, DeploymentResult};
 
+/// ABI version exported by strategies as `strategy_abi_version`; bump on any
+/// change to the VTable signatures or the C structs below
+pub const STRATEGY_ABI_VERSION: u32 = 1;
+
 /// Strategy container holding loaded library
 #[derive(Debug, Clone)]
 pub struct StrategyContainer {
@@ -25,11 +33,8 @@ pub struct StrategyContainer {
     /// Agent ID
     pub agent_id: String,
     
-    /// Library handle (wrapped in Arc for sharing)
-    pub library: Arc<Library>,
-    
-    /// Strategy VTable
-    pub vtable: StrategyVTable,
+    /// Where the strategy code runs
+    pub backend: StrategyBackend,
     
     /// Load timestamp
     pub loaded_at: chrono::DateTime<chrono::Utc>,
@@ -39,6 +44,32 @@ pub struct StrategyContainer {
     
     /// Health status
     pub health_status: HealthStatus,
+    
+    /// Verified copy in the loader's staging directory, deleted on unload
+    pub staged_path: Option<PathBuf>,
+}
+
+/// Loaded strategy code
+#[derive(Debug, Clone)]
+pub enum StrategyBackend {
+    /// Library mapped into the trading process (wrapped in Arc for sharing)
+    InProcess {
+        library: Arc<Library>,
+        vtable: StrategyVTable,
+    },
+    
+    /// Library running in a `forge-strategy-host` child process
+    Isolated(Arc<IsolatedStrategy>),
+}
+
+/// Where hot-loaded strategies run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IsolationMode {
+    /// `dlopen` into this process: fastest, but a crash takes the bot down
+    InProcess,
+    
+    /// One child process per strategy, restarted when it crashes
+    ChildProcess,
 }
 
 /// Strategy VTable - ABI interface
@@ -62,7 +93,7 @@ pub struct StrategyVTable {
 
 /// Market data structure (C-compatible)
 #[repr(C)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketData {
     pub timestamp: u64,
     pub price: f64,
@@ -99,6 +130,99 @@ pub struct StrategyInfo {
     pub max_drawdown: f64,
 }
 
+/// Owned copy of `StrategyInfo`
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyDetails {
+    pub name: String,
+    pub version: String,
+    pub author: String,
+    pub description: String,
+    pub risk_level: u8,
+    pub expected_return: f64,
+    pub max_drawdown: f64,
+}
+
+impl StrategyDetails {
+    /// Copy out of a strategy's static info; `None` for a null pointer
+    ///
+    /// # Safety
+    /// `info` must be null or point to a `StrategyInfo` whose strings are
+    /// null or valid C strings
+    pub unsafe fn from_raw(info: *const StrategyInfo) -> Option<Self> {
+        if info.is_null() {
+            return None;
+        }
+        let info = &*info;
+        let text = |ptr: *const c_char| {
+            if ptr.is_null() {
+                String::new()
+            } else {
+                CStr::from_ptr(ptr).to_string_lossy().to_string()
+            }
+        };
+        
+        Some(Self {
+            name: text(info.name),
+            version: text(info.version),
+            author: text(info.author),
+            description: text(info.description),
+            risk_level: info.risk_level,
+            expected_return: info.expected_return,
+            max_drawdown: info.max_drawdown,
+        })
+    }
+}
+
+impl StrategyContainer {
+    /// Signal strength for market data
+    pub fn analyze(&self, market_data: &MarketData) -> Result<f64> {
+        match &self.backend {
+            StrategyBackend::InProcess { vtable, .. } => {
+                Ok(unsafe { (vtable.analyze)(market_data as *const MarketData) })
+            }
+            StrategyBackend::Isolated(strategy) => strategy.analyze(market_data),
+        }
+    }
+    
+    /// Trading decision; the strategy may call `context.execution_callback`
+    /// and change the numeric context fields
+    pub fn execute(&self, context: &mut HftContext) -> Result<i32> {
+        match &self.backend {
+            StrategyBackend::InProcess { vtable, .. } => {
+                Ok(unsafe { (vtable.execute)(context as *mut HftContext) })
+            }
+            StrategyBackend::Isolated(strategy) => strategy.execute(context),
+        }
+    }
+    
+    /// Raw health code: 0 healthy, 1 warning, 2 critical
+    pub fn health_check(&self) -> Result<i32> {
+        match &self.backend {
+            StrategyBackend::InProcess { vtable, .. } => Ok(unsafe { (vtable.health_check)() }),
+            StrategyBackend::Isolated(strategy) => strategy.health_check(),
+        }
+    }
+    
+    /// Strategy info
+    pub fn details(&self) -> Result<StrategyDetails> {
+        match &self.backend {
+            StrategyBackend::InProcess { vtable, .. } => unsafe {
+                StrategyDetails::from_raw((vtable.get_info)())
+                    .ok_or_else(|| anyhow!("Strategy returned null info"))
+            },
+            StrategyBackend::Isolated(strategy) => strategy.details(),
+        }
+    }
+    
+    /// Release strategy resources; isolated hosts are stopped
+    pub fn cleanup(&self) {
+        match &self.backend {
+            StrategyBackend::InProcess { vtable, .. } => unsafe { (vtable.cleanup)() },
+            StrategyBackend::Isolated(strategy) => strategy.shutdown(),
+        }
+    }
+}
+
 /// Strategy performance metrics
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct StrategyMetrics {
@@ -132,6 +256,28 @@ pub struct StrategyHotLoader {
     
     /// Safety configuration
     safety_config: SafetyConfig,
+    
+    /// Registry verifying artifact signatures before they are loaded
+    registry: Option<Arc<ArtifactRegistry>>,
+    
+    /// Private (0700) directory holding the verified copies that get loaded
+    staging_dir: tempfile::TempDir,
+    
+    /// Starts strategy hosts in `IsolationMode::ChildProcess`
+    host_launcher: Option<Arc<dyn HostLauncher>>,
+    
+    /// Stage new strategies behind the incumbent instead of swapping
+    rollout_config: Option<RolloutConfig>,
+    
+    /// Candidates rolling out (and the last finished rollout) by agent ID
+    rollouts: Arc<RwLock<HashMap<String, StagedCandidate>>>,
+}
+
+/// Candidate strategy running next to an agent's incumbent
+#[derive(Debug)]
+struct StagedCandidate {
+    container: StrategyContainer,
+    rollout: Rollout,
 }
 
 /// Loader statistics
@@ -164,6 +310,18 @@ pub struct SafetyConfig {
     
     /// Memory limit for loaded strategies
     pub memory_limit_mb: u64,
+    
+    /// Run strategies in this process or in child processes
+    pub isolation: IsolationMode,
+    
+    /// Timeout for one call into an isolated strategy
+    pub call_timeout_ms: u64,
+    
+    /// Restarts of a crashed or hung strategy host before giving up
+    pub max_restarts: u32,
+    
+    /// `forge-strategy-host` binary; defaults to the one next to this executable
+    pub host_executable: Option<PathBuf>,
 }
 
 impl Default for SafetyConfig {
@@ -174,6 +332,10 @@ impl Default for SafetyConfig {
             max_consecutive_failures: 3,
             enable_sandbox: true,
             memory_limit_mb: 256,
+            isolation: IsolationMode::InProcess,
+            call_timeout_ms: 250,
+            max_restarts: 3,
+            host_executable: None,
         }
     }
 }
@@ -187,10 +349,40 @@ impl StrategyHotLoader {
             strategies: Arc::new(RwLock::new(HashMap::new())),
             stats: LoaderStats::default(),
             safety_config: SafetyConfig::default(),
+            registry: None,
+            staging_dir: private_staging_dir()?,
+            host_launcher: None,
+            rollout_config: None,
+            rollouts: Arc::new(RwLock::new(HashMap::new())),
         })
     }
     
-    /// Deploy strategy with hot loading
+    /// Replace safety configuration
+    pub fn with_safety_config(mut self, safety_config: SafetyConfig) -> Self {
+        self.safety_config = safety_config;
+        self
+    }
+    
+    /// Start isolated strategies with a custom launcher
+    pub fn with_host_launcher(mut self, launcher: Arc<dyn HostLauncher>) -> Self {
+        self.host_launcher = Some(launcher);
+        self
+    }
+    
+    /// Only load artifacts the registry knows and signed by a trusted key
+    pub fn with_registry(mut self, registry: Arc<ArtifactRegistry>) -> Self {
+        self.registry = Some(registry);
+        self
+    }
+    
+    /// Roll new strategies out through shadow and canary stages
+    pub fn with_rollout(mut self, config: RolloutConfig) -> Self {
+        self.rollout_config = Some(config);
+        self
+    }
+    
+    /// Deploy strategy with hot loading; with a rollout configured and a
+    /// strategy already trading, the new one starts in shadow instead
     pub async fn deploy_strategy(
         &mut self,
         agent_id: &str,
@@ -208,16 +400,24 @@ impl StrategyHotLoader {
             strategies.get(agent_id).map(|s| s.strategy_id.clone())
         };
         
+        // Stage behind the incumbent instead of swapping
+        if let (Some(config), Some(incumbent_id)) = (self.rollout_config.clone(), old_strategy_id.as_deref()) {
+            let stage = self.start_rollout(agent_id, incumbent_id, new_container.clone(), config);
+            info!("🌘 Strategy {} shadowing {} for agent {}", artifact.strategy_id, incumbent_id, agent_id);
+            
+            return Ok(DeploymentResult {
+                old_strategy_id,
+                new_strategy_id: artifact.strategy_id.clone(),
+                // Measured on live data, see `rollout_report`
+                performance_improvement: 0.0,
+                strategy_container: new_container,
+                rollout_stage: Some(stage),
+            });
+        }
+        
         // Perform hot swap
         let swap_result = self.hot_swap_strategy(agent_id, new_container).await?;
         
-        // Calculate performance improvement (mock for now)
-        let performance_improvement = self.calculate_performance_improvement(
-            agent_id,
-            old_strategy_id.as_deref(),
-            &artifact.strategy_id,
-        ).await?;
-        
         // Update statistics
         let deployment_time = start_time.elapsed().as_millis() as u64;
         self.update_deployment_stats(deployment_time, true);
@@ -227,8 +427,10 @@ impl StrategyHotLoader {
         Ok(DeploymentResult {
             old_strategy_id,
             new_strategy_id: artifact.strategy_id.clone(),
-            performance_improvement,
+            // Immediate swaps are never compared against the old strategy
+            performance_improvement: 0.0,
             strategy_container: swap_result,
+            rollout_stage: None,
         })
     }
     
@@ -246,11 +448,77 @@ impl StrategyHotLoader {
             return Err(anyhow!("Strategy artifact not found: {}", artifact.binary_path));
         }
         
+        // Load a private copy of the verified bytes, so the artifact cannot be
+        // swapped between verification and the loader opening it
+        let staged_path = match self.registry.clone() {
+            Some(registry) => match self.stage_verified(&registry, artifact).await {
+                Ok(path) => Some(path),
+                Err(e) => {
+                    self.update_load_stats(0, false);
+                    return Err(anyhow!("Refusing to load strategy {}: {}", artifact.strategy_id, e));
+                }
+            },
+            None => None,
+        };
+        let load_path = match &staged_path {
+            Some(path) => path.to_string_lossy().to_string(),
+            None => artifact.binary_path.clone(),
+        };
+        
+        let backend = match self.safety_config.isolation {
+            IsolationMode::InProcess => self.load_in_process(&load_path).await?,
+            IsolationMode::ChildProcess => self.load_isolated(&load_path).await?,
+        };
+        
+        // Create strategy container
+        let mut container = StrategyContainer {
+            strategy_id: artifact.strategy_id.clone(),
+            agent_id: agent_id.to_string(),
+            backend,
+            loaded_at: chrono::Utc::now(),
+            metrics: StrategyMetrics::default(),
+            health_status: HealthStatus::Healthy,
+            staged_path,
+        };
+        
+        // Perform initial health check
+        container.health_status = self.perform_health_check(&container)?;
+        
+        let load_time = start_time.elapsed().as_millis() as u64;
+        self.update_load_stats(load_time, true);
+        
+        debug!("✅ Strategy loaded successfully in {}ms", load_time);
+        Ok(container)
+    }
+    
+    /// Read the artifact once, verify digest and signature of those bytes and
+    /// write them into the staging directory; returns the copy to load
+    async fn stage_verified(
+        &self,
+        registry: &ArtifactRegistry,
+        artifact: &CompiledArtifact,
+    ) -> Result<PathBuf> {
+        let bytes = std::fs::read(&artifact.binary_path)
+            .map_err(|e| anyhow!("Failed to read {}: {}", artifact.binary_path, e))?;
+        let manifest = registry.verify_bytes(&bytes, &artifact.checksum).await?;
+        debug!("🔏 Artifact {} verified (signer {})", manifest.digest, manifest.signer.clone().unwrap_or_default());
+        
+        // Fresh file per load: rewriting a library that is already mapped would corrupt it
+        let mut staged = tempfile::Builder::new()
+            .prefix(&format!("{}-", &manifest.digest[..16]))
+            .tempfile_in(self.staging_dir.path())?;
+        staged.write_all(&bytes)?;
+        let (_, path) = staged.keep().map_err(|e| anyhow!("Failed to stage artifact: {}", e))?;
+        Ok(path)
+    }
+    
+    /// `dlopen` the library into this process
+    async fn load_in_process(&self, path: &str) -> Result<StrategyBackend> {
         // Load library with timeout
         let library = tokio::time::timeout(
             Duration::from_millis(self.safety_config.max_load_time_ms),
             tokio::task::spawn_blocking({
-                let path = artifact.binary_path.clone();
+                let path = path.to_string();
                 move || unsafe { Library::new(&path) }
             })
         ).await
@@ -259,80 +527,48 @@ impl StrategyHotLoader {
         .map_err(|e| anyhow!("Failed to load library: {}", e))?;
         
         // Extract strategy VTable
-        let vtable = self.extract_vtable(&library)?;
-        
-        // Perform initial health check
-        let health_status = self.perform_health_check(&vtable)?;
+        let vtable = extract_vtable(&library)?;
         
-        // Create strategy container
-        let container = StrategyContainer {
-            strategy_id: artifact.strategy_id.clone(),
-            agent_id: agent_id.to_string(),
+        Ok(StrategyBackend::InProcess {
             library: Arc::new(library),
             vtable,
-            loaded_at: chrono::Utc::now(),
-            metrics: StrategyMetrics::default(),
-            health_status,
+        })
+    }
+    
+    /// Start a strategy host process for the library
+    async fn load_isolated(&self, path: &str) -> Result<StrategyBackend> {
+        let launcher = match &self.host_launcher {
+            Some(launcher) => launcher.clone(),
+            None => match &self.safety_config.host_executable {
+                Some(executable) => Arc::new(ProcessLauncher::new(executable)) as Arc<dyn HostLauncher>,
+                None => Arc::new(ProcessLauncher::sibling()?),
+            },
         };
         
-        let load_time = start_time.elapsed().as_millis() as u64;
-        self.update_load_stats(load_time, true);
+        let config = self.safety_config.clone();
+        let path = PathBuf::from(path);
+        let strategy = tokio::task::spawn_blocking(move || IsolatedStrategy::spawn(&path, launcher, &config))
+            .await
+            .map_err(|e| anyhow!("Failed to spawn load task: {}", e))??;
         
-        debug!("✅ Strategy loaded successfully in {}ms", load_time);
-        Ok(container)
-    }
-    
-    /// Extract VTable from loaded library
-    fn extract_vtable(&self, library: &Library) -> Result<StrategyVTable> {
-        unsafe {
-            // Load required symbols
-            let analyze: Symbol<unsafe extern "C" fn(*const MarketData) -> f64> = 
-                library.get(b"strategy_analyze")
-                    .map_err(|e| anyhow!("Failed to load analyze function: {}", e))?;
-            
-            let execute: Symbol<unsafe extern "C" fn(*mut HftContext) -> i32> = 
-                library.get(b"strategy_execute")
-                    .map_err(|e| anyhow!("Failed to load execute function: {}", e))?;
-            
-            let cleanup: Symbol<unsafe extern "C" fn()> = 
-                library.get(b"strategy_cleanup")
-                    .map_err(|e| anyhow!("Failed to load cleanup function: {}", e))?;
-            
-            let get_info: Symbol<unsafe extern "C" fn() -> *const StrategyInfo> = 
-                library.get(b"strategy_get_info")
-                    .map_err(|e| anyhow!("Failed to load get_info function: {}", e))?;
-            
-            let health_check: Symbol<unsafe extern "C" fn() -> i32> = 
-                library.get(b"strategy_health_check")
-                    .map_err(|e| anyhow!("Failed to load health_check function: {}", e))?;
-            
-            Ok(StrategyVTable {
-                analyze: *analyze,
-                execute: *execute,
-                cleanup: *cleanup,
-                get_info: *get_info,
-                health_check: *health_check,
-            })
-        }
+        Ok(StrategyBackend::Isolated(Arc::new(strategy)))
     }
     
     /// Perform health check on strategy
-    fn perform_health_check(&self, vtable: &StrategyVTable) -> Result<HealthStatus> {
-        unsafe {
-            let health_code = (vtable.health_check)();
-            
-            match health_code {
-                0 => Ok(HealthStatus::Healthy),
-                1 => Ok(HealthStatus::Warning { 
-                    message: "Strategy reported warning status".to_string() 
-                }),
-                2 => Ok(HealthStatus::Critical { 
-                    message: "Strategy reported critical status".to_string() 
-                }),
-                _ => Ok(HealthStatus::Failed { 
-                    error: format!("Strategy health check failed with code: {}", health_code) 
-                }),
-            }
+    fn perform_health_check(&self, container: &StrategyContainer) -> Result<HealthStatus> {
+        let health_code = container.health_check()?;
+        
+        match health_code {
+            0 => Ok(HealthStatus::Healthy),
+            1 => Ok(HealthStatus::Warning { 
+                message: "Strategy reported warning status".to_string() 
+            }),
+            2 => Ok(HealthStatus::Critical { 
+                message: "Strategy reported critical status".to_string() 
+            }),
+            _ => Ok(HealthStatus::Failed { 
+                error: format!("Strategy health check failed with code: {}", health_code) 
+            }),
         }
     }
     
@@ -349,16 +585,11 @@ impl StrategyHotLoader {
         {
             let mut strategies = self.strategies.write().unwrap();
 
-            // Cleanup old strategy if exists
-            if let Some(old_container) = strategies.get(agent_id) {
+            // Insert new strategy and unload the old one if it exists
+            if let Some(old_container) = strategies.insert(agent_id.to_string(), new_container.clone()) {
                 debug!("🧹 Cleaning up old strategy: {}", old_container.strategy_id);
-                unsafe {
-                    (old_container.vtable.cleanup)();
-                }
+                unload(&old_container);
             }
-
-            // Insert new strategy
-            strategies.insert(agent_id.to_string(), new_container.clone());
         }
 
         // Update swap statistics
@@ -369,76 +600,191 @@ impl StrategyHotLoader {
         Ok(new_container)
     }
     
-    /// Calculate performance improvement
-    async fn calculate_performance_improvement(
+    /// Put a candidate in shadow next to the incumbent, replacing any
+    /// rollout still in progress
+    fn start_rollout(
         &self,
         agent_id: &str,
-        old_strategy_id: Option<&str>,
-        new_strategy_id: &str,
-    ) -> Result<f64> {
-        // Mock implementation - in production would use TensorZero observability data
-        match old_strategy_id {
-            Some(_) => {
-                // Simulate performance improvement calculation
-                let improvement = 0.05 + (rand::random::<f64>() * 0.1); // 5-15% improvement
-                Ok(improvement)
+        incumbent_strategy_id: &str,
+        candidate: StrategyContainer,
+        config: RolloutConfig,
+    ) -> RolloutStage {
+        let rollout = Rollout::new(agent_id, incumbent_strategy_id, &candidate.strategy_id, config);
+        let stage = rollout.stage().clone();
+        
+        let mut rollouts = self.rollouts.write().unwrap();
+        let staged = StagedCandidate { container: candidate, rollout };
+        if let Some(mut previous) = rollouts.insert(agent_id.to_string(), staged) {
+            if previous.rollout.abort("superseded by a newer candidate").is_some() {
+                debug!("🧹 Cleaning up superseded candidate: {}", previous.container.strategy_id);
+                unload(&previous.container);
+            }
+        }
+        
+        stage
+    }
+    
+    /// Candidate still rolling out for the agent, with its capital fraction
+    fn live_candidate(&self, agent_id: &str) -> Option<(StrategyContainer, f64)> {
+        let rollouts = self.rollouts.read().unwrap();
+        rollouts.get(agent_id)
+            .filter(|staged| staged.rollout.stage().is_live())
+            .map(|staged| (staged.container.clone(), staged.rollout.capital_fraction()))
+    }
+    
+    /// Run the candidate on the incumbent's market data and record both decisions
+    fn shadow_analysis(&self, agent_id: &str, market_data: &MarketData, incumbent_signal: f64) {
+        let candidate = match self.live_candidate(agent_id) {
+            Some((candidate, _)) => candidate,
+            None => return,
+        };
+        
+        let candidate_signal = candidate.analyze(market_data).map_err(|e| e.to_string());
+        let transition = {
+            let mut rollouts = self.rollouts.write().unwrap();
+            rollouts.get_mut(agent_id)
+                .and_then(|staged| staged.rollout.observe(market_data, incumbent_signal, candidate_signal))
+        };
+        
+        if let Some(stage) = transition {
+            self.apply_transition(agent_id, stage);
+        }
+    }
+    
+    /// Act on a rollout stage change: promote, unload or just log
+    fn apply_transition(&self, agent_id: &str, stage: RolloutStage) {
+        let candidate = {
+            let rollouts = self.rollouts.read().unwrap();
+            match rollouts.get(agent_id) {
+                Some(staged) => staged.container.clone(),
+                None => return,
+            }
+        };
+        
+        match stage {
+            RolloutStage::Shadow => {}
+            RolloutStage::Canary { capital_fraction } => {
+                info!("🐤 Strategy {} promoted to canary for agent {} with {:.0}% of capital",
+                      candidate.strategy_id, agent_id, capital_fraction * 100.0);
+            }
+            RolloutStage::Active => {
+                let mut strategies = self.strategies.write().unwrap();
+                if let Some(old_container) = strategies.insert(agent_id.to_string(), candidate.clone()) {
+                    debug!("🧹 Cleaning up old strategy: {}", old_container.strategy_id);
+                    unload(&old_container);
+                }
+                info!("🏆 Strategy {} beat the incumbent and is now active for agent {}",
+                      candidate.strategy_id, agent_id);
             }
-            None => {
-                // First deployment, no comparison possible
-                Ok(0.0)
+            RolloutStage::RolledBack { reason } => {
+                warn!("⏪ Rolling back strategy {} for agent {}: {}", candidate.strategy_id, agent_id, reason);
+                unload(&candidate);
             }
         }
     }
     
-    /// Execute strategy analysis
+    /// Current or last rollout for the agent
+    pub fn rollout_report(&self, agent_id: &str) -> Option<RolloutReport> {
+        let rollouts = self.rollouts.read().unwrap();
+        rollouts.get(agent_id).map(|staged| staged.rollout.report())
+    }
+    
+    /// Incumbent vs candidate decisions differing by at least `min_delta`,
+    /// plus every decision the candidate failed to make
+    pub fn decision_diffs(&self, agent_id: &str, min_delta: f64) -> Vec<DecisionDiff> {
+        let rollouts = self.rollouts.read().unwrap();
+        rollouts.get(agent_id)
+            .map(|staged| staged.rollout.diffs(min_delta))
+            .unwrap_or_default()
+    }
+    
+    /// Roll the agent's candidate back by hand
+    pub fn abort_rollout(&self, agent_id: &str, reason: &str) -> Result<()> {
+        let stage = {
+            let mut rollouts = self.rollouts.write().unwrap();
+            let staged = rollouts.get_mut(agent_id)
+                .ok_or_else(|| anyhow!("No rollout for agent: {}", agent_id))?;
+            staged.rollout.abort(reason)
+                .ok_or_else(|| anyhow!("Rollout for agent {} already finished", agent_id))?
+        };
+        
+        self.apply_transition(agent_id, stage);
+        Ok(())
+    }
+    
+    /// Execute strategy analysis; a candidate in rollout sees the same data
     pub async fn execute_analysis(
         &self,
         agent_id: &str,
         market_data: &MarketData,
     ) -> Result<f64> {
-        let strategies = self.strategies.read().unwrap();
-        
-        let container = strategies.get(agent_id)
-            .ok_or_else(|| anyhow!("No strategy loaded for agent: {}", agent_id))?;
-        
-        // Check health status
-        match &container.health_status {
-            HealthStatus::Failed { error } => {
-                return Err(anyhow!("Strategy is in failed state: {}", error));
-            }
-            HealthStatus::Critical { message } => {
-                warn!("Strategy in critical state: {}", message);
+        let signal_strength = {
+            let strategies = self.strategies.read().unwrap();
+            
+            let container = strategies.get(agent_id)
+                .ok_or_else(|| anyhow!("No strategy loaded for agent: {}", agent_id))?;
+            
+            // Check health status
+            match &container.health_status {
+                HealthStatus::Failed { error } => {
+                    return Err(anyhow!("Strategy is in failed state: {}", error));
+                }
+                HealthStatus::Critical { message } => {
+                    warn!("Strategy in critical state: {}", message);
+                }
+                _ => {}
             }
-            _ => {}
-        }
-        
-        // Execute analysis
-        let signal_strength = unsafe {
-            (container.vtable.analyze)(market_data as *const MarketData)
+            
+            // Execute analysis
+            container.analyze(market_data)?
         };
         
         debug!("📊 Strategy analysis for agent {}: signal_strength = {:.3}", 
                agent_id, signal_strength);
         
+        // Shadow decisions are recorded, never returned
+        self.shadow_analysis(agent_id, market_data, signal_strength);
+        
         Ok(signal_strength)
     }
     
-    /// Execute trading strategy
+    /// Execute trading strategy; a canary candidate trades its capital fraction
     pub async fn execute_trading(
         &self,
         agent_id: &str,
         context: &mut HftContext,
     ) -> Result<i32> {
-        let strategies = self.strategies.read().unwrap();
+        let canary = self.live_candidate(agent_id)
+            .filter(|(_, capital_fraction)| *capital_fraction > 0.0);
         
-        let container = strategies.get(agent_id)
-            .ok_or_else(|| anyhow!("No strategy loaded for agent: {}", agent_id))?;
-        
-        // Execute trading logic
-        let result = unsafe {
-            (container.vtable.execute)(context as *mut HftContext)
+        let (result, canary_result) = {
+            let strategies = self.strategies.read().unwrap();
+            
+            let container = strategies.get(agent_id)
+                .ok_or_else(|| anyhow!("No strategy loaded for agent: {}", agent_id))?;
+            
+            // Execute trading logic
+            match &canary {
+                Some((candidate, capital_fraction)) => {
+                    let (result, canary_result) = execute_split(container, candidate, *capital_fraction, context);
+                    (result, Some(canary_result))
+                }
+                None => (container.execute(context), None),
+            }
         };
         
+        if let Some(Err(e)) = canary_result {
+            warn!("⚠️ Canary strategy for agent {} failed to execute: {}", agent_id, e);
+            let transition = {
+                let mut rollouts = self.rollouts.write().unwrap();
+                rollouts.get_mut(agent_id).and_then(|staged| staged.rollout.record_error())
+            };
+            if let Some(stage) = transition {
+                self.apply_transition(agent_id, stage);
+            }
+        }
+        let result = result?;
+        
         debug!("⚡ Strategy execution for agent {}: result = {}", agent_id, result);
         
         Ok(result)
@@ -451,19 +797,8 @@ impl StrategyHotLoader {
         let container = strategies.get(agent_id)
             .ok_or_else(|| anyhow!("No strategy loaded for agent: {}", agent_id))?;
         
-        unsafe {
-            let info_ptr = (container.vtable.get_info)();
-            if info_ptr.is_null() {
-                return Err(anyhow!("Strategy returned null info"));
-            }
-            
-            let info = &*info_ptr;
-            let name = CStr::from_ptr(info.name).to_string_lossy();
-            let version = CStr::from_ptr(info.version).to_string_lossy();
-            let description = CStr::from_ptr(info.description).to_string_lossy();
-            
-            Ok(format!("Strategy: {} v{} - {}", name, version, description))
-        }
+        let details = container.details()?;
+        Ok(format!("Strategy: {} v{} - {}", details.name, details.version, details.description))
     }
     
     /// Update load statistics
@@ -526,9 +861,14 @@ impl StrategyHotLoader {
         
         for (agent_id, container) in strategies.iter() {
             debug!("Cleaning up strategy for agent: {}", agent_id);
-            unsafe {
-                (container.vtable.cleanup)();
-            }
+            container.cleanup();
+        }
+        
+        // Promoted candidates are in `strategies`, rolled back ones already cleaned up
+        let rollouts = self.rollouts.read().unwrap();
+        for (agent_id, staged) in rollouts.iter().filter(|(_, staged)| staged.rollout.stage().is_live()) {
+            debug!("Cleaning up candidate strategy for agent: {}", agent_id);
+            staged.container.cleanup();
         }
         
         info!("✅ All strategies cleaned up");
@@ -536,6 +876,107 @@ impl StrategyHotLoader {
     }
 }
 
+/// Split one execution between incumbent and canary by capital fraction;
+/// both see the same context scaled to their share, and their changes to
+/// position and balance are summed back into `context`
+fn execute_split(
+    incumbent: &StrategyContainer,
+    candidate: &StrategyContainer,
+    capital_fraction: f64,
+    context: &mut HftContext,
+) -> (Result<i32>, Result<i32>) {
+    let max_position_size = context.max_position_size;
+    let mut canary_context = HftContext {
+        agent_id: context.agent_id,
+        position_size: context.position_size * capital_fraction,
+        available_balance: context.available_balance * capital_fraction,
+        max_position_size: max_position_size * capital_fraction,
+        risk_limit: context.risk_limit,
+        execution_callback: context.execution_callback,
+    };
+    context.position_size -= canary_context.position_size;
+    context.available_balance -= canary_context.available_balance;
+    context.max_position_size -= canary_context.max_position_size;
+    
+    let incumbent_result = incumbent.execute(context);
+    let candidate_result = candidate.execute(&mut canary_context);
+    
+    context.position_size += canary_context.position_size;
+    context.available_balance += canary_context.available_balance;
+    context.max_position_size = max_position_size;
+    
+    (incumbent_result, candidate_result)
+}
+
+/// Release a strategy that was replaced or rolled back and delete its
+/// verified copy; the mapped library or running host is unaffected
+fn unload(container: &StrategyContainer) {
+    container.cleanup();
+    if let Some(path) = &container.staged_path {
+        if let Err(e) = std::fs::remove_file(path) {
+            warn!("Failed to remove staged copy {}: {}", path.display(), e);
+        }
+    }
+}
+
+/// Staging directory only this user can read, write or list
+fn private_staging_dir() -> Result<tempfile::TempDir> {
+    let dir = tempfile::Builder::new().prefix("forge-verified-").tempdir()?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dir.path(), std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(dir)
+}
+
+/// Extract VTable from loaded library, checking the ABI version before
+/// any other symbol is touched
+pub fn extract_vtable(library: &Library) -> Result<StrategyVTable> {
+    unsafe {
+        let abi_version: Symbol<unsafe extern "C" fn() -> u32> = 
+            library.get(b"strategy_abi_version")
+                .map_err(|_| anyhow!("Strategy does not export strategy_abi_version (built for an older FORGE?)"))?;
+        
+        let version = abi_version();
+        if version != STRATEGY_ABI_VERSION {
+            return Err(anyhow!(
+                "Strategy ABI version {} is not supported (expected {})",
+                version, STRATEGY_ABI_VERSION
+            ));
+        }
+        
+        // Load required symbols
+        let analyze: Symbol<unsafe extern "C" fn(*const MarketData) -> f64> = 
+            library.get(b"strategy_analyze")
+                .map_err(|e| anyhow!("Failed to load analyze function: {}", e))?;
+        
+        let execute: Symbol<unsafe extern "C" fn(*mut HftContext) -> i32> = 
+            library.get(b"strategy_execute")
+                .map_err(|e| anyhow!("Failed to load execute function: {}", e))?;
+        
+        let cleanup: Symbol<unsafe extern "C" fn()> = 
+            library.get(b"strategy_cleanup")
+                .map_err(|e| anyhow!("Failed to load cleanup function: {}", e))?;
+        
+        let get_info: Symbol<unsafe extern "C" fn() -> *const StrategyInfo> = 
+            library.get(b"strategy_get_info")
+                .map_err(|e| anyhow!("Failed to load get_info function: {}", e))?;
+        
+        let health_check: Symbol<unsafe extern "C" fn() -> i32> = 
+            library.get(b"strategy_health_check")
+                .map_err(|e| anyhow!("Failed to load health_check function: {}", e))?;
+        
+        Ok(StrategyVTable {
+            analyze: *analyze,
+            execute: *execute,
+            cleanup: *cleanup,
+            get_info: *get_info,
+            health_check: *health_check,
+        })
+    }
+}
+
 impl Drop for StrategyHotLoader {
     fn drop(&mut self) {
         // Cleanup all strategies on drop
@@ -548,6 +989,7 @@ impl Drop for StrategyHotLoader {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use super::super::strategy_host::{serve, HostConnection, StrategyCalls};
     
     #[test]
     fn test_hot_loader_creation() {
@@ -562,11 +1004,273 @@ mod tests {
         assert_eq!(std::mem::size_of::<MarketData>(), 8 * 8); // 8 f64/u64 fields
     }
     
+    #[tokio::test]
+    async fn test_registry_verification_runs_before_library_load() {
+        use super::super::artifact_registry::{ArtifactProvenance, MemoryStore};
+        
+        let dir = tempfile::TempDir::new().unwrap();
+        let registry = Arc::new(
+            ArtifactRegistry::new(Arc::new(MemoryStore::new()))
+                .with_signing_key(ed25519_dalek::SigningKey::from_bytes(&[3; 32]))
+        );
+        let provenance = ArtifactProvenance {
+            dsl_hash: "abc".to_string(),
+            compiler_version: "0.1.0".to_string(),
+            rustc_version: "rustc 1.80.0".to_string(),
+            target: "x86_64-unknown-linux-gnu".to_string(),
+            optimization_level: "3".to_string(),
+        };
+        let manifest = registry.publish("agent", "s", b"signed bytes", provenance).await.unwrap();
+        
+        let path = dir.path().join("strategy.so");
+        let artifact = CompiledArtifact {
+            strategy_id: "s".to_string(),
+            binary_path: path.to_string_lossy().to_string(),
+            checksum: manifest.digest.clone(),
+            compilation_time: Duration::ZERO,
+            optimization_level: "3".to_string(),
+        };
+        let mut loader = StrategyHotLoader::new().unwrap().with_registry(registry);
+        
+        // Tampered file never reaches Library::new
+        std::fs::write(&path, b"evil bytes").unwrap();
+        let err = loader.deploy_strategy("agent", &artifact).await.unwrap_err().to_string();
+        assert!(err.contains("Refusing to load strategy s"), "{}", err);
+        assert_eq!(loader.stats.failed_loads, 1);
+        
+        // Verified file gets as far as the dynamic loader (which rejects non-ELF)
+        std::fs::write(&path, b"signed bytes").unwrap();
+        let err = loader.deploy_strategy("agent", &artifact).await.unwrap_err().to_string();
+        assert!(err.contains("Failed to load library"), "{}", err);
+        
+        // ...loaded from a copy in the private staging directory, not from `path`
+        let staged: Vec<_> = std::fs::read_dir(loader.staging_dir.path()).unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(staged.len(), 1);
+        assert_eq!(std::fs::read(&staged[0]).unwrap(), b"signed bytes");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(loader.staging_dir.path()).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+    }
+    
+    /// Constant signal; `NaN` crashes the host
+    struct FixedSignal(f64);
+    
+    impl StrategyCalls for FixedSignal {
+        fn analyze(&self, _market_data: &MarketData) -> f64 {
+            if self.0.is_nan() {
+                panic!("simulated crash");
+            }
+            self.0
+        }
+        
+        fn execute(&self, context: &mut HftContext) -> i32 {
+            context.available_balance -= context.position_size;
+            context.available_balance as i32
+        }
+        
+        fn health_check(&self) -> i32 {
+            0
+        }
+        
+        fn details(&self) -> Option<StrategyDetails> {
+            None
+        }
+        
+        fn cleanup(&self) {}
+    }
+    
+    /// Hosts `FixedSignal` in a thread, the signal being the library's file stem
+    #[derive(Debug)]
+    struct SignalLauncher;
+    
+    impl HostLauncher for SignalLauncher {
+        fn launch(&self, library: &Path, _timeout: Duration) -> Result<HostConnection> {
+            let signal: f64 = library.file_stem().unwrap().to_string_lossy().parse()?;
+            let (parent, host) = std::os::unix::net::UnixStream::pair()?;
+            std::thread::spawn(move || serve(host, &FixedSignal(signal)));
+            Ok(HostConnection::new(parent, None))
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_staged_rollout_promotes_and_rolls_back() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let artifact = |signal: &str| {
+            let path = dir.path().join(format!("{}.so", signal));
+            std::fs::write(&path, b"").unwrap();
+            CompiledArtifact {
+                strategy_id: format!("s{}", signal),
+                binary_path: path.to_string_lossy().to_string(),
+                checksum: String::new(),
+                compilation_time: Duration::ZERO,
+                optimization_level: "3".to_string(),
+            }
+        };
+        let market = |t: u64| MarketData {
+            timestamp: t,
+            price: 100.0 + t as f64,
+            volume: 0.0,
+            bid: 0.0,
+            ask: 0.0,
+            momentum_signal: 0.0,
+            volatility: 0.0,
+            liquidity_score: 0.0,
+        };
+        let mut loader = StrategyHotLoader::new().unwrap()
+            .with_safety_config(SafetyConfig {
+                isolation: IsolationMode::ChildProcess,
+                max_restarts: 0,
+                ..SafetyConfig::default()
+            })
+            .with_host_launcher(Arc::new(SignalLauncher))
+            .with_rollout(RolloutConfig {
+                shadow_decisions: 3,
+                canary_capital_fraction: 0.25,
+                evaluation_window: 3,
+                min_calls_for_error_rate: 2,
+                ..RolloutConfig::default()
+            });
+        
+        // Nothing to compare the first strategy against
+        let first = loader.deploy_strategy("agent", &artifact("0")).await.unwrap();
+        assert_eq!(first.rollout_stage, None);
+        
+        let staged = loader.deploy_strategy("agent", &artifact("1")).await.unwrap();
+        assert_eq!(staged.rollout_stage, Some(RolloutStage::Shadow));
+        assert_eq!(loader.get_loaded_strategies()["agent"], "s0");
+        
+        // Shadow signals are recorded but the incumbent's are returned
+        for t in 0..3 {
+            assert_eq!(loader.execute_analysis("agent", &market(t)).await.unwrap(), 0.0);
+        }
+        assert_eq!(
+            loader.rollout_report("agent").unwrap().stage,
+            RolloutStage::Canary { capital_fraction: 0.25 }
+        );
+        assert_eq!(loader.decision_diffs("agent", 0.5).len(), 3);
+        
+        // Canary trades a quarter of the capital, the context comes back summed
+        let mut context = HftContext {
+            agent_id: std::ptr::null(),
+            position_size: 8.0,
+            available_balance: 100.0,
+            max_position_size: 10.0,
+            risk_limit: 0.1,
+            execution_callback: None,
+        };
+        assert_eq!(loader.execute_trading("agent", &mut context).await.unwrap(), 69);
+        assert_eq!(context.available_balance, 92.0);
+        assert_eq!(context.max_position_size, 10.0);
+        
+        // Long candidate beats the flat incumbent in a rising market
+        for t in 3..6 {
+            loader.execute_analysis("agent", &market(t)).await.unwrap();
+        }
+        let report = loader.rollout_report("agent").unwrap();
+        assert_eq!(report.stage, RolloutStage::Active);
+        assert!(report.performance_improvement.unwrap() > 0.0);
+        assert_eq!(loader.get_loaded_strategies()["agent"], "s1");
+        
+        // A crashing candidate is rolled back and the incumbent keeps trading
+        loader.deploy_strategy("agent", &artifact("NaN")).await.unwrap();
+        for t in 6..8 {
+            assert_eq!(loader.execute_analysis("agent", &market(t)).await.unwrap(), 1.0);
+        }
+        let report = loader.rollout_report("agent").unwrap();
+        assert!(matches!(report.stage, RolloutStage::RolledBack { .. }), "{:?}", report.stage);
+        assert_eq!(report.candidate.errors, 2);
+        assert!(loader.decision_diffs("agent", 10.0).iter().all(|d| d.candidate_error.is_some()));
+        assert_eq!(loader.get_loaded_strategies()["agent"], "s1");
+        assert!(loader.abort_rollout("agent", "operator").is_err());
+    }
+    
+    /// Hosts a flat `FixedSignal` whatever the library is called
+    #[derive(Debug)]
+    struct FlatLauncher;
+    
+    impl HostLauncher for FlatLauncher {
+        fn launch(&self, _library: &Path, _timeout: Duration) -> Result<HostConnection> {
+            let (parent, host) = std::os::unix::net::UnixStream::pair()?;
+            std::thread::spawn(move || serve(host, &FixedSignal(0.0)));
+            Ok(HostConnection::new(parent, None))
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_staged_copies_are_deleted_on_swap_and_rollback() {
+        use super::super::artifact_registry::{ArtifactProvenance, MemoryStore};
+        
+        let dir = tempfile::TempDir::new().unwrap();
+        let registry = Arc::new(
+            ArtifactRegistry::new(Arc::new(MemoryStore::new()))
+                .with_signing_key(ed25519_dalek::SigningKey::from_bytes(&[3; 32]))
+        );
+        let mut artifacts = Vec::new();
+        for version in ["v1", "v2", "v3"] {
+            let provenance = ArtifactProvenance {
+                dsl_hash: version.to_string(),
+                compiler_version: "0.1.0".to_string(),
+                rustc_version: "rustc 1.80.0".to_string(),
+                target: "x86_64-unknown-linux-gnu".to_string(),
+                optimization_level: "3".to_string(),
+            };
+            let manifest = registry.publish("agent", version, version.as_bytes(), provenance).await.unwrap();
+            let path = dir.path().join(format!("{}.so", version));
+            std::fs::write(&path, version).unwrap();
+            artifacts.push(CompiledArtifact {
+                strategy_id: version.to_string(),
+                binary_path: path.to_string_lossy().to_string(),
+                checksum: manifest.digest,
+                compilation_time: Duration::ZERO,
+                optimization_level: "3".to_string(),
+            });
+        }
+        let mut loader = StrategyHotLoader::new().unwrap()
+            .with_safety_config(SafetyConfig {
+                isolation: IsolationMode::ChildProcess,
+                max_restarts: 0,
+                ..SafetyConfig::default()
+            })
+            .with_host_launcher(Arc::new(FlatLauncher))
+            .with_registry(registry);
+        let staged = |loader: &StrategyHotLoader| -> Vec<Vec<u8>> {
+            std::fs::read_dir(loader.staging_dir.path()).unwrap()
+                .map(|entry| std::fs::read(entry.unwrap().path()).unwrap())
+                .collect()
+        };
+        
+        // Swapping v2 in deletes v1's copy
+        loader.deploy_strategy("agent", &artifacts[0]).await.unwrap();
+        loader.deploy_strategy("agent", &artifacts[1]).await.unwrap();
+        assert_eq!(staged(&loader), [b"v2".to_vec()]);
+        
+        // A rolled back candidate's copy goes with it
+        loader = loader.with_rollout(RolloutConfig::default());
+        loader.deploy_strategy("agent", &artifacts[2]).await.unwrap();
+        assert_eq!(staged(&loader).len(), 2);
+        loader.abort_rollout("agent", "operator").unwrap();
+        assert_eq!(staged(&loader), [b"v2".to_vec()]);
+    }
+    
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_libraries_without_abi_version_are_rejected() {
+        let library = unsafe { Library::new("libc.so.6") }.unwrap();
+        let err = extract_vtable(&library).unwrap_err().to_string();
+        assert!(err.contains("does not export strategy_abi_version"), "{}", err);
+    }
+    
     #[test]
     fn test_safety_config_defaults() {
         let config = SafetyConfig::default();
         assert_eq!(config.max_load_time_ms, 5000);
         assert_eq!(config.max_consecutive_failures, 3);
         assert!(config.enable_sandbox);
+        assert_eq!(config.isolation, IsolationMode::InProcess);
     }
 }
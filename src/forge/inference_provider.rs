@@ -0,0 +1,371 @@
+//! INFERENCE PROVIDERS - Online, Recorded, Replayed & Local
+//!
+//! Źródła odpowiedzi dla TensorZeroGateway: serwer HTTP, nagrywanie wymian
+//! do storage, deterministyczny replay po hashu requestu i lokalne szablony.
+//! Dzięki temu cała pętla ewolucji działa w CI i na maszynach bez sieci.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+use super::artifact_registry::ArtifactStore;
+use super::dsl_generator::{StrategyTemplates, StrategyType};
+use super::tensorzero_gateway::{
+    ContentBlock, InferenceInput, InferenceRequest, InferenceResponse, TensorZeroConfig, Usage,
+};
+
+/// Where the gateway gets its answers
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum ProviderMode {
+    /// TensorZero HTTP gateway
+    #[default]
+    Http,
+
+    /// HTTP gateway, saving every exchange under `dir`
+    Record { dir: PathBuf },
+
+    /// Exchanges recorded under `dir`, looked up by request hash
+    Replay { dir: PathBuf },
+
+    /// `StrategyTemplates` served locally, no network at all
+    Template,
+}
+
+/// Answers inference requests for `TensorZeroGateway`
+#[async_trait]
+pub trait InferenceProvider: Send + Sync + fmt::Debug {
+    async fn infer(&self, request: &InferenceRequest) -> Result<InferenceResponse>;
+
+    /// Offline providers answer deterministically, so retrying is pointless
+    fn is_offline(&self) -> bool {
+        false
+    }
+}
+
+/// Hash identifying a request for replay: model, function and input only,
+/// so tags and episode IDs don't break lookups
+pub fn request_hash(request: &InferenceRequest) -> String {
+    #[derive(Serialize)]
+    struct Key<'a> {
+        model_name: &'a str,
+        function_name: &'a Option<String>,
+        input: &'a InferenceInput,
+    }
+
+    let key = Key {
+        model_name: &request.model_name,
+        function_name: &request.function_name,
+        input: &request.input,
+    };
+    let bytes = serde_json::to_vec(&key).expect("inference request serializes");
+    hex::encode(Sha256::digest(&bytes))
+}
+
+/// Request/response pair as stored by `RecordingProvider`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedExchange {
+    pub request_hash: String,
+    pub recorded_at: chrono::DateTime<chrono::Utc>,
+    pub request: InferenceRequest,
+    pub response: InferenceResponse,
+}
+
+fn exchange_key(hash: &str) -> String {
+    format!("inferences/{}.json", hash)
+}
+
+/// TensorZero gateway over HTTP
+#[derive(Debug, Clone)]
+pub struct HttpProvider {
+    gateway_url: String,
+    http_client: Client,
+    timeout: Duration,
+}
+
+impl HttpProvider {
+    /// Create the client and check the gateway's health endpoint
+    pub async fn connect(config: &TensorZeroConfig) -> Result<Self> {
+        let timeout = Duration::from_secs(config.timeout_seconds);
+        let http_client = Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| anyhow!("Failed to create HTTP client: {}", e))?;
+
+        // Test connection to TensorZero Gateway
+        let health_url = format!("{}/health", config.gateway_url);
+        match http_client.get(&health_url).send().await {
+            Ok(response) if response.status().is_success() => {
+                info!("✅ TensorZero Gateway connection successful");
+            }
+            Ok(response) => {
+                warn!(
+                    "⚠️ TensorZero Gateway responded with status: {}",
+                    response.status()
+                );
+            }
+            Err(e) => {
+                error!("❌ Failed to connect to TensorZero Gateway: {}", e);
+                return Err(anyhow!("TensorZero Gateway connection failed: {}", e));
+            }
+        }
+
+        Ok(Self {
+            gateway_url: config.gateway_url.clone(),
+            http_client,
+            timeout,
+        })
+    }
+}
+
+#[async_trait]
+impl InferenceProvider for HttpProvider {
+    async fn infer(&self, request: &InferenceRequest) -> Result<InferenceResponse> {
+        let url = format!("{}/inference", self.gateway_url);
+
+        let response = tokio::time::timeout(
+            self.timeout,
+            self.http_client.post(&url).json(request).send(),
+        )
+        .await
+        .map_err(|_| anyhow!("Request timeout"))?
+        .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow!("TensorZero API error {}: {}", status, error_text));
+        }
+
+        response
+            .json()
+            .await
+            .map_err(|e| anyhow!("Failed to parse response: {}", e))
+    }
+}
+
+/// Passes requests through and stores every successful exchange
+#[derive(Debug)]
+pub struct RecordingProvider {
+    inner: Arc<dyn InferenceProvider>,
+    store: Arc<dyn ArtifactStore>,
+}
+
+impl RecordingProvider {
+    pub fn new(inner: Arc<dyn InferenceProvider>, store: Arc<dyn ArtifactStore>) -> Self {
+        Self { inner, store }
+    }
+}
+
+#[async_trait]
+impl InferenceProvider for RecordingProvider {
+    async fn infer(&self, request: &InferenceRequest) -> Result<InferenceResponse> {
+        let response = self.inner.infer(request).await?;
+
+        let exchange = RecordedExchange {
+            request_hash: request_hash(request),
+            recorded_at: chrono::Utc::now(),
+            request: request.clone(),
+            response: response.clone(),
+        };
+        self.store
+            .put(
+                &exchange_key(&exchange.request_hash),
+                &serde_json::to_vec_pretty(&exchange)?,
+            )
+            .await?;
+        debug!("📼 Recorded inference {}", exchange.request_hash);
+
+        Ok(response)
+    }
+}
+
+/// Serves recorded exchanges; requests never recorded fail
+#[derive(Debug)]
+pub struct ReplayProvider {
+    store: Arc<dyn ArtifactStore>,
+}
+
+impl ReplayProvider {
+    pub fn new(store: Arc<dyn ArtifactStore>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl InferenceProvider for ReplayProvider {
+    async fn infer(&self, request: &InferenceRequest) -> Result<InferenceResponse> {
+        let hash = request_hash(request);
+        let bytes = self.store.get(&exchange_key(&hash)).await?.ok_or_else(|| {
+            anyhow!(
+                "No recorded response for request {} (model {}, function {})",
+                hash,
+                request.model_name,
+                request.function_name.as_deref().unwrap_or("-")
+            )
+        })?;
+        let exchange: RecordedExchange = serde_json::from_slice(&bytes)
+            .map_err(|e| anyhow!("Corrupt recording for request {}: {}", hash, e))?;
+
+        debug!("📼 Replayed inference {}", hash);
+        Ok(exchange.response)
+    }
+
+    fn is_offline(&self) -> bool {
+        true
+    }
+}
+
+/// Answers with the template for the strategy type named in the prompt
+/// (`STRATEGY TYPE: Momentum`), momentum if none is named
+#[derive(Debug, Clone, Default)]
+pub struct TemplateProvider {
+    templates: StrategyTemplates,
+}
+
+impl TemplateProvider {
+    pub fn new(templates: StrategyTemplates) -> Self {
+        Self { templates }
+    }
+}
+
+/// Strategy type from a `STRATEGY TYPE: <name>` prompt line
+fn requested_strategy_type(prompt: &str) -> Option<StrategyType> {
+    let name = prompt
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("STRATEGY TYPE:"))?
+        .trim();
+    StrategyType::ALL
+        .into_iter()
+        .find(|strategy_type| format!("{:?}", strategy_type) == name)
+}
+
+#[async_trait]
+impl InferenceProvider for TemplateProvider {
+    async fn infer(&self, request: &InferenceRequest) -> Result<InferenceResponse> {
+        let strategy_type = request
+            .input
+            .messages
+            .iter()
+            .find_map(|message| requested_strategy_type(&message.content))
+            .unwrap_or(StrategyType::Momentum);
+
+        Ok(InferenceResponse {
+            inference_id: format!("template-{}", &request_hash(request)[..16]),
+            episode_id: request.episode_id.clone(),
+            variant_name: "template".to_string(),
+            content: vec![ContentBlock {
+                content_type: "text".to_string(),
+                text: Some(self.templates.for_type(strategy_type).to_string()),
+                tool_use: None,
+            }],
+            usage: Usage {
+                input_tokens: 0,
+                output_tokens: 0,
+                total_tokens: 0,
+            },
+            model_name: request.model_name.clone(),
+            model_provider_name: "template".to_string(),
+        })
+    }
+
+    fn is_offline(&self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::artifact_registry::MemoryStore;
+    use super::super::tensorzero_gateway::Message;
+    use super::*;
+    use std::collections::HashMap;
+
+    fn request(prompt: &str) -> InferenceRequest {
+        InferenceRequest {
+            model_name: "openai::gpt-4o".to_string(),
+            input: InferenceInput {
+                messages: vec![Message {
+                    role: "user".to_string(),
+                    content: prompt.to_string(),
+                }],
+                system: Some("system".to_string()),
+                temperature: Some(0.7),
+                max_tokens: Some(4000),
+                tools: None,
+            },
+            stream: Some(false),
+            function_name: Some("strategy_generation".to_string()),
+            episode_id: None,
+            tags: None,
+        }
+    }
+
+    #[test]
+    fn test_request_hash_ignores_tags_and_episode() {
+        let plain = request("STRATEGY TYPE: Breakout");
+        let tagged = InferenceRequest {
+            episode_id: Some("episode".to_string()),
+            tags: Some(HashMap::from([("k".to_string(), "v".to_string())])),
+            ..plain.clone()
+        };
+        assert_eq!(request_hash(&plain), request_hash(&tagged));
+        assert_ne!(
+            request_hash(&plain),
+            request_hash(&request("STRATEGY TYPE: Arbitrage"))
+        );
+        assert_eq!(request_hash(&plain).len(), 64);
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let store: Arc<dyn ArtifactStore> = Arc::new(MemoryStore::new());
+        let templates = StrategyTemplates::default();
+        let recorder = RecordingProvider::new(Arc::new(TemplateProvider::default()), store.clone());
+        let replay = ReplayProvider::new(store.clone());
+
+        let breakout = request("Analysis...\nSTRATEGY TYPE: Breakout\nGenerate");
+        let recorded = recorder.infer(&breakout).await.unwrap();
+        assert_eq!(
+            recorded.content[0].text.as_deref(),
+            Some(templates.breakout_template.as_str())
+        );
+        assert_eq!(store.list("inferences/").await.unwrap().len(), 1);
+
+        let replayed = replay.infer(&breakout).await.unwrap();
+        assert_eq!(replayed.inference_id, recorded.inference_id);
+        assert_eq!(replayed.content[0].text, recorded.content[0].text);
+        assert!(replay.is_offline() && !recorder.is_offline());
+
+        let err = replay
+            .infer(&request("STRATEGY TYPE: Arbitrage"))
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.starts_with("No recorded response for request"),
+            "{}",
+            err
+        );
+
+        // No type named: momentum
+        let unnamed = TemplateProvider::default()
+            .infer(&request("anything"))
+            .await
+            .unwrap();
+        assert_eq!(
+            unnamed.content[0].text.as_deref(),
+            Some(templates.momentum_template.as_str())
+        );
+    }
+}
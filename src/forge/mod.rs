@@ -4,17 +4,23 @@
 //! Integracja TensorZero jako rdzenia procesu ewolucyjnego
 
 pub mod tensorzero_gateway;
+pub mod inference_provider;
 pub mod dsl_generator;
 pub mod strategy_compiler;
+pub mod artifact_registry;
 pub mod hot_loader;
+pub mod rollout;
+pub mod strategy_host;
 pub mod autonomous_evolution;
+pub mod walk_forward;
 pub mod formal_verification;
+pub mod dsl;
 // pub mod forge_orchestrator; // TODO: Implement later
 
 use anyhow::Result;
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tracing::info;
+use tracing::{info, warn};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -22,6 +28,7 @@ use tensorzero_gateway::{TensorZeroGateway, TensorZeroConfig};
 use dsl_generator::{StrategyDSLGenerator, StrategyDSL};
 use strategy_compiler::{StrategyCompiler, CompilerConfig};
 use hot_loader::{StrategyHotLoader, StrategyContainer};
+use rollout::{RolloutConfig, RolloutStage};
 use autonomous_evolution::AutonomousEvolutionEngine;
 use formal_verification::FormalVerificationEngine;
 
@@ -105,6 +112,10 @@ pub struct SafetyParams {
     
     /// Circuit breaker threshold
     pub circuit_breaker_threshold: f64,
+    
+    /// Load strategies without signature checks when no signing key is configured
+    #[serde(default)]
+    pub allow_unsigned_strategies: bool,
 }
 
 /// Metryki FORGE
@@ -150,6 +161,7 @@ impl Default for ForgeConfig {
                 testing_period_hours: 24,
                 min_successful_trades: 10,
                 circuit_breaker_threshold: 0.15, // 15% loss triggers circuit breaker
+                allow_unsigned_strategies: false,
             },
         }
     }
@@ -176,7 +188,20 @@ impl TheForge {
         
         // Initialize Hot Loader
         info!("🔄 Initializing Strategy Hot Loader...");
-        let hot_loader = StrategyHotLoader::new()?;
+        let mut hot_loader = StrategyHotLoader::new()?.with_rollout(RolloutConfig {
+            max_drawdown: config.safety_params.max_loss_threshold,
+            ..RolloutConfig::default()
+        });
+        let registry = strategy_compiler.registry();
+        if registry.has_trusted_keys() {
+            hot_loader = hot_loader.with_registry(registry);
+        } else if config.safety_params.allow_unsigned_strategies {
+            warn!("⚠️ No artifact signing key configured - loading UNSIGNED strategies (allow_unsigned_strategies)");
+        } else {
+            return Err(anyhow::anyhow!(
+                "No artifact signing key configured; set compiler_config.artifact_storage.signing_key_path or opt in with safety_params.allow_unsigned_strategies"
+            ));
+        }
         
         info!("✅ FORGE initialized successfully");
         
@@ -270,10 +295,16 @@ impl TheForge {
     async fn deploy_strategy(&mut self, agent_id: &str, artifact: &CompiledArtifact) -> Result<DeploymentResult> {
         let result = self.hot_loader.deploy_strategy(agent_id, artifact).await?;
         
-        // Update active strategies
-        {
-            let mut active = self.active_strategies.write().await;
-            active.insert(agent_id.to_string(), result.strategy_container.clone());
+        // Update active strategies; staged candidates only become active
+        // once the rollout promotes them
+        match &result.rollout_stage {
+            Some(stage) => {
+                info!("🌘 Strategy {} for agent {} staged: {:?}", result.new_strategy_id, agent_id, stage);
+            }
+            None => {
+                let mut active = self.active_strategies.write().await;
+                active.insert(agent_id.to_string(), result.strategy_container.clone());
+            }
         }
         
         // Update metrics
@@ -369,11 +400,19 @@ pub struct DeploymentResult {
     pub new_strategy_id: String,
     pub performance_improvement: f64,
     pub strategy_container: StrategyContainer,
+    /// Rollout stage when the strategy was staged instead of swapped in
+    pub rollout_stage: Option<RolloutStage>,
 }
 
 /// Inicjalizuj FORGE z domyślną konfiguracją
+///
+/// Strategies are only loaded signed, so the artifact signing key is read
+/// from `FORGE_SIGNING_KEY_PATH`; without it initialization fails. Use
+/// `init_forge_with_config` to opt in to unsigned strategies instead.
 pub async fn init_forge() -> Result<TheForge> {
-    let config = ForgeConfig::default();
+    let mut config = ForgeConfig::default();
+    config.compiler_config.artifact_storage.signing_key_path =
+        std::env::var("FORGE_SIGNING_KEY_PATH").ok();
     TheForge::new(config).await
 }
 
@@ -0,0 +1,548 @@
+//! STAGED ROLLOUT - Shadow & Canary Deployment
+//!
+//! Nowa strategia nie przejmuje agenta od razu: najpierw działa w cieniu
+//! obok obecnej (te same MarketData, decyzje tylko zapisywane), potem
+//! dostaje część kapitału, a pełną kontrolę tylko gdy na żywych danych
+//! wygrywa z obecną strategią. Błędy albo drawdown → automatyczny rollback.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use super::hot_loader::MarketData;
+
+/// Promotion and rollback thresholds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RolloutConfig {
+    /// Decisions the candidate makes in shadow before it gets capital
+    pub shadow_decisions: u64,
+
+    /// Capital fraction the candidate trades during canary
+    pub canary_capital_fraction: f64,
+
+    /// Canary decisions over which candidate and incumbent are compared
+    pub evaluation_window: u64,
+
+    /// Return margin by which the candidate must beat the incumbent
+    pub min_improvement: f64,
+
+    /// Candidate error rate that triggers rollback
+    pub max_error_rate: f64,
+
+    /// Calls needed before the error rate is acted on
+    pub min_calls_for_error_rate: u64,
+
+    /// Candidate drawdown that triggers rollback
+    pub max_drawdown: f64,
+
+    /// Decision diffs kept for queries
+    pub max_recorded_diffs: usize,
+}
+
+impl Default for RolloutConfig {
+    fn default() -> Self {
+        Self {
+            shadow_decisions: 500,
+            canary_capital_fraction: 0.1,
+            evaluation_window: 1000,
+            min_improvement: 0.0,
+            max_error_rate: 0.05,
+            min_calls_for_error_rate: 20,
+            max_drawdown: 0.05,
+            max_recorded_diffs: 10_000,
+        }
+    }
+}
+
+/// Where a candidate strategy is in its rollout
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RolloutStage {
+    /// Runs on live data, decisions recorded but never executed
+    Shadow,
+
+    /// Trades `capital_fraction` of the agent's capital next to the incumbent
+    Canary { capital_fraction: f64 },
+
+    /// Replaced the incumbent
+    Active,
+
+    /// Unloaded; the incumbent keeps trading
+    RolledBack { reason: String },
+}
+
+impl RolloutStage {
+    /// Candidate still runs next to the incumbent
+    pub fn is_live(&self) -> bool {
+        matches!(self, RolloutStage::Shadow | RolloutStage::Canary { .. })
+    }
+}
+
+/// Live metrics of one strategy during a rollout stage
+///
+/// Each signal is held as a position in [-1, 1] until the next market
+/// update and marked to market, so shadow decisions are scored on the same
+/// prices as executed ones
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveMetrics {
+    pub calls: u64,
+    pub errors: u64,
+    pub equity: f64,
+    pub peak_equity: f64,
+    pub max_drawdown: f64,
+}
+
+impl Default for LiveMetrics {
+    fn default() -> Self {
+        Self {
+            calls: 0,
+            errors: 0,
+            equity: 1.0,
+            peak_equity: 1.0,
+            max_drawdown: 0.0,
+        }
+    }
+}
+
+impl LiveMetrics {
+    /// Return since the stage started
+    pub fn total_return(&self) -> f64 {
+        self.equity - 1.0
+    }
+
+    pub fn error_rate(&self) -> f64 {
+        if self.calls == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.calls as f64
+        }
+    }
+
+    fn mark(&mut self, position: f64, price_return: f64) {
+        self.equity *= 1.0 + position * price_return;
+        self.peak_equity = self.peak_equity.max(self.equity);
+        self.max_drawdown = self.max_drawdown.max(1.0 - self.equity / self.peak_equity);
+    }
+}
+
+/// Incumbent and candidate decisions on the same market update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionDiff {
+    pub recorded_at: DateTime<Utc>,
+    pub market_timestamp: u64,
+    pub price: f64,
+    pub stage: RolloutStage,
+    pub incumbent_signal: f64,
+    pub candidate_signal: Option<f64>,
+    pub candidate_error: Option<String>,
+}
+
+impl DecisionDiff {
+    /// Candidate minus incumbent signal; `None` if the candidate failed
+    pub fn delta(&self) -> Option<f64> {
+        self.candidate_signal
+            .map(|candidate| candidate - self.incumbent_signal)
+    }
+
+    /// Strategies would trade in opposite directions
+    pub fn is_reversal(&self) -> bool {
+        self.candidate_signal
+            .is_some_and(|candidate| candidate * self.incumbent_signal < 0.0)
+    }
+}
+
+/// Snapshot of a rollout for dashboards and the API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RolloutReport {
+    pub agent_id: String,
+    pub incumbent_strategy_id: String,
+    pub candidate_strategy_id: String,
+    pub stage: RolloutStage,
+    pub started_at: DateTime<Utc>,
+    pub stage_started_at: DateTime<Utc>,
+    pub stage_decisions: u64,
+    pub candidate: LiveMetrics,
+    pub incumbent: LiveMetrics,
+    pub reversals: u64,
+    pub performance_improvement: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Positions {
+    price: f64,
+    incumbent: f64,
+    candidate: f64,
+}
+
+/// Rollout state machine: Shadow → Canary → Active, or RolledBack from any
+/// live stage
+#[derive(Debug)]
+pub struct Rollout {
+    agent_id: String,
+    incumbent_strategy_id: String,
+    candidate_strategy_id: String,
+    config: RolloutConfig,
+    stage: RolloutStage,
+    started_at: DateTime<Utc>,
+    stage_started_at: DateTime<Utc>,
+    stage_decisions: u64,
+    candidate: LiveMetrics,
+    incumbent: LiveMetrics,
+    positions: Option<Positions>,
+    diffs: VecDeque<DecisionDiff>,
+    reversals: u64,
+    performance_improvement: Option<f64>,
+}
+
+impl Rollout {
+    /// Start in shadow
+    pub fn new(
+        agent_id: &str,
+        incumbent_strategy_id: &str,
+        candidate_strategy_id: &str,
+        config: RolloutConfig,
+    ) -> Self {
+        let now = Utc::now();
+        Self {
+            agent_id: agent_id.to_string(),
+            incumbent_strategy_id: incumbent_strategy_id.to_string(),
+            candidate_strategy_id: candidate_strategy_id.to_string(),
+            config,
+            stage: RolloutStage::Shadow,
+            started_at: now,
+            stage_started_at: now,
+            stage_decisions: 0,
+            candidate: LiveMetrics::default(),
+            incumbent: LiveMetrics::default(),
+            positions: None,
+            diffs: VecDeque::new(),
+            reversals: 0,
+            performance_improvement: None,
+        }
+    }
+
+    pub fn stage(&self) -> &RolloutStage {
+        &self.stage
+    }
+
+    pub fn candidate_strategy_id(&self) -> &str {
+        &self.candidate_strategy_id
+    }
+
+    /// Capital share the candidate may trade right now
+    pub fn capital_fraction(&self) -> f64 {
+        match self.stage {
+            RolloutStage::Canary { capital_fraction } => capital_fraction,
+            RolloutStage::Active => 1.0,
+            _ => 0.0,
+        }
+    }
+
+    /// Candidate minus incumbent return over the canary window, once decided
+    pub fn performance_improvement(&self) -> Option<f64> {
+        self.performance_improvement
+    }
+
+    /// Record both decisions on one market update; returns the new stage if
+    /// this observation promoted or rolled back the candidate
+    pub fn observe(
+        &mut self,
+        market_data: &MarketData,
+        incumbent_signal: f64,
+        candidate_signal: Result<f64, String>,
+    ) -> Option<RolloutStage> {
+        if !self.stage.is_live() {
+            return None;
+        }
+
+        if let Some(previous) = self.positions {
+            if previous.price > 0.0 && market_data.price > 0.0 {
+                let price_return = market_data.price / previous.price - 1.0;
+                self.incumbent.mark(previous.incumbent, price_return);
+                self.candidate.mark(previous.candidate, price_return);
+            }
+        }
+
+        let candidate_signal = candidate_signal.and_then(|signal| {
+            if signal.is_finite() {
+                Ok(signal)
+            } else {
+                Err(format!("non-finite signal {}", signal))
+            }
+        });
+        self.incumbent.calls += 1;
+        self.candidate.calls += 1;
+        if candidate_signal.is_err() {
+            self.candidate.errors += 1;
+        }
+        self.positions = Some(Positions {
+            price: market_data.price,
+            incumbent: position(incumbent_signal),
+            // A failing candidate is flat until it answers again
+            candidate: candidate_signal.as_ref().map_or(0.0, |s| position(*s)),
+        });
+
+        let diff = DecisionDiff {
+            recorded_at: Utc::now(),
+            market_timestamp: market_data.timestamp,
+            price: market_data.price,
+            stage: self.stage.clone(),
+            incumbent_signal,
+            candidate_signal: candidate_signal.as_ref().ok().copied(),
+            candidate_error: candidate_signal.err(),
+        };
+        if diff.is_reversal() {
+            self.reversals += 1;
+        }
+        if self.diffs.len() >= self.config.max_recorded_diffs {
+            self.diffs.pop_front();
+        }
+        self.diffs.push_back(diff);
+
+        self.stage_decisions += 1;
+        self.evaluate()
+    }
+
+    /// Candidate failed outside of `observe`, e.g. while executing a canary trade
+    pub fn record_error(&mut self) -> Option<RolloutStage> {
+        if !self.stage.is_live() {
+            return None;
+        }
+        self.candidate.calls += 1;
+        self.candidate.errors += 1;
+        self.evaluate()
+    }
+
+    /// Stop the rollout, keeping the incumbent
+    pub fn abort(&mut self, reason: &str) -> Option<RolloutStage> {
+        if !self.stage.is_live() {
+            return None;
+        }
+        self.transition(RolloutStage::RolledBack {
+            reason: reason.to_string(),
+        })
+    }
+
+    /// Recorded diffs with a failed candidate or `|delta| >= min_delta`,
+    /// oldest first
+    pub fn diffs(&self, min_delta: f64) -> Vec<DecisionDiff> {
+        self.diffs
+            .iter()
+            .filter(|diff| diff.delta().is_none_or(|delta| delta.abs() >= min_delta))
+            .cloned()
+            .collect()
+    }
+
+    pub fn report(&self) -> RolloutReport {
+        RolloutReport {
+            agent_id: self.agent_id.clone(),
+            incumbent_strategy_id: self.incumbent_strategy_id.clone(),
+            candidate_strategy_id: self.candidate_strategy_id.clone(),
+            stage: self.stage.clone(),
+            started_at: self.started_at,
+            stage_started_at: self.stage_started_at,
+            stage_decisions: self.stage_decisions,
+            candidate: self.candidate.clone(),
+            incumbent: self.incumbent.clone(),
+            reversals: self.reversals,
+            performance_improvement: self.performance_improvement,
+        }
+    }
+
+    fn evaluate(&mut self) -> Option<RolloutStage> {
+        if let Some(reason) = self.rollback_reason() {
+            return self.transition(RolloutStage::RolledBack { reason });
+        }
+
+        match self.stage {
+            RolloutStage::Shadow if self.stage_decisions >= self.config.shadow_decisions => self
+                .transition(RolloutStage::Canary {
+                    capital_fraction: self.config.canary_capital_fraction,
+                }),
+            RolloutStage::Canary { .. }
+                if self.stage_decisions >= self.config.evaluation_window =>
+            {
+                let improvement = self.candidate.total_return() - self.incumbent.total_return();
+                self.performance_improvement = Some(improvement);
+                if improvement > self.config.min_improvement {
+                    self.transition(RolloutStage::Active)
+                } else {
+                    let reason = format!(
+                        "candidate returned {:.2}% vs incumbent {:.2}% over {} decisions",
+                        self.candidate.total_return() * 100.0,
+                        self.incumbent.total_return() * 100.0,
+                        self.stage_decisions
+                    );
+                    self.transition(RolloutStage::RolledBack { reason })
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn rollback_reason(&self) -> Option<String> {
+        if self.candidate.calls >= self.config.min_calls_for_error_rate
+            && self.candidate.error_rate() > self.config.max_error_rate
+        {
+            return Some(format!(
+                "error rate {:.1}% exceeds {:.1}%",
+                self.candidate.error_rate() * 100.0,
+                self.config.max_error_rate * 100.0
+            ));
+        }
+        if self.candidate.max_drawdown > self.config.max_drawdown {
+            return Some(format!(
+                "drawdown {:.2}% exceeds {:.2}%",
+                self.candidate.max_drawdown * 100.0,
+                self.config.max_drawdown * 100.0
+            ));
+        }
+        None
+    }
+
+    fn transition(&mut self, stage: RolloutStage) -> Option<RolloutStage> {
+        self.stage = stage.clone();
+        self.stage_started_at = Utc::now();
+        // Promotion is judged on the new stage alone; finished rollouts
+        // keep their final metrics for the report
+        if matches!(self.stage, RolloutStage::Canary { .. }) {
+            self.stage_decisions = 0;
+            self.candidate = LiveMetrics::default();
+            self.incumbent = LiveMetrics::default();
+        }
+        Some(stage)
+    }
+}
+
+fn position(signal: f64) -> f64 {
+    if signal.is_finite() {
+        signal.clamp(-1.0, 1.0)
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn market(timestamp: u64, price: f64) -> MarketData {
+        MarketData {
+            timestamp,
+            price,
+            volume: 0.0,
+            bid: price,
+            ask: price,
+            momentum_signal: 0.0,
+            volatility: 0.0,
+            liquidity_score: 1.0,
+        }
+    }
+
+    fn config() -> RolloutConfig {
+        RolloutConfig {
+            shadow_decisions: 5,
+            canary_capital_fraction: 0.2,
+            evaluation_window: 10,
+            min_calls_for_error_rate: 4,
+            max_drawdown: 0.1,
+            ..RolloutConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_candidate_beating_incumbent_is_promoted() {
+        let mut rollout = Rollout::new("agent", "old", "new", config());
+        let mut transitions = Vec::new();
+
+        // Rising market: the long candidate beats the flat incumbent
+        for t in 0..15 {
+            let price = 100.0 * (1.0 + 0.001 * t as f64);
+            if let Some(stage) = rollout.observe(&market(t, price), 0.0, Ok(0.8)) {
+                transitions.push((t, stage));
+            }
+        }
+
+        assert_eq!(
+            transitions,
+            vec![
+                (
+                    4,
+                    RolloutStage::Canary {
+                        capital_fraction: 0.2
+                    }
+                ),
+                (14, RolloutStage::Active),
+            ]
+        );
+        assert_eq!(rollout.capital_fraction(), 1.0);
+        let improvement = rollout.performance_improvement().unwrap();
+        assert!(
+            improvement > 0.007 && improvement < 0.009,
+            "{}",
+            improvement
+        );
+
+        // Finished rollouts ignore further data
+        assert_eq!(rollout.observe(&market(15, 200.0), 0.0, Ok(1.0)), None);
+        assert_eq!(rollout.report().stage_decisions, 10);
+    }
+
+    #[test]
+    fn test_errors_and_drawdown_roll_back() {
+        let mut failing = Rollout::new("agent", "old", "new", config());
+        let mut stage = None;
+        for t in 0..4 {
+            let candidate = if t % 2 == 0 {
+                Err("host crashed".to_string())
+            } else {
+                Ok(f64::NAN)
+            };
+            stage = failing.observe(&market(t, 100.0), 0.5, candidate);
+        }
+        assert_eq!(
+            stage,
+            Some(RolloutStage::RolledBack {
+                reason: "error rate 100.0% exceeds 5.0%".to_string()
+            })
+        );
+
+        let mut losing = Rollout::new("agent", "old", "new", config());
+        losing.observe(&market(0, 100.0), 0.0, Ok(1.0));
+        let stage = losing.observe(&market(1, 85.0), 0.0, Ok(1.0));
+        assert_eq!(
+            stage,
+            Some(RolloutStage::RolledBack {
+                reason: "drawdown 15.00% exceeds 10.00%".to_string()
+            })
+        );
+        assert_eq!(losing.capital_fraction(), 0.0);
+        assert_eq!(losing.abort("operator"), None);
+    }
+
+    #[test]
+    fn test_decision_diffs_are_queryable() {
+        let mut rollout = Rollout::new(
+            "agent",
+            "old",
+            "new",
+            RolloutConfig {
+                max_recorded_diffs: 3,
+                min_calls_for_error_rate: 100,
+                ..config()
+            },
+        );
+        rollout.observe(&market(1, 100.0), 0.5, Ok(0.5));
+        rollout.observe(&market(2, 100.0), 0.5, Ok(-0.3));
+        rollout.observe(&market(3, 100.0), 0.5, Err("timeout".to_string()));
+        rollout.observe(&market(4, 100.0), 0.5, Ok(0.6));
+
+        // Oldest diff dropped, errors always match
+        let diffs = rollout.diffs(0.5);
+        let timestamps: Vec<u64> = diffs.iter().map(|d| d.market_timestamp).collect();
+        assert_eq!(timestamps, vec![2, 3]);
+        assert!(diffs[0].is_reversal());
+        assert_eq!(diffs[1].candidate_error.as_deref(), Some("timeout"));
+        assert_eq!(rollout.diffs(0.0).len(), 3);
+        assert_eq!(rollout.report().reversals, 1);
+    }
+}
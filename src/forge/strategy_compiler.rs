@@ -8,12 +8,16 @@ use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::fs;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tracing::{info, warn, error};
+use tracing::{debug, info, warn, error};
 use sha2::{Sha256, Digest};
 use tempfile::TempDir;
 
+use super::artifact_registry::{self, ArtifactProvenance, ArtifactRegistry};
+use super::dsl::{DslStrategy, FeatureSchema};
 use super::dsl_generator::StrategyDSL;
+use super::hot_loader::STRATEGY_ABI_VERSION;
 use super::CompiledArtifact;
 
 /// Strategy Compiler configuration
@@ -56,6 +60,10 @@ pub struct ArtifactStorageConfig {
     pub access_key: Option<String>,
     pub secret_key: Option<String>,
     pub local_path: Option<String>,
+    
+    /// Hex Ed25519 secret key file; artifacts are published unsigned without it
+    #[serde(default)]
+    pub signing_key_path: Option<String>,
 }
 
 /// Storage type enumeration
@@ -81,6 +89,7 @@ impl Default for CompilerConfig {
                 access_key: None,
                 secret_key: None,
                 local_path: Some("./artifacts".to_string()),
+                signing_key_path: None,
             },
             timeout_seconds: 300, // 5 minutes
             debug_symbols: false,
@@ -105,6 +114,15 @@ pub struct StrategyCompiler {
     
     /// Template directory
     template_dir: PathBuf,
+    
+    /// Features DSL expressions may reference
+    feature_schema: FeatureSchema,
+    
+    /// Signed, content-addressed artifact storage (also the build cache)
+    registry: Arc<ArtifactRegistry>,
+    
+    /// `rustc --version` recorded in artifact provenance
+    rustc_version: String,
 }
 
 /// Compilation statistics
@@ -143,34 +161,100 @@ impl StrategyCompiler {
             .arg("--version")
             .output();
         
-        match compiler_check {
+        let rustc_version = match compiler_check {
             Ok(output) if output.status.success() => {
-                let version = String::from_utf8_lossy(&output.stdout);
-                info!("✅ Compiler found: {}", version.trim());
+                let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                info!("✅ Compiler found: {}", version);
+                version
             }
             Ok(_) => {
                 warn!("⚠️ Compiler found but version check failed");
+                "unknown".to_string()
             }
             Err(e) => {
                 error!("❌ Compiler not found: {}", e);
                 return Err(anyhow!("Compiler not accessible: {}", e));
             }
-        }
+        };
         
         let template_dir = PathBuf::from("src/forge/templates");
+        let registry = Arc::new(Self::open_registry(&config)?);
         
         Ok(Self {
             config,
             stats: CompilerStats::default(),
             template_dir,
+            feature_schema: FeatureSchema::standard(),
+            registry,
+            rustc_version,
         })
     }
     
+    /// Registry for the configured storage, signing if a key is configured
+    fn open_registry(config: &CompilerConfig) -> Result<ArtifactRegistry> {
+        let storage = &config.artifact_storage;
+        let root = match storage.storage_type {
+            StorageType::Local => storage.local_path.clone().unwrap_or_else(|| config.output_dir.clone()),
+            _ => {
+                warn!("Storage type not yet implemented: {:?}, using local registry", storage.storage_type);
+                config.output_dir.clone()
+            }
+        };
+        
+        let mut registry = ArtifactRegistry::local(Path::new(&root).join("registry"))?;
+        if let Some(key_path) = &storage.signing_key_path {
+            registry = registry.with_signing_key(artifact_registry::load_signing_key(key_path)?);
+            info!("🔏 Signing strategy artifacts with key from {}", key_path);
+        }
+        Ok(registry)
+    }
+    
+    /// Replace the artifact registry, e.g. to share one with the hot loader
+    pub fn with_registry(mut self, registry: Arc<ArtifactRegistry>) -> Self {
+        self.registry = registry;
+        self
+    }
+    
+    /// Artifact registry backing storage and the compilation cache
+    pub fn registry(&self) -> Arc<ArtifactRegistry> {
+        self.registry.clone()
+    }
+    
+    /// Replace the feature schema used to check DSL expressions
+    pub fn with_feature_schema(mut self, feature_schema: FeatureSchema) -> Self {
+        self.feature_schema = feature_schema;
+        self
+    }
+    
+    /// Load strategy DSL for in-process execution, skipping the cargo build.
+    /// Unknown identifiers and ill-typed triggers are reported here.
+    pub fn load_interpreted(&self, dsl: &StrategyDSL) -> Result<DslStrategy> {
+        let ast = dsl.ast()
+            .map_err(|e| anyhow!("Strategy {} failed to parse: {}", dsl.name, e))?;
+        
+        let strategy = DslStrategy::load(&ast, &self.feature_schema).map_err(|diagnostics| {
+            anyhow!(
+                "Strategy {} failed to load:\n{}",
+                dsl.name,
+                super::dsl::render_diagnostics(&diagnostics)
+            )
+        })?;
+        
+        info!("🧮 Loaded interpreted strategy: {} ({} rules)", dsl.name, strategy.rules().len());
+        Ok(strategy)
+    }
+    
     /// Compile strategy DSL to native library
     pub async fn compile(&mut self, dsl: &StrategyDSL, agent_id: &str) -> Result<CompiledArtifact> {
         let start_time = Instant::now();
         info!("🔨 Compiling strategy: {} for agent: {}", dsl.name, agent_id);
         
+        // Reject DSL that does not parse before spending a cargo build on it
+        if let Err(e) = dsl.ast() {
+            self.stats.failed_compilations += 1;
+            return Err(anyhow!("Strategy {} failed to parse: {}", dsl.name, e));
+        }
+        
         // Check compilation cache first
         if let Some(cached_artifact) = self.check_compilation_cache(dsl).await? {
             info!("💾 Using cached compilation for strategy: {}", dsl.name);
@@ -211,7 +295,7 @@ impl StrategyCompiler {
             .ok_or_else(|| anyhow!("Compilation succeeded but no artifact produced"))?;
         
         // Store artifact
-        self.store_artifact(&artifact).await?;
+        self.store_artifact(&artifact, dsl, agent_id).await?;
         
         // Update statistics
         let compilation_time = start_time.elapsed().as_millis() as u64;
@@ -221,25 +305,39 @@ impl StrategyCompiler {
         Ok(artifact)
     }
     
-    /// Check compilation cache
+    /// Check compilation cache: an artifact in the registry built from the
+    /// same DSL with the same toolchain, target and optimization level
     async fn check_compilation_cache(&self, dsl: &StrategyDSL) -> Result<Option<CompiledArtifact>> {
-        // Calculate DSL hash for cache key
-        let dsl_hash = self.calculate_dsl_hash(dsl);
-        
-        // Check if artifact exists in storage
-        let cache_path = format!("{}/cache/{}.so", self.config.output_dir, dsl_hash);
-        
-        if Path::new(&cache_path).exists() {
-            // Load cached artifact metadata
-            let metadata_path = format!("{}/cache/{}.json", self.config.output_dir, dsl_hash);
-            if let Ok(metadata_content) = fs::read_to_string(&metadata_path) {
-                if let Ok(artifact) = serde_json::from_str::<CompiledArtifact>(&metadata_content) {
-                    return Ok(Some(artifact));
-                }
-            }
+        let Some(manifest) = self.registry.lookup(&self.provenance(dsl)).await? else {
+            return Ok(None);
+        };
+        
+        let artifact_name = format!("{}_{}.so", dsl.strategy_id, &manifest.digest[..8]);
+        let path = Path::new(&self.config.output_dir).join(artifact_name);
+        if let Err(e) = self.registry.materialize(&manifest.digest, &path).await {
+            warn!("⚠️ Ignoring cached artifact {}: {}", manifest.digest, e);
+            return Ok(None);
         }
         
-        Ok(None)
+        Ok(Some(CompiledArtifact {
+            strategy_id: dsl.strategy_id.clone(),
+            binary_path: path.to_string_lossy().to_string(),
+            checksum: manifest.digest,
+            compilation_time: Duration::ZERO,
+            optimization_level: manifest.provenance.optimization_level,
+        }))
+    }
+    
+    /// Build inputs recorded in the artifact manifest
+    fn provenance(&self, dsl: &StrategyDSL) -> ArtifactProvenance {
+        ArtifactProvenance {
+            dsl_hash: self.calculate_dsl_hash(dsl),
+            // Cached builds for another strategy ABI must not be reused
+            compiler_version: format!("{}+abi{}", env!("CARGO_PKG_VERSION"), STRATEGY_ABI_VERSION),
+            rustc_version: self.rustc_version.clone(),
+            target: self.config.target_arch.clone(),
+            optimization_level: self.config.optimization_level.clone(),
+        }
     }
     
     /// Generate Rust source code from DSL
@@ -251,6 +349,7 @@ impl StrategyCompiler {
             .replace("{{STRATEGY_NAME}}", &dsl.name)
             .replace("{{STRATEGY_ID}}", &dsl.strategy_id)
             .replace("{{AGENT_ID}}", agent_id)
+            .replace("{{ABI_VERSION}}", &STRATEGY_ABI_VERSION.to_string())
             .replace("{{MAX_DRAWDOWN}}", &dsl.risk_model.max_drawdown.to_string())
             .replace("{{DAILY_LOSS_LIMIT}}", &dsl.risk_model.daily_loss_limit.to_string())
             .replace("{{POSITION_SIZE}}", &dsl.risk_model.position_size.to_string())
@@ -494,23 +593,17 @@ rustflags = [
             .collect()
     }
     
-    /// Store artifact in configured storage
-    async fn store_artifact(&self, artifact: &CompiledArtifact) -> Result<()> {
-        match self.config.artifact_storage.storage_type {
-            StorageType::Local => {
-                // Already stored locally during compilation
-                Ok(())
-            }
-            StorageType::S3 => {
-                // TODO: Implement S3 storage
-                warn!("S3 storage not yet implemented");
-                Ok(())
-            }
-            _ => {
-                warn!("Storage type not yet implemented: {:?}", self.config.artifact_storage.storage_type);
-                Ok(())
-            }
-        }
+    /// Publish artifact to the registry, signed, under the agent's history
+    async fn store_artifact(&self, artifact: &CompiledArtifact, dsl: &StrategyDSL, agent_id: &str) -> Result<()> {
+        let bytes = fs::read(&artifact.binary_path)
+            .map_err(|e| anyhow!("Failed to read artifact {}: {}", artifact.binary_path, e))?;
+        
+        let manifest = self.registry
+            .publish(agent_id, &artifact.strategy_id, &bytes, self.provenance(dsl))
+            .await?;
+        
+        debug!("📦 Stored artifact {} (signed: {})", manifest.digest, manifest.is_signed());
+        Ok(())
     }
     
     /// Calculate DSL hash for caching
@@ -568,6 +661,106 @@ mod tests {
         assert!(rust_code.contains("&&"));
     }
     
+    #[tokio::test]
+    async fn test_compile_rejects_unparseable_dsl() {
+        let output_dir = TempDir::new().unwrap();
+        let mut compiler = StrategyCompiler::new(CompilerConfig {
+            output_dir: output_dir.path().to_string_lossy().to_string(),
+            ..CompilerConfig::default()
+        }).unwrap();
+        
+        let dsl = StrategyDSL {
+            strategy_id: "broken".to_string(),
+            name: "Broken".to_string(),
+            source_code: "strategy Broken:\n  risk_model:\n    stop_loss: [2%\n".to_string(),
+            risk_model: super::super::dsl_generator::RiskModel {
+                max_drawdown: 0.05,
+                daily_loss_limit: 0.02,
+                position_size: 0.1,
+                stop_loss: None,
+                take_profit: None,
+                max_positions: None,
+            },
+            entry_logic: vec![],
+            exit_logic: vec![],
+            ai_models: vec![],
+            metadata: super::super::dsl_generator::GenerationMetadata {
+                generated_at: chrono::Utc::now(),
+                generator_version: "1.0.0".to_string(),
+                parent_strategy_id: None,
+                generation_method: super::super::dsl_generator::GenerationMethod::FromScratch,
+                performance_target: 0.0,
+                complexity_score: 1,
+            },
+        };
+        
+        let err = compiler.compile(&dsl, "agent").await.unwrap_err();
+        assert!(err.to_string().contains("unclosed `[`"), "{}", err);
+        assert_eq!(compiler.get_stats().failed_compilations, 1);
+    }
+    
+    #[test]
+    fn test_load_interpreted_reports_unknown_identifiers() {
+        let source = "strategy Typo:\n  entry_logic:\n    - trigger: \"momentum_signl > 0.7\"\n      action: market_buy\n".to_string();
+        let dsl = StrategyDSL::from_source(source, super::super::dsl_generator::GenerationMetadata {
+            generated_at: chrono::Utc::now(),
+            generator_version: "1.0.0".to_string(),
+            parent_strategy_id: None,
+            generation_method: super::super::dsl_generator::GenerationMethod::FromScratch,
+            performance_target: 0.0,
+            complexity_score: 1,
+        }).unwrap();
+        
+        let compiler = StrategyCompiler::new(CompilerConfig::default()).unwrap();
+        let err = compiler.load_interpreted(&dsl).unwrap_err().to_string();
+        assert!(err.contains("3:17: error: unknown identifier `momentum_signl`; did you mean `momentum_signal`?"), "{}", err);
+        
+        let compiler = compiler.with_feature_schema(FeatureSchema::new().with_number("momentum_signl"));
+        let strategy = compiler.load_interpreted(&dsl).unwrap();
+        assert_eq!(strategy.required_features(), ["momentum_signl".to_string()]);
+    }
+    
+    #[tokio::test]
+    async fn test_compilation_cache_is_served_from_registry() {
+        let output_dir = TempDir::new().unwrap();
+        let output_path = output_dir.path().to_string_lossy().to_string();
+        let config = CompilerConfig {
+            output_dir: output_path.clone(),
+            artifact_storage: ArtifactStorageConfig {
+                local_path: Some(output_path),
+                ..CompilerConfig::default().artifact_storage
+            },
+            ..CompilerConfig::default()
+        };
+        let compiler = StrategyCompiler::new(config.clone()).unwrap();
+        
+        let source = "strategy Cached:\n  entry_logic:\n    - trigger: \"momentum_signal > 0.7\"\n      action: market_buy\n".to_string();
+        let dsl = StrategyDSL::from_source(source, super::super::dsl_generator::GenerationMetadata {
+            generated_at: chrono::Utc::now(),
+            generator_version: "1.0.0".to_string(),
+            parent_strategy_id: None,
+            generation_method: super::super::dsl_generator::GenerationMethod::FromScratch,
+            performance_target: 0.0,
+            complexity_score: 1,
+        }).unwrap();
+        assert!(compiler.check_compilation_cache(&dsl).await.unwrap().is_none());
+        
+        let manifest = compiler.registry()
+            .publish("agent", &dsl.strategy_id, b"\x7fELF cached", compiler.provenance(&dsl))
+            .await
+            .unwrap();
+        let artifact = compiler.check_compilation_cache(&dsl).await.unwrap().unwrap();
+        assert_eq!(artifact.checksum, manifest.digest);
+        assert_eq!(fs::read(&artifact.binary_path).unwrap(), b"\x7fELF cached");
+        
+        // A different optimization level is a different build
+        let compiler = StrategyCompiler::new(CompilerConfig {
+            optimization_level: "2".to_string(),
+            ..config
+        }).unwrap();
+        assert!(compiler.check_compilation_cache(&dsl).await.unwrap().is_none());
+    }
+    
     #[test]
     fn test_action_conversion() {
         let compiler = StrategyCompiler::new(CompilerConfig::default()).unwrap();
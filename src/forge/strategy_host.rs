@@ -0,0 +1,780 @@
+//! STRATEGY HOST - Out-of-Process Strategy Isolation
+//!
+//! Każda strategia w osobnym procesie `forge-strategy-host`: panic albo
+//! segfault w wygenerowanym kodzie zabija tylko hosta, nie bota
+//! Kompaktowy protokół IPC po Unix socket, timeouty wywołań, restart po crashu
+//!
+//! Protocol: every frame is a little-endian `u32` length followed by a
+//! bincode-encoded [`Request`] (parent → host) or [`Response`] (host → parent).
+//! The host answers each request with exactly one final response; while
+//! `strategy_execute` runs, every call the strategy makes to its
+//! `execution_callback` is forwarded to the parent as [`Response::Callback`]
+//! and blocks until the parent replies with [`Request::CallbackResult`], so
+//! the strategy sees the callback's return value exactly as in-process.
+
+use anyhow::{anyhow, Result};
+use libloading::Library;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::cell::RefCell;
+use std::ffi::CString;
+use std::fmt;
+use std::io::{ErrorKind, Read, Write};
+use std::os::raw::c_char;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
+
+use super::hot_loader::{
+    extract_vtable, HftContext, MarketData, SafetyConfig, StrategyDetails, StrategyVTable,
+    STRATEGY_ABI_VERSION,
+};
+
+/// Frames larger than this are a protocol error, not an allocation
+const MAX_FRAME_BYTES: usize = 1 << 20;
+
+/// Parent → host
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum Request {
+    Analyze(MarketData),
+    Execute(ContextState),
+    /// Return value of a forwarded `execution_callback` call
+    CallbackResult(i32),
+    HealthCheck,
+    Info,
+    Cleanup,
+}
+
+/// Host → parent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum Response {
+    /// Library loaded and its ABI version checked
+    Ready {
+        abi_version: u32,
+    },
+    Signal(f64),
+    /// Strategy called `execution_callback(agent_id, position_size, price)`
+    Callback {
+        position_size: f64,
+        price: f64,
+    },
+    /// `strategy_execute` returned; `context` carries fields it may have changed
+    Executed {
+        code: i32,
+        context: ContextState,
+    },
+    Health(i32),
+    Info(StrategyDetails),
+    CleanedUp,
+    Error(String),
+}
+
+/// `HftContext` without its pointers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct ContextState {
+    agent_id: String,
+    position_size: f64,
+    available_balance: f64,
+    max_position_size: f64,
+    risk_limit: f64,
+    has_callback: bool,
+}
+
+impl ContextState {
+    /// # Safety
+    /// `context.agent_id` must be null or a valid C string
+    unsafe fn capture(context: &HftContext) -> Self {
+        let agent_id = if context.agent_id.is_null() {
+            String::new()
+        } else {
+            std::ffi::CStr::from_ptr(context.agent_id)
+                .to_string_lossy()
+                .to_string()
+        };
+        Self {
+            agent_id,
+            position_size: context.position_size,
+            available_balance: context.available_balance,
+            max_position_size: context.max_position_size,
+            risk_limit: context.risk_limit,
+            has_callback: context.execution_callback.is_some(),
+        }
+    }
+
+    /// Write back what the strategy may have changed; pointers stay
+    fn apply(&self, context: &mut HftContext) {
+        context.position_size = self.position_size;
+        context.available_balance = self.available_balance;
+        context.max_position_size = self.max_position_size;
+        context.risk_limit = self.risk_limit;
+    }
+}
+
+fn write_frame<T: Serialize>(stream: &mut UnixStream, message: &T) -> std::io::Result<()> {
+    let payload =
+        bincode::serialize(message).map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+    let mut frame = Vec::with_capacity(4 + payload.len());
+    frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    frame.extend_from_slice(&payload);
+    stream.write_all(&frame)
+}
+
+fn read_frame<T: DeserializeOwned>(stream: &mut UnixStream) -> std::io::Result<T> {
+    let mut length = [0u8; 4];
+    stream.read_exact(&mut length)?;
+    let length = u32::from_le_bytes(length) as usize;
+    if length > MAX_FRAME_BYTES {
+        return Err(std::io::Error::new(
+            ErrorKind::InvalidData,
+            format!("frame of {} bytes exceeds limit", length),
+        ));
+    }
+    let mut payload = vec![0u8; length];
+    stream.read_exact(&mut payload)?;
+    bincode::deserialize(&payload).map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))
+}
+
+/// Strategy entry points as seen by the host loop
+pub trait StrategyCalls {
+    fn analyze(&self, market_data: &MarketData) -> f64;
+    fn execute(&self, context: &mut HftContext) -> i32;
+    fn health_check(&self) -> i32;
+    fn details(&self) -> Option<StrategyDetails>;
+    fn cleanup(&self);
+}
+
+/// Strategy library loaded inside the host process
+pub struct LoadedStrategy {
+    vtable: StrategyVTable,
+    _library: Library,
+}
+
+impl LoadedStrategy {
+    /// Load a strategy library, checking its ABI version first
+    pub fn load(path: &Path) -> Result<Self> {
+        let library =
+            unsafe { Library::new(path) }.map_err(|e| anyhow!("Failed to load library: {}", e))?;
+        let vtable = extract_vtable(&library)?;
+        Ok(Self {
+            vtable,
+            _library: library,
+        })
+    }
+}
+
+impl StrategyCalls for LoadedStrategy {
+    fn analyze(&self, market_data: &MarketData) -> f64 {
+        unsafe { (self.vtable.analyze)(market_data as *const MarketData) }
+    }
+
+    fn execute(&self, context: &mut HftContext) -> i32 {
+        unsafe { (self.vtable.execute)(context as *mut HftContext) }
+    }
+
+    fn health_check(&self) -> i32 {
+        unsafe { (self.vtable.health_check)() }
+    }
+
+    fn details(&self) -> Option<StrategyDetails> {
+        unsafe { StrategyDetails::from_raw((self.vtable.get_info)()) }
+    }
+
+    fn cleanup(&self) {
+        unsafe { (self.vtable.cleanup)() }
+    }
+}
+
+thread_local! {
+    /// Connection back to the parent while `strategy_execute` runs
+    static CALLBACK_CHANNEL: RefCell<Option<UnixStream>> = const { RefCell::new(None) };
+}
+
+/// `execution_callback` handed to strategies in the host: forwards the call
+/// to the parent and returns the parent's answer (-1 if it is unreachable)
+unsafe extern "C" fn forward_execution(
+    _agent_id: *const c_char,
+    position_size: f64,
+    price: f64,
+) -> i32 {
+    CALLBACK_CHANNEL.with(|channel| {
+        let mut channel = channel.borrow_mut();
+        let Some(stream) = channel.as_mut() else {
+            return -1;
+        };
+        let callback = Response::Callback {
+            position_size,
+            price,
+        };
+        if write_frame(stream, &callback).is_err() {
+            return -1;
+        }
+        match read_frame(stream) {
+            Ok(Request::CallbackResult(code)) => code,
+            _ => -1,
+        }
+    })
+}
+
+/// Host loop: announce readiness, then answer requests until the parent
+/// hangs up
+pub fn serve(mut stream: UnixStream, strategy: &dyn StrategyCalls) -> Result<()> {
+    write_frame(
+        &mut stream,
+        &Response::Ready {
+            abi_version: STRATEGY_ABI_VERSION,
+        },
+    )?;
+
+    loop {
+        let request = match read_frame::<Request>(&mut stream) {
+            Ok(request) => request,
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(()),
+            Err(e) => return Err(anyhow!("Strategy host read failed: {}", e)),
+        };
+
+        let response = match request {
+            Request::Analyze(market_data) => Response::Signal(strategy.analyze(&market_data)),
+            Request::Execute(state) => {
+                let agent_id = CString::new(state.agent_id.clone()).unwrap_or_default();
+                let mut context = HftContext {
+                    agent_id: agent_id.as_ptr(),
+                    position_size: state.position_size,
+                    available_balance: state.available_balance,
+                    max_position_size: state.max_position_size,
+                    risk_limit: state.risk_limit,
+                    execution_callback: if state.has_callback {
+                        Some(forward_execution)
+                    } else {
+                        None
+                    },
+                };
+
+                CALLBACK_CHANNEL.with(|channel| *channel.borrow_mut() = stream.try_clone().ok());
+                let code = strategy.execute(&mut context);
+                CALLBACK_CHANNEL.with(|channel| *channel.borrow_mut() = None);
+
+                Response::Executed {
+                    code,
+                    context: unsafe { ContextState::capture(&context) },
+                }
+            }
+            Request::HealthCheck => Response::Health(strategy.health_check()),
+            Request::Info => match strategy.details() {
+                Some(details) => Response::Info(details),
+                None => Response::Error("Strategy returned null info".to_string()),
+            },
+            Request::Cleanup => {
+                strategy.cleanup();
+                Response::CleanedUp
+            }
+            Request::CallbackResult(_) => {
+                Response::Error("Callback result outside of execute".to_string())
+            }
+        };
+        write_frame(&mut stream, &response)?;
+    }
+}
+
+/// `forge-strategy-host` entry point: connect to the parent, load the
+/// library and serve it
+pub fn run(library: &Path, socket: &Path) -> Result<()> {
+    let mut stream = UnixStream::connect(socket)
+        .map_err(|e| anyhow!("Failed to connect to {}: {}", socket.display(), e))?;
+
+    let strategy = match LoadedStrategy::load(library) {
+        Ok(strategy) => strategy,
+        Err(e) => {
+            let _ = write_frame(&mut stream, &Response::Error(e.to_string()));
+            return Err(e);
+        }
+    };
+    serve(stream, &strategy)
+}
+
+/// Live connection to one strategy host
+#[derive(Debug)]
+pub struct HostConnection {
+    stream: UnixStream,
+    /// Killed when the connection is dropped
+    child: Option<Child>,
+}
+
+impl HostConnection {
+    pub fn new(stream: UnixStream, child: Option<Child>) -> Self {
+        Self { stream, child }
+    }
+
+    /// Send one request and wait for its final response, answering
+    /// forwarded callbacks on the way
+    fn exchange(
+        &mut self,
+        request: &Request,
+        timeout: Duration,
+        on_callback: &mut dyn FnMut(f64, f64) -> i32,
+    ) -> std::io::Result<Response> {
+        self.stream.set_read_timeout(Some(timeout))?;
+        self.stream.set_write_timeout(Some(timeout))?;
+        write_frame(&mut self.stream, request)?;
+        loop {
+            match read_frame(&mut self.stream)? {
+                Response::Callback {
+                    position_size,
+                    price,
+                } => {
+                    let code = on_callback(position_size, price);
+                    write_frame(&mut self.stream, &Request::CallbackResult(code))?;
+                }
+                response => return Ok(response),
+            }
+        }
+    }
+}
+
+impl Drop for HostConnection {
+    fn drop(&mut self) {
+        if let Some(child) = &mut self.child {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Starts a strategy host for a library
+pub trait HostLauncher: Send + Sync + fmt::Debug {
+    fn launch(&self, library: &Path, timeout: Duration) -> Result<HostConnection>;
+}
+
+/// Runs `forge-strategy-host --library <so> --socket <path>`
+#[derive(Debug, Clone)]
+pub struct ProcessLauncher {
+    executable: PathBuf,
+}
+
+impl ProcessLauncher {
+    pub fn new(executable: impl Into<PathBuf>) -> Self {
+        Self {
+            executable: executable.into(),
+        }
+    }
+
+    /// Host binary installed next to the current executable
+    pub fn sibling() -> Result<Self> {
+        let current = std::env::current_exe()
+            .map_err(|e| anyhow!("Cannot locate current executable: {}", e))?;
+        Ok(Self::new(current.with_file_name("forge-strategy-host")))
+    }
+}
+
+impl HostLauncher for ProcessLauncher {
+    fn launch(&self, library: &Path, timeout: Duration) -> Result<HostConnection> {
+        let socket = std::env::temp_dir().join(format!("forge-host-{}.sock", uuid::Uuid::new_v4()));
+        let listener = UnixListener::bind(&socket)
+            .map_err(|e| anyhow!("Failed to bind {}: {}", socket.display(), e))?;
+        listener.set_nonblocking(true)?;
+
+        let spawned = Command::new(&self.executable)
+            .arg("--library")
+            .arg(library)
+            .arg("--socket")
+            .arg(&socket)
+            .stdin(Stdio::null())
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(e) => {
+                let _ = std::fs::remove_file(&socket);
+                return Err(anyhow!(
+                    "Failed to start {}: {}",
+                    self.executable.display(),
+                    e
+                ));
+            }
+        };
+
+        let deadline = Instant::now() + timeout;
+        let accepted = loop {
+            match listener.accept() {
+                Ok((stream, _)) => break Ok(stream),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    if let Ok(Some(status)) = child.try_wait() {
+                        break Err(anyhow!(
+                            "Strategy host exited with {} before connecting",
+                            status
+                        ));
+                    }
+                    if Instant::now() >= deadline {
+                        break Err(anyhow!(
+                            "Strategy host did not connect within {:?}",
+                            timeout
+                        ));
+                    }
+                    std::thread::sleep(Duration::from_millis(5));
+                }
+                Err(e) => break Err(anyhow!("Strategy host accept failed: {}", e)),
+            }
+        };
+        let _ = std::fs::remove_file(&socket);
+
+        match accepted {
+            Ok(stream) => {
+                stream.set_nonblocking(false)?;
+                debug!("🔌 Strategy host pid {} connected", child.id());
+                Ok(HostConnection::new(stream, Some(child)))
+            }
+            Err(e) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                Err(e)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct HostState {
+    connection: Option<HostConnection>,
+    restarts: u32,
+    shut_down: bool,
+}
+
+/// Strategy running in a child process; a crashed or hung host is killed
+/// and restarted on the next call, up to `max_restarts` times
+#[derive(Debug)]
+pub struct IsolatedStrategy {
+    library: PathBuf,
+    launcher: Arc<dyn HostLauncher>,
+    call_timeout: Duration,
+    load_timeout: Duration,
+    max_restarts: u32,
+    state: parking_lot::Mutex<HostState>,
+}
+
+impl IsolatedStrategy {
+    /// Start the host now so load and ABI errors surface at deploy time
+    pub fn spawn(
+        library: &Path,
+        launcher: Arc<dyn HostLauncher>,
+        config: &SafetyConfig,
+    ) -> Result<Self> {
+        let strategy = Self {
+            library: library.to_path_buf(),
+            launcher,
+            call_timeout: Duration::from_millis(config.call_timeout_ms),
+            load_timeout: Duration::from_millis(config.max_load_time_ms),
+            max_restarts: config.max_restarts,
+            state: parking_lot::Mutex::new(HostState::default()),
+        };
+        let connection = strategy.connect()?;
+        strategy.state.lock().connection = Some(connection);
+        info!("🛡️ Strategy {} isolated in host process", library.display());
+        Ok(strategy)
+    }
+
+    /// Times the host has been restarted after a crash or timeout
+    pub fn restarts(&self) -> u32 {
+        self.state.lock().restarts
+    }
+
+    fn connect(&self) -> Result<HostConnection> {
+        let mut connection = self.launcher.launch(&self.library, self.load_timeout)?;
+        connection
+            .stream
+            .set_read_timeout(Some(self.load_timeout))?;
+        match read_frame(&mut connection.stream) {
+            Ok(Response::Ready { abi_version }) if abi_version == STRATEGY_ABI_VERSION => {
+                Ok(connection)
+            }
+            Ok(Response::Ready { abi_version }) => Err(anyhow!(
+                "Strategy host speaks ABI version {}, expected {}",
+                abi_version,
+                STRATEGY_ABI_VERSION
+            )),
+            Ok(Response::Error(message)) => {
+                Err(anyhow!("Strategy host failed to load: {}", message))
+            }
+            Ok(other) => Err(anyhow!("Unexpected strategy host greeting: {:?}", other)),
+            Err(e) => Err(anyhow!("Strategy host did not become ready: {}", e)),
+        }
+    }
+
+    fn call(
+        &self,
+        request: Request,
+        on_callback: &mut dyn FnMut(f64, f64) -> i32,
+    ) -> Result<Response> {
+        let mut state = self.state.lock();
+        if state.shut_down {
+            return Err(anyhow!(
+                "Strategy host for {} is shut down",
+                self.library.display()
+            ));
+        }
+        if state.connection.is_none() {
+            if state.restarts >= self.max_restarts {
+                return Err(anyhow!(
+                    "Strategy host for {} failed {} times; not restarting",
+                    self.library.display(),
+                    state.restarts
+                ));
+            }
+            state.restarts += 1;
+            warn!(
+                "♻️ Restarting strategy host for {} (restart {})",
+                self.library.display(),
+                state.restarts
+            );
+            state.connection = Some(self.connect()?);
+        }
+
+        let connection = state.connection.as_mut().expect("connected above");
+        match connection.exchange(&request, self.call_timeout, on_callback) {
+            Ok(Response::Error(message)) => Err(anyhow!("Strategy host error: {}", message)),
+            Ok(response) => Ok(response),
+            Err(e) => {
+                // Timed out or crashed: the host is in an unknown state, kill it
+                state.connection = None;
+                error!(
+                    "💥 Strategy host for {} failed: {}",
+                    self.library.display(),
+                    e
+                );
+                Err(anyhow!("Strategy host call failed: {}", e))
+            }
+        }
+    }
+
+    pub fn analyze(&self, market_data: &MarketData) -> Result<f64> {
+        match self.call(Request::Analyze(market_data.clone()), &mut |_, _| -1)? {
+            Response::Signal(signal) => Ok(signal),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Same hand-off as in-process: callbacks reach `context.execution_callback`
+    /// with `context.agent_id`, and changed context fields are written back
+    pub fn execute(&self, context: &mut HftContext) -> Result<i32> {
+        let state = unsafe { ContextState::capture(context) };
+        let agent_id = context.agent_id;
+        let callback = context.execution_callback;
+        let mut forward = |position_size: f64, price: f64| match callback {
+            Some(callback) => unsafe { callback(agent_id, position_size, price) },
+            None => -1,
+        };
+
+        match self.call(Request::Execute(state), &mut forward)? {
+            Response::Executed {
+                code,
+                context: state,
+            } => {
+                state.apply(context);
+                Ok(code)
+            }
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub fn health_check(&self) -> Result<i32> {
+        match self.call(Request::HealthCheck, &mut |_, _| -1)? {
+            Response::Health(code) => Ok(code),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    pub fn details(&self) -> Result<StrategyDetails> {
+        match self.call(Request::Info, &mut |_, _| -1)? {
+            Response::Info(details) => Ok(details),
+            other => Err(unexpected(other)),
+        }
+    }
+
+    /// Run the strategy's cleanup and stop the host for good
+    pub fn shutdown(&self) {
+        if let Err(e) = self.call(Request::Cleanup, &mut |_, _| -1) {
+            debug!("Strategy host cleanup failed: {}", e);
+        }
+        let mut state = self.state.lock();
+        state.connection = None;
+        state.shut_down = true;
+    }
+}
+
+fn unexpected(response: Response) -> anyhow::Error {
+    anyhow!("Unexpected strategy host response: {:?}", response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Strategy whose behaviour is steered by the market price
+    struct ScriptedStrategy;
+
+    impl StrategyCalls for ScriptedStrategy {
+        fn analyze(&self, market_data: &MarketData) -> f64 {
+            match market_data.price {
+                p if p < 0.0 => panic!("simulated crash"),
+                p if p > 1e6 => {
+                    std::thread::sleep(Duration::from_millis(500));
+                    0.0
+                }
+                p => p / 100.0,
+            }
+        }
+
+        fn execute(&self, context: &mut HftContext) -> i32 {
+            let filled = match context.execution_callback {
+                Some(callback) => unsafe {
+                    callback(context.agent_id, context.position_size, 101.5)
+                },
+                None => 0,
+            };
+            context.position_size /= 2.0;
+            filled
+        }
+
+        fn health_check(&self) -> i32 {
+            0
+        }
+
+        fn details(&self) -> Option<StrategyDetails> {
+            Some(StrategyDetails {
+                name: "Scripted".to_string(),
+                version: "1.0.0".to_string(),
+                ..StrategyDetails::default()
+            })
+        }
+
+        fn cleanup(&self) {}
+    }
+
+    /// Hosts in threads over socket pairs; a panicking strategy closes its end
+    #[derive(Debug, Default)]
+    struct ThreadLauncher {
+        launches: AtomicU32,
+        greeting: Option<u32>,
+    }
+
+    impl HostLauncher for ThreadLauncher {
+        fn launch(&self, _library: &Path, _timeout: Duration) -> Result<HostConnection> {
+            self.launches.fetch_add(1, Ordering::SeqCst);
+            let (parent, mut host) = UnixStream::pair()?;
+            match self.greeting {
+                Some(abi_version) => {
+                    write_frame(&mut host, &Response::Ready { abi_version })?;
+                    std::mem::forget(host);
+                }
+                None => {
+                    std::thread::spawn(move || serve(host, &ScriptedStrategy));
+                }
+            }
+            Ok(HostConnection::new(parent, None))
+        }
+    }
+
+    fn config() -> SafetyConfig {
+        SafetyConfig {
+            call_timeout_ms: 100,
+            max_restarts: 2,
+            ..SafetyConfig::default()
+        }
+    }
+
+    fn market(price: f64) -> MarketData {
+        MarketData {
+            timestamp: 0,
+            price,
+            volume: 0.0,
+            bid: 0.0,
+            ask: 0.0,
+            momentum_signal: 0.0,
+            volatility: 0.0,
+            liquidity_score: 0.0,
+        }
+    }
+
+    unsafe extern "C" fn record_fill(
+        agent_id: *const c_char,
+        position_size: f64,
+        price: f64,
+    ) -> i32 {
+        let agent_id = std::ffi::CStr::from_ptr(agent_id).to_str().unwrap();
+        assert_eq!(agent_id, "agent-7");
+        (position_size * price) as i32
+    }
+
+    #[test]
+    fn test_calls_and_callback_hand_off() {
+        let launcher = Arc::new(ThreadLauncher::default());
+        let strategy = IsolatedStrategy::spawn(Path::new("s.so"), launcher, &config()).unwrap();
+
+        assert_eq!(strategy.analyze(&market(50.0)).unwrap(), 0.5);
+        assert_eq!(strategy.health_check().unwrap(), 0);
+        assert_eq!(strategy.details().unwrap().name, "Scripted");
+
+        let agent_id = CString::new("agent-7").unwrap();
+        let mut context = HftContext {
+            agent_id: agent_id.as_ptr(),
+            position_size: 10.0,
+            available_balance: 1000.0,
+            max_position_size: 100.0,
+            risk_limit: 0.02,
+            execution_callback: Some(record_fill),
+        };
+        // The callback ran in this process and its result reached the strategy
+        assert_eq!(strategy.execute(&mut context).unwrap(), 1015);
+        assert_eq!(context.position_size, 5.0);
+        assert_eq!(context.agent_id, agent_id.as_ptr());
+
+        context.execution_callback = None;
+        assert_eq!(strategy.execute(&mut context).unwrap(), 0);
+        assert_eq!(strategy.restarts(), 0);
+    }
+
+    #[test]
+    fn test_crashes_and_timeouts_restart_the_host() {
+        let launcher = Arc::new(ThreadLauncher::default());
+        let strategy =
+            IsolatedStrategy::spawn(Path::new("s.so"), launcher.clone(), &config()).unwrap();
+
+        assert!(strategy.analyze(&market(-1.0)).is_err());
+        assert_eq!(strategy.analyze(&market(50.0)).unwrap(), 0.5);
+        assert_eq!(strategy.restarts(), 1);
+
+        let err = strategy.analyze(&market(2e6)).unwrap_err().to_string();
+        assert!(err.contains("Strategy host call failed"), "{}", err);
+        assert_eq!(strategy.analyze(&market(20.0)).unwrap(), 0.2);
+        assert_eq!(strategy.restarts(), 2);
+        assert_eq!(launcher.launches.load(Ordering::SeqCst), 3);
+
+        // Restart budget spent
+        assert!(strategy.analyze(&market(-1.0)).is_err());
+        let err = strategy.analyze(&market(50.0)).unwrap_err().to_string();
+        assert!(err.contains("failed 2 times; not restarting"), "{}", err);
+
+        strategy.shutdown();
+        assert!(strategy
+            .health_check()
+            .unwrap_err()
+            .to_string()
+            .contains("shut down"));
+    }
+
+    #[test]
+    fn test_host_with_other_abi_is_rejected() {
+        let launcher = Arc::new(ThreadLauncher {
+            greeting: Some(STRATEGY_ABI_VERSION + 1),
+            ..ThreadLauncher::default()
+        });
+        let err = IsolatedStrategy::spawn(Path::new("s.so"), launcher, &config())
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains(&format!("expected {}", STRATEGY_ABI_VERSION)),
+            "{}",
+            err
+        );
+    }
+}
@@ -44,6 +44,9 @@ pub struct StrategyInfo {
     pub max_drawdown: f64,
 }
 
+// ABI version checked by the hot loader before any other symbol is used
+const STRATEGY_ABI_VERSION: u32 = {{ABI_VERSION}};
+
 // Strategy configuration constants
 const MAX_DRAWDOWN: f64 = {{MAX_DRAWDOWN}};
 const DAILY_LOSS_LIMIT: f64 = {{DAILY_LOSS_LIMIT}};
@@ -55,6 +58,12 @@ static STRATEGY_VERSION: &str = "1.0.0\0";
 static STRATEGY_AUTHOR: &str = "THE OVERMIND PROTOCOL\0";
 static STRATEGY_DESCRIPTION: &str = "AI-Generated Trading Strategy\0";
 
+/// ABI version this strategy was generated for
+#[no_mangle]
+pub extern "C" fn strategy_abi_version() -> u32 {
+    STRATEGY_ABI_VERSION
+}
+
 /// Analyze market data and return signal strength
 #[no_mangle]
 pub unsafe extern "C" fn strategy_analyze(market_data: *const MarketData) -> f64 {
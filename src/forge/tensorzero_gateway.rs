@@ -4,12 +4,17 @@
 //! Unified API dla wszystkich LLM providers
 
 use anyhow::{Result, anyhow};
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
-use tokio::time::timeout;
-use tracing::{debug, info, warn, error};
-use reqwest::Client;
+use tracing::{debug, info, warn};
+
+use super::artifact_registry::LocalFsStore;
+use super::inference_provider::{
+    HttpProvider, InferenceProvider, ProviderMode, RecordingProvider, ReplayProvider, TemplateProvider,
+};
 
 /// TensorZero Gateway configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +42,10 @@ pub struct TensorZeroConfig {
     
     /// Enable observability
     pub enable_observability: bool,
+    
+    /// HTTP, record, replay or local templates
+    #[serde(default)]
+    pub provider: ProviderMode,
 }
 
 impl Default for TensorZeroConfig {
@@ -53,6 +62,7 @@ impl Default for TensorZeroConfig {
             timeout_seconds: 30,
             max_retries: 3,
             enable_observability: true,
+            provider: ProviderMode::Http,
         }
     }
 }
@@ -63,11 +73,11 @@ pub struct TensorZeroGateway {
     /// Configuration
     config: TensorZeroConfig,
     
-    /// HTTP client
-    http_client: Client,
+    /// Source of inference responses
+    provider: Arc<dyn InferenceProvider>,
     
     /// Request metrics
-    metrics: TensorZeroMetrics,
+    metrics: Mutex<TensorZeroMetrics>,
 }
 
 /// TensorZero metrics
@@ -155,38 +165,39 @@ pub struct Usage {
 }
 
 impl TensorZeroGateway {
-    /// Create new TensorZero Gateway
+    /// Create new TensorZero Gateway with the provider selected in `config`
     pub async fn new(config: TensorZeroConfig) -> Result<Self> {
-        let http_client = Client::builder()
-            .timeout(Duration::from_secs(config.timeout_seconds))
-            .build()
-            .map_err(|e| anyhow!("Failed to create HTTP client: {}", e))?;
-        
-        // Test connection to TensorZero Gateway
-        let health_url = format!("{}/health", config.gateway_url);
-        match http_client.get(&health_url).send().await {
-            Ok(response) if response.status().is_success() => {
-                info!("✅ TensorZero Gateway connection successful");
-            }
-            Ok(response) => {
-                warn!("⚠️ TensorZero Gateway responded with status: {}", response.status());
-            }
-            Err(e) => {
-                error!("❌ Failed to connect to TensorZero Gateway: {}", e);
-                return Err(anyhow!("TensorZero Gateway connection failed: {}", e));
+        let provider: Arc<dyn InferenceProvider> = match &config.provider {
+            ProviderMode::Http => Arc::new(HttpProvider::connect(&config).await?),
+            ProviderMode::Record { dir } => Arc::new(RecordingProvider::new(
+                Arc::new(HttpProvider::connect(&config).await?),
+                Arc::new(LocalFsStore::new(dir)?),
+            )),
+            ProviderMode::Replay { dir } => {
+                if !dir.is_dir() {
+                    return Err(anyhow!("Replay directory {} does not exist", dir.display()));
+                }
+                Arc::new(ReplayProvider::new(Arc::new(LocalFsStore::new(dir)?)))
             }
-        }
+            ProviderMode::Template => Arc::new(TemplateProvider::default()),
+        };
+        info!("🧩 TensorZero provider: {:?}", config.provider);
         
-        Ok(Self {
+        Ok(Self::from_provider(config, provider))
+    }
+    
+    /// Gateway answering from a custom provider
+    pub fn from_provider(config: TensorZeroConfig, provider: Arc<dyn InferenceProvider>) -> Self {
+        Self {
             config,
-            http_client,
-            metrics: TensorZeroMetrics::default(),
-        })
+            provider,
+            metrics: Mutex::new(TensorZeroMetrics::default()),
+        }
     }
     
     /// Generate strategy DSL using TensorZero
     pub async fn generate_strategy_dsl(
-        &mut self,
+        &self,
         prompt: &str,
         context: Option<&str>,
         function_name: Option<&str>,
@@ -218,32 +229,44 @@ impl TensorZeroGateway {
         };
         
         // Send request with retries
-        let response = self.send_inference_request_with_retries(request).await?;
+        let response = self.inference(request).await?;
         
         // Extract generated DSL
         let dsl = self.extract_dsl_from_response(&response)?;
         
+        debug!("Generated strategy DSL: {} characters in {}ms", dsl.len(), start_time.elapsed().as_millis());
+        Ok(dsl)
+    }
+    
+    /// Run an inference request with retries and fallback models
+    pub async fn inference(&self, request: InferenceRequest) -> Result<InferenceResponse> {
+        let start_time = std::time::Instant::now();
+        
+        let response = self.send_inference_request_with_retries(request).await?;
+        
         // Update metrics
         let response_time = start_time.elapsed().as_millis() as u64;
         self.update_metrics(&response, response_time, true);
         
-        debug!("Generated strategy DSL: {} characters", dsl.len());
-        Ok(dsl)
+        Ok(response)
     }
     
     /// Send inference request with retries and fallbacks
-    async fn send_inference_request_with_retries(&mut self, mut request: InferenceRequest) -> Result<InferenceResponse> {
+    async fn send_inference_request_with_retries(&self, mut request: InferenceRequest) -> Result<InferenceResponse> {
         let mut last_error = None;
         
+        // Offline providers give the same answer every time
+        let attempts = if self.provider.is_offline() { 1 } else { self.config.max_retries };
+        
         // Try primary model
-        for attempt in 0..self.config.max_retries {
-            match self.send_inference_request(&request).await {
+        for attempt in 0..attempts {
+            match self.provider.infer(&request).await {
                 Ok(response) => return Ok(response),
                 Err(e) => {
                     warn!("Inference attempt {} failed: {}", attempt + 1, e);
                     last_error = Some(e);
                     
-                    if attempt < self.config.max_retries - 1 {
+                    if attempt < attempts - 1 {
                         tokio::time::sleep(Duration::from_millis(1000 * (attempt as u64 + 1))).await;
                     }
                 }
@@ -256,9 +279,9 @@ impl TensorZeroGateway {
             warn!("Trying fallback model: {}", fallback_model);
             request.model_name = fallback_model.clone();
 
-            match self.send_inference_request(&request).await {
+            match self.provider.infer(&request).await {
                 Ok(response) => {
-                    self.metrics.fallback_usage_count += 1;
+                    self.metrics.lock().fallback_usage_count += 1;
                     return Ok(response);
                 }
                 Err(e) => {
@@ -271,31 +294,6 @@ impl TensorZeroGateway {
         Err(last_error.unwrap_or_else(|| anyhow!("All inference attempts failed")))
     }
     
-    /// Send single inference request
-    async fn send_inference_request(&mut self, request: &InferenceRequest) -> Result<InferenceResponse> {
-        let url = format!("{}/inference", self.config.gateway_url);
-        
-        let response = timeout(
-            Duration::from_secs(self.config.timeout_seconds),
-            self.http_client.post(&url)
-                .json(request)
-                .send()
-        ).await
-        .map_err(|_| anyhow!("Request timeout"))?
-        .map_err(|e| anyhow!("HTTP request failed: {}", e))?;
-        
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(anyhow!("TensorZero API error {}: {}", status, error_text));
-        }
-        
-        let inference_response: InferenceResponse = response.json().await
-            .map_err(|e| anyhow!("Failed to parse response: {}", e))?;
-        
-        Ok(inference_response)
-    }
-    
     /// Build system prompt for strategy generation
     fn build_strategy_system_prompt(&self, context: Option<&str>) -> String {
         let base_prompt = r#"
@@ -393,26 +391,27 @@ FOCUS ON:
     }
     
     /// Update metrics
-    fn update_metrics(&mut self, response: &InferenceResponse, response_time_ms: u64, success: bool) {
-        self.metrics.total_requests += 1;
+    fn update_metrics(&self, response: &InferenceResponse, response_time_ms: u64, success: bool) {
+        let mut metrics = self.metrics.lock();
+        metrics.total_requests += 1;
         
         if success {
-            self.metrics.successful_requests += 1;
+            metrics.successful_requests += 1;
         } else {
-            self.metrics.failed_requests += 1;
+            metrics.failed_requests += 1;
         }
         
-        self.metrics.total_tokens_used += response.usage.total_tokens as u64;
+        metrics.total_tokens_used += response.usage.total_tokens as u64;
         
         // Update average response time
-        let total_requests = self.metrics.total_requests;
-        self.metrics.average_response_time_ms = 
-            (self.metrics.average_response_time_ms * (total_requests - 1) + response_time_ms) / total_requests;
+        let total_requests = metrics.total_requests;
+        metrics.average_response_time_ms = 
+            (metrics.average_response_time_ms * (total_requests - 1) + response_time_ms) / total_requests;
     }
     
     /// Get metrics
-    pub fn get_metrics(&self) -> &TensorZeroMetrics {
-        &self.metrics
+    pub fn get_metrics(&self) -> TensorZeroMetrics {
+        self.metrics.lock().clone()
     }
     
     /// Get configuration
@@ -438,11 +437,10 @@ mod tests {
     
     #[test]
     fn test_code_extraction_from_markdown() {
-        let gateway = TensorZeroGateway {
-            config: TensorZeroConfig::default(),
-            http_client: Client::new(),
-            metrics: TensorZeroMetrics::default(),
-        };
+        let gateway = TensorZeroGateway::from_provider(
+            TensorZeroConfig::default(),
+            Arc::new(TemplateProvider::default()),
+        );
         
         let markdown_text = r#"
 Here's your strategy:
@@ -0,0 +1,876 @@
+//! WALK-FORWARD EVALUATION - Measured Fitness for Evolved Strategies
+//!
+//! Kandydat DSL odtwarzany na nagranych danych rynkowych (`market_replay`)
+//! w kolejnych oknach train/test. Fitness liczony out-of-sample z Sharpe,
+//! max drawdown i turnover; strażnicy przeciw przeuczeniu odrzucają
+//! strategie z degradacją OOS albo zbyt małą liczbą transakcji.
+//!
+//! The recorded ticks provide `price`, `volume`, `bid`/`ask` (the trade
+//! price), `spread`, rolling `volatility`, `price_change_1h`/`_24h`, the
+//! position features (`profit`, `loss`, `drawdown`, `daily_loss`,
+//! `holding_time`, `execution_latency`) and the declared indicators.
+//! A candidate referencing anything else cannot be measured and is refused.
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, VecDeque};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use super::artifact_registry::ArtifactStore;
+use super::dsl::{DslStrategy, ExprValue, FeatureSchema, FeatureSnapshot, Signal};
+use crate::modules::data_ingestor::MarketData;
+use crate::modules::market_replay::{self, ReplayEvent};
+
+const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 3600.0;
+
+/// Features every simulated bar provides, besides indicator outputs
+const RECORDED_FEATURES: [&str; 14] = [
+    "price",
+    "volume",
+    "bid",
+    "ask",
+    "spread",
+    "volatility",
+    "price_change_1h",
+    "price_change_24h",
+    "profit",
+    "loss",
+    "daily_loss",
+    "drawdown",
+    "holding_time",
+    "execution_latency",
+];
+
+/// Walk-forward split, costs, fitness weights and overfitting guards
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WalkForwardConfig {
+    /// Bars in each in-sample window
+    pub train_bars: usize,
+
+    /// Bars in each out-of-sample window following it
+    pub test_bars: usize,
+
+    /// Bars between fold starts; `test_bars` when unset
+    pub step_bars: Option<usize>,
+
+    /// Fee per unit of capital traded
+    pub fee_rate: f64,
+
+    /// Bars in the rolling `volatility` feature
+    pub volatility_window: usize,
+
+    /// Sharpe annualization; inferred from the median bar spacing when unset
+    pub periods_per_year: Option<f64>,
+
+    /// Fitness cost per unit of max drawdown
+    pub drawdown_penalty: f64,
+
+    /// Fitness cost per unit of capital traded per bar
+    pub turnover_penalty: f64,
+
+    /// Minimum trades opened across all out-of-sample windows
+    pub min_trades: usize,
+
+    /// Largest tolerated relative drop from in-sample to out-of-sample Sharpe
+    pub max_degradation: f64,
+}
+
+impl Default for WalkForwardConfig {
+    fn default() -> Self {
+        Self {
+            train_bars: 500,
+            test_bars: 250,
+            step_bars: None,
+            fee_rate: 0.001,
+            volatility_window: 20,
+            periods_per_year: None,
+            drawdown_penalty: 10.0,
+            turnover_penalty: 50.0,
+            min_trades: 10,
+            max_degradation: 0.5,
+        }
+    }
+}
+
+/// Performance over one window of bars
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WindowMetrics {
+    pub bars: usize,
+    pub total_return: f64,
+    pub sharpe_ratio: f64,
+    pub max_drawdown: f64,
+
+    /// Capital traded per bar
+    pub turnover: f64,
+
+    /// Positions opened in the window
+    pub trades: usize,
+
+    /// Share of positions closed in the window with a profit
+    pub win_rate: f64,
+    pub avg_holding_secs: f64,
+    pub fitness: f64,
+}
+
+/// In-sample and out-of-sample result of one fold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FoldResult {
+    pub index: usize,
+
+    /// Bar indices `[start, end)`
+    pub train: (usize, usize),
+    pub test: (usize, usize),
+    pub in_sample: WindowMetrics,
+    pub out_of_sample: WindowMetrics,
+}
+
+/// Full walk-forward result of one candidate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WalkForwardReport {
+    pub strategy_name: String,
+    pub symbol: String,
+    pub bars: usize,
+    pub periods_per_year: f64,
+    pub config: WalkForwardConfig,
+    pub folds: Vec<FoldResult>,
+
+    /// Fold metrics averaged (drawdown: worst, trades: summed)
+    pub in_sample: WindowMetrics,
+    pub out_of_sample: WindowMetrics,
+
+    /// Relative drop from in-sample to out-of-sample Sharpe
+    pub degradation: f64,
+
+    /// Out-of-sample fitness; the number candidates are ranked by
+    pub fitness: f64,
+
+    /// Guards the candidate failed; empty when it may be promoted
+    pub rejections: Vec<String>,
+}
+
+impl WalkForwardReport {
+    pub fn passed(&self) -> bool {
+        self.rejections.is_empty()
+    }
+}
+
+/// Runs DSL candidates over recorded bars, fold by fold
+#[derive(Debug, Clone)]
+pub struct WalkForwardEvaluator {
+    config: WalkForwardConfig,
+    schema: FeatureSchema,
+}
+
+impl WalkForwardEvaluator {
+    pub fn new(config: WalkForwardConfig) -> Self {
+        Self {
+            config,
+            schema: FeatureSchema::standard(),
+        }
+    }
+
+    /// Schema candidates are loaded against
+    pub fn with_feature_schema(mut self, schema: FeatureSchema) -> Self {
+        self.schema = schema;
+        self
+    }
+
+    pub fn config(&self) -> &WalkForwardConfig {
+        &self.config
+    }
+
+    /// Backtest `source` over `bars` (one symbol, oldest first).
+    ///
+    /// Fails when the candidate does not load, needs features the recording
+    /// lacks, or the recording is too short for a single fold.
+    pub fn evaluate(&self, source: &str, bars: &[MarketData]) -> Result<WalkForwardReport> {
+        let strategy = DslStrategy::from_source(source, &self.schema)?;
+
+        let mut provided: BTreeSet<String> =
+            RECORDED_FEATURES.iter().map(|s| s.to_string()).collect();
+        provided.extend(strategy.indicator_engine().outputs());
+        let missing: Vec<&str> = strategy
+            .required_features()
+            .iter()
+            .filter(|name| !provided.contains(*name))
+            .map(String::as_str)
+            .collect();
+        if !missing.is_empty() {
+            bail!(
+                "Strategy {} needs features the recorded data lacks: {}",
+                strategy.name,
+                missing.join(", ")
+            );
+        }
+
+        let folds = self.folds(bars.len());
+        if folds.is_empty() {
+            bail!(
+                "Walk-forward needs at least {} bars, recording has {}",
+                self.config.train_bars + self.config.test_bars,
+                bars.len()
+            );
+        }
+
+        let periods_per_year = self
+            .config
+            .periods_per_year
+            .unwrap_or_else(|| infer_periods_per_year(bars));
+        let outcomes = self.simulate(&strategy, bars);
+
+        let folds: Vec<FoldResult> = folds
+            .into_iter()
+            .enumerate()
+            .map(|(index, (train, test))| FoldResult {
+                index,
+                train,
+                test,
+                in_sample: self.window_metrics(&outcomes[train.0..train.1], periods_per_year),
+                out_of_sample: self.window_metrics(&outcomes[test.0..test.1], periods_per_year),
+            })
+            .collect();
+        let in_sample = summarize(folds.iter().map(|f| &f.in_sample));
+        let out_of_sample = summarize(folds.iter().map(|f| &f.out_of_sample));
+
+        let degradation = if in_sample.sharpe_ratio > 0.0 {
+            1.0 - out_of_sample.sharpe_ratio / in_sample.sharpe_ratio
+        } else {
+            0.0
+        };
+
+        let mut rejections = Vec::new();
+        if out_of_sample.trades < self.config.min_trades {
+            rejections.push(format!(
+                "only {} out-of-sample trades, need {}",
+                out_of_sample.trades, self.config.min_trades
+            ));
+        }
+        if degradation > self.config.max_degradation {
+            rejections.push(format!(
+                "out-of-sample Sharpe {:.2} is {:.0}% below in-sample {:.2}",
+                out_of_sample.sharpe_ratio,
+                degradation * 100.0,
+                in_sample.sharpe_ratio
+            ));
+        }
+
+        Ok(WalkForwardReport {
+            strategy_name: strategy.name.clone(),
+            symbol: bars[0].symbol.clone(),
+            bars: bars.len(),
+            periods_per_year,
+            config: self.config.clone(),
+            fitness: out_of_sample.fitness,
+            folds,
+            in_sample,
+            out_of_sample,
+            degradation,
+            rejections,
+        })
+    }
+
+    /// `(train, test)` bar ranges of every complete fold
+    fn folds(&self, bars: usize) -> Vec<((usize, usize), (usize, usize))> {
+        let WalkForwardConfig {
+            train_bars,
+            test_bars,
+            ..
+        } = self.config;
+        if train_bars == 0 || test_bars == 0 {
+            return Vec::new();
+        }
+        let step = self.config.step_bars.unwrap_or(test_bars).max(1);
+
+        (0..)
+            .map(|i| i * step)
+            .take_while(|start| start + train_bars + test_bars <= bars)
+            .map(|start| {
+                let split = start + train_bars;
+                ((start, split), (split, split + test_bars))
+            })
+            .collect()
+    }
+
+    /// Run the strategy over every bar once; indicators and positions carry
+    /// across fold boundaries, so each window starts warmed up
+    fn simulate(&self, strategy: &DslStrategy, bars: &[MarketData]) -> Vec<BarOutcome> {
+        let mut indicators = strategy.indicator_engine();
+        let mut returns = VecDeque::with_capacity(self.config.volatility_window + 1);
+        let mut book = Book::default();
+        let mut outcomes = Vec::with_capacity(bars.len());
+        let mut previous: Option<&MarketData> = None;
+
+        for bar in bars {
+            let price_return = previous
+                .filter(|p| p.price > 0.0)
+                .map_or(0.0, |p| bar.price / p.price - 1.0);
+            let mut outcome = BarOutcome {
+                net_return: book.mark(price_return, bar.timestamp),
+                ..BarOutcome::default()
+            };
+
+            if previous.is_some() {
+                returns.push_back(price_return);
+                if returns.len() > self.config.volatility_window {
+                    returns.pop_front();
+                }
+            }
+            indicators.on_market_data(bar);
+
+            let mut snapshot = FeatureSnapshot::new()
+                .with_number("price", bar.price)
+                .with_number("volume", bar.volume)
+                .with_number("bid", bar.price)
+                .with_number("ask", bar.price)
+                .with_number("spread", 0.0)
+                .with_number("price_change_1h", bar.price_change_1h)
+                .with_number("price_change_24h", bar.price_change_24h)
+                .with_duration("execution_latency", Duration::ZERO)
+                .with_indicators(&indicators, &bar.symbol);
+            if self.config.volatility_window > 1 && returns.len() == self.config.volatility_window {
+                snapshot.set(
+                    "volatility",
+                    ExprValue::Number(std_dev(returns.make_contiguous())),
+                );
+            }
+            book.describe(bar, &mut snapshot);
+
+            // Features still warming up fail evaluation; the bar is skipped
+            if let Ok(evaluation) = strategy.evaluate(&snapshot) {
+                let target = target_position(
+                    strategy,
+                    book.position,
+                    &evaluation.entries,
+                    &evaluation.exits,
+                );
+                outcome = book.rebalance(target, bar, self.config.fee_rate, outcome);
+            }
+
+            outcomes.push(outcome);
+            previous = Some(bar);
+        }
+
+        outcomes
+    }
+
+    fn window_metrics(&self, outcomes: &[BarOutcome], periods_per_year: f64) -> WindowMetrics {
+        let bars = outcomes.len();
+        let returns: Vec<f64> = outcomes.iter().map(|o| o.net_return).collect();
+
+        let mut equity = 1.0_f64;
+        let mut peak = 1.0_f64;
+        let mut max_drawdown = 0.0_f64;
+        for r in &returns {
+            equity *= 1.0 + r;
+            peak = peak.max(equity);
+            max_drawdown = max_drawdown.max(1.0 - equity / peak);
+        }
+
+        let deviation = std_dev(&returns);
+        let sharpe_ratio = if deviation > f64::EPSILON {
+            mean(&returns) / deviation * periods_per_year.sqrt()
+        } else {
+            0.0
+        };
+        let turnover = if bars > 0 {
+            outcomes.iter().map(|o| o.traded).sum::<f64>() / bars as f64
+        } else {
+            0.0
+        };
+
+        let closed: Vec<&ClosedTrade> = outcomes.iter().filter_map(|o| o.closed.as_ref()).collect();
+        let (win_rate, avg_holding_secs) = if closed.is_empty() {
+            (0.0, 0.0)
+        } else {
+            let n = closed.len() as f64;
+            (
+                closed.iter().filter(|t| t.pnl > 0.0).count() as f64 / n,
+                closed.iter().map(|t| t.holding_secs).sum::<f64>() / n,
+            )
+        };
+
+        WindowMetrics {
+            bars,
+            total_return: equity - 1.0,
+            sharpe_ratio,
+            max_drawdown,
+            turnover,
+            trades: outcomes.iter().filter(|o| o.opened).count(),
+            win_rate,
+            avg_holding_secs,
+            fitness: sharpe_ratio
+                - self.config.drawdown_penalty * max_drawdown
+                - self.config.turnover_penalty * turnover,
+        }
+    }
+}
+
+/// What happened on one bar
+#[derive(Debug, Clone, Default)]
+struct BarOutcome {
+    /// Return on capital after fees
+    net_return: f64,
+
+    /// Capital traded
+    traded: f64,
+    opened: bool,
+    closed: Option<ClosedTrade>,
+}
+
+#[derive(Debug, Clone)]
+struct ClosedTrade {
+    pnl: f64,
+    holding_secs: f64,
+}
+
+#[derive(Debug, Clone)]
+struct OpenTrade {
+    entry_price: f64,
+    entry_equity: f64,
+    opened_at: DateTime<Utc>,
+}
+
+/// Simulated account: signed position as a fraction of equity
+#[derive(Debug, Clone)]
+struct Book {
+    position: f64,
+    equity: f64,
+    peak: f64,
+    day: Option<chrono::NaiveDate>,
+    day_start_equity: f64,
+    open: Option<OpenTrade>,
+}
+
+impl Default for Book {
+    fn default() -> Self {
+        Self {
+            position: 0.0,
+            equity: 1.0,
+            peak: 1.0,
+            day: None,
+            day_start_equity: 1.0,
+            open: None,
+        }
+    }
+}
+
+impl Book {
+    /// Mark the held position to the new price
+    fn mark(&mut self, price_return: f64, at: DateTime<Utc>) -> f64 {
+        let day = at.date_naive();
+        if self.day != Some(day) {
+            self.day = Some(day);
+            self.day_start_equity = self.equity;
+        }
+
+        let r = self.position * price_return;
+        self.equity *= 1.0 + r;
+        self.peak = self.peak.max(self.equity);
+        r
+    }
+
+    /// Position features of the snapshot
+    fn describe(&self, bar: &MarketData, snapshot: &mut FeatureSnapshot) {
+        let pnl = self.open.as_ref().map_or(0.0, |open| {
+            self.position.signum() * (bar.price / open.entry_price - 1.0)
+        });
+        let holding_secs = self.open.as_ref().map_or(0.0, |open| {
+            (bar.timestamp - open.opened_at).num_milliseconds().max(0) as f64 / 1000.0
+        });
+
+        snapshot.set("profit", ExprValue::Number(pnl.max(0.0)));
+        snapshot.set("loss", ExprValue::Number((-pnl).max(0.0)));
+        snapshot.set("drawdown", ExprValue::Number(1.0 - self.equity / self.peak));
+        snapshot.set(
+            "daily_loss",
+            ExprValue::Number((1.0 - self.equity / self.day_start_equity).max(0.0)),
+        );
+        snapshot.set("holding_time", ExprValue::Duration(holding_secs));
+    }
+
+    /// Trade to `target`, paying fees on the traded amount
+    fn rebalance(
+        &mut self,
+        target: f64,
+        bar: &MarketData,
+        fee_rate: f64,
+        mut outcome: BarOutcome,
+    ) -> BarOutcome {
+        let traded = (target - self.position).abs();
+        if traded <= f64::EPSILON {
+            return outcome;
+        }
+
+        let fee = traded * fee_rate;
+        self.equity *= 1.0 - fee;
+        outcome.net_return = (1.0 + outcome.net_return) * (1.0 - fee) - 1.0;
+        outcome.traded = traded;
+
+        let flipped = target * self.position < 0.0;
+        if let Some(open) = &self.open {
+            if target == 0.0 || flipped {
+                outcome.closed = Some(ClosedTrade {
+                    pnl: self.equity - open.entry_equity,
+                    holding_secs: (bar.timestamp - open.opened_at).num_milliseconds().max(0) as f64
+                        / 1000.0,
+                });
+                self.open = None;
+            }
+        }
+        if self.open.is_none() && target != 0.0 {
+            self.open = Some(OpenTrade {
+                entry_price: bar.price,
+                entry_equity: self.equity,
+                opened_at: bar.timestamp,
+            });
+            outcome.opened = true;
+        }
+
+        self.position = target;
+        outcome
+    }
+}
+
+/// Position after acting on the fired rules: the highest-priority exit
+/// scales the position down by its `size` (all of it by default); when
+/// flat, the highest-priority entry opens `size` (else `position_size`,
+/// else everything), short for sell actions. No pyramiding.
+fn target_position(
+    strategy: &DslStrategy,
+    position: f64,
+    entries: &[Signal],
+    exits: &[Signal],
+) -> f64 {
+    if position != 0.0 {
+        return match exits.first() {
+            Some(exit) => {
+                let size = exit.arg("size").unwrap_or(1.0).clamp(0.0, 1.0);
+                let target = position * (1.0 - size);
+                if target.abs() <= f64::EPSILON {
+                    0.0
+                } else {
+                    target
+                }
+            }
+            None => position,
+        };
+    }
+
+    let Some(entry) = entries.first() else {
+        return 0.0;
+    };
+    let size = entry
+        .arg("size")
+        .or_else(|| strategy.constant("position_size").and_then(|c| c.as_f64()))
+        .unwrap_or(1.0)
+        .clamp(0.0, 1.0);
+    let action = entry.action.to_lowercase();
+    if action.contains("sell") || action.contains("short") {
+        -size
+    } else {
+        size
+    }
+}
+
+/// Fold aggregate: averages, except the worst drawdown and the trade count
+fn summarize<'a>(windows: impl Iterator<Item = &'a WindowMetrics>) -> WindowMetrics {
+    let windows: Vec<&WindowMetrics> = windows.collect();
+    if windows.is_empty() {
+        return WindowMetrics::default();
+    }
+    let n = windows.len() as f64;
+    let avg = |f: fn(&WindowMetrics) -> f64| windows.iter().map(|w| f(w)).sum::<f64>() / n;
+
+    WindowMetrics {
+        bars: windows.iter().map(|w| w.bars).sum(),
+        total_return: avg(|w| w.total_return),
+        sharpe_ratio: avg(|w| w.sharpe_ratio),
+        max_drawdown: windows.iter().map(|w| w.max_drawdown).fold(0.0, f64::max),
+        turnover: avg(|w| w.turnover),
+        trades: windows.iter().map(|w| w.trades).sum(),
+        win_rate: avg(|w| w.win_rate),
+        avg_holding_secs: avg(|w| w.avg_holding_secs),
+        fitness: avg(|w| w.fitness),
+    }
+}
+
+/// Bars per year from the median spacing of the recording; 1 (no
+/// annualization) when timestamps don't advance
+fn infer_periods_per_year(bars: &[MarketData]) -> f64 {
+    let mut gaps: Vec<i64> = bars
+        .windows(2)
+        .map(|w| (w[1].timestamp - w[0].timestamp).num_milliseconds())
+        .filter(|gap| *gap > 0)
+        .collect();
+    if gaps.is_empty() {
+        return 1.0;
+    }
+    gaps.sort_unstable();
+    SECONDS_PER_YEAR / (gaps[gaps.len() / 2] as f64 / 1000.0)
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        0.0
+    } else {
+        values.iter().sum::<f64>() / values.len() as f64
+    }
+}
+
+fn std_dev(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let m = mean(values);
+    (values.iter().map(|v| (v - m).powi(2)).sum::<f64>() / (values.len() - 1) as f64).sqrt()
+}
+
+/// Market ticks of `symbol`, oldest first
+pub fn market_series(events: &[ReplayEvent], symbol: &str) -> Vec<MarketData> {
+    let mut bars: Vec<MarketData> = events
+        .iter()
+        .filter_map(|event| match event {
+            ReplayEvent::Market(data) if data.symbol == symbol => Some(data.clone()),
+            _ => None,
+        })
+        .collect();
+    bars.sort_by_key(|bar| bar.timestamp);
+    bars
+}
+
+/// Load recorded JSONL/CSV files and keep the ticks of `symbol`
+pub fn load_market_series(paths: &[PathBuf], symbol: &str) -> Result<Vec<MarketData>> {
+    let bars = market_series(&market_replay::load_events(paths)?, symbol);
+    if bars.is_empty() {
+        return Err(anyhow!("No market data for {} in {:?}", symbol, paths));
+    }
+    Ok(bars)
+}
+
+/// Audit trail of one evolution experiment: the candidate, how it was
+/// measured and why it was or wasn't promoted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExperimentRecord {
+    pub experiment_id: String,
+    pub strategy_id: String,
+    pub cycle: u64,
+    pub evaluated_at: DateTime<Utc>,
+    pub dsl: String,
+
+    /// Walk-forward result; `None` when evaluation failed
+    pub report: Option<WalkForwardReport>,
+    pub error: Option<String>,
+
+    /// Fitness gain over the best champion at evaluation time
+    pub improvement: Option<f64>,
+    pub promoted: bool,
+    pub decision: String,
+}
+
+impl ExperimentRecord {
+    pub fn key(&self) -> String {
+        format!("experiments/{:06}/{}.json", self.cycle, self.experiment_id)
+    }
+
+    pub async fn persist(&self, store: &dyn ArtifactStore) -> Result<()> {
+        store
+            .put(&self.key(), &serde_json::to_vec_pretty(self)?)
+            .await
+    }
+}
+
+/// Stored experiment records, of one cycle or all of them, oldest first
+pub async fn load_experiment_records(
+    store: &dyn ArtifactStore,
+    cycle: Option<u64>,
+) -> Result<Vec<ExperimentRecord>> {
+    let prefix = match cycle {
+        Some(cycle) => format!("experiments/{:06}/", cycle),
+        None => "experiments/".to_string(),
+    };
+
+    let mut records = Vec::new();
+    for key in store.list(&prefix).await? {
+        let Some(bytes) = store.get(&key).await? else {
+            continue;
+        };
+        let record: ExperimentRecord = serde_json::from_slice(&bytes)
+            .map_err(|e| anyhow!("Corrupt experiment record {}: {}", key, e))?;
+        records.push(record);
+    }
+    records.sort_by_key(|r| (r.cycle, r.evaluated_at));
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::artifact_registry::MemoryStore;
+    use super::*;
+    use crate::modules::data_ingestor::DataSource;
+    use chrono::TimeZone;
+
+    /// Sawtooth: 20 bars up 1%, 20 bars down 1%, one bar per minute
+    fn sawtooth(n: usize) -> Vec<MarketData> {
+        let start = Utc.timestamp_opt(1_700_000_000, 0).unwrap();
+        let mut price = 100.0;
+        (0..n)
+            .map(|i| {
+                let change = if (i / 20) % 2 == 0 { 0.01 } else { -0.01 };
+                price *= 1.0 + change;
+                MarketData {
+                    symbol: "BONK".to_string(),
+                    price,
+                    volume: 1_000.0,
+                    timestamp: start + chrono::Duration::minutes(i as i64),
+                    source: DataSource::Replay,
+                    price_change_1h: change,
+                    price_change_24h: 0.0,
+                }
+            })
+            .collect()
+    }
+
+    fn strategy(entry: &str) -> String {
+        format!(
+            "strategy Candidate:\n  risk_model:\n    position_size: 50%\n  entry_logic:\n    - trigger: \"{}\"\n      action: market_buy(size=position_size)\n      priority: 1\n  exit_logic:\n    - trigger: \"price_change_1h < 0\"\n      action: market_sell(size=100%)\n      priority: 1\n",
+            entry
+        )
+    }
+
+    fn evaluator() -> WalkForwardEvaluator {
+        WalkForwardEvaluator::new(WalkForwardConfig {
+            train_bars: 200,
+            test_bars: 100,
+            min_trades: 3,
+            ..WalkForwardConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_trend_follower_measured_out_of_sample() {
+        let bars = sawtooth(800);
+        let report = evaluator()
+            .evaluate(&strategy("price_change_1h > 0"), &bars)
+            .unwrap();
+
+        // (800 - 300) / 100 + 1 folds
+        assert_eq!(report.folds.len(), 6);
+        assert_eq!(report.folds[1].train, (100, 300));
+        assert_eq!(report.folds[1].test, (300, 400));
+        assert!((report.periods_per_year - 525_600.0).abs() < 1e-6);
+
+        // Buys every up leg, sells on the first down bar
+        let oos = &report.out_of_sample;
+        assert_eq!(oos.trades, 6 * 100 / 40);
+        assert!(oos.total_return > 0.0, "{:?}", oos);
+        assert!(oos.sharpe_ratio > 0.0 && oos.max_drawdown > 0.0);
+        assert!(
+            (oos.turnover - 2.0 * 0.5 / 40.0).abs() < 0.01,
+            "{}",
+            oos.turnover
+        );
+        assert_eq!(oos.win_rate, 1.0);
+        assert!(report.fitness < oos.sharpe_ratio);
+        assert!(report.passed(), "{:?}", report.rejections);
+
+        let json = serde_json::to_string(&report).unwrap();
+        let back: WalkForwardReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.folds.len(), 6);
+        assert_eq!(back.out_of_sample.trades, oos.trades);
+        assert!((back.fitness - report.fitness).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_overfitting_guards_and_unmeasurable_candidates() {
+        let bars = sawtooth(800);
+
+        // Never trades
+        let idle = evaluator()
+            .evaluate(&strategy("price_change_1h > 1"), &bars)
+            .unwrap();
+        assert_eq!(idle.out_of_sample.trades, 0);
+        assert_eq!(idle.rejections, vec!["only 0 out-of-sample trades, need 3"]);
+
+        // Profitable in-sample, losing once the regime flips
+        let mut regime = sawtooth(300);
+        let start = regime.last().unwrap().timestamp;
+        let mut price = regime.last().unwrap().price;
+        for i in 1..=100 {
+            let change = if i % 2 == 0 { 0.01 } else { -0.011 };
+            price *= 1.0 + change;
+            regime.push(MarketData {
+                price,
+                price_change_1h: change,
+                timestamp: start + chrono::Duration::minutes(i),
+                ..regime[0].clone()
+            });
+        }
+        let report = WalkForwardEvaluator::new(WalkForwardConfig {
+            train_bars: 300,
+            test_bars: 100,
+            min_trades: 1,
+            ..WalkForwardConfig::default()
+        })
+        .evaluate(&strategy("price_change_1h > 0"), &regime)
+        .unwrap();
+        assert!(report.in_sample.sharpe_ratio > 0.0);
+        assert!(report.out_of_sample.sharpe_ratio < 0.0);
+        assert!(report.degradation > 1.0);
+        assert!(!report.passed());
+        assert!(
+            report.rejections[0].contains("below in-sample"),
+            "{:?}",
+            report.rejections
+        );
+
+        let err = evaluator()
+            .evaluate(&strategy("momentum_signal > 0.7"), &bars)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("lacks: momentum_signal"), "{}", err);
+
+        let err = evaluator()
+            .evaluate(&strategy("price_change_1h > 0"), &bars[..299])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("at least 300 bars"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_experiment_records_persisted_per_cycle() {
+        let store = MemoryStore::new();
+        let report = evaluator()
+            .evaluate(&strategy("price_change_1h > 0"), &sawtooth(400))
+            .unwrap();
+
+        for (cycle, id) in [(2, "b"), (1, "a"), (2, "c")] {
+            ExperimentRecord {
+                experiment_id: id.to_string(),
+                strategy_id: format!("evolved_{}", id),
+                cycle,
+                evaluated_at: Utc::now(),
+                dsl: strategy("price_change_1h > 0"),
+                report: Some(report.clone()),
+                error: None,
+                improvement: Some(report.fitness),
+                promoted: id == "b",
+                decision: "test".to_string(),
+            }
+            .persist(&store)
+            .await
+            .unwrap();
+        }
+
+        let all = load_experiment_records(&store, None).await.unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].experiment_id, "a");
+
+        let second = load_experiment_records(&store, Some(2)).await.unwrap();
+        assert_eq!(second.len(), 2);
+        assert!(second.iter().any(|r| r.promoted));
+        assert_eq!(second[0].report.as_ref(), Some(&report));
+    }
+}
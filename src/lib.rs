@@ -25,6 +25,7 @@
 pub mod config;
 pub mod models;
 pub mod modules;
+pub mod monitoring;
 pub mod overmind;
 pub mod geohot;
 pub mod pheromind;
@@ -38,6 +39,7 @@ pub mod cryptoinsight;
 pub mod memory;
 pub mod neural_execution;
 pub mod cargo_resolver;
+pub mod backtest;
 
 // Re-export commonly used items
 pub use config::Config;
@@ -107,6 +109,9 @@ pub use forge::strategy_compiler::{StrategyCompiler, CompilerConfig, Compilation
 pub use forge::{CompiledArtifact};
 pub use forge::hot_loader::{StrategyHotLoader, StrategyContainer, MarketData, HftContext, StrategyMetrics};
 
+// BACKTEST exports
+pub use backtest::{BacktestEngine, BacktestConfig, BacktestReport};
+
 // DYNAMIC AGENT SYSTEM exports - FAZA 2 OPERACJI "FORGE"
 pub use agents::{DynamicAgent, DynamicAgentConfig, DynamicAgentMetrics, AgentType, AgentState, AgentCommand};
 pub use agents::{AgentManager, AgentManagerMetrics, AutoEvolutionConfig, RiskParameters, ExecutionParameters};
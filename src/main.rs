@@ -21,8 +21,27 @@ use std::sync::Arc;
 use tracing::{error, info, warn};
 
 use config::Config;
-use modules::ai_connector;
+use modules::ai_connector::CommandListener;
+use modules::data_ingestor::DataIngestor;
+use modules::error_handling::ErrorHandler;
+use modules::executor::Executor;
+use modules::fill_simulator::{AmmFillSimulator, FillSimulatorConfig};
+use modules::kill_switch::{create_kill_switch_router, KillSwitch, KillSwitchTriggers};
+use modules::market_replay::{ReplayConfig, ReplaySpeed};
+use modules::memcoin_strategies::{create_strategy_registry_router, StrategyRegistryConfig};
+use modules::order_manager::{OrderManager, OrderManagerConfig};
+use modules::persistence::PersistenceManager;
+use modules::position_ledger::{CostBasisMethod, PositionLedger};
+use modules::risk::{RiskManager, RiskParameters};
+use modules::risk_rules::{create_risk_audit_router, RiskRuleChain, RiskRulesConfig};
+use modules::rpc_failover::RpcFailoverClient;
+use modules::strategy::StrategyEngine;
+use modules::supervisor::{Supervisor, SupervisorConfig};
+use modules::swap_builder::{LiveSwapContext, SwapTransactionBuilder};
+use modules::trade_journal::TradeJournal;
+use modules::wallet_manager::WalletConfigBuilder;
 use overmind::OvermindProtocol;
+use tokio::sync::mpsc;
 
 #[derive(Clone)]
 struct AppState {
@@ -184,14 +203,12 @@ async fn main() -> Result<()> {
         overmind: Arc::new(tokio::sync::RwLock::new(overmind)),
     };
 
-    // Start AI Connector in background
-    info!("🧠 Starting AI Connector for command processing...");
-    let ai_config = config.clone();
-    tokio::spawn(async move {
-        if let Err(e) = ai_connector::listen_for_commands().await {
-            error!("AI Connector error: {}", e);
-        }
-    });
+    // Trading pipeline under supervision; drained in order on SIGINT/SIGTERM
+    let monitoring_state = monitoring::MonitoringState::new();
+    let mut supervisor =
+        Supervisor::new(SupervisorConfig::default()).with_monitoring(monitoring_state.clone());
+    let pipeline_router =
+        spawn_trading_pipeline(&mut supervisor, &config, replay_from_env()?).await?;
 
     // Create HTTP server
     let app = Router::new()
@@ -211,7 +228,9 @@ async fn main() -> Result<()> {
         .route("/overmind/evolution/analyze", post(evolution_analyze_agent))
         .route("/overmind/evolution/evolve", post(evolution_evolve_agent))
         .route("/overmind/evolution/status", get(evolution_status))
-        .with_state(app_state);
+        .with_state(app_state)
+        .nest("/monitoring", monitoring::create_monitoring_router(monitoring_state))
+        .merge(pipeline_router);
 
     let port = config.server.port;
     let listener = tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port)).await?;
@@ -233,12 +252,225 @@ async fn main() -> Result<()> {
     info!("🧬 Evolution Evolve: http://localhost:{}/overmind/evolution/evolve", port);
     info!("🧬 Evolution Status: http://localhost:{}/overmind/evolution/status", port);
     info!("��🧠 AI Connector listening for commands on overmind:commands");
+    info!("🩺 Component Health: http://localhost:{}/monitoring/health", port);
+    info!("🛡️ Risk Decisions: http://localhost:{}/risk/decisions", port);
+    info!("🛑 Kill Switch: http://localhost:{}/kill-switch", port);
+    info!("🧩 Strategies: http://localhost:{}/strategies", port);
+
+    // Server stops with the pipeline; a server failure triggers shutdown
+    let shutdown = supervisor.shutdown_token();
+    let server = tokio::spawn(async move {
+        let result = axum::serve(listener, app)
+            .with_graceful_shutdown(shutdown.clone().cancelled_owned())
+            .await;
+        shutdown.cancel();
+        result
+    });
 
-    axum::serve(listener, app).await?;
+    supervisor.run_until_shutdown().await?;
+    server.await??;
 
+    info!("👋 THE OVERMIND PROTOCOL stopped");
     Ok(())
 }
 
+/// Recorded market data to feed the pipeline instead of the live feed:
+/// `OVERMIND_REPLAY_FILES` lists JSONL/CSV files (comma separated) and
+/// `OVERMIND_REPLAY_SPEED` paces them (`realtime`, `max` or e.g. `10x`)
+fn replay_from_env() -> Result<Option<ReplayConfig>> {
+    let files = match std::env::var("OVERMIND_REPLAY_FILES") {
+        Ok(files) => files,
+        Err(_) => return Ok(None),
+    };
+    let paths: Vec<std::path::PathBuf> = files
+        .split(',')
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(std::path::PathBuf::from)
+        .collect();
+    let speed = match std::env::var("OVERMIND_REPLAY_SPEED") {
+        Ok(speed) => speed.parse()?,
+        Err(_) => ReplaySpeed::Realtime,
+    };
+    Ok(Some(ReplayConfig::new(paths, speed)))
+}
+
+/// Build ingest -> strategy -> risk -> execute -> persist, hand each stage to
+/// the supervisor upstream first (persistence is drained last) and return the
+/// risk audit, kill switch and strategy routes. With `replay` the ingestor
+/// plays back recorded market data and the AI Connector is left out, so a
+/// paper run is reproducible offline
+async fn spawn_trading_pipeline(
+    supervisor: &mut Supervisor,
+    config: &Config,
+    replay: Option<ReplayConfig>,
+) -> Result<Router> {
+    if replay.is_some() && config.is_live_trading() {
+        anyhow::bail!(
+            "Market replay runs in paper mode only; live mode would sign orders for recorded prices"
+        );
+    }
+
+    let (market_data_tx, market_data_rx) = mpsc::unbounded_channel();
+    let (signal_tx, signal_rx) = mpsc::unbounded_channel();
+    let (approved_tx, approved_rx) = mpsc::unbounded_channel();
+    let (persistence_tx, persistence_rx) = mpsc::unbounded_channel();
+    let (execution_result_tx, execution_result_rx) = mpsc::unbounded_channel();
+    let ledger = Arc::new(PositionLedger::new(CostBasisMethod::Fifo));
+
+    let strategy_config = match std::env::var("OVERMIND_STRATEGIES_CONFIG") {
+        Ok(path) => StrategyRegistryConfig::from_file(&path)?,
+        Err(_) => StrategyRegistryConfig::default(),
+    };
+    let mut strategy_engine =
+        StrategyEngine::new(market_data_rx, signal_tx).with_strategy_config(strategy_config);
+    strategy_engine.initialize_memcoin_strategies().await?;
+    strategy_engine.activate_memcoin_strategies().await?;
+    let strategy_registry = strategy_engine.strategy_registry();
+
+    let risk_params = RiskParameters {
+        max_position_size: config.trading.max_position_size,
+        max_daily_loss: config.trading.max_daily_loss,
+        ..Default::default()
+    };
+    let rules_config = match std::env::var("OVERMIND_RISK_RULES_CONFIG") {
+        Ok(path) => RiskRulesConfig::from_file(&path)?,
+        Err(_) => RiskRulesConfig::pre_trade(&risk_params),
+    };
+    let rule_chain = RiskRuleChain::from_config(&rules_config);
+    let kill_switch_path = std::env::var("OVERMIND_KILL_SWITCH_PATH")
+        .unwrap_or_else(|_| "data/kill_switch.json".to_string());
+    let kill_switch = Arc::new(KillSwitch::open(&kill_switch_path)?.with_triggers(
+        KillSwitchTriggers {
+            max_daily_loss: Some(config.trading.max_daily_loss),
+            ..Default::default()
+        },
+    ));
+    // The journal is embedded sqlite; a server URL (such as the default
+    // postgresql:// one) falls back to a local file instead of failing startup
+    let journal = match TradeJournal::from_database_url(&config.database.url) {
+        Ok(journal) => journal,
+        Err(e) => {
+            warn!("⚠️ {}; journaling to data/trade_journal.db instead", e);
+            TradeJournal::open("data/trade_journal.db")?
+        }
+    };
+    let journal = Arc::new(journal);
+    let router = Router::new()
+        .nest("/risk", create_risk_audit_router(journal.clone(), &rule_chain))
+        .nest(
+            "/kill-switch",
+            create_kill_switch_router(
+                kill_switch.clone(),
+                std::env::var("OVERMIND_KILL_SWITCH_TOKEN").ok(),
+            ),
+        )
+        .nest(
+            "/strategies",
+            create_strategy_registry_router(
+                strategy_registry,
+                std::env::var("OVERMIND_STRATEGY_API_TOKEN").ok(),
+            ),
+        );
+
+    let risk_manager = RiskManager::new(signal_rx, approved_tx, risk_params)
+        .with_rule_chain(rule_chain)
+        .with_persistence(persistence_tx)
+        .with_ledger(ledger.clone())
+        .with_kill_switch(kill_switch.clone());
+
+    // Paper fills pay AMM price impact and network costs instead of the target price
+    let fill_config = match std::env::var("OVERMIND_FILL_SIMULATOR_CONFIG") {
+        Ok(path) => FillSimulatorConfig::from_file(&path)?,
+        Err(_) => FillSimulatorConfig::default(),
+    };
+
+    let mut executor = Executor::new(
+        approved_rx,
+        execution_result_tx.clone(),
+        config.trading.mode.clone(),
+        config.solana.rpc_url.clone(),
+        config.solana.wallet_private_key.clone(),
+    )
+    .with_fill_model(Arc::new(AmmFillSimulator::new(fill_config)))
+    .with_ledger(ledger.clone())
+    .with_kill_switch(kill_switch.clone());
+
+    // Live swaps read pools, send and confirm through one failover RPC client,
+    // whose failure storms engage the kill switch; signing checks it too
+    let order_manager = if config.is_live_trading() {
+        let rpc = Arc::new(
+            RpcFailoverClient::new(config.solana.clone()).with_kill_switch(kill_switch.clone()),
+        );
+        let pools_path = std::env::var("OVERMIND_LIVE_POOLS")
+            .unwrap_or_else(|_| "config/live_pools.json".to_string());
+        let live_swap = Arc::new(LiveSwapContext {
+            builder: Arc::new(SwapTransactionBuilder::new(rpc.clone())),
+            wallet: Arc::new(WalletConfigBuilder::parse_private_key(
+                &config.solana.wallet_private_key,
+            )?),
+            sender: rpc.clone(),
+            pools: LiveSwapContext::load_pools(&pools_path)?,
+            kill_switch: Some(kill_switch.clone()),
+        });
+        let order_manager = Arc::new(
+            OrderManager::new(rpc, execution_result_tx, OrderManagerConfig::default())
+                .with_resubmitter(live_swap.clone())
+                .with_ledger(ledger),
+        );
+        info!(
+            "🔴 Live swaps signed by {} on {} pool(s)",
+            live_swap.wallet_id(),
+            live_swap.pools.len()
+        );
+        executor = executor
+            .with_live_swap(live_swap)
+            .with_order_manager(order_manager.clone());
+        Some(order_manager)
+    } else {
+        None
+    };
+
+    let mut data_ingestor = DataIngestor::new(
+        market_data_tx,
+        config.api.helius_api_key.clone(),
+        config.api.quicknode_api_key.clone(),
+    );
+    match replay {
+        Some(replay) => {
+            info!(
+                "📼 Replaying {} recorded file(s) at {:?}",
+                replay.paths.len(),
+                replay.clock.speed()
+            );
+            data_ingestor = data_ingestor.with_replay(replay);
+        }
+        None => {
+            // The AI Connector's circuit breaker opening engages the kill switch
+            info!("🧠 Starting AI Connector for command processing...");
+            supervisor.spawn(
+                "ai_connector",
+                CommandListener::new(ErrorHandler::new().with_kill_switch(kill_switch.clone())),
+            );
+        }
+    }
+    supervisor.spawn("data_ingestor", data_ingestor);
+    supervisor.spawn("strategy_engine", strategy_engine);
+    supervisor.spawn("risk_manager", risk_manager);
+    supervisor.spawn("executor", executor);
+    if let Some(order_manager) = order_manager {
+        supervisor.spawn("order_manager", order_manager);
+    }
+    supervisor.spawn(
+        "persistence",
+        PersistenceManager::new(persistence_rx, execution_result_rx, config.database.url.clone())
+            .with_journal(journal),
+    );
+
+    info!("🛡️ Trading pipeline running under supervision");
+    Ok(router)
+}
+
 async fn health_check(State(state): State<AppState>) -> Result<Json<Value>, StatusCode> {
     let health_status = json!({
         "status": "healthy",
@@ -757,3 +989,56 @@ async fn evolution_evolve_agent(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use config::TradingMode;
+    use modules::supervisor::ComponentState;
+    use modules::trade_journal::JournalQuery;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_pipeline_runs_on_replayed_market_data() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        // Rally the sniping path buys into
+        let mut csv = "timestamp,symbol,price,volume\n".to_string();
+        for i in 0..=20 {
+            csv += &format!("2025-01-01T00:00:{:02}Z,SOL/USDC,{},1000000\n", i, 100 + i);
+        }
+        let recording = dir.path().join("rally.csv");
+        std::fs::write(&recording, csv)?;
+
+        std::env::set_var("OVERMIND_KILL_SWITCH_PATH", dir.path().join("kill_switch.json"));
+        let mut config = Config::from_env()?;
+        config.trading.mode = TradingMode::Paper;
+        let journal_path = dir.path().join("journal.db");
+        config.database.url = format!("sqlite://{}", journal_path.display());
+
+        let mut supervisor = Supervisor::new(SupervisorConfig::default());
+        let replay = ReplayConfig::new(vec![recording], ReplaySpeed::AsFastAsPossible);
+        spawn_trading_pipeline(&mut supervisor, &config, Some(replay)).await?;
+        assert!(supervisor.status("ai_connector").is_none());
+
+        // The ingestor stops once the recording has been played out
+        tokio::time::timeout(Duration::from_secs(10), async {
+            let stopped = Some(ComponentState::Stopped);
+            while supervisor.status("data_ingestor").map(|s| s.state) != stopped {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await?;
+        supervisor.shutdown().await?;
+
+        // Replayed ticks went through strategy and risk into the journal
+        let journal = TradeJournal::open(&journal_path)?;
+        assert!(!journal.query_signals(&JournalQuery::default())?.is_empty());
+
+        // Live mode never runs on recorded prices
+        config.trading.mode = TradingMode::Live;
+        let replay = ReplayConfig::new(vec![], ReplaySpeed::AsFastAsPossible);
+        let mut supervisor = Supervisor::new(SupervisorConfig::default());
+        assert!(spawn_trading_pipeline(&mut supervisor, &config, Some(replay)).await.is_err());
+        Ok(())
+    }
+}
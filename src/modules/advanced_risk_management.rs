@@ -1,8 +1,11 @@
 //! Advanced Risk Management System for THE OVERMIND PROTOCOL
 //!
 //! Comprehensive risk management with dynamic position sizing, correlation analysis,
-//! drawdown protection, circuit breakers, and portfolio rebalancing.
+//! drawdown protection, circuit breakers, portfolio rebalancing and VaR/CVaR limits.
 
+use crate::modules::tail_risk::{
+    ReturnHistory, TailRiskInputs, TailRiskReport, VarConfig, VarEstimate, VarMethod,
+};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -24,6 +27,8 @@ pub struct AdvancedRiskConfig {
     pub stop_loss_multiplier: f64,      // Stop loss as multiple of volatility
     pub take_profit_multiplier: f64,    // Take profit as multiple of volatility
     pub risk_free_rate: f64,            // Risk-free rate for Sharpe ratio calculation
+    #[serde(default)]
+    pub var: VarConfig, // VaR/CVaR confidence, horizon and limits
 }
 
 impl Default for AdvancedRiskConfig {
@@ -40,6 +45,7 @@ impl Default for AdvancedRiskConfig {
             stop_loss_multiplier: 2.0, // 2x volatility for stop loss
             take_profit_multiplier: 3.0, // 3x volatility for take profit
             risk_free_rate: 0.02,      // 2% annual risk-free rate
+            var: VarConfig::default(),
         }
     }
 }
@@ -66,11 +72,13 @@ pub struct PortfolioMetrics {
     pub current_drawdown: f64,
     pub sharpe_ratio: f64,
     pub volatility: f64,
-    pub var_95: f64,             // Value at Risk (95% confidence)
+    pub var_95: f64,             // Value at Risk (var.confidence, 95% by default)
     pub expected_shortfall: f64, // Expected Shortfall (CVaR)
     pub portfolio_beta: f64,
     pub correlation_risk: f64,
     pub concentration_risk: f64,
+    #[serde(default)]
+    pub tail_risk: Option<TailRiskReport>, // All VaR methods with per-position breakdown
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -95,6 +103,9 @@ pub enum RiskAlertType {
     ConcentrationRisk,
     LiquidityRisk,
     MarketRisk,
+    VarLimitExceeded,
+    ExpectedShortfallLimitExceeded,
+    ComponentVarConcentration,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -115,7 +126,7 @@ pub struct CircuitBreaker {
     pub trigger_count: u32,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CorrelationMatrix {
     pub symbols: Vec<String>,
     pub matrix: Vec<Vec<f64>>,
@@ -154,6 +165,7 @@ impl AdvancedRiskManager {
                 portfolio_beta: 0.0,
                 correlation_risk: 0.0,
                 concentration_risk: 0.0,
+                tail_risk: None,
             })),
             price_history: Arc::new(RwLock::new(HashMap::new())),
             correlation_matrix: Arc::new(RwLock::new(CorrelationMatrix {
@@ -228,6 +240,8 @@ impl AdvancedRiskManager {
         let portfolio_metrics = self.portfolio_metrics.clone();
         let config = self.config.clone();
         let risk_alerts = self.risk_alerts.clone();
+        let price_history = self.price_history.clone();
+        let correlation_matrix = self.correlation_matrix.clone();
 
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(Duration::from_secs(10));
@@ -237,7 +251,33 @@ impl AdvancedRiskManager {
 
                 // Calculate portfolio metrics
                 let positions_guard = positions.read().await;
-                let metrics = Self::calculate_portfolio_metrics(&*positions_guard, &config).await;
+                let mut metrics =
+                    Self::calculate_portfolio_metrics(&*positions_guard, &config).await;
+
+                // Tail risk from price history; keeps the rough estimate until enough is recorded
+                let tail_risk = {
+                    let price_history_guard = price_history.read().await;
+                    let correlation_matrix_guard = correlation_matrix.read().await;
+                    TailRiskReport::compute(
+                        &positions_guard,
+                        &price_history_guard,
+                        &correlation_matrix_guard,
+                        &config.var,
+                    )
+                };
+                drop(positions_guard);
+                let mut alerts = Vec::new();
+                match tail_risk {
+                    Ok(report) => {
+                        if let Some(primary) = report.estimate(config.var.primary_method) {
+                            metrics.var_95 = primary.value_at_risk;
+                            metrics.expected_shortfall = primary.expected_shortfall;
+                        }
+                        alerts.extend(Self::check_tail_risk_limits(&report, &config));
+                        metrics.tail_risk = Some(report);
+                    }
+                    Err(e) => debug!("📉 Tail risk not available: {}", e),
+                }
 
                 // Update portfolio metrics
                 {
@@ -246,7 +286,7 @@ impl AdvancedRiskManager {
                 }
 
                 // Check for risk alerts
-                let alerts = Self::check_risk_thresholds(&metrics, &config).await;
+                alerts.extend(Self::check_risk_thresholds(&metrics, &config).await);
                 if !alerts.is_empty() {
                     let mut risk_alerts_guard = risk_alerts.lock().await;
                     risk_alerts_guard.extend(alerts);
@@ -474,6 +514,7 @@ impl AdvancedRiskManager {
             portfolio_beta: 1.0,        // Would be calculated against market benchmark
             correlation_risk: Self::calculate_correlation_risk(positions).await,
             concentration_risk: Self::calculate_concentration_risk(positions).await,
+            tail_risk: None,
         }
     }
 
@@ -591,6 +632,118 @@ impl AdvancedRiskManager {
         alerts
     }
 
+    /// VaR and ES limits against the most pessimistic method; component VaR
+    /// concentration against the primary method
+    pub(crate) fn check_tail_risk_limits(
+        report: &TailRiskReport,
+        config: &AdvancedRiskConfig,
+    ) -> Vec<RiskAlert> {
+        let mut alerts = Vec::new();
+        if report.portfolio_value <= 0.0 {
+            return alerts;
+        }
+        let timestamp = report.timestamp;
+        let limits = &config.var;
+
+        if let Some(worst) = report.worst() {
+            let var_ratio = worst.value_at_risk / report.portfolio_value;
+            if var_ratio > limits.max_var {
+                alerts.push(RiskAlert {
+                    id: format!("var_{}", timestamp),
+                    timestamp,
+                    alert_type: RiskAlertType::VarLimitExceeded,
+                    severity: AlertSeverity::High,
+                    message: format!(
+                        "{:?} VaR ({:.0}%, {} day) at {:.2}% of portfolio exceeds {:.2}% limit",
+                        worst.method,
+                        worst.confidence * 100.0,
+                        worst.horizon_days,
+                        var_ratio * 100.0,
+                        limits.max_var * 100.0
+                    ),
+                    affected_positions: Vec::new(),
+                    recommended_action: "Reduce exposure in the largest VaR contributors"
+                        .to_string(),
+                    risk_metrics: [
+                        ("value_at_risk".to_string(), worst.value_at_risk),
+                        ("var_ratio".to_string(), var_ratio),
+                        ("var_limit".to_string(), limits.max_var),
+                    ]
+                    .into(),
+                });
+            }
+        }
+
+        if let Some(worst) = report
+            .estimates
+            .iter()
+            .max_by(|a, b| a.expected_shortfall.total_cmp(&b.expected_shortfall))
+        {
+            let es_ratio = worst.expected_shortfall / report.portfolio_value;
+            if es_ratio > limits.max_expected_shortfall {
+                alerts.push(RiskAlert {
+                    id: format!("expected_shortfall_{}", timestamp),
+                    timestamp,
+                    alert_type: RiskAlertType::ExpectedShortfallLimitExceeded,
+                    severity: AlertSeverity::Critical,
+                    message: format!(
+                        "{:?} Expected Shortfall at {:.2}% of portfolio exceeds {:.2}% limit",
+                        worst.method,
+                        es_ratio * 100.0,
+                        limits.max_expected_shortfall * 100.0
+                    ),
+                    affected_positions: Vec::new(),
+                    recommended_action: "Hedge or cut positions driving tail losses".to_string(),
+                    risk_metrics: [
+                        ("expected_shortfall".to_string(), worst.expected_shortfall),
+                        ("es_ratio".to_string(), es_ratio),
+                        ("es_limit".to_string(), limits.max_expected_shortfall),
+                    ]
+                    .into(),
+                });
+            }
+        }
+
+        if let Some(primary) = report.estimate(limits.primary_method) {
+            let concentrated: Vec<_> = primary
+                .positions
+                .iter()
+                .filter(|p| {
+                    primary.positions.len() > 1 && p.contribution > limits.max_component_var_share
+                })
+                .collect();
+            if !concentrated.is_empty() {
+                alerts.push(RiskAlert {
+                    id: format!("component_var_{}", timestamp),
+                    timestamp,
+                    alert_type: RiskAlertType::ComponentVarConcentration,
+                    severity: AlertSeverity::Medium,
+                    message: format!(
+                        "{} carry more than {:.0}% of portfolio VaR",
+                        concentrated
+                            .iter()
+                            .map(|p| format!("{} ({:.1}%)", p.symbol, p.contribution * 100.0))
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        limits.max_component_var_share * 100.0
+                    ),
+                    affected_positions: concentrated.iter().map(|p| p.symbol.clone()).collect(),
+                    recommended_action: "Rebalance away from the dominant VaR contributors"
+                        .to_string(),
+                    risk_metrics: concentrated
+                        .iter()
+                        .map(|p| (format!("component_var_{}", p.symbol), p.component_var))
+                        .collect(),
+                });
+            }
+        }
+
+        if !alerts.is_empty() {
+            warn!("🚨 Tail risk limits breached: {} alerts", alerts.len());
+        }
+        alerts
+    }
+
     async fn needs_rebalancing(
         positions: &HashMap<String, Position>,
         config: &AdvancedRiskConfig,
@@ -602,28 +755,22 @@ impl AdvancedRiskManager {
 
     async fn calculate_correlation_matrix(
         price_history: &HashMap<String, Vec<(u64, f64)>>,
-        _config: &AdvancedRiskConfig,
+        config: &AdvancedRiskConfig,
     ) -> Option<CorrelationMatrix> {
-        let symbols: Vec<String> = price_history.keys().cloned().collect();
+        let mut symbols: Vec<String> = price_history.keys().cloned().collect();
+        symbols.sort();
 
         if symbols.len() < 2 {
             return None;
         }
 
-        let n = symbols.len();
-        let mut matrix = vec![vec![0.0; n]; n];
-
-        // Calculate correlation coefficients (simplified)
-        for i in 0..n {
-            for j in 0..n {
-                if i == j {
-                    matrix[i][j] = 1.0;
-                } else {
-                    // Simplified correlation calculation
-                    matrix[i][j] = 0.3; // Mock correlation value
-                }
-            }
+        // Pearson correlation of returns on the VaR bar grid
+        let history =
+            ReturnHistory::from_prices(price_history, &symbols, config.var.return_interval_secs);
+        if history.returns.len() < config.var.min_observations {
+            return None;
         }
+        let matrix = history.correlation();
 
         Some(CorrelationMatrix {
             symbols,
@@ -692,6 +839,31 @@ impl AdvancedRiskManager {
             .unwrap_or(false)
     }
 
+    /// Portfolio VaR/ES by one method for the current positions
+    pub async fn calculate_var(&self, method: VarMethod) -> Result<VarEstimate> {
+        let positions_guard = self.positions.read().await;
+        let price_history_guard = self.price_history.read().await;
+        let correlation_matrix_guard = self.correlation_matrix.read().await;
+
+        let inputs =
+            TailRiskInputs::new(&positions_guard, &price_history_guard, &self.config.var)?;
+        Ok(inputs.estimate(method, &correlation_matrix_guard, &self.config.var))
+    }
+
+    /// Portfolio VaR/ES by every method, with marginal and component VaR
+    pub async fn calculate_tail_risk(&self) -> Result<TailRiskReport> {
+        let positions_guard = self.positions.read().await;
+        let price_history_guard = self.price_history.read().await;
+        let correlation_matrix_guard = self.correlation_matrix.read().await;
+
+        TailRiskReport::compute(
+            &positions_guard,
+            &price_history_guard,
+            &correlation_matrix_guard,
+            &self.config.var,
+        )
+    }
+
     pub async fn get_correlation_matrix(&self) -> CorrelationMatrix {
         let correlation_matrix_guard = self.correlation_matrix.read().await;
         correlation_matrix_guard.clone()
@@ -3,6 +3,7 @@
 // Handles communication via DragonflyDB and vector memory integration
 
 use anyhow::Result;
+use async_trait::async_trait;
 use chrono;
 use redis::aio::ConnectionManager;
 use redis::{AsyncCommands, Client, Commands};
@@ -12,12 +13,16 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
 use tokio::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, instrument, warn};
 use uuid::Uuid;
 
+use crate::error_context;
+use crate::modules::error_handling::{ErrorContext, ErrorHandler, OvermindError};
 use crate::modules::hybrid_price_fetcher::HybridPriceFetcher;
 use crate::modules::jupiter_dex::execute_real_dex_swap;
 use crate::modules::strategy::TradingSignal;
+use crate::modules::supervisor::SupervisedComponent;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
     commitment_config::CommitmentConfig, signature::Keypair, signer::Signer, system_instruction,
@@ -189,6 +194,64 @@ pub async fn listen_for_commands() -> Result<()> {
     }
 }
 
+/// Failed starts of the command listener before its circuit breaker opens
+const LISTENER_CIRCUIT_THRESHOLD: u32 = 3;
+
+/// How long the command listener's circuit breaker stays open
+const LISTENER_CIRCUIT_COOLDOWN: Duration = Duration::from_secs(300);
+
+/// `listen_for_commands` as a supervised pipeline component
+///
+/// Every failed start goes through the error handler; once the listener has
+/// failed `LISTENER_CIRCUIT_THRESHOLD` times the `ai_connector` circuit
+/// breaker opens, which engages the handler's kill switch.
+pub struct CommandListener {
+    error_handler: ErrorHandler,
+    failures: u32,
+}
+
+impl CommandListener {
+    pub fn new(error_handler: ErrorHandler) -> Self {
+        Self {
+            error_handler,
+            failures: 0,
+        }
+    }
+
+    async fn record_failure(&mut self, e: &anyhow::Error) {
+        self.failures += 1;
+        let error = OvermindError::Network {
+            message: e.to_string(),
+            retryable: true,
+        };
+        self.error_handler
+            .handle_error(&error, error_context!("ai_connector", "listen_for_commands"))
+            .await;
+
+        if self.failures >= LISTENER_CIRCUIT_THRESHOLD
+            && !self.error_handler.is_circuit_breaker_open("ai_connector")
+        {
+            self.error_handler
+                .trip_circuit_breaker("ai_connector", LISTENER_CIRCUIT_COOLDOWN);
+        }
+    }
+}
+
+#[async_trait]
+impl SupervisedComponent for CommandListener {
+    async fn run(&mut self, shutdown: CancellationToken) -> Result<()> {
+        tokio::select! {
+            _ = shutdown.cancelled() => Ok(()),
+            result = listen_for_commands() => {
+                if let Err(ref e) = result {
+                    self.record_failure(e).await;
+                }
+                result
+            }
+        }
+    }
+}
+
 /// Process a command from the Python Brain and execute it
 async fn process_brain_command(command_json: &str) -> Result<String> {
     // Parse the JSON command
@@ -1487,4 +1550,26 @@ mod tests {
         assert_eq!(ai_decision.confidence, 0.85);
         assert_eq!(ai_decision.symbol, "SOL/USDC");
     }
+
+    #[tokio::test]
+    async fn test_listener_failures_open_circuit_and_engage_kill_switch() {
+        use crate::modules::kill_switch::{KillSource, KillSwitch, KillTarget};
+
+        let kill_switch = Arc::new(KillSwitch::in_memory());
+        let mut listener =
+            CommandListener::new(ErrorHandler::new().with_kill_switch(kill_switch.clone()));
+        let any = KillTarget::default();
+        let e = anyhow::anyhow!("connection refused");
+
+        for _ in 1..LISTENER_CIRCUIT_THRESHOLD {
+            listener.record_failure(&e).await;
+        }
+        assert!(!listener.error_handler.is_circuit_breaker_open("ai_connector"));
+        assert!(kill_switch.check(&any).is_ok());
+
+        listener.record_failure(&e).await;
+        assert!(listener.error_handler.is_circuit_breaker_open("ai_connector"));
+        assert!(kill_switch.check(&any).is_err());
+        assert_eq!(kill_switch.entries()[0].source, KillSource::AiCircuitOpen);
+    }
 }
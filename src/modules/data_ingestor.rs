@@ -1,10 +1,14 @@
 // Data Ingestor Module
 // Handles real-time market data ingestion from Helius and QuickNode
 
+use crate::modules::market_replay::{MarketReplay, RecordedTransaction, ReplayClock, ReplayConfig};
+use crate::modules::supervisor::SupervisedComponent;
 use anyhow::Result;
+use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +27,7 @@ pub struct MarketData {
 pub enum DataSource {
     Helius,
     QuickNode,
+    Replay,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +44,11 @@ pub struct DataIngestor {
     is_running: bool,
     client: Client,
     config: HeliusConfig,
+    // Historical replay mode (None = live/simulated feed)
+    replay: Option<ReplayConfig>,
+    transaction_sender: Option<mpsc::UnboundedSender<RecordedTransaction>>,
+    // Cancelled by stop() or the supervisor
+    shutdown: CancellationToken,
 }
 
 #[allow(dead_code)]
@@ -58,13 +68,49 @@ impl DataIngestor {
                 api_key: helius_api_key,
                 rpc_url: "https://api.helius.xyz/v0/addresses/".to_string(),
             },
+            replay: None,
+            transaction_sender: None,
+            shutdown: CancellationToken::new(),
         }
     }
 
+    /// Replay recorded market data instead of the live feed
+    pub fn with_replay(mut self, replay: ReplayConfig) -> Self {
+        self.replay = Some(replay);
+        self
+    }
+
+    /// Forward raw transaction events from replay files
+    pub fn with_transaction_sender(
+        mut self,
+        sender: mpsc::UnboundedSender<RecordedTransaction>,
+    ) -> Self {
+        self.transaction_sender = Some(sender);
+        self
+    }
+
+    /// Clock handle for pausing or re-pacing an active replay
+    pub fn replay_clock(&self) -> Option<ReplayClock> {
+        self.replay.as_ref().map(|r| r.clock.clone())
+    }
+
     pub async fn start(&mut self) -> Result<()> {
         info!("🔄 DataIngestor starting...");
         self.is_running = true;
 
+        if let Some(ref replay) = self.replay {
+            info!("📼 DataIngestor in replay mode: {} file(s)", replay.paths.len());
+            let replay = MarketReplay::from_config(replay)?;
+            let run = replay.run(&self.market_data_sender, self.transaction_sender.as_ref());
+            tokio::select! {
+                _ = self.shutdown.cancelled() => info!("🛑 Replay interrupted by shutdown"),
+                result = run => {
+                    result?;
+                }
+            }
+            return Ok(());
+        }
+
         // TODO: Implement actual WebSocket connections to Helius and QuickNode
         // For now, simulate market data
         self.simulate_market_data().await?;
@@ -75,6 +121,7 @@ impl DataIngestor {
     pub async fn stop(&mut self) {
         info!("🛑 DataIngestor stopping...");
         self.is_running = false;
+        self.shutdown.cancel();
     }
 
     async fn simulate_market_data(&self) -> Result<()> {
@@ -87,7 +134,10 @@ impl DataIngestor {
                 break;
             }
 
-            interval.tick().await;
+            tokio::select! {
+                _ = self.shutdown.cancelled() => break,
+                _ = interval.tick() => {}
+            }
 
             // Simple price simulation with small variations
             price_base += (chrono::Utc::now().timestamp_millis() % 10) as f64 * 0.1 - 0.5;
@@ -145,6 +195,14 @@ impl DataIngestor {
     }
 }
 
+#[async_trait]
+impl SupervisedComponent for DataIngestor {
+    async fn run(&mut self, shutdown: CancellationToken) -> Result<()> {
+        self.shutdown = shutdown;
+        self.start().await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,4 +218,30 @@ mod tests {
 
         assert!(!ingestor.is_running);
     }
+
+    #[tokio::test]
+    async fn test_replay_mode_feeds_market_channel() -> Result<()> {
+        use crate::modules::market_replay::ReplaySpeed;
+
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("ticks.csv");
+        std::fs::write(
+            &path,
+            "timestamp,symbol,price,volume\n\
+             2025-01-01T00:00:00Z,SOL/USDC,100.0,1000\n\
+             2025-01-01T00:00:01Z,SOL/USDC,101.0,1100\n",
+        )?;
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut ingestor = DataIngestor::new(tx, "key".to_string(), "key".to_string())
+            .with_replay(ReplayConfig::new(vec![path], ReplaySpeed::AsFastAsPossible));
+
+        ingestor.start().await?;
+
+        assert_eq!(rx.recv().await.unwrap().price, 100.0);
+        let last = rx.recv().await.unwrap();
+        assert_eq!(last.price, 101.0);
+        assert!(matches!(last.source, DataSource::Replay));
+        Ok(())
+    }
 }
@@ -3,13 +3,20 @@
 //! Provides real transaction building for various Solana DEXes
 //! including Raydium, Jupiter, Orca, and others.
 
-use anyhow::{Context, Result};
+use crate::modules::swap_builder::{SwapQuote, SwapTransactionBuilder};
+use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
 use solana_sdk::{
-    instruction::Instruction, pubkey::Pubkey, signature::Keypair, signer::Signer,
-    transaction::Transaction,
+    hash::Hash,
+    instruction::Instruction,
+    message::{v0, VersionedMessage},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    transaction::VersionedTransaction,
 };
 use std::str::FromStr;
+use std::sync::Arc;
 use tracing::{debug, info};
 
 /// Supported DEX types
@@ -56,6 +63,8 @@ pub struct DexRoute {
 pub struct DexIntegration {
     /// Available trading pairs
     trading_pairs: Vec<TradingPair>,
+    /// Real Raydium AMM v4 instruction building (None = mock instructions)
+    swap_builder: Option<Arc<SwapTransactionBuilder>>,
 }
 
 impl DexIntegration {
@@ -63,16 +72,28 @@ impl DexIntegration {
     pub fn new() -> Self {
         Self {
             trading_pairs: Self::initialize_trading_pairs(),
+            swap_builder: None,
         }
     }
 
+    /// Build Raydium swaps from on-chain pool state
+    pub fn with_swap_builder(mut self, swap_builder: Arc<SwapTransactionBuilder>) -> Self {
+        self.swap_builder = Some(swap_builder);
+        self
+    }
+
+    /// Register a pool for a trading pair
+    pub fn add_trading_pair(&mut self, pair: TradingPair) {
+        self.trading_pairs.push(pair);
+    }
+
     /// Build swap transaction for the specified DEX
     pub async fn build_swap_transaction(
         &self,
         params: SwapParams,
         dex_type: DexType,
         wallet: &Keypair,
-    ) -> Result<Transaction> {
+    ) -> Result<VersionedTransaction> {
         info!(
             "🔄 Building swap transaction for {:?}: {} -> {}",
             dex_type, params.input_mint, params.output_mint
@@ -115,11 +136,17 @@ impl DexIntegration {
     /// Build Raydium swap transaction
     async fn build_raydium_swap(
         &self,
-        _params: SwapParams,
+        params: SwapParams,
         wallet: &Keypair,
-    ) -> Result<Transaction> {
+    ) -> Result<VersionedTransaction> {
         debug!("Building Raydium swap transaction");
 
+        if let Some(ref swap_builder) = self.swap_builder {
+            return self
+                .build_raydium_swap_from_pool(swap_builder, params, wallet)
+                .await;
+        }
+
         // Raydium program ID
         let raydium_program_id = Pubkey::from_str("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8")
             .context("Invalid Raydium program ID")?;
@@ -142,12 +169,50 @@ impl DexIntegration {
             .await
     }
 
+    /// Raydium AMM v4 swap with compute budget, ATA creation and wSOL handling
+    async fn build_raydium_swap_from_pool(
+        &self,
+        swap_builder: &SwapTransactionBuilder,
+        params: SwapParams,
+        wallet: &Keypair,
+    ) -> Result<VersionedTransaction> {
+        let pair = self
+            .trading_pairs
+            .iter()
+            .find(|pair| {
+                matches!(pair.dex_type, DexType::Raydium)
+                    && ((pair.base_mint == params.input_mint
+                        && pair.quote_mint == params.output_mint)
+                        || (pair.base_mint == params.output_mint
+                            && pair.quote_mint == params.input_mint))
+            })
+            .ok_or_else(|| {
+                anyhow!(
+                    "No Raydium pool for {} -> {}",
+                    params.input_mint,
+                    params.output_mint
+                )
+            })?;
+
+        let pool = swap_builder.load_pool(&pair.pool_address).await?;
+        let slippage_bps = (params.slippage_tolerance * 10_000.0) as u16;
+        let mut quote: SwapQuote =
+            pool.quote(&params.input_mint, params.amount_in, slippage_bps)?;
+        // Never accept less than the caller asked for
+        quote.minimum_out = quote.minimum_out.max(params.minimum_amount_out);
+
+        let instructions = swap_builder.swap_instructions(&pool, &wallet.pubkey(), &quote);
+        let (recent_blockhash, _) = swap_builder.latest_blockhash().await?;
+
+        Self::sign_v0(&instructions, wallet, recent_blockhash)
+    }
+
     /// Build Jupiter swap transaction
     async fn build_jupiter_swap(
         &self,
         _params: SwapParams,
         wallet: &Keypair,
-    ) -> Result<Transaction> {
+    ) -> Result<VersionedTransaction> {
         debug!("Building Jupiter swap transaction");
 
         // Jupiter program ID
@@ -172,7 +237,11 @@ impl DexIntegration {
     }
 
     /// Build Orca swap transaction
-    async fn build_orca_swap(&self, _params: SwapParams, wallet: &Keypair) -> Result<Transaction> {
+    async fn build_orca_swap(
+        &self,
+        _params: SwapParams,
+        wallet: &Keypair,
+    ) -> Result<VersionedTransaction> {
         debug!("Building Orca swap transaction");
 
         // Orca program ID
@@ -190,7 +259,11 @@ impl DexIntegration {
     }
 
     /// Build Serum swap transaction
-    async fn build_serum_swap(&self, _params: SwapParams, wallet: &Keypair) -> Result<Transaction> {
+    async fn build_serum_swap(
+        &self,
+        _params: SwapParams,
+        wallet: &Keypair,
+    ) -> Result<VersionedTransaction> {
         debug!("Building Serum swap transaction");
 
         // Serum program ID
@@ -208,7 +281,11 @@ impl DexIntegration {
     }
 
     /// Build Saber swap transaction
-    async fn build_saber_swap(&self, _params: SwapParams, wallet: &Keypair) -> Result<Transaction> {
+    async fn build_saber_swap(
+        &self,
+        _params: SwapParams,
+        wallet: &Keypair,
+    ) -> Result<VersionedTransaction> {
         debug!("Building Saber swap transaction");
 
         // Saber program ID
@@ -230,16 +307,25 @@ impl DexIntegration {
         &self,
         instruction: Instruction,
         wallet: &Keypair,
-    ) -> Result<Transaction> {
+    ) -> Result<VersionedTransaction> {
         // In production, this would get the latest blockhash from RPC
         // For now, use a mock blockhash
-        let recent_blockhash = solana_sdk::hash::Hash::default();
+        let recent_blockhash = Hash::default();
 
-        let mut transaction = Transaction::new_with_payer(&[instruction], Some(&wallet.pubkey()));
-
-        transaction.sign(&[wallet], recent_blockhash);
+        Self::sign_v0(&[instruction], wallet, recent_blockhash)
+    }
 
-        Ok(transaction)
+    /// Compile a v0 message paid by the wallet and sign it
+    fn sign_v0(
+        instructions: &[Instruction],
+        wallet: &Keypair,
+        recent_blockhash: Hash,
+    ) -> Result<VersionedTransaction> {
+        let message =
+            v0::Message::try_compile(&wallet.pubkey(), instructions, &[], recent_blockhash)
+                .map_err(|e| anyhow!("Failed to compile v0 message: {}", e))?;
+        VersionedTransaction::try_new(VersionedMessage::V0(message), &[wallet])
+            .map_err(|e| anyhow!("Failed to sign swap transaction: {}", e))
     }
 
     /// Find pool address for a trading pair
@@ -330,4 +416,46 @@ mod tests {
 
         assert!(dex.is_pair_supported(&sol_mint, &usdc_mint));
     }
+
+    #[tokio::test]
+    async fn test_raydium_swap_uses_pool_state() -> Result<()> {
+        use crate::modules::swap_builder::tests::recorded_pool;
+        use crate::modules::swap_builder::{RAYDIUM_AMM_V4_PROGRAM_ID, WSOL_MINT};
+
+        let token_mint = Pubkey::new_unique();
+        let (amm_id, recorded) = recorded_pool(token_mint);
+        let mut dex = DexIntegration::new()
+            .with_swap_builder(Arc::new(SwapTransactionBuilder::new(Arc::new(recorded))));
+        dex.add_trading_pair(TradingPair {
+            base_mint: token_mint,
+            quote_mint: WSOL_MINT,
+            pool_address: amm_id,
+            dex_type: DexType::Raydium,
+        });
+
+        let wallet = Keypair::new();
+        let params = SwapParams {
+            input_mint: token_mint,
+            output_mint: WSOL_MINT,
+            amount_in: 1_000_000_000,
+            minimum_amount_out: 0,
+            slippage_tolerance: 0.01,
+            user_wallet: wallet.pubkey(),
+        };
+
+        let transaction = dex
+            .build_swap_transaction(params, DexType::Raydium, &wallet)
+            .await?;
+        assert!(matches!(transaction.message, VersionedMessage::V0(_)));
+        assert!(transaction.verify_with_results().into_iter().all(|ok| ok));
+        let account_keys = transaction.message.static_account_keys();
+        let swap = transaction
+            .message
+            .instructions()
+            .iter()
+            .find(|ix| account_keys[ix.program_id_index as usize] == RAYDIUM_AMM_V4_PROGRAM_ID)
+            .expect("swap instruction");
+        assert_eq!(swap.accounts.len(), 18);
+        Ok(())
+    }
 }
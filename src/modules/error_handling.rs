@@ -3,7 +3,9 @@
 //! Provides comprehensive error handling, recovery mechanisms,
 //! and detailed error reporting for THE OVERMIND PROTOCOL.
 
+use crate::modules::kill_switch::KillSwitch;
 use anyhow::Result;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use thiserror::Error;
 use tokio::time::sleep;
@@ -117,6 +119,8 @@ pub struct ErrorHandler {
     circuit_breakers: std::collections::HashMap<String, CircuitBreakerState>,
     /// Error statistics
     error_stats: ErrorStatistics,
+    /// Told about every tripped breaker so AI service outages halt trading
+    kill_switch: Option<Arc<KillSwitch>>,
 }
 
 /// Circuit breaker state
@@ -144,9 +148,16 @@ impl ErrorHandler {
         Self {
             circuit_breakers: std::collections::HashMap::new(),
             error_stats: ErrorStatistics::default(),
+            kill_switch: None,
         }
     }
 
+    /// Report tripped circuit breakers to the kill switch
+    pub fn with_kill_switch(mut self, kill_switch: Arc<KillSwitch>) -> Self {
+        self.kill_switch = Some(kill_switch);
+        self
+    }
+
     /// Handle an error with appropriate recovery strategy
     pub async fn handle_error(
         &mut self,
@@ -246,6 +257,10 @@ impl ErrorHandler {
             "🔴 Circuit breaker tripped for {}: cooldown {:?}",
             service, cooldown
         );
+
+        if let Some(ref kill_switch) = self.kill_switch {
+            kill_switch.report_circuit_open(service);
+        }
     }
 
     /// Reset circuit breaker for a service
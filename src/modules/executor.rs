@@ -2,12 +2,23 @@
 // Handles AI-enhanced trade execution on Solana blockchain with TensorZero optimization
 
 use crate::config::TradingMode;
+use crate::modules::fill_simulator::FillModel;
 use crate::modules::hft_engine::{HftEngine, HftEngineConfig};
+use crate::modules::kill_switch::{KillEntry, KillSwitch, KillTarget};
+use crate::modules::order_manager::{OrderManager, Submission};
+use crate::modules::position_ledger::PositionLedger;
 use crate::modules::risk::ApprovedSignal;
+use crate::modules::strategy::TradeAction;
+use crate::modules::supervisor::SupervisedComponent;
+use crate::modules::swap_builder::LiveSwapContext;
 use anyhow::Result;
+use async_trait::async_trait;
 use rand;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +31,68 @@ pub struct ExecutionResult {
     pub fees: f64,
     pub timestamp: chrono::DateTime<chrono::Utc>,
     pub error_message: Option<String>,
+    // Trade context for the position ledger (absent in older journal records)
+    #[serde(default)]
+    pub symbol: String,
+    #[serde(default)]
+    pub action: Option<TradeAction>,
+    #[serde(default)]
+    pub wallet_id: Option<String>,
+    #[serde(default)]
+    pub fee_breakdown: Option<FeeBreakdown>,
+}
+
+impl ExecutionResult {
+    /// Nothing signed or sent: the kill switch stopped the signal
+    pub fn killed(signal: &ApprovedSignal, entry: &KillEntry, wallet_id: Option<String>) -> Self {
+        Self {
+            signal_id: signal.original_signal.signal_id.clone(),
+            transaction_id: String::new(),
+            status: ExecutionStatus::Cancelled,
+            executed_quantity: 0.0,
+            executed_price: 0.0,
+            fees: 0.0,
+            timestamp: chrono::Utc::now(),
+            error_message: Some(format!("kill switch ({}): {}", entry.scope, entry.reason)),
+            symbol: signal.original_signal.symbol.clone(),
+            action: Some(signal.original_signal.action.clone()),
+            wallet_id,
+            fee_breakdown: None,
+        }
+    }
+}
+
+/// Execution costs by source, in quote units
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FeeBreakdown {
+    pub dex_fee: f64,
+    pub network_fee: f64,
+    pub priority_fee: f64,
+    pub jito_tip: f64,
+    /// Fees reported without a breakdown
+    pub other: f64,
+}
+
+impl FeeBreakdown {
+    /// Flat fee with unknown composition
+    pub fn unclassified(fees: f64) -> Self {
+        Self {
+            other: fees,
+            ..Default::default()
+        }
+    }
+
+    pub fn total(&self) -> f64 {
+        self.dex_fee + self.network_fee + self.priority_fee + self.jito_tip + self.other
+    }
+
+    pub fn add(&mut self, other: &FeeBreakdown) {
+        self.dex_fee += other.dex_fee;
+        self.network_fee += other.network_fee;
+        self.priority_fee += other.priority_fee;
+        self.jito_tip += other.jito_tip;
+        self.other += other.other;
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,6 +114,20 @@ pub struct Executor {
     // THE OVERMIND PROTOCOL - HFT Engine integration
     hft_engine: Option<HftEngine>,
     hft_mode_enabled: bool,
+    // Paper-mode fill model (None = fill at target price)
+    fill_model: Option<Arc<dyn FillModel>>,
+    // Shared position/PnL ledger fed with every execution result
+    ledger: Option<Arc<PositionLedger>>,
+    // On-chain confirmation tracking for submitted transactions
+    order_manager: Option<Arc<OrderManager>>,
+    // Real swap building/sending for live trades (None = simulated)
+    live_swap: Option<Arc<LiveSwapContext>>,
+    // Blockhash expiry of sent transactions, by signature, until handed to the order manager
+    submitted_block_heights: Mutex<HashMap<String, u64>>,
+    // Checked before every signal is executed (None = never halted)
+    kill_switch: Option<Arc<KillSwitch>>,
+    // Cancelled by stop() or the supervisor
+    shutdown: CancellationToken,
 }
 
 #[allow(dead_code)]
@@ -61,9 +148,46 @@ impl Executor {
             is_running: false,
             hft_engine: None,
             hft_mode_enabled: false,
+            fill_model: None,
+            ledger: None,
+            order_manager: None,
+            live_swap: None,
+            submitted_block_heights: Mutex::new(HashMap::new()),
+            kill_switch: None,
+            shutdown: CancellationToken::new(),
         }
     }
 
+    /// Simulate paper fills with the given model instead of filling at target price
+    pub fn with_fill_model(mut self, fill_model: Arc<dyn FillModel>) -> Self {
+        self.fill_model = Some(fill_model);
+        self
+    }
+
+    /// Record every execution result in the shared position ledger
+    pub fn with_ledger(mut self, ledger: Arc<PositionLedger>) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
+    /// Hand pending transactions to the order manager for confirmation tracking
+    pub fn with_order_manager(mut self, order_manager: Arc<OrderManager>) -> Self {
+        self.order_manager = Some(order_manager);
+        self
+    }
+
+    /// Build and send real Raydium swaps for live trades
+    pub fn with_live_swap(mut self, live_swap: Arc<LiveSwapContext>) -> Self {
+        self.live_swap = Some(live_swap);
+        self
+    }
+
+    /// Cancel signals the kill switch covers instead of executing them
+    pub fn with_kill_switch(mut self, kill_switch: Arc<KillSwitch>) -> Self {
+        self.kill_switch = Some(kill_switch);
+        self
+    }
+
     /// Create new OVERMIND Executor with HFT Engine enabled
     pub fn new_with_hft(
         signal_receiver: mpsc::UnboundedReceiver<ApprovedSignal>,
@@ -85,6 +209,13 @@ impl Executor {
             is_running: false,
             hft_engine: Some(hft_engine),
             hft_mode_enabled: true,
+            fill_model: None,
+            ledger: None,
+            order_manager: None,
+            live_swap: None,
+            submitted_block_heights: Mutex::new(HashMap::new()),
+            kill_switch: None,
+            shutdown: CancellationToken::new(),
         })
     }
 
@@ -107,19 +238,31 @@ impl Executor {
         }
 
         self.is_running = true;
+        let shutdown = self.shutdown.clone();
 
         while self.is_running {
-            if let Some(approved_signal) = self.signal_receiver.recv().await {
-                self.execute_signal(approved_signal).await?;
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                approved_signal = self.signal_receiver.recv() => match approved_signal {
+                    Some(approved_signal) => self.execute_signal(approved_signal).await?,
+                    None => break,
+                },
             }
         }
 
+        // Signals approved before risk stopped are executed so their results reach persistence
+        while let Ok(approved_signal) = self.signal_receiver.try_recv() {
+            self.execute_signal(approved_signal).await?;
+        }
+        self.is_running = false;
+
         Ok(())
     }
 
     pub async fn stop(&mut self) {
         info!("🛑 Executor stopping...");
         self.is_running = false;
+        self.shutdown.cancel();
     }
 
     async fn execute_signal(&mut self, signal: ApprovedSignal) -> Result<()> {
@@ -154,6 +297,28 @@ impl Executor {
             );
         }
 
+        // Kill switch is checked before any mode builds or signs a transaction
+        if let Some(ref kill_switch) = self.kill_switch {
+            let wallet_id = self
+                .live_swap
+                .as_ref()
+                .map(|live_swap| live_swap.wallet_id());
+            let target =
+                KillTarget::for_signal(&signal.original_signal).with_wallet(wallet_id.clone());
+            if let Some(entry) = kill_switch.blocking(&target) {
+                warn!(
+                    "🛑 Signal {} not executed: kill switch engaged ({}) - {}",
+                    signal_id, entry.scope, entry.reason
+                );
+                let result = ExecutionResult::killed(&signal, &entry, wallet_id);
+                if let Err(e) = self.persistence_sender.send(result.clone()) {
+                    error!("Failed to send execution result to persistence: {}", e);
+                }
+                self.log_execution_result(&result);
+                return Ok(());
+            }
+        }
+
         let result = match (&self.trading_mode, self.hft_mode_enabled) {
             (&TradingMode::Paper, false) => self.execute_paper_trade(signal).await?,
             (&TradingMode::Paper, true) => self.execute_ai_paper_trade(signal).await?,
@@ -161,7 +326,31 @@ impl Executor {
             (&TradingMode::Live, true) => self.execute_ai_live_trade(signal).await?,
         };
 
-        // Send result to persistence
+        // Sent-but-unconfirmed transactions are followed on-chain; the order
+        // manager reports every state change to persistence (and the ledger)
+        if let (ExecutionStatus::Pending, Some(order_manager)) =
+            (&result.status, &self.order_manager)
+        {
+            order_manager
+                .track(
+                    result.clone(),
+                    Submission {
+                        signature: result.transaction_id.clone(),
+                        last_valid_block_height: self
+                            .submitted_block_heights
+                            .lock()
+                            .unwrap()
+                            .remove(&result.transaction_id),
+                    },
+                )
+                .await;
+            return Ok(());
+        }
+
+        // Update positions, then send result to persistence
+        if let Some(ref ledger) = self.ledger {
+            ledger.apply_execution(&result);
+        }
         if let Err(e) = self.persistence_sender.send(result.clone()) {
             error!("Failed to send execution result to persistence: {}", e);
         }
@@ -177,6 +366,28 @@ impl Executor {
             signal.original_signal.signal_id
         );
 
+        if let Some(ref fill_model) = self.fill_model {
+            let fill = fill_model.simulate(&signal);
+            debug!(
+                "📝 {} fill: {:.6}/{:.6} @ {:.6}, impact {:.1} bps, {} slot(s) delayed",
+                fill_model.name(),
+                fill.filled_quantity,
+                fill.requested_quantity,
+                fill.average_price,
+                fill.price_impact_bps,
+                fill.slots_delayed
+            );
+
+            // Simulate submission latency and slot delay
+            tokio::time::sleep(fill.latency).await;
+
+            return Ok(fill.into_execution_result(
+                &signal.original_signal,
+                format!("paper_{}", uuid::Uuid::new_v4()),
+                chrono::Utc::now(),
+            ));
+        }
+
         // Simulate execution delay
         tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
 
@@ -189,6 +400,10 @@ impl Executor {
             fees: signal.approved_quantity * signal.original_signal.target_price * 0.001, // 0.1% fee
             timestamp: chrono::Utc::now(),
             error_message: None,
+            symbol: signal.original_signal.symbol.clone(),
+            action: Some(signal.original_signal.action.clone()),
+            wallet_id: None,
+            fee_breakdown: None,
         };
 
         Ok(result)
@@ -206,7 +421,9 @@ impl Executor {
         Ok(result)
     }
 
-    /// Execute transaction with retry logic and exponential backoff
+    /// Execute transaction with retry logic and exponential backoff. Only
+    /// failures before anything was sent are retried; a swap whose send
+    /// errored comes back as Pending and is followed by its signature
     async fn execute_with_retry(&self, signal: &ApprovedSignal) -> Result<ExecutionResult> {
         let max_retries = 3;
         let mut retry_count = 0;
@@ -239,6 +456,10 @@ impl Executor {
                             fees: 0.0,
                             timestamp: chrono::Utc::now(),
                             error_message: Some(format!("Max retries exceeded: {}", e)),
+                            symbol: signal.original_signal.symbol.clone(),
+                            action: Some(signal.original_signal.action.clone()),
+                            wallet_id: self.live_swap.as_ref().map(|l| l.wallet_id()),
+                            fee_breakdown: None,
                         });
                     }
 
@@ -258,16 +479,41 @@ impl Executor {
 
     /// Build and send transaction to Solana network
     async fn build_and_send_transaction(&self, signal: &ApprovedSignal) -> Result<ExecutionResult> {
-        let start_time = std::time::Instant::now();
+        if let Some(ref live_swap) = self.live_swap {
+            let (swap, signature) = live_swap
+                .execute(
+                    &signal.original_signal.symbol,
+                    &signal.original_signal.action,
+                    signal.approved_quantity,
+                    signal.original_signal.target_price,
+                )
+                .await?;
+            self.submitted_block_heights
+                .lock()
+                .unwrap()
+                .insert(signature.clone(), swap.built.last_valid_block_height);
+
+            // Quoted fill; confirmation is up to the order manager, which also
+            // polls swaps whose send errored until their blockhash expires
+            return Ok(ExecutionResult {
+                signal_id: signal.original_signal.signal_id.clone(),
+                transaction_id: signature,
+                status: ExecutionStatus::Pending,
+                executed_quantity: swap.quantity,
+                executed_price: swap.price,
+                fees: swap.fees.total(),
+                timestamp: chrono::Utc::now(),
+                error_message: swap.send_error.clone(),
+                symbol: signal.original_signal.symbol.clone(),
+                action: Some(signal.original_signal.action.clone()),
+                wallet_id: Some(live_swap.wallet_id()),
+                fee_breakdown: Some(swap.fees),
+            });
+        }
 
-        // TODO: Implement actual Solana transaction building and sending
-        // This would involve:
-        // 1. Building the transaction with Solana SDK
-        // 2. Signing with wallet private key
-        // 3. Sending via RPC with proper configuration
-        // 4. Monitoring transaction status
+        let start_time = std::time::Instant::now();
 
-        // For now, simulate with realistic behavior
+        // No live swap context configured: simulate with realistic behavior
         tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
 
         // Simulate occasional failures (10% failure rate)
@@ -286,6 +532,10 @@ impl Executor {
             fees: signal.approved_quantity * signal.original_signal.target_price * 0.0015,
             timestamp: chrono::Utc::now(),
             error_message: None,
+            symbol: signal.original_signal.symbol.clone(),
+            action: Some(signal.original_signal.action.clone()),
+            wallet_id: None,
+            fee_breakdown: None,
         })
     }
 
@@ -327,6 +577,10 @@ impl Executor {
                             * 0.0005, // Lower fees with AI
                         timestamp: chrono::Utc::now(),
                         error_message: None,
+                        symbol: signal.original_signal.symbol.clone(),
+                        action: Some(signal.original_signal.action.clone()),
+                        wallet_id: None,
+                        fee_breakdown: None,
                     })
                 }
                 Err(e) => {
@@ -377,6 +631,10 @@ impl Executor {
                             * 0.0015, // Lower fees with Jito
                         timestamp: chrono::Utc::now(),
                         error_message: None,
+                        symbol: signal.original_signal.symbol.clone(),
+                        action: Some(signal.original_signal.action.clone()),
+                        wallet_id: None,
+                        fee_breakdown: None,
                     })
                 }
                 Err(e) => {
@@ -435,6 +693,14 @@ impl Executor {
     }
 }
 
+#[async_trait]
+impl SupervisedComponent for Executor {
+    async fn run(&mut self, shutdown: CancellationToken) -> Result<()> {
+        self.shutdown = shutdown;
+        self.start().await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -456,4 +722,139 @@ mod tests {
 
         assert!(!executor.is_running);
     }
+
+    #[tokio::test]
+    async fn test_paper_trade_uses_fill_model() -> Result<()> {
+        use crate::modules::fill_simulator::{AmmFillSimulator, FillSimulatorConfig, PoolReserves};
+        use crate::modules::risk::KineticShieldStatus;
+        use crate::modules::strategy::{StrategyType, TradeAction, TradingSignal};
+
+        let simulator = Arc::new(AmmFillSimulator::new(FillSimulatorConfig {
+            latency_ms: 1,
+            latency_jitter_ms: 0,
+            slot_delay: 0,
+            failure_probability: 0.0,
+            seed: Some(1),
+            ..Default::default()
+        }));
+        simulator.update_pool("SOL/USDC", PoolReserves::new(1_000.0, 100_000.0));
+
+        let (_signal_tx, signal_rx) = mpsc::unbounded_channel();
+        let (persistence_tx, _persistence_rx) = mpsc::unbounded_channel();
+        let executor = Executor::new(
+            signal_rx,
+            persistence_tx,
+            TradingMode::Paper,
+            "https://api.mainnet-beta.solana.com".to_string(),
+            "test_key".to_string(),
+        )
+        .with_fill_model(simulator);
+
+        let signal = ApprovedSignal {
+            original_signal: TradingSignal {
+                signal_id: "paper-1".to_string(),
+                symbol: "SOL/USDC".to_string(),
+                action: TradeAction::Buy,
+                quantity: 10.0,
+                target_price: 100.0,
+                price: Some(100.0),
+                confidence: 0.9,
+                timestamp: chrono::Utc::now(),
+                strategy_type: StrategyType::TokenSniping,
+                urgency: None,
+                metadata: None,
+            },
+            approved_quantity: 10.0,
+            risk_score: 0.1,
+            approval_timestamp: chrono::Utc::now(),
+            kinetic_shield_status: KineticShieldStatus::Active,
+            risk_audit: Vec::new(),
+        };
+
+        let result = executor.execute_paper_trade(signal).await?;
+        assert!(matches!(result.status, ExecutionStatus::Confirmed));
+        // Filled above target price because of pool impact, with DEX + network costs
+        assert!(result.executed_price > 100.0);
+        assert!(result.fees > result.executed_quantity * result.executed_price * 0.0025);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_live_trade_sends_swap_and_records_blockhash_expiry() -> Result<()> {
+        use crate::modules::risk::KineticShieldStatus;
+        use crate::modules::strategy::{StrategyType, TradingSignal};
+        use crate::modules::swap_builder::tests::{recorded_pool, RecordingSender};
+        use crate::modules::swap_builder::SwapTransactionBuilder;
+        use base64::{engine::general_purpose, Engine as _};
+        use solana_sdk::{
+            pubkey::Pubkey, signature::Keypair, signer::Signer, transaction::VersionedTransaction,
+        };
+
+        let (amm_id, recorded) = recorded_pool(Pubkey::new_unique());
+        let sender = Arc::new(RecordingSender(parking_lot::Mutex::new(Vec::new())));
+        let wallet = Arc::new(Keypair::new());
+        let wallet_id = wallet.pubkey().to_string();
+        let live_swap = Arc::new(LiveSwapContext {
+            builder: Arc::new(SwapTransactionBuilder::new(Arc::new(recorded))),
+            wallet,
+            sender: sender.clone(),
+            pools: HashMap::from([("BONK/SOL".to_string(), amm_id)]),
+            kill_switch: None,
+        });
+
+        let (_signal_tx, signal_rx) = mpsc::unbounded_channel();
+        let (persistence_tx, _persistence_rx) = mpsc::unbounded_channel();
+        let executor = Executor::new(
+            signal_rx,
+            persistence_tx,
+            TradingMode::Live,
+            "https://api.mainnet-beta.solana.com".to_string(),
+            "test_key".to_string(),
+        )
+        .with_live_swap(live_swap);
+
+        let signal = ApprovedSignal {
+            original_signal: TradingSignal {
+                signal_id: "live-1".to_string(),
+                symbol: "BONK/SOL".to_string(),
+                action: TradeAction::Buy,
+                quantity: 10_000.0,
+                target_price: 0.0001,
+                price: Some(0.0001),
+                confidence: 0.9,
+                timestamp: chrono::Utc::now(),
+                strategy_type: StrategyType::TokenSniping,
+                urgency: None,
+                metadata: None,
+            },
+            approved_quantity: 10_000.0,
+            risk_score: 0.1,
+            approval_timestamp: chrono::Utc::now(),
+            kinetic_shield_status: KineticShieldStatus::Active,
+            risk_audit: Vec::new(),
+        };
+
+        let result = executor.build_and_send_transaction(&signal).await?;
+        let sent_signature = {
+            let wire = general_purpose::STANDARD.decode(&sender.0.lock()[0])?;
+            let sent: VersionedTransaction = bincode::deserialize(&wire)?;
+            sent.signatures[0].to_string()
+        };
+        assert!(matches!(result.status, ExecutionStatus::Pending));
+        assert_eq!(result.transaction_id, sent_signature);
+        assert_eq!(result.wallet_id, Some(wallet_id));
+        assert_eq!(sender.0.lock().len(), 1);
+        // Quoted fill is slightly worse than target because of pool fee and impact
+        assert!(result.executed_quantity < 10_000.0);
+        assert!(result.fee_breakdown.as_ref().unwrap().dex_fee > 0.0);
+        assert_eq!(
+            executor
+                .submitted_block_heights
+                .lock()
+                .unwrap()
+                .get(&sent_signature),
+            Some(&1_000)
+        );
+        Ok(())
+    }
 }
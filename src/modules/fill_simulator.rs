@@ -0,0 +1,560 @@
+// Fill Simulator Module
+// Pluggable paper-trading fill models: constant-product AMM price impact,
+// latency/slot delay, DEX + priority fee + Jito tip costs, partial fills and failures
+
+use crate::modules::executor::{ExecutionResult, ExecutionStatus, FeeBreakdown};
+use crate::modules::risk::ApprovedSignal;
+use crate::modules::strategy::{TradeAction, TradingSignal};
+use anyhow::{anyhow, Context, Result};
+use parking_lot::Mutex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{debug, info};
+
+const LAMPORTS_PER_SOL: f64 = 1_000_000_000.0;
+
+/// Pluggable fill model used by the Executor in paper mode
+pub trait FillModel: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Simulate a fill for an approved signal. Latency is reported, not slept.
+    fn simulate(&self, signal: &ApprovedSignal) -> SimulatedFill;
+}
+
+/// Constant-product pool reserves (base = token, quote = pricing currency)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PoolReserves {
+    pub base_reserve: f64,
+    pub quote_reserve: f64,
+}
+
+impl PoolReserves {
+    pub fn new(base_reserve: f64, quote_reserve: f64) -> Self {
+        Self {
+            base_reserve,
+            quote_reserve,
+        }
+    }
+
+    /// Pool with `quote_depth` on the quote side at `price`
+    pub fn from_price(price: f64, quote_depth: f64) -> Self {
+        Self::new(quote_depth / price, quote_depth)
+    }
+
+    /// Spot price (quote per base)
+    pub fn price(&self) -> f64 {
+        self.quote_reserve / self.base_reserve
+    }
+
+    pub fn k(&self) -> f64 {
+        self.base_reserve * self.quote_reserve
+    }
+
+    /// Same invariant, moved to a new spot price
+    pub fn repriced(&self, price: f64) -> Self {
+        let k = self.k();
+        Self::new((k / price).sqrt(), (k * price).sqrt())
+    }
+
+    /// Quote paid to take `base_out` out of the pool (before DEX fee)
+    pub fn quote_in_for_base_out(&self, base_out: f64) -> f64 {
+        self.quote_reserve * base_out / (self.base_reserve - base_out)
+    }
+
+    /// Quote received for putting `base_in` into the pool (before DEX fee)
+    pub fn quote_out_for_base_in(&self, base_in: f64) -> f64 {
+        self.quote_reserve * base_in / (self.base_reserve + base_in)
+    }
+
+    /// Largest buy whose average price stays within `max_slippage` (fraction) of spot
+    pub fn max_buy_within(&self, max_slippage: f64) -> f64 {
+        self.base_reserve * max_slippage / (1.0 + max_slippage)
+    }
+
+    /// Largest sell whose average price stays within `max_slippage` (fraction) of spot
+    pub fn max_sell_within(&self, max_slippage: f64) -> f64 {
+        if max_slippage >= 1.0 {
+            f64::INFINITY
+        } else {
+            self.base_reserve * max_slippage / (1.0 - max_slippage)
+        }
+    }
+}
+
+/// Cost assumptions for the AMM fill simulator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FillSimulatorConfig {
+    /// Pool swap fee (Raydium AMM v4 = 25 bps)
+    pub dex_fee_bps: f64,
+    /// Signature fee per transaction
+    pub base_fee_lamports: u64,
+    pub compute_unit_limit: u32,
+    pub compute_unit_price_micro_lamports: u64,
+    /// Jito bundle tip, only paid when the bundle lands
+    pub jito_tip_lamports: u64,
+    /// SOL price in quote units, used to convert network costs
+    pub sol_price_quote: f64,
+    /// Submission + propagation latency
+    pub latency_ms: u64,
+    pub latency_jitter_ms: u64,
+    /// Slots between signal and inclusion
+    pub slot_delay: u64,
+    pub slot_duration_ms: u64,
+    /// Random price move per slot of delay, in basis points
+    pub slot_volatility_bps: f64,
+    /// Slippage tolerance; larger orders are partially filled
+    pub max_slippage_bps: f64,
+    /// Fills smaller than this fraction of the request fail instead
+    pub min_fill_ratio: f64,
+    /// Probability a transaction is dropped or reverts
+    pub failure_probability: f64,
+    /// Synthetic pool depth (quote side) for symbols without known reserves
+    pub default_quote_depth: Option<f64>,
+    /// Fixed RNG seed for reproducible paper runs
+    pub seed: Option<u64>,
+}
+
+impl Default for FillSimulatorConfig {
+    fn default() -> Self {
+        Self {
+            dex_fee_bps: 25.0,
+            base_fee_lamports: 5_000,
+            compute_unit_limit: 200_000,
+            compute_unit_price_micro_lamports: 10_000,
+            jito_tip_lamports: 10_000,
+            sol_price_quote: 150.0,
+            latency_ms: 150,
+            latency_jitter_ms: 100,
+            slot_delay: 1,
+            slot_duration_ms: 400,
+            slot_volatility_bps: 10.0,
+            max_slippage_bps: 300.0,
+            min_fill_ratio: 0.1,
+            failure_probability: 0.05,
+            default_quote_depth: Some(100_000.0),
+            seed: None,
+        }
+    }
+}
+
+impl FillSimulatorConfig {
+    /// Load from a TOML file; omitted fields keep their defaults
+    pub fn from_file(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read fill simulator config: {}", path))?;
+        let config: Self = toml::from_str(&content)
+            .map_err(|e| anyhow!("Invalid fill simulator config: {}", e))?;
+        info!("📂 Fill simulator config loaded from {}", path);
+        Ok(config)
+    }
+
+    pub fn priority_fee_lamports(&self) -> u64 {
+        self.compute_unit_limit as u64 * self.compute_unit_price_micro_lamports / 1_000_000
+    }
+
+    fn lamports_to_quote(&self, lamports: u64) -> f64 {
+        lamports as f64 / LAMPORTS_PER_SOL * self.sol_price_quote
+    }
+}
+
+/// Outcome of a simulated fill
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulatedFill {
+    pub status: ExecutionStatus,
+    pub requested_quantity: f64,
+    pub filled_quantity: f64,
+    /// Average execution price before fees
+    pub average_price: f64,
+    /// Pool spot price when the signal was received
+    pub reference_price: f64,
+    /// Average price vs reference, in basis points (adverse is positive)
+    pub price_impact_bps: f64,
+    pub costs: FeeBreakdown,
+    pub latency: Duration,
+    pub slots_delayed: u64,
+    pub error_message: Option<String>,
+}
+
+impl SimulatedFill {
+    pub fn is_partial(&self) -> bool {
+        matches!(self.status, ExecutionStatus::Confirmed)
+            && self.filled_quantity < self.requested_quantity
+    }
+
+    pub fn into_execution_result(
+        self,
+        signal: &TradingSignal,
+        transaction_id: String,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    ) -> ExecutionResult {
+        ExecutionResult {
+            signal_id: signal.signal_id.clone(),
+            transaction_id,
+            status: self.status,
+            executed_quantity: self.filled_quantity,
+            executed_price: self.average_price,
+            fees: self.costs.total(),
+            timestamp,
+            error_message: self.error_message,
+            symbol: signal.symbol.clone(),
+            action: Some(signal.action.clone()),
+            wallet_id: None,
+            fee_breakdown: Some(self.costs),
+        }
+    }
+}
+
+/// Constant-product AMM fill simulator
+pub struct AmmFillSimulator {
+    config: FillSimulatorConfig,
+    pools: Mutex<HashMap<String, PoolReserves>>,
+    rng: Mutex<StdRng>,
+}
+
+impl AmmFillSimulator {
+    pub fn new(config: FillSimulatorConfig) -> Self {
+        let rng = match config.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self {
+            config,
+            pools: Mutex::new(HashMap::new()),
+            rng: Mutex::new(rng),
+        }
+    }
+
+    pub fn config(&self) -> &FillSimulatorConfig {
+        &self.config
+    }
+
+    /// Set the latest known reserves for a symbol's pool
+    pub fn update_pool(&self, symbol: &str, reserves: PoolReserves) {
+        self.pools.lock().insert(symbol.to_string(), reserves);
+    }
+
+    pub fn pool(&self, symbol: &str) -> Option<PoolReserves> {
+        self.pools.lock().get(symbol).copied()
+    }
+
+    fn failed(
+        &self,
+        requested_quantity: f64,
+        reference_price: f64,
+        costs: FeeBreakdown,
+        latency: Duration,
+        slots_delayed: u64,
+        error: String,
+    ) -> SimulatedFill {
+        SimulatedFill {
+            status: ExecutionStatus::Failed,
+            requested_quantity,
+            filled_quantity: 0.0,
+            average_price: 0.0,
+            reference_price,
+            price_impact_bps: 0.0,
+            costs,
+            latency,
+            slots_delayed,
+            error_message: Some(error),
+        }
+    }
+}
+
+impl FillModel for AmmFillSimulator {
+    fn name(&self) -> &str {
+        "constant_product_amm"
+    }
+
+    fn simulate(&self, signal: &ApprovedSignal) -> SimulatedFill {
+        let original = &signal.original_signal;
+        let requested = signal.approved_quantity;
+        let is_buy = matches!(original.action, TradeAction::Buy | TradeAction::MarketBuy);
+        let is_sell = matches!(original.action, TradeAction::Sell | TradeAction::MarketSell);
+
+        let mut rng = self.rng.lock();
+        let config = &self.config;
+
+        let jitter = if config.latency_jitter_ms > 0 {
+            rng.gen_range(0..=config.latency_jitter_ms)
+        } else {
+            0
+        };
+        let latency = Duration::from_millis(
+            config.latency_ms + jitter + config.slot_delay * config.slot_duration_ms,
+        );
+        let slots = config.slot_delay;
+
+        if !is_buy && !is_sell {
+            return self.failed(
+                requested,
+                original.target_price,
+                FeeBreakdown::default(),
+                Duration::ZERO,
+                0,
+                format!("Nothing to fill for {} signal", original.action),
+            );
+        }
+
+        let pool = match self.pool(&original.symbol) {
+            Some(pool) => pool,
+            None => match config.default_quote_depth {
+                Some(depth) if original.target_price > 0.0 => {
+                    PoolReserves::from_price(original.target_price, depth)
+                }
+                _ => {
+                    return self.failed(
+                        requested,
+                        original.target_price,
+                        FeeBreakdown::default(),
+                        Duration::ZERO,
+                        0,
+                        format!("No pool reserves for {}", original.symbol),
+                    )
+                }
+            },
+        };
+        let reference_price = pool.price();
+
+        // A landed-but-reverted transaction still pays signature + priority fee;
+        // the Jito tip is only paid when the bundle lands
+        let network_costs = FeeBreakdown {
+            network_fee: config.lamports_to_quote(config.base_fee_lamports),
+            priority_fee: config.lamports_to_quote(config.priority_fee_lamports()),
+            ..Default::default()
+        };
+
+        if rng.gen::<f64>() < config.failure_probability {
+            return self.failed(
+                requested,
+                reference_price,
+                network_costs,
+                latency,
+                slots,
+                "Simulated transaction failure (dropped or reverted)".to_string(),
+            );
+        }
+
+        // Price drifts while the transaction waits for inclusion
+        let drift = if slots > 0 && config.slot_volatility_bps > 0.0 {
+            rng.gen_range(-1.0..=1.0) * config.slot_volatility_bps / 10_000.0
+                * (slots as f64).sqrt()
+        } else {
+            0.0
+        };
+        drop(rng);
+        let pool_at_fill = pool.repriced(reference_price * (1.0 + drift));
+
+        // Slippage tolerance is measured against the price the signal saw
+        let max_slippage = config.max_slippage_bps / 10_000.0;
+        let filled = if is_buy {
+            let limit_price = reference_price * (1.0 + max_slippage);
+            let tolerance = (limit_price / pool_at_fill.price() - 1.0).max(0.0);
+            requested.min(pool_at_fill.max_buy_within(tolerance))
+        } else {
+            let limit_price = reference_price * (1.0 - max_slippage);
+            let tolerance = (1.0 - limit_price / pool_at_fill.price()).max(0.0);
+            requested.min(pool_at_fill.max_sell_within(tolerance))
+        };
+
+        if requested <= 0.0 || filled < requested * config.min_fill_ratio || filled <= 0.0 {
+            return self.failed(
+                requested,
+                reference_price,
+                network_costs,
+                latency,
+                slots,
+                format!(
+                    "Insufficient liquidity: {:.6} of {:.6} fillable within {} bps",
+                    filled, requested, config.max_slippage_bps
+                ),
+            );
+        }
+
+        let (notional, pool_after) = if is_buy {
+            let quote_in = pool_at_fill.quote_in_for_base_out(filled);
+            (
+                quote_in,
+                PoolReserves::new(
+                    pool_at_fill.base_reserve - filled,
+                    pool_at_fill.quote_reserve + quote_in,
+                ),
+            )
+        } else {
+            let quote_out = pool_at_fill.quote_out_for_base_in(filled);
+            (
+                quote_out,
+                PoolReserves::new(
+                    pool_at_fill.base_reserve + filled,
+                    pool_at_fill.quote_reserve - quote_out,
+                ),
+            )
+        };
+        // Our own trade moves the pool for subsequent paper fills
+        self.update_pool(&original.symbol, pool_after);
+
+        let average_price = notional / filled;
+        let price_impact_bps = if is_buy {
+            (average_price / reference_price - 1.0) * 10_000.0
+        } else {
+            (1.0 - average_price / reference_price) * 10_000.0
+        };
+        let costs = FeeBreakdown {
+            dex_fee: notional * config.dex_fee_bps / 10_000.0,
+            jito_tip: config.lamports_to_quote(config.jito_tip_lamports),
+            ..network_costs
+        };
+
+        let fill = SimulatedFill {
+            status: ExecutionStatus::Confirmed,
+            requested_quantity: requested,
+            filled_quantity: filled,
+            average_price,
+            reference_price,
+            price_impact_bps,
+            costs,
+            latency,
+            slots_delayed: slots,
+            error_message: None,
+        };
+
+        if fill.is_partial() {
+            info!(
+                "📝 Partial paper fill for {}: {:.6}/{:.6} (limit {} bps)",
+                original.signal_id, filled, requested, config.max_slippage_bps
+            );
+        }
+        debug!(
+            "📝 Paper fill {}: avg {:.6} vs ref {:.6} ({:.1} bps impact), costs {:.6}",
+            original.signal_id,
+            average_price,
+            reference_price,
+            price_impact_bps,
+            fill.costs.total()
+        );
+
+        fill
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::risk::KineticShieldStatus;
+    use crate::modules::strategy::{StrategyType, TradingSignal};
+
+    fn approved(action: TradeAction, quantity: f64, price: f64) -> ApprovedSignal {
+        ApprovedSignal {
+            original_signal: TradingSignal {
+                signal_id: "sig-1".to_string(),
+                symbol: "SOL/USDC".to_string(),
+                action,
+                quantity,
+                target_price: price,
+                price: Some(price),
+                confidence: 0.9,
+                timestamp: chrono::Utc::now(),
+                strategy_type: StrategyType::TokenSniping,
+                urgency: None,
+                metadata: None,
+            },
+            approved_quantity: quantity,
+            risk_score: 0.1,
+            approval_timestamp: chrono::Utc::now(),
+            kinetic_shield_status: KineticShieldStatus::Active,
+            risk_audit: Vec::new(),
+        }
+    }
+
+    fn deterministic() -> FillSimulatorConfig {
+        FillSimulatorConfig {
+            latency_jitter_ms: 0,
+            slot_volatility_bps: 0.0,
+            failure_probability: 0.0,
+            seed: Some(7),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_constant_product_price_impact() {
+        let sim = AmmFillSimulator::new(deterministic());
+        sim.update_pool("SOL/USDC", PoolReserves::new(1_000.0, 100_000.0));
+
+        let fill = sim.simulate(&approved(TradeAction::Buy, 10.0, 100.0));
+        assert!(matches!(fill.status, ExecutionStatus::Confirmed));
+        assert_eq!(fill.filled_quantity, 10.0);
+
+        // 100_000 * 10 / 990 = 1010.10 quote for 10 base
+        assert!((fill.average_price - 101.0101).abs() < 1e-3);
+        assert!((fill.price_impact_bps - 101.01).abs() < 0.1);
+        assert!((fill.costs.dex_fee - 1010.101 * 0.0025).abs() < 1e-3);
+        assert!(fill.costs.jito_tip > 0.0 && fill.costs.priority_fee > 0.0);
+
+        // Our buy moved the pool: the next identical order is more expensive
+        let next = sim.simulate(&approved(TradeAction::Buy, 10.0, 100.0));
+        assert!(next.average_price > fill.average_price);
+    }
+
+    #[test]
+    fn test_partial_fill_within_slippage_limit() {
+        let sim = AmmFillSimulator::new(FillSimulatorConfig {
+            max_slippage_bps: 100.0,
+            ..deterministic()
+        });
+        sim.update_pool("SOL/USDC", PoolReserves::new(1_000.0, 100_000.0));
+
+        let fill = sim.simulate(&approved(TradeAction::Buy, 50.0, 100.0));
+        assert!(fill.is_partial());
+        assert!((fill.filled_quantity - 1_000.0 * 0.01 / 1.01).abs() < 1e-9);
+        assert!(fill.average_price <= 101.0 + 1e-9);
+
+        // Sell side mirrors the constraint
+        let sim = AmmFillSimulator::new(FillSimulatorConfig {
+            max_slippage_bps: 100.0,
+            ..deterministic()
+        });
+        sim.update_pool("SOL/USDC", PoolReserves::new(1_000.0, 100_000.0));
+        let fill = sim.simulate(&approved(TradeAction::Sell, 50.0, 100.0));
+        assert!(fill.is_partial());
+        assert!(fill.average_price >= 99.0 - 1e-9);
+    }
+
+    #[test]
+    fn test_failures_still_pay_network_fees() {
+        let sim = AmmFillSimulator::new(FillSimulatorConfig {
+            failure_probability: 1.0,
+            ..deterministic()
+        });
+        let fill = sim.simulate(&approved(TradeAction::Buy, 1.0, 100.0));
+
+        assert!(matches!(fill.status, ExecutionStatus::Failed));
+        assert_eq!(fill.filled_quantity, 0.0);
+        assert_eq!(fill.costs.jito_tip, 0.0);
+        assert!(fill.costs.network_fee > 0.0);
+        assert_eq!(fill.latency, Duration::from_millis(150 + 400));
+    }
+
+    #[test]
+    fn test_seeded_runs_are_reproducible() {
+        let config = FillSimulatorConfig {
+            failure_probability: 0.3,
+            seed: Some(42),
+            ..Default::default()
+        };
+        let run = || {
+            let sim = AmmFillSimulator::new(config.clone());
+            (0..20)
+                .map(|_| {
+                    let fill = sim.simulate(&approved(TradeAction::Buy, 5.0, 100.0));
+                    (fill.filled_quantity, fill.average_price, fill.latency)
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(run(), run());
+    }
+}
@@ -11,7 +11,7 @@ use solana_sdk::{
     pubkey::Pubkey,
     signature::{Keypair, Signature},
     signer::Signer,
-    transaction::Transaction,
+    transaction::{Transaction, VersionedTransaction},
 };
 use std::{str::FromStr, time::Duration};
 use tokio::time::sleep;
@@ -259,7 +259,10 @@ impl HftEngine {
     }
 
     /// Create basic transaction without TensorZero optimization
-    async fn create_basic_transaction(&self, signal: &TradingSignal) -> Result<Transaction> {
+    async fn create_basic_transaction(
+        &self,
+        signal: &TradingSignal,
+    ) -> Result<VersionedTransaction> {
         debug!(
             "Creating basic transaction for {} {}",
             signal.action, signal.symbol
@@ -281,11 +284,14 @@ impl HftEngine {
             recent_blockhash,
         );
 
-        Ok(transaction)
+        Ok(VersionedTransaction::from(transaction))
     }
 
     /// Optimize transaction parameters using TensorZero
-    async fn optimize_with_tensorzero(&self, signal: &TradingSignal) -> Result<Transaction> {
+    async fn optimize_with_tensorzero(
+        &self,
+        signal: &TradingSignal,
+    ) -> Result<VersionedTransaction> {
         debug!(
             "Optimizing transaction with TensorZero for {}",
             signal.symbol
@@ -336,7 +342,7 @@ impl HftEngine {
     }
 
     /// Execute transaction with retry logic
-    async fn execute_with_retry(&self, transaction: Transaction) -> Result<Signature> {
+    async fn execute_with_retry(&self, transaction: VersionedTransaction) -> Result<Signature> {
         let mut attempts = 0;
         let max_attempts = self.config.retry_attempts as usize;
 
@@ -362,7 +368,7 @@ impl HftEngine {
     }
 
     /// Execute a single transaction attempt
-    async fn execute_transaction(&self, transaction: Transaction) -> Result<Signature> {
+    async fn execute_transaction(&self, transaction: VersionedTransaction) -> Result<Signature> {
         if self.config.use_jito_bundles {
             self.execute_with_jito_bundle(transaction).await
         } else {
@@ -371,7 +377,10 @@ impl HftEngine {
     }
 
     /// Execute transaction using Jito bundles for MEV protection
-    async fn execute_with_jito_bundle(&self, transaction: Transaction) -> Result<Signature> {
+    async fn execute_with_jito_bundle(
+        &self,
+        transaction: VersionedTransaction,
+    ) -> Result<Signature> {
         if let Some(ref jito_client) = self.jito_client {
             info!("🛡️ Executing transaction via Jito bundle for MEV protection");
 
@@ -407,7 +416,10 @@ impl HftEngine {
     }
 
     /// Execute transaction using standard Solana RPC
-    async fn execute_with_standard_rpc(&self, transaction: Transaction) -> Result<Signature> {
+    async fn execute_with_standard_rpc(
+        &self,
+        transaction: VersionedTransaction,
+    ) -> Result<Signature> {
         // In production, this would send the transaction to the Solana network
         // For now, we'll just return a mock signature
 
@@ -422,7 +434,7 @@ impl HftEngine {
         &self,
         signal: &TradingSignal,
         optimization: &OptimizationResponse,
-    ) -> Result<Transaction> {
+    ) -> Result<VersionedTransaction> {
         info!("🧠 Building optimized transaction with TensorZero parameters");
 
         debug!("Using optimized parameters:");
@@ -449,7 +461,7 @@ impl HftEngine {
         &self,
         signal: &TradingSignal,
         optimization: Option<&OptimizationResponse>,
-    ) -> Result<Transaction> {
+    ) -> Result<VersionedTransaction> {
         info!(
             "🔄 Building DEX transaction for {} {}",
             signal.action, signal.symbol
@@ -533,7 +545,7 @@ impl HftEngine {
 
     /// Build a mock transaction for testing
     #[allow(dead_code)]
-    fn build_mock_transaction(&self, signal: &TradingSignal) -> Result<Transaction> {
+    fn build_mock_transaction(&self, signal: &TradingSignal) -> Result<VersionedTransaction> {
         // Create a simple system transfer of 1 lamport from wallet to itself
         // This creates a real, valid Solana transaction for testing purposes
 
@@ -562,7 +574,7 @@ impl HftEngine {
             signal.action, signal.symbol
         );
 
-        Ok(transaction)
+        Ok(VersionedTransaction::from(transaction))
     }
 
     /// Get current performance metrics
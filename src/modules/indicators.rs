@@ -0,0 +1,1069 @@
+// Indicators Module
+// Streaming technical indicators, O(1) per tick. Ticks are folded into OHLCV
+// bars per token and timeframe; every indicator updates once per closed bar
+// from running sums or recursive averages, never by rescanning history.
+
+use crate::modules::data_ingestor::MarketData;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
+use std::sync::Arc;
+
+/// Engine shared between the event dispatcher and the strategies reading it
+pub type SharedIndicators = Arc<RwLock<IndicatorEngine>>;
+
+/// Bar width; `Tick` closes a bar on every tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Timeframe {
+    Tick,
+    S15,
+    M1,
+    M5,
+    M15,
+    H1,
+}
+
+impl Timeframe {
+    /// Bar width in milliseconds, `None` for `Tick`
+    pub fn millis(&self) -> Option<i64> {
+        match self {
+            Timeframe::Tick => None,
+            Timeframe::S15 => Some(15_000),
+            Timeframe::M1 => Some(60_000),
+            Timeframe::M5 => Some(300_000),
+            Timeframe::M15 => Some(900_000),
+            Timeframe::H1 => Some(3_600_000),
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<Self> {
+        match s {
+            "tick" => Ok(Timeframe::Tick),
+            "15s" => Ok(Timeframe::S15),
+            "1m" => Ok(Timeframe::M1),
+            "5m" => Ok(Timeframe::M5),
+            "15m" => Ok(Timeframe::M15),
+            "1h" => Ok(Timeframe::H1),
+            _ => Err(anyhow!(
+                "Unknown timeframe '{}'; expected tick, 15s, 1m, 5m, 15m or 1h",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Timeframe {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Timeframe::Tick => "tick",
+            Timeframe::S15 => "15s",
+            Timeframe::M1 => "1m",
+            Timeframe::M5 => "5m",
+            Timeframe::M15 => "15m",
+            Timeframe::H1 => "1h",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// OHLCV bar
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Bar {
+    pub start: DateTime<Utc>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+impl Bar {
+    fn open_at(start: DateTime<Utc>, price: f64, volume: f64) -> Self {
+        Self {
+            start,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume,
+        }
+    }
+
+    fn add(&mut self, price: f64, volume: f64) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        self.volume += volume;
+    }
+
+    /// Typical price `(high + low + close) / 3`, used by VWAP
+    pub fn typical_price(&self) -> f64 {
+        (self.high + self.low + self.close) / 3.0
+    }
+}
+
+/// Input series of a moving average
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IndicatorSource {
+    Price,
+    Volume,
+}
+
+impl IndicatorSource {
+    fn of(&self, bar: &Bar) -> f64 {
+        match self {
+            IndicatorSource::Price => bar.close,
+            IndicatorSource::Volume => bar.volume,
+        }
+    }
+}
+
+/// Declarative indicator configuration
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum IndicatorSpec {
+    Sma {
+        period: usize,
+        source: IndicatorSource,
+    },
+    Ema {
+        period: usize,
+        source: IndicatorSource,
+    },
+    Rsi {
+        period: usize,
+    },
+    Macd {
+        fast: usize,
+        slow: usize,
+        signal: usize,
+    },
+    /// Rolling VWAP over the last `period` bars
+    Vwap {
+        period: usize,
+    },
+    Atr {
+        period: usize,
+    },
+    Bollinger {
+        period: usize,
+        std_dev: f64,
+    },
+    /// Bar volume divided by the average volume of the previous `period` bars
+    VolumeSpike {
+        period: usize,
+    },
+}
+
+impl IndicatorSpec {
+    /// Feature names this indicator publishes when declared as `name`
+    pub fn outputs(&self, name: &str) -> Vec<String> {
+        match self {
+            IndicatorSpec::Macd { .. } => vec![
+                name.to_string(),
+                format!("{}_signal", name),
+                format!("{}_histogram", name),
+            ],
+            IndicatorSpec::Bollinger { .. } => vec![
+                format!("{}_upper", name),
+                format!("{}_middle", name),
+                format!("{}_lower", name),
+            ],
+            _ => vec![name.to_string()],
+        }
+    }
+
+    fn validate(&self) -> Result<()> {
+        let periods: &[usize] = match self {
+            IndicatorSpec::Sma { period, .. }
+            | IndicatorSpec::Ema { period, .. }
+            | IndicatorSpec::Rsi { period }
+            | IndicatorSpec::Vwap { period }
+            | IndicatorSpec::Atr { period }
+            | IndicatorSpec::VolumeSpike { period } => &[*period],
+            IndicatorSpec::Bollinger { period, std_dev } => {
+                if !(std_dev.is_finite() && *std_dev > 0.0) {
+                    return Err(anyhow!("Bollinger std_dev must be positive"));
+                }
+                &[*period]
+            }
+            IndicatorSpec::Macd { fast, slow, signal } => {
+                if fast >= slow {
+                    return Err(anyhow!(
+                        "MACD fast period ({}) must be shorter than slow period ({})",
+                        fast,
+                        slow
+                    ));
+                }
+                &[*fast, *slow, *signal]
+            }
+        };
+        if periods.contains(&0) {
+            return Err(anyhow!("Indicator period must be at least 1"));
+        }
+        Ok(())
+    }
+
+    fn build(&self) -> Indicator {
+        match *self {
+            IndicatorSpec::Sma { period, source } => Indicator::Sma(Sma::new(period), source),
+            IndicatorSpec::Ema { period, source } => Indicator::Ema(Ema::new(period), source),
+            IndicatorSpec::Rsi { period } => Indicator::Rsi(Rsi::new(period)),
+            IndicatorSpec::Macd { fast, slow, signal } => {
+                Indicator::Macd(Macd::new(fast, slow, signal))
+            }
+            IndicatorSpec::Vwap { period } => Indicator::Vwap(Vwap::new(period)),
+            IndicatorSpec::Atr { period } => Indicator::Atr(Atr::new(period)),
+            IndicatorSpec::Bollinger { period, std_dev } => {
+                Indicator::Bollinger(Bollinger::new(period, std_dev))
+            }
+            IndicatorSpec::VolumeSpike { period } => {
+                Indicator::VolumeSpike(VolumeSpike::new(period))
+            }
+        }
+    }
+}
+
+/// Named indicator computed on one timeframe
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndicatorDefinition {
+    pub name: String,
+    pub spec: IndicatorSpec,
+    pub timeframe: Timeframe,
+}
+
+/// Simple moving average over a ring buffer with a running sum
+#[derive(Debug, Clone)]
+pub struct Sma {
+    period: usize,
+    window: VecDeque<f64>,
+    sum: f64,
+}
+
+impl Sma {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1),
+            sum: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        self.window.push_back(value);
+        self.sum += value;
+        if self.window.len() > self.period {
+            self.sum -= self.window.pop_front().unwrap_or(0.0);
+        }
+        self.value()
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        (self.window.len() == self.period).then(|| self.sum / self.period as f64)
+    }
+}
+
+/// Exponential moving average, seeded with the SMA of the first `period` values
+#[derive(Debug, Clone)]
+pub struct Ema {
+    period: usize,
+    alpha: f64,
+    count: usize,
+    seed: f64,
+    value: Option<f64>,
+}
+
+impl Ema {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            alpha: 2.0 / (period as f64 + 1.0),
+            count: 0,
+            seed: 0.0,
+            value: None,
+        }
+    }
+
+    pub fn update(&mut self, value: f64) -> Option<f64> {
+        self.value = match self.value {
+            Some(previous) => Some(previous + self.alpha * (value - previous)),
+            None => {
+                self.count += 1;
+                self.seed += value;
+                (self.count == self.period).then(|| self.seed / self.period as f64)
+            }
+        };
+        self.value
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+/// Wilder's RSI
+#[derive(Debug, Clone)]
+pub struct Rsi {
+    period: usize,
+    previous: Option<f64>,
+    count: usize,
+    avg_gain: f64,
+    avg_loss: f64,
+}
+
+impl Rsi {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            previous: None,
+            count: 0,
+            avg_gain: 0.0,
+            avg_loss: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, close: f64) -> Option<f64> {
+        let previous = self.previous.replace(close)?;
+        let change = close - previous;
+        let (gain, loss) = (change.max(0.0), (-change).max(0.0));
+        let period = self.period as f64;
+
+        if self.count < self.period {
+            // Seed with simple averages of the first `period` changes
+            self.count += 1;
+            self.avg_gain += gain / period;
+            self.avg_loss += loss / period;
+        } else {
+            self.avg_gain = (self.avg_gain * (period - 1.0) + gain) / period;
+            self.avg_loss = (self.avg_loss * (period - 1.0) + loss) / period;
+        }
+        self.value()
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        if self.count < self.period {
+            return None;
+        }
+        if self.avg_loss == 0.0 {
+            // Flat series is neutral, only gains is maximally overbought
+            return Some(if self.avg_gain == 0.0 { 50.0 } else { 100.0 });
+        }
+        let rs = self.avg_gain / self.avg_loss;
+        Some(100.0 - 100.0 / (1.0 + rs))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MacdValue {
+    pub macd: f64,
+    pub signal: f64,
+    pub histogram: f64,
+}
+
+/// MACD line, signal line and histogram
+#[derive(Debug, Clone)]
+pub struct Macd {
+    fast: Ema,
+    slow: Ema,
+    signal: Ema,
+    value: Option<MacdValue>,
+}
+
+impl Macd {
+    pub fn new(fast: usize, slow: usize, signal: usize) -> Self {
+        Self {
+            fast: Ema::new(fast),
+            slow: Ema::new(slow),
+            signal: Ema::new(signal),
+            value: None,
+        }
+    }
+
+    pub fn update(&mut self, close: f64) -> Option<MacdValue> {
+        let fast = self.fast.update(close);
+        let slow = self.slow.update(close);
+        if let (Some(fast), Some(slow)) = (fast, slow) {
+            let macd = fast - slow;
+            if let Some(signal) = self.signal.update(macd) {
+                self.value = Some(MacdValue {
+                    macd,
+                    signal,
+                    histogram: macd - signal,
+                });
+            }
+        }
+        self.value
+    }
+
+    pub fn value(&self) -> Option<MacdValue> {
+        self.value
+    }
+}
+
+/// Rolling volume-weighted average price over `period` bars
+#[derive(Debug, Clone)]
+pub struct Vwap {
+    period: usize,
+    window: VecDeque<(f64, f64)>,
+    price_volume: f64,
+    volume: f64,
+}
+
+impl Vwap {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            window: VecDeque::with_capacity(period + 1),
+            price_volume: 0.0,
+            volume: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, bar: &Bar) -> Option<f64> {
+        let entry = (bar.typical_price() * bar.volume, bar.volume);
+        self.window.push_back(entry);
+        self.price_volume += entry.0;
+        self.volume += entry.1;
+        if self.window.len() > self.period {
+            if let Some((pv, v)) = self.window.pop_front() {
+                self.price_volume -= pv;
+                self.volume -= v;
+            }
+        }
+        self.value()
+    }
+
+    /// Available from the first bar with volume; covers up to `period` bars
+    pub fn value(&self) -> Option<f64> {
+        (self.volume > 0.0).then(|| self.price_volume / self.volume)
+    }
+}
+
+/// Wilder's average true range
+#[derive(Debug, Clone)]
+pub struct Atr {
+    period: usize,
+    previous_close: Option<f64>,
+    count: usize,
+    value: f64,
+}
+
+impl Atr {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period,
+            previous_close: None,
+            count: 0,
+            value: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, bar: &Bar) -> Option<f64> {
+        let range = bar.high - bar.low;
+        let true_range = match self.previous_close.replace(bar.close) {
+            Some(close) => range
+                .max((bar.high - close).abs())
+                .max((bar.low - close).abs()),
+            None => range,
+        };
+        let period = self.period as f64;
+        if self.count < self.period {
+            self.count += 1;
+            self.value += true_range / period;
+        } else {
+            self.value = (self.value * (period - 1.0) + true_range) / period;
+        }
+        self.value()
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        (self.count == self.period).then_some(self.value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BollingerValue {
+    pub upper: f64,
+    pub middle: f64,
+    pub lower: f64,
+}
+
+/// Bollinger bands from a rolling sum and sum of squares
+#[derive(Debug, Clone)]
+pub struct Bollinger {
+    period: usize,
+    std_dev: f64,
+    window: VecDeque<f64>,
+    sum: f64,
+    sum_sq: f64,
+}
+
+impl Bollinger {
+    pub fn new(period: usize, std_dev: f64) -> Self {
+        Self {
+            period,
+            std_dev,
+            window: VecDeque::with_capacity(period + 1),
+            sum: 0.0,
+            sum_sq: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, close: f64) -> Option<BollingerValue> {
+        self.window.push_back(close);
+        self.sum += close;
+        self.sum_sq += close * close;
+        if self.window.len() > self.period {
+            let old = self.window.pop_front().unwrap_or(0.0);
+            self.sum -= old;
+            self.sum_sq -= old * old;
+        }
+        self.value()
+    }
+
+    pub fn value(&self) -> Option<BollingerValue> {
+        if self.window.len() < self.period {
+            return None;
+        }
+        let n = self.period as f64;
+        let mean = self.sum / n;
+        // Population variance; clamp the rounding error of the running sums
+        let deviation = (self.sum_sq / n - mean * mean).max(0.0).sqrt() * self.std_dev;
+        Some(BollingerValue {
+            upper: mean + deviation,
+            middle: mean,
+            lower: mean - deviation,
+        })
+    }
+}
+
+/// Current bar volume relative to the average of the previous `period` bars
+#[derive(Debug, Clone)]
+pub struct VolumeSpike {
+    baseline: Sma,
+    value: Option<f64>,
+}
+
+impl VolumeSpike {
+    pub fn new(period: usize) -> Self {
+        Self {
+            baseline: Sma::new(period),
+            value: None,
+        }
+    }
+
+    pub fn update(&mut self, volume: f64) -> Option<f64> {
+        self.value = self
+            .baseline
+            .value()
+            .filter(|average| *average > 0.0)
+            .map(|average| volume / average);
+        self.baseline.update(volume);
+        self.value
+    }
+
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Indicator {
+    Sma(Sma, IndicatorSource),
+    Ema(Ema, IndicatorSource),
+    Rsi(Rsi),
+    Macd(Macd),
+    Vwap(Vwap),
+    Atr(Atr),
+    Bollinger(Bollinger),
+    VolumeSpike(VolumeSpike),
+}
+
+impl Indicator {
+    fn update(&mut self, bar: &Bar) {
+        match self {
+            Indicator::Sma(sma, source) => {
+                sma.update(source.of(bar));
+            }
+            Indicator::Ema(ema, source) => {
+                ema.update(source.of(bar));
+            }
+            Indicator::Rsi(rsi) => {
+                rsi.update(bar.close);
+            }
+            Indicator::Macd(macd) => {
+                macd.update(bar.close);
+            }
+            Indicator::Vwap(vwap) => {
+                vwap.update(bar);
+            }
+            Indicator::Atr(atr) => {
+                atr.update(bar);
+            }
+            Indicator::Bollinger(bands) => {
+                bands.update(bar.close);
+            }
+            Indicator::VolumeSpike(spike) => {
+                spike.update(bar.volume);
+            }
+        }
+    }
+
+    /// Values in the order of `IndicatorSpec::outputs`; `None` while warming up
+    fn values(&self) -> Option<Vec<f64>> {
+        match self {
+            Indicator::Sma(sma, _) => sma.value().map(|v| vec![v]),
+            Indicator::Ema(ema, _) => ema.value().map(|v| vec![v]),
+            Indicator::Rsi(rsi) => rsi.value().map(|v| vec![v]),
+            Indicator::Macd(macd) => macd.value().map(|v| vec![v.macd, v.signal, v.histogram]),
+            Indicator::Vwap(vwap) => vwap.value().map(|v| vec![v]),
+            Indicator::Atr(atr) => atr.value().map(|v| vec![v]),
+            Indicator::Bollinger(bands) => bands.value().map(|v| vec![v.upper, v.middle, v.lower]),
+            Indicator::VolumeSpike(spike) => spike.value().map(|v| vec![v]),
+        }
+    }
+}
+
+/// Bars and indicator state of one token on one timeframe
+#[derive(Debug, Clone)]
+struct Series {
+    timeframe: Timeframe,
+    current: Option<Bar>,
+    closed_bars: u64,
+    /// Indices into `IndicatorEngine::definitions`
+    indicators: Vec<(usize, Indicator)>,
+}
+
+impl Series {
+    fn on_tick(&mut self, price: f64, volume: f64, at: DateTime<Utc>) {
+        let Some(width) = self.timeframe.millis() else {
+            self.close(Bar::open_at(at, price, volume));
+            return;
+        };
+
+        let bucket = at.timestamp_millis().div_euclid(width) * width;
+        let start = DateTime::from_timestamp_millis(bucket).unwrap_or(at);
+        match &mut self.current {
+            // Late ticks are folded into the open bar rather than reopening history
+            Some(bar) if start <= bar.start => bar.add(price, volume),
+            _ => {
+                if let Some(bar) = self.current.take() {
+                    self.close(bar);
+                }
+                self.current = Some(Bar::open_at(start, price, volume));
+            }
+        }
+    }
+
+    fn close(&mut self, bar: Bar) {
+        self.closed_bars += 1;
+        for (_, indicator) in &mut self.indicators {
+            indicator.update(&bar);
+        }
+    }
+}
+
+/// Indicators for every token, computed on the timeframes they are declared on.
+///
+/// Values only cover closed bars, so a 1m EMA changes once a minute however
+/// many ticks arrive; gaps without ticks do not produce empty bars.
+#[derive(Debug, Clone, Default)]
+pub struct IndicatorEngine {
+    definitions: Vec<IndicatorDefinition>,
+    /// One series per declared timeframe for every token seen so far
+    series: HashMap<String, Vec<Series>>,
+}
+
+impl IndicatorEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set used by the memcoin strategies: trend, momentum and volatility on 1m bars
+    pub fn memcoin_default() -> Self {
+        let price = IndicatorSource::Price;
+        let mut engine = Self::new();
+        for (name, spec) in [
+            (
+                "ema_fast",
+                IndicatorSpec::Ema {
+                    period: 12,
+                    source: price,
+                },
+            ),
+            (
+                "ema_slow",
+                IndicatorSpec::Ema {
+                    period: 26,
+                    source: price,
+                },
+            ),
+            ("rsi", IndicatorSpec::Rsi { period: 14 }),
+            ("atr", IndicatorSpec::Atr { period: 14 }),
+            ("volume_spike", IndicatorSpec::VolumeSpike { period: 20 }),
+        ] {
+            engine
+                .add(IndicatorDefinition {
+                    name: name.to_string(),
+                    spec,
+                    timeframe: Timeframe::M1,
+                })
+                .expect("built-in indicator set is valid");
+        }
+        engine
+    }
+
+    pub fn with_indicator(
+        mut self,
+        name: &str,
+        spec: IndicatorSpec,
+        timeframe: Timeframe,
+    ) -> Result<Self> {
+        self.add(IndicatorDefinition {
+            name: name.to_string(),
+            spec,
+            timeframe,
+        })?;
+        Ok(self)
+    }
+
+    /// Add an indicator; tokens already tracked start computing it from
+    /// their next closed bar
+    pub fn add(&mut self, definition: IndicatorDefinition) -> Result<()> {
+        definition.spec.validate()?;
+        let outputs = definition.spec.outputs(&definition.name);
+        if let Some(taken) = self
+            .definitions
+            .iter()
+            .flat_map(|d| d.spec.outputs(&d.name))
+            .find(|name| outputs.contains(name))
+        {
+            return Err(anyhow!("Indicator output '{}' is already defined", taken));
+        }
+
+        let index = self.definitions.len();
+        for token_series in self.series.values_mut() {
+            match token_series
+                .iter_mut()
+                .find(|s| s.timeframe == definition.timeframe)
+            {
+                Some(series) => series.indicators.push((index, definition.spec.build())),
+                None => token_series.push(Series {
+                    timeframe: definition.timeframe,
+                    current: None,
+                    closed_bars: 0,
+                    indicators: vec![(index, definition.spec.build())],
+                }),
+            }
+        }
+        self.definitions.push(definition);
+        Ok(())
+    }
+
+    pub fn definitions(&self) -> &[IndicatorDefinition] {
+        &self.definitions
+    }
+
+    /// Every feature name the engine can publish
+    pub fn outputs(&self) -> Vec<String> {
+        self.definitions
+            .iter()
+            .flat_map(|d| d.spec.outputs(&d.name))
+            .collect()
+    }
+
+    pub fn on_market_data(&mut self, data: &MarketData) {
+        self.on_tick(&data.symbol, data.price, data.volume, data.timestamp);
+    }
+
+    /// Fold a trade/quote tick into every timeframe of `token`
+    pub fn on_tick(&mut self, token: &str, price: f64, volume: f64, at: DateTime<Utc>) {
+        if !price.is_finite() || !volume.is_finite() {
+            return;
+        }
+        if !self.series.contains_key(token) {
+            let series = self.new_series();
+            self.series.insert(token.to_string(), series);
+        }
+        if let Some(token_series) = self.series.get_mut(token) {
+            for series in token_series {
+                series.on_tick(price, volume, at);
+            }
+        }
+    }
+
+    fn new_series(&self) -> Vec<Series> {
+        let mut series: Vec<Series> = Vec::new();
+        for (index, definition) in self.definitions.iter().enumerate() {
+            let indicator = (index, definition.spec.build());
+            match series
+                .iter_mut()
+                .find(|s| s.timeframe == definition.timeframe)
+            {
+                Some(existing) => existing.indicators.push(indicator),
+                None => series.push(Series {
+                    timeframe: definition.timeframe,
+                    current: None,
+                    closed_bars: 0,
+                    indicators: vec![indicator],
+                }),
+            }
+        }
+        series
+    }
+
+    fn series(&self, token: &str, timeframe: Timeframe) -> Option<&Series> {
+        self.series
+            .get(token)?
+            .iter()
+            .find(|s| s.timeframe == timeframe)
+    }
+
+    /// Current value of one output for `token`; `None` until warmed up
+    pub fn value(&self, token: &str, output: &str) -> Option<f64> {
+        self.series.get(token)?.iter().find_map(|series| {
+            series.indicators.iter().find_map(|(index, indicator)| {
+                let definition = &self.definitions[*index];
+                let position = definition
+                    .spec
+                    .outputs(&definition.name)
+                    .iter()
+                    .position(|name| name == output)?;
+                indicator.values()?.get(position).copied()
+            })
+        })
+    }
+
+    /// Every warmed-up output for `token`
+    pub fn values(&self, token: &str) -> BTreeMap<String, f64> {
+        let mut values = BTreeMap::new();
+        for series in self.series.get(token).into_iter().flatten() {
+            for (index, indicator) in &series.indicators {
+                let definition = &self.definitions[*index];
+                if let Some(outputs) = indicator.values() {
+                    values.extend(
+                        definition
+                            .spec
+                            .outputs(&definition.name)
+                            .into_iter()
+                            .zip(outputs),
+                    );
+                }
+            }
+        }
+        values
+    }
+
+    /// Open (not yet closed) bar of `token` on `timeframe`
+    pub fn current_bar(&self, token: &str, timeframe: Timeframe) -> Option<Bar> {
+        self.series(token, timeframe)?.current
+    }
+
+    /// Number of bars closed so far for `token` on `timeframe`
+    pub fn closed_bars(&self, token: &str, timeframe: Timeframe) -> u64 {
+        self.series(token, timeframe).map_or(0, |s| s.closed_bars)
+    }
+
+    /// Drop all state of a token, e.g. once it is no longer traded
+    pub fn remove_token(&mut self, token: &str) {
+        self.series.remove(token);
+    }
+
+    pub fn into_shared(self) -> SharedIndicators {
+        Arc::new(RwLock::new(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        // Minute-aligned so 0..59 fall into one 1m bar
+        Utc.timestamp_opt(1_699_999_980 + seconds, 0).unwrap()
+    }
+
+    fn bar(high: f64, low: f64, close: f64, volume: f64) -> Bar {
+        Bar {
+            start: at(0),
+            open: close,
+            high,
+            low,
+            close,
+            volume,
+        }
+    }
+
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.expect("indicator should be ready");
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_moving_averages() {
+        let mut sma = Sma::new(3);
+        assert_eq!(sma.update(1.0), None);
+        assert_eq!(sma.update(2.0), None);
+        assert_close(sma.update(3.0), 2.0);
+        assert_close(sma.update(7.0), 4.0);
+
+        let mut ema = Ema::new(3);
+        ema.update(1.0);
+        ema.update(2.0);
+        assert_close(ema.update(3.0), 2.0);
+        assert_close(ema.update(4.0), 3.0);
+    }
+
+    #[test]
+    fn test_rsi_matches_wilder_reference() {
+        // Wilder's 14-period example closes
+        let closes = [
+            44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08, 45.89, 46.03,
+            45.61, 46.28, 46.28, 46.00,
+        ];
+        let mut rsi = Rsi::new(14);
+        let values: Vec<Option<f64>> = closes.iter().map(|c| rsi.update(*c)).collect();
+        assert!(values[..14].iter().all(Option::is_none));
+        assert!(
+            (values[14].unwrap() - 70.46).abs() < 0.01,
+            "{:?}",
+            values[14]
+        );
+        assert!(
+            (values[15].unwrap() - 66.25).abs() < 0.01,
+            "{:?}",
+            values[15]
+        );
+
+        let mut flat = Rsi::new(2);
+        for _ in 0..4 {
+            flat.update(1.0);
+        }
+        assert_close(flat.value(), 50.0);
+    }
+
+    #[test]
+    fn test_macd_bollinger_atr_vwap() {
+        let mut macd = Macd::new(2, 3, 2);
+        let mut last = None;
+        for close in [1.0, 2.0, 3.0, 4.0, 5.0] {
+            last = macd.update(close);
+        }
+        // Linear series: fast and slow EMAs settle one step apart
+        let value = last.unwrap();
+        assert!(value.macd > 0.0);
+        assert!((value.macd - value.signal - value.histogram).abs() < 1e-12);
+
+        let mut bands = Bollinger::new(4, 2.0);
+        let mut value = None;
+        for close in [2.0, 4.0, 4.0, 6.0] {
+            value = bands.update(close);
+        }
+        let value = value.unwrap();
+        assert_close(Some(value.middle), 4.0);
+        assert_close(Some(value.upper), 4.0 + 2.0 * 2f64.sqrt());
+
+        let mut atr = Atr::new(2);
+        assert_eq!(atr.update(&bar(10.0, 8.0, 9.0, 1.0)), None);
+        // True range uses the gap from the previous close: 12 - 9 = 3
+        assert_close(atr.update(&bar(12.0, 11.0, 11.5, 1.0)), 2.5);
+        assert_close(atr.update(&bar(12.0, 11.0, 11.5, 1.0)), 1.75);
+
+        let mut vwap = Vwap::new(2);
+        vwap.update(&bar(10.0, 10.0, 10.0, 1.0));
+        assert_close(vwap.update(&bar(20.0, 20.0, 20.0, 3.0)), 17.5);
+        assert_close(vwap.update(&bar(30.0, 30.0, 30.0, 1.0)), 22.5);
+    }
+
+    #[test]
+    fn test_volume_spike_uses_previous_bars() {
+        let mut spike = VolumeSpike::new(2);
+        assert_eq!(spike.update(100.0), None);
+        assert_eq!(spike.update(100.0), None);
+        assert_close(spike.update(300.0), 3.0);
+        assert_close(spike.update(200.0), 1.0);
+    }
+
+    #[test]
+    fn test_engine_buckets_ticks_per_token_and_timeframe() {
+        let mut engine = IndicatorEngine::new()
+            .with_indicator(
+                "sma_1m",
+                IndicatorSpec::Sma {
+                    period: 2,
+                    source: IndicatorSource::Price,
+                },
+                Timeframe::M1,
+            )
+            .unwrap()
+            .with_indicator(
+                "vol_tick",
+                IndicatorSpec::Sma {
+                    period: 2,
+                    source: IndicatorSource::Volume,
+                },
+                Timeframe::Tick,
+            )
+            .unwrap();
+
+        // First minute: closes at 2.0; second minute closes at 4.0
+        engine.on_tick("BONK", 1.0, 10.0, at(0));
+        engine.on_tick("BONK", 2.0, 30.0, at(30));
+        engine.on_tick("WIF", 100.0, 1.0, at(30));
+        engine.on_tick("BONK", 4.0, 5.0, at(60));
+        assert_eq!(engine.value("BONK", "sma_1m"), None);
+        assert_close(engine.value("BONK", "vol_tick"), 17.5);
+
+        engine.on_tick("BONK", 6.0, 5.0, at(120));
+        assert_close(engine.value("BONK", "sma_1m"), 3.0);
+        assert_eq!(engine.closed_bars("BONK", Timeframe::M1), 2);
+        assert_eq!(
+            engine.current_bar("BONK", Timeframe::M1).unwrap().close,
+            6.0
+        );
+        assert_eq!(engine.value("WIF", "sma_1m"), None);
+
+        engine.remove_token("BONK");
+        assert!(engine.values("BONK").is_empty());
+    }
+
+    #[test]
+    fn test_engine_rejects_bad_definitions() {
+        let engine = IndicatorEngine::memcoin_default();
+        assert!(engine
+            .clone()
+            .with_indicator("rsi", IndicatorSpec::Rsi { period: 7 }, Timeframe::M5)
+            .is_err());
+        assert!(engine
+            .clone()
+            .with_indicator(
+                "macd",
+                IndicatorSpec::Macd {
+                    fast: 26,
+                    slow: 12,
+                    signal: 9
+                },
+                Timeframe::M1
+            )
+            .is_err());
+        assert!(engine
+            .with_indicator("flat", IndicatorSpec::Atr { period: 0 }, Timeframe::M1)
+            .is_err());
+        assert_eq!(
+            IndicatorSpec::Bollinger {
+                period: 20,
+                std_dev: 2.0
+            }
+            .outputs("bb"),
+            vec!["bb_upper", "bb_middle", "bb_lower"]
+        );
+    }
+}
@@ -114,8 +114,8 @@ impl JitoClient {
         })
     }
 
-    /// Execute transaction using Jito bundle
-    pub async fn execute_bundle(&self, transaction: Transaction) -> Result<BundleResult> {
+    /// Execute a legacy or versioned transaction using Jito bundle
+    pub async fn execute_bundle<T: Serialize>(&self, transaction: T) -> Result<BundleResult> {
         let start_time = Instant::now();
 
         info!("🚀 Executing transaction via Jito bundle for MEV protection");
@@ -185,7 +185,7 @@ impl JitoClient {
     }
 
     /// Serialize transaction to base64
-    fn serialize_transaction(&self, transaction: &Transaction) -> Result<String> {
+    fn serialize_transaction<T: Serialize>(&self, transaction: &T) -> Result<String> {
         let serialized =
             bincode::serialize(transaction).context("Failed to serialize transaction")?;
 
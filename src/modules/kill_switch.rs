@@ -0,0 +1,560 @@
+// Kill Switch Module
+// One persistent stop for all trading: engaged globally or per strategy,
+// wallet or token, by an operator over HTTP or automatically on daily loss,
+// RPC failure storms and open AI circuits. State is written to disk and
+// reloaded on start, so a halt survives restarts until explicitly released.
+// Executors check it before anything is signed.
+
+use crate::modules::strategy::TradingSignal;
+use anyhow::{anyhow, Context, Result};
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::Json;
+use axum::routing::{get, post};
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+/// What an engaged kill switch stops
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "scope", rename_all = "snake_case")]
+pub enum KillScope {
+    Global,
+    /// Strategy type as displayed, e.g. `token_sniping`
+    Strategy {
+        id: String,
+    },
+    /// Wallet id or public key
+    Wallet {
+        id: String,
+    },
+    /// Trading symbol
+    Token {
+        id: String,
+    },
+}
+
+impl KillScope {
+    pub fn strategy(id: impl Into<String>) -> Self {
+        KillScope::Strategy { id: id.into() }
+    }
+
+    pub fn wallet(id: impl Into<String>) -> Self {
+        KillScope::Wallet { id: id.into() }
+    }
+
+    pub fn token(id: impl Into<String>) -> Self {
+        KillScope::Token { id: id.into() }
+    }
+
+    fn covers(&self, target: &KillTarget) -> bool {
+        match self {
+            KillScope::Global => true,
+            KillScope::Strategy { id } => target.strategy.as_deref() == Some(id.as_str()),
+            KillScope::Wallet { id } => target.wallet.as_deref() == Some(id.as_str()),
+            KillScope::Token { id } => target.token.as_deref() == Some(id.as_str()),
+        }
+    }
+}
+
+impl fmt::Display for KillScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KillScope::Global => write!(f, "global"),
+            KillScope::Strategy { id } => write!(f, "strategy:{}", id),
+            KillScope::Wallet { id } => write!(f, "wallet:{}", id),
+            KillScope::Token { id } => write!(f, "token:{}", id),
+        }
+    }
+}
+
+/// Who or what engaged the switch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KillSource {
+    Operator,
+    DailyLoss,
+    RpcFailureStorm,
+    AiCircuitOpen,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KillEntry {
+    #[serde(flatten)]
+    pub scope: KillScope,
+    pub source: KillSource,
+    pub reason: String,
+    pub engaged_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// The trade about to be signed; unknown parts match no scoped entry
+#[derive(Debug, Clone, Default)]
+pub struct KillTarget {
+    pub strategy: Option<String>,
+    pub wallet: Option<String>,
+    pub token: Option<String>,
+}
+
+impl KillTarget {
+    pub fn for_signal(signal: &TradingSignal) -> Self {
+        Self {
+            strategy: Some(signal.strategy_type.to_string()),
+            wallet: None,
+            token: Some(signal.symbol.clone()),
+        }
+    }
+
+    pub fn token(symbol: impl Into<String>) -> Self {
+        Self {
+            token: Some(symbol.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_wallet(mut self, wallet: Option<String>) -> Self {
+        self.wallet = wallet;
+        self
+    }
+}
+
+/// Thresholds for automatic engagement; all of them engage the global scope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KillSwitchTriggers {
+    pub max_daily_loss: Option<f64>, // Daily PnL below -limit engages
+    pub rpc_failure_threshold: u32,  // Failed RPC calls within the window
+    pub rpc_failure_window_secs: u64,
+    pub ai_services: Vec<String>, // Services whose open circuit engages
+}
+
+impl Default for KillSwitchTriggers {
+    fn default() -> Self {
+        Self {
+            max_daily_loss: None,
+            rpc_failure_threshold: 20,
+            rpc_failure_window_secs: 60,
+            ai_services: vec![
+                "tensorzero".to_string(),
+                "ai_connector".to_string(),
+                "deepseek".to_string(),
+                "jina_ai".to_string(),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct KillSwitchState {
+    entries: Vec<KillEntry>,
+}
+
+#[derive(Debug)]
+pub struct KillSwitch {
+    // None = not persisted (tests, paper tools)
+    path: Option<PathBuf>,
+    triggers: KillSwitchTriggers,
+    state: RwLock<KillSwitchState>,
+    rpc_failures: Mutex<VecDeque<Instant>>,
+}
+
+impl KillSwitch {
+    /// Load the switch persisted at `path`; a missing file starts disengaged.
+    /// An unreadable file is an error rather than a silent reset
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let state = if path.exists() {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read kill switch state: {}", path.display()))?;
+            serde_json::from_str(&content)
+                .with_context(|| format!("Corrupt kill switch state: {}", path.display()))?
+        } else {
+            KillSwitchState::default()
+        };
+
+        let switch = Self {
+            path: Some(path),
+            triggers: KillSwitchTriggers::default(),
+            state: RwLock::new(state),
+            rpc_failures: Mutex::new(VecDeque::new()),
+        };
+        for entry in switch.entries() {
+            warn!(
+                "🛑 Kill switch restored ({}): {} since {}",
+                entry.scope, entry.reason, entry.engaged_at
+            );
+        }
+        Ok(switch)
+    }
+
+    pub fn in_memory() -> Self {
+        Self {
+            path: None,
+            triggers: KillSwitchTriggers::default(),
+            state: RwLock::new(KillSwitchState::default()),
+            rpc_failures: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn with_triggers(mut self, triggers: KillSwitchTriggers) -> Self {
+        self.triggers = triggers;
+        self
+    }
+
+    /// Engage `scope`; returns false when it was already engaged (the
+    /// original entry is kept). Memory is updated before the write, so a
+    /// failed write still halts trading
+    pub fn engage(
+        &self,
+        scope: KillScope,
+        source: KillSource,
+        reason: impl Into<String>,
+    ) -> Result<bool> {
+        let reason = reason.into();
+        {
+            let mut state = self.state.write().unwrap();
+            if state.entries.iter().any(|entry| entry.scope == scope) {
+                return Ok(false);
+            }
+            error!(
+                "🛑 KILL SWITCH ENGAGED ({}) by {:?}: {}",
+                scope, source, reason
+            );
+            state.entries.push(KillEntry {
+                scope,
+                source,
+                reason,
+                engaged_at: chrono::Utc::now(),
+            });
+        }
+        self.persist()?;
+        Ok(true)
+    }
+
+    /// Release `scope`; returns false when it wasn't engaged
+    pub fn release(&self, scope: &KillScope) -> Result<bool> {
+        {
+            let mut state = self.state.write().unwrap();
+            let before = state.entries.len();
+            state.entries.retain(|entry| &entry.scope != scope);
+            if state.entries.len() == before {
+                return Ok(false);
+            }
+        }
+        info!("✅ Kill switch released ({})", scope);
+        self.persist()?;
+        Ok(true)
+    }
+
+    pub fn entries(&self) -> Vec<KillEntry> {
+        self.state.read().unwrap().entries.clone()
+    }
+
+    pub fn is_engaged(&self, scope: &KillScope) -> bool {
+        self.state
+            .read()
+            .unwrap()
+            .entries
+            .iter()
+            .any(|entry| &entry.scope == scope)
+    }
+
+    /// The entry stopping `target`, global first
+    pub fn blocking(&self, target: &KillTarget) -> Option<KillEntry> {
+        let state = self.state.read().unwrap();
+        state
+            .entries
+            .iter()
+            .filter(|entry| entry.scope.covers(target))
+            .min_by_key(|entry| entry.scope != KillScope::Global)
+            .cloned()
+    }
+
+    /// Err naming the engaged scope if `target` may not be signed
+    pub fn check(&self, target: &KillTarget) -> Result<()> {
+        match self.blocking(target) {
+            Some(entry) => Err(anyhow!(
+                "Kill switch engaged ({}): {}",
+                entry.scope,
+                entry.reason
+            )),
+            None => Ok(()),
+        }
+    }
+
+    // ========== AUTOMATIC TRIGGERS ==========
+
+    /// Engage globally once daily PnL falls below the configured loss limit
+    pub fn report_daily_pnl(&self, daily_pnl: f64) {
+        if let Some(limit) = self.triggers.max_daily_loss {
+            if daily_pnl < -limit {
+                self.auto_engage(
+                    KillSource::DailyLoss,
+                    format!("daily pnl {:.2} beyond loss limit {:.2}", daily_pnl, limit),
+                );
+            }
+        }
+    }
+
+    /// Count a failed RPC call; a storm of them within the window engages globally
+    pub fn report_rpc_failure(&self, endpoint: &str) {
+        let window = Duration::from_secs(self.triggers.rpc_failure_window_secs);
+        let failures = {
+            let mut failures = self.rpc_failures.lock().unwrap();
+            let now = Instant::now();
+            failures.push_back(now);
+            while failures
+                .front()
+                .is_some_and(|first| now.duration_since(*first) > window)
+            {
+                failures.pop_front();
+            }
+            failures.len()
+        };
+
+        if failures as u32 >= self.triggers.rpc_failure_threshold {
+            self.auto_engage(
+                KillSource::RpcFailureStorm,
+                format!(
+                    "{} RPC failures within {}s (last on {})",
+                    failures, self.triggers.rpc_failure_window_secs, endpoint
+                ),
+            );
+        }
+    }
+
+    /// Engage globally when an AI service's circuit breaker opens
+    pub fn report_circuit_open(&self, service: &str) {
+        if self.triggers.ai_services.iter().any(|s| s == service) {
+            self.auto_engage(
+                KillSource::AiCircuitOpen,
+                format!("AI circuit breaker open for {}", service),
+            );
+        }
+    }
+
+    fn auto_engage(&self, source: KillSource, reason: String) {
+        if let Err(e) = self.engage(KillScope::Global, source, reason) {
+            error!("❌ Failed to persist kill switch state: {}", e);
+        }
+    }
+
+    fn persist(&self) -> Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+        let content = serde_json::to_string_pretty(&*self.state.read().unwrap())?;
+
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Write-then-rename so a crash never leaves a half-written file
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, content)
+            .with_context(|| format!("Failed to write kill switch state: {}", tmp.display()))?;
+        std::fs::rename(&tmp, path)
+            .with_context(|| format!("Failed to write kill switch state: {}", path.display()))?;
+        Ok(())
+    }
+}
+
+// ========== HTTP API ==========
+
+#[derive(Clone)]
+struct KillSwitchApiState {
+    kill_switch: Arc<KillSwitch>,
+    api_token: Option<Arc<str>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EngageRequest {
+    #[serde(flatten)]
+    scope: KillScope,
+    reason: String,
+}
+
+/// `GET /` lists engaged scopes; `POST /engage` and `POST /release` take a
+/// scope (`{"scope": "token", "id": "BONK"}`, plus `reason` to engage) and
+/// need `Authorization: Bearer <api_token>`. Without a token they are refused
+pub fn create_kill_switch_router(
+    kill_switch: Arc<KillSwitch>,
+    api_token: Option<String>,
+) -> Router {
+    Router::new()
+        .route("/", get(list_entries))
+        .route("/engage", post(engage_scope))
+        .route("/release", post(release_scope))
+        .with_state(KillSwitchApiState {
+            kill_switch,
+            api_token: api_token.filter(|t| !t.is_empty()).map(Arc::from),
+        })
+}
+
+/// Bearer token check, constant time in the token length
+pub(crate) fn authorize(headers: &HeaderMap, api_token: Option<&str>) -> Result<(), StatusCode> {
+    let expected = api_token.ok_or(StatusCode::FORBIDDEN)?;
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let matches = provided.len() == expected.len()
+        && provided
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0;
+    if matches {
+        Ok(())
+    } else {
+        Err(StatusCode::UNAUTHORIZED)
+    }
+}
+
+async fn list_entries(State(state): State<KillSwitchApiState>) -> Json<Vec<KillEntry>> {
+    Json(state.kill_switch.entries())
+}
+
+async fn engage_scope(
+    State(state): State<KillSwitchApiState>,
+    headers: HeaderMap,
+    Json(request): Json<EngageRequest>,
+) -> Result<Json<Vec<KillEntry>>, StatusCode> {
+    authorize(&headers, state.api_token.as_deref())?;
+    state
+        .kill_switch
+        .engage(request.scope, KillSource::Operator, request.reason)
+        .map_err(|e| {
+            error!("❌ Kill switch engage failed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    Ok(Json(state.kill_switch.entries()))
+}
+
+async fn release_scope(
+    State(state): State<KillSwitchApiState>,
+    headers: HeaderMap,
+    Json(scope): Json<KillScope>,
+) -> Result<Json<Vec<KillEntry>>, StatusCode> {
+    authorize(&headers, state.api_token.as_deref())?;
+    let released = state.kill_switch.release(&scope).map_err(|e| {
+        error!("❌ Kill switch release failed: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    if !released {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(state.kill_switch.entries()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoped_state_survives_restart() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("state/kill_switch.json");
+
+        let switch = KillSwitch::open(&path)?;
+        let bonk = KillTarget::token("BONK").with_wallet(Some("hot-1".to_string()));
+        assert!(switch.check(&bonk).is_ok());
+
+        assert!(switch.engage(
+            KillScope::token("BONK"),
+            KillSource::Operator,
+            "rug suspected"
+        )?);
+        assert!(!switch.engage(KillScope::token("BONK"), KillSource::Operator, "again")?);
+        switch.engage(
+            KillScope::strategy("meme_virus"),
+            KillSource::Operator,
+            "drawdown",
+        )?;
+
+        let restarted = KillSwitch::open(&path)?;
+        assert_eq!(restarted.entries(), switch.entries());
+        let err = restarted.check(&bonk).unwrap_err().to_string();
+        assert_eq!(err, "Kill switch engaged (token:BONK): rug suspected");
+        assert!(restarted.check(&KillTarget::token("SOL")).is_ok());
+
+        // Global wins over narrower scopes
+        restarted.engage(KillScope::Global, KillSource::Operator, "maintenance")?;
+        assert_eq!(restarted.blocking(&bonk).unwrap().scope, KillScope::Global);
+
+        assert!(restarted.release(&KillScope::Global)?);
+        assert!(restarted.release(&KillScope::token("BONK"))?);
+        assert!(!restarted.release(&KillScope::token("BONK"))?);
+        assert!(KillSwitch::open(&path)?.check(&bonk).is_ok());
+
+        std::fs::write(&path, "not json")?;
+        assert!(KillSwitch::open(&path).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_automatic_triggers() {
+        let triggers = KillSwitchTriggers {
+            max_daily_loss: Some(100.0),
+            rpc_failure_threshold: 3,
+            ..Default::default()
+        };
+        let any = KillTarget::default();
+
+        let switch = KillSwitch::in_memory().with_triggers(triggers.clone());
+        switch.report_daily_pnl(-99.0);
+        assert!(switch.check(&any).is_ok());
+        switch.report_daily_pnl(-150.0);
+        assert_eq!(switch.entries()[0].source, KillSource::DailyLoss);
+
+        let switch = KillSwitch::in_memory().with_triggers(triggers.clone());
+        switch.report_rpc_failure("helius");
+        switch.report_rpc_failure("helius");
+        assert!(switch.check(&any).is_ok());
+        switch.report_rpc_failure("quicknode");
+        let entry = switch.blocking(&any).unwrap();
+        assert_eq!(entry.source, KillSource::RpcFailureStorm);
+        assert!(
+            entry.reason.contains("last on quicknode"),
+            "{}",
+            entry.reason
+        );
+
+        let switch = KillSwitch::in_memory().with_triggers(triggers);
+        switch.report_circuit_open("helius_streamer");
+        assert!(switch.check(&any).is_ok());
+        switch.report_circuit_open("tensorzero");
+        assert_eq!(switch.entries()[0].source, KillSource::AiCircuitOpen);
+    }
+
+    #[test]
+    fn test_api_authorization() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(authorize(&headers, None), Err(StatusCode::FORBIDDEN));
+        assert_eq!(
+            authorize(&headers, Some("secret")),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+
+        headers.insert(header::AUTHORIZATION, "Bearer secreT".parse().unwrap());
+        assert_eq!(
+            authorize(&headers, Some("secret")),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+        headers.insert(header::AUTHORIZATION, "Bearer secret".parse().unwrap());
+        assert_eq!(authorize(&headers, Some("secret")), Ok(()));
+        assert_eq!(authorize(&headers, None), Err(StatusCode::FORBIDDEN));
+
+        let request: EngageRequest =
+            serde_json::from_str(r#"{"scope": "wallet", "id": "hot-1", "reason": "leak"}"#)
+                .unwrap();
+        assert_eq!(request.scope, KillScope::wallet("hot-1"));
+        let global: KillScope = serde_json::from_str(r#"{"scope": "global"}"#).unwrap();
+        assert_eq!(global, KillScope::Global);
+    }
+}
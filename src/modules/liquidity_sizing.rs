@@ -0,0 +1,482 @@
+// Liquidity Sizing Module
+// Caps order size by what the market can absorb: given pool reserves or an
+// order-book depth curve, the largest quantity whose entry price impact, and
+// whose exit impact against liquidity cut down to a stressed level, both stay
+// under configured basis points. Applied after volatility/capital sizing.
+// Sells only unwind, so they are at most held to the exit budget against
+// today's liquidity.
+
+use crate::modules::fill_simulator::PoolReserves;
+use crate::modules::strategy::{TradeAction, TradingSignal};
+use serde::{Deserialize, Serialize};
+
+const BPS: f64 = 10_000.0;
+
+/// One price level of a depth curve
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DepthLevel {
+    pub price: f64,
+    pub quantity: f64,
+}
+
+/// Order-book depth around a mid price; each side ordered away from mid
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepthCurve {
+    pub mid_price: f64,
+    pub bids: Vec<DepthLevel>,
+    pub asks: Vec<DepthLevel>,
+}
+
+impl DepthCurve {
+    fn side(&self, buy: bool) -> &[DepthLevel] {
+        if buy {
+            &self.asks
+        } else {
+            &self.bids
+        }
+    }
+
+    /// Average fill distance from mid (fraction) for `quantity`; infinite past the book
+    fn impact(&self, buy: bool, quantity: f64) -> f64 {
+        let mut remaining = quantity;
+        let mut distance = 0.0;
+        for level in self.side(buy) {
+            let take = remaining.min(level.quantity);
+            distance += take * (level.price - self.mid_price).abs();
+            remaining -= take;
+            if remaining <= 0.0 {
+                return distance / quantity / self.mid_price;
+            }
+        }
+        f64::INFINITY
+    }
+
+    /// Largest quantity whose average fill stays within `max_slippage` of mid
+    fn max_quantity_within(&self, buy: bool, max_slippage: f64) -> f64 {
+        let allowed = max_slippage * self.mid_price;
+        let mut quantity = 0.0;
+        let mut distance = 0.0;
+        for level in self.side(buy) {
+            let level_distance = (level.price - self.mid_price).abs();
+            if level_distance <= allowed {
+                quantity += level.quantity;
+                distance += level.quantity * level_distance;
+                continue;
+            }
+            // Part of this level keeps the average on the limit
+            let take = ((allowed * quantity - distance) / (level_distance - allowed))
+                .clamp(0.0, level.quantity);
+            return quantity + take;
+        }
+        quantity
+    }
+
+    fn scaled(&self, factor: f64) -> Self {
+        let scale = |levels: &[DepthLevel]| {
+            levels
+                .iter()
+                .map(|level| DepthLevel {
+                    price: level.price,
+                    quantity: level.quantity * factor,
+                })
+                .collect()
+        };
+        Self {
+            mid_price: self.mid_price,
+            bids: scale(&self.bids),
+            asks: scale(&self.asks),
+        }
+    }
+}
+
+/// Liquidity a token trades against; quantities are base (token) units
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum LiquidityProfile {
+    /// Constant-product AMM pool
+    Pool(PoolReserves),
+    /// Order book or aggregated route depth
+    DepthCurve(DepthCurve),
+}
+
+impl LiquidityProfile {
+    /// Pool from the total (both sides) liquidity quoted for a token
+    pub fn from_total_liquidity(price: f64, liquidity: f64) -> Self {
+        LiquidityProfile::Pool(PoolReserves::from_price(price, liquidity / 2.0))
+    }
+
+    /// Liquidity carried by a signal as `metadata.liquidity`
+    pub fn from_signal(signal: &TradingSignal) -> Option<Self> {
+        signal
+            .metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get("liquidity"))
+            .and_then(|liquidity| serde_json::from_value(liquidity.clone()).ok())
+    }
+
+    /// Spot or mid price, quote per base
+    pub fn price(&self) -> f64 {
+        match self {
+            LiquidityProfile::Pool(pool) => pool.price(),
+            LiquidityProfile::DepthCurve(curve) => curve.mid_price,
+        }
+    }
+
+    /// Average price impact (fraction) of trading `quantity`
+    pub fn impact(&self, buy: bool, quantity: f64) -> f64 {
+        if quantity <= 0.0 {
+            return 0.0;
+        }
+        match self {
+            LiquidityProfile::Pool(pool) if buy => {
+                if quantity >= pool.base_reserve {
+                    f64::INFINITY
+                } else {
+                    quantity / (pool.base_reserve - quantity)
+                }
+            }
+            LiquidityProfile::Pool(pool) => quantity / (pool.base_reserve + quantity),
+            LiquidityProfile::DepthCurve(curve) => curve.impact(buy, quantity),
+        }
+    }
+
+    /// Largest quantity whose average price impact stays within `max_slippage`
+    pub fn max_quantity_within(&self, buy: bool, max_slippage: f64) -> f64 {
+        match self {
+            LiquidityProfile::Pool(pool) if buy => pool.max_buy_within(max_slippage),
+            LiquidityProfile::Pool(pool) => pool.max_sell_within(max_slippage),
+            LiquidityProfile::DepthCurve(curve) => curve.max_quantity_within(buy, max_slippage),
+        }
+    }
+
+    /// Same price with only `factor` of the liquidity left (LP pull, thin book)
+    pub fn stressed(&self, factor: f64) -> Self {
+        match self {
+            LiquidityProfile::Pool(pool) => LiquidityProfile::Pool(PoolReserves::new(
+                pool.base_reserve * factor,
+                pool.quote_reserve * factor,
+            )),
+            LiquidityProfile::DepthCurve(curve) => {
+                LiquidityProfile::DepthCurve(curve.scaled(factor))
+            }
+        }
+    }
+}
+
+/// Impact limits, in basis points
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LiquiditySizingConfig {
+    pub max_entry_impact_bps: f64,
+    pub max_exit_impact_bps: f64,
+    /// Share of today's liquidity assumed to be left when exiting (0.0-1.0)
+    pub stressed_liquidity: f64,
+}
+
+impl Default for LiquiditySizingConfig {
+    fn default() -> Self {
+        Self {
+            max_entry_impact_bps: 100.0, // 1% to get in
+            max_exit_impact_bps: 300.0,  // 3% to get out
+            stressed_liquidity: 0.5,     // after half the liquidity is gone
+        }
+    }
+}
+
+/// What the liquidity allows for one trade direction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LiquidityLimit {
+    pub max_quantity: f64,
+    pub entry_max_quantity: f64,
+    pub exit_max_quantity: f64,
+    pub price: f64,
+}
+
+impl LiquidityLimit {
+    /// Largest order in quote units
+    pub fn max_notional(&self) -> f64 {
+        self.max_quantity * self.price
+    }
+
+    /// The stressed exit, not the entry, is what limits the size
+    pub fn exit_bound(&self) -> bool {
+        self.exit_max_quantity < self.entry_max_quantity
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LiquidityConstraint {
+    config: LiquiditySizingConfig,
+}
+
+impl LiquidityConstraint {
+    pub fn new(config: LiquiditySizingConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn config(&self) -> &LiquiditySizingConfig {
+        &self.config
+    }
+
+    /// Whether `action` opens a position
+    pub fn is_entry(action: &TradeAction) -> bool {
+        matches!(action, TradeAction::Buy | TradeAction::MarketBuy)
+    }
+
+    /// Whether `action` unwinds a position
+    pub fn is_exit(action: &TradeAction) -> bool {
+        matches!(action, TradeAction::Sell | TradeAction::MarketSell)
+    }
+
+    /// Size limit for `action`: a buy must fit the entry budget now and the
+    /// exit budget once liquidity is stressed; a sell only the exit budget now
+    pub fn limit(&self, profile: &LiquidityProfile, action: &TradeAction) -> LiquidityLimit {
+        let mut limit = LiquidityLimit {
+            max_quantity: f64::INFINITY,
+            entry_max_quantity: f64::INFINITY,
+            exit_max_quantity: f64::INFINITY,
+            price: profile.price(),
+        };
+        if Self::is_entry(action) {
+            limit.entry_max_quantity =
+                profile.max_quantity_within(true, self.config.max_entry_impact_bps / BPS);
+            limit.exit_max_quantity = profile
+                .stressed(self.config.stressed_liquidity)
+                .max_quantity_within(false, self.config.max_exit_impact_bps / BPS);
+        } else if Self::is_exit(action) {
+            limit.exit_max_quantity =
+                profile.max_quantity_within(false, self.config.max_exit_impact_bps / BPS);
+        }
+        limit.max_quantity = limit.entry_max_quantity.min(limit.exit_max_quantity);
+        limit
+    }
+
+    /// Expected entry impact of buying `quantity`, in bps; 0 for other actions
+    pub fn entry_impact_bps(
+        &self,
+        profile: &LiquidityProfile,
+        action: &TradeAction,
+        quantity: f64,
+    ) -> f64 {
+        if Self::is_entry(action) {
+            profile.impact(true, quantity) * BPS
+        } else {
+            0.0
+        }
+    }
+
+    /// Expected impact of unwinding `quantity`, in bps: into stressed
+    /// liquidity after a buy, into today's liquidity for a sell
+    pub fn exit_impact_bps(
+        &self,
+        profile: &LiquidityProfile,
+        action: &TradeAction,
+        quantity: f64,
+    ) -> f64 {
+        if Self::is_entry(action) {
+            profile
+                .stressed(self.config.stressed_liquidity)
+                .impact(false, quantity)
+                * BPS
+        } else if Self::is_exit(action) {
+            profile.impact(false, quantity) * BPS
+        } else {
+            0.0
+        }
+    }
+
+    /// `quantity` reduced to the liquidity limit
+    pub fn cap(&self, profile: &LiquidityProfile, action: &TradeAction, quantity: f64) -> f64 {
+        quantity.min(self.limit(profile, action).max_quantity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::risk_rules::{RiskContext, RiskRule, RiskRulesConfig, RuleVerdict};
+    use crate::modules::strategy::StrategyType;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-6 * b.abs().max(1.0)
+    }
+
+    #[test]
+    fn test_pool_limits_entry_and_stressed_exit() {
+        // 1M tokens at 0.001 = 1,000 quote each side
+        let pool = LiquidityProfile::Pool(PoolReserves::new(1_000_000.0, 1_000.0));
+        let constraint = LiquidityConstraint::new(LiquiditySizingConfig {
+            max_entry_impact_bps: 100.0,
+            max_exit_impact_bps: 300.0,
+            stressed_liquidity: 0.5,
+        });
+
+        // Entry: q / (x - q) <= 1%  → 9,900.99; exit: q / (x/2 + q) <= 3% → 15,463.92
+        let limit = constraint.limit(&pool, &TradeAction::Buy);
+        assert!(close(limit.entry_max_quantity, 1_000_000.0 * 0.01 / 1.01));
+        assert!(close(limit.exit_max_quantity, 500_000.0 * 0.03 / 0.97));
+        assert_eq!(limit.max_quantity, limit.entry_max_quantity);
+        assert!(!limit.exit_bound());
+        assert!(close(
+            constraint.entry_impact_bps(&pool, &TradeAction::Buy, limit.max_quantity),
+            100.0
+        ));
+        assert!(close(limit.max_notional(), limit.max_quantity * 0.001));
+
+        // A harsher stress makes the exit the binding side
+        let harsh = LiquidityConstraint::new(LiquiditySizingConfig {
+            stressed_liquidity: 0.1,
+            ..constraint.config().clone()
+        });
+        let limit = harsh.limit(&pool, &TradeAction::Buy);
+        assert!(limit.exit_bound());
+        assert!(close(
+            harsh.exit_impact_bps(&pool, &TradeAction::Buy, limit.max_quantity),
+            300.0
+        ));
+        assert_eq!(harsh.cap(&pool, &TradeAction::Buy, 100.0), 100.0);
+        assert_eq!(harsh.cap(&pool, &TradeAction::Buy, 1e9), limit.max_quantity);
+        assert_eq!(
+            harsh.limit(&pool, &TradeAction::Hold).max_quantity,
+            f64::INFINITY
+        );
+
+        // Sells skip the entry cap and the stress: q / (x + q) <= 3% → 30,927.84
+        let limit = harsh.limit(&pool, &TradeAction::Sell);
+        assert_eq!(limit.entry_max_quantity, f64::INFINITY);
+        assert!(close(limit.max_quantity, 1_000_000.0 * 0.03 / 0.97));
+        assert_eq!(
+            harsh.entry_impact_bps(&pool, &TradeAction::Sell, limit.max_quantity),
+            0.0
+        );
+        assert!(close(
+            harsh.exit_impact_bps(&pool, &TradeAction::Sell, limit.max_quantity),
+            300.0
+        ));
+    }
+
+    #[test]
+    fn test_depth_curve_walks_levels() {
+        let curve = LiquidityProfile::DepthCurve(DepthCurve {
+            mid_price: 100.0,
+            bids: vec![
+                DepthLevel {
+                    price: 99.0,
+                    quantity: 10.0,
+                },
+                DepthLevel {
+                    price: 97.0,
+                    quantity: 10.0,
+                },
+            ],
+            asks: vec![
+                DepthLevel {
+                    price: 100.5,
+                    quantity: 10.0,
+                },
+                DepthLevel {
+                    price: 102.0,
+                    quantity: 20.0,
+                },
+            ],
+        });
+
+        // 10 @ 0.5 + 10 @ 2.0 away from mid → 1.25% average
+        assert!(close(curve.impact(true, 20.0), 0.0125));
+        assert_eq!(curve.impact(true, 31.0), f64::INFINITY);
+        // 1% average: 10 @ 0.5 plus t @ 2.0 with (5 + 2t) / (10 + t) = 1 → t = 5
+        assert!(close(curve.max_quantity_within(true, 0.01), 15.0));
+        assert!(close(curve.impact(true, 15.0), 0.01));
+        // The whole book is within 10%
+        assert!(close(curve.max_quantity_within(false, 0.10), 20.0));
+
+        // Half the book gone: 5 @ 1.0 then 5 @ 3.0 → 2% average for 10
+        let stressed = curve.stressed(0.5);
+        assert!(close(stressed.impact(false, 10.0), 0.02));
+        assert_eq!(stressed.price(), 100.0);
+    }
+
+    #[test]
+    fn test_liquidity_impact_rule() {
+        let config = RiskRulesConfig::from_toml_str(
+            r#"
+            [[rules]]
+            rule = "liquidity_impact"
+            max_entry_impact_bps = 100.0
+            max_exit_impact_bps = 300.0
+            "#,
+        )
+        .unwrap();
+        let rule = &config.rules[0];
+        let signal = TradingSignal {
+            signal_id: "sig".to_string(),
+            symbol: "BONK".to_string(),
+            action: TradeAction::Buy,
+            quantity: 50_000.0,
+            target_price: 0.001,
+            price: Some(0.001),
+            confidence: 0.9,
+            timestamp: chrono::Utc::now(),
+            strategy_type: StrategyType::MemeVirus,
+            urgency: None,
+            metadata: Some(serde_json::json!({
+                "liquidity": {"kind": "pool", "base_reserve": 1_000_000.0, "quote_reserve": 1_000.0}
+            })),
+        };
+
+        // No liquidity data: nothing to cap against
+        assert_eq!(
+            rule.check(&signal, 50_000.0, &RiskContext::default())
+                .verdict,
+            RuleVerdict::Pass
+        );
+
+        let context = RiskContext {
+            liquidity: LiquidityProfile::from_signal(&signal),
+            ..RiskContext::default()
+        };
+        let check = rule.check(&signal, 50_000.0, &context);
+        let RuleVerdict::Resize { quantity, .. } = check.verdict else {
+            panic!("expected resize, got {:?}", check.verdict);
+        };
+        assert!(close(quantity, 1_000_000.0 * 0.01 / 1.01));
+        assert!(close(check.inputs["entry_impact_bps"], 100.0));
+        assert!(check.inputs["stressed_exit_impact_bps"] < 300.0);
+
+        assert_eq!(
+            rule.check(&signal, 1_000.0, &context).verdict,
+            RuleVerdict::Pass
+        );
+
+        // Sells are held to the exit budget against today's pool, not stressed
+        let sell = TradingSignal {
+            action: TradeAction::Sell,
+            ..signal.clone()
+        };
+        let check = rule.check(&sell, 50_000.0, &context);
+        let RuleVerdict::Resize { quantity, .. } = check.verdict else {
+            panic!("expected resize, got {:?}", check.verdict);
+        };
+        assert!(close(quantity, 1_000_000.0 * 0.03 / 0.97));
+        assert_eq!(check.inputs["entry_impact_bps"], 0.0);
+        assert!(close(check.inputs["exit_impact_bps"], 300.0));
+
+        // An empty book rejects buys but never sells
+        let empty = RiskContext {
+            liquidity: Some(LiquidityProfile::DepthCurve(DepthCurve {
+                mid_price: 0.001,
+                bids: Vec::new(),
+                asks: Vec::new(),
+            })),
+            ..RiskContext::default()
+        };
+        assert!(matches!(
+            rule.check(&signal, 1_000.0, &empty).verdict,
+            RuleVerdict::Reject { .. }
+        ));
+        assert_eq!(
+            rule.check(&sell, 1_000.0, &empty).verdict,
+            RuleVerdict::Pass
+        );
+    }
+}
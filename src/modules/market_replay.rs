@@ -0,0 +1,478 @@
+// Market Replay Module
+// Replays recorded MarketData and raw transaction events from JSONL/CSV files
+
+use crate::modules::data_ingestor::{DataSource, MarketData};
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::{mpsc, watch};
+use tracing::{debug, info, warn};
+
+/// Replay pacing relative to the original recording
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ReplaySpeed {
+    /// Original inter-event spacing
+    Realtime,
+    /// Original spacing divided by the factor (e.g. 10.0 = 10x faster)
+    Accelerated(f64),
+    /// No waiting between events
+    AsFastAsPossible,
+}
+
+impl std::str::FromStr for ReplaySpeed {
+    type Err = anyhow::Error;
+
+    /// `realtime`, `max` or an acceleration factor such as `10x`
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "realtime" => Ok(Self::Realtime),
+            "max" => Ok(Self::AsFastAsPossible),
+            factor => {
+                let factor: f64 = factor.trim_end_matches('x').parse().with_context(|| {
+                    format!(
+                        "Invalid replay speed '{}': expected realtime, max or a factor like 10x",
+                        s
+                    )
+                })?;
+                if !factor.is_finite() || factor <= 0.0 {
+                    anyhow::bail!("Replay acceleration must be positive, got '{}'", s);
+                }
+                Ok(Self::Accelerated(factor))
+            }
+        }
+    }
+}
+
+/// Raw on-chain transaction captured alongside market data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedTransaction {
+    pub signature: String,
+    #[serde(default)]
+    pub slot: Option<u64>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    #[serde(default)]
+    pub program_id: Option<String>,
+    #[serde(default)]
+    pub accounts: Vec<String>,
+    #[serde(default)]
+    pub payload: serde_json::Value,
+}
+
+/// Single recorded event in a replay stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReplayEvent {
+    Market(MarketData),
+    Transaction(RecordedTransaction),
+}
+
+impl ReplayEvent {
+    pub fn timestamp(&self) -> chrono::DateTime<chrono::Utc> {
+        match self {
+            ReplayEvent::Market(data) => data.timestamp,
+            ReplayEvent::Transaction(tx) => tx.timestamp,
+        }
+    }
+}
+
+struct ClockState {
+    speed: ReplaySpeed,
+    virtual_now: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Controllable virtual clock driving a replay.
+/// Cloned handles share state, so a caller can pause, resume or change
+/// speed while the replay is running.
+#[derive(Clone)]
+pub struct ReplayClock {
+    state: Arc<Mutex<ClockState>>,
+    paused: Arc<watch::Sender<bool>>,
+}
+
+impl ReplayClock {
+    pub fn new(speed: ReplaySpeed) -> Self {
+        let (paused, _) = watch::channel(false);
+        Self {
+            state: Arc::new(Mutex::new(ClockState {
+                speed,
+                virtual_now: None,
+            })),
+            paused: Arc::new(paused),
+        }
+    }
+
+    /// Virtual time of the last emitted event
+    pub fn now(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.state.lock().virtual_now
+    }
+
+    pub fn speed(&self) -> ReplaySpeed {
+        self.state.lock().speed
+    }
+
+    pub fn set_speed(&self, speed: ReplaySpeed) {
+        info!("⏩ Replay speed set to {:?}", speed);
+        self.state.lock().speed = speed;
+    }
+
+    pub fn pause(&self) {
+        self.paused.send_replace(true);
+    }
+
+    pub fn resume(&self) {
+        self.paused.send_replace(false);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    /// Wait (in wall-clock time) until `timestamp` is due, then advance the
+    /// virtual clock to it. Timestamps in the past never move the clock back.
+    pub async fn advance_to(&self, timestamp: chrono::DateTime<chrono::Utc>) {
+        let mut paused = self.paused.subscribe();
+        let _ = paused.wait_for(|paused| !*paused).await;
+
+        let (speed, previous) = {
+            let state = self.state.lock();
+            (state.speed, state.virtual_now)
+        };
+
+        if let Some(previous) = previous {
+            if timestamp > previous {
+                let delta = (timestamp - previous).to_std().unwrap_or_default();
+                let wait = match speed {
+                    ReplaySpeed::Realtime => Some(delta),
+                    ReplaySpeed::Accelerated(factor) if factor > 0.0 => Some(delta.div_f64(factor)),
+                    _ => None,
+                };
+                if let Some(wait) = wait {
+                    tokio::time::sleep(wait).await;
+                }
+            }
+        }
+
+        let mut state = self.state.lock();
+        state.virtual_now = Some(match state.virtual_now {
+            Some(current) if current > timestamp => current,
+            _ => timestamp,
+        });
+    }
+}
+
+/// File formats accepted by the replay loader
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ReplayFormat {
+    Jsonl,
+    Csv,
+}
+
+impl ReplayFormat {
+    pub fn from_path(path: &Path) -> Result<Self> {
+        match path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()) {
+            Some(ref ext) if ext == "jsonl" || ext == "ndjson" || ext == "json" => Ok(Self::Jsonl),
+            Some(ref ext) if ext == "csv" => Ok(Self::Csv),
+            _ => anyhow::bail!("Unknown replay file format: {}", path.display()),
+        }
+    }
+}
+
+/// Replay configuration for DataIngestor
+#[derive(Clone)]
+pub struct ReplayConfig {
+    pub paths: Vec<PathBuf>,
+    pub clock: ReplayClock,
+}
+
+impl ReplayConfig {
+    pub fn new(paths: Vec<PathBuf>, speed: ReplaySpeed) -> Self {
+        Self {
+            paths,
+            clock: ReplayClock::new(speed),
+        }
+    }
+}
+
+/// Summary of a finished replay
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplayStats {
+    pub market_ticks: u64,
+    pub transactions: u64,
+    pub first_timestamp: Option<chrono::DateTime<chrono::Utc>>,
+    pub last_timestamp: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Load all events from the given files, merged and ordered by timestamp.
+/// Events with equal timestamps keep their file order.
+pub fn load_events(paths: &[PathBuf]) -> Result<Vec<ReplayEvent>> {
+    let mut events = Vec::new();
+    for path in paths {
+        let mut loaded = load_file(path)?;
+        info!("📼 Loaded {} replay events from {}", loaded.len(), path.display());
+        events.append(&mut loaded);
+    }
+    events.sort_by_key(|e| e.timestamp());
+    Ok(events)
+}
+
+pub fn load_file(path: &Path) -> Result<Vec<ReplayEvent>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read replay file {}", path.display()))?;
+    match ReplayFormat::from_path(path)? {
+        ReplayFormat::Jsonl => parse_jsonl(&content),
+        ReplayFormat::Csv => parse_csv(&content),
+    }
+}
+
+/// One event per line: either a tagged `ReplayEvent` or a bare `MarketData` record
+pub fn parse_jsonl(content: &str) -> Result<Vec<ReplayEvent>> {
+    let mut events = Vec::new();
+    for (line_no, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let event = match serde_json::from_str::<ReplayEvent>(line) {
+            Ok(event) => event,
+            Err(_) => ReplayEvent::Market(
+                serde_json::from_str::<MarketData>(line)
+                    .with_context(|| format!("Invalid replay record on line {}", line_no + 1))?,
+            ),
+        };
+        events.push(event);
+    }
+    Ok(events)
+}
+
+/// Market data only. Required columns: `timestamp,symbol,price,volume`;
+/// optional: `price_change_1h,price_change_24h`.
+pub fn parse_csv(content: &str) -> Result<Vec<ReplayEvent>> {
+    let mut lines = content
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty() && !l.trim_start().starts_with('#'));
+
+    let header: Vec<String> = match lines.next() {
+        Some((_, header)) => header.split(',').map(|h| h.trim().to_lowercase()).collect(),
+        None => return Ok(Vec::new()),
+    };
+    let column = |name: &str| header.iter().position(|h| h == name);
+    let required = |name: &str| {
+        column(name).with_context(|| format!("Replay CSV is missing the '{}' column", name))
+    };
+
+    let ts_col = required("timestamp")?;
+    let symbol_col = required("symbol")?;
+    let price_col = required("price")?;
+    let volume_col = required("volume")?;
+    let change_1h_col = column("price_change_1h");
+    let change_24h_col = column("price_change_24h");
+
+    let mut events = Vec::new();
+    for (line_no, line) in lines {
+        let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+        let field = |idx: usize| {
+            fields
+                .get(idx)
+                .copied()
+                .with_context(|| format!("Missing column {} on line {}", idx + 1, line_no + 1))
+        };
+        let number = |idx: Option<usize>| -> Result<f64> {
+            match idx {
+                Some(idx) => field(idx)?
+                    .parse::<f64>()
+                    .with_context(|| format!("Invalid number on line {}", line_no + 1)),
+                None => Ok(0.0),
+            }
+        };
+
+        events.push(ReplayEvent::Market(MarketData {
+            symbol: field(symbol_col)?.to_string(),
+            price: number(Some(price_col))?,
+            volume: number(Some(volume_col))?,
+            timestamp: parse_timestamp(field(ts_col)?)
+                .with_context(|| format!("Invalid timestamp on line {}", line_no + 1))?,
+            source: DataSource::Replay,
+            price_change_1h: number(change_1h_col)?,
+            price_change_24h: number(change_24h_col)?,
+        }));
+    }
+    Ok(events)
+}
+
+/// RFC3339, or unix time in seconds / milliseconds
+fn parse_timestamp(value: &str) -> Result<chrono::DateTime<chrono::Utc>> {
+    if let Ok(ts) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Ok(ts.with_timezone(&chrono::Utc));
+    }
+    let raw: i64 = value.parse()?;
+    let ts = if raw.abs() >= 100_000_000_000 {
+        chrono::DateTime::from_timestamp_millis(raw)
+    } else {
+        chrono::DateTime::from_timestamp(raw, 0)
+    };
+    ts.context("Timestamp out of range")
+}
+
+/// Emits recorded events on the live pipeline channels, paced by a ReplayClock
+pub struct MarketReplay {
+    events: Vec<ReplayEvent>,
+    clock: ReplayClock,
+}
+
+impl MarketReplay {
+    pub fn new(mut events: Vec<ReplayEvent>, clock: ReplayClock) -> Self {
+        events.sort_by_key(|e| e.timestamp());
+        Self { events, clock }
+    }
+
+    pub fn from_config(config: &ReplayConfig) -> Result<Self> {
+        Ok(Self::new(load_events(&config.paths)?, config.clock.clone()))
+    }
+
+    pub fn events(&self) -> &[ReplayEvent] {
+        &self.events
+    }
+
+    pub fn clock(&self) -> ReplayClock {
+        self.clock.clone()
+    }
+
+    /// Replay every event. Transactions are dropped when no transaction
+    /// channel is given; the replay ends early if the market channel closes.
+    pub async fn run(
+        &self,
+        market_sender: &mpsc::UnboundedSender<MarketData>,
+        transaction_sender: Option<&mpsc::UnboundedSender<RecordedTransaction>>,
+    ) -> Result<ReplayStats> {
+        let mut stats = ReplayStats::default();
+        info!(
+            "📼 Replaying {} events at {:?}",
+            self.events.len(),
+            self.clock.speed()
+        );
+
+        for event in &self.events {
+            self.clock.advance_to(event.timestamp()).await;
+            stats.first_timestamp.get_or_insert(event.timestamp());
+            stats.last_timestamp = Some(event.timestamp());
+
+            match event {
+                ReplayEvent::Market(data) => {
+                    if market_sender.send(data.clone()).is_err() {
+                        warn!("📼 Market data channel closed, stopping replay");
+                        break;
+                    }
+                    stats.market_ticks += 1;
+                }
+                ReplayEvent::Transaction(tx) => {
+                    if let Some(sender) = transaction_sender {
+                        if sender.send(tx.clone()).is_err() {
+                            debug!("📼 Transaction channel closed, dropping {}", tx.signature);
+                            continue;
+                        }
+                        stats.transactions += 1;
+                    }
+                }
+            }
+        }
+
+        info!(
+            "📼 Replay finished: {} ticks, {} transactions",
+            stats.market_ticks, stats.transactions
+        );
+        Ok(stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSONL: &str = r#"
+{"type":"market","symbol":"BONK","price":1.0,"volume":100.0,"timestamp":"2025-01-01T00:00:02Z","source":"Helius","price_change_1h":1.0,"price_change_24h":2.0}
+{"type":"transaction","signature":"5xyz","slot":42,"timestamp":"2025-01-01T00:00:01Z"}
+{"symbol":"BONK","price":1.1,"volume":120.0,"timestamp":"2025-01-01T00:00:03Z","source":"QuickNode","price_change_1h":0.0,"price_change_24h":0.0}
+"#;
+
+    #[test]
+    fn test_parse_replay_speed() {
+        let speed = |s: &str| s.parse::<ReplaySpeed>().ok();
+        assert_eq!(speed("realtime"), Some(ReplaySpeed::Realtime));
+        assert_eq!(speed("MAX"), Some(ReplaySpeed::AsFastAsPossible));
+        assert_eq!(speed("10x"), Some(ReplaySpeed::Accelerated(10.0)));
+        assert_eq!(speed("2.5"), Some(ReplaySpeed::Accelerated(2.5)));
+        assert_eq!(speed("0x"), None);
+        assert_eq!(speed("fast"), None);
+    }
+
+    #[test]
+    fn test_parse_jsonl_mixed_records() -> Result<()> {
+        let events = parse_jsonl(JSONL)?;
+        assert_eq!(events.len(), 3);
+        assert!(matches!(events[1], ReplayEvent::Transaction(_)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_csv_with_unix_timestamps() -> Result<()> {
+        let csv = "timestamp,symbol,price,volume,price_change_1h\n\
+                   1735689600,WIF,2.5,1000,3.5\n\
+                   1735689601000,WIF,2.6,900,4.0\n";
+        let events = parse_csv(csv)?;
+        assert_eq!(events.len(), 2);
+        match &events[1] {
+            ReplayEvent::Market(data) => {
+                assert_eq!(data.price, 2.6);
+                assert_eq!(data.price_change_1h, 4.0);
+                assert_eq!(data.timestamp.timestamp(), 1735689601);
+            }
+            _ => panic!("expected market data"),
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_replay_emits_in_timestamp_order() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("session.jsonl");
+        std::fs::write(&path, JSONL)?;
+
+        let config = ReplayConfig::new(vec![path], ReplaySpeed::AsFastAsPossible);
+        let replay = MarketReplay::from_config(&config)?;
+
+        let (market_tx, mut market_rx) = mpsc::unbounded_channel();
+        let (tx_tx, mut tx_rx) = mpsc::unbounded_channel();
+        let stats = replay.run(&market_tx, Some(&tx_tx)).await?;
+
+        assert_eq!(stats.market_ticks, 2);
+        assert_eq!(stats.transactions, 1);
+        assert_eq!(tx_rx.recv().await.unwrap().signature, "5xyz");
+        assert_eq!(market_rx.recv().await.unwrap().price, 1.0);
+        assert_eq!(market_rx.recv().await.unwrap().price, 1.1);
+        assert_eq!(
+            config.clock.now().unwrap().to_rfc3339(),
+            "2025-01-01T00:00:03+00:00"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_accelerated_pacing() -> Result<()> {
+        let events = parse_jsonl(JSONL)?;
+        let replay = MarketReplay::new(events, ReplayClock::new(ReplaySpeed::Accelerated(20.0)));
+        let (market_tx, _market_rx) = mpsc::unbounded_channel();
+
+        let started = std::time::Instant::now();
+        replay.run(&market_tx, None).await?;
+
+        // 2 seconds of recording at 20x ~= 100ms
+        let elapsed = started.elapsed();
+        assert!(elapsed >= std::time::Duration::from_millis(95));
+        assert!(elapsed < std::time::Duration::from_secs(1));
+        Ok(())
+    }
+}
@@ -314,7 +314,11 @@ impl MemcoinStrategy for DeathSpiralInterceptStrategy {
         StrategyType::DeathSpiralIntercept
     }
 
-    async fn process_signal(&self, signal: &(dyn std::any::Any + Send + Sync)) -> Result<Option<TradingSignal>> {
+    fn subscriptions(&self) -> &[MarketEventKind] {
+        &[MarketEventKind::PanicSell]
+    }
+
+    async fn process_signal(&self, event: &MarketEvent) -> Result<Option<TradingSignal>> {
         if !self.is_active {
             return Ok(None);
         }
@@ -325,8 +329,7 @@ impl MemcoinStrategy for DeathSpiralInterceptStrategy {
             return Ok(exit_signals.into_iter().next()); // Zwróć pierwszy sygnał wyjścia
         }
 
-        // Próba konwersji sygnału na PanicSellEvent
-        if let Some(panic_event) = signal.downcast_ref::<PanicSellEvent>() {
+        if let MarketEvent::PanicSell(panic_event) = event {
             debug!("Processing panic sell event for {}: volume={}%, drop={}%", 
                    panic_event.token, panic_event.volume_percentage, panic_event.price_drop);
 
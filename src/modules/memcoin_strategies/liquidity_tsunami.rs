@@ -187,13 +187,16 @@ impl MemcoinStrategy for LiquidityTsunamiStrategy {
         StrategyType::LiquidityTsunami
     }
 
-    async fn process_signal(&self, signal: &(dyn std::any::Any + Send + Sync)) -> Result<Option<TradingSignal>> {
+    fn subscriptions(&self) -> &[MarketEventKind] {
+        &[MarketEventKind::Liquidity]
+    }
+
+    async fn process_signal(&self, event: &MarketEvent) -> Result<Option<TradingSignal>> {
         if !self.is_active {
             return Ok(None);
         }
 
-        // Próba konwersji sygnału na LiquidityEvent
-        if let Some(liquidity_event) = signal.downcast_ref::<LiquidityEvent>() {
+        if let MarketEvent::Liquidity(liquidity_event) = event {
             debug!("Processing liquidity event for {}", liquidity_event.mint);
 
             // Aktualizuj bufor
@@ -341,7 +341,11 @@ impl MemcoinStrategy for MemeVirusStrategy {
         StrategyType::MemeVirus
     }
 
-    async fn process_signal(&self, signal: &(dyn std::any::Any + Send + Sync)) -> Result<Option<TradingSignal>> {
+    fn subscriptions(&self) -> &[MarketEventKind] {
+        &[MarketEventKind::ViralMeme]
+    }
+
+    async fn process_signal(&self, event: &MarketEvent) -> Result<Option<TradingSignal>> {
         if !self.is_active {
             return Ok(None);
         }
@@ -349,8 +353,8 @@ impl MemcoinStrategy for MemeVirusStrategy {
         // Cleanup nieaktywnych tokenów
         self.cleanup_dormant_tokens().await;
 
-        // Próba konwersji sygnału na SocialSignal (dla wykrywania narracji)
-        if let Some(social_signal) = signal.downcast_ref::<SocialSignal>() {
+        // Wiralowe sygnały społecznościowe (dla wykrywania narracji)
+        if let MarketEvent::ViralMeme(social_signal) = event {
             debug!("Processing meme virus signal for {}", social_signal.token);
 
             // Sprawdź czy token już istnieje
@@ -16,12 +16,16 @@ pub mod whale_shadowing;
 pub mod death_spiral_intercept;
 pub mod meme_virus;
 pub mod phoenix_engine;
+pub mod registry;
 
 use anyhow::Result;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
+use crate::modules::data_ingestor::MarketData;
+use crate::modules::indicators::SharedIndicators;
 use crate::modules::strategy::{TradingSignal, StrategyType};
+use death_spiral_intercept::PanicSellEvent;
 
 /// Wspólne typy danych dla strategii memcoin
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -70,6 +74,100 @@ impl std::fmt::Display for WhaleAction {
     }
 }
 
+/// Nowa pula płynności (np. Raydium / Meteora)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewPoolEvent {
+    pub pool_address: String,
+    pub token_mint: String,
+    pub quote_mint: String,
+    pub dex: String,
+    pub initial_liquidity: f64, // Płynność początkowa w SOL
+    pub initial_price: f64,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// Typowane zdarzenia rynkowe przekazywane do strategii memcoin
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MarketEvent {
+    Tick(MarketData),
+    Liquidity(LiquidityEvent),
+    Social(SocialSignal),
+    /// Wiralowa narracja memowa (sygnał społecznościowy o wysokiej intensywności)
+    ViralMeme(SocialSignal),
+    Whale(WhaleTransaction),
+    PanicSell(PanicSellEvent),
+    NewPool(NewPoolEvent),
+    Arbitrage(ArbitrageOpportunity),
+}
+
+/// Rodzaj zdarzenia, używany do subskrypcji i routingu
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MarketEventKind {
+    Tick,
+    Liquidity,
+    Social,
+    ViralMeme,
+    Whale,
+    PanicSell,
+    NewPool,
+    Arbitrage,
+}
+
+impl MarketEvent {
+    pub fn kind(&self) -> MarketEventKind {
+        match self {
+            MarketEvent::Tick(_) => MarketEventKind::Tick,
+            MarketEvent::Liquidity(_) => MarketEventKind::Liquidity,
+            MarketEvent::Social(_) => MarketEventKind::Social,
+            MarketEvent::ViralMeme(_) => MarketEventKind::ViralMeme,
+            MarketEvent::Whale(_) => MarketEventKind::Whale,
+            MarketEvent::PanicSell(_) => MarketEventKind::PanicSell,
+            MarketEvent::NewPool(_) => MarketEventKind::NewPool,
+            MarketEvent::Arbitrage(_) => MarketEventKind::Arbitrage,
+        }
+    }
+
+    /// Token, którego dotyczy zdarzenie
+    pub fn token(&self) -> &str {
+        match self {
+            MarketEvent::Tick(data) => &data.symbol,
+            MarketEvent::Liquidity(event) => &event.mint,
+            MarketEvent::Social(signal) | MarketEvent::ViralMeme(signal) => &signal.token,
+            MarketEvent::Whale(tx) => &tx.token,
+            MarketEvent::PanicSell(event) => &event.token,
+            MarketEvent::NewPool(pool) => &pool.token_mint,
+            MarketEvent::Arbitrage(opportunity) => &opportunity.token,
+        }
+    }
+
+    pub fn timestamp(&self) -> chrono::DateTime<chrono::Utc> {
+        match self {
+            MarketEvent::Tick(data) => data.timestamp,
+            MarketEvent::Liquidity(event) => event.timestamp,
+            MarketEvent::Social(signal) | MarketEvent::ViralMeme(signal) => signal.timestamp,
+            MarketEvent::Whale(tx) => tx.timestamp,
+            MarketEvent::PanicSell(event) => event.timestamp,
+            MarketEvent::NewPool(pool) => pool.timestamp,
+            MarketEvent::Arbitrage(opportunity) => opportunity.timestamp,
+        }
+    }
+}
+
+impl std::fmt::Display for MarketEventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MarketEventKind::Tick => write!(f, "tick"),
+            MarketEventKind::Liquidity => write!(f, "liquidity"),
+            MarketEventKind::Social => write!(f, "social"),
+            MarketEventKind::ViralMeme => write!(f, "viral_meme"),
+            MarketEventKind::Whale => write!(f, "whale"),
+            MarketEventKind::PanicSell => write!(f, "panic_sell"),
+            MarketEventKind::NewPool => write!(f, "new_pool"),
+            MarketEventKind::Arbitrage => write!(f, "arbitrage"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeBundle {
     pub action: TradeAction,
@@ -97,6 +195,7 @@ pub enum UrgencyLevel {
 
 /// Parametry strategii memcoin
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MemcoinStrategyParams {
     pub capital_allocation: f32,    // Procent kapitału (0.0 - 1.0)
     pub risk_tolerance: f32,        // Tolerancja ryzyka (0.0 - 1.0)
@@ -137,8 +236,11 @@ pub trait MemcoinStrategy: Send + Sync {
     /// Typ strategii
     fn strategy_type(&self) -> StrategyType;
     
-    /// Przetwarzanie sygnału rynkowego
-    async fn process_signal(&self, signal: &(dyn std::any::Any + Send + Sync)) -> Result<Option<TradingSignal>>;
+    /// Rodzaje zdarzeń, które strategia subskrybuje; silnik przekazuje tylko te
+    fn subscriptions(&self) -> &[MarketEventKind];
+
+    /// Przetwarzanie zdarzenia rynkowego
+    async fn process_signal(&self, event: &MarketEvent) -> Result<Option<TradingSignal>>;
     
     /// Sprawdzenie czy strategia jest aktywna
     fn is_active(&self) -> bool;
@@ -151,6 +253,9 @@ pub trait MemcoinStrategy: Send + Sync {
     
     /// Aktualizacja parametrów strategii
     async fn update_params(&mut self, params: MemcoinStrategyParams) -> Result<()>;
+
+    /// Wspólny silnik wskaźników, aktualizowany przez rejestr przy każdym ticku
+    fn attach_indicators(&mut self, _indicators: SharedIndicators) {}
 }
 
 /// Metryki sukcesu strategii
@@ -223,3 +328,9 @@ pub use phoenix_engine::{
     MarketSignal
 };
 pub use phoenix_engine::WhaleAction as PhoenixWhaleAction;
+
+// STRATEGY REGISTRY exports
+pub use registry::{
+    StrategyRegistry, StrategyRegistryConfig, StrategyEntryConfig, StrategyFactory,
+    StrategyStatus, StrategyInfo, create_strategy_registry_router
+};
@@ -23,9 +23,10 @@ use dashmap::DashMap;
 use crossbeam_channel::{Sender, Receiver, unbounded};
 use moka::future::Cache;
 
+use crate::modules::indicators::{SharedIndicators, Timeframe};
 use crate::modules::strategy::{TradingSignal, TradeAction, StrategyType};
 use crate::modules::memcoin_strategies::{
-    MemcoinStrategy, MemcoinStrategyParams
+    MarketEvent, MarketEventKind, MemcoinStrategy, MemcoinStrategyParams, WhaleTransaction
 };
 
 /// Phoenix Engine Configuration - Zaawansowana konfiguracja
@@ -225,16 +226,18 @@ impl AdaptiveRiskManager {
     }
 
     pub async fn update_market_conditions(&self, volatility: f32, recent_pnl: f32) {
-        let mut vol = self.market_volatility.write().await;
-        *vol = volatility;
+        *self.market_volatility.write().await = volatility;
 
-        let mut performance = self.recent_performance.write().await;
-        performance.push(recent_pnl);
-        if performance.len() > 10 {
-            performance.remove(0);
+        {
+            let mut performance = self.recent_performance.write().await;
+            performance.push(recent_pnl);
+            if performance.len() > 10 {
+                performance.remove(0);
+            }
         }
 
-        // Adapt risk level based on conditions
+        // Adapt risk level based on conditions (write guards released above,
+        // adapt_risk_level re-acquires both locks)
         self.adapt_risk_level().await;
     }
 
@@ -323,6 +326,24 @@ pub enum WhaleAction {
     Transfer,
 }
 
+impl From<&WhaleTransaction> for WhaleAlert {
+    fn from(tx: &WhaleTransaction) -> Self {
+        use crate::modules::memcoin_strategies::WhaleAction as ShadowAction;
+
+        Self {
+            whale_wallet: tx.wallet.clone(),
+            token: tx.token.clone(),
+            action: match tx.action {
+                ShadowAction::Accumulation => WhaleAction::Buy,
+                ShadowAction::PreDump => WhaleAction::Sell,
+                ShadowAction::Idle => WhaleAction::Transfer,
+            },
+            amount: tx.amount,
+            timestamp: tx.timestamp,
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct WhaleMetrics {
     pub tracked_whales: AtomicU64,
@@ -413,7 +434,7 @@ pub struct DexPrice {
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArbitrageOpportunity {
     pub token: String,
     pub buy_dex: String,
@@ -609,6 +630,9 @@ pub struct PhoenixEngine {
     // Lock-free communication channels
     signal_sender: Sender<TradingSignal>,
     signal_receiver: Receiver<TradingSignal>,
+
+    // Shared streaming indicators (ATR, RSI, ...), attached by the registry
+    indicators: Option<SharedIndicators>,
 }
 
 #[derive(Debug, Clone)]
@@ -671,6 +695,7 @@ impl PhoenixEngine {
 
             signal_sender,
             signal_receiver,
+            indicators: None,
         }
     }
 
@@ -789,9 +814,17 @@ impl PhoenixEngine {
         total_pnl as f32 / self.config.capital_allocation as f32
     }
 
-    async fn calculate_market_volatility(&self, _token: &str) -> f32 {
-        // Placeholder - would calculate based on price history
-        0.15
+    async fn calculate_market_volatility(&self, token: &str) -> f32 {
+        // ATR of 1m bars relative to price; conservative default while warming up
+        const DEFAULT_VOLATILITY: f32 = 0.15;
+        let Some(indicators) = &self.indicators else {
+            return DEFAULT_VOLATILITY;
+        };
+        let engine = indicators.read();
+        match (engine.value(token, "atr"), engine.current_bar(token, Timeframe::M1)) {
+            (Some(atr), Some(bar)) if bar.close > 0.0 => (atr / bar.close) as f32,
+            _ => DEFAULT_VOLATILITY,
+        }
     }
 
     /// Pobiera metryki wydajności
@@ -840,25 +873,27 @@ impl MemcoinStrategy for PhoenixEngine {
         StrategyType::PhoenixEngine
     }
 
-    async fn process_signal(&self, signal: &(dyn std::any::Any + Send + Sync)) -> Result<Option<TradingSignal>> {
+    fn subscriptions(&self) -> &[MarketEventKind] {
+        &[MarketEventKind::Tick, MarketEventKind::Whale, MarketEventKind::Arbitrage]
+    }
+
+    async fn process_signal(&self, event: &MarketEvent) -> Result<Option<TradingSignal>> {
         if !self.is_active {
             return Ok(None);
         }
 
-        // Try to downcast to different signal types
-        if let Some(whale_alert) = signal.downcast_ref::<WhaleAlert>() {
-            return self.process_whale_signal(whale_alert).await;
-        }
-
-        if let Some(market_signal) = signal.downcast_ref::<MarketSignal>() {
-            return self.process_market_signal(&market_signal.token, market_signal.price, market_signal.volume).await;
-        }
-
-        if let Some(arbitrage_signal) = signal.downcast_ref::<ArbitrageOpportunity>() {
-            return self.execute_arbitrage_trade(arbitrage_signal.clone()).await;
+        match event {
+            MarketEvent::Tick(data) => {
+                self.process_market_signal(&data.symbol, data.price, data.volume).await
+            }
+            MarketEvent::Whale(whale_tx) => {
+                self.process_whale_signal(&WhaleAlert::from(whale_tx)).await
+            }
+            MarketEvent::Arbitrage(opportunity) => {
+                self.execute_arbitrage_trade(opportunity.clone()).await
+            }
+            _ => Ok(None),
         }
-
-        Ok(None)
     }
 
     fn is_active(&self) -> bool {
@@ -893,6 +928,10 @@ impl MemcoinStrategy for PhoenixEngine {
 
         Ok(())
     }
+
+    fn attach_indicators(&mut self, indicators: SharedIndicators) {
+        self.indicators = Some(indicators);
+    }
 }
 
 impl PhoenixEngine {
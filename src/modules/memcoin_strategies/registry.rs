@@ -0,0 +1,799 @@
+//! STRATEGY REGISTRY
+//!
+//! Config-driven registry of `Box<dyn MemcoinStrategy>` instances used by
+//! `StrategyEngine`. Strategies are built from named factories, so new ones
+//! (PhoenixEngine, micro-lightning, future strategies) plug in without
+//! touching `strategy.rs`. Strategies can be added, removed, paused and
+//! resumed at runtime through the shared registry handle, and only receive
+//! the `MarketEvent` kinds they subscribe to.
+
+use super::{
+    death_spiral_intercept::DeathSpiralInterceptStrategy,
+    liquidity_tsunami::LiquidityTsunamiStrategy, meme_virus::MemeVirusStrategy,
+    phoenix_engine::PhoenixEngine, social_fission::SocialFissionStrategy,
+    whale_shadowing::WhaleShadowingStrategy, MarketEvent, MarketEventKind, MemcoinStrategy,
+    MemcoinStrategyParams,
+};
+use crate::modules::indicators::{IndicatorEngine, SharedIndicators};
+use crate::modules::kill_switch::authorize;
+use crate::modules::micro_lightning::MicroLightningStrategy;
+use crate::modules::strategy::{StrategyType, TradingSignal};
+use anyhow::{anyhow, Context, Result};
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::Json;
+use axum::routing::{get, post};
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use tracing::{error, info, warn};
+
+/// Builds a strategy instance from the capital allocated to it
+pub type StrategyFactory = Arc<dyn Fn(f64) -> Box<dyn MemcoinStrategy> + Send + Sync>;
+
+/// Kinds registered by `StrategyRegistry::new`
+pub const BUILTIN_STRATEGY_KINDS: [&str; 7] = [
+    "liquidity_tsunami",
+    "social_fission",
+    "whale_shadowing",
+    "death_spiral_intercept",
+    "meme_virus",
+    "phoenix_engine",
+    "micro_lightning",
+];
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_capital_allocation() -> f64 {
+    1.0
+}
+
+/// One `[[strategies]]` entry of the registry config
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyEntryConfig {
+    /// Unique registry name
+    pub name: String,
+    /// Factory key; defaults to `name`
+    #[serde(default)]
+    pub kind: Option<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Share of engine capital handed to the strategy (0.0 - 1.0]. Strategies
+    /// still apply their own internal allocation on top of it.
+    #[serde(default = "default_capital_allocation")]
+    pub capital_allocation: f64,
+    /// Overrides applied via `MemcoinStrategy::update_params` after construction
+    #[serde(default)]
+    pub params: Option<MemcoinStrategyParams>,
+}
+
+impl StrategyEntryConfig {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            kind: None,
+            enabled: default_enabled(),
+            capital_allocation: default_capital_allocation(),
+            params: None,
+        }
+    }
+
+    pub fn kind(&self) -> &str {
+        self.kind.as_deref().unwrap_or(&self.name)
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(anyhow!("Strategy name must not be empty"));
+        }
+        if !(self.capital_allocation > 0.0 && self.capital_allocation <= 1.0) {
+            return Err(anyhow!(
+                "Strategy '{}' capital_allocation must be in (0.0, 1.0], got {}",
+                self.name,
+                self.capital_allocation
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Registry configuration, usually loaded from a TOML file:
+///
+/// ```toml
+/// [[strategies]]
+/// name = "liquidity_tsunami"
+/// capital_allocation = 0.5
+///
+/// [[strategies]]
+/// name = "phoenix"
+/// kind = "phoenix_engine"
+/// enabled = false
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyRegistryConfig {
+    #[serde(default)]
+    pub strategies: Vec<StrategyEntryConfig>,
+}
+
+impl Default for StrategyRegistryConfig {
+    /// The five MEMCOIN SWARMGUARD strategies, enabled with full capital
+    fn default() -> Self {
+        Self {
+            strategies: BUILTIN_STRATEGY_KINDS[..5]
+                .iter()
+                .map(|name| StrategyEntryConfig::new(name))
+                .collect(),
+        }
+    }
+}
+
+impl StrategyRegistryConfig {
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        let config: Self =
+            toml::from_str(content).map_err(|e| anyhow!("Invalid strategy config: {}", e))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_file(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read strategy config: {}", path))?;
+        let config = Self::from_toml_str(&content)?;
+        info!(
+            "📂 Strategy config loaded from {}: {} entries",
+            path,
+            config.strategies.len()
+        );
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let mut names = HashSet::new();
+        for entry in &self.strategies {
+            entry.validate()?;
+            if !names.insert(entry.name.as_str()) {
+                return Err(anyhow!("Duplicate strategy name '{}'", entry.name));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Lifecycle state of a registered strategy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StrategyStatus {
+    /// Built but not activated yet
+    Loaded,
+    Active,
+    /// Deactivated at runtime; `resume` re-activates it
+    Paused,
+    /// Disabled in config; `resume` enables it
+    Disabled,
+}
+
+/// Snapshot of a registry entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyInfo {
+    pub name: String,
+    pub kind: String,
+    pub strategy_type: StrategyType,
+    pub status: StrategyStatus,
+    pub capital_allocation: f64,
+    pub capital: f64,
+    pub subscriptions: Vec<MarketEventKind>,
+}
+
+struct RegisteredStrategy {
+    config: StrategyEntryConfig,
+    capital: f64,
+    status: StrategyStatus,
+    subscriptions: HashSet<MarketEventKind>,
+    strategy: Box<dyn MemcoinStrategy>,
+}
+
+/// Ordered set of strategies; dispatch follows registration order
+pub struct StrategyRegistry {
+    capital: f64,
+    factories: HashMap<String, StrategyFactory>,
+    strategies: Vec<RegisteredStrategy>,
+    /// Fed by every dispatched tick, shared with the strategies
+    indicators: SharedIndicators,
+}
+
+impl StrategyRegistry {
+    /// Create a registry with the built-in factories
+    pub fn new(capital: f64) -> Self {
+        let mut registry = Self {
+            capital,
+            factories: HashMap::new(),
+            strategies: Vec::new(),
+            indicators: IndicatorEngine::memcoin_default().into_shared(),
+        };
+
+        registry.register_factory("liquidity_tsunami", |capital| {
+            Box::new(LiquidityTsunamiStrategy::new(capital))
+        });
+        registry.register_factory("social_fission", |capital| {
+            Box::new(SocialFissionStrategy::new(capital))
+        });
+        registry.register_factory("whale_shadowing", |capital| {
+            Box::new(WhaleShadowingStrategy::new(capital))
+        });
+        registry.register_factory("death_spiral_intercept", |capital| {
+            Box::new(DeathSpiralInterceptStrategy::new(capital))
+        });
+        registry.register_factory("meme_virus", |capital| {
+            Box::new(MemeVirusStrategy::new(capital))
+        });
+        registry.register_factory("phoenix_engine", |capital| {
+            Box::new(PhoenixEngine::new(capital))
+        });
+        registry.register_factory("micro_lightning", |capital| {
+            Box::new(MicroLightningStrategy::new(capital))
+        });
+
+        registry
+    }
+
+    /// Register (or replace) a factory so config entries can refer to `kind`
+    pub fn register_factory<F>(&mut self, kind: &str, factory: F)
+    where
+        F: Fn(f64) -> Box<dyn MemcoinStrategy> + Send + Sync + 'static,
+    {
+        self.factories.insert(kind.to_string(), Arc::new(factory));
+    }
+
+    pub fn capital(&self) -> f64 {
+        self.capital
+    }
+
+    /// Capital used for strategies built from now on
+    pub fn set_capital(&mut self, capital: f64) {
+        self.capital = capital;
+    }
+
+    /// Indicator engine updated on every `Tick` before strategies see it
+    pub fn indicators(&self) -> SharedIndicators {
+        self.indicators.clone()
+    }
+
+    /// Replace the indicator engine; strategies registered from now on get
+    /// the new one
+    pub fn with_indicators(mut self, engine: IndicatorEngine) -> Self {
+        self.indicators = engine.into_shared();
+        self
+    }
+
+    /// Build every entry of `config`; fails without changes on invalid config
+    pub async fn load(&mut self, config: &StrategyRegistryConfig) -> Result<usize> {
+        config.validate()?;
+        for entry in &config.strategies {
+            if !self.factories.contains_key(entry.kind()) {
+                return Err(anyhow!(
+                    "Unknown strategy kind '{}' for '{}'",
+                    entry.kind(),
+                    entry.name
+                ));
+            }
+            if self.contains(&entry.name) {
+                return Err(anyhow!("Strategy '{}' is already registered", entry.name));
+            }
+        }
+
+        for entry in &config.strategies {
+            self.add(entry.clone()).await?;
+        }
+        Ok(config.strategies.len())
+    }
+
+    /// Build and register a strategy. Enabled strategies start as `Loaded`
+    /// and are activated by `activate_all` (or `resume`).
+    pub async fn add(&mut self, entry: StrategyEntryConfig) -> Result<()> {
+        entry.validate()?;
+        if self.contains(&entry.name) {
+            return Err(anyhow!("Strategy '{}' is already registered", entry.name));
+        }
+        let factory = self
+            .factories
+            .get(entry.kind())
+            .cloned()
+            .ok_or_else(|| anyhow!("Unknown strategy kind '{}'", entry.kind()))?;
+
+        let capital = self.capital * entry.capital_allocation;
+        let mut strategy = factory(capital);
+        strategy.attach_indicators(self.indicators.clone());
+        if let Some(params) = entry.params.clone() {
+            strategy.update_params(params).await?;
+        }
+
+        let status = if entry.enabled {
+            StrategyStatus::Loaded
+        } else {
+            StrategyStatus::Disabled
+        };
+        info!(
+            "🧩 Registered strategy '{}' ({}) with {:.2} capital [{:?}]",
+            entry.name,
+            strategy.name(),
+            capital,
+            status
+        );
+
+        self.strategies.push(RegisteredStrategy {
+            config: entry,
+            capital,
+            status,
+            subscriptions: strategy.subscriptions().iter().copied().collect(),
+            strategy,
+        });
+        Ok(())
+    }
+
+    /// Deactivate and drop a strategy
+    pub async fn remove(&mut self, name: &str) -> Result<()> {
+        let index = self.index_of(name)?;
+        let mut entry = self.strategies.remove(index);
+        if entry.status == StrategyStatus::Active {
+            entry.strategy.deactivate().await?;
+        }
+        info!("🗑️ Removed strategy '{}'", name);
+        Ok(())
+    }
+
+    /// Stop routing market events to a strategy without dropping its state
+    pub async fn pause(&mut self, name: &str) -> Result<()> {
+        let index = self.index_of(name)?;
+        let entry = &mut self.strategies[index];
+        if entry.status == StrategyStatus::Active {
+            entry.strategy.deactivate().await?;
+        }
+        if entry.status != StrategyStatus::Disabled {
+            entry.status = StrategyStatus::Paused;
+        }
+        info!("⏸️ Paused strategy '{}'", name);
+        Ok(())
+    }
+
+    /// Activate a loaded, paused or disabled strategy
+    pub async fn resume(&mut self, name: &str) -> Result<()> {
+        let index = self.index_of(name)?;
+        let entry = &mut self.strategies[index];
+        if entry.status != StrategyStatus::Active {
+            entry.strategy.activate().await?;
+            entry.status = StrategyStatus::Active;
+            entry.config.enabled = true;
+        }
+        info!("▶️ Resumed strategy '{}'", name);
+        Ok(())
+    }
+
+    pub async fn update_params(&mut self, name: &str, params: MemcoinStrategyParams) -> Result<()> {
+        let index = self.index_of(name)?;
+        let entry = &mut self.strategies[index];
+        entry.strategy.update_params(params.clone()).await?;
+        entry.config.params = Some(params);
+        Ok(())
+    }
+
+    /// Activate every `Loaded` strategy; returns how many were activated
+    pub async fn activate_all(&mut self) -> Result<usize> {
+        let mut activated = 0;
+        for entry in self
+            .strategies
+            .iter_mut()
+            .filter(|e| e.status == StrategyStatus::Loaded)
+        {
+            entry.strategy.activate().await?;
+            entry.status = StrategyStatus::Active;
+            activated += 1;
+            info!("✅ Activated {} strategy", entry.strategy.name());
+        }
+        Ok(activated)
+    }
+
+    /// Deactivate every active strategy (back to `Loaded`)
+    pub async fn deactivate_all(&mut self) -> Result<()> {
+        for entry in self
+            .strategies
+            .iter_mut()
+            .filter(|e| e.status == StrategyStatus::Active)
+        {
+            entry.strategy.deactivate().await?;
+            entry.status = StrategyStatus::Loaded;
+        }
+        Ok(())
+    }
+
+    /// Route an event to the active strategies subscribed to its kind
+    pub async fn dispatch(&self, event: &MarketEvent) -> Vec<TradingSignal> {
+        let kind = event.kind();
+        if let MarketEvent::Tick(data) = event {
+            self.indicators.write().on_market_data(data);
+        }
+        let mut signals = Vec::new();
+        for entry in self
+            .strategies
+            .iter()
+            .filter(|e| e.status == StrategyStatus::Active && e.subscriptions.contains(&kind))
+        {
+            match entry.strategy.process_signal(event).await {
+                Ok(Some(signal)) => signals.push(signal),
+                Ok(None) => {}
+                Err(e) => warn!(
+                    "⚠️ Strategy '{}' failed to process event: {}",
+                    entry.config.name, e
+                ),
+            }
+        }
+        signals
+    }
+
+    /// Names of active strategies subscribed to `kind`
+    pub fn subscribers(&self, kind: MarketEventKind) -> Vec<&str> {
+        self.strategies
+            .iter()
+            .filter(|e| e.status == StrategyStatus::Active && e.subscriptions.contains(&kind))
+            .map(|e| e.config.name.as_str())
+            .collect()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.strategies.iter().any(|e| e.config.name == name)
+    }
+
+    pub fn status(&self, name: &str) -> Option<StrategyStatus> {
+        self.strategies
+            .iter()
+            .find(|e| e.config.name == name)
+            .map(|e| e.status)
+    }
+
+    pub fn len(&self) -> usize {
+        self.strategies.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strategies.is_empty()
+    }
+
+    pub fn active_count(&self) -> usize {
+        self.strategies
+            .iter()
+            .filter(|e| e.status == StrategyStatus::Active)
+            .count()
+    }
+
+    pub fn list(&self) -> Vec<StrategyInfo> {
+        self.strategies
+            .iter()
+            .map(|e| StrategyInfo {
+                name: e.config.name.clone(),
+                kind: e.config.kind().to_string(),
+                strategy_type: e.strategy.strategy_type(),
+                status: e.status,
+                capital_allocation: e.config.capital_allocation,
+                capital: e.capital,
+                subscriptions: e.strategy.subscriptions().to_vec(),
+            })
+            .collect()
+    }
+
+    /// Current registry contents as config (e.g. to persist runtime changes)
+    pub fn to_config(&self) -> StrategyRegistryConfig {
+        StrategyRegistryConfig {
+            strategies: self
+                .strategies
+                .iter()
+                .map(|e| {
+                    let mut config = e.config.clone();
+                    config.enabled = e.status != StrategyStatus::Disabled;
+                    config
+                })
+                .collect(),
+        }
+    }
+
+    fn index_of(&self, name: &str) -> Result<usize> {
+        self.strategies
+            .iter()
+            .position(|e| e.config.name == name)
+            .ok_or_else(|| anyhow!("Strategy '{}' is not registered", name))
+    }
+}
+
+// ========== HTTP API ==========
+
+#[derive(Clone)]
+struct StrategyApiState {
+    registry: Arc<RwLock<StrategyRegistry>>,
+    api_token: Option<Arc<str>>,
+}
+
+/// `GET /` lists strategies; `POST /` adds one (a `[[strategies]]` entry as
+/// JSON, activated when enabled), `DELETE /:name` removes it and
+/// `POST /:name/pause`, `POST /:name/resume` and `POST /:name/params` change
+/// it at runtime. Changes need `Authorization: Bearer <api_token>`; without a
+/// token they are refused
+pub fn create_strategy_registry_router(
+    registry: Arc<RwLock<StrategyRegistry>>,
+    api_token: Option<String>,
+) -> Router {
+    Router::new()
+        .route("/", get(list_strategies).post(add_strategy))
+        .route("/:name", axum::routing::delete(remove_strategy))
+        .route("/:name/pause", post(pause_strategy))
+        .route("/:name/resume", post(resume_strategy))
+        .route("/:name/params", post(update_strategy_params))
+        .with_state(StrategyApiState {
+            registry,
+            api_token: api_token.filter(|t| !t.is_empty()).map(Arc::from),
+        })
+}
+
+async fn list_strategies(State(state): State<StrategyApiState>) -> Json<Vec<StrategyInfo>> {
+    Json(state.registry.read().await.list())
+}
+
+async fn add_strategy(
+    State(state): State<StrategyApiState>,
+    headers: HeaderMap,
+    Json(entry): Json<StrategyEntryConfig>,
+) -> Result<Json<Vec<StrategyInfo>>, StatusCode> {
+    authorize(&headers, state.api_token.as_deref())?;
+    let mut registry = state.registry.write().await;
+    let (name, enabled) = (entry.name.clone(), entry.enabled);
+    if let Err(e) = registry.add(entry).await {
+        warn!("⚠️ Strategy add rejected: {}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if enabled {
+        registry.resume(&name).await.map_err(|e| {
+            error!("❌ Failed to activate strategy '{}': {}", name, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    }
+    Ok(Json(registry.list()))
+}
+
+async fn remove_strategy(
+    State(state): State<StrategyApiState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> Result<Json<Vec<StrategyInfo>>, StatusCode> {
+    authorize(&headers, state.api_token.as_deref())?;
+    let mut registry = state.registry.write().await;
+    if !registry.contains(&name) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    registry.remove(&name).await.map_err(|e| {
+        error!("❌ Failed to remove strategy '{}': {}", name, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(registry.list()))
+}
+
+async fn pause_strategy(
+    State(state): State<StrategyApiState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> Result<Json<Vec<StrategyInfo>>, StatusCode> {
+    authorize(&headers, state.api_token.as_deref())?;
+    let mut registry = state.registry.write().await;
+    if !registry.contains(&name) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    registry.pause(&name).await.map_err(|e| {
+        error!("❌ Failed to pause strategy '{}': {}", name, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(registry.list()))
+}
+
+async fn resume_strategy(
+    State(state): State<StrategyApiState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+) -> Result<Json<Vec<StrategyInfo>>, StatusCode> {
+    authorize(&headers, state.api_token.as_deref())?;
+    let mut registry = state.registry.write().await;
+    if !registry.contains(&name) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    registry.resume(&name).await.map_err(|e| {
+        error!("❌ Failed to resume strategy '{}': {}", name, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(registry.list()))
+}
+
+async fn update_strategy_params(
+    State(state): State<StrategyApiState>,
+    headers: HeaderMap,
+    Path(name): Path<String>,
+    Json(params): Json<MemcoinStrategyParams>,
+) -> Result<Json<Vec<StrategyInfo>>, StatusCode> {
+    authorize(&headers, state.api_token.as_deref())?;
+    let mut registry = state.registry.write().await;
+    if !registry.contains(&name) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    if let Err(e) = registry.update_params(&name, params).await {
+        warn!("⚠️ Strategy '{}' params rejected: {}", name, e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    Ok(Json(registry.list()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::memcoin_strategies::{LiquidityEvent, SocialSignal};
+
+    fn tsunami_event() -> MarketEvent {
+        MarketEvent::Liquidity(LiquidityEvent {
+            mint: "TEST".to_string(),
+            delta: 80.0,
+            velocity: 0.9,
+            volatility: 0.1,
+            timestamp: chrono::Utc::now(),
+        })
+    }
+
+    #[test]
+    fn test_config_parsing() {
+        let config = StrategyRegistryConfig::from_toml_str(
+            r#"
+            [[strategies]]
+            name = "liquidity_tsunami"
+            capital_allocation = 0.5
+
+            [[strategies]]
+            name = "phoenix"
+            kind = "phoenix_engine"
+            enabled = false
+
+            [strategies.params]
+            risk_tolerance = 0.3
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.strategies.len(), 2);
+        assert_eq!(config.strategies[0].capital_allocation, 0.5);
+        assert_eq!(config.strategies[1].kind(), "phoenix_engine");
+        assert!(!config.strategies[1].enabled);
+        let params = config.strategies[1].params.as_ref().unwrap();
+        assert_eq!(params.risk_tolerance, 0.3);
+        assert_eq!(params.stop_loss, MemcoinStrategyParams::default().stop_loss);
+
+        let duplicate = "[[strategies]]\nname = \"a\"\n[[strategies]]\nname = \"a\"\n";
+        assert!(StrategyRegistryConfig::from_toml_str(duplicate).is_err());
+        let over = "[[strategies]]\nname = \"a\"\ncapital_allocation = 1.5\n";
+        assert!(StrategyRegistryConfig::from_toml_str(over).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_load_and_runtime_control() {
+        let mut registry = StrategyRegistry::new(10_000.0);
+        let mut config = StrategyRegistryConfig::default();
+        config.strategies[0].capital_allocation = 0.5;
+        config.strategies[1].enabled = false;
+
+        assert_eq!(registry.load(&config).await.unwrap(), 5);
+        assert_eq!(registry.activate_all().await.unwrap(), 4);
+        assert_eq!(
+            registry.status("social_fission"),
+            Some(StrategyStatus::Disabled)
+        );
+        assert_eq!(registry.list()[0].capital, 5_000.0);
+
+        let signals = registry.dispatch(&tsunami_event()).await;
+        assert_eq!(signals.len(), 1);
+        assert_eq!(signals[0].strategy_type, StrategyType::LiquidityTsunami);
+
+        registry.pause("liquidity_tsunami").await.unwrap();
+        assert!(registry.dispatch(&tsunami_event()).await.is_empty());
+        registry.resume("liquidity_tsunami").await.unwrap();
+        assert_eq!(registry.dispatch(&tsunami_event()).await.len(), 1);
+
+        registry.remove("liquidity_tsunami").await.unwrap();
+        assert!(!registry.contains("liquidity_tsunami"));
+        assert!(registry.remove("liquidity_tsunami").await.is_err());
+
+        assert!(registry
+            .add(StrategyEntryConfig::new("unknown_kind"))
+            .await
+            .is_err());
+        assert!(registry
+            .add(StrategyEntryConfig::new("meme_virus"))
+            .await
+            .is_err());
+        assert_eq!(registry.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_custom_factory_plugs_in() {
+        let mut registry = StrategyRegistry::new(1_000.0);
+        registry.register_factory("tsunami_clone", |capital| {
+            Box::new(LiquidityTsunamiStrategy::new(capital))
+        });
+
+        let mut entry = StrategyEntryConfig::new("clone");
+        entry.kind = Some("tsunami_clone".to_string());
+        registry.add(entry).await.unwrap();
+        registry.activate_all().await.unwrap();
+
+        assert_eq!(registry.active_count(), 1);
+        assert_eq!(registry.dispatch(&tsunami_event()).await.len(), 1);
+        assert_eq!(registry.to_config().strategies[0].kind(), "tsunami_clone");
+    }
+
+    #[tokio::test]
+    async fn test_events_routed_by_subscription() {
+        let mut registry = StrategyRegistry::new(10_000.0);
+        registry
+            .load(&StrategyRegistryConfig::default())
+            .await
+            .unwrap();
+        registry.activate_all().await.unwrap();
+
+        assert_eq!(
+            registry.subscribers(MarketEventKind::Liquidity),
+            vec!["liquidity_tsunami"]
+        );
+        assert_eq!(
+            registry.subscribers(MarketEventKind::Social),
+            vec!["social_fission"]
+        );
+        assert_eq!(
+            registry.subscribers(MarketEventKind::ViralMeme),
+            vec!["meme_virus"]
+        );
+        assert!(registry.subscribers(MarketEventKind::NewPool).is_empty());
+
+        // A social signal only reaches SOCIAL FISSION, never MEME VIRUS
+        let social = MarketEvent::Social(SocialSignal {
+            token: "TEST".to_string(),
+            intensity: 95.0,
+            sentiment: 0.9,
+            mentions_count: 50,
+            source: "twitter".to_string(),
+            timestamp: chrono::Utc::now(),
+        });
+        for signal in registry.dispatch(&social).await {
+            assert_eq!(signal.strategy_type, StrategyType::SocialFission);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ticks_feed_shared_indicators() {
+        use crate::modules::data_ingestor::{DataSource, MarketData};
+        use crate::modules::indicators::{IndicatorSpec, Timeframe};
+
+        let engine = IndicatorEngine::new()
+            .with_indicator("atr", IndicatorSpec::Atr { period: 2 }, Timeframe::Tick)
+            .unwrap();
+        let registry = StrategyRegistry::new(1_000.0).with_indicators(engine);
+
+        // No strategy subscribes, the engine is still updated
+        for price in [1.0, 1.2, 1.1] {
+            let tick = MarketEvent::Tick(MarketData {
+                symbol: "BONK".to_string(),
+                price,
+                volume: 100.0,
+                timestamp: chrono::Utc::now(),
+                source: DataSource::Replay,
+                price_change_1h: 0.0,
+                price_change_24h: 0.0,
+            });
+            assert!(registry.dispatch(&tick).await.is_empty());
+        }
+
+        let atr = registry.indicators().read().value("BONK", "atr").unwrap();
+        assert!((atr - 0.1).abs() < 1e-9, "{}", atr);
+    }
+}
@@ -259,13 +259,16 @@ impl MemcoinStrategy for SocialFissionStrategy {
         StrategyType::SocialFission
     }
 
-    async fn process_signal(&self, signal: &(dyn std::any::Any + Send + Sync)) -> Result<Option<TradingSignal>> {
+    fn subscriptions(&self) -> &[MarketEventKind] {
+        &[MarketEventKind::Social]
+    }
+
+    async fn process_signal(&self, event: &MarketEvent) -> Result<Option<TradingSignal>> {
         if !self.is_active {
             return Ok(None);
         }
 
-        // Próba konwersji sygnału na SocialSignal
-        if let Some(social_signal) = signal.downcast_ref::<SocialSignal>() {
+        if let MarketEvent::Social(social_signal) = event {
             debug!("Processing social signal for {}: intensity={}, sentiment={}", 
                    social_signal.token, social_signal.intensity, social_signal.sentiment);
 
@@ -313,13 +313,16 @@ impl MemcoinStrategy for WhaleShadowingStrategy {
         StrategyType::WhaleShadowing
     }
 
-    async fn process_signal(&self, signal: &(dyn std::any::Any + Send + Sync)) -> Result<Option<TradingSignal>> {
+    fn subscriptions(&self) -> &[MarketEventKind] {
+        &[MarketEventKind::Whale]
+    }
+
+    async fn process_signal(&self, event: &MarketEvent) -> Result<Option<TradingSignal>> {
         if !self.is_active {
             return Ok(None);
         }
 
-        // Próba konwersji sygnału na WhaleTransaction
-        if let Some(whale_tx) = signal.downcast_ref::<WhaleTransaction>() {
+        if let MarketEvent::Whale(whale_tx) = event {
             debug!("Processing whale transaction: {} {} {} SOL", 
                    whale_tx.wallet, whale_tx.action, whale_tx.amount);
 
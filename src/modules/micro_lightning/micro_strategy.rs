@@ -11,7 +11,7 @@ use tracing::{debug, info, warn, error};
 
 use crate::modules::strategy::{TradingSignal, TradeAction, StrategyType, UrgencyLevel};
 use crate::modules::memcoin_strategies::{
-    MemcoinStrategy, MemcoinStrategyParams, StrategyMetrics
+    MarketEvent, MarketEventKind, MemcoinStrategy, MemcoinStrategyParams, StrategyMetrics
 };
 
 use super::{
@@ -356,14 +356,17 @@ impl MemcoinStrategy for MicroLightningStrategy {
         StrategyType::MicroLightning
     }
 
-    async fn process_signal(&self, signal: &(dyn std::any::Any + Send + Sync)) -> Result<Option<TradingSignal>> {
+    fn subscriptions(&self) -> &[MarketEventKind] {
+        &[MarketEventKind::NewPool]
+    }
+
+    async fn process_signal(&self, event: &MarketEvent) -> Result<Option<TradingSignal>> {
         if !self.is_active {
             return Ok(None);
         }
 
-        // Try to downcast to TokenData
-        if let Some(_token_data) = signal.downcast_ref::<TokenData>() {
-            // Process the token data (placeholder for now)
+        if let MarketEvent::NewPool(_pool) = event {
+            // Process the new pool candidate (placeholder for now)
             return Ok(None);
         }
 
@@ -4,6 +4,8 @@
 //! Implements $20/60min portfolio segmentation with tactical allocations
 
 use anyhow::Result;
+use crate::modules::liquidity_sizing::{LiquidityConstraint, LiquidityProfile};
+use crate::modules::strategy::TradeAction;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use tracing::{info, warn};
@@ -156,6 +158,29 @@ impl MicroWallet {
         position_size
     }
 
+    /// Lightning position size capped so entry and stressed exit impact stay
+    /// within the constraint; `liquidity` must be priced in USD
+    pub fn get_liquidity_capped_position_size(
+        &self,
+        risk_percentage: f64,
+        constraint: &LiquidityConstraint,
+        liquidity: &LiquidityProfile,
+    ) -> f64 {
+        let position_size = self.get_lightning_position_size(risk_percentage);
+        let limit = constraint.limit(liquidity, &TradeAction::Buy);
+
+        if position_size > limit.max_notional() {
+            info!(
+                "🌊 Lightning position capped by liquidity: ${:.2} -> ${:.2} ({} impact bound)",
+                position_size, limit.max_notional(),
+                if limit.exit_bound() { "exit" } else { "entry" }
+            );
+            return limit.max_notional();
+        }
+
+        position_size
+    }
+
     /// Get reentry allocation
     pub fn get_reentry_allocation(&self, boost_percentage: f64) -> f64 {
         let allocation = self.reentry * boost_percentage.min(0.6); // Max 60% of reentry buffer
@@ -289,6 +314,21 @@ mod tests {
         assert_eq!(wallet.psychology, 5.0); // 4.0 + 1.0 tax
     }
 
+    #[test]
+    fn test_liquidity_capped_position_size() {
+        let wallet = MicroWallet::new();
+        let constraint = LiquidityConstraint::default();
+
+        // $20k pool: 80% of the $4 lightning wallet fits easily
+        let deep = LiquidityProfile::from_total_liquidity(0.001, 20_000.0);
+        assert_eq!(wallet.get_liquidity_capped_position_size(0.8, &constraint, &deep), 3.2);
+
+        // $400 pool: 1% entry impact allows ~$1.98
+        let thin = LiquidityProfile::from_total_liquidity(0.001, 400.0);
+        let capped = wallet.get_liquidity_capped_position_size(0.8, &constraint, &thin);
+        assert!((capped - 200.0 * 0.01 / 1.01).abs() < 1e-9);
+    }
+
     #[test]
     fn test_wallet_integrity() {
         let wallet = MicroWallet::new();
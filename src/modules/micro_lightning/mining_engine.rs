@@ -10,6 +10,9 @@ use tracing::{debug, info, warn};
 
 use super::entry_conditions::TokenData;
 use super::micro_wallet::MicroWallet;
+use crate::modules::liquidity_sizing::{
+    LiquidityConstraint, LiquidityProfile, LiquiditySizingConfig,
+};
 
 /// DEX options for trade execution
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -104,6 +107,8 @@ pub struct MiningConfig {
     pub default_slippage: f64,
     pub priority_fee_multiplier: f64,
     pub max_concurrent_positions: usize,
+    #[serde(default)]
+    pub liquidity_sizing: LiquiditySizingConfig, // Impact caps against token liquidity
 }
 
 impl Default for MiningConfig {
@@ -116,6 +121,7 @@ impl Default for MiningConfig {
             default_slippage: 3.5,
             priority_fee_multiplier: 1.5,
             max_concurrent_positions: 3,
+            liquidity_sizing: LiquiditySizingConfig::default(),
         }
     }
 }
@@ -162,7 +168,15 @@ impl MiningEngine {
 
         // Calculate position sizing
         let wallet = MicroWallet::new(); // In real implementation, this would be passed in
-        let position_size = wallet.get_lightning_position_size(self.config.default_position_size_ratio);
+        let position_size = if token.liquidity > 0.0 && token.entry_price > 0.0 {
+            wallet.get_liquidity_capped_position_size(
+                self.config.default_position_size_ratio,
+                &LiquidityConstraint::new(self.config.liquidity_sizing.clone()),
+                &LiquidityProfile::from_total_liquidity(token.entry_price, token.liquidity),
+            )
+        } else {
+            wallet.get_lightning_position_size(self.config.default_position_size_ratio)
+        };
         let reentry_amount = wallet.get_reentry_allocation(self.config.default_reentry_boost_ratio);
         let dlmm_allocation = wallet.get_tactical_exit_allocation();
 
@@ -7,10 +7,16 @@ Organized into logical groups for better maintainability and clarity
 // CORE SYSTEM MODULES
 // ============================================================================
 pub mod data_ingestor;
+pub mod market_replay;
 pub mod executor;
+pub mod fill_simulator;
+pub mod order_manager;
+pub mod position_ledger;
 pub mod persistence;
+pub mod trade_journal;
 pub mod error_handling;
 pub mod metrics;
+pub mod supervisor;
 
 // ============================================================================
 // AI & INTELLIGENCE MODULES
@@ -26,6 +32,8 @@ pub mod tensorzero_client;
 // ============================================================================
 pub mod strategy;
 pub mod risk;
+pub mod risk_rules;
+pub mod kill_switch;
 pub mod hft_engine;
 pub mod real_sell_executor;
 pub mod micro_lightning;
@@ -38,6 +46,8 @@ pub mod hybrid_price_fetcher;
 pub mod real_price_fetcher;
 pub mod jupiter_dex;
 pub mod rpc_failover;
+pub mod swap_builder;
+pub mod indicators;
 
 // ============================================================================
 // JITO & MEV MODULES (THE OVERMIND PROTOCOL)
@@ -94,9 +104,11 @@ pub mod submillisecond_optimizer;
 // ============================================================================
 pub mod advanced_risk_management;
 pub mod dynamic_position_sizing;
+pub mod liquidity_sizing;
 pub mod portfolio_rebalancer;
 pub mod profit_manager;
 pub mod resource_manager;
+pub mod tail_risk;
 
 // ============================================================================
 // SNIPLE SOLANA BOT ENHANCED MODULES
@@ -9,6 +9,8 @@ use tracing::{debug, error, info, warn};
 use crate::config::TradingMode;
 use crate::modules::executor::{ExecutionResult, ExecutionStatus};
 use crate::modules::hft_engine::{HftEngine, HftEngineConfig};
+use crate::modules::kill_switch::{KillSwitch, KillTarget};
+use crate::modules::position_ledger::PositionLedger;
 use crate::modules::risk::ApprovedSignal;
 use crate::modules::strategy::StrategyType;
 use crate::modules::wallet_manager::{WalletManager, WalletSelectionCriteria, WalletType};
@@ -37,6 +39,10 @@ pub struct MultiWalletExecutor {
     wallet_selection_timeout_ms: u64,
     fallback_wallet_id: Option<String>,
     execution_stats: Arc<RwLock<ExecutionStats>>,
+    // Shared position/PnL ledger fed with every execution result
+    ledger: Option<Arc<PositionLedger>>,
+    // Checked against the routed wallet before its keypair is loaded
+    kill_switch: Option<Arc<KillSwitch>>,
 }
 
 /// Execution statistics per wallet
@@ -73,9 +79,23 @@ impl MultiWalletExecutor {
             wallet_selection_timeout_ms,
             fallback_wallet_id,
             execution_stats: Arc::new(RwLock::new(ExecutionStats::default())),
+            ledger: None,
+            kill_switch: None,
         }
     }
 
+    /// Record every execution result in the shared position ledger
+    pub fn with_ledger(mut self, ledger: Arc<PositionLedger>) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
+    /// Cancel signals the kill switch covers, including wallet-scoped kills
+    pub fn with_kill_switch(mut self, kill_switch: Arc<KillSwitch>) -> Self {
+        self.kill_switch = Some(kill_switch);
+        self
+    }
+
     /// Create new multi-wallet executor with HFT engine
     #[allow(clippy::too_many_arguments)]
     pub fn new_with_hft(
@@ -103,6 +123,8 @@ impl MultiWalletExecutor {
             wallet_selection_timeout_ms,
             fallback_wallet_id,
             execution_stats: Arc::new(RwLock::new(ExecutionStats::default())),
+            ledger: None,
+            kill_switch: None,
         })
     }
 
@@ -184,7 +206,10 @@ impl MultiWalletExecutor {
         // Step 3: Update statistics
         self.update_execution_stats(&result).await;
 
-        // Step 4: Send result to persistence
+        // Step 4: Update positions, then send result to persistence
+        if let Some(ref ledger) = self.ledger {
+            ledger.apply_execution(&result);
+        }
         if let Err(e) = self.persistence_sender.send(result.clone()) {
             error!("Failed to send execution result to persistence: {}", e);
         }
@@ -288,6 +313,22 @@ impl MultiWalletExecutor {
             signal_id, wallet_id, routed_signal.wallet_selection_reason
         );
 
+        if let Some(ref kill_switch) = self.kill_switch {
+            let target = KillTarget::for_signal(&routed_signal.original_signal.original_signal)
+                .with_wallet(Some(wallet_id.clone()));
+            if let Some(entry) = kill_switch.blocking(&target) {
+                warn!(
+                    "🛑 Signal {} not executed with wallet {}: kill switch engaged ({}) - {}",
+                    signal_id, wallet_id, entry.scope, entry.reason
+                );
+                return Ok(ExecutionResult::killed(
+                    &routed_signal.original_signal,
+                    &entry,
+                    Some(wallet_id),
+                ));
+            }
+        }
+
         // Get wallet keypair for signing
         let wallet_manager = self.wallet_manager.read().await;
         let wallet_keypair = wallet_manager.get_wallet_keypair(&wallet_id).await?;
@@ -345,6 +386,10 @@ impl MultiWalletExecutor {
                 * 0.001,
             timestamp: chrono::Utc::now(),
             error_message: None,
+            symbol: routed_signal.original_signal.original_signal.symbol.clone(),
+            action: Some(routed_signal.original_signal.original_signal.action.clone()),
+            wallet_id: Some(wallet_id.to_string()),
+            fee_breakdown: None,
         })
     }
 
@@ -394,6 +439,10 @@ impl MultiWalletExecutor {
                             * 0.0005,
                         timestamp: chrono::Utc::now(),
                         error_message: None,
+                        symbol: routed_signal.original_signal.original_signal.symbol.clone(),
+                        action: Some(routed_signal.original_signal.original_signal.action.clone()),
+                        wallet_id: Some(wallet_id.to_string()),
+                        fee_breakdown: None,
                     })
                 }
                 Err(_) => {
@@ -440,6 +489,10 @@ impl MultiWalletExecutor {
                 * 0.0025,
             timestamp: chrono::Utc::now(),
             error_message: None,
+            symbol: routed_signal.original_signal.original_signal.symbol.clone(),
+            action: Some(routed_signal.original_signal.original_signal.action.clone()),
+            wallet_id: Some(wallet_id.to_string()),
+            fee_breakdown: None,
         })
     }
 
@@ -491,6 +544,10 @@ impl MultiWalletExecutor {
                             * 0.0015,
                         timestamp: chrono::Utc::now(),
                         error_message: None,
+                        symbol: routed_signal.original_signal.original_signal.symbol.clone(),
+                        action: Some(routed_signal.original_signal.original_signal.action.clone()),
+                        wallet_id: Some(wallet_id.to_string()),
+                        fee_breakdown: None,
                     })
                 }
                 Err(_) => {
@@ -579,3 +636,68 @@ impl MultiWalletExecutor {
         self.execution_stats.read().await.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::kill_switch::{KillScope, KillSource};
+    use crate::modules::risk::KineticShieldStatus;
+    use crate::modules::strategy::{TradeAction, TradingSignal};
+
+    fn routed(wallet_id: &str) -> RoutedSignal {
+        RoutedSignal {
+            original_signal: ApprovedSignal {
+                original_signal: TradingSignal {
+                    signal_id: "routed-1".to_string(),
+                    symbol: "BONK/SOL".to_string(),
+                    action: TradeAction::Buy,
+                    quantity: 10.0,
+                    target_price: 1.0,
+                    price: Some(1.0),
+                    confidence: 0.9,
+                    timestamp: chrono::Utc::now(),
+                    strategy_type: StrategyType::MemeVirus,
+                    urgency: None,
+                    metadata: None,
+                },
+                approved_quantity: 10.0,
+                risk_score: 0.1,
+                approval_timestamp: chrono::Utc::now(),
+                kinetic_shield_status: KineticShieldStatus::Active,
+                risk_audit: Vec::new(),
+            },
+            selected_wallet_id: wallet_id.to_string(),
+            wallet_selection_reason: "test".to_string(),
+            routing_timestamp: chrono::Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_wallet_kill_blocks_signing() -> Result<()> {
+        let kill_switch = Arc::new(KillSwitch::in_memory());
+        kill_switch.engage(KillScope::wallet("aggressive-1"), KillSource::Operator, "drained")?;
+
+        let (_signal_tx, signal_rx) = mpsc::unbounded_channel();
+        let (persistence_tx, _persistence_rx) = mpsc::unbounded_channel();
+        // No wallets registered: reaching the keypair lookup is an error
+        let mut executor = MultiWalletExecutor::new(
+            signal_rx,
+            persistence_tx,
+            Arc::new(RwLock::new(WalletManager::new())),
+            TradingMode::Paper,
+            "https://api.devnet.solana.com".to_string(),
+            100,
+            None,
+        )
+        .with_kill_switch(kill_switch);
+
+        let result = executor.execute_routed_signal(routed("aggressive-1")).await?;
+        assert!(matches!(result.status, ExecutionStatus::Cancelled));
+        assert_eq!(result.wallet_id.as_deref(), Some("aggressive-1"));
+        assert!(result.error_message.unwrap().contains("drained"));
+
+        // Other wallets still go on to load their keypair
+        assert!(executor.execute_routed_signal(routed("primary-1")).await.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,779 @@
+// Order Manager Module
+// Tracks submitted transaction signatures on-chain:
+// sent → processed → confirmed → finalized, or failed / dropped / expired,
+// with timeouts, fresh-blockhash resubmission once the original blockhash has
+// expired, and ExecutionResult updates to persistence. Orders are keyed by signature.
+
+use crate::modules::executor::{ExecutionResult, ExecutionStatus};
+use crate::modules::position_ledger::PositionLedger;
+use crate::modules::rpc_failover::RpcFailoverClient;
+use crate::modules::supervisor::SupervisedComponent;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::{mpsc, RwLock};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+
+/// Lifecycle state of a submitted order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum OrderState {
+    Sent,
+    Processed,
+    Confirmed,
+    Finalized,
+    /// Landed with an on-chain error
+    Failed,
+    /// Never landed within the confirmation timeout
+    Dropped,
+    /// Blockhash expired before the transaction landed
+    Expired,
+}
+
+impl OrderState {
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            OrderState::Finalized | OrderState::Failed | OrderState::Dropped | OrderState::Expired
+        )
+    }
+
+    pub fn execution_status(&self) -> ExecutionStatus {
+        match self {
+            OrderState::Sent | OrderState::Processed => ExecutionStatus::Pending,
+            OrderState::Confirmed | OrderState::Finalized => ExecutionStatus::Confirmed,
+            OrderState::Failed | OrderState::Dropped | OrderState::Expired => {
+                ExecutionStatus::Failed
+            }
+        }
+    }
+}
+
+/// Commitment level reported for a signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Commitment {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl Commitment {
+    fn order_state(&self) -> OrderState {
+        match self {
+            Commitment::Processed => OrderState::Processed,
+            Commitment::Confirmed => OrderState::Confirmed,
+            Commitment::Finalized => OrderState::Finalized,
+        }
+    }
+}
+
+/// On-chain status of a signature
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignatureStatus {
+    pub slot: u64,
+    pub commitment: Commitment,
+    pub err: Option<String>,
+}
+
+/// Chain view used to track orders
+#[async_trait]
+pub trait OrderStatusSource: Send + Sync {
+    /// None when the cluster has not seen the signature
+    async fn signature_status(&self, signature: &str) -> Result<Option<SignatureStatus>>;
+    async fn block_height(&self) -> Result<u64>;
+}
+
+/// Rebuilds and re-signs an order with a fresh blockhash
+#[async_trait]
+pub trait OrderResubmitter: Send + Sync {
+    async fn resubmit(&self, order: &TrackedOrder) -> Result<Submission>;
+}
+
+/// A signed transaction that has been sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Submission {
+    pub signature: String,
+    /// Last block height at which the blockhash is valid (None = timeout only)
+    pub last_valid_block_height: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderManagerConfig {
+    pub poll_interval_ms: u64,
+    /// Time to reach `Confirmed` before an attempt counts as dropped. An attempt
+    /// whose blockhash is known is only dropped once that blockhash has expired,
+    /// since until then the original transaction can still land
+    pub confirmation_timeout_secs: u64,
+    pub max_resubmits: u32,
+}
+
+impl Default for OrderManagerConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval_ms: 400,
+            confirmation_timeout_secs: 60,
+            max_resubmits: 2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackedOrder {
+    pub order_id: String,
+    pub signature: String,
+    pub state: OrderState,
+    pub slot: Option<u64>,
+    pub last_valid_block_height: Option<u64>,
+    pub attempts: u32,
+    pub submitted_at: chrono::DateTime<chrono::Utc>,
+    pub history: Vec<(OrderState, chrono::DateTime<chrono::Utc>)>,
+    /// Result as reported by the executor; status/signature are kept current
+    pub result: ExecutionResult,
+}
+
+/// Tracks orders until they reach a terminal state
+pub struct OrderManager {
+    source: Arc<dyn OrderStatusSource>,
+    resubmitter: Option<Arc<dyn OrderResubmitter>>,
+    persistence_sender: mpsc::UnboundedSender<ExecutionResult>,
+    config: OrderManagerConfig,
+    orders: Arc<RwLock<HashMap<String, TrackedOrder>>>,
+    ledger: Option<Arc<PositionLedger>>,
+}
+
+impl OrderManager {
+    pub fn new(
+        source: Arc<dyn OrderStatusSource>,
+        persistence_sender: mpsc::UnboundedSender<ExecutionResult>,
+        config: OrderManagerConfig,
+    ) -> Self {
+        Self {
+            source,
+            resubmitter: None,
+            persistence_sender,
+            config,
+            orders: Arc::new(RwLock::new(HashMap::new())),
+            ledger: None,
+        }
+    }
+
+    /// Resubmit orders with a fresh blockhash once the original has expired unseen
+    pub fn with_resubmitter(mut self, resubmitter: Arc<dyn OrderResubmitter>) -> Self {
+        self.resubmitter = Some(resubmitter);
+        self
+    }
+
+    /// Book confirmed orders into the shared position ledger
+    pub fn with_ledger(mut self, ledger: Arc<PositionLedger>) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
+    /// Start tracking a sent transaction; `result` is the executor's view of the order
+    pub async fn track(&self, mut result: ExecutionResult, submission: Submission) {
+        let now = chrono::Utc::now();
+        result.transaction_id = submission.signature.clone();
+        result.status = ExecutionStatus::Pending;
+        result.timestamp = now;
+
+        if self.orders.read().await.contains_key(&submission.signature) {
+            warn!("📨 Signature {} is already tracked", submission.signature);
+            return;
+        }
+
+        let order = TrackedOrder {
+            order_id: result.signal_id.clone(),
+            signature: submission.signature,
+            state: OrderState::Sent,
+            slot: None,
+            last_valid_block_height: submission.last_valid_block_height,
+            attempts: 1,
+            submitted_at: now,
+            history: vec![(OrderState::Sent, now)],
+            result,
+        };
+        info!("📨 Tracking order {} ({})", order.order_id, order.signature);
+        self.emit(&order);
+        self.orders
+            .write()
+            .await
+            .insert(order.signature.clone(), order);
+    }
+
+    /// Order by its current transaction signature
+    pub async fn order(&self, signature: &str) -> Option<TrackedOrder> {
+        self.orders.read().await.get(signature).cloned()
+    }
+
+    /// Every order submitted for a signal, oldest first
+    pub async fn orders_for_signal(&self, signal_id: &str) -> Vec<TrackedOrder> {
+        let mut orders: Vec<TrackedOrder> = self
+            .orders
+            .read()
+            .await
+            .values()
+            .filter(|o| o.order_id == signal_id)
+            .cloned()
+            .collect();
+        orders.sort_by_key(|o| o.submitted_at);
+        orders
+    }
+
+    pub async fn active_orders(&self) -> Vec<TrackedOrder> {
+        self.orders
+            .read()
+            .await
+            .values()
+            .filter(|o| !o.state.is_terminal())
+            .cloned()
+            .collect()
+    }
+
+    /// Poll active orders every `poll_interval_ms` until `shutdown` is cancelled
+    pub async fn run(&self, shutdown: CancellationToken) -> Result<()> {
+        info!("📨 OrderManager starting...");
+        let mut interval = tokio::time::interval(std::time::Duration::from_millis(
+            self.config.poll_interval_ms,
+        ));
+
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                _ = interval.tick() => {
+                    if let Err(e) = self.poll_once().await {
+                        warn!("📨 Order status poll failed: {}", e);
+                    }
+                }
+            }
+        }
+
+        info!("🛑 OrderManager stopping...");
+        Ok(())
+    }
+
+    /// Advance every active order once
+    pub async fn poll_once(&self) -> Result<()> {
+        let active: Vec<String> = self
+            .orders
+            .read()
+            .await
+            .values()
+            .filter(|o| !o.state.is_terminal())
+            .map(|o| o.signature.clone())
+            .collect();
+        if active.is_empty() {
+            return Ok(());
+        }
+
+        let block_height = match self.source.block_height().await {
+            Ok(height) => Some(height),
+            Err(e) => {
+                warn!("📨 Block height unavailable, expiry check skipped: {}", e);
+                None
+            }
+        };
+
+        for signature in active {
+            if let Err(e) = self.advance(&signature, block_height).await {
+                warn!("📨 Failed to update order {}: {}", signature, e);
+            }
+        }
+        Ok(())
+    }
+
+    async fn advance(&self, signature: &str, block_height: Option<u64>) -> Result<()> {
+        let order = self
+            .order(signature)
+            .await
+            .ok_or_else(|| anyhow!("Unknown order {}", signature))?;
+
+        let expired = matches!(
+            (block_height, order.last_valid_block_height),
+            (Some(height), Some(last_valid)) if height > last_valid
+        );
+        // With a known blockhash the transaction can land until it expires
+        let timed_out = chrono::Utc::now() - order.submitted_at
+            > chrono::Duration::seconds(self.config.confirmation_timeout_secs as i64)
+            && (expired || order.last_valid_block_height.is_none());
+
+        match self.source.signature_status(&order.signature).await? {
+            Some(status) if status.err.is_some() => {
+                let err = status.err.unwrap_or_default();
+                self.transition(signature, OrderState::Failed, Some(status.slot), Some(err))
+                    .await;
+            }
+            Some(status) => {
+                let state = status.commitment.order_state();
+                if state > order.state {
+                    self.transition(signature, state, Some(status.slot), None)
+                        .await;
+                } else if state < OrderState::Confirmed && timed_out {
+                    // Seen but never confirmed; it landed once, so never resubmit
+                    self.transition(
+                        signature,
+                        OrderState::Dropped,
+                        Some(status.slot),
+                        Some(format!(
+                            "stuck at {:?} past confirmation timeout after {} attempt(s)",
+                            state, order.attempts
+                        )),
+                    )
+                    .await;
+                }
+            }
+            None if expired => self.resubmit_or_close(order, OrderState::Expired).await,
+            None if timed_out => self.resubmit_or_close(order, OrderState::Dropped).await,
+            None => {}
+        }
+        Ok(())
+    }
+
+    /// Only an expired blockhash proves the original can no longer land, so
+    /// dropped orders (no blockhash known) are closed rather than resubmitted
+    async fn resubmit_or_close(&self, order: TrackedOrder, state: OrderState) {
+        let reason = match state {
+            OrderState::Expired => "blockhash expired",
+            _ => "confirmation timeout",
+        };
+
+        if let (OrderState::Expired, Some(ref resubmitter)) = (state, &self.resubmitter) {
+            if order.attempts <= self.config.max_resubmits {
+                match resubmitter.resubmit(&order).await {
+                    Ok(submission) => {
+                        warn!(
+                            "🔁 Order {} {} — resubmitted as {} (attempt {})",
+                            order.order_id,
+                            reason,
+                            submission.signature,
+                            order.attempts + 1
+                        );
+                        // Re-keyed under the new signature; the old one is dead
+                        let now = chrono::Utc::now();
+                        let mut orders = self.orders.write().await;
+                        if let Some(mut tracked) = orders.remove(&order.signature) {
+                            tracked.signature = submission.signature.clone();
+                            tracked.last_valid_block_height = submission.last_valid_block_height;
+                            tracked.attempts += 1;
+                            tracked.submitted_at = now;
+                            tracked.state = OrderState::Sent;
+                            tracked.slot = None;
+                            tracked.history.push((OrderState::Sent, now));
+                            tracked.result.transaction_id = submission.signature;
+                            tracked.result.status = ExecutionStatus::Pending;
+                            tracked.result.timestamp = now;
+                            self.emit(&tracked);
+                            orders.insert(tracked.signature.clone(), tracked);
+                        }
+                        return;
+                    }
+                    Err(e) => error!("🔁 Resubmission of {} failed: {}", order.order_id, e),
+                }
+            }
+        }
+
+        self.transition(
+            &order.signature,
+            state,
+            None,
+            Some(format!("{} after {} attempt(s)", reason, order.attempts)),
+        )
+        .await;
+    }
+
+    async fn transition(
+        &self,
+        signature: &str,
+        state: OrderState,
+        slot: Option<u64>,
+        error_message: Option<String>,
+    ) {
+        let now = chrono::Utc::now();
+        let mut orders = self.orders.write().await;
+        let Some(order) = orders.get_mut(signature) else {
+            return;
+        };
+        let order_id = order.order_id.clone();
+
+        debug!("📨 Order {}: {:?} → {:?}", order_id, order.state, state);
+        order.state = state;
+        order.slot = slot.or(order.slot);
+        order.history.push((state, now));
+        order.result.status = state.execution_status();
+        order.result.timestamp = now;
+        if error_message.is_some() {
+            order.result.error_message = error_message;
+        }
+        if matches!(state.execution_status(), ExecutionStatus::Failed) {
+            order.result.executed_quantity = 0.0;
+        }
+
+        match state {
+            OrderState::Confirmed | OrderState::Finalized => {
+                info!("✅ Order {} {:?} at slot {:?}", order_id, state, order.slot)
+            }
+            OrderState::Failed | OrderState::Dropped | OrderState::Expired => warn!(
+                "❌ Order {} {:?}: {}",
+                order_id,
+                state,
+                order.result.error_message.as_deref().unwrap_or("unknown")
+            ),
+            _ => {}
+        }
+        self.emit(order);
+    }
+
+    fn emit(&self, order: &TrackedOrder) {
+        if let Some(ref ledger) = self.ledger {
+            ledger.apply_execution(&order.result);
+        }
+        if let Err(e) = self.persistence_sender.send(order.result.clone()) {
+            error!("Failed to send order update to persistence: {}", e);
+        }
+    }
+}
+
+/// The executor holds the same manager to track orders, so the shared handle
+/// is what gets supervised
+#[async_trait]
+impl SupervisedComponent for Arc<OrderManager> {
+    async fn run(&mut self, shutdown: CancellationToken) -> Result<()> {
+        OrderManager::run(self, shutdown).await
+    }
+}
+
+#[async_trait]
+impl OrderStatusSource for RpcFailoverClient {
+    async fn signature_status(&self, signature: &str) -> Result<Option<SignatureStatus>> {
+        let response = self.get_signature_statuses(&[signature]).await?;
+        if let Some(status) = parse_signature_status(&response.result) {
+            return Ok(Some(status));
+        }
+
+        // Statuses only cover recent slots; older landings are found via getTransaction
+        let response = self.get_transaction(signature).await?;
+        Ok(parse_transaction_status(&response.result))
+    }
+
+    async fn block_height(&self) -> Result<u64> {
+        let response = self.get_block_height().await?;
+        response
+            .result
+            .as_u64()
+            .ok_or_else(|| anyhow!("Invalid getBlockHeight result: {}", response.result))
+    }
+}
+
+/// Parse the first entry of a `getSignatureStatuses` result
+pub fn parse_signature_status(result: &Value) -> Option<SignatureStatus> {
+    let status = result.get("value")?.get(0)?;
+    if status.is_null() {
+        return None;
+    }
+
+    let commitment = match status.get("confirmationStatus").and_then(Value::as_str) {
+        Some("finalized") => Commitment::Finalized,
+        Some("confirmed") => Commitment::Confirmed,
+        _ => Commitment::Processed,
+    };
+    Some(SignatureStatus {
+        slot: status.get("slot").and_then(Value::as_u64).unwrap_or(0),
+        commitment,
+        err: status
+            .get("err")
+            .filter(|err| !err.is_null())
+            .map(|err| err.to_string()),
+    })
+}
+
+/// Parse a `getTransaction` result (confirmed commitment or better)
+pub fn parse_transaction_status(result: &Value) -> Option<SignatureStatus> {
+    if result.is_null() {
+        return None;
+    }
+    Some(SignatureStatus {
+        slot: result.get("slot").and_then(Value::as_u64).unwrap_or(0),
+        commitment: Commitment::Confirmed,
+        err: result
+            .get("meta")
+            .and_then(|meta| meta.get("err"))
+            .filter(|err| !err.is_null())
+            .map(|err| err.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+
+    /// Scripted chain: per-signature status sequence, fixed block height
+    #[derive(Default)]
+    struct MockChain {
+        statuses: Mutex<HashMap<String, Vec<Option<SignatureStatus>>>>,
+        block_height: Mutex<u64>,
+    }
+
+    impl MockChain {
+        fn script(&self, signature: &str, statuses: Vec<Option<SignatureStatus>>) {
+            self.statuses.lock().insert(signature.to_string(), statuses);
+        }
+    }
+
+    #[async_trait]
+    impl OrderStatusSource for MockChain {
+        async fn signature_status(&self, signature: &str) -> Result<Option<SignatureStatus>> {
+            let mut statuses = self.statuses.lock();
+            let script = statuses.entry(signature.to_string()).or_default();
+            Ok(if script.len() > 1 {
+                script.remove(0)
+            } else {
+                script.first().cloned().flatten()
+            })
+        }
+
+        async fn block_height(&self) -> Result<u64> {
+            Ok(*self.block_height.lock())
+        }
+    }
+
+    struct FreshBlockhash;
+
+    #[async_trait]
+    impl OrderResubmitter for FreshBlockhash {
+        async fn resubmit(&self, order: &TrackedOrder) -> Result<Submission> {
+            Ok(Submission {
+                signature: format!("{}_retry{}", order.order_id, order.attempts),
+                last_valid_block_height: Some(1_000),
+            })
+        }
+    }
+
+    fn status(commitment: Commitment) -> Option<SignatureStatus> {
+        Some(SignatureStatus {
+            slot: 42,
+            commitment,
+            err: None,
+        })
+    }
+
+    fn pending(signal_id: &str) -> ExecutionResult {
+        ExecutionResult {
+            signal_id: signal_id.to_string(),
+            transaction_id: String::new(),
+            status: ExecutionStatus::Pending,
+            executed_quantity: 1.0,
+            executed_price: 100.0,
+            fees: 0.01,
+            timestamp: chrono::Utc::now(),
+            error_message: None,
+            symbol: "SOL/USDC".to_string(),
+            action: None,
+            wallet_id: None,
+            fee_breakdown: None,
+        }
+    }
+
+    fn drain(rx: &mut mpsc::UnboundedReceiver<ExecutionResult>) -> Vec<ExecutionResult> {
+        std::iter::from_fn(|| rx.try_recv().ok()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_order_progresses_to_finalized() -> Result<()> {
+        let chain = Arc::new(MockChain::default());
+        chain.script(
+            "sig1",
+            vec![
+                None,
+                status(Commitment::Processed),
+                status(Commitment::Confirmed),
+                status(Commitment::Finalized),
+            ],
+        );
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let manager = OrderManager::new(chain, tx, OrderManagerConfig::default());
+
+        manager
+            .track(
+                pending("order-1"),
+                Submission {
+                    signature: "sig1".to_string(),
+                    last_valid_block_height: Some(100),
+                },
+            )
+            .await;
+        for _ in 0..5 {
+            manager.poll_once().await?;
+        }
+
+        let order = manager.order("sig1").await.unwrap();
+        assert_eq!(order.state, OrderState::Finalized);
+        let states: Vec<OrderState> = order.history.iter().map(|(s, _)| *s).collect();
+        assert_eq!(
+            states,
+            vec![
+                OrderState::Sent,
+                OrderState::Processed,
+                OrderState::Confirmed,
+                OrderState::Finalized
+            ]
+        );
+
+        let updates = drain(&mut rx);
+        assert_eq!(updates.len(), 4);
+        assert!(matches!(updates[0].status, ExecutionStatus::Pending));
+        assert!(matches!(updates[2].status, ExecutionStatus::Confirmed));
+        assert!(updates.iter().all(|u| u.transaction_id == "sig1"));
+        assert!(manager.active_orders().await.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_expired_blockhash_resubmits_then_expires() -> Result<()> {
+        let chain = Arc::new(MockChain::default());
+        *chain.block_height.lock() = 200;
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let manager = OrderManager::new(
+            chain.clone(),
+            tx,
+            OrderManagerConfig {
+                max_resubmits: 1,
+                ..Default::default()
+            },
+        )
+        .with_resubmitter(Arc::new(FreshBlockhash));
+
+        manager
+            .track(
+                pending("order-2"),
+                Submission {
+                    signature: "sig2".to_string(),
+                    last_valid_block_height: Some(150),
+                },
+            )
+            .await;
+
+        // Height 200 > 150: resubmitted with a fresh blockhash
+        manager.poll_once().await?;
+        assert!(manager.order("sig2").await.is_none());
+        let order = manager.order("order-2_retry1").await.unwrap();
+        assert_eq!(order.state, OrderState::Sent);
+        assert_eq!(order.signature, "order-2_retry1");
+        assert_eq!(order.attempts, 2);
+
+        // Fresh blockhash also expires; resubmit budget is spent
+        *chain.block_height.lock() = 1_001;
+        manager.poll_once().await?;
+        let order = manager.order("order-2_retry1").await.unwrap();
+        assert_eq!(order.state, OrderState::Expired);
+
+        let last = drain(&mut rx).pop().unwrap();
+        assert!(matches!(last.status, ExecutionStatus::Failed));
+        assert_eq!(last.executed_quantity, 0.0);
+        assert!(last.error_message.unwrap().contains("blockhash expired"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_timeout_waits_for_blockhash_expiry() -> Result<()> {
+        let chain = Arc::new(MockChain::default());
+        *chain.block_height.lock() = 100;
+        chain.script("sig4", vec![None]);
+        chain.script("sig5", vec![status(Commitment::Processed)]);
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let manager = OrderManager::new(
+            chain.clone(),
+            tx,
+            OrderManagerConfig {
+                confirmation_timeout_secs: 0,
+                ..Default::default()
+            },
+        )
+        .with_resubmitter(Arc::new(FreshBlockhash));
+
+        // A repeated signal is tracked under each signature
+        for signature in ["sig4", "sig5"] {
+            manager
+                .track(
+                    pending("order-4"),
+                    Submission {
+                        signature: signature.to_string(),
+                        last_valid_block_height: Some(150),
+                    },
+                )
+                .await;
+        }
+        assert_eq!(manager.orders_for_signal("order-4").await.len(), 2);
+
+        // Past the timeout, but either could still land: keep waiting
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        manager.poll_once().await?;
+        assert_eq!(manager.order("sig4").await.unwrap().state, OrderState::Sent);
+        assert_eq!(
+            manager.order("sig5").await.unwrap().state,
+            OrderState::Processed
+        );
+
+        // Blockhash expired: the unseen one is resubmitted, the stuck one dropped
+        *chain.block_height.lock() = 151;
+        manager.poll_once().await?;
+        assert!(manager.order("sig4").await.is_none());
+        assert_eq!(manager.order("order-4_retry1").await.unwrap().attempts, 2);
+        let stuck = manager.order("sig5").await.unwrap();
+        assert_eq!(stuck.state, OrderState::Dropped);
+        assert!(stuck.result.error_message.unwrap().contains("Processed"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_on_chain_error_fails_order() -> Result<()> {
+        let chain = Arc::new(MockChain::default());
+        chain.script(
+            "sig3",
+            vec![Some(SignatureStatus {
+                slot: 7,
+                commitment: Commitment::Confirmed,
+                err: Some("{\"InstructionError\":[2,{\"Custom\":30}]}".to_string()),
+            })],
+        );
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let manager = OrderManager::new(chain, tx, OrderManagerConfig::default());
+        manager
+            .track(
+                pending("order-3"),
+                Submission {
+                    signature: "sig3".to_string(),
+                    last_valid_block_height: None,
+                },
+            )
+            .await;
+
+        manager.poll_once().await?;
+        let order = manager.order("sig3").await.unwrap();
+        assert_eq!(order.state, OrderState::Failed);
+        assert_eq!(order.slot, Some(7));
+        assert!(order.result.error_message.unwrap().contains("Custom"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_rpc_statuses() {
+        let result = serde_json::json!({
+            "context": {"slot": 100},
+            "value": [{"slot": 99, "confirmations": 3, "err": null, "confirmationStatus": "confirmed"}]
+        });
+        let status = parse_signature_status(&result).unwrap();
+        assert_eq!(status.commitment, Commitment::Confirmed);
+        assert_eq!(status.slot, 99);
+        assert!(status.err.is_none());
+
+        assert!(parse_signature_status(&serde_json::json!({"value": [null]})).is_none());
+
+        let tx =
+            serde_json::json!({"slot": 5, "meta": {"err": {"InstructionError": [0, "Custom"]}}});
+        assert!(parse_transaction_status(&tx).unwrap().err.is_some());
+        assert!(parse_transaction_status(&Value::Null).is_none());
+    }
+}
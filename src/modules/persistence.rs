@@ -2,14 +2,24 @@
 // Handles data storage and retrieval
 
 use crate::modules::executor::ExecutionResult;
-use anyhow::Result;
+use crate::modules::risk::{ApprovedSignal, RiskDecision};
+use crate::modules::strategy::TradingSignal;
+use crate::modules::supervisor::SupervisedComponent;
+use crate::modules::trade_journal::TradeJournal;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use tokio::sync::mpsc;
-use tracing::{debug, info};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PersistenceMessage {
     ExecutionResult(ExecutionResult),
+    TradingSignal(TradingSignal),
+    ApprovedSignal(ApprovedSignal),
+    RiskDecision(RiskDecision),
     HealthCheck,
 }
 
@@ -19,6 +29,9 @@ pub struct PersistenceManager {
     execution_result_receiver: mpsc::UnboundedReceiver<ExecutionResult>,
     database_url: String,
     is_running: bool,
+    journal: Option<Arc<TradeJournal>>,
+    // Cancelled by stop() or the supervisor
+    shutdown: CancellationToken,
 }
 
 #[allow(dead_code)]
@@ -33,34 +46,65 @@ impl PersistenceManager {
             execution_result_receiver,
             database_url,
             is_running: false,
+            journal: None,
+            shutdown: CancellationToken::new(),
         }
     }
 
+    /// Use an already opened journal instead of opening `database_url` on start
+    pub fn with_journal(mut self, journal: Arc<TradeJournal>) -> Self {
+        self.journal = Some(journal);
+        self
+    }
+
+    /// Shared handle for querying the trade journal
+    pub fn journal(&self) -> Option<Arc<TradeJournal>> {
+        self.journal.clone()
+    }
+
     pub async fn start(&mut self) -> Result<()> {
         info!("💾 PersistenceManager starting...");
         self.is_running = true;
 
-        // TODO: Initialize database connection
-        // let pool = sqlx::PgPool::connect(&self.database_url).await?;
+        if self.journal.is_none() {
+            let journal = TradeJournal::from_database_url(&self.database_url)
+                .with_context(|| format!("Failed to open trade journal at {}", self.database_url))?;
+            self.journal = Some(Arc::new(journal));
+        }
 
-        while self.is_running {
+        let shutdown = self.shutdown.clone();
+        let (mut messages_open, mut results_open) = (true, true);
+        while self.is_running && (messages_open || results_open) {
             tokio::select! {
-                Some(message) = self.message_receiver.recv() => {
-                    self.handle_message(message).await?;
-                }
-                Some(execution_result) = self.execution_result_receiver.recv() => {
-                    self.store_execution_result(execution_result).await?;
-                }
-                else => break,
+                _ = shutdown.cancelled() => break,
+                message = self.message_receiver.recv(), if messages_open => match message {
+                    Some(message) => self.handle_message(message).await?,
+                    None => messages_open = false,
+                },
+                result = self.execution_result_receiver.recv(), if results_open => match result {
+                    Some(result) => self.store_execution_result(result).await?,
+                    None => results_open = false,
+                },
             }
         }
 
+        // Persistence stops last: flush everything the pipeline queued
+        while let Ok(message) = self.message_receiver.try_recv() {
+            self.handle_message(message).await?;
+        }
+        while let Ok(execution_result) = self.execution_result_receiver.try_recv() {
+            self.store_execution_result(execution_result).await?;
+        }
+        self.is_running = false;
+        info!("💾 PersistenceManager flushed");
+
         Ok(())
     }
 
     pub async fn stop(&mut self) {
         info!("🛑 PersistenceManager stopping...");
         self.is_running = false;
+        self.shutdown.cancel();
     }
 
     async fn handle_message(&self, message: PersistenceMessage) -> Result<()> {
@@ -68,6 +112,19 @@ impl PersistenceManager {
             PersistenceMessage::ExecutionResult(result) => {
                 self.store_execution_result(result).await?;
             }
+            PersistenceMessage::TradingSignal(signal) => {
+                self.with_journal_write("signal", |journal| journal.record_signal(&signal));
+            }
+            PersistenceMessage::ApprovedSignal(approved) => {
+                self.with_journal_write("approved signal", |journal| {
+                    journal.record_approved_signal(&approved)
+                });
+            }
+            PersistenceMessage::RiskDecision(decision) => {
+                self.with_journal_write("risk decision", |journal| {
+                    journal.record_risk_decision(&decision)
+                });
+            }
             PersistenceMessage::HealthCheck => {
                 debug!("💓 Persistence health check");
             }
@@ -78,23 +135,8 @@ impl PersistenceManager {
     async fn store_execution_result(&self, result: ExecutionResult) -> Result<()> {
         debug!("💾 Storing execution result: {}", result.transaction_id);
 
-        // TODO: Implement actual database storage
-        // sqlx::query!(
-        //     "INSERT INTO execution_results (signal_id, transaction_id, status, executed_quantity, executed_price, fees, timestamp, error_message)
-        //      VALUES ($1, $2, $3, $4, $5, $6, $7, $8)",
-        //     result.signal_id,
-        //     result.transaction_id,
-        //     serde_json::to_string(&result.status)?,
-        //     result.executed_quantity,
-        //     result.executed_price,
-        //     result.fees,
-        //     result.timestamp,
-        //     result.error_message
-        // )
-        // .execute(&pool)
-        // .await?;
-
-        // For now, just log the storage
+        self.with_journal_write("execution result", |journal| journal.record_execution(&result));
+
         info!(
             "📊 Stored execution result: {} ({})",
             result.transaction_id, result.signal_id
@@ -102,6 +144,30 @@ impl PersistenceManager {
 
         Ok(())
     }
+
+    /// Journal write failures are logged rather than propagated so a single
+    /// bad record never stops the persistence loop.
+    fn with_journal_write<F>(&self, what: &str, write: F)
+    where
+        F: FnOnce(&TradeJournal) -> Result<()>,
+    {
+        match self.journal {
+            Some(ref journal) => {
+                if let Err(e) = write(journal) {
+                    error!("❌ Failed to journal {}: {}", what, e);
+                }
+            }
+            None => error!("❌ Trade journal not initialized, dropping {}", what),
+        }
+    }
+}
+
+#[async_trait]
+impl SupervisedComponent for PersistenceManager {
+    async fn run(&mut self, shutdown: CancellationToken) -> Result<()> {
+        self.shutdown = shutdown;
+        self.start().await
+    }
 }
 
 #[cfg(test)]
@@ -117,4 +183,77 @@ mod tests {
 
         assert!(!manager.is_running);
     }
+
+    #[tokio::test]
+    async fn test_execution_results_reach_journal() -> Result<()> {
+        use crate::modules::executor::ExecutionStatus;
+        use crate::modules::trade_journal::JournalQuery;
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let (exec_tx, exec_rx) = mpsc::unbounded_channel();
+        let journal = Arc::new(TradeJournal::open_in_memory()?);
+
+        let mut manager = PersistenceManager::new(rx, exec_rx, "sqlite::memory:".to_string())
+            .with_journal(journal.clone());
+
+        exec_tx.send(ExecutionResult {
+            signal_id: "sig-1".to_string(),
+            transaction_id: "tx-1".to_string(),
+            status: ExecutionStatus::Confirmed,
+            executed_quantity: 1.0,
+            executed_price: 100.0,
+            fees: 0.1,
+            timestamp: chrono::Utc::now(),
+            error_message: None,
+            symbol: "SOL/USDC".to_string(),
+            action: None,
+            wallet_id: None,
+            fee_breakdown: None,
+        })?;
+        drop(tx);
+        drop(exec_tx);
+
+        manager.start().await?;
+
+        let stored = journal.query_executions(&JournalQuery::new().signal_id("sig-1"))?;
+        assert_eq!(stored.len(), 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_flushes_queued_results() -> Result<()> {
+        use crate::modules::executor::ExecutionStatus;
+        use crate::modules::trade_journal::JournalQuery;
+
+        let (_tx, rx) = mpsc::unbounded_channel();
+        let (exec_tx, exec_rx) = mpsc::unbounded_channel();
+        let journal = Arc::new(TradeJournal::open_in_memory()?);
+        let mut manager = PersistenceManager::new(rx, exec_rx, "sqlite::memory:".to_string())
+            .with_journal(journal.clone());
+
+        for i in 0..5 {
+            exec_tx.send(ExecutionResult {
+                signal_id: format!("sig-{}", i),
+                transaction_id: format!("tx-{}", i),
+                status: ExecutionStatus::Confirmed,
+                executed_quantity: 1.0,
+                executed_price: 100.0,
+                fees: 0.1,
+                timestamp: chrono::Utc::now(),
+                error_message: None,
+                symbol: "SOL/USDC".to_string(),
+                action: None,
+                wallet_id: None,
+                fee_breakdown: None,
+            })?;
+        }
+
+        // Senders stay open: only the cancelled token ends the loop
+        let shutdown = CancellationToken::new();
+        shutdown.cancel();
+        manager.run(shutdown).await?;
+
+        assert_eq!(journal.query_executions(&JournalQuery::new())?.len(), 5);
+        Ok(())
+    }
 }
@@ -0,0 +1,534 @@
+// Position Ledger Module
+// Single source of truth for positions and PnL, built from ExecutionResults.
+// Per-wallet, per-token positions with FIFO or average cost basis, realized and
+// unrealized PnL marked to the price fetchers, and fee breakdowns.
+
+use crate::modules::executor::{ExecutionResult, ExecutionStatus, FeeBreakdown};
+use crate::modules::hybrid_price_fetcher::HybridPriceFetcher;
+use crate::modules::real_price_fetcher::RealPriceFetcher;
+use crate::modules::strategy::TradeAction;
+use anyhow::Result;
+use async_trait::async_trait;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use tracing::{debug, info, warn};
+
+/// Wallet used for executions that do not name one
+pub const DEFAULT_WALLET: &str = "default";
+
+/// How closing trades relieve cost basis
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum CostBasisMethod {
+    Fifo,
+    AverageCost,
+}
+
+/// Price source used to mark open positions
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+    async fn get_price(&self, symbol: &str) -> Result<f64>;
+}
+
+#[async_trait]
+impl PriceSource for RealPriceFetcher {
+    async fn get_price(&self, symbol: &str) -> Result<f64> {
+        self.get_real_price(symbol).await
+    }
+}
+
+#[async_trait]
+impl PriceSource for HybridPriceFetcher {
+    async fn get_price(&self, symbol: &str) -> Result<f64> {
+        self.get_real_price(symbol).await
+    }
+}
+
+/// Open lot; `unit_cost` includes the entry fees
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lot {
+    pub quantity: f64,
+    pub unit_cost: f64,
+    pub acquired_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerPosition {
+    pub wallet_id: String,
+    pub symbol: String,
+    pub quantity: f64,
+    /// Cost of the open quantity, including entry fees
+    pub cost_basis: f64,
+    pub realized_pnl: f64,
+    pub fees: FeeBreakdown,
+    pub last_price: Option<f64>,
+    pub lots: VecDeque<Lot>,
+    pub opened_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl LedgerPosition {
+    fn new(wallet_id: &str, symbol: &str, at: chrono::DateTime<chrono::Utc>) -> Self {
+        Self {
+            wallet_id: wallet_id.to_string(),
+            symbol: symbol.to_string(),
+            quantity: 0.0,
+            cost_basis: 0.0,
+            realized_pnl: 0.0,
+            fees: FeeBreakdown::default(),
+            last_price: None,
+            lots: VecDeque::new(),
+            opened_at: None,
+            updated_at: at,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.quantity > f64::EPSILON
+    }
+
+    pub fn average_cost(&self) -> f64 {
+        if self.is_open() {
+            self.cost_basis / self.quantity
+        } else {
+            0.0
+        }
+    }
+
+    /// Market value at the last mark (cost basis when never marked)
+    pub fn market_value(&self) -> f64 {
+        match self.last_price {
+            Some(price) => self.quantity * price,
+            None => self.cost_basis,
+        }
+    }
+
+    pub fn unrealized_pnl(&self) -> f64 {
+        self.market_value() - self.cost_basis
+    }
+}
+
+/// Effect of one execution on the ledger
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LedgerUpdate {
+    pub wallet_id: String,
+    pub symbol: String,
+    pub realized_pnl: f64,
+    pub position_quantity: f64,
+}
+
+/// Aggregate PnL across all wallets
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PnlSummary {
+    pub realized_pnl: f64,
+    pub unrealized_pnl: f64,
+    pub daily_realized_pnl: f64,
+    pub fees: FeeBreakdown,
+    pub open_positions: usize,
+    pub gross_exposure: f64,
+}
+
+impl PnlSummary {
+    pub fn total_pnl(&self) -> f64 {
+        self.realized_pnl + self.unrealized_pnl
+    }
+}
+
+struct LedgerState {
+    positions: HashMap<(String, String), LedgerPosition>,
+    applied_transactions: HashSet<String>,
+    realized_pnl: f64,
+    daily_realized: (chrono::NaiveDate, f64),
+    fees: FeeBreakdown,
+}
+
+/// Position and PnL ledger shared by executors, RiskManager and ProfitManager
+pub struct PositionLedger {
+    method: CostBasisMethod,
+    state: RwLock<LedgerState>,
+}
+
+impl PositionLedger {
+    pub fn new(method: CostBasisMethod) -> Self {
+        Self {
+            method,
+            state: RwLock::new(LedgerState {
+                positions: HashMap::new(),
+                applied_transactions: HashSet::new(),
+                realized_pnl: 0.0,
+                daily_realized: (chrono::Utc::now().date_naive(), 0.0),
+                fees: FeeBreakdown::default(),
+            }),
+        }
+    }
+
+    pub fn method(&self) -> CostBasisMethod {
+        self.method
+    }
+
+    /// Apply a confirmed execution. Failed, pending, non-trade and already
+    /// applied executions are ignored.
+    pub fn apply_execution(&self, result: &ExecutionResult) -> Option<LedgerUpdate> {
+        if !matches!(result.status, ExecutionStatus::Confirmed)
+            || result.executed_quantity <= 0.0
+            || result.symbol.is_empty()
+        {
+            return None;
+        }
+        let is_buy = match result.action {
+            Some(TradeAction::Buy) | Some(TradeAction::MarketBuy) => true,
+            Some(TradeAction::Sell) | Some(TradeAction::MarketSell) => false,
+            _ => return None,
+        };
+
+        let mut state = self.state.write();
+        if !state
+            .applied_transactions
+            .insert(result.transaction_id.clone())
+        {
+            debug!("📒 Execution {} already applied", result.transaction_id);
+            return None;
+        }
+
+        let wallet_id = result.wallet_id.as_deref().unwrap_or(DEFAULT_WALLET);
+        let fees = result
+            .fee_breakdown
+            .clone()
+            .unwrap_or_else(|| FeeBreakdown::unclassified(result.fees));
+        let key = (wallet_id.to_string(), result.symbol.clone());
+        let position = state
+            .positions
+            .entry(key)
+            .or_insert_with(|| LedgerPosition::new(wallet_id, &result.symbol, result.timestamp));
+
+        position.fees.add(&fees);
+        position.last_price = Some(result.executed_price);
+        position.updated_at = result.timestamp;
+
+        let realized = if is_buy {
+            let cost = result.executed_quantity * result.executed_price + fees.total();
+            if !position.is_open() {
+                position.opened_at = Some(result.timestamp);
+            }
+            position.lots.push_back(Lot {
+                quantity: result.executed_quantity,
+                unit_cost: cost / result.executed_quantity,
+                acquired_at: result.timestamp,
+            });
+            position.quantity += result.executed_quantity;
+            position.cost_basis += cost;
+            0.0
+        } else {
+            let quantity = result.executed_quantity.min(position.quantity);
+            if quantity < result.executed_quantity {
+                warn!(
+                    "📒 Sell of {:.6} {} exceeds position {:.6} in wallet {}; excess ignored",
+                    result.executed_quantity, result.symbol, position.quantity, wallet_id
+                );
+            }
+
+            let average_cost = position.average_cost();
+            let relieved = match self.method {
+                CostBasisMethod::Fifo => relieve_fifo(&mut position.lots, quantity),
+                CostBasisMethod::AverageCost => {
+                    relieve_average(&mut position.lots, quantity, average_cost)
+                }
+            };
+            let proceeds = quantity * result.executed_price - fees.total();
+            position.quantity -= quantity;
+            position.cost_basis -= relieved;
+            if !position.is_open() {
+                position.quantity = 0.0;
+                position.cost_basis = 0.0;
+                position.lots.clear();
+                position.opened_at = None;
+            }
+
+            let pnl = proceeds - relieved;
+            position.realized_pnl += pnl;
+            pnl
+        };
+        let update = LedgerUpdate {
+            wallet_id: wallet_id.to_string(),
+            symbol: result.symbol.clone(),
+            realized_pnl: realized,
+            position_quantity: position.quantity,
+        };
+
+        state.realized_pnl += realized;
+        state.fees.add(&fees);
+        let day = result.timestamp.date_naive();
+        if state.daily_realized.0 != day {
+            state.daily_realized = (day, 0.0);
+        }
+        state.daily_realized.1 += realized;
+
+        debug!(
+            "📒 Ledger {} {}: qty {:.6}, realized {:+.6}",
+            update.wallet_id, update.symbol, update.position_quantity, update.realized_pnl
+        );
+        Some(update)
+    }
+
+    /// Mark every position in `symbol` to `price`
+    pub fn mark_price(&self, symbol: &str, price: f64) {
+        let mut state = self.state.write();
+        for position in state.positions.values_mut().filter(|p| p.symbol == symbol) {
+            position.last_price = Some(price);
+        }
+    }
+
+    /// Mark all open positions using a price fetcher; returns the number of symbols marked
+    pub async fn mark_to_market(&self, source: &dyn PriceSource) -> Result<usize> {
+        let symbols: HashSet<String> = self
+            .state
+            .read()
+            .positions
+            .values()
+            .filter(|p| p.is_open())
+            .map(|p| p.symbol.clone())
+            .collect();
+
+        let mut marked = 0;
+        for symbol in symbols {
+            match source.get_price(&symbol).await {
+                Ok(price) if price > 0.0 => {
+                    self.mark_price(&symbol, price);
+                    marked += 1;
+                }
+                Ok(price) => warn!("📒 Ignoring non-positive mark {} for {}", price, symbol),
+                Err(e) => warn!("📒 Failed to mark {}: {}", symbol, e),
+            }
+        }
+        Ok(marked)
+    }
+
+    pub fn position(&self, wallet_id: &str, symbol: &str) -> Option<LedgerPosition> {
+        self.state
+            .read()
+            .positions
+            .get(&(wallet_id.to_string(), symbol.to_string()))
+            .cloned()
+    }
+
+    pub fn open_positions(&self) -> Vec<LedgerPosition> {
+        self.state
+            .read()
+            .positions
+            .values()
+            .filter(|p| p.is_open())
+            .cloned()
+            .collect()
+    }
+
+    pub fn wallet_positions(&self, wallet_id: &str) -> Vec<LedgerPosition> {
+        self.state
+            .read()
+            .positions
+            .values()
+            .filter(|p| p.wallet_id == wallet_id)
+            .cloned()
+            .collect()
+    }
+
+    /// Open quantity of `symbol` across all wallets
+    pub fn open_quantity(&self, symbol: &str) -> f64 {
+        self.state
+            .read()
+            .positions
+            .values()
+            .filter(|p| p.symbol == symbol)
+            .map(|p| p.quantity)
+            .sum()
+    }
+
+    /// Market value of `symbol` across all wallets
+    pub fn exposure(&self, symbol: &str) -> f64 {
+        self.state
+            .read()
+            .positions
+            .values()
+            .filter(|p| p.symbol == symbol)
+            .map(|p| p.market_value())
+            .sum()
+    }
+
+    pub fn realized_pnl(&self) -> f64 {
+        self.state.read().realized_pnl
+    }
+
+    pub fn unrealized_pnl(&self) -> f64 {
+        self.state
+            .read()
+            .positions
+            .values()
+            .map(|p| p.unrealized_pnl())
+            .sum()
+    }
+
+    /// Realized PnL for the current UTC day
+    pub fn daily_realized_pnl(&self) -> f64 {
+        let state = self.state.read();
+        if state.daily_realized.0 == chrono::Utc::now().date_naive() {
+            state.daily_realized.1
+        } else {
+            0.0
+        }
+    }
+
+    pub fn summary(&self) -> PnlSummary {
+        let state = self.state.read();
+        let open: Vec<&LedgerPosition> = state.positions.values().filter(|p| p.is_open()).collect();
+        PnlSummary {
+            realized_pnl: state.realized_pnl,
+            unrealized_pnl: open.iter().map(|p| p.unrealized_pnl()).sum(),
+            daily_realized_pnl: if state.daily_realized.0 == chrono::Utc::now().date_naive() {
+                state.daily_realized.1
+            } else {
+                0.0
+            },
+            fees: state.fees.clone(),
+            open_positions: open.len(),
+            gross_exposure: open.iter().map(|p| p.market_value()).sum(),
+        }
+    }
+
+    pub fn log_summary(&self) {
+        let summary = self.summary();
+        info!(
+            "📒 Ledger: realized {:+.4}, unrealized {:+.4}, fees {:.4}, {} open position(s), exposure {:.4}",
+            summary.realized_pnl,
+            summary.unrealized_pnl,
+            summary.fees.total(),
+            summary.open_positions,
+            summary.gross_exposure
+        );
+    }
+}
+
+impl Default for PositionLedger {
+    fn default() -> Self {
+        Self::new(CostBasisMethod::Fifo)
+    }
+}
+
+/// Remove `quantity` from the oldest lots first; returns the cost relieved
+fn relieve_fifo(lots: &mut VecDeque<Lot>, mut quantity: f64) -> f64 {
+    let mut relieved = 0.0;
+    while quantity > f64::EPSILON {
+        let Some(lot) = lots.front_mut() else { break };
+        let take = lot.quantity.min(quantity);
+        relieved += take * lot.unit_cost;
+        lot.quantity -= take;
+        quantity -= take;
+        if lot.quantity <= f64::EPSILON {
+            lots.pop_front();
+        }
+    }
+    relieved
+}
+
+/// Remove `quantity` pro rata at the average cost; returns the cost relieved
+fn relieve_average(lots: &mut VecDeque<Lot>, quantity: f64, average_cost: f64) -> f64 {
+    let total: f64 = lots.iter().map(|l| l.quantity).sum();
+    if total > 0.0 {
+        let keep = ((total - quantity) / total).max(0.0);
+        for lot in lots.iter_mut() {
+            lot.quantity *= keep;
+            lot.unit_cost = average_cost;
+        }
+        lots.retain(|l| l.quantity > f64::EPSILON);
+    }
+    quantity * average_cost
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(
+        tx: &str,
+        action: TradeAction,
+        quantity: f64,
+        price: f64,
+        fees: f64,
+    ) -> ExecutionResult {
+        ExecutionResult {
+            signal_id: format!("sig_{}", tx),
+            transaction_id: tx.to_string(),
+            status: ExecutionStatus::Confirmed,
+            executed_quantity: quantity,
+            executed_price: price,
+            fees,
+            timestamp: chrono::Utc::now(),
+            error_message: None,
+            symbol: "BONK".to_string(),
+            action: Some(action),
+            wallet_id: Some("w1".to_string()),
+            fee_breakdown: None,
+        }
+    }
+
+    #[test]
+    fn test_fifo_cost_basis() {
+        let ledger = PositionLedger::new(CostBasisMethod::Fifo);
+        ledger.apply_execution(&fill("t1", TradeAction::Buy, 10.0, 1.0, 0.0));
+        ledger.apply_execution(&fill("t2", TradeAction::Buy, 10.0, 2.0, 0.0));
+
+        // FIFO: selling 10 closes the 1.0 lot
+        let update = ledger
+            .apply_execution(&fill("t3", TradeAction::Sell, 10.0, 3.0, 0.0))
+            .unwrap();
+        assert!((update.realized_pnl - 20.0).abs() < 1e-9);
+
+        let position = ledger.position("w1", "BONK").unwrap();
+        assert_eq!(position.quantity, 10.0);
+        assert!((position.average_cost() - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_average_cost_basis() {
+        let ledger = PositionLedger::new(CostBasisMethod::AverageCost);
+        ledger.apply_execution(&fill("t1", TradeAction::Buy, 10.0, 1.0, 0.0));
+        ledger.apply_execution(&fill("t2", TradeAction::Buy, 10.0, 2.0, 0.0));
+
+        let update = ledger
+            .apply_execution(&fill("t3", TradeAction::Sell, 10.0, 3.0, 0.0))
+            .unwrap();
+        assert!((update.realized_pnl - 15.0).abs() < 1e-9);
+        assert!((ledger.position("w1", "BONK").unwrap().average_cost() - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_fees_unrealized_and_idempotency() {
+        let ledger = PositionLedger::default();
+        let mut buy = fill("t1", TradeAction::Buy, 100.0, 1.0, 1.0);
+        buy.fee_breakdown = Some(FeeBreakdown {
+            dex_fee: 0.25,
+            priority_fee: 0.5,
+            jito_tip: 0.25,
+            ..Default::default()
+        });
+        ledger.apply_execution(&buy);
+        // Same transaction reported twice is counted once
+        assert!(ledger.apply_execution(&buy).is_none());
+
+        ledger.mark_price("BONK", 1.5);
+        let summary = ledger.summary();
+        assert_eq!(summary.open_positions, 1);
+        assert!((summary.unrealized_pnl - 49.0).abs() < 1e-9);
+        assert!((summary.fees.priority_fee - 0.5).abs() < 1e-9);
+        assert!((ledger.exposure("BONK") - 150.0).abs() < 1e-9);
+
+        let mut failed = fill("t2", TradeAction::Sell, 100.0, 1.5, 0.0);
+        failed.status = ExecutionStatus::Failed;
+        assert!(ledger.apply_execution(&failed).is_none());
+
+        ledger.apply_execution(&fill("t3", TradeAction::Sell, 100.0, 1.5, 0.5));
+        let summary = ledger.summary();
+        assert_eq!(summary.open_positions, 0);
+        assert!((summary.realized_pnl - 48.5).abs() < 1e-9);
+        assert!((ledger.daily_realized_pnl() - 48.5).abs() < 1e-9);
+        assert!((summary.fees.total() - 1.5).abs() < 1e-9);
+    }
+}
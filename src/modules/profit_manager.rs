@@ -1,3 +1,4 @@
+use crate::modules::position_ledger::{PnlSummary, PositionLedger};
 use anyhow::Result;
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
@@ -9,6 +10,7 @@ use solana_sdk::{
     transaction::Transaction,
 };
 use std::str::FromStr;
+use std::sync::Arc;
 use tracing::{info, error};
 
 /// Profit Manager for THE OVERMIND PROTOCOL
@@ -20,6 +22,8 @@ pub struct ProfitManager {
     transfer_threshold: f64,
     transfer_percentage: f64,
     enabled: bool,
+    // Realized/unrealized PnL source of truth
+    ledger: Option<Arc<PositionLedger>>,
 }
 
 impl ProfitManager {
@@ -40,9 +44,16 @@ impl ProfitManager {
             transfer_threshold,
             transfer_percentage,
             enabled,
+            ledger: None,
         })
     }
 
+    /// Read trading PnL from the shared position ledger
+    pub fn with_ledger(mut self, ledger: Arc<PositionLedger>) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
     /// Check if profit transfer should be executed
     pub async fn check_and_transfer_profits(&self, initial_balance: f64) -> Result<bool> {
         if !self.enabled {
@@ -55,6 +66,14 @@ impl ProfitManager {
         // Calculate profit
         let profit = current_balance - initial_balance;
         
+        // Only sweep balance gains backed by realized trading profit
+        if let Some(ref ledger) = self.ledger {
+            if ledger.realized_pnl() <= 0.0 {
+                info!("📒 Skipping profit transfer: ledger realized PnL {:.6}", ledger.realized_pnl());
+                return Ok(false);
+            }
+        }
+
         if profit >= self.transfer_threshold {
             info!("💰 Profit detected: {:.6} SOL (threshold: {:.6})", profit, self.transfer_threshold);
             
@@ -130,6 +149,7 @@ impl ProfitManager {
             profit_wallet_balance: profit_balance,
             total_profit: (trading_balance - initial_balance) + (profit_balance - 27.6), // Assuming 27.6 was initial
             profit_percentage: ((trading_balance - initial_balance) / initial_balance) * 100.0,
+            ledger: self.ledger.as_ref().map(|ledger| ledger.summary()),
         })
     }
 }
@@ -141,6 +161,7 @@ pub struct ProfitStats {
     pub profit_wallet_balance: f64,
     pub total_profit: f64,
     pub profit_percentage: f64,
+    pub ledger: Option<PnlSummary>,
 }
 
 impl ProfitStats {
@@ -151,5 +172,14 @@ impl ProfitStats {
         info!("   Profit Wallet: {:.6} SOL", self.profit_wallet_balance);
         info!("   Total Profit: {:.6} SOL ({:.2}%)", 
               self.total_profit, self.profit_percentage);
+        if let Some(ref ledger) = self.ledger {
+            info!("   Realized PnL: {:+.6} (today {:+.6})",
+                  ledger.realized_pnl, ledger.daily_realized_pnl);
+            info!("   Unrealized PnL: {:+.6} across {} open position(s)",
+                  ledger.unrealized_pnl, ledger.open_positions);
+            info!("   Fees: {:.6} (dex {:.6}, priority {:.6}, jito {:.6}, network {:.6})",
+                  ledger.fees.total(), ledger.fees.dex_fee, ledger.fees.priority_fee,
+                  ledger.fees.jito_tip, ledger.fees.network_fee);
+        }
     }
 }
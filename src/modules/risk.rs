@@ -1,17 +1,26 @@
 // Risk Manager Module
 // Evaluates trading signals against risk parameters
 // Enhanced with KINETIC SHIELD system for memcoin strategies
+// Checks run as ordered RiskRule chains (see risk_rules), audited per decision
 
-use crate::modules::strategy::TradingSignal;
+use crate::modules::strategy::{StrategyType, TradingSignal};
 use crate::modules::memcoin_strategies::KineticShieldConfig;
+use crate::modules::persistence::PersistenceMessage;
+use crate::modules::position_ledger::PositionLedger;
+use crate::modules::kill_switch::KillSwitch;
+use crate::modules::liquidity_sizing::LiquidityProfile;
+use crate::modules::risk_rules::{RiskContext, RiskRuleChain, RiskRulesConfig, RuleAudit};
+use crate::modules::supervisor::SupervisedComponent;
 use crate::modules::micro_lightning::{
     OperationControl, EmergencyTrigger, TimeProtocol
 };
 use anyhow::Result;
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -51,6 +60,34 @@ pub struct ApprovedSignal {
     pub risk_score: f64,
     pub approval_timestamp: chrono::DateTime<chrono::Utc>,
     pub kinetic_shield_status: KineticShieldStatus,
+    /// Rules that judged the signal, in chain order
+    #[serde(default)]
+    pub risk_audit: Vec<RuleAudit>,
+}
+
+/// Outcome of a pre-trade risk evaluation
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum RiskOutcome {
+    Approved,
+    Resized,
+    Rejected,
+}
+
+/// Journaled record of why a signal was approved, resized or rejected
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RiskDecision {
+    pub signal_id: String,
+    pub symbol: String,
+    pub strategy_type: StrategyType,
+    pub outcome: RiskOutcome,
+    pub requested_quantity: f64,
+    pub approved_quantity: f64,
+    pub risk_score: Option<f64>,
+    pub reason: Option<String>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Every rule evaluated, with the inputs it saw
+    #[serde(default)]
+    pub rule_audit: Vec<RuleAudit>,
 }
 
 /// Micro-lightning risk adjustment
@@ -125,6 +162,18 @@ pub struct RiskManager {
     loss_stats: Arc<RwLock<LossStatistics>>,
     token_exposures: Arc<RwLock<HashMap<String, f64>>>, // token -> exposure amount
     system_state: Arc<RwLock<SystemState>>,
+    // Trade journal feed (signals + decisions)
+    persistence_sender: Option<mpsc::UnboundedSender<PersistenceMessage>>,
+    // Position/PnL source of truth (None = hand-fed daily_pnl)
+    ledger: Option<Arc<PositionLedger>>,
+    // Pre-trade rules run by evaluate_signal
+    rule_chain: RiskRuleChain,
+    // Rules run by apply_kinetic_shield
+    shield_chain: RiskRuleChain,
+    // Fed the daily PnL seen by every evaluation (daily loss trigger)
+    kill_switch: Option<Arc<KillSwitch>>,
+    // Cancelled by stop() or the supervisor
+    shutdown: CancellationToken,
 }
 
 /// Stan systemu KINETIC SHIELD
@@ -143,6 +192,8 @@ impl RiskManager {
         execution_sender: mpsc::UnboundedSender<ApprovedSignal>,
         risk_params: RiskParameters,
     ) -> Self {
+        let rule_chain = RiskRuleChain::from_config(&RiskRulesConfig::pre_trade(&risk_params));
+        let shield_chain = RiskRuleChain::from_config(&RiskRulesConfig::kinetic_shield(&risk_params));
         Self {
             signal_receiver,
             execution_sender,
@@ -153,63 +204,106 @@ impl RiskManager {
             loss_stats: Arc::new(RwLock::new(LossStatistics::default())),
             token_exposures: Arc::new(RwLock::new(HashMap::new())),
             system_state: Arc::new(RwLock::new(SystemState::Normal)),
+            persistence_sender: None,
+            ledger: None,
+            rule_chain,
+            shield_chain,
+            kill_switch: None,
+            shutdown: CancellationToken::new(),
         }
     }
 
+    /// Replace the pre-trade rules, e.g. with `RiskRulesConfig::from_file`
+    pub fn with_rule_chain(mut self, chain: RiskRuleChain) -> Self {
+        self.rule_chain = chain;
+        self
+    }
+
+    pub fn rule_chain(&self) -> &RiskRuleChain {
+        &self.rule_chain
+    }
+
+    /// Journal every incoming signal and risk decision via PersistenceManager
+    pub fn with_persistence(mut self, sender: mpsc::UnboundedSender<PersistenceMessage>) -> Self {
+        self.persistence_sender = Some(sender);
+        self
+    }
+
+    /// Read daily PnL and token exposure from the shared position ledger
+    pub fn with_ledger(mut self, ledger: Arc<PositionLedger>) -> Self {
+        self.ledger = Some(ledger);
+        self
+    }
+
+    /// Engage the kill switch once the daily loss trigger is breached
+    pub fn with_kill_switch(mut self, kill_switch: Arc<KillSwitch>) -> Self {
+        self.kill_switch = Some(kill_switch);
+        self
+    }
+
     pub async fn start(&mut self) -> Result<()> {
         info!(
             "🛡️ RiskManager starting with params: {:?}",
             self.risk_params
         );
         self.is_running = true;
+        let shutdown = self.shutdown.clone();
 
         while self.is_running {
-            if let Some(signal) = self.signal_receiver.recv().await {
-                self.evaluate_signal(signal).await?;
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                signal = self.signal_receiver.recv() => match signal {
+                    Some(signal) => self.evaluate_signal(signal).await?,
+                    None => break,
+                },
             }
         }
 
+        // Evaluate signals the strategy engine queued before it stopped
+        while let Ok(signal) = self.signal_receiver.try_recv() {
+            self.evaluate_signal(signal).await?;
+        }
+        self.is_running = false;
+
         Ok(())
     }
 
     pub async fn stop(&mut self) {
         info!("🛑 RiskManager stopping...");
         self.is_running = false;
+        self.shutdown.cancel();
     }
 
-    async fn evaluate_signal(&mut self, signal: TradingSignal) -> Result<()> {
+    pub(crate) async fn evaluate_signal(&mut self, signal: TradingSignal) -> Result<()> {
         debug!("Evaluating signal: {}", signal.signal_id);
+        self.journal(PersistenceMessage::TradingSignal(signal.clone()));
 
-        // Check confidence threshold
-        if signal.confidence < self.risk_params.min_confidence_threshold {
-            warn!(
-                "Signal {} rejected: confidence {} below threshold {}",
-                signal.signal_id, signal.confidence, self.risk_params.min_confidence_threshold
-            );
-            return Ok(());
+        let context = self.risk_context(&signal).await;
+        if let Some(ref kill_switch) = self.kill_switch {
+            kill_switch.report_daily_pnl(context.daily_pnl);
         }
+        let decision = self.rule_chain.evaluate(&signal, &context);
 
-        // Check position size limits
-        let approved_quantity = self.check_position_limits(&signal)?;
-        if approved_quantity <= 0.0 {
+        if decision.outcome == RiskOutcome::Rejected {
             warn!(
-                "Signal {} rejected: position size limits exceeded",
-                signal.signal_id
+                "Signal {} rejected by {}",
+                signal.signal_id,
+                decision.reason.as_deref().unwrap_or("risk rules")
             );
-            return Ok(());
-        }
-
-        // Check daily loss limits
-        if !self.check_daily_loss_limits()? {
-            warn!(
-                "Signal {} rejected: daily loss limits exceeded",
-                signal.signal_id
+            self.journal_decision(
+                &signal,
+                RiskOutcome::Rejected,
+                0.0,
+                None,
+                decision.reason,
+                decision.audit,
             );
             return Ok(());
         }
 
         // Calculate risk score
         let risk_score = self.calculate_risk_score(&signal)?;
+        let approved_quantity = decision.approved_quantity;
 
         // Approve signal
         let approved_signal = ApprovedSignal {
@@ -218,8 +312,19 @@ impl RiskManager {
             risk_score,
             approval_timestamp: chrono::Utc::now(),
             kinetic_shield_status: KineticShieldStatus::Active, // Domyślnie aktywny
+            risk_audit: decision.audit.clone(),
         };
 
+        self.journal_decision(
+            &signal,
+            decision.outcome,
+            approved_quantity,
+            Some(risk_score),
+            decision.reason,
+            decision.audit,
+        );
+        self.journal(PersistenceMessage::ApprovedSignal(approved_signal.clone()));
+
         self.send_approved_signal(approved_signal).await?;
         info!(
             "✅ Signal {} approved with quantity {}",
@@ -229,15 +334,63 @@ impl RiskManager {
         Ok(())
     }
 
-    fn check_position_limits(&self, signal: &TradingSignal) -> Result<f64> {
-        if signal.quantity > self.risk_params.max_position_size {
-            return Ok(self.risk_params.max_position_size);
+    /// Inputs the rule chains see for `signal`
+    async fn risk_context(&self, signal: &TradingSignal) -> RiskContext {
+        RiskContext {
+            daily_pnl: self.get_daily_pnl(),
+            token_exposure: self.current_exposure(&signal.symbol).await,
+            volatility: signal
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.get("volatility"))
+                .and_then(|volatility| volatility.as_f64()),
+            liquidity: LiquidityProfile::from_signal(signal),
+            loss_stats: self.loss_stats.read().await.clone(),
+        }
+    }
+
+    /// With a ledger, exposure is the filled position (sells reduce it);
+    /// otherwise the running total of approvals
+    async fn current_exposure(&self, token: &str) -> f64 {
+        match self.ledger {
+            Some(ref ledger) => ledger.open_quantity(token),
+            None => *self.token_exposures.read().await.get(token).unwrap_or(&0.0),
+        }
+    }
+
+    fn journal(&self, message: PersistenceMessage) {
+        if let Some(ref sender) = self.persistence_sender {
+            if let Err(e) = sender.send(message) {
+                error!("Failed to send risk record to persistence: {}", e);
+            }
         }
-        Ok(signal.quantity)
+    }
+
+    fn journal_decision(
+        &self,
+        signal: &TradingSignal,
+        outcome: RiskOutcome,
+        approved_quantity: f64,
+        risk_score: Option<f64>,
+        reason: Option<String>,
+        rule_audit: Vec<RuleAudit>,
+    ) {
+        self.journal(PersistenceMessage::RiskDecision(RiskDecision {
+            signal_id: signal.signal_id.clone(),
+            symbol: signal.symbol.clone(),
+            strategy_type: signal.strategy_type.clone(),
+            outcome,
+            requested_quantity: signal.quantity,
+            approved_quantity,
+            risk_score,
+            reason,
+            timestamp: chrono::Utc::now(),
+            rule_audit,
+        }));
     }
 
     fn check_daily_loss_limits(&self) -> Result<bool> {
-        Ok(self.daily_pnl > -self.risk_params.max_daily_loss)
+        Ok(self.get_daily_pnl() > -self.risk_params.max_daily_loss)
     }
 
     fn calculate_risk_score(&self, signal: &TradingSignal) -> Result<f64> {
@@ -294,76 +447,15 @@ impl RiskManager {
         self.daily_pnl += pnl_change;
     }
 
+    /// Today's realized plus open unrealized PnL when a ledger is attached
     pub fn get_daily_pnl(&self) -> f64 {
-        self.daily_pnl
-    }
-
-    // ========== KINETIC SHIELD IMPLEMENTATION ==========
-
-    /// Circuit Breaker - automatyczne wyłączenie handlu
-    pub async fn check_circuit_breaker(&self) -> Result<bool> {
-        let loss_stats = self.loss_stats.read().await;
-        let mut system_state = self.system_state.write().await;
-
-        // Sprawdź daily drawdown limit (7.5%)
-        if loss_stats.daily_drawdown > self.risk_params.kinetic_shield.daily_drawdown_limit {
-            warn!("🚨 KINETIC SHIELD: Circuit breaker triggered - daily drawdown {}% > {}%",
-                  loss_stats.daily_drawdown, self.risk_params.kinetic_shield.daily_drawdown_limit);
-            *system_state = SystemState::Lockdown;
-            return Ok(true);
-        }
-
-        // Sprawdź hourly loss streak (5 strat z rzędu)
-        if loss_stats.hourly_loss_streak > self.risk_params.kinetic_shield.hourly_loss_streak_limit {
-            warn!("🚨 KINETIC SHIELD: Circuit breaker triggered - loss streak {} > {}",
-                  loss_stats.hourly_loss_streak, self.risk_params.kinetic_shield.hourly_loss_streak_limit);
-            *system_state = SystemState::Lockdown;
-            return Ok(true);
-        }
-
-        Ok(false)
-    }
-
-    /// Volatility Scaling - dynamiczna korekta wielkości pozycji
-    pub async fn apply_volatility_scaling(&self, signal: &TradingSignal, volatility: f32) -> f64 {
-        let base_size = signal.quantity;
-
-        if volatility > self.risk_params.kinetic_shield.max_volatility {
-            let scaling_factor = 1.0 - (volatility / self.risk_params.kinetic_shield.max_volatility).min(0.5);
-            let scaled_size = base_size * scaling_factor as f64;
-
-            info!("🛡️ KINETIC SHIELD: Volatility scaling applied - volatility={}, factor={}, size: {} → {}",
-                  volatility, scaling_factor, base_size, scaled_size);
-
-            return scaled_size;
+        match self.ledger {
+            Some(ref ledger) => ledger.daily_realized_pnl() + ledger.unrealized_pnl(),
+            None => self.daily_pnl,
         }
-
-        base_size
     }
 
-    /// Cross-Strategy Exposure Limit - maksymalna ekspozycja na token
-    pub async fn check_exposure_limit(&self, token: &str, additional_exposure: f64) -> Result<f64> {
-        let mut exposures = self.token_exposures.write().await;
-        let current_exposure = *exposures.get(token).unwrap_or(&0.0);
-        let total_exposure = current_exposure + additional_exposure;
-
-        let capital = 10000.0; // TODO: Get from config
-        let max_exposure = capital * self.risk_params.kinetic_shield.exposure_limit_per_token as f64;
-
-        if total_exposure > max_exposure {
-            let allowed_exposure = (max_exposure - current_exposure).max(0.0);
-            warn!("🛡️ KINETIC SHIELD: Exposure limit reached for {} - requested: {}, allowed: {}",
-                  token, additional_exposure, allowed_exposure);
-
-            // Aktualizuj ekspozycję
-            exposures.insert(token.to_string(), current_exposure + allowed_exposure);
-            return Ok(allowed_exposure);
-        }
-
-        // Aktualizuj ekspozycję
-        exposures.insert(token.to_string(), total_exposure);
-        Ok(additional_exposure)
-    }
+    // ========== KINETIC SHIELD IMPLEMENTATION ==========
 
     /// Aktualizacja statystyk strat
     pub async fn update_loss_statistics(&self, pnl: f64) -> Result<()> {
@@ -410,42 +502,44 @@ impl RiskManager {
         }
     }
 
-    /// Aplikacja KINETIC SHIELD do sygnału
+    /// Aplikacja KINETIC SHIELD do sygnału: circuit breaker, exposure limit
+    /// i volatility scaling jako łańcuch reguł
     pub async fn apply_kinetic_shield(&self, signal: TradingSignal) -> Result<Option<ApprovedSignal>> {
-        // 1. Circuit Breaker Check
-        if self.check_circuit_breaker().await? {
-            warn!("🚨 KINETIC SHIELD: Signal blocked by circuit breaker");
+        let context = self.risk_context(&signal).await;
+        let decision = self.shield_chain.evaluate(&signal, &context);
+
+        if decision.outcome == RiskOutcome::Rejected {
+            if decision.rejected_by() == Some("circuit_breaker") {
+                *self.system_state.write().await = SystemState::Lockdown;
+                warn!("🚨 KINETIC SHIELD: Circuit breaker triggered - {}",
+                      decision.reason.as_deref().unwrap_or_default());
+            } else {
+                warn!("🛡️ KINETIC SHIELD: Signal blocked - {}",
+                      decision.reason.as_deref().unwrap_or_default());
+            }
             return Ok(None);
         }
 
-        // 2. Exposure Limit Check
-        let allowed_quantity = self.check_exposure_limit(&signal.symbol, signal.quantity).await?;
-        if allowed_quantity <= 0.0 {
-            warn!("🛡️ KINETIC SHIELD: Signal blocked by exposure limit");
-            return Ok(None);
+        // Aktualizuj ekspozycję (z ledgerem liczy się wypełniona pozycja)
+        if self.ledger.is_none() {
+            let mut exposures = self.token_exposures.write().await;
+            *exposures.entry(signal.symbol.clone()).or_insert(0.0) += decision.approved_quantity;
         }
 
-        // 3. Volatility Scaling (symulacja volatility)
-        let simulated_volatility = 0.3; // TODO: Get real volatility
-        let scaled_quantity = self.apply_volatility_scaling(&signal, simulated_volatility).await;
-        let final_quantity = allowed_quantity.min(scaled_quantity);
-
-        // 4. Calculate risk score
         let risk_score = self.calculate_risk_score(&signal)?;
-
-        // 5. Get KINETIC SHIELD status
         let shield_status = self.get_kinetic_shield_status().await;
 
         let approved_signal = ApprovedSignal {
             original_signal: signal,
-            approved_quantity: final_quantity,
+            approved_quantity: decision.approved_quantity,
             risk_score,
             approval_timestamp: chrono::Utc::now(),
             kinetic_shield_status: shield_status,
+            risk_audit: decision.audit,
         };
 
         info!("✅ KINETIC SHIELD: Signal approved - quantity: {} → {}",
-              approved_signal.original_signal.quantity, final_quantity);
+              approved_signal.original_signal.quantity, approved_signal.approved_quantity);
 
         Ok(Some(approved_signal))
     }
@@ -547,6 +641,14 @@ impl RiskManager {
     }
 }
 
+#[async_trait]
+impl SupervisedComponent for RiskManager {
+    async fn run(&mut self, shutdown: CancellationToken) -> Result<()> {
+        self.shutdown = shutdown;
+        self.start().await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -572,4 +674,57 @@ mod tests {
         assert!(!manager.is_running);
         assert_eq!(manager.daily_pnl, 0.0);
     }
+
+    #[tokio::test]
+    async fn test_daily_loss_read_from_ledger() -> Result<()> {
+        use crate::modules::executor::{ExecutionResult, ExecutionStatus};
+        use crate::modules::position_ledger::PositionLedger;
+        use crate::modules::strategy::TradeAction;
+
+        let (_signal_tx, signal_rx) = mpsc::unbounded_channel();
+        let (execution_tx, _execution_rx) = mpsc::unbounded_channel();
+        let ledger = Arc::new(PositionLedger::default());
+        let manager = RiskManager::new(signal_rx, execution_tx, RiskParameters::default())
+            .with_ledger(ledger.clone());
+
+        let fill = |tx: &str, action: TradeAction, price: f64| ExecutionResult {
+            signal_id: tx.to_string(),
+            transaction_id: tx.to_string(),
+            status: ExecutionStatus::Confirmed,
+            executed_quantity: 1_000.0,
+            executed_price: price,
+            fees: 0.0,
+            timestamp: chrono::Utc::now(),
+            error_message: None,
+            symbol: "BONK".to_string(),
+            action: Some(action),
+            wallet_id: None,
+            fee_breakdown: None,
+        };
+        ledger.apply_execution(&fill("buy", TradeAction::Buy, 1.0));
+        // 1000 held of a 12% x 10k cap leaves room for ~200 more
+        let signal = TradingSignal {
+            signal_id: "bonk".to_string(),
+            symbol: "BONK".to_string(),
+            action: TradeAction::Buy,
+            quantity: 500.0,
+            target_price: 1.0,
+            price: Some(1.0),
+            confidence: 0.9,
+            timestamp: chrono::Utc::now(),
+            strategy_type: StrategyType::MomentumTrading,
+            urgency: None,
+            metadata: None,
+        };
+        let approved = manager.apply_kinetic_shield(signal).await?.expect("resized, not rejected");
+        assert!((approved.approved_quantity - 200.0).abs() < 1e-3);
+        assert_eq!(approved.risk_audit.len(), 3);
+        assert!(manager.check_daily_loss_limits()?);
+
+        // Closing at a loss beyond max_daily_loss trips the limit without hand-fed PnL
+        ledger.apply_execution(&fill("sell", TradeAction::Sell, 0.0));
+        assert!(manager.get_daily_pnl() <= -1_000.0);
+        assert!(!manager.check_daily_loss_limits()?);
+        Ok(())
+    }
 }
@@ -0,0 +1,743 @@
+// Risk Rules Module
+// Ordered, configurable pre-trade rule chain used by RiskManager.
+// Every rule approves, resizes or rejects a signal and reports the inputs
+// it looked at, so each decision carries a full audit trail.
+
+use crate::modules::liquidity_sizing::{
+    LiquidityConstraint, LiquidityProfile, LiquiditySizingConfig,
+};
+use crate::modules::risk::{LossStatistics, RiskDecision, RiskOutcome, RiskParameters};
+use crate::modules::strategy::{StrategyType, TradingSignal};
+use crate::modules::trade_journal::{JournalQuery, TradeJournal};
+use anyhow::{anyhow, Context, Result};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::Json;
+use axum::routing::get;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+use tracing::{error, info};
+
+/// State a rule may consult besides the signal itself
+#[derive(Debug, Clone, Default)]
+pub struct RiskContext {
+    pub daily_pnl: f64,
+    /// Current exposure to the signal's token
+    pub token_exposure: f64,
+    /// Token volatility, when the signal carries one (`metadata.volatility`)
+    pub volatility: Option<f64>,
+    /// Pool reserves or depth curve, when the signal carries them (`metadata.liquidity`)
+    pub liquidity: Option<LiquidityProfile>,
+    pub loss_stats: LossStatistics,
+}
+
+/// What a rule decided about the quantity it was handed
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleVerdict {
+    Pass,
+    Resize { quantity: f64, reason: String },
+    Reject { reason: String },
+}
+
+/// Verdict plus the inputs it was based on
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleCheck {
+    pub verdict: RuleVerdict,
+    pub inputs: BTreeMap<String, f64>,
+}
+
+impl RuleCheck {
+    pub fn pass() -> Self {
+        Self {
+            verdict: RuleVerdict::Pass,
+            inputs: BTreeMap::new(),
+        }
+    }
+
+    pub fn resize(quantity: f64, reason: impl Into<String>) -> Self {
+        Self {
+            verdict: RuleVerdict::Resize {
+                quantity,
+                reason: reason.into(),
+            },
+            inputs: BTreeMap::new(),
+        }
+    }
+
+    pub fn reject(reason: impl Into<String>) -> Self {
+        Self {
+            verdict: RuleVerdict::Reject {
+                reason: reason.into(),
+            },
+            inputs: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_input(mut self, name: &str, value: f64) -> Self {
+        self.inputs.insert(name.to_string(), value);
+        self
+    }
+}
+
+/// One pre-trade check in a `RiskRuleChain`
+pub trait RiskRule: Send + Sync + fmt::Debug {
+    fn name(&self) -> &str;
+
+    /// Judge `quantity` (already resized by earlier rules) for `signal`
+    fn check(&self, signal: &TradingSignal, quantity: f64, context: &RiskContext) -> RuleCheck;
+}
+
+/// Audit entry of one rule's evaluation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleAudit {
+    pub rule: String,
+    pub outcome: RiskOutcome,
+    pub quantity_in: f64,
+    pub quantity_out: f64,
+    pub inputs: BTreeMap<String, f64>,
+    pub reason: Option<String>,
+}
+
+/// Result of running the whole chain
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainDecision {
+    pub outcome: RiskOutcome,
+    pub approved_quantity: f64,
+    /// Reason of the rejecting rule, or of every resize
+    pub reason: Option<String>,
+    /// Every rule evaluated, in chain order
+    pub audit: Vec<RuleAudit>,
+}
+
+impl ChainDecision {
+    /// Rule that rejected the signal, if any
+    pub fn rejected_by(&self) -> Option<&str> {
+        self.audit
+            .iter()
+            .find(|entry| entry.outcome == RiskOutcome::Rejected)
+            .map(|entry| entry.rule.as_str())
+    }
+}
+
+/// Built-in rules, as written in a rule file:
+///
+/// ```toml
+/// [[rules]]
+/// rule = "min_confidence"
+/// threshold = 0.7
+///
+/// [[rules]]
+/// rule = "exposure_limit"
+/// limit_per_token = 0.12
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum BuiltinRule {
+    /// Reject signals below a confidence threshold
+    MinConfidence { threshold: f64 },
+
+    /// Resize down to a maximum quantity
+    MaxPositionSize { max_quantity: f64 },
+
+    /// Reject once today's PnL is at or beyond `-max_daily_loss`
+    DailyLossLimit { max_daily_loss: f64 },
+
+    /// KINETIC SHIELD: reject on daily drawdown (%) or hourly loss streak
+    CircuitBreaker {
+        daily_drawdown_limit: f32,
+        hourly_loss_streak_limit: u32,
+    },
+
+    /// KINETIC SHIELD: cap exposure per token at a share of capital
+    ExposureLimit {
+        #[serde(default = "default_capital")]
+        capital: f64,
+        limit_per_token: f64,
+    },
+
+    /// KINETIC SHIELD: scale down when volatility exceeds the maximum
+    VolatilityScaling { max_volatility: f64 },
+
+    /// MICRO-LIGHTNING: micro operations enabled and within their loss limit
+    MicroLightning { enabled: bool, max_loss: f64 },
+
+    /// Cap buys by entry impact and exit impact into stressed liquidity (bps);
+    /// sells are at most capped by exit impact now and never rejected
+    LiquidityImpact {
+        max_entry_impact_bps: f64,
+        max_exit_impact_bps: f64,
+        #[serde(default = "default_stressed_liquidity")]
+        stressed_liquidity: f64,
+    },
+}
+
+fn default_capital() -> f64 {
+    10_000.0
+}
+
+fn default_stressed_liquidity() -> f64 {
+    LiquiditySizingConfig::default().stressed_liquidity
+}
+
+impl BuiltinRule {
+    const fn name(&self) -> &'static str {
+        match self {
+            BuiltinRule::MinConfidence { .. } => "min_confidence",
+            BuiltinRule::MaxPositionSize { .. } => "max_position_size",
+            BuiltinRule::DailyLossLimit { .. } => "daily_loss_limit",
+            BuiltinRule::CircuitBreaker { .. } => "circuit_breaker",
+            BuiltinRule::ExposureLimit { .. } => "exposure_limit",
+            BuiltinRule::VolatilityScaling { .. } => "volatility_scaling",
+            BuiltinRule::MicroLightning { .. } => "micro_lightning",
+            BuiltinRule::LiquidityImpact { .. } => "liquidity_impact",
+        }
+    }
+}
+
+impl RiskRule for BuiltinRule {
+    fn name(&self) -> &str {
+        BuiltinRule::name(self)
+    }
+
+    fn check(&self, signal: &TradingSignal, quantity: f64, context: &RiskContext) -> RuleCheck {
+        match *self {
+            BuiltinRule::MinConfidence { threshold } => {
+                let check = if signal.confidence < threshold {
+                    RuleCheck::reject(format!(
+                        "confidence {} below threshold {}",
+                        signal.confidence, threshold
+                    ))
+                } else {
+                    RuleCheck::pass()
+                };
+                check
+                    .with_input("confidence", signal.confidence)
+                    .with_input("threshold", threshold)
+            }
+            BuiltinRule::MaxPositionSize { max_quantity } => {
+                let check = if quantity > max_quantity {
+                    RuleCheck::resize(
+                        max_quantity,
+                        format!(
+                            "quantity {} above max position size {}",
+                            quantity, max_quantity
+                        ),
+                    )
+                } else {
+                    RuleCheck::pass()
+                };
+                check.with_input("max_quantity", max_quantity)
+            }
+            BuiltinRule::DailyLossLimit { max_daily_loss } => {
+                let check = if context.daily_pnl > -max_daily_loss {
+                    RuleCheck::pass()
+                } else {
+                    RuleCheck::reject(format!(
+                        "daily pnl {:.2} beyond loss limit",
+                        context.daily_pnl
+                    ))
+                };
+                check
+                    .with_input("daily_pnl", context.daily_pnl)
+                    .with_input("max_daily_loss", max_daily_loss)
+            }
+            BuiltinRule::CircuitBreaker {
+                daily_drawdown_limit,
+                hourly_loss_streak_limit,
+            } => {
+                let stats = &context.loss_stats;
+                let check = if stats.daily_drawdown > daily_drawdown_limit {
+                    RuleCheck::reject(format!(
+                        "daily drawdown {}% > {}%",
+                        stats.daily_drawdown, daily_drawdown_limit
+                    ))
+                } else if stats.hourly_loss_streak > hourly_loss_streak_limit {
+                    RuleCheck::reject(format!(
+                        "loss streak {} > {}",
+                        stats.hourly_loss_streak, hourly_loss_streak_limit
+                    ))
+                } else {
+                    RuleCheck::pass()
+                };
+                check
+                    .with_input("daily_drawdown", stats.daily_drawdown as f64)
+                    .with_input("daily_drawdown_limit", daily_drawdown_limit as f64)
+                    .with_input("hourly_loss_streak", stats.hourly_loss_streak as f64)
+                    .with_input("hourly_loss_streak_limit", hourly_loss_streak_limit as f64)
+            }
+            BuiltinRule::ExposureLimit {
+                capital,
+                limit_per_token,
+            } => {
+                let max_exposure = capital * limit_per_token;
+                let allowed = (max_exposure - context.token_exposure).max(0.0);
+                let check = if quantity <= allowed {
+                    RuleCheck::pass()
+                } else if allowed > 0.0 {
+                    RuleCheck::resize(
+                        allowed,
+                        format!("exposure limit for {}: {} allowed", signal.symbol, allowed),
+                    )
+                } else {
+                    RuleCheck::reject(format!("exposure limit for {} reached", signal.symbol))
+                };
+                check
+                    .with_input("token_exposure", context.token_exposure)
+                    .with_input("max_exposure", max_exposure)
+            }
+            BuiltinRule::VolatilityScaling { max_volatility } => {
+                let Some(volatility) = context.volatility else {
+                    return RuleCheck::pass().with_input("max_volatility", max_volatility);
+                };
+                let check = if volatility > max_volatility {
+                    let factor = 1.0 - (volatility / max_volatility).min(0.5);
+                    RuleCheck::resize(
+                        quantity * factor,
+                        format!(
+                            "volatility {} above {}, scaled by {}",
+                            volatility, max_volatility, factor
+                        ),
+                    )
+                    .with_input("scaling_factor", factor)
+                } else {
+                    RuleCheck::pass()
+                };
+                check
+                    .with_input("volatility", volatility)
+                    .with_input("max_volatility", max_volatility)
+            }
+            BuiltinRule::MicroLightning { enabled, max_loss } => {
+                if signal.strategy_type != StrategyType::MicroLightning {
+                    return RuleCheck::pass();
+                }
+                let check = if !enabled {
+                    RuleCheck::reject("micro-lightning operations disabled")
+                } else if context.daily_pnl < -max_loss {
+                    RuleCheck::reject(format!(
+                        "daily loss ${:.2} exceeds micro limit ${:.2}",
+                        context.daily_pnl.abs(),
+                        max_loss
+                    ))
+                } else {
+                    RuleCheck::pass()
+                };
+                check
+                    .with_input("daily_pnl", context.daily_pnl)
+                    .with_input("max_loss", max_loss)
+            }
+            BuiltinRule::LiquidityImpact {
+                max_entry_impact_bps,
+                max_exit_impact_bps,
+                stressed_liquidity,
+            } => {
+                let Some(ref liquidity) = context.liquidity else {
+                    return RuleCheck::pass();
+                };
+                let constraint = LiquidityConstraint::new(LiquiditySizingConfig {
+                    max_entry_impact_bps,
+                    max_exit_impact_bps,
+                    stressed_liquidity,
+                });
+                let limit = constraint.limit(liquidity, &signal.action);
+                let selling = LiquidityConstraint::is_exit(&signal.action);
+                // Getting out is never blocked, even into an empty book
+                let approved = if selling && limit.max_quantity <= 0.0 {
+                    quantity
+                } else {
+                    quantity.min(limit.max_quantity)
+                };
+                let check = if approved >= quantity {
+                    RuleCheck::pass()
+                } else if approved > 0.0 {
+                    RuleCheck::resize(
+                        approved,
+                        format!(
+                            "{} impact limit for {}: {:.4} allowed",
+                            if limit.exit_bound() { "exit" } else { "entry" },
+                            signal.symbol,
+                            approved
+                        ),
+                    )
+                } else {
+                    RuleCheck::reject(format!("no liquidity for {}", signal.symbol))
+                };
+                check
+                    .with_input("max_quantity", limit.max_quantity)
+                    .with_input(
+                        "entry_impact_bps",
+                        constraint.entry_impact_bps(liquidity, &signal.action, approved),
+                    )
+                    .with_input(
+                        if selling {
+                            "exit_impact_bps"
+                        } else {
+                            "stressed_exit_impact_bps"
+                        },
+                        constraint.exit_impact_bps(liquidity, &signal.action, approved),
+                    )
+            }
+        }
+    }
+}
+
+/// Rule file contents; rules run in file order
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RiskRulesConfig {
+    #[serde(default)]
+    pub rules: Vec<BuiltinRule>,
+}
+
+impl RiskRulesConfig {
+    /// The checks `RiskManager` always ran: confidence, position size, daily loss
+    pub fn pre_trade(params: &RiskParameters) -> Self {
+        Self {
+            rules: vec![
+                BuiltinRule::MinConfidence {
+                    threshold: params.min_confidence_threshold,
+                },
+                BuiltinRule::MaxPositionSize {
+                    max_quantity: params.max_position_size,
+                },
+                BuiltinRule::DailyLossLimit {
+                    max_daily_loss: params.max_daily_loss,
+                },
+            ],
+        }
+    }
+
+    /// KINETIC SHIELD: circuit breaker, exposure limit, volatility scaling
+    pub fn kinetic_shield(params: &RiskParameters) -> Self {
+        let shield = &params.kinetic_shield;
+        Self {
+            rules: vec![
+                BuiltinRule::CircuitBreaker {
+                    daily_drawdown_limit: shield.daily_drawdown_limit,
+                    hourly_loss_streak_limit: shield.hourly_loss_streak_limit,
+                },
+                BuiltinRule::ExposureLimit {
+                    capital: default_capital(),
+                    limit_per_token: shield.exposure_limit_per_token as f64,
+                },
+                BuiltinRule::VolatilityScaling {
+                    max_volatility: shield.max_volatility as f64,
+                },
+            ],
+        }
+    }
+
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        let config: Self =
+            toml::from_str(content).map_err(|e| anyhow!("Invalid risk rule config: {}", e))?;
+        if config.rules.is_empty() {
+            return Err(anyhow!("Risk rule config defines no rules"));
+        }
+        Ok(config)
+    }
+
+    pub fn from_file(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read risk rule config: {}", path))?;
+        let config = Self::from_toml_str(&content)?;
+        info!(
+            "📂 Risk rules loaded from {}: {} rules",
+            path,
+            config.rules.len()
+        );
+        Ok(config)
+    }
+}
+
+/// Ordered rules; the first rejection stops the chain, resizes only shrink
+#[derive(Debug, Clone, Default)]
+pub struct RiskRuleChain {
+    rules: Vec<Arc<dyn RiskRule>>,
+}
+
+impl RiskRuleChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_config(config: &RiskRulesConfig) -> Self {
+        config
+            .rules
+            .iter()
+            .cloned()
+            .fold(Self::new(), |chain, rule| chain.with_rule(rule))
+    }
+
+    /// Append a rule, built-in or custom
+    pub fn with_rule(mut self, rule: impl RiskRule + 'static) -> Self {
+        self.rules.push(Arc::new(rule));
+        self
+    }
+
+    pub fn rule_names(&self) -> Vec<String> {
+        self.rules
+            .iter()
+            .map(|rule| rule.name().to_string())
+            .collect()
+    }
+
+    pub fn evaluate(&self, signal: &TradingSignal, context: &RiskContext) -> ChainDecision {
+        // Rules compare and scale quantities; NaN or a negative size would slip past them
+        if !signal.quantity.is_finite() || signal.quantity <= 0.0 {
+            return ChainDecision {
+                outcome: RiskOutcome::Rejected,
+                approved_quantity: 0.0,
+                reason: Some(format!("invalid quantity {}", signal.quantity)),
+                audit: Vec::new(),
+            };
+        }
+
+        let mut quantity = signal.quantity;
+        let mut audit = Vec::with_capacity(self.rules.len());
+        let mut reasons = Vec::new();
+
+        for rule in &self.rules {
+            let check = rule.check(signal, quantity, context);
+            let (outcome, quantity_out, reason) = match check.verdict {
+                RuleVerdict::Pass => (RiskOutcome::Approved, quantity, None),
+                RuleVerdict::Resize {
+                    quantity: resized,
+                    reason,
+                } => {
+                    // A rule may only shrink the order; NaN resizes reject it
+                    let resized = resized.max(0.0).min(quantity);
+                    if resized > 0.0 {
+                        (RiskOutcome::Resized, resized, Some(reason))
+                    } else {
+                        (RiskOutcome::Rejected, 0.0, Some(reason))
+                    }
+                }
+                RuleVerdict::Reject { reason } => (RiskOutcome::Rejected, 0.0, Some(reason)),
+            };
+
+            audit.push(RuleAudit {
+                rule: rule.name().to_string(),
+                outcome: outcome.clone(),
+                quantity_in: quantity,
+                quantity_out,
+                inputs: check.inputs,
+                reason: reason.clone(),
+            });
+
+            if outcome == RiskOutcome::Rejected {
+                return ChainDecision {
+                    outcome,
+                    approved_quantity: 0.0,
+                    reason: reason.map(|r| format!("{}: {}", rule.name(), r)),
+                    audit,
+                };
+            }
+            if let Some(reason) = reason {
+                reasons.push(format!("{}: {}", rule.name(), reason));
+            }
+            quantity = quantity_out;
+        }
+
+        ChainDecision {
+            outcome: if quantity < signal.quantity {
+                RiskOutcome::Resized
+            } else {
+                RiskOutcome::Approved
+            },
+            approved_quantity: quantity,
+            reason: (!reasons.is_empty()).then(|| reasons.join("; ")),
+            audit,
+        }
+    }
+}
+
+// ========== HTTP API ==========
+
+#[derive(Clone)]
+struct RiskAuditState {
+    journal: Arc<TradeJournal>,
+    rules: Vec<String>,
+}
+
+/// `/rules` (active chain, in order), `/decisions` (journal query:
+/// `signal_id`, `strategy`, `token`, `from`, `to`, `limit`) and
+/// `/decisions/:signal_id`
+pub fn create_risk_audit_router(journal: Arc<TradeJournal>, chain: &RiskRuleChain) -> Router {
+    Router::new()
+        .route("/rules", get(list_rules))
+        .route("/decisions", get(query_decisions))
+        .route("/decisions/:signal_id", get(signal_decisions))
+        .with_state(RiskAuditState {
+            journal,
+            rules: chain.rule_names(),
+        })
+}
+
+async fn list_rules(State(state): State<RiskAuditState>) -> Json<Vec<String>> {
+    Json(state.rules)
+}
+
+async fn query_decisions(
+    State(state): State<RiskAuditState>,
+    Query(query): Query<JournalQuery>,
+) -> Result<Json<Vec<RiskDecision>>, StatusCode> {
+    state
+        .journal
+        .query_risk_decisions(&query)
+        .map(Json)
+        .map_err(|e| {
+            error!("❌ Risk decision query failed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+async fn signal_decisions(
+    State(state): State<RiskAuditState>,
+    Path(signal_id): Path<String>,
+) -> Result<Json<Vec<RiskDecision>>, StatusCode> {
+    let decisions = state
+        .journal
+        .query_risk_decisions(&JournalQuery::new().signal_id(signal_id))
+        .map_err(|e| {
+            error!("❌ Risk decision query failed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if decisions.is_empty() {
+        return Err(StatusCode::NOT_FOUND);
+    }
+    Ok(Json(decisions))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::strategy::TradeAction;
+
+    fn signal(quantity: f64, confidence: f64) -> TradingSignal {
+        TradingSignal {
+            signal_id: "sig".to_string(),
+            symbol: "BONK".to_string(),
+            action: TradeAction::Buy,
+            quantity,
+            target_price: 1.0,
+            price: Some(1.0),
+            confidence,
+            timestamp: chrono::Utc::now(),
+            strategy_type: StrategyType::MomentumTrading,
+            urgency: None,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_chain_resizes_and_rejects_with_audit() {
+        let config = RiskRulesConfig::from_toml_str(
+            r#"
+            [[rules]]
+            rule = "min_confidence"
+            threshold = 0.7
+
+            [[rules]]
+            rule = "max_position_size"
+            max_quantity = 1000.0
+
+            [[rules]]
+            rule = "exposure_limit"
+            limit_per_token = 0.12
+
+            [[rules]]
+            rule = "volatility_scaling"
+            max_volatility = 0.5
+            "#,
+        )
+        .unwrap();
+        let chain = RiskRuleChain::from_config(&config);
+        assert_eq!(
+            chain.rule_names(),
+            [
+                "min_confidence",
+                "max_position_size",
+                "exposure_limit",
+                "volatility_scaling"
+            ]
+        );
+
+        // 1500 → 1000 (max size) → 800 (1200 cap, 400 held) → 400 (vol 0.8 over 0.5 halves it)
+        let context = RiskContext {
+            token_exposure: 400.0,
+            volatility: Some(0.8),
+            ..RiskContext::default()
+        };
+        let decision = chain.evaluate(&signal(1500.0, 0.9), &context);
+        assert_eq!(decision.outcome, RiskOutcome::Resized);
+        assert!((decision.approved_quantity - 400.0).abs() < 1e-9);
+        let outcomes: Vec<_> = decision.audit.iter().map(|a| a.outcome.clone()).collect();
+        assert_eq!(
+            outcomes,
+            [
+                RiskOutcome::Approved,
+                RiskOutcome::Resized,
+                RiskOutcome::Resized,
+                RiskOutcome::Resized
+            ]
+        );
+        assert_eq!(decision.audit[2].quantity_in, 1000.0);
+        assert_eq!(decision.audit[2].quantity_out, 800.0);
+        assert_eq!(decision.audit[2].inputs["max_exposure"], 1200.0);
+        assert_eq!(decision.audit[3].inputs["scaling_factor"], 0.5);
+        assert!(decision.reason.unwrap().starts_with("max_position_size: "));
+
+        // Rejection stops the chain
+        let decision = chain.evaluate(&signal(10.0, 0.5), &context);
+        assert_eq!(decision.outcome, RiskOutcome::Rejected);
+        assert_eq!(decision.rejected_by(), Some("min_confidence"));
+        assert_eq!(decision.audit.len(), 1);
+        assert_eq!(decision.audit[0].inputs["confidence"], 0.5);
+        assert_eq!(
+            decision.reason.as_deref(),
+            Some("min_confidence: confidence 0.5 below threshold 0.7")
+        );
+
+        // No room left under the exposure cap
+        let full = RiskContext {
+            token_exposure: 1200.0,
+            ..RiskContext::default()
+        };
+        let decision = chain.evaluate(&signal(10.0, 0.9), &full);
+        assert_eq!(decision.rejected_by(), Some("exposure_limit"));
+    }
+
+    #[test]
+    fn test_invalid_quantities_are_rejected_before_the_chain() {
+        let chain = RiskRuleChain::from_config(&RiskRulesConfig::kinetic_shield(
+            &RiskParameters::default(),
+        ));
+        for quantity in [f64::NAN, f64::INFINITY, -5.0, 0.0] {
+            let decision = chain.evaluate(&signal(quantity, 0.9), &RiskContext::default());
+            assert_eq!(decision.outcome, RiskOutcome::Rejected, "{}", quantity);
+            assert_eq!(decision.approved_quantity, 0.0);
+            assert!(decision.audit.is_empty());
+            assert!(decision.reason.unwrap().starts_with("invalid quantity"));
+        }
+    }
+
+    #[test]
+    fn test_rule_config_parsing() {
+        let err = RiskRulesConfig::from_toml_str("[[rules]]\nrule = \"teleport\"\n").unwrap_err();
+        assert!(
+            err.to_string().contains("Invalid risk rule config"),
+            "{}",
+            err
+        );
+        assert!(RiskRulesConfig::from_toml_str("").is_err());
+
+        let shield = RiskRulesConfig::kinetic_shield(&RiskParameters::default());
+        let text = toml::to_string(&shield).unwrap();
+        assert_eq!(RiskRulesConfig::from_toml_str(&text).unwrap(), shield);
+        assert_eq!(
+            RiskRuleChain::from_config(&shield).rule_names(),
+            ["circuit_breaker", "exposure_limit", "volatility_scaling"]
+        );
+    }
+}
@@ -2,6 +2,7 @@
 // Handles multiple RPC endpoints with automatic failover and health monitoring
 
 use crate::config::{RpcEndpoint, SolanaConfig};
+use crate::modules::kill_switch::KillSwitch;
 use anyhow::{anyhow, Result};
 use serde_json::Value;
 use std::sync::Arc;
@@ -15,6 +16,8 @@ pub struct RpcFailoverClient {
     config: SolanaConfig,
     http_client: reqwest::Client,
     current_endpoint_index: Arc<RwLock<usize>>,
+    // Every failed call is reported so an RPC failure storm halts trading
+    kill_switch: Option<Arc<KillSwitch>>,
 }
 
 #[derive(Debug, Clone)]
@@ -37,6 +40,26 @@ impl RpcFailoverClient {
             config,
             http_client,
             current_endpoint_index: Arc::new(RwLock::new(0)),
+            kill_switch: None,
+        }
+    }
+
+    /// Report failed calls to the kill switch's RPC failure storm trigger
+    pub fn with_kill_switch(mut self, kill_switch: Arc<KillSwitch>) -> Self {
+        self.kill_switch = Some(kill_switch);
+        self
+    }
+
+    async fn report_failure(&self, endpoint_index: usize) {
+        if let Some(ref kill_switch) = self.kill_switch {
+            let endpoint_name = {
+                let endpoints = self.endpoints.read().await;
+                endpoints
+                    .get(endpoint_index)
+                    .map(|e| e.name.clone())
+                    .unwrap_or_default()
+            };
+            kill_switch.report_rpc_failure(&endpoint_name);
         }
     }
 
@@ -44,7 +67,11 @@ impl RpcFailoverClient {
     pub async fn call(&self, method: &str, params: Value) -> Result<RpcResponse> {
         if !self.config.failover_enabled {
             // Use primary endpoint only
-            return self.call_single_endpoint(0, method, params).await;
+            let result = self.call_single_endpoint(0, method, params).await;
+            if result.is_err() {
+                self.report_failure(0).await;
+            }
+            return result;
         }
 
         let endpoint_count = {
@@ -89,6 +116,7 @@ impl RpcFailoverClient {
                         endpoint_name, e
                     );
                     last_error = Some(e);
+                    self.report_failure(index).await;
 
                     // Mark endpoint as unhealthy
                     self.mark_endpoint_unhealthy(index).await;
@@ -347,8 +375,27 @@ impl RpcFailoverClient {
     pub async fn get_transaction(&self, signature: &str) -> Result<RpcResponse> {
         self.call(
             "getTransaction",
-            serde_json::json!([signature, {"encoding": "base64"}]),
+            serde_json::json!([signature, {
+                "encoding": "base64",
+                "commitment": "confirmed",
+                "maxSupportedTransactionVersion": 0
+            }]),
         )
         .await
     }
+
+    /// Get processed/confirmed/finalized status for recent signatures
+    pub async fn get_signature_statuses(&self, signatures: &[&str]) -> Result<RpcResponse> {
+        self.call(
+            "getSignatureStatuses",
+            serde_json::json!([signatures, {"searchTransactionHistory": false}]),
+        )
+        .await
+    }
+
+    /// Get current block height (for blockhash expiry checks)
+    pub async fn get_block_height(&self) -> Result<RpcResponse> {
+        self.call("getBlockHeight", serde_json::json!([{"commitment": "confirmed"}]))
+            .await
+    }
 }
@@ -3,16 +3,16 @@
 
 use crate::modules::data_ingestor::MarketData;
 use crate::modules::memcoin_strategies::{
-    MemcoinStrategy, LiquidityEvent, SocialSignal,
-    liquidity_tsunami::LiquidityTsunamiStrategy,
-    social_fission::SocialFissionStrategy,
-    whale_shadowing::WhaleShadowingStrategy,
-    death_spiral_intercept::{DeathSpiralInterceptStrategy, PanicSellEvent},
-    meme_virus::MemeVirusStrategy,
+    LiquidityEvent, MarketEvent, SocialSignal, StrategyRegistry, StrategyRegistryConfig,
+    death_spiral_intercept::PanicSellEvent,
 };
+use crate::modules::supervisor::SupervisedComponent;
 use anyhow::Result;
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use tokio::sync::mpsc;
+use std::sync::Arc;
+use tokio::sync::{mpsc, RwLock};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -125,13 +125,12 @@ pub struct StrategyEngine {
     market_data_receiver: mpsc::UnboundedReceiver<MarketData>,
     signal_sender: mpsc::UnboundedSender<TradingSignal>,
     is_running: bool,
-    // MEMCOIN SWARMGUARD STRATEGIES - używamy enum zamiast trait object
-    liquidity_tsunami: Option<LiquidityTsunamiStrategy>,
-    social_fission: Option<SocialFissionStrategy>,
-    whale_shadowing: Option<WhaleShadowingStrategy>,
-    death_spiral_intercept: Option<DeathSpiralInterceptStrategy>,
-    meme_virus: Option<MemeVirusStrategy>,
+    // MEMCOIN SWARMGUARD STRATEGIES - config-driven registry, shared for runtime control
+    strategies: Arc<RwLock<StrategyRegistry>>,
+    strategy_config: StrategyRegistryConfig,
     capital: f64,
+    // Cancelled by stop() or the supervisor
+    shutdown: CancellationToken,
 }
 
 #[allow(dead_code)]
@@ -144,77 +143,78 @@ impl StrategyEngine {
             market_data_receiver,
             signal_sender,
             is_running: false,
-            liquidity_tsunami: None,
-            social_fission: None,
-            whale_shadowing: None,
-            death_spiral_intercept: None,
-            meme_virus: None,
+            strategies: Arc::new(RwLock::new(StrategyRegistry::new(10000.0))),
+            strategy_config: StrategyRegistryConfig::default(),
             capital: 10000.0, // Default capital
+            shutdown: CancellationToken::new(),
         }
     }
 
-    /// Inicjalizacja strategii memcoin
-    pub fn initialize_memcoin_strategies(&mut self) -> Result<()> {
-        // Inicjalizuj wszystkie strategie MEMCOIN SWARMGUARD
-        self.liquidity_tsunami = Some(LiquidityTsunamiStrategy::new(self.capital));
-        self.social_fission = Some(SocialFissionStrategy::new(self.capital));
-        self.whale_shadowing = Some(WhaleShadowingStrategy::new(self.capital));
-        self.death_spiral_intercept = Some(DeathSpiralInterceptStrategy::new(self.capital));
-        self.meme_virus = Some(MemeVirusStrategy::new(self.capital));
-
-        info!("🦾 MEMCOIN SWARMGUARD strategies initialized: 5 strategies loaded");
-        Ok(())
+    /// Capital allocated to memcoin strategies (set before initialization)
+    pub fn with_capital(mut self, capital: f64) -> Self {
+        self.capital = capital;
+        self
     }
 
-    /// Aktywacja wszystkich strategii memcoin
-    pub async fn activate_memcoin_strategies(&mut self) -> Result<()> {
-        if let Some(ref mut strategy) = self.liquidity_tsunami {
-            strategy.activate().await?;
-            info!("✅ Activated LIQUIDITY TSUNAMI strategy");
-        }
-
-        if let Some(ref mut strategy) = self.social_fission {
-            strategy.activate().await?;
-            info!("✅ Activated SOCIAL FISSION strategy");
-        }
+    /// Strategy set to build on initialization (defaults to the five SWARMGUARD strategies)
+    pub fn with_strategy_config(mut self, config: StrategyRegistryConfig) -> Self {
+        self.strategy_config = config;
+        self
+    }
 
-        if let Some(ref mut strategy) = self.whale_shadowing {
-            strategy.activate().await?;
-            info!("✅ Activated WHALE SHADOWING strategy");
-        }
+    /// Shared registry handle for runtime add/remove/pause/resume
+    pub fn strategy_registry(&self) -> Arc<RwLock<StrategyRegistry>> {
+        self.strategies.clone()
+    }
 
-        if let Some(ref mut strategy) = self.death_spiral_intercept {
-            strategy.activate().await?;
-            info!("✅ Activated DEATH SPIRAL INTERCEPT strategy");
-        }
+    /// Inicjalizacja strategii memcoin
+    pub async fn initialize_memcoin_strategies(&mut self) -> Result<()> {
+        let mut registry = self.strategies.write().await;
+        registry.set_capital(self.capital);
+        let loaded = registry.load(&self.strategy_config).await?;
 
-        if let Some(ref mut strategy) = self.meme_virus {
-            strategy.activate().await?;
-            info!("✅ Activated MEME VIRUS strategy");
-        }
+        info!("🦾 MEMCOIN SWARMGUARD strategies initialized: {} strategies loaded", loaded);
+        Ok(())
+    }
 
+    /// Aktywacja wszystkich włączonych strategii memcoin
+    pub async fn activate_memcoin_strategies(&mut self) -> Result<()> {
+        let activated = self.strategies.write().await.activate_all().await?;
+        info!("✅ Activated {} memcoin strategies", activated);
         Ok(())
     }
 
     pub async fn start(&mut self) -> Result<()> {
         info!("🧠 StrategyEngine starting...");
         self.is_running = true;
+        let shutdown = self.shutdown.clone();
 
         while self.is_running {
-            if let Some(market_data) = self.market_data_receiver.recv().await {
-                self.process_market_data(market_data).await?;
+            tokio::select! {
+                _ = shutdown.cancelled() => break,
+                market_data = self.market_data_receiver.recv() => match market_data {
+                    Some(market_data) => self.process_market_data(market_data).await?,
+                    None => break,
+                },
             }
         }
 
+        // Process whatever the ingestor queued before it stopped
+        while let Ok(market_data) = self.market_data_receiver.try_recv() {
+            self.process_market_data(market_data).await?;
+        }
+        self.is_running = false;
+
         Ok(())
     }
 
     pub async fn stop(&mut self) {
         info!("🛑 StrategyEngine stopping...");
         self.is_running = false;
+        self.shutdown.cancel();
     }
 
-    async fn process_market_data(&self, data: MarketData) -> Result<()> {
+    pub(crate) async fn process_market_data(&self, data: MarketData) -> Result<()> {
         debug!("Processing market data for symbol: {}", data.symbol);
 
         // Przetwarzanie przez strategie memcoin
@@ -259,82 +259,75 @@ impl StrategyEngine {
 
     /// Przetwarzanie sygnałów przez strategie memcoin
     async fn process_memcoin_signals(&self, data: &MarketData) -> Result<()> {
-
-        // Generuj różne typy sygnałów na podstawie market data
+        // Generuj różne typy zdarzeń na podstawie market data
+        let mut events = Vec::new();
 
         // 1. LIQUIDITY TSUNAMI - symulacja liquidity event
         if data.volume > 1000.0 {
-            let liquidity_event = LiquidityEvent {
+            events.push(MarketEvent::Liquidity(LiquidityEvent {
                 mint: data.symbol.clone(),
                 delta: data.volume * 0.05, // 5% of volume as delta
                 velocity: (data.price_change_24h.abs() / 100.0).min(1.0),
                 volatility: data.price_change_24h.abs() / 100.0,
                 timestamp: chrono::Utc::now(),
-            };
-
-            if let Some(ref strategy) = self.liquidity_tsunami {
-                if let Ok(Some(signal)) = strategy.process_signal(&liquidity_event).await {
-                    self.send_signal(signal).await?;
-                }
-            }
+            }));
         }
 
         // 2. SOCIAL FISSION - symulacja social signal
         if data.price_change_1h > 5.0 {
-            let social_signal = SocialSignal {
+            events.push(MarketEvent::Social(SocialSignal {
                 token: data.symbol.clone(),
                 intensity: (data.price_change_1h * 10.0).min(100.0) as f32,
                 sentiment: if data.price_change_1h > 0.0 { 0.8 } else { -0.8 },
                 mentions_count: (data.volume / 100.0) as u32,
                 source: "twitter".to_string(),
                 timestamp: chrono::Utc::now(),
-            };
-
-            if let Some(ref strategy) = self.social_fission {
-                if let Ok(Some(signal)) = strategy.process_signal(&social_signal).await {
-                    self.send_signal(signal).await?;
-                }
-            }
+            }));
         }
 
         // 3. DEATH SPIRAL INTERCEPT - symulacja panic sell
         if data.price_change_1h < -15.0 && data.volume > 500.0 {
-            let panic_event = PanicSellEvent {
+            events.push(MarketEvent::PanicSell(PanicSellEvent {
                 token: data.symbol.clone(),
                 volume_percentage: ((data.volume / 10000.0) * 100.0) as f32, // Symulacja % podaży
                 price_drop: data.price_change_1h.abs() as f32,
                 sell_transactions: vec![], // Placeholder
                 timestamp: chrono::Utc::now(),
-            };
-
-            if let Some(ref strategy) = self.death_spiral_intercept {
-                if let Ok(Some(signal)) = strategy.process_signal(&panic_event).await {
-                    self.send_signal(signal).await?;
-                }
-            }
+            }));
         }
 
         // 4. MEME VIRUS - symulacja viral meme
         if data.price_change_24h > 50.0 {
-            let social_signal = SocialSignal {
+            events.push(MarketEvent::ViralMeme(SocialSignal {
                 token: data.symbol.clone(),
                 intensity: 95.0,
                 sentiment: 0.9,
                 mentions_count: 50,
                 source: "viral meme detected".to_string(),
                 timestamp: chrono::Utc::now(),
-            };
+            }));
+        }
 
-            if let Some(ref strategy) = self.meme_virus {
-                if let Ok(Some(signal)) = strategy.process_signal(&social_signal).await {
-                    self.send_signal(signal).await?;
-                }
-            }
+        // 5. Raw tick for strategies such as PHOENIX ENGINE
+        events.push(MarketEvent::Tick(data.clone()));
+
+        for event in &events {
+            self.route_event(event).await?;
         }
 
         Ok(())
     }
 
+    /// Route a market event (e.g. whale transactions or new pools from an
+    /// external feed) to the strategies subscribed to its kind
+    pub async fn route_event(&self, event: &MarketEvent) -> Result<()> {
+        let signals = self.strategies.read().await.dispatch(event).await;
+        for signal in signals {
+            self.send_signal(signal).await?;
+        }
+        Ok(())
+    }
+
     /// Wysłanie sygnału handlowego
     async fn send_signal(&self, signal: TradingSignal) -> Result<()> {
         info!("🎯 MEMCOIN SWARMGUARD signal: {:?} {} {} SOL (confidence: {})",
@@ -381,6 +374,14 @@ impl StrategyEngine {
     }
 }
 
+#[async_trait]
+impl SupervisedComponent for StrategyEngine {
+    async fn run(&mut self, shutdown: CancellationToken) -> Result<()> {
+        self.shutdown = shutdown;
+        self.start().await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -394,6 +395,37 @@ mod tests {
         assert!(!engine.is_running);
     }
 
+    #[tokio::test]
+    async fn test_registry_driven_engine() {
+        let (_market_tx, market_rx) = mpsc::unbounded_channel();
+        let (signal_tx, mut signal_rx) = mpsc::unbounded_channel();
+
+        let mut config = StrategyRegistryConfig::default();
+        config.strategies.retain(|s| s.name == "liquidity_tsunami");
+        let mut engine = StrategyEngine::new(market_rx, signal_tx).with_strategy_config(config);
+        engine.initialize_memcoin_strategies().await.unwrap();
+        engine.activate_memcoin_strategies().await.unwrap();
+
+        let data = MarketData {
+            symbol: "BONK".to_string(),
+            price: 1.0,
+            volume: 5000.0,
+            timestamp: chrono::Utc::now(),
+            source: crate::modules::data_ingestor::DataSource::Helius,
+            price_change_1h: 0.0,
+            price_change_24h: 90.0,
+        };
+        engine.process_market_data(data.clone()).await.unwrap();
+        let signal = signal_rx.try_recv().unwrap();
+        assert_eq!(signal.strategy_type, StrategyType::LiquidityTsunami);
+
+        // Paused strategies stop receiving market events
+        let registry = engine.strategy_registry();
+        registry.write().await.pause("liquidity_tsunami").await.unwrap();
+        engine.process_market_data(data).await.unwrap();
+        assert!(signal_rx.try_recv().is_err());
+    }
+
     #[test]
     fn test_calculate_slippage() {
         // Create a minimal StrategyEngine for testing
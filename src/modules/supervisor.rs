@@ -0,0 +1,528 @@
+// Supervisor Module
+// Owns pipeline component tasks: cancellation on SIGINT/SIGTERM, ordered
+// drain (upstream first, persistence last) and restarts with backoff
+
+use crate::monitoring::MonitoringState;
+use anyhow::Result;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+/// A long-running pipeline stage. `run` returns once `shutdown` is cancelled
+/// and everything already queued for the component has been processed.
+#[async_trait]
+pub trait SupervisedComponent: Send + 'static {
+    async fn run(&mut self, shutdown: CancellationToken) -> Result<()>;
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupervisorConfig {
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// Consecutive crashes before a component is given up on
+    pub max_restarts: u32,
+    /// Time a component gets to drain after cancellation before it is aborted
+    pub drain_timeout_secs: u64,
+    /// Interval for refreshing running components' monitoring heartbeat
+    pub heartbeat_interval_secs: u64,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            initial_backoff_ms: 500,
+            max_backoff_ms: 30_000,
+            max_restarts: 10,
+            drain_timeout_secs: 10,
+            heartbeat_interval_secs: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ComponentState {
+    Starting,
+    Running,
+    Restarting,
+    Draining,
+    Stopped,
+    Failed,
+}
+
+impl ComponentState {
+    /// Status string understood by `monitoring` health/readiness checks
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ComponentState::Starting => "starting",
+            ComponentState::Running => "running",
+            ComponentState::Restarting => "restarting",
+            ComponentState::Draining => "draining",
+            ComponentState::Stopped => "stopped",
+            ComponentState::Failed => "error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentStatus {
+    pub state: ComponentState,
+    pub restarts: u32,
+    pub last_error: Option<String>,
+}
+
+/// Component state shared between the supervisor, its tasks and monitoring
+#[derive(Clone)]
+struct StateReporter {
+    states: Arc<Mutex<HashMap<String, ComponentStatus>>>,
+    monitoring: Option<MonitoringState>,
+}
+
+impl StateReporter {
+    fn report(&self, name: &str, state: ComponentState, error: Option<String>) {
+        let restarts = {
+            let mut states = self.states.lock();
+            let status = states.entry(name.to_string()).or_insert(ComponentStatus {
+                state,
+                restarts: 0,
+                last_error: None,
+            });
+            status.state = state;
+            if state == ComponentState::Restarting {
+                status.restarts += 1;
+            }
+            if error.is_some() {
+                status.last_error = error;
+            }
+            status.restarts
+        };
+
+        if let Some(ref monitoring) = self.monitoring {
+            monitoring.record_component_state(name, state.as_str(), restarts as u64);
+        }
+    }
+}
+
+struct SupervisedTask {
+    name: String,
+    token: CancellationToken,
+    handle: JoinHandle<()>,
+}
+
+/// Runs pipeline components and shuts them down in order
+pub struct Supervisor {
+    config: SupervisorConfig,
+    shutdown: CancellationToken,
+    reporter: StateReporter,
+    /// Pipeline order: upstream first
+    tasks: Vec<SupervisedTask>,
+}
+
+impl Supervisor {
+    pub fn new(config: SupervisorConfig) -> Self {
+        Self {
+            config,
+            shutdown: CancellationToken::new(),
+            reporter: StateReporter {
+                states: Arc::new(Mutex::new(HashMap::new())),
+                monitoring: None,
+            },
+            tasks: Vec::new(),
+        }
+    }
+
+    /// Mirror component state into the health/readiness endpoints
+    pub fn with_monitoring(mut self, monitoring: MonitoringState) -> Self {
+        self.reporter.monitoring = Some(monitoring);
+        self
+    }
+
+    /// Cancelled when shutdown begins (e.g. for the HTTP server's graceful shutdown)
+    pub fn shutdown_token(&self) -> CancellationToken {
+        self.shutdown.clone()
+    }
+
+    /// Start supervising a component. Call in pipeline order, upstream first:
+    /// components are drained in the same order on shutdown.
+    pub fn spawn<C: SupervisedComponent>(&mut self, name: &str, component: C) {
+        let token = CancellationToken::new();
+        self.reporter.report(name, ComponentState::Starting, None);
+
+        let handle = tokio::spawn(supervise(
+            name.to_string(),
+            component,
+            token.clone(),
+            self.config.clone(),
+            self.reporter.clone(),
+        ));
+
+        self.tasks.push(SupervisedTask {
+            name: name.to_string(),
+            token,
+            handle,
+        });
+    }
+
+    pub fn status(&self, name: &str) -> Option<ComponentStatus> {
+        self.reporter.states.lock().get(name).cloned()
+    }
+
+    pub fn statuses(&self) -> HashMap<String, ComponentStatus> {
+        self.reporter.states.lock().clone()
+    }
+
+    /// Run until SIGINT/SIGTERM (or the shutdown token), then shut down
+    pub async fn run_until_shutdown(self) -> Result<()> {
+        tokio::select! {
+            _ = shutdown_signal() => {}
+            _ = self.shutdown.cancelled() => info!("🛑 Shutdown requested"),
+        }
+        self.shutdown().await
+    }
+
+    /// Cancel and drain components one at a time, upstream first, so every
+    /// stage has flushed into the next before that one stops
+    pub async fn shutdown(self) -> Result<()> {
+        info!(
+            "🛑 Supervisor shutting down {} component(s)...",
+            self.tasks.len()
+        );
+        self.shutdown.cancel();
+
+        for task in self.tasks {
+            info!("⏳ Draining {}...", task.name);
+            task.token.cancel();
+            if let Err(e) = task.handle.await {
+                error!("❌ Supervisor task for {} failed: {}", task.name, e);
+            }
+        }
+
+        info!("✅ All components stopped");
+        Ok(())
+    }
+}
+
+/// Resolves on SIGINT (Ctrl+C) or, on Unix, SIGTERM
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            error!("❌ Failed to listen for SIGINT: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                error!("❌ Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("🛑 SIGINT received"),
+        _ = terminate => info!("🛑 SIGTERM received"),
+    }
+}
+
+/// Run `component` until `token` is cancelled, restarting it after errors
+/// and panics with exponential backoff
+async fn supervise<C: SupervisedComponent>(
+    name: String,
+    component: C,
+    token: CancellationToken,
+    config: SupervisorConfig,
+    reporter: StateReporter,
+) {
+    // tokio's Mutex is not poisoned by a panicking holder, so a crashed
+    // component can be locked and started again
+    let component = Arc::new(tokio::sync::Mutex::new(component));
+    let max_backoff = Duration::from_millis(config.max_backoff_ms);
+    let drain_timeout = Duration::from_secs(config.drain_timeout_secs);
+    let mut backoff = Duration::from_millis(config.initial_backoff_ms);
+    let mut consecutive_crashes = 0;
+
+    loop {
+        info!("▶️ Starting {}", name);
+        reporter.report(&name, ComponentState::Running, None);
+        let started = tokio::time::Instant::now();
+
+        let mut run = {
+            let component = component.clone();
+            let token = token.clone();
+            tokio::spawn(async move { component.lock().await.run(token).await })
+        };
+
+        let mut heartbeat =
+            tokio::time::interval(Duration::from_secs(config.heartbeat_interval_secs.max(1)));
+        heartbeat.tick().await;
+        let drain_deadline = tokio::time::sleep(Duration::MAX);
+        tokio::pin!(drain_deadline);
+        let mut draining = false;
+
+        let outcome = loop {
+            tokio::select! {
+                outcome = &mut run => break Some(outcome),
+                _ = heartbeat.tick(), if !draining => {
+                    reporter.report(&name, ComponentState::Running, None);
+                }
+                _ = token.cancelled(), if !draining => {
+                    draining = true;
+                    reporter.report(&name, ComponentState::Draining, None);
+                    drain_deadline
+                        .as_mut()
+                        .reset(tokio::time::Instant::now() + drain_timeout);
+                }
+                _ = &mut drain_deadline, if draining => {
+                    warn!("⚠️ {} did not drain within {:?}, aborting", name, drain_timeout);
+                    run.abort();
+                    break None;
+                }
+            }
+        };
+
+        let error = match outcome {
+            Some(Ok(Ok(()))) => None,
+            Some(Ok(Err(e))) => Some(format!("{:#}", e)),
+            Some(Err(e)) if e.is_panic() => Some("panicked".to_string()),
+            Some(Err(e)) => Some(e.to_string()),
+            None => Some("drain timed out".to_string()),
+        };
+
+        // Clean exits and anything that ends during shutdown are final
+        if error.is_none() || token.is_cancelled() {
+            if let Some(ref e) = error {
+                warn!("⚠️ {} stopped with error during shutdown: {}", name, e);
+            } else {
+                info!("⏹️ {} stopped", name);
+            }
+            reporter.report(&name, ComponentState::Stopped, error);
+            return;
+        }
+
+        // A run that outlived the maximum backoff counts as recovered
+        if started.elapsed() > max_backoff {
+            consecutive_crashes = 0;
+            backoff = Duration::from_millis(config.initial_backoff_ms);
+        }
+        consecutive_crashes += 1;
+
+        let error = error.unwrap_or_default();
+        if consecutive_crashes > config.max_restarts {
+            error!(
+                "❌ {} crashed {} times in a row, giving up: {}",
+                name, consecutive_crashes, error
+            );
+            reporter.report(&name, ComponentState::Failed, Some(error));
+            return;
+        }
+
+        error!(
+            "❌ {} crashed: {}. Restarting in {:?} ({}/{})",
+            name, error, backoff, consecutive_crashes, config.max_restarts
+        );
+        reporter.report(&name, ComponentState::Restarting, Some(error));
+
+        tokio::select! {
+            _ = tokio::time::sleep(backoff) => {}
+            _ = token.cancelled() => {
+                reporter.report(&name, ComponentState::Stopped, None);
+                return;
+            }
+        }
+        backoff = (backoff * 2).min(max_backoff);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use tokio::sync::mpsc;
+
+    /// Fails (or panics) `crashes` times, then runs until cancelled
+    struct Flaky {
+        crashes: u32,
+        panic: bool,
+        runs: Arc<AtomicU32>,
+    }
+
+    #[async_trait]
+    impl SupervisedComponent for Flaky {
+        async fn run(&mut self, shutdown: CancellationToken) -> Result<()> {
+            let run = self.runs.fetch_add(1, Ordering::SeqCst);
+            if run < self.crashes {
+                if self.panic {
+                    panic!("flaky component panicked");
+                }
+                return Err(anyhow!("flaky component failed"));
+            }
+            shutdown.cancelled().await;
+            Ok(())
+        }
+    }
+
+    /// Forwards numbers downstream (or collects them at the end of the pipe)
+    struct Stage {
+        receiver: mpsc::UnboundedReceiver<u32>,
+        sender: Option<mpsc::UnboundedSender<u32>>,
+        seen: Arc<Mutex<Vec<u32>>>,
+        stopped: Arc<Mutex<Vec<&'static str>>>,
+        name: &'static str,
+    }
+
+    impl Stage {
+        fn handle(&mut self, value: u32) {
+            self.seen.lock().push(value);
+            if let Some(ref sender) = self.sender {
+                let _ = sender.send(value);
+            }
+        }
+    }
+
+    #[async_trait]
+    impl SupervisedComponent for Stage {
+        async fn run(&mut self, shutdown: CancellationToken) -> Result<()> {
+            loop {
+                tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    value = self.receiver.recv() => match value {
+                        Some(value) => self.handle(value),
+                        None => break,
+                    },
+                }
+            }
+            while let Ok(value) = self.receiver.try_recv() {
+                self.handle(value);
+            }
+            self.stopped.lock().push(self.name);
+            Ok(())
+        }
+    }
+
+    fn fast_config() -> SupervisorConfig {
+        SupervisorConfig {
+            initial_backoff_ms: 100,
+            max_backoff_ms: 1_000,
+            max_restarts: 3,
+            drain_timeout_secs: 1,
+            heartbeat_interval_secs: 1,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_crashed_component_restarts_with_backoff() -> Result<()> {
+        let monitoring = MonitoringState::new();
+        let mut supervisor = Supervisor::new(fast_config()).with_monitoring(monitoring.clone());
+        let runs = Arc::new(AtomicU32::new(0));
+        supervisor.spawn(
+            "executor",
+            Flaky {
+                crashes: 2,
+                panic: false,
+                runs: runs.clone(),
+            },
+        );
+
+        // 100ms + 200ms of backoff before the third (healthy) run
+        tokio::time::sleep(Duration::from_millis(250)).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 3);
+
+        let status = supervisor.status("executor").unwrap();
+        assert_eq!(status.state, ComponentState::Running);
+        assert_eq!(status.restarts, 2);
+        assert!(status
+            .last_error
+            .unwrap()
+            .contains("flaky component failed"));
+        {
+            let health = monitoring.health.lock().unwrap();
+            assert_eq!(health.executor.status, "running");
+            assert_eq!(health.executor.error_count, 2);
+        }
+
+        supervisor.shutdown().await?;
+        assert_eq!(monitoring.health.lock().unwrap().executor.status, "stopped");
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_panics_are_restarted_until_limit() -> Result<()> {
+        let mut supervisor = Supervisor::new(fast_config());
+        let runs = Arc::new(AtomicU32::new(0));
+        supervisor.spawn(
+            "risk_manager",
+            Flaky {
+                crashes: u32::MAX,
+                panic: true,
+                runs: runs.clone(),
+            },
+        );
+
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        let status = supervisor.status("risk_manager").unwrap();
+        assert_eq!(status.state, ComponentState::Failed);
+        // First run plus `max_restarts` restarts
+        assert_eq!(runs.load(Ordering::SeqCst), 4);
+        assert_eq!(status.last_error.as_deref(), Some("panicked"));
+
+        supervisor.shutdown().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_upstream_first() -> Result<()> {
+        let (source_tx, source_rx) = mpsc::unbounded_channel();
+        let (sink_tx, sink_rx) = mpsc::unbounded_channel();
+        let upstream_seen = Arc::new(Mutex::new(Vec::new()));
+        let sink_seen = Arc::new(Mutex::new(Vec::new()));
+        let stopped = Arc::new(Mutex::new(Vec::new()));
+
+        let mut supervisor = Supervisor::new(fast_config());
+        supervisor.spawn(
+            "strategy_engine",
+            Stage {
+                receiver: source_rx,
+                sender: Some(sink_tx),
+                seen: upstream_seen.clone(),
+                stopped: stopped.clone(),
+                name: "strategy_engine",
+            },
+        );
+        supervisor.spawn(
+            "persistence",
+            Stage {
+                receiver: sink_rx,
+                sender: None,
+                seen: sink_seen.clone(),
+                stopped: stopped.clone(),
+                name: "persistence",
+            },
+        );
+
+        for value in 0..1_000 {
+            source_tx.send(value)?;
+        }
+        supervisor.shutdown().await?;
+
+        // Everything the upstream stage accepted reached the last stage
+        assert_eq!(*sink_seen.lock(), *upstream_seen.lock());
+        assert_eq!(*stopped.lock(), vec!["strategy_engine", "persistence"]);
+        Ok(())
+    }
+}
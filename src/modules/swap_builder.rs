@@ -0,0 +1,1319 @@
+//! Swap Transaction Builder
+//!
+//! Assembles signed v0 swap transactions for Raydium AMM v4 pools:
+//! compute budget with priority fee, idempotent associated token account
+//! creation, wSOL wrap/unwrap and the `SwapBaseIn` instruction. Pool state is
+//! read through [`AccountSource`], so the whole path can run against recorded
+//! account data without a live cluster.
+
+use crate::modules::executor::FeeBreakdown;
+use crate::modules::kill_switch::{KillSwitch, KillTarget};
+use crate::modules::order_manager::{OrderResubmitter, Submission, TrackedOrder};
+use crate::modules::rpc_failover::RpcFailoverClient;
+use crate::modules::strategy::TradeAction;
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    hash::Hash,
+    instruction::{AccountMeta, Instruction},
+    message::{v0, VersionedMessage},
+    pubkey,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::VersionedTransaction,
+};
+use std::collections::HashMap;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+pub const SYSTEM_PROGRAM_ID: Pubkey = pubkey!("11111111111111111111111111111111");
+pub const COMPUTE_BUDGET_PROGRAM_ID: Pubkey =
+    pubkey!("ComputeBudget111111111111111111111111111111");
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+pub const WSOL_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+pub const RAYDIUM_AMM_V4_PROGRAM_ID: Pubkey =
+    pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+
+/// Raydium AMM v4 `AmmInfo` account size and field offsets
+pub const AMM_INFO_LEN: usize = 752;
+const AMM_NONCE: usize = 8;
+const AMM_BASE_DECIMALS: usize = 32;
+const AMM_QUOTE_DECIMALS: usize = 40;
+const AMM_SWAP_FEE_NUMERATOR: usize = 176;
+const AMM_SWAP_FEE_DENOMINATOR: usize = 184;
+const AMM_BASE_NEED_TAKE_PNL: usize = 192;
+const AMM_QUOTE_NEED_TAKE_PNL: usize = 200;
+const AMM_BASE_VAULT: usize = 336;
+const AMM_QUOTE_VAULT: usize = 368;
+const AMM_BASE_MINT: usize = 400;
+const AMM_QUOTE_MINT: usize = 432;
+const AMM_OPEN_ORDERS: usize = 496;
+const AMM_MARKET: usize = 528;
+const AMM_MARKET_PROGRAM: usize = 560;
+const AMM_TARGET_ORDERS: usize = 592;
+
+/// Serum/OpenBook v3 `MarketState` account size and field offsets
+pub const MARKET_STATE_LEN: usize = 388;
+const MARKET_VAULT_SIGNER_NONCE: usize = 45;
+const MARKET_BASE_VAULT: usize = 117;
+const MARKET_QUOTE_VAULT: usize = 165;
+const MARKET_EVENT_QUEUE: usize = 253;
+const MARKET_BIDS: usize = 285;
+const MARKET_ASKS: usize = 317;
+
+/// Serum/OpenBook v3 `OpenOrders` totals held on the order book
+const OPEN_ORDERS_BASE_TOTAL: usize = 85;
+const OPEN_ORDERS_QUOTE_TOTAL: usize = 101;
+
+/// SPL token account `amount`
+const TOKEN_ACCOUNT_AMOUNT: usize = 64;
+
+/// Raydium AMM v4 `SwapBaseIn` instruction tag
+const RAYDIUM_SWAP_BASE_IN: u8 = 9;
+
+/// Account data lookups for pool state and blockhashes
+#[async_trait]
+pub trait AccountSource: Send + Sync {
+    /// Raw account data, `None` for accounts that do not exist
+    async fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<Vec<u8>>>>;
+    /// Latest blockhash and the last block height at which it is valid
+    async fn latest_blockhash(&self) -> Result<(Hash, u64)>;
+}
+
+#[async_trait]
+impl AccountSource for RpcFailoverClient {
+    async fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<Vec<u8>>>> {
+        let keys: Vec<String> = pubkeys.iter().map(|key| key.to_string()).collect();
+        let response = self
+            .call(
+                "getMultipleAccounts",
+                serde_json::json!([keys, {"encoding": "base64", "commitment": "confirmed"}]),
+            )
+            .await?;
+
+        let values = response.result["value"]
+            .as_array()
+            .ok_or_else(|| anyhow!("Invalid getMultipleAccounts result: {}", response.result))?;
+        values
+            .iter()
+            .map(|value| -> Result<Option<Vec<u8>>> {
+                match value["data"][0].as_str() {
+                    Some(data) => Ok(Some(general_purpose::STANDARD.decode(data)?)),
+                    None => Ok(None),
+                }
+            })
+            .collect()
+    }
+
+    async fn latest_blockhash(&self) -> Result<(Hash, u64)> {
+        let response = self.get_latest_blockhash().await?;
+        let value = &response.result["value"];
+        let blockhash = value["blockhash"]
+            .as_str()
+            .ok_or_else(|| anyhow!("Invalid getLatestBlockhash result: {}", response.result))?;
+        let last_valid_block_height = value["lastValidBlockHeight"]
+            .as_u64()
+            .ok_or_else(|| anyhow!("Invalid getLatestBlockhash result: {}", response.result))?;
+        Ok((Hash::from_str(blockhash)?, last_valid_block_height))
+    }
+}
+
+/// Account snapshot (pubkey -> base64 data) for offline builds and tests
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RecordedAccounts {
+    pub accounts: HashMap<String, String>,
+    pub blockhash: String,
+    pub last_valid_block_height: u64,
+}
+
+impl RecordedAccounts {
+    pub fn new(blockhash: Hash, last_valid_block_height: u64) -> Self {
+        Self {
+            accounts: HashMap::new(),
+            blockhash: blockhash.to_string(),
+            last_valid_block_height,
+        }
+    }
+
+    /// Snapshot the given accounts and current blockhash from a live source
+    pub async fn record(source: &dyn AccountSource, pubkeys: &[Pubkey]) -> Result<Self> {
+        let (blockhash, last_valid_block_height) = source.latest_blockhash().await?;
+        let mut recorded = Self::new(blockhash, last_valid_block_height);
+        for (pubkey, data) in pubkeys
+            .iter()
+            .zip(source.get_multiple_accounts(pubkeys).await?)
+        {
+            if let Some(data) = data {
+                recorded.insert(*pubkey, &data);
+            }
+        }
+        info!("📼 Recorded {} account(s)", recorded.accounts.len());
+        Ok(recorded)
+    }
+
+    pub fn insert(&mut self, pubkey: Pubkey, data: &[u8]) {
+        self.accounts
+            .insert(pubkey.to_string(), general_purpose::STANDARD.encode(data));
+    }
+
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let content = std::fs::read_to_string(path.as_ref()).with_context(|| {
+            format!(
+                "Failed to read account snapshot {}",
+                path.as_ref().display()
+            )
+        })?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AccountSource for RecordedAccounts {
+    async fn get_multiple_accounts(&self, pubkeys: &[Pubkey]) -> Result<Vec<Option<Vec<u8>>>> {
+        pubkeys
+            .iter()
+            .map(|pubkey| {
+                self.accounts
+                    .get(&pubkey.to_string())
+                    .map(|data| general_purpose::STANDARD.decode(data))
+                    .transpose()
+                    .map_err(anyhow::Error::from)
+            })
+            .collect()
+    }
+
+    async fn latest_blockhash(&self) -> Result<(Hash, u64)> {
+        Ok((
+            Hash::from_str(&self.blockhash)?,
+            self.last_valid_block_height,
+        ))
+    }
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+    data.get(offset..offset + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or_else(|| anyhow!("Account data too short for u64 at {}", offset))
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Result<Pubkey> {
+    data.get(offset..offset + 32)
+        .map(|bytes| Pubkey::new_from_array(bytes.try_into().unwrap()))
+        .ok_or_else(|| anyhow!("Account data too short for pubkey at {}", offset))
+}
+
+/// Every account the Raydium AMM v4 swap instruction touches
+#[derive(Debug, Clone, PartialEq)]
+pub struct RaydiumPoolKeys {
+    pub amm_id: Pubkey,
+    pub amm_authority: Pubkey,
+    pub open_orders: Pubkey,
+    pub target_orders: Pubkey,
+    pub base_vault: Pubkey,
+    pub quote_vault: Pubkey,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub market_program: Pubkey,
+    pub market: Pubkey,
+    pub market_bids: Pubkey,
+    pub market_asks: Pubkey,
+    pub market_event_queue: Pubkey,
+    pub market_base_vault: Pubkey,
+    pub market_quote_vault: Pubkey,
+    pub market_vault_signer: Pubkey,
+}
+
+impl RaydiumPoolKeys {
+    /// Decode pool keys from the AMM and market account data
+    pub fn from_account_data(amm_id: Pubkey, amm: &[u8], market: &[u8]) -> Result<Self> {
+        if amm.len() != AMM_INFO_LEN {
+            return Err(anyhow!(
+                "AMM account {} has {} bytes, expected {}",
+                amm_id,
+                amm.len(),
+                AMM_INFO_LEN
+            ));
+        }
+        if market.len() < MARKET_STATE_LEN {
+            return Err(anyhow!(
+                "Market account has {} bytes, expected at least {}",
+                market.len(),
+                MARKET_STATE_LEN
+            ));
+        }
+
+        let nonce = read_u64(amm, AMM_NONCE)?;
+        let amm_authority = Pubkey::create_program_address(
+            &[b"amm authority", &[nonce as u8]],
+            &RAYDIUM_AMM_V4_PROGRAM_ID,
+        )
+        .map_err(|e| anyhow!("Invalid AMM authority nonce {}: {}", nonce, e))?;
+
+        let market_id = read_pubkey(amm, AMM_MARKET)?;
+        let market_program = read_pubkey(amm, AMM_MARKET_PROGRAM)?;
+        let vault_signer_nonce = read_u64(market, MARKET_VAULT_SIGNER_NONCE)?;
+        let market_vault_signer = Pubkey::create_program_address(
+            &[market_id.as_ref(), &vault_signer_nonce.to_le_bytes()],
+            &market_program,
+        )
+        .map_err(|e| anyhow!("Invalid vault signer nonce {}: {}", vault_signer_nonce, e))?;
+
+        Ok(Self {
+            amm_id,
+            amm_authority,
+            open_orders: read_pubkey(amm, AMM_OPEN_ORDERS)?,
+            target_orders: read_pubkey(amm, AMM_TARGET_ORDERS)?,
+            base_vault: read_pubkey(amm, AMM_BASE_VAULT)?,
+            quote_vault: read_pubkey(amm, AMM_QUOTE_VAULT)?,
+            base_mint: read_pubkey(amm, AMM_BASE_MINT)?,
+            quote_mint: read_pubkey(amm, AMM_QUOTE_MINT)?,
+            market_program,
+            market: market_id,
+            market_bids: read_pubkey(market, MARKET_BIDS)?,
+            market_asks: read_pubkey(market, MARKET_ASKS)?,
+            market_event_queue: read_pubkey(market, MARKET_EVENT_QUEUE)?,
+            market_base_vault: read_pubkey(market, MARKET_BASE_VAULT)?,
+            market_quote_vault: read_pubkey(market, MARKET_QUOTE_VAULT)?,
+            market_vault_signer,
+        })
+    }
+}
+
+/// Pool keys plus the reserves and fees needed to quote a swap
+#[derive(Debug, Clone)]
+pub struct RaydiumPool {
+    pub keys: RaydiumPoolKeys,
+    pub base_decimals: u8,
+    pub quote_decimals: u8,
+    /// Vault + open orders balance minus pending PnL, in base atoms
+    pub base_reserve: u64,
+    /// Vault + open orders balance minus pending PnL, in quote atoms
+    pub quote_reserve: u64,
+    pub swap_fee_numerator: u64,
+    pub swap_fee_denominator: u64,
+}
+
+impl RaydiumPool {
+    /// Build a pool from the AMM, market, vault and open orders account data
+    pub fn from_account_data(
+        amm_id: Pubkey,
+        amm: &[u8],
+        market: &[u8],
+        base_vault: &[u8],
+        quote_vault: &[u8],
+        open_orders: &[u8],
+    ) -> Result<Self> {
+        let keys = RaydiumPoolKeys::from_account_data(amm_id, amm, market)?;
+
+        let base_total = read_u64(base_vault, TOKEN_ACCOUNT_AMOUNT)?
+            + read_u64(open_orders, OPEN_ORDERS_BASE_TOTAL)?;
+        let quote_total = read_u64(quote_vault, TOKEN_ACCOUNT_AMOUNT)?
+            + read_u64(open_orders, OPEN_ORDERS_QUOTE_TOTAL)?;
+
+        Ok(Self {
+            keys,
+            base_decimals: read_u64(amm, AMM_BASE_DECIMALS)? as u8,
+            quote_decimals: read_u64(amm, AMM_QUOTE_DECIMALS)? as u8,
+            base_reserve: base_total.saturating_sub(read_u64(amm, AMM_BASE_NEED_TAKE_PNL)?),
+            quote_reserve: quote_total.saturating_sub(read_u64(amm, AMM_QUOTE_NEED_TAKE_PNL)?),
+            swap_fee_numerator: read_u64(amm, AMM_SWAP_FEE_NUMERATOR)?,
+            swap_fee_denominator: read_u64(amm, AMM_SWAP_FEE_DENOMINATOR)?,
+        })
+    }
+
+    /// Output mint for a swap paying `input_mint` into this pool
+    pub fn output_mint(&self, input_mint: &Pubkey) -> Result<Pubkey> {
+        if *input_mint == self.keys.base_mint {
+            Ok(self.keys.quote_mint)
+        } else if *input_mint == self.keys.quote_mint {
+            Ok(self.keys.base_mint)
+        } else {
+            Err(anyhow!(
+                "Mint {} is not traded by pool {}",
+                input_mint,
+                self.keys.amm_id
+            ))
+        }
+    }
+
+    /// Constant-product quote with the pool swap fee taken from the input
+    pub fn quote(
+        &self,
+        input_mint: &Pubkey,
+        amount_in: u64,
+        slippage_bps: u16,
+    ) -> Result<SwapQuote> {
+        let output_mint = self.output_mint(input_mint)?;
+        let (reserve_in, reserve_out) = if *input_mint == self.keys.base_mint {
+            (self.base_reserve, self.quote_reserve)
+        } else {
+            (self.quote_reserve, self.base_reserve)
+        };
+        if reserve_in == 0 || reserve_out == 0 || self.swap_fee_denominator == 0 {
+            return Err(anyhow!("Pool {} has no liquidity", self.keys.amm_id));
+        }
+
+        // Raydium rounds the fee up
+        let fee = (amount_in as u128 * self.swap_fee_numerator as u128)
+            .div_ceil(self.swap_fee_denominator as u128) as u64;
+        let amount_in_after_fee = amount_in.saturating_sub(fee) as u128;
+        let expected_out = (reserve_out as u128 * amount_in_after_fee
+            / (reserve_in as u128 + amount_in_after_fee)) as u64;
+        let minimum_out =
+            (expected_out as u128 * (10_000 - slippage_bps.min(10_000)) as u128 / 10_000) as u64;
+
+        // Execution price vs. spot price, fee excluded
+        let spot_out = reserve_out as f64 * amount_in_after_fee as f64 / reserve_in as f64;
+        let price_impact_bps = if spot_out > 0.0 {
+            (1.0 - expected_out as f64 / spot_out) * 10_000.0
+        } else {
+            0.0
+        };
+
+        Ok(SwapQuote {
+            input_mint: *input_mint,
+            output_mint,
+            amount_in,
+            expected_out,
+            minimum_out,
+            fee,
+            price_impact_bps,
+        })
+    }
+}
+
+/// Expected result of a swap against the current reserves (amounts in atoms)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SwapQuote {
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub expected_out: u64,
+    pub minimum_out: u64,
+    /// Pool fee, in input atoms
+    pub fee: u64,
+    pub price_impact_bps: f64,
+}
+
+/// `ComputeBudgetInstruction::SetComputeUnitLimit`
+pub fn set_compute_unit_limit(units: u32) -> Instruction {
+    let mut data = vec![2];
+    data.extend_from_slice(&units.to_le_bytes());
+    Instruction {
+        program_id: COMPUTE_BUDGET_PROGRAM_ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+/// `ComputeBudgetInstruction::SetComputeUnitPrice` (priority fee)
+pub fn set_compute_unit_price(micro_lamports: u64) -> Instruction {
+    let mut data = vec![3];
+    data.extend_from_slice(&micro_lamports.to_le_bytes());
+    Instruction {
+        program_id: COMPUTE_BUDGET_PROGRAM_ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+/// Associated token account address for `owner` and `mint`
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), TOKEN_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// `AssociatedTokenAccountInstruction::CreateIdempotent`
+pub fn create_associated_token_account_idempotent(
+    payer: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: ASSOCIATED_TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(associated_token_address(owner, mint), false),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        ],
+        data: vec![1],
+    }
+}
+
+/// `SystemInstruction::Transfer`
+pub fn system_transfer(from: &Pubkey, to: &Pubkey, lamports: u64) -> Instruction {
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend_from_slice(&lamports.to_le_bytes());
+    Instruction {
+        program_id: SYSTEM_PROGRAM_ID,
+        accounts: vec![AccountMeta::new(*from, true), AccountMeta::new(*to, false)],
+        data,
+    }
+}
+
+/// `TokenInstruction::SyncNative` (credit lamports sent to a wSOL account)
+pub fn sync_native(account: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: TOKEN_PROGRAM_ID,
+        accounts: vec![AccountMeta::new(*account, false)],
+        data: vec![17],
+    }
+}
+
+/// `TokenInstruction::CloseAccount` (unwraps wSOL back to the owner)
+pub fn close_token_account(account: &Pubkey, destination: &Pubkey, owner: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*account, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        data: vec![9],
+    }
+}
+
+/// Raydium AMM v4 `SwapBaseIn` (exact input, minimum output)
+pub fn raydium_swap_base_in(
+    keys: &RaydiumPoolKeys,
+    user_source: &Pubkey,
+    user_destination: &Pubkey,
+    owner: &Pubkey,
+    amount_in: u64,
+    minimum_amount_out: u64,
+) -> Instruction {
+    let mut data = vec![RAYDIUM_SWAP_BASE_IN];
+    data.extend_from_slice(&amount_in.to_le_bytes());
+    data.extend_from_slice(&minimum_amount_out.to_le_bytes());
+
+    Instruction {
+        program_id: RAYDIUM_AMM_V4_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new(keys.amm_id, false),
+            AccountMeta::new_readonly(keys.amm_authority, false),
+            AccountMeta::new(keys.open_orders, false),
+            AccountMeta::new(keys.target_orders, false),
+            AccountMeta::new(keys.base_vault, false),
+            AccountMeta::new(keys.quote_vault, false),
+            AccountMeta::new_readonly(keys.market_program, false),
+            AccountMeta::new(keys.market, false),
+            AccountMeta::new(keys.market_bids, false),
+            AccountMeta::new(keys.market_asks, false),
+            AccountMeta::new(keys.market_event_queue, false),
+            AccountMeta::new(keys.market_base_vault, false),
+            AccountMeta::new(keys.market_quote_vault, false),
+            AccountMeta::new_readonly(keys.market_vault_signer, false),
+            AccountMeta::new(*user_source, false),
+            AccountMeta::new(*user_destination, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        data,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwapBuilderConfig {
+    pub compute_unit_limit: u32,
+    /// Priority fee per compute unit
+    pub priority_fee_micro_lamports: u64,
+    pub default_slippage_bps: u16,
+    /// Close the wSOL account after the swap, returning SOL to the wallet
+    pub unwrap_sol: bool,
+}
+
+impl Default for SwapBuilderConfig {
+    fn default() -> Self {
+        Self {
+            compute_unit_limit: 200_000,
+            priority_fee_micro_lamports: 50_000,
+            default_slippage_bps: 100,
+            unwrap_sol: true,
+        }
+    }
+}
+
+impl SwapBuilderConfig {
+    /// Priority fee paid on top of the base signature fee, in lamports
+    pub fn priority_fee_lamports(&self) -> u64 {
+        (self.compute_unit_limit as u128 * self.priority_fee_micro_lamports as u128)
+            .div_ceil(1_000_000) as u64
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SwapRequest {
+    /// Raydium AMM v4 pool id
+    pub pool: Pubkey,
+    pub input_mint: Pubkey,
+    /// Exact input amount in atoms
+    pub amount_in: u64,
+    /// Overrides `SwapBuilderConfig::default_slippage_bps`
+    pub slippage_bps: Option<u16>,
+}
+
+/// A signed swap ready to be sent
+#[derive(Debug, Clone)]
+pub struct BuiltSwap {
+    pub transaction: VersionedTransaction,
+    pub signature: Signature,
+    pub quote: SwapQuote,
+    pub last_valid_block_height: u64,
+}
+
+impl BuiltSwap {
+    /// Wire format for `sendTransaction` with base64 encoding
+    pub fn serialize_base64(&self) -> Result<String> {
+        Ok(general_purpose::STANDARD.encode(bincode::serialize(&self.transaction)?))
+    }
+}
+
+/// Builds Raydium AMM v4 swaps from live or recorded account data
+pub struct SwapTransactionBuilder {
+    source: Arc<dyn AccountSource>,
+    config: SwapBuilderConfig,
+}
+
+impl SwapTransactionBuilder {
+    pub fn new(source: Arc<dyn AccountSource>) -> Self {
+        Self {
+            source,
+            config: SwapBuilderConfig::default(),
+        }
+    }
+
+    pub fn with_config(mut self, config: SwapBuilderConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn config(&self) -> &SwapBuilderConfig {
+        &self.config
+    }
+
+    /// Fetch and decode pool keys, reserves and fees
+    pub async fn load_pool(&self, amm_id: &Pubkey) -> Result<RaydiumPool> {
+        let amm = self
+            .source
+            .get_multiple_accounts(&[*amm_id])
+            .await?
+            .pop()
+            .flatten()
+            .ok_or_else(|| anyhow!("AMM account {} not found", amm_id))?;
+
+        let dependent = [
+            read_pubkey(&amm, AMM_MARKET)?,
+            read_pubkey(&amm, AMM_BASE_VAULT)?,
+            read_pubkey(&amm, AMM_QUOTE_VAULT)?,
+            read_pubkey(&amm, AMM_OPEN_ORDERS)?,
+        ];
+        let accounts = self.source.get_multiple_accounts(&dependent).await?;
+        let mut accounts = dependent
+            .iter()
+            .zip(accounts)
+            .map(|(pubkey, data)| data.ok_or_else(|| anyhow!("Pool account {} not found", pubkey)));
+        let market = accounts.next().unwrap()?;
+        let base_vault = accounts.next().unwrap()?;
+        let quote_vault = accounts.next().unwrap()?;
+        let open_orders = accounts.next().unwrap()?;
+
+        let pool = RaydiumPool::from_account_data(
+            *amm_id,
+            &amm,
+            &market,
+            &base_vault,
+            &quote_vault,
+            &open_orders,
+        )?;
+        debug!(
+            "🏊 Loaded Raydium pool {}: {} base / {} quote",
+            amm_id, pool.base_reserve, pool.quote_reserve
+        );
+        Ok(pool)
+    }
+
+    /// Instruction list for a swap: compute budget, token accounts, wSOL
+    /// wrap, swap and (optionally) wSOL unwrap
+    pub fn swap_instructions(
+        &self,
+        pool: &RaydiumPool,
+        owner: &Pubkey,
+        quote: &SwapQuote,
+    ) -> Vec<Instruction> {
+        let source = associated_token_address(owner, &quote.input_mint);
+        let destination = associated_token_address(owner, &quote.output_mint);
+
+        let mut instructions = vec![
+            set_compute_unit_limit(self.config.compute_unit_limit),
+            set_compute_unit_price(self.config.priority_fee_micro_lamports),
+            create_associated_token_account_idempotent(owner, owner, &quote.input_mint),
+            create_associated_token_account_idempotent(owner, owner, &quote.output_mint),
+        ];
+
+        if quote.input_mint == WSOL_MINT {
+            instructions.push(system_transfer(owner, &source, quote.amount_in));
+            instructions.push(sync_native(&source));
+        }
+
+        instructions.push(raydium_swap_base_in(
+            &pool.keys,
+            &source,
+            &destination,
+            owner,
+            quote.amount_in,
+            quote.minimum_out,
+        ));
+
+        if self.config.unwrap_sol {
+            let wsol_account = if quote.input_mint == WSOL_MINT {
+                Some(source)
+            } else if quote.output_mint == WSOL_MINT {
+                Some(destination)
+            } else {
+                None
+            };
+            if let Some(account) = wsol_account {
+                instructions.push(close_token_account(&account, owner, owner));
+            }
+        }
+
+        instructions
+    }
+
+    /// Latest blockhash from the account source
+    pub async fn latest_blockhash(&self) -> Result<(Hash, u64)> {
+        self.source.latest_blockhash().await
+    }
+
+    /// Quote, assemble and sign a v0 swap transaction
+    pub async fn build_swap(&self, request: &SwapRequest, payer: &Keypair) -> Result<BuiltSwap> {
+        let pool = self.load_pool(&request.pool).await?;
+        self.build_swap_with_pool(&pool, request, payer).await
+    }
+
+    /// Same as [`Self::build_swap`] for an already loaded pool
+    pub async fn build_swap_with_pool(
+        &self,
+        pool: &RaydiumPool,
+        request: &SwapRequest,
+        payer: &Keypair,
+    ) -> Result<BuiltSwap> {
+        let slippage_bps = request
+            .slippage_bps
+            .unwrap_or(self.config.default_slippage_bps);
+        let quote = pool.quote(&request.input_mint, request.amount_in, slippage_bps)?;
+        if quote.minimum_out == 0 {
+            return Err(anyhow!(
+                "Swap of {} atoms on {} rounds to zero output",
+                request.amount_in,
+                request.pool
+            ));
+        }
+
+        let instructions = self.swap_instructions(pool, &payer.pubkey(), &quote);
+        let (blockhash, last_valid_block_height) = self.source.latest_blockhash().await?;
+
+        let message = v0::Message::try_compile(&payer.pubkey(), &instructions, &[], blockhash)
+            .map_err(|e| anyhow!("Failed to compile v0 message: {}", e))?;
+        let transaction = VersionedTransaction::try_new(VersionedMessage::V0(message), &[payer])
+            .map_err(|e| anyhow!("Failed to sign swap transaction: {}", e))?;
+        let signature = transaction.signatures[0];
+
+        info!(
+            "🔨 Built Raydium swap {}: {} {} -> ≥{} {} (impact {:.1} bps)",
+            signature,
+            quote.amount_in,
+            quote.input_mint,
+            quote.minimum_out,
+            quote.output_mint,
+            quote.price_impact_bps
+        );
+
+        Ok(BuiltSwap {
+            transaction,
+            signature,
+            quote,
+            last_valid_block_height,
+        })
+    }
+}
+
+/// Sends signed transactions, returning the signature reported by the node
+#[async_trait]
+pub trait TransactionSender: Send + Sync {
+    async fn send_transaction(&self, transaction_base64: &str) -> Result<String>;
+}
+
+#[async_trait]
+impl TransactionSender for RpcFailoverClient {
+    async fn send_transaction(&self, transaction_base64: &str) -> Result<String> {
+        let response = RpcFailoverClient::send_transaction(self, transaction_base64).await?;
+        response
+            .result
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("Invalid sendTransaction result: {}", response.result))
+    }
+}
+
+/// A built swap translated back into trade units (quantity in base tokens,
+/// price and fees in SOL)
+#[derive(Debug, Clone)]
+pub struct LiveSwap {
+    pub built: BuiltSwap,
+    pub quantity: f64,
+    pub price: f64,
+    pub fees: FeeBreakdown,
+    /// Set by `execute` when every send attempt errored; the transaction may
+    /// still have landed, so it must be tracked by signature, not rebuilt
+    pub send_error: Option<String>,
+}
+
+/// Everything the live executor needs to turn a trade into a signed swap.
+/// Pools must be SOL-quoted (Raydium base = token, quote = wSOL).
+pub struct LiveSwapContext {
+    pub builder: Arc<SwapTransactionBuilder>,
+    pub wallet: Arc<Keypair>,
+    pub sender: Arc<dyn TransactionSender>,
+    /// Trading symbol -> Raydium AMM v4 pool id
+    pub pools: HashMap<String, Pubkey>,
+    /// Checked before every swap is built and signed, resubmissions included
+    pub kill_switch: Option<Arc<KillSwitch>>,
+}
+
+impl LiveSwapContext {
+    /// Network fee per signature, in lamports
+    const SIGNATURE_FEE_LAMPORTS: u64 = 5_000;
+
+    /// Sends of the same signed bytes before giving up on the RPC
+    const SEND_ATTEMPTS: u32 = 3;
+
+    /// Read a JSON map of trading symbol -> Raydium AMM v4 pool id
+    pub fn load_pools<P: AsRef<Path>>(path: P) -> Result<HashMap<String, Pubkey>> {
+        let content = std::fs::read_to_string(path.as_ref())
+            .with_context(|| format!("Failed to read live pools {}", path.as_ref().display()))?;
+        let pools: HashMap<String, String> = serde_json::from_str(&content)?;
+        pools
+            .into_iter()
+            .map(|(symbol, pool)| {
+                let pool = Pubkey::from_str(&pool)
+                    .with_context(|| format!("Invalid pool id for {}: {}", symbol, pool))?;
+                Ok((symbol, pool))
+            })
+            .collect()
+    }
+
+    /// Signing wallet's public key, as used for wallet-scoped kill switches
+    pub fn wallet_id(&self) -> String {
+        self.wallet.pubkey().to_string()
+    }
+
+    /// Build a swap buying (SOL in) or selling (token in) `quantity` tokens
+    pub async fn build_for_trade(
+        &self,
+        symbol: &str,
+        action: &TradeAction,
+        quantity: f64,
+        price: f64,
+    ) -> Result<LiveSwap> {
+        if let Some(ref kill_switch) = self.kill_switch {
+            kill_switch.check(&KillTarget::token(symbol).with_wallet(Some(self.wallet_id())))?;
+        }
+
+        let pool_id = self
+            .pools
+            .get(symbol)
+            .ok_or_else(|| anyhow!("No Raydium pool configured for {}", symbol))?;
+        let pool = self.builder.load_pool(pool_id).await?;
+        if pool.keys.quote_mint != WSOL_MINT {
+            return Err(anyhow!("Pool {} for {} is not SOL-quoted", pool_id, symbol));
+        }
+
+        let base_scale = 10f64.powi(pool.base_decimals as i32);
+        let quote_scale = 10f64.powi(pool.quote_decimals as i32);
+        let (input_mint, amount_in) = match action {
+            TradeAction::Buy | TradeAction::MarketBuy => (
+                pool.keys.quote_mint,
+                (quantity * price * quote_scale) as u64,
+            ),
+            TradeAction::Sell | TradeAction::MarketSell => {
+                (pool.keys.base_mint, (quantity * base_scale) as u64)
+            }
+            TradeAction::Hold => return Err(anyhow!("Hold signals do not trade")),
+        };
+
+        let request = SwapRequest {
+            pool: *pool_id,
+            input_mint,
+            amount_in,
+            slippage_bps: None,
+        };
+        let built = self
+            .builder
+            .build_swap_with_pool(&pool, &request, &self.wallet)
+            .await?;
+
+        // Expected fill from the quote; the order manager confirms it on-chain
+        let quote = &built.quote;
+        let (base_atoms, quote_atoms, dex_fee) = if input_mint == pool.keys.base_mint {
+            let fee_in_quote = quote.fee as f64 * quote.expected_out as f64 / amount_in as f64;
+            (amount_in, quote.expected_out, fee_in_quote)
+        } else {
+            (quote.expected_out, amount_in, quote.fee as f64)
+        };
+        let lamports_per_sol = 10f64.powi(9);
+
+        Ok(LiveSwap {
+            quantity: base_atoms as f64 / base_scale,
+            price: (quote_atoms as f64 / quote_scale) / (base_atoms as f64 / base_scale),
+            fees: FeeBreakdown {
+                dex_fee: dex_fee / quote_scale,
+                network_fee: Self::SIGNATURE_FEE_LAMPORTS as f64 / lamports_per_sol,
+                priority_fee: self.builder.config().priority_fee_lamports() as f64
+                    / lamports_per_sol,
+                ..Default::default()
+            },
+            built,
+            send_error: None,
+        })
+    }
+
+    /// Build, sign and send; returns the swap and its signature.
+    ///
+    /// Errors only come from building, before anything is sent. An RPC error
+    /// on send does not mean the transaction failed, so the same signed bytes
+    /// are re-sent (the cluster deduplicates by signature) and, if every
+    /// attempt errors, the swap is still returned with `send_error` set for
+    /// the caller to poll its signature until the blockhash expires.
+    pub async fn execute(
+        &self,
+        symbol: &str,
+        action: &TradeAction,
+        quantity: f64,
+        price: f64,
+    ) -> Result<(LiveSwap, String)> {
+        let mut swap = self
+            .build_for_trade(symbol, action, quantity, price)
+            .await?;
+        let signature = swap.built.signature.to_string();
+        let wire = swap.built.serialize_base64()?;
+
+        let mut backoff_ms = 100;
+        for attempt in 1..=Self::SEND_ATTEMPTS {
+            match self.sender.send_transaction(&wire).await {
+                Ok(reported) => {
+                    if reported != signature {
+                        warn!("⚠️ Node reported {} for swap {}", reported, signature);
+                    }
+                    info!("📤 Sent {:?} {} swap: {}", action, symbol, signature);
+                    swap.send_error = None;
+                    return Ok((swap, signature));
+                }
+                Err(e) => {
+                    warn!(
+                        "⚠️ Send of {} failed (attempt {}/{}): {}",
+                        signature,
+                        attempt,
+                        Self::SEND_ATTEMPTS,
+                        e
+                    );
+                    swap.send_error = Some(e.to_string());
+                    if attempt < Self::SEND_ATTEMPTS {
+                        tokio::time::sleep(std::time::Duration::from_millis(backoff_ms)).await;
+                        backoff_ms *= 2;
+                    }
+                }
+            }
+        }
+
+        warn!(
+            "📤 {:?} {} swap {} unacknowledged; tracking it until its blockhash expires",
+            action, symbol, signature
+        );
+        Ok((swap, signature))
+    }
+}
+
+/// Resubmission rebuilds the swap with a fresh blockhash and current reserves;
+/// the order manager only asks once the previous blockhash has expired
+#[async_trait]
+impl OrderResubmitter for LiveSwapContext {
+    async fn resubmit(&self, order: &TrackedOrder) -> Result<Submission> {
+        let action = order
+            .result
+            .action
+            .as_ref()
+            .ok_or_else(|| anyhow!("Order {} has no trade action", order.order_id))?;
+        let (swap, signature) = self
+            .execute(
+                &order.result.symbol,
+                action,
+                order.result.executed_quantity,
+                order.result.executed_price,
+            )
+            .await?;
+        Ok(Submission {
+            signature,
+            last_valid_block_height: Some(swap.built.last_valid_block_height),
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    fn write_u64(data: &mut [u8], offset: usize, value: u64) {
+        data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn write_pubkey(data: &mut [u8], offset: usize, pubkey: &Pubkey) {
+        data[offset..offset + 32].copy_from_slice(pubkey.as_ref());
+    }
+
+    fn token_account(mint: &Pubkey, amount: u64) -> Vec<u8> {
+        let mut data = vec![0u8; 165];
+        write_pubkey(&mut data, 0, mint);
+        write_u64(&mut data, TOKEN_ACCOUNT_AMOUNT, amount);
+        data
+    }
+
+    /// First nonce that yields an off-curve program address
+    fn valid_nonce(seeds: impl Fn(u64) -> Option<Pubkey>) -> u64 {
+        (0..=255)
+            .rev()
+            .find(|nonce| seeds(*nonce).is_some())
+            .unwrap()
+    }
+
+    /// Layout-correct SOL/token pool snapshot: 100 SOL vs 1,000,000 tokens,
+    /// 0.25% swap fee
+    pub(crate) fn recorded_pool(token_mint: Pubkey) -> (Pubkey, RecordedAccounts) {
+        let amm_id = Pubkey::new_unique();
+        let market_id = Pubkey::new_unique();
+        let market_program = Pubkey::new_unique();
+        let base_vault = Pubkey::new_unique();
+        let quote_vault = Pubkey::new_unique();
+        let open_orders = Pubkey::new_unique();
+
+        let mut amm = vec![0u8; AMM_INFO_LEN];
+        let amm_nonce = valid_nonce(|nonce| {
+            Pubkey::create_program_address(
+                &[b"amm authority", &[nonce as u8]],
+                &RAYDIUM_AMM_V4_PROGRAM_ID,
+            )
+            .ok()
+        });
+        write_u64(&mut amm, AMM_NONCE, amm_nonce);
+        write_u64(&mut amm, AMM_BASE_DECIMALS, 6);
+        write_u64(&mut amm, AMM_QUOTE_DECIMALS, 9);
+        write_u64(&mut amm, AMM_SWAP_FEE_NUMERATOR, 25);
+        write_u64(&mut amm, AMM_SWAP_FEE_DENOMINATOR, 10_000);
+        write_pubkey(&mut amm, AMM_BASE_VAULT, &base_vault);
+        write_pubkey(&mut amm, AMM_QUOTE_VAULT, &quote_vault);
+        write_pubkey(&mut amm, AMM_BASE_MINT, &token_mint);
+        write_pubkey(&mut amm, AMM_QUOTE_MINT, &WSOL_MINT);
+        write_pubkey(&mut amm, AMM_OPEN_ORDERS, &open_orders);
+        write_pubkey(&mut amm, AMM_MARKET, &market_id);
+        write_pubkey(&mut amm, AMM_MARKET_PROGRAM, &market_program);
+        write_pubkey(&mut amm, AMM_TARGET_ORDERS, &Pubkey::new_unique());
+
+        let mut market = vec![0u8; MARKET_STATE_LEN];
+        let signer_nonce = valid_nonce(|nonce| {
+            Pubkey::create_program_address(
+                &[market_id.as_ref(), &nonce.to_le_bytes()],
+                &market_program,
+            )
+            .ok()
+        });
+        write_u64(&mut market, MARKET_VAULT_SIGNER_NONCE, signer_nonce);
+        for offset in [
+            MARKET_BASE_VAULT,
+            MARKET_QUOTE_VAULT,
+            MARKET_EVENT_QUEUE,
+            MARKET_BIDS,
+            MARKET_ASKS,
+        ] {
+            write_pubkey(&mut market, offset, &Pubkey::new_unique());
+        }
+
+        let mut recorded = RecordedAccounts::new(Hash::new_unique(), 1_000);
+        recorded.insert(amm_id, &amm);
+        recorded.insert(market_id, &market);
+        recorded.insert(base_vault, &token_account(&token_mint, 1_000_000_000_000));
+        recorded.insert(quote_vault, &token_account(&WSOL_MINT, 100_000_000_000));
+        recorded.insert(open_orders, &[0u8; 3228]);
+        (amm_id, recorded)
+    }
+
+    #[test]
+    fn test_instruction_encoding() {
+        assert_eq!(
+            set_compute_unit_limit(200_000).data,
+            vec![2, 0x40, 0x0d, 0x03, 0]
+        );
+        assert_eq!(
+            set_compute_unit_price(1).data,
+            vec![3, 1, 0, 0, 0, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            system_transfer(&Pubkey::new_unique(), &Pubkey::new_unique(), 5).data,
+            vec![2, 0, 0, 0, 5, 0, 0, 0, 0, 0, 0, 0]
+        );
+
+        let owner = Pubkey::new_unique();
+        let create = create_associated_token_account_idempotent(&owner, &owner, &WSOL_MINT);
+        assert_eq!(create.data, vec![1]);
+        assert_eq!(
+            create.accounts[1].pubkey,
+            associated_token_address(&owner, &WSOL_MINT)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_pool_decoding_and_quote() -> Result<()> {
+        let token_mint = Pubkey::new_unique();
+        let (amm_id, recorded) = recorded_pool(token_mint);
+        let builder = SwapTransactionBuilder::new(Arc::new(recorded));
+
+        let pool = builder.load_pool(&amm_id).await?;
+        assert_eq!(pool.keys.base_mint, token_mint);
+        assert_eq!(pool.keys.quote_mint, WSOL_MINT);
+        assert_eq!(pool.base_decimals, 6);
+        assert_eq!(pool.quote_reserve, 100_000_000_000);
+
+        // 1 SOL in: 0.25% fee, then x*y=k against 100 SOL / 1M tokens
+        let quote = pool.quote(&WSOL_MINT, 1_000_000_000, 100)?;
+        assert_eq!(quote.output_mint, token_mint);
+        assert_eq!(quote.fee, 2_500_000);
+        assert_eq!(quote.expected_out, 9_876_482_091);
+        assert_eq!(quote.minimum_out, 9_777_717_270);
+        assert!(quote.price_impact_bps > 90.0 && quote.price_impact_bps < 110.0);
+
+        assert!(pool.quote(&Pubkey::new_unique(), 1, 100).is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_build_v0_swap_from_recorded_accounts() -> Result<()> {
+        let token_mint = Pubkey::new_unique();
+        let (amm_id, recorded) = recorded_pool(token_mint);
+        let blockhash = Hash::from_str(&recorded.blockhash)?;
+        let builder = SwapTransactionBuilder::new(Arc::new(recorded));
+        let payer = Keypair::new();
+
+        let built = builder
+            .build_swap(
+                &SwapRequest {
+                    pool: amm_id,
+                    input_mint: WSOL_MINT,
+                    amount_in: 1_000_000_000,
+                    slippage_bps: None,
+                },
+                &payer,
+            )
+            .await?;
+
+        assert_eq!(built.last_valid_block_height, 1_000);
+        assert!(built.transaction.verify_with_results().iter().all(|ok| *ok));
+        let VersionedMessage::V0(message) = &built.transaction.message else {
+            panic!("expected a v0 message");
+        };
+        assert_eq!(message.recent_blockhash, blockhash);
+        assert_eq!(message.account_keys[0], payer.pubkey());
+
+        // limit, price, 2x ATA, wrap (transfer + sync), swap, unwrap
+        let programs: Vec<Pubkey> = message
+            .instructions
+            .iter()
+            .map(|ix| message.account_keys[ix.program_id_index as usize])
+            .collect();
+        assert_eq!(
+            programs,
+            vec![
+                COMPUTE_BUDGET_PROGRAM_ID,
+                COMPUTE_BUDGET_PROGRAM_ID,
+                ASSOCIATED_TOKEN_PROGRAM_ID,
+                ASSOCIATED_TOKEN_PROGRAM_ID,
+                SYSTEM_PROGRAM_ID,
+                TOKEN_PROGRAM_ID,
+                RAYDIUM_AMM_V4_PROGRAM_ID,
+                TOKEN_PROGRAM_ID,
+            ]
+        );
+
+        let swap = &message.instructions[6];
+        assert_eq!(swap.accounts.len(), 18);
+        assert_eq!(swap.data[0], RAYDIUM_SWAP_BASE_IN);
+        assert_eq!(swap.data[1..9], 1_000_000_000u64.to_le_bytes());
+        assert_eq!(swap.data[9..17], built.quote.minimum_out.to_le_bytes());
+        assert_eq!(
+            message.account_keys[swap.accounts[15] as usize],
+            associated_token_address(&payer.pubkey(), &WSOL_MINT)
+        );
+
+        assert!(!built.serialize_base64()?.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_recorded_accounts_round_trip() -> Result<()> {
+        let (amm_id, recorded) = recorded_pool(Pubkey::new_unique());
+        let file = tempfile::NamedTempFile::new()?;
+        recorded.save(file.path())?;
+
+        let loaded = RecordedAccounts::from_file(file.path())?;
+        let replayed = RecordedAccounts::record(&loaded, &[amm_id]).await?;
+        assert_eq!(
+            replayed.accounts.get(&amm_id.to_string()),
+            recorded.accounts.get(&amm_id.to_string())
+        );
+        assert!(loaded
+            .get_multiple_accounts(&[Pubkey::new_unique()])
+            .await?[0]
+            .is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_pools() -> Result<()> {
+        let file = tempfile::NamedTempFile::new()?;
+        std::fs::write(
+            file.path(),
+            format!(r#"{{"BONK/SOL": "{}"}}"#, RAYDIUM_AMM_V4_PROGRAM_ID),
+        )?;
+        let pools = LiveSwapContext::load_pools(file.path())?;
+        assert_eq!(pools["BONK/SOL"], RAYDIUM_AMM_V4_PROGRAM_ID);
+
+        std::fs::write(file.path(), r#"{"BONK/SOL": "not-a-pubkey"}"#)?;
+        let err = LiveSwapContext::load_pools(file.path()).unwrap_err();
+        assert!(err.to_string().contains("BONK/SOL"), "{}", err);
+        Ok(())
+    }
+
+    /// Captures sent transactions and answers with sequential signatures
+    pub(crate) struct RecordingSender(pub(crate) parking_lot::Mutex<Vec<String>>);
+
+    #[async_trait]
+    impl TransactionSender for RecordingSender {
+        async fn send_transaction(&self, transaction_base64: &str) -> Result<String> {
+            self.0.lock().push(transaction_base64.to_string());
+            Ok(format!("sig_{}", self.0.lock().len()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_live_swap_context_converts_trade_units() -> Result<()> {
+        use crate::modules::kill_switch::{KillScope, KillSource};
+
+        let token_mint = Pubkey::new_unique();
+        let (amm_id, recorded) = recorded_pool(token_mint);
+        let sender = Arc::new(RecordingSender(parking_lot::Mutex::new(Vec::new())));
+        let context = LiveSwapContext {
+            builder: Arc::new(SwapTransactionBuilder::new(Arc::new(recorded))),
+            wallet: Arc::new(Keypair::new()),
+            sender: sender.clone(),
+            pools: HashMap::from([("BONK/SOL".to_string(), amm_id)]),
+            kill_switch: None,
+        };
+
+        // 10,000 tokens at 0.0001 SOL = 1 SOL in
+        let (swap, signature) = context
+            .execute("BONK/SOL", &TradeAction::Buy, 10_000.0, 0.0001)
+            .await?;
+        assert_eq!(signature, swap.built.signature.to_string());
+        assert!(swap.send_error.is_none());
+        assert_eq!(swap.built.quote.amount_in, 1_000_000_000);
+        assert!((swap.quantity - 9_876.482091).abs() < 1e-6);
+        assert!((swap.price * swap.quantity - 1.0).abs() < 1e-9);
+        assert!((swap.fees.dex_fee - 0.0025).abs() < 1e-12);
+        assert!((swap.fees.priority_fee - 0.00001).abs() < 1e-12);
+
+        let sell = context
+            .build_for_trade("BONK/SOL", &TradeAction::Sell, 1_000.0, 0.0001)
+            .await?;
+        assert_eq!(sell.built.quote.input_mint, token_mint);
+        assert_eq!(sell.built.quote.amount_in, 1_000_000_000);
+
+        assert!(context
+            .build_for_trade("WIF/SOL", &TradeAction::Buy, 1.0, 1.0)
+            .await
+            .is_err());
+        assert_eq!(sender.0.lock().len(), 1);
+
+        // Nothing is signed for a killed wallet
+        let kill_switch = Arc::new(KillSwitch::in_memory());
+        kill_switch.engage(
+            KillScope::wallet(context.wallet_id()),
+            KillSource::Operator,
+            "key rotation",
+        )?;
+        let context = LiveSwapContext {
+            kill_switch: Some(kill_switch),
+            ..context
+        };
+        let err = context
+            .execute("BONK/SOL", &TradeAction::Buy, 10_000.0, 0.0001)
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string().starts_with("Kill switch engaged (wallet:"),
+            "{}",
+            err
+        );
+        assert_eq!(sender.0.lock().len(), 1);
+        Ok(())
+    }
+
+    /// Errors on every send, as a timing-out RPC would, but keeps the bytes
+    struct TimeoutSender(parking_lot::Mutex<Vec<String>>);
+
+    #[async_trait]
+    impl TransactionSender for TimeoutSender {
+        async fn send_transaction(&self, transaction_base64: &str) -> Result<String> {
+            self.0.lock().push(transaction_base64.to_string());
+            Err(anyhow!("RPC timeout"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_errors_resend_the_same_transaction() -> Result<()> {
+        let (amm_id, recorded) = recorded_pool(Pubkey::new_unique());
+        let sender = Arc::new(TimeoutSender(parking_lot::Mutex::new(Vec::new())));
+        let context = LiveSwapContext {
+            builder: Arc::new(SwapTransactionBuilder::new(Arc::new(recorded))),
+            wallet: Arc::new(Keypair::new()),
+            sender: sender.clone(),
+            pools: HashMap::from([("BONK/SOL".to_string(), amm_id)]),
+            kill_switch: None,
+        };
+
+        // Never rebuilt or re-signed: one signature, identical bytes each time
+        let (swap, signature) = context
+            .execute("BONK/SOL", &TradeAction::Buy, 10_000.0, 0.0001)
+            .await?;
+        assert_eq!(signature, swap.built.signature.to_string());
+        assert!(swap.send_error.unwrap().contains("RPC timeout"));
+        let sent = sender.0.lock();
+        assert_eq!(sent.len(), LiveSwapContext::SEND_ATTEMPTS as usize);
+        assert!(sent.iter().all(|wire| *wire == sent[0]));
+        Ok(())
+    }
+}
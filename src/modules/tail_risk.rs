@@ -0,0 +1,805 @@
+//! Tail Risk Measures for THE OVERMIND PROTOCOL
+//!
+//! Portfolio Value at Risk and Expected Shortfall (CVaR) computed by
+//! historical simulation, variance-covariance over the correlation matrix
+//! and Monte Carlo path generation, with marginal and component VaR per
+//! position. Used by `AdvancedRiskManager`.
+
+use anyhow::{anyhow, Result};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::modules::advanced_risk_management::{CorrelationMatrix, Position};
+
+/// (timestamp, price) samples per symbol, as kept by `AdvancedRiskManager`
+pub type PriceHistory = HashMap<String, Vec<(u64, f64)>>;
+
+/// How the loss distribution is obtained
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VarMethod {
+    /// Recorded returns replayed against today's exposures
+    #[default]
+    Historical,
+
+    /// Normal returns with volatilities from history and the correlation matrix
+    Parametric,
+
+    /// Correlated normal paths over the horizon, compounded per bar
+    MonteCarlo,
+}
+
+impl VarMethod {
+    pub const ALL: [VarMethod; 3] = [
+        VarMethod::Historical,
+        VarMethod::Parametric,
+        VarMethod::MonteCarlo,
+    ];
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VarConfig {
+    pub confidence: f64,               // VaR confidence level (0.5-1.0)
+    pub horizon_days: f64,             // Loss horizon
+    pub return_interval_secs: u64,     // Bar size prices are resampled to
+    pub min_observations: usize,       // Aligned returns needed before estimating
+    pub monte_carlo_paths: usize,      // Simulated paths per estimate
+    pub monte_carlo_seed: Option<u64>, // Fixed seed for reproducible runs
+    pub primary_method: VarMethod,     // Method reported in PortfolioMetrics
+    pub max_var: f64,                  // VaR limit as % of portfolio value
+    pub max_expected_shortfall: f64,   // ES limit as % of portfolio value
+    pub max_component_var_share: f64,  // Largest share of VaR one position may carry
+}
+
+impl Default for VarConfig {
+    fn default() -> Self {
+        Self {
+            confidence: 0.95,
+            horizon_days: 1.0,
+            return_interval_secs: 3600, // Hourly bars
+            min_observations: 30,
+            monte_carlo_paths: 10_000,
+            monte_carlo_seed: None,
+            primary_method: VarMethod::Historical,
+            max_var: 0.05,                 // 5% of portfolio value
+            max_expected_shortfall: 0.075, // 7.5% of portfolio value
+            max_component_var_share: 0.5,  // 50% of VaR in one position
+        }
+    }
+}
+
+impl VarConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.confidence.is_nan() || self.confidence <= 0.5 || self.confidence >= 1.0 {
+            return Err(anyhow!(
+                "VaR confidence must be in (0.5, 1.0), got {}",
+                self.confidence
+            ));
+        }
+        if self.horizon_days.is_nan() || self.horizon_days <= 0.0 {
+            return Err(anyhow!(
+                "VaR horizon must be positive, got {} days",
+                self.horizon_days
+            ));
+        }
+        if self.return_interval_secs == 0 || self.monte_carlo_paths == 0 {
+            return Err(anyhow!(
+                "VaR return interval and Monte Carlo path count must be positive"
+            ));
+        }
+        Ok(())
+    }
+
+    /// Horizon measured in return bars
+    pub fn horizon_bars(&self) -> f64 {
+        self.horizon_days * 86_400.0 / self.return_interval_secs as f64
+    }
+}
+
+/// One position's share of portfolio VaR
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionRisk {
+    pub symbol: String,
+    pub exposure: f64,      // Signed position value
+    pub marginal_var: f64,  // dVaR / dExposure
+    pub component_var: f64, // exposure * marginal VaR; components sum to VaR
+    pub contribution: f64,  // component VaR / portfolio VaR
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VarEstimate {
+    pub method: VarMethod,
+    pub confidence: f64,
+    pub horizon_days: f64,
+    pub value_at_risk: f64,      // Loss not exceeded at `confidence`
+    pub expected_shortfall: f64, // Mean loss beyond VaR
+    pub positions: Vec<PositionRisk>,
+}
+
+/// All three estimates for the current portfolio
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TailRiskReport {
+    pub timestamp: u64,
+    pub portfolio_value: f64, // Gross exposure the limits are measured against
+    pub observations: usize,  // Aligned returns behind the estimates
+    pub estimates: Vec<VarEstimate>,
+}
+
+impl TailRiskReport {
+    pub fn compute(
+        positions: &HashMap<String, Position>,
+        price_history: &PriceHistory,
+        correlation: &CorrelationMatrix,
+        config: &VarConfig,
+    ) -> Result<Self> {
+        let inputs = TailRiskInputs::new(positions, price_history, config)?;
+        let estimates = VarMethod::ALL
+            .iter()
+            .map(|method| inputs.estimate(*method, correlation, config))
+            .collect();
+
+        Ok(Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            portfolio_value: inputs.exposures.iter().map(|e| e.abs()).sum(),
+            observations: inputs.history.returns.len(),
+            estimates,
+        })
+    }
+
+    pub fn estimate(&self, method: VarMethod) -> Option<&VarEstimate> {
+        self.estimates.iter().find(|e| e.method == method)
+    }
+
+    /// The most pessimistic estimate, checked against limits
+    pub fn worst(&self) -> Option<&VarEstimate> {
+        self.estimates
+            .iter()
+            .max_by(|a, b| a.value_at_risk.total_cmp(&b.value_at_risk))
+    }
+}
+
+/// Simple returns per bar, aligned across symbols (rows are bars)
+#[derive(Debug, Clone)]
+pub struct ReturnHistory {
+    pub symbols: Vec<String>,
+    pub returns: Vec<Vec<f64>>,
+}
+
+impl ReturnHistory {
+    /// Resample each price series to the last price per `interval_secs` bar
+    /// and keep bars every symbol has; a gap between kept bars counts as one bar
+    pub fn from_prices(
+        price_history: &PriceHistory,
+        symbols: &[String],
+        interval_secs: u64,
+    ) -> Self {
+        let bars: Vec<BTreeMap<u64, f64>> = symbols
+            .iter()
+            .map(|symbol| {
+                let mut bars = BTreeMap::new();
+                for (time, price) in price_history.get(symbol).into_iter().flatten() {
+                    bars.insert(time / interval_secs, *price);
+                }
+                bars
+            })
+            .collect();
+
+        let common: Vec<u64> = match bars.first() {
+            Some(first) => first
+                .keys()
+                .filter(|bar| bars.iter().all(|series| series.contains_key(bar)))
+                .copied()
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let returns = common
+            .windows(2)
+            .filter_map(|pair| {
+                bars.iter()
+                    .map(|series| {
+                        let (prev, next) = (series[&pair[0]], series[&pair[1]]);
+                        (prev > 0.0).then(|| next / prev - 1.0)
+                    })
+                    .collect::<Option<Vec<f64>>>()
+            })
+            .collect();
+
+        Self {
+            symbols: symbols.to_vec(),
+            returns,
+        }
+    }
+
+    fn column_means(&self) -> Vec<f64> {
+        let n = self.returns.len().max(1) as f64;
+        (0..self.symbols.len())
+            .map(|i| self.returns.iter().map(|row| row[i]).sum::<f64>() / n)
+            .collect()
+    }
+
+    /// Sample covariance of bar returns
+    pub fn covariance(&self) -> Vec<Vec<f64>> {
+        let k = self.symbols.len();
+        let means = self.column_means();
+        let denominator = (self.returns.len().saturating_sub(1)).max(1) as f64;
+        let mut covariance = vec![vec![0.0; k]; k];
+        for row in &self.returns {
+            for i in 0..k {
+                for j in 0..k {
+                    covariance[i][j] += (row[i] - means[i]) * (row[j] - means[j]);
+                }
+            }
+        }
+        for row in covariance.iter_mut() {
+            for value in row.iter_mut() {
+                *value /= denominator;
+            }
+        }
+        covariance
+    }
+
+    /// Pearson correlation of bar returns
+    pub fn correlation(&self) -> Vec<Vec<f64>> {
+        let covariance = self.covariance();
+        let k = self.symbols.len();
+        let mut correlation = vec![vec![0.0; k]; k];
+        for i in 0..k {
+            for j in 0..k {
+                let scale = (covariance[i][i] * covariance[j][j]).sqrt();
+                correlation[i][j] = if i == j {
+                    1.0
+                } else if scale > 0.0 {
+                    (covariance[i][j] / scale).clamp(-1.0, 1.0)
+                } else {
+                    0.0
+                };
+            }
+        }
+        correlation
+    }
+}
+
+/// Exposures and aligned returns for the positions being measured
+#[derive(Debug, Clone)]
+pub struct TailRiskInputs {
+    pub exposures: Vec<f64>,
+    pub history: ReturnHistory,
+}
+
+impl TailRiskInputs {
+    pub fn new(
+        positions: &HashMap<String, Position>,
+        price_history: &PriceHistory,
+        config: &VarConfig,
+    ) -> Result<Self> {
+        config.validate()?;
+
+        let mut held: Vec<(&String, f64)> = positions
+            .iter()
+            .map(|(symbol, p)| (symbol, p.quantity * p.current_price))
+            .filter(|(_, exposure)| *exposure != 0.0)
+            .collect();
+        held.sort_by(|a, b| a.0.cmp(b.0));
+        if held.is_empty() {
+            return Err(anyhow!("No open positions to measure"));
+        }
+
+        let missing: Vec<&str> = held
+            .iter()
+            .filter(|(symbol, _)| !price_history.contains_key(*symbol))
+            .map(|(symbol, _)| symbol.as_str())
+            .collect();
+        if !missing.is_empty() {
+            return Err(anyhow!("No price history for {}", missing.join(", ")));
+        }
+
+        let symbols: Vec<String> = held.iter().map(|(symbol, _)| (*symbol).clone()).collect();
+        let history =
+            ReturnHistory::from_prices(price_history, &symbols, config.return_interval_secs);
+        if history.returns.len() < config.min_observations {
+            return Err(anyhow!(
+                "VaR needs at least {} aligned returns, have {}",
+                config.min_observations,
+                history.returns.len()
+            ));
+        }
+
+        Ok(Self {
+            exposures: held.iter().map(|(_, exposure)| *exposure).collect(),
+            history,
+        })
+    }
+
+    pub fn estimate(
+        &self,
+        method: VarMethod,
+        correlation: &CorrelationMatrix,
+        config: &VarConfig,
+    ) -> VarEstimate {
+        match method {
+            VarMethod::Historical => self.historical(config),
+            VarMethod::Parametric => self.parametric(correlation, config),
+            VarMethod::MonteCarlo => self.monte_carlo(correlation, config),
+        }
+    }
+
+    /// Historical simulation; one-bar P&L scaled to the horizon by sqrt(time)
+    pub fn historical(&self, config: &VarConfig) -> VarEstimate {
+        let scale = config.horizon_bars().sqrt();
+        let scenarios = self
+            .history
+            .returns
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(&self.exposures)
+                    .map(|(r, exposure)| -exposure * r * scale)
+                    .collect()
+            })
+            .collect();
+        self.scenario_estimate(VarMethod::Historical, scenarios, config)
+    }
+
+    /// Variance-covariance VaR with zero mean:
+    /// VaR = z * sigma_p * sqrt(h), ES = sigma_p * sqrt(h) * phi(z) / (1 - c)
+    pub fn parametric(&self, correlation: &CorrelationMatrix, config: &VarConfig) -> VarEstimate {
+        let covariance = self.covariance(correlation);
+        let sigma_exposure: Vec<f64> = covariance
+            .iter()
+            .map(|row| row.iter().zip(&self.exposures).map(|(c, e)| c * e).sum())
+            .collect();
+        let variance: f64 = sigma_exposure
+            .iter()
+            .zip(&self.exposures)
+            .map(|(se, e)| se * e)
+            .sum();
+        let sigma = variance.max(0.0).sqrt();
+
+        let z = normal_quantile(config.confidence);
+        let scale = config.horizon_bars().sqrt();
+        let value_at_risk = z * sigma * scale;
+        let expected_shortfall = sigma * scale * normal_pdf(z) / (1.0 - config.confidence);
+
+        let marginal: Vec<f64> = sigma_exposure
+            .iter()
+            .map(|se| {
+                if sigma > 0.0 {
+                    z * scale * se / sigma
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+        let positions = self.position_risks(&marginal, value_at_risk);
+
+        VarEstimate {
+            method: VarMethod::Parametric,
+            confidence: config.confidence,
+            horizon_days: config.horizon_days,
+            value_at_risk,
+            expected_shortfall,
+            positions,
+        }
+    }
+
+    /// Zero-drift correlated normal paths, one step per bar of the horizon
+    pub fn monte_carlo(&self, correlation: &CorrelationMatrix, config: &VarConfig) -> VarEstimate {
+        let factor = cholesky(&self.covariance(correlation));
+        let bars = config.horizon_bars();
+        let steps = (bars.ceil() as usize).max(1);
+        let step_scale = (bars / steps as f64).sqrt();
+        let mut rng = match config.monte_carlo_seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        let k = self.exposures.len();
+        let mut shocks = vec![0.0; k];
+        let scenarios = (0..config.monte_carlo_paths)
+            .map(|_| {
+                let mut growth = vec![1.0; k];
+                for _ in 0..steps {
+                    for shock in shocks.iter_mut() {
+                        *shock = standard_normal(&mut rng);
+                    }
+                    for (i, g) in growth.iter_mut().enumerate() {
+                        let r: f64 = (0..=i).map(|j| factor[i][j] * shocks[j]).sum();
+                        *g *= 1.0 + r * step_scale;
+                    }
+                }
+                growth
+                    .iter()
+                    .zip(&self.exposures)
+                    .map(|(g, exposure)| -exposure * (g - 1.0))
+                    .collect()
+            })
+            .collect();
+        self.scenario_estimate(VarMethod::MonteCarlo, scenarios, config)
+    }
+
+    /// Bar covariance from sample volatilities and the correlation matrix;
+    /// pairs the matrix doesn't cover use the sample correlation
+    fn covariance(&self, correlation: &CorrelationMatrix) -> Vec<Vec<f64>> {
+        let sample = self.history.covariance();
+        let sample_correlation = self.history.correlation();
+        let index: Vec<Option<usize>> = self
+            .history
+            .symbols
+            .iter()
+            .map(|symbol| correlation.symbols.iter().position(|s| s == symbol))
+            .collect();
+
+        let k = self.exposures.len();
+        let mut covariance = vec![vec![0.0; k]; k];
+        for i in 0..k {
+            for j in 0..k {
+                let rho = if i == j {
+                    1.0
+                } else {
+                    match (index[i], index[j]) {
+                        (Some(a), Some(b)) => correlation
+                            .matrix
+                            .get(a)
+                            .and_then(|row| row.get(b))
+                            .copied()
+                            .unwrap_or(sample_correlation[i][j])
+                            .clamp(-1.0, 1.0),
+                        _ => sample_correlation[i][j],
+                    }
+                };
+                covariance[i][j] = rho * (sample[i][i] * sample[j][j]).sqrt();
+            }
+        }
+        covariance
+    }
+
+    /// VaR and ES from per-position scenario losses. Components are the
+    /// positions' mean losses in the tail, scaled so they sum to VaR
+    fn scenario_estimate(
+        &self,
+        method: VarMethod,
+        scenarios: Vec<Vec<f64>>,
+        config: &VarConfig,
+    ) -> VarEstimate {
+        let mut ranked: Vec<(f64, Vec<f64>)> = scenarios
+            .into_iter()
+            .map(|losses| (losses.iter().sum(), losses))
+            .collect();
+        ranked.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let n = ranked.len();
+        let cutoff = ((config.confidence * n as f64).ceil() as usize)
+            .saturating_sub(1)
+            .min(n.saturating_sub(1));
+        let tail = &ranked[cutoff..];
+        let value_at_risk = tail.first().map(|(loss, _)| *loss).unwrap_or(0.0);
+        let expected_shortfall =
+            tail.iter().map(|(loss, _)| loss).sum::<f64>() / tail.len().max(1) as f64;
+
+        let k = self.exposures.len();
+        let tail_losses: Vec<f64> = (0..k)
+            .map(|i| {
+                tail.iter().map(|(_, losses)| losses[i]).sum::<f64>() / tail.len().max(1) as f64
+            })
+            .collect();
+        let to_var = if expected_shortfall.abs() > f64::EPSILON {
+            value_at_risk / expected_shortfall
+        } else {
+            0.0
+        };
+        let marginal: Vec<f64> = tail_losses
+            .iter()
+            .zip(&self.exposures)
+            .map(|(loss, exposure)| loss * to_var / exposure)
+            .collect();
+        let positions = self.position_risks(&marginal, value_at_risk);
+
+        VarEstimate {
+            method,
+            confidence: config.confidence,
+            horizon_days: config.horizon_days,
+            value_at_risk,
+            expected_shortfall,
+            positions,
+        }
+    }
+
+    fn position_risks(&self, marginal: &[f64], value_at_risk: f64) -> Vec<PositionRisk> {
+        self.history
+            .symbols
+            .iter()
+            .zip(&self.exposures)
+            .zip(marginal)
+            .map(|((symbol, exposure), marginal_var)| {
+                let component_var = exposure * marginal_var;
+                PositionRisk {
+                    symbol: symbol.clone(),
+                    exposure: *exposure,
+                    marginal_var: *marginal_var,
+                    component_var,
+                    contribution: if value_at_risk > 0.0 {
+                        component_var / value_at_risk
+                    } else {
+                        0.0
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+/// Lower-triangular factor of a covariance matrix; directions with no
+/// variance left (semi-definite input) get a zero column
+fn cholesky(matrix: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let k = matrix.len();
+    let mut factor = vec![vec![0.0; k]; k];
+    for i in 0..k {
+        for j in 0..=i {
+            let sum = matrix[i][j] - (0..j).map(|m| factor[i][m] * factor[j][m]).sum::<f64>();
+            factor[i][j] = if i == j {
+                sum.max(0.0).sqrt()
+            } else if factor[j][j] > 1e-12 {
+                sum / factor[j][j]
+            } else {
+                0.0
+            };
+        }
+    }
+    factor
+}
+
+/// Box-Muller standard normal draw
+fn standard_normal(rng: &mut StdRng) -> f64 {
+    let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+    let u2: f64 = rng.gen();
+    (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+}
+
+fn normal_pdf(z: f64) -> f64 {
+    (-0.5 * z * z).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
+/// Inverse standard normal CDF (Acklam's rational approximation, ~1e-9)
+pub fn normal_quantile(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1,
+        2.209460984245205e2,
+        -2.759285104469687e2,
+        1.38357751867269e2,
+        -3.066479806614716e1,
+        2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1,
+        1.615858368580409e2,
+        -1.556989798598866e2,
+        6.680131188771972e1,
+        -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3,
+        -3.223964580411365e-1,
+        -2.400758277161838,
+        -2.549732539343734,
+        4.374664141464968,
+        2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3,
+        3.224671290700398e-1,
+        2.445134137142996,
+        3.754408661907416,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+
+    if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p <= 1.0 - P_LOW {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    } else {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::advanced_risk_management::{
+        AdvancedRiskConfig, AdvancedRiskManager, RiskAlertType,
+    };
+
+    fn position(symbol: &str, quantity: f64, price: f64) -> Position {
+        Position {
+            symbol: symbol.to_string(),
+            quantity,
+            entry_price: price,
+            current_price: price,
+            entry_time: 0,
+            stop_loss: None,
+            take_profit: None,
+            unrealized_pnl: 0.0,
+            risk_score: 0.0,
+        }
+    }
+
+    /// Hourly prices following `returns`, starting at 100
+    fn prices(returns: impl Iterator<Item = f64>) -> Vec<(u64, f64)> {
+        let mut price = 100.0;
+        let mut series = vec![(0, price)];
+        for (bar, r) in returns.enumerate() {
+            price *= 1.0 + r;
+            series.push(((bar as u64 + 1) * 3600, price));
+        }
+        series
+    }
+
+    fn book() -> (HashMap<String, Position>, PriceHistory) {
+        // Deterministic, roughly uncorrelated return streams
+        let sol = (0..200).map(|i| 0.01 * ((i as f64) * 1.3).sin());
+        let bonk = (0..200).map(|i| 0.03 * ((i as f64) * 2.9 + 1.0).cos());
+        let positions = HashMap::from([
+            ("SOL".to_string(), position("SOL", 10.0, 100.0)),
+            ("BONK".to_string(), position("BONK", 20.0, 100.0)),
+        ]);
+        let history = HashMap::from([
+            ("SOL".to_string(), prices(sol)),
+            ("BONK".to_string(), prices(bonk)),
+        ]);
+        (positions, history)
+    }
+
+    #[test]
+    fn test_components_sum_to_var() {
+        assert!((normal_quantile(0.95) - 1.644_853_627).abs() < 1e-6);
+        assert!((normal_quantile(0.01) + 2.326_347_874).abs() < 1e-6);
+
+        let (positions, history) = book();
+        let config = VarConfig {
+            monte_carlo_seed: Some(7),
+            ..VarConfig::default()
+        };
+        let report =
+            TailRiskReport::compute(&positions, &history, &CorrelationMatrix::default(), &config)
+                .unwrap();
+        assert_eq!(report.estimates.len(), 3);
+        assert_eq!(report.portfolio_value, 3_000.0);
+
+        for estimate in &report.estimates {
+            assert!(estimate.value_at_risk > 0.0, "{:?}", estimate.method);
+            assert!(estimate.expected_shortfall >= estimate.value_at_risk);
+            let components: f64 = estimate.positions.iter().map(|p| p.component_var).sum();
+            assert!(
+                (components - estimate.value_at_risk).abs() < 1e-6 * estimate.value_at_risk,
+                "{:?}: {} vs {}",
+                estimate.method,
+                components,
+                estimate.value_at_risk
+            );
+            // BONK is 3x as volatile and twice the size: it dominates the risk
+            let bonk = &estimate.positions[0];
+            assert_eq!(bonk.symbol, "BONK");
+            assert!(bonk.contribution > 0.8, "{:?}", estimate.method);
+        }
+
+        // Monte Carlo converges on the variance-covariance answer
+        let parametric = report.estimate(VarMethod::Parametric).unwrap();
+        let monte_carlo = report.estimate(VarMethod::MonteCarlo).unwrap();
+        let gap = (monte_carlo.value_at_risk / parametric.value_at_risk - 1.0).abs();
+        assert!(gap < 0.1, "{}", gap);
+    }
+
+    #[test]
+    fn test_confidence_horizon_and_correlation() {
+        let (positions, history) = book();
+        let base = VarConfig::default();
+        let inputs = TailRiskInputs::new(&positions, &history, &base).unwrap();
+        let symbols = inputs.history.symbols.clone();
+        let independent = CorrelationMatrix {
+            symbols: symbols.clone(),
+            matrix: vec![vec![1.0, 0.0], vec![0.0, 1.0]],
+            last_updated: 0,
+        };
+        let locked = CorrelationMatrix {
+            symbols,
+            matrix: vec![vec![1.0, 1.0], vec![1.0, 1.0]],
+            last_updated: 0,
+        };
+
+        let one_day = inputs.parametric(&independent, &base);
+        let four_days = inputs.parametric(
+            &independent,
+            &VarConfig {
+                horizon_days: 4.0,
+                ..base.clone()
+            },
+        );
+        assert!((four_days.value_at_risk / one_day.value_at_risk - 2.0).abs() < 1e-9);
+
+        let strict = inputs.historical(&VarConfig {
+            confidence: 0.99,
+            ..base.clone()
+        });
+        assert!(strict.value_at_risk > inputs.historical(&base).value_at_risk);
+
+        // Standalone VaRs add up under perfect correlation and add in
+        // quadrature under independence
+        let covariance = inputs.history.covariance();
+        let standalone: Vec<f64> = (0..2)
+            .map(|i| {
+                normal_quantile(0.95)
+                    * covariance[i][i].sqrt()
+                    * inputs.exposures[i].abs()
+                    * base.horizon_bars().sqrt()
+            })
+            .collect();
+        let together = inputs.parametric(&locked, &base);
+        assert!((together.value_at_risk - standalone.iter().sum::<f64>()).abs() < 1e-6);
+        let quadrature = standalone.iter().map(|v| v * v).sum::<f64>().sqrt();
+        assert!((one_day.value_at_risk - quadrature).abs() < 1e-6);
+
+        assert!(VarConfig {
+            confidence: 1.0,
+            ..base.clone()
+        }
+        .validate()
+        .is_err());
+        let short = VarConfig {
+            min_observations: 500,
+            ..base
+        };
+        let err = TailRiskInputs::new(&positions, &history, &short).unwrap_err();
+        assert!(err.to_string().contains("at least 500"), "{}", err);
+    }
+
+    #[test]
+    fn test_limit_breaches_raise_alerts() {
+        let (positions, history) = book();
+        let mut config = AdvancedRiskConfig::default();
+        config.var.monte_carlo_seed = Some(1);
+        let report = TailRiskReport::compute(
+            &positions,
+            &history,
+            &CorrelationMatrix::default(),
+            &config.var,
+        )
+        .unwrap();
+        let kinds = |config: &AdvancedRiskConfig| -> Vec<String> {
+            AdvancedRiskManager::check_tail_risk_limits(&report, config)
+                .iter()
+                .map(|alert| format!("{:?}", alert.alert_type))
+                .collect()
+        };
+
+        // Loose limits: only BONK's dominance of VaR is flagged
+        config.var.max_var = 1.0;
+        config.var.max_expected_shortfall = 1.0;
+        assert_eq!(kinds(&config), ["ComponentVarConcentration"]);
+
+        config.var.max_var = 0.001;
+        config.var.max_expected_shortfall = 0.001;
+        config.var.max_component_var_share = 2.0;
+        assert_eq!(
+            kinds(&config),
+            [
+                format!("{:?}", RiskAlertType::VarLimitExceeded),
+                format!("{:?}", RiskAlertType::ExpectedShortfallLimitExceeded),
+            ]
+        );
+    }
+}
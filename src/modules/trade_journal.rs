@@ -0,0 +1,585 @@
+// Trade Journal Module
+// Embedded SQLite journal of signals, risk decisions and execution results
+
+use crate::modules::executor::ExecutionResult;
+use crate::modules::risk::{ApprovedSignal, RiskDecision};
+use crate::modules::strategy::{StrategyType, TradingSignal};
+use anyhow::{Context, Result};
+use parking_lot::Mutex;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::{debug, info};
+
+/// Current journal schema version (highest migration applied on open)
+pub const SCHEMA_VERSION: i64 = 1;
+
+/// A single forward-only schema migration
+struct Migration {
+    version: i64,
+    description: &'static str,
+    sql: &'static str,
+}
+
+/// Ordered list of schema migrations. Never edit an applied migration -
+/// append a new one with the next version number instead.
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    description: "initial trade journal schema",
+    sql: "
+        CREATE TABLE trading_signals (
+            signal_id     TEXT PRIMARY KEY,
+            symbol        TEXT NOT NULL,
+            strategy      TEXT NOT NULL,
+            action        TEXT NOT NULL,
+            timestamp_ms  INTEGER NOT NULL,
+            payload       TEXT NOT NULL
+        );
+        CREATE INDEX idx_signals_symbol ON trading_signals(symbol, timestamp_ms);
+        CREATE INDEX idx_signals_strategy ON trading_signals(strategy, timestamp_ms);
+
+        CREATE TABLE risk_decisions (
+            id            INTEGER PRIMARY KEY AUTOINCREMENT,
+            signal_id     TEXT NOT NULL,
+            symbol        TEXT NOT NULL,
+            strategy      TEXT NOT NULL,
+            outcome       TEXT NOT NULL,
+            timestamp_ms  INTEGER NOT NULL,
+            payload       TEXT NOT NULL
+        );
+        CREATE INDEX idx_risk_signal ON risk_decisions(signal_id);
+        CREATE INDEX idx_risk_time ON risk_decisions(timestamp_ms);
+
+        CREATE TABLE approved_signals (
+            signal_id     TEXT PRIMARY KEY,
+            symbol        TEXT NOT NULL,
+            strategy      TEXT NOT NULL,
+            timestamp_ms  INTEGER NOT NULL,
+            payload       TEXT NOT NULL
+        );
+
+        CREATE TABLE execution_results (
+            id              INTEGER PRIMARY KEY AUTOINCREMENT,
+            signal_id       TEXT NOT NULL,
+            transaction_id  TEXT NOT NULL,
+            status          TEXT NOT NULL,
+            timestamp_ms    INTEGER NOT NULL,
+            payload         TEXT NOT NULL
+        );
+        CREATE INDEX idx_exec_signal ON execution_results(signal_id);
+        CREATE INDEX idx_exec_time ON execution_results(timestamp_ms);
+    ",
+}];
+
+/// Filter for journal queries. All set fields are combined with AND.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JournalQuery {
+    pub signal_id: Option<String>,
+    pub strategy: Option<StrategyType>,
+    pub token: Option<String>,
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    pub limit: Option<usize>,
+}
+
+impl JournalQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn signal_id(mut self, signal_id: impl Into<String>) -> Self {
+        self.signal_id = Some(signal_id.into());
+        self
+    }
+
+    pub fn strategy(mut self, strategy: StrategyType) -> Self {
+        self.strategy = Some(strategy);
+        self
+    }
+
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Inclusive time range
+    pub fn between(
+        mut self,
+        from: chrono::DateTime<chrono::Utc>,
+        to: chrono::DateTime<chrono::Utc>,
+    ) -> Self {
+        self.from = Some(from);
+        self.to = Some(to);
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Build a WHERE clause over the `s` (signals) alias and the given
+    /// timestamp column, returning the SQL fragment and its bound values.
+    fn where_clause(&self, time_column: &str) -> (String, Vec<Value>) {
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+
+        if let Some(ref signal_id) = self.signal_id {
+            values.push(Value::Text(signal_id.clone()));
+            conditions.push(format!("s.signal_id = ?{}", values.len()));
+        }
+        if let Some(ref strategy) = self.strategy {
+            values.push(Value::Text(strategy.to_string()));
+            conditions.push(format!("s.strategy = ?{}", values.len()));
+        }
+        if let Some(ref token) = self.token {
+            values.push(Value::Text(token.clone()));
+            conditions.push(format!("s.symbol = ?{}", values.len()));
+        }
+        if let Some(from) = self.from {
+            values.push(Value::Integer(from.timestamp_millis()));
+            conditions.push(format!("{} >= ?{}", time_column, values.len()));
+        }
+        if let Some(to) = self.to {
+            values.push(Value::Integer(to.timestamp_millis()));
+            conditions.push(format!("{} <= ?{}", time_column, values.len()));
+        }
+
+        let mut clause = if conditions.is_empty() {
+            String::new()
+        } else {
+            format!(" WHERE {}", conditions.join(" AND "))
+        };
+        clause.push_str(&format!(" ORDER BY {} ASC", time_column));
+        if let Some(limit) = self.limit {
+            clause.push_str(&format!(" LIMIT {}", limit));
+        }
+
+        (clause, values)
+    }
+}
+
+/// Everything the journal knows about a single signal, in pipeline order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeRecord {
+    pub signal: TradingSignal,
+    pub risk_decisions: Vec<RiskDecision>,
+    pub approved: Option<ApprovedSignal>,
+    pub executions: Vec<ExecutionResult>,
+}
+
+/// Embedded, file-backed trade journal
+pub struct TradeJournal {
+    conn: Mutex<Connection>,
+}
+
+impl TradeJournal {
+    /// Open (or create) a journal file and bring its schema up to date
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                std::fs::create_dir_all(parent).with_context(|| {
+                    format!("Failed to create journal directory {}", parent.display())
+                })?;
+            }
+        }
+
+        let conn = Connection::open(path)
+            .with_context(|| format!("Failed to open trade journal {}", path.display()))?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+
+        info!("💾 Trade journal opened at {}", path.display());
+        Self::from_connection(conn)
+    }
+
+    /// Volatile journal, used by tests and backtests
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    /// Open a journal from a `DatabaseConfig::url` style string.
+    /// Accepts `sqlite://path`, `sqlite:path`, `sqlite::memory:` or a bare file path.
+    pub fn from_database_url(url: &str) -> Result<Self> {
+        if url == "sqlite::memory:" || url == ":memory:" {
+            return Self::open_in_memory();
+        }
+
+        let path = if let Some(path) = url.strip_prefix("sqlite://") {
+            path
+        } else if let Some(path) = url.strip_prefix("sqlite:") {
+            path
+        } else if url.contains("://") {
+            anyhow::bail!(
+                "Unsupported trade journal URL '{}': only embedded sqlite:// journals are supported",
+                url
+            );
+        } else {
+            url
+        };
+
+        Self::open(path)
+    }
+
+    fn from_connection(mut conn: Connection) -> Result<Self> {
+        Self::migrate(&mut conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn migrate(conn: &mut Connection) -> Result<()> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS schema_migrations (
+                version      INTEGER PRIMARY KEY,
+                description  TEXT NOT NULL,
+                applied_at   INTEGER NOT NULL
+            );",
+        )?;
+
+        let current: i64 = conn.query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+            [],
+            |row| row.get(0),
+        )?;
+
+        if current > SCHEMA_VERSION {
+            anyhow::bail!(
+                "Trade journal schema v{} is newer than supported v{}",
+                current,
+                SCHEMA_VERSION
+            );
+        }
+
+        for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+            let tx = conn.transaction()?;
+            tx.execute_batch(migration.sql).with_context(|| {
+                format!("Trade journal migration v{} failed", migration.version)
+            })?;
+            tx.execute(
+                "INSERT INTO schema_migrations (version, description, applied_at) VALUES (?1, ?2, ?3)",
+                params![
+                    migration.version,
+                    migration.description,
+                    chrono::Utc::now().timestamp_millis()
+                ],
+            )?;
+            tx.commit()?;
+            info!(
+                "📐 Trade journal migrated to v{}: {}",
+                migration.version, migration.description
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Schema version currently applied to the journal file
+    pub fn schema_version(&self) -> Result<i64> {
+        let conn = self.conn.lock();
+        Ok(conn.query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM schema_migrations",
+            [],
+            |row| row.get(0),
+        )?)
+    }
+
+    pub fn record_signal(&self, signal: &TradingSignal) -> Result<()> {
+        debug!("💾 Journaling signal: {}", signal.signal_id);
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT OR REPLACE INTO trading_signals (signal_id, symbol, strategy, action, timestamp_ms, payload)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                signal.signal_id,
+                signal.symbol,
+                signal.strategy_type.to_string(),
+                signal.action.to_string(),
+                signal.timestamp.timestamp_millis(),
+                serde_json::to_string(signal)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn record_risk_decision(&self, decision: &RiskDecision) -> Result<()> {
+        debug!("💾 Journaling risk decision for signal: {}", decision.signal_id);
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT INTO risk_decisions (signal_id, symbol, strategy, outcome, timestamp_ms, payload)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                decision.signal_id,
+                decision.symbol,
+                decision.strategy_type.to_string(),
+                format!("{:?}", decision.outcome),
+                decision.timestamp.timestamp_millis(),
+                serde_json::to_string(decision)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn record_approved_signal(&self, approved: &ApprovedSignal) -> Result<()> {
+        let signal = &approved.original_signal;
+        debug!("💾 Journaling approved signal: {}", signal.signal_id);
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT OR REPLACE INTO approved_signals (signal_id, symbol, strategy, timestamp_ms, payload)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                signal.signal_id,
+                signal.symbol,
+                signal.strategy_type.to_string(),
+                approved.approval_timestamp.timestamp_millis(),
+                serde_json::to_string(approved)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Append an execution result. Later results for the same signal
+    /// (e.g. status updates) are kept as additional rows.
+    pub fn record_execution(&self, result: &ExecutionResult) -> Result<()> {
+        debug!("💾 Journaling execution result: {}", result.transaction_id);
+        let conn = self.conn.lock();
+        conn.execute(
+            "INSERT INTO execution_results (signal_id, transaction_id, status, timestamp_ms, payload)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                result.signal_id,
+                result.transaction_id,
+                format!("{:?}", result.status),
+                result.timestamp.timestamp_millis(),
+                serde_json::to_string(result)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn query_signals(&self, query: &JournalQuery) -> Result<Vec<TradingSignal>> {
+        let (clause, values) = query.where_clause("s.timestamp_ms");
+        let sql = format!("SELECT s.payload FROM trading_signals s{}", clause);
+        self.query_payloads(&sql, values)
+    }
+
+    pub fn query_risk_decisions(&self, query: &JournalQuery) -> Result<Vec<RiskDecision>> {
+        let (clause, values) = query.where_clause("s.timestamp_ms");
+        let sql = format!("SELECT s.payload FROM risk_decisions s{}", clause);
+        self.query_payloads(&sql, values)
+    }
+
+    pub fn query_approved_signals(&self, query: &JournalQuery) -> Result<Vec<ApprovedSignal>> {
+        let (clause, values) = query.where_clause("s.timestamp_ms");
+        let sql = format!("SELECT s.payload FROM approved_signals s{}", clause);
+        self.query_payloads(&sql, values)
+    }
+
+    /// Execution results do not carry strategy or token themselves, so
+    /// those filters are resolved through the originating signal.
+    pub fn query_executions(&self, query: &JournalQuery) -> Result<Vec<ExecutionResult>> {
+        let (clause, values) = query.where_clause("e.timestamp_ms");
+        let clause = clause.replace("s.signal_id", "e.signal_id");
+        let sql = format!(
+            "SELECT e.payload FROM execution_results e
+             LEFT JOIN trading_signals s ON s.signal_id = e.signal_id{}",
+            clause
+        );
+        self.query_payloads(&sql, values)
+    }
+
+    /// Reconstruct the full pipeline history of every signal matching the query
+    pub fn trade_history(&self, query: &JournalQuery) -> Result<Vec<TradeRecord>> {
+        let signals = self.query_signals(query)?;
+        let mut records = Vec::with_capacity(signals.len());
+
+        for signal in signals {
+            let by_id = JournalQuery::new().signal_id(signal.signal_id.clone());
+            records.push(TradeRecord {
+                risk_decisions: self.query_risk_decisions(&by_id)?,
+                approved: self.query_approved_signals(&by_id)?.into_iter().next(),
+                executions: self.query_executions(&by_id)?,
+                signal,
+            });
+        }
+
+        Ok(records)
+    }
+
+    /// Latest execution result for a signal, if any
+    pub fn latest_execution(&self, signal_id: &str) -> Result<Option<ExecutionResult>> {
+        let conn = self.conn.lock();
+        let payload: Option<String> = conn
+            .query_row(
+                "SELECT payload FROM execution_results WHERE signal_id = ?1 ORDER BY id DESC LIMIT 1",
+                params![signal_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        payload
+            .map(|p| serde_json::from_str(&p).context("Corrupt execution result payload"))
+            .transpose()
+    }
+
+    fn query_payloads<T: serde::de::DeserializeOwned>(
+        &self,
+        sql: &str,
+        values: Vec<Value>,
+    ) -> Result<Vec<T>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare(sql)?;
+        let rows = stmt.query_map(params_from_iter(values.iter()), |row| row.get::<_, String>(0))?;
+
+        let mut items = Vec::new();
+        for payload in rows {
+            let payload = payload?;
+            items.push(serde_json::from_str(&payload).context("Corrupt trade journal payload")?);
+        }
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::executor::ExecutionStatus;
+    use crate::modules::risk::{KineticShieldStatus, RiskOutcome};
+    use crate::modules::strategy::TradeAction;
+
+    fn signal(id: &str, symbol: &str, strategy: StrategyType, minutes_ago: i64) -> TradingSignal {
+        TradingSignal {
+            signal_id: id.to_string(),
+            symbol: symbol.to_string(),
+            action: TradeAction::Buy,
+            quantity: 10.0,
+            target_price: 1.5,
+            price: Some(1.5),
+            confidence: 0.8,
+            timestamp: chrono::Utc::now() - chrono::Duration::minutes(minutes_ago),
+            strategy_type: strategy,
+            urgency: None,
+            metadata: None,
+        }
+    }
+
+    fn execution(signal_id: &str, status: ExecutionStatus) -> ExecutionResult {
+        ExecutionResult {
+            signal_id: signal_id.to_string(),
+            transaction_id: format!("tx_{}", signal_id),
+            status,
+            executed_quantity: 10.0,
+            executed_price: 1.51,
+            fees: 0.015,
+            timestamp: chrono::Utc::now(),
+            error_message: None,
+            symbol: "BONK".to_string(),
+            action: Some(TradeAction::Buy),
+            wallet_id: None,
+            fee_breakdown: None,
+        }
+    }
+
+    #[test]
+    fn test_migrations_applied_once() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("journal.db");
+
+        let journal = TradeJournal::open(&path)?;
+        assert_eq!(journal.schema_version()?, SCHEMA_VERSION);
+        drop(journal);
+
+        // Re-opening must not re-run migrations
+        let journal = TradeJournal::from_database_url(&format!("sqlite://{}", path.display()))?;
+        assert_eq!(journal.schema_version()?, SCHEMA_VERSION);
+        Ok(())
+    }
+
+    #[test]
+    fn test_rejects_server_urls() {
+        assert!(TradeJournal::from_database_url("postgresql://localhost/overmind").is_err());
+    }
+
+    #[test]
+    fn test_execution_survives_reopen() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("journal.db");
+
+        {
+            let journal = TradeJournal::open(&path)?;
+            journal.record_signal(&signal("sig-1", "BONK", StrategyType::LiquidityTsunami, 5))?;
+            journal.record_execution(&execution("sig-1", ExecutionStatus::Confirmed))?;
+        }
+
+        let journal = TradeJournal::open(&path)?;
+        let executions = journal.query_executions(&JournalQuery::new().signal_id("sig-1"))?;
+        assert_eq!(executions.len(), 1);
+        assert_eq!(executions[0].transaction_id, "tx_sig-1");
+        Ok(())
+    }
+
+    #[test]
+    fn test_query_by_strategy_token_and_time() -> Result<()> {
+        let journal = TradeJournal::open_in_memory()?;
+        journal.record_signal(&signal("a", "BONK", StrategyType::LiquidityTsunami, 120))?;
+        journal.record_signal(&signal("b", "WIF", StrategyType::WhaleShadowing, 30))?;
+        journal.record_signal(&signal("c", "BONK", StrategyType::WhaleShadowing, 10))?;
+        for id in ["a", "b", "c"] {
+            journal.record_execution(&execution(id, ExecutionStatus::Confirmed))?;
+        }
+
+        let whale = journal.query_signals(&JournalQuery::new().strategy(StrategyType::WhaleShadowing))?;
+        assert_eq!(whale.len(), 2);
+
+        let bonk = journal.query_executions(&JournalQuery::new().token("BONK"))?;
+        assert_eq!(bonk.len(), 2);
+
+        let now = chrono::Utc::now();
+        let last_hour = journal.query_signals(
+            &JournalQuery::new().between(now - chrono::Duration::hours(1), now),
+        )?;
+        assert_eq!(last_hour.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_trade_history_reconstruction() -> Result<()> {
+        let journal = TradeJournal::open_in_memory()?;
+        let sig = signal("sig-9", "POPCAT", StrategyType::MemeVirus, 1);
+        journal.record_signal(&sig)?;
+        journal.record_risk_decision(&RiskDecision {
+            signal_id: sig.signal_id.clone(),
+            symbol: sig.symbol.clone(),
+            strategy_type: sig.strategy_type.clone(),
+            outcome: RiskOutcome::Approved,
+            requested_quantity: sig.quantity,
+            approved_quantity: 8.0,
+            risk_score: Some(0.4),
+            reason: None,
+            timestamp: chrono::Utc::now(),
+            rule_audit: Vec::new(),
+        })?;
+        journal.record_approved_signal(&ApprovedSignal {
+            original_signal: sig.clone(),
+            approved_quantity: 8.0,
+            risk_score: 0.4,
+            approval_timestamp: chrono::Utc::now(),
+            kinetic_shield_status: KineticShieldStatus::Active,
+            risk_audit: Vec::new(),
+        })?;
+        journal.record_execution(&execution("sig-9", ExecutionStatus::Pending))?;
+        journal.record_execution(&execution("sig-9", ExecutionStatus::Confirmed))?;
+
+        let history = journal.trade_history(&JournalQuery::new().signal_id("sig-9"))?;
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].risk_decisions.len(), 1);
+        assert!(history[0].approved.is_some());
+        assert_eq!(history[0].executions.len(), 2);
+
+        let latest = journal.latest_execution("sig-9")?.unwrap();
+        assert!(matches!(latest.status, ExecutionStatus::Confirmed));
+        Ok(())
+    }
+}
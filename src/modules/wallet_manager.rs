@@ -564,7 +564,7 @@ impl WalletConfigBuilder {
         self.config
     }
 
-    fn parse_private_key(private_key: &str) -> Result<Keypair> {
+    pub(crate) fn parse_private_key(private_key: &str) -> Result<Keypair> {
         // Try JSON array format first
         if private_key.starts_with('[') && private_key.ends_with(']') {
             let bytes: Vec<u8> = serde_json::from_str(private_key)
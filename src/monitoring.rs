@@ -201,6 +201,23 @@ impl MonitoringState {
             }
         }
     }
+
+    /// Update a component's lifecycle status (restarts are reported as the
+    /// error count), keeping its message count
+    pub fn record_component_state(&self, component: &str, status: &str, restarts: u64) {
+        let message_count = match self.health.lock() {
+            Ok(health) => match component {
+                "data_ingestor" => health.data_ingestor.message_count,
+                "strategy_engine" => health.strategy_engine.message_count,
+                "risk_manager" => health.risk_manager.message_count,
+                "executor" => health.executor.message_count,
+                "persistence" => health.persistence.message_count,
+                _ => 0,
+            },
+            Err(_) => return,
+        };
+        self.update_component_health(component, status, message_count, restarts);
+    }
 }
 
 // Health check endpoint
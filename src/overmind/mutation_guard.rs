@@ -1,7 +1,9 @@
 //! MutationGuard Security System for FAZA 11
 //! 
 //! Comprehensive security and validation system for genetic mutations
-//! including risk simulation, Hotz compliance, and black swan testing
+//! including risk simulation, Hotz compliance, and black swan testing.
+//! With a `StressScenarioLibrary` attached, plans are replayed through the
+//! backtest pipeline over recorded market episodes instead.
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
@@ -9,7 +11,8 @@ use std::collections::HashMap;
 use std::time::Duration;
 use tracing::{info, debug, warn};
 
-use super::evolution::{ConfigMutationPlan, ConfigMutation, RiskLevel, RiskAssessment};
+use super::evolution::{ConfigMutationPlan, ConfigMutation, MutationType, RiskLevel, RiskAssessment};
+use crate::backtest::{BacktestConfig, StressScenarioLibrary};
 
 /// Maximum safe drawdown threshold (Hotz philosophy: never exceed 15%)
 const MAX_SAFE_DRAWDOWN: f64 = 0.15;
@@ -36,6 +39,9 @@ pub struct MutationGuard {
     
     /// Validation statistics
     validation_stats: ValidationStats,
+    
+    /// Recorded stress episodes and the pipeline config plans are applied to
+    scenario_replay: Option<(StressScenarioLibrary, BacktestConfig)>,
 }
 
 /// Risk simulation engine
@@ -169,6 +175,8 @@ pub struct ScenarioResult {
     pub projected_drawdown: f64,
     pub survival_probability: f64,
     pub recovery_time: Duration,
+    /// False when a replayed episode ended before the drawdown was recovered
+    pub recovered: bool,
 }
 
 /// Guard error types
@@ -227,9 +235,24 @@ impl MutationGuard {
             hotz_checker,
             stress_tester,
             validation_stats: ValidationStats::default(),
+            scenario_replay: None,
         }
     }
     
+    /// Validate plans by replaying them through recorded stress episodes
+    ///
+    /// Each plan is applied to `baseline` and run through the backtest
+    /// pipeline over every scenario; the measured drawdown and time to
+    /// recovery replace the coefficient projection and synthetic scenarios.
+    pub fn with_scenario_library(mut self, library: StressScenarioLibrary, baseline: BacktestConfig) -> Self {
+        if library.is_empty() {
+            warn!("⚠️ Stress scenario library is empty - keeping synthetic stress tests");
+            return self;
+        }
+        self.scenario_replay = Some((library, baseline));
+        self
+    }
+    
     /// Validate mutation plan with comprehensive security checks
     pub async fn validate_plan(
         &mut self,
@@ -244,8 +267,24 @@ impl MutationGuard {
         let mut recommendations = Vec::new();
         let mut validation_passed = true;
         
-        // 1. Risk Simulation
-        let projected_drawdown = self.risk_simulator.project_drawdown(plan).await?;
+        // 1. Risk Simulation (replayed when recorded episodes are available)
+        let replayed = match self.scenario_replay {
+            Some((ref library, ref baseline)) => {
+                let (candidate, unmapped) = apply_plan_to_backtest(baseline, plan);
+                for target in unmapped {
+                    warnings.push(format!(
+                        "Mutation target {} has no pipeline parameter - replayed without it",
+                        target
+                    ));
+                }
+                Some(self.stress_tester.replay_stress_tests(library, &candidate).await?)
+            }
+            None => None,
+        };
+        let projected_drawdown = match replayed {
+            Some(ref result) => result.max_projected_drawdown,
+            None => self.risk_simulator.project_drawdown(plan).await?,
+        };
         if projected_drawdown > MAX_SAFE_DRAWDOWN {
             validation_passed = false;
             self.validation_stats.risk_rejections += 1;
@@ -277,7 +316,10 @@ impl MutationGuard {
         }
         
         // 4. Stress Testing
-        let stress_test_result = self.stress_tester.run_stress_tests(plan).await?;
+        let stress_test_result = match replayed {
+            Some(result) => result,
+            None => self.stress_tester.run_stress_tests(plan).await?,
+        };
         if !stress_test_result.passed {
             validation_passed = false;
             self.validation_stats.stress_test_failures += 1;
@@ -429,6 +471,118 @@ impl MutationGuard {
     }
 }
 
+/// Backtest pipeline parameter a mutation target drives
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BacktestParam {
+    /// Kinetic shield daily drawdown limit, stored in percent
+    DailyDrawdownLimit,
+    MaxDailyLoss,
+    MaxVolatility,
+    MaxPositionSize,
+    ExposureLimitPerToken,
+    MinConfidenceThreshold,
+    SlippageBps,
+}
+
+/// Mutation targets the backtest pipeline can replay
+///
+/// Targets are matched exactly; anything else is reported as unmapped.
+const BACKTEST_TARGETS: &[(&str, BacktestParam)] = &[
+    ("risk_thresholds.max_drawdown", BacktestParam::DailyDrawdownLimit),
+    ("risk_thresholds.max_daily_loss", BacktestParam::MaxDailyLoss),
+    ("risk_thresholds.max_volatility", BacktestParam::MaxVolatility),
+    ("position_sizing.max_position_size", BacktestParam::MaxPositionSize),
+    ("position_sizing.exposure_limit_per_token", BacktestParam::ExposureLimitPerToken),
+    ("trading_strategy.min_confidence_threshold", BacktestParam::MinConfidenceThreshold),
+    ("hft_params.slippage_bps", BacktestParam::SlippageBps),
+];
+
+impl BacktestParam {
+    fn for_target(target: &str) -> Option<Self> {
+        BACKTEST_TARGETS
+            .iter()
+            .find(|(name, _)| *name == target)
+            .map(|(_, param)| *param)
+    }
+
+    /// Current value, in the units of the mutation target
+    fn get(self, config: &BacktestConfig) -> f64 {
+        let risk = &config.risk_params;
+        let shield = &risk.kinetic_shield;
+        match self {
+            // Mutation targets carry drawdown as a fraction (0.01-0.50)
+            Self::DailyDrawdownLimit => shield.daily_drawdown_limit as f64 / 100.0,
+            Self::MaxDailyLoss => risk.max_daily_loss,
+            Self::MaxVolatility => shield.max_volatility as f64,
+            Self::MaxPositionSize => risk.max_position_size,
+            Self::ExposureLimitPerToken => shield.exposure_limit_per_token as f64,
+            Self::MinConfidenceThreshold => risk.min_confidence_threshold,
+            Self::SlippageBps => config.fill.slippage_bps,
+        }
+    }
+
+    fn set(self, config: &mut BacktestConfig, value: f64) {
+        let risk = &mut config.risk_params;
+        let shield = &mut risk.kinetic_shield;
+        match self {
+            Self::DailyDrawdownLimit => shield.daily_drawdown_limit = (value * 100.0) as f32,
+            Self::MaxDailyLoss => risk.max_daily_loss = value,
+            Self::MaxVolatility => shield.max_volatility = value as f32,
+            Self::MaxPositionSize => risk.max_position_size = value,
+            Self::ExposureLimitPerToken => shield.exposure_limit_per_token = value as f32,
+            Self::MinConfidenceThreshold => risk.min_confidence_threshold = value,
+            Self::SlippageBps => config.fill.slippage_bps = value,
+        }
+    }
+}
+
+/// Apply a mutation plan to a backtest pipeline configuration
+///
+/// Returns the mutated config and the targets it has no numeric parameter for.
+pub fn apply_plan_to_backtest(
+    base: &BacktestConfig,
+    plan: &ConfigMutationPlan,
+) -> (BacktestConfig, Vec<String>) {
+    let mut config = base.clone();
+    let mut unmapped = Vec::new();
+
+    for mutation in &plan.mutations {
+        let param = match BacktestParam::for_target(&mutation.target) {
+            Some(param) => param,
+            None => {
+                unmapped.push(mutation.target.clone());
+                continue;
+            }
+        };
+
+        // Same arithmetic as GeneticModifier applies to the TOML config
+        let value = param.get(&config);
+        let mutated = match mutation.mutation_type {
+            MutationType::Increase => value * (1.0 + mutation.delta),
+            MutationType::Decrease => value * (1.0 - mutation.delta.abs()),
+            MutationType::Replace => mutation.delta,
+            MutationType::Toggle => {
+                unmapped.push(mutation.target.clone());
+                continue;
+            }
+        };
+        param.set(&mut config, mutated);
+    }
+
+    (config, unmapped)
+}
+
+/// Survival odds for a projected drawdown
+fn survival_probability(projected_drawdown: f64) -> f64 {
+    if projected_drawdown > 0.30 {
+        0.20 // Low survival chance
+    } else if projected_drawdown > 0.15 {
+        0.60 // Moderate survival chance
+    } else {
+        0.90 // High survival chance
+    }
+}
+
 impl RiskSimulator {
     /// Project drawdown for mutation plan
     pub async fn project_drawdown(&self, plan: &ConfigMutationPlan) -> Result<f64> {
@@ -580,13 +734,7 @@ impl BlackSwanSimulator {
         let projected_drawdown = base_drawdown * mutation_impact;
 
         // Calculate survival probability
-        let survival_probability = if projected_drawdown > 0.30 {
-            0.20 // Low survival chance
-        } else if projected_drawdown > 0.15 {
-            0.60 // Moderate survival chance
-        } else {
-            0.90 // High survival chance
-        };
+        let survival_probability = survival_probability(projected_drawdown);
 
         // Estimate recovery time
         let base_recovery = Duration::from_secs(1800); // 30 minutes base
@@ -600,6 +748,194 @@ impl BlackSwanSimulator {
             projected_drawdown,
             survival_probability,
             recovery_time,
+            recovered: true,
         })
     }
+
+    /// Replay a candidate pipeline config through recorded stress episodes
+    pub async fn replay_stress_tests(
+        &self,
+        library: &StressScenarioLibrary,
+        config: &BacktestConfig,
+    ) -> Result<StressTestResult> {
+        debug!("📼 Replaying {} recorded stress scenarios", library.scenarios().len());
+
+        let mut scenario_results = Vec::new();
+        let mut max_projected_drawdown: f64 = 0.0;
+        let mut max_recovery_time = Duration::from_secs(0);
+        let mut all_recovered = true;
+
+        for replay in library.replay(config).await? {
+            let recovered = replay.time_to_recovery.is_some();
+            // Unrecovered episodes count every second spent underwater
+            let recovery_time = replay.time_to_recovery.unwrap_or(replay.time_underwater);
+
+            max_projected_drawdown = max_projected_drawdown.max(replay.max_drawdown);
+            max_recovery_time = max_recovery_time.max(recovery_time);
+            all_recovered &= recovered;
+
+            scenario_results.push(ScenarioResult {
+                scenario_name: replay.name,
+                projected_drawdown: replay.max_drawdown,
+                survival_probability: survival_probability(replay.max_drawdown),
+                recovery_time,
+                recovered,
+            });
+        }
+
+        let passed = all_recovered
+            && max_projected_drawdown <= self.max_drawdown_tolerance
+            && max_recovery_time <= self.recovery_time_limit;
+
+        if passed {
+            info!("✅ Replayed stress tests passed");
+        } else {
+            warn!(
+                "⚠️ Replayed stress tests failed - max drawdown: {:.2}%, recovery: {}s{}",
+                max_projected_drawdown * 100.0,
+                max_recovery_time.as_secs(),
+                if all_recovered { "" } else { " (not recovered)" }
+            );
+        }
+
+        Ok(StressTestResult {
+            passed,
+            max_projected_drawdown,
+            recovery_time_estimate: max_recovery_time,
+            scenario_results,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::{RecordedScenario, StressKind};
+    use crate::modules::data_ingestor::{DataSource, MarketData};
+    use crate::modules::market_replay::ReplayEvent;
+
+    fn mutation(target: &str, delta: f64, mutation_type: MutationType) -> ConfigMutation {
+        ConfigMutation {
+            target: target.to_string(),
+            delta,
+            mutation_type,
+            justification: "test".to_string(),
+        }
+    }
+
+    fn plan(mutations: Vec<ConfigMutation>) -> ConfigMutationPlan {
+        ConfigMutationPlan {
+            target_candidate: uuid::Uuid::new_v4(),
+            mutations,
+            expected_improvement: 0.0,
+            risk_assessment: RiskAssessment {
+                risk_level: RiskLevel::Low,
+                max_drawdown_impact: 0.05,
+                hotz_compliance: true,
+                safety_score: 0.85,
+            },
+            validation_required: true,
+        }
+    }
+
+    /// Rally the sniping path buys into, then a crash and a partial rebound
+    fn crash_library() -> StressScenarioLibrary {
+        let start = chrono::DateTime::parse_from_rfc3339("2025-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        let mut path: Vec<f64> = (0..=20).map(|i| 100.0 + i as f64).collect();
+        path.extend([84.0, 84.0, 90.0, 96.0]);
+        let events = path
+            .iter()
+            .enumerate()
+            .map(|(i, price)| {
+                ReplayEvent::Market(MarketData {
+                    symbol: "SOL/USDC".to_string(),
+                    price: *price,
+                    volume: 1_000_000.0,
+                    timestamp: start + chrono::Duration::seconds(i as i64),
+                    source: DataSource::Replay,
+                    price_change_1h: 0.0,
+                    price_change_24h: 0.0,
+                })
+            })
+            .collect();
+        StressScenarioLibrary::new()
+            .with_scenario(RecordedScenario::new("crash", StressKind::FlashCrash, events))
+    }
+
+    fn baseline() -> BacktestConfig {
+        let mut config = BacktestConfig {
+            initial_capital: 1_000_000.0,
+            close_positions_at_end: false,
+            ..Default::default()
+        };
+        config.risk_params.min_confidence_threshold = 0.5;
+        config
+    }
+
+    #[test]
+    fn test_apply_plan_to_backtest() {
+        let base = BacktestConfig::default();
+        let plan = plan(vec![
+            mutation("risk_thresholds.max_drawdown", -0.1, MutationType::Decrease),
+            mutation("position_sizing.exposure_limit_per_token", 0.25, MutationType::Increase),
+            mutation("hft_params.slippage_bps", 80.0, MutationType::Replace),
+            mutation("hft_params.aggression", 0.05, MutationType::Increase),
+            mutation("risk_thresholds.max_drawdown_buffer", 0.05, MutationType::Increase),
+        ]);
+
+        let (config, unmapped) = apply_plan_to_backtest(&base, &plan);
+
+        // The fractional drawdown target lands on the percent limit: 7.5% → 6.75%
+        let shield = &config.risk_params.kinetic_shield;
+        assert!((shield.daily_drawdown_limit - 6.75).abs() < 1e-4);
+        assert!((shield.exposure_limit_per_token - 0.15).abs() < 1e-6);
+        assert_eq!(config.fill.slippage_bps, 80.0);
+        // Only exact targets are applied
+        assert_eq!(unmapped, ["hft_params.aggression", "risk_thresholds.max_drawdown_buffer"]);
+        assert_eq!(
+            base.risk_params.kinetic_shield.daily_drawdown_limit,
+            BacktestConfig::default().risk_params.kinetic_shield.daily_drawdown_limit
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validate_plan_replays_recorded_library() -> Result<()> {
+        let plan = plan(vec![
+            mutation("position_sizing.exposure_limit_per_token", 0.25, MutationType::Increase),
+            mutation("hft_params.aggression", 0.05, MutationType::Increase),
+        ]);
+        let history = MarketHistory {
+            price_history: MutationGuard::generate_sample_prices(),
+            volatility_events: MutationGuard::generate_sample_volatility_events(),
+            black_swan_events: MutationGuard::generate_sample_black_swans(),
+        };
+        let mut guard = MutationGuard::new().with_scenario_library(crash_library(), baseline());
+
+        let result = guard.validate_plan(&plan, &history).await?;
+
+        let stress = &result.stress_test_result;
+        let names: Vec<_> = stress
+            .scenario_results
+            .iter()
+            .map(|r| r.scenario_name.as_str())
+            .collect();
+        assert_eq!(names, ["crash"]);
+        // Measured on the mutated config, deeper than the baseline
+        let (candidate, _) = apply_plan_to_backtest(&baseline(), &plan);
+        let expected = crash_library().replay(&candidate).await?;
+        let unmutated = crash_library().replay(&baseline()).await?;
+        assert_eq!(stress.max_projected_drawdown, expected[0].max_drawdown);
+        assert!(stress.max_projected_drawdown > unmutated[0].max_drawdown);
+        // The episode ends below its peak, so the plan cannot pass
+        assert!(!stress.scenario_results[0].recovered);
+        assert!(!stress.passed);
+        assert!(!result.passed);
+        assert!(result
+            .warnings
+            .iter()
+            .any(|w| w.contains("hft_params.aggression") && w.contains("no pipeline parameter")));
+        Ok(())
+    }
 }
@@ -0,0 +1,183 @@
+//! MEMCOIN STRATEGY EVENT FUZZING
+//!
+//! Feeds generated `MarketEvent` streams through the strategy registry and
+//! checks that routing honours subscriptions and strategies never emit
+//! malformed signals.
+
+use proptest::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+use overmind_protocol::modules::data_ingestor::{DataSource, MarketData};
+use overmind_protocol::modules::memcoin_strategies::{
+    death_spiral_intercept::PanicSellEvent, ArbitrageOpportunity, LiquidityEvent, MarketEvent,
+    NewPoolEvent, SocialSignal, StrategyEntryConfig, StrategyRegistry, StrategyRegistryConfig,
+    WhaleAction, WhaleTransaction,
+};
+use overmind_protocol::modules::strategy::StrategyType;
+
+fn token() -> impl Strategy<Value = String> {
+    prop::sample::select(vec!["BONK", "WIF", "POPCAT", "MEW"]).prop_map(str::to_string)
+}
+
+fn social(token: String, intensity: f32, sentiment: f32, mentions_count: u32) -> SocialSignal {
+    SocialSignal {
+        token,
+        intensity,
+        sentiment,
+        mentions_count,
+        source: "twitter".to_string(),
+        timestamp: chrono::Utc::now(),
+    }
+}
+
+fn market_event() -> impl Strategy<Value = MarketEvent> {
+    prop_oneof![
+        (token(), 1e-6f64..1_000.0, 0.0f64..1e7, -90.0f64..90.0, -90.0f64..300.0).prop_map(
+            |(symbol, price, volume, price_change_1h, price_change_24h)| {
+                MarketEvent::Tick(MarketData {
+                    symbol,
+                    price,
+                    volume,
+                    timestamp: chrono::Utc::now(),
+                    source: DataSource::Replay,
+                    price_change_1h,
+                    price_change_24h,
+                })
+            }
+        ),
+        (token(), 0.0f64..500.0, 0.0f64..1.0, 0.0f64..1.0).prop_map(
+            |(mint, delta, velocity, volatility)| {
+                MarketEvent::Liquidity(LiquidityEvent {
+                    mint,
+                    delta,
+                    velocity,
+                    volatility,
+                    timestamp: chrono::Utc::now(),
+                })
+            }
+        ),
+        (token(), 0.0f32..100.0, -1.0f32..1.0, 0u32..500)
+            .prop_map(|(t, i, s, m)| MarketEvent::Social(social(t, i, s, m))),
+        (token(), 0.0f32..100.0, -1.0f32..1.0, 0u32..500)
+            .prop_map(|(t, i, s, m)| MarketEvent::ViralMeme(social(t, i, s, m))),
+        (
+            token(),
+            prop::sample::select(vec!["whale_a", "whale_b", "minnow"]),
+            0.0f64..10_000.0,
+            0u8..3
+        )
+            .prop_map(|(token, wallet, amount, action)| {
+                MarketEvent::Whale(WhaleTransaction {
+                    signature: uuid::Uuid::new_v4().to_string(),
+                    wallet: wallet.to_string(),
+                    token,
+                    amount,
+                    action: match action {
+                        0 => WhaleAction::Accumulation,
+                        1 => WhaleAction::PreDump,
+                        _ => WhaleAction::Idle,
+                    },
+                    timestamp: chrono::Utc::now(),
+                })
+            }),
+        (token(), 0.0f32..50.0, 0.0f32..90.0).prop_map(|(token, volume_percentage, price_drop)| {
+            MarketEvent::PanicSell(PanicSellEvent {
+                token,
+                volume_percentage,
+                price_drop,
+                sell_transactions: vec![],
+                timestamp: chrono::Utc::now(),
+            })
+        }),
+        (token(), 0.0f64..1_000.0, 1e-6f64..10.0).prop_map(
+            |(token_mint, initial_liquidity, initial_price)| {
+                MarketEvent::NewPool(NewPoolEvent {
+                    pool_address: uuid::Uuid::new_v4().to_string(),
+                    token_mint,
+                    quote_mint: "So11111111111111111111111111111111111111112".to_string(),
+                    dex: "raydium".to_string(),
+                    initial_liquidity,
+                    initial_price,
+                    timestamp: chrono::Utc::now(),
+                })
+            }
+        ),
+        (token(), 1e-6f64..10.0, 0.0f64..0.1, 0.0f64..1_000.0).prop_map(
+            |(token, buy_price, spread, max_size)| {
+                let sell_price = buy_price * (1.0 + spread);
+                MarketEvent::Arbitrage(ArbitrageOpportunity {
+                    token,
+                    buy_dex: "raydium".to_string(),
+                    sell_dex: "orca".to_string(),
+                    buy_price,
+                    sell_price,
+                    profit_bps: (spread * 10_000.0) as u32,
+                    max_size,
+                    timestamp: chrono::Utc::now(),
+                })
+            }
+        ),
+    ]
+}
+
+async fn registry() -> StrategyRegistry {
+    let mut config = StrategyRegistryConfig::default();
+    let mut phoenix = StrategyEntryConfig::new("phoenix");
+    phoenix.kind = Some("phoenix_engine".to_string());
+    config.strategies.push(phoenix);
+
+    let mut registry = StrategyRegistry::new(10_000.0);
+    registry.load(&config).await.unwrap();
+    registry.activate_all().await.unwrap();
+    registry
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn events_reach_only_subscribed_strategies(events in prop::collection::vec(market_event(), 1..40)) {
+        // Paused clock: simulated bundle submissions sleep, so let time auto-advance
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .start_paused(true)
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let registry = registry().await;
+            let types: HashMap<String, StrategyType> = registry
+                .list()
+                .into_iter()
+                .map(|info| (info.name, info.strategy_type))
+                .collect();
+
+            for event in &events {
+                let allowed: HashSet<StrategyType> = registry
+                    .subscribers(event.kind())
+                    .into_iter()
+                    .map(|name| types[name].clone())
+                    .collect();
+
+                for signal in registry.dispatch(event).await {
+                    prop_assert!(
+                        allowed.contains(&signal.strategy_type),
+                        "{:?} answered a {} event",
+                        signal.strategy_type,
+                        event.kind()
+                    );
+                    prop_assert!(signal.quantity.is_finite() && signal.quantity >= 0.0);
+                    prop_assert!(signal.confidence.is_finite());
+                }
+            }
+            Ok(())
+        })?;
+    }
+
+    #[test]
+    fn events_round_trip_through_json(event in market_event()) {
+        let json = serde_json::to_string(&event).unwrap();
+        let decoded: MarketEvent = serde_json::from_str(&json).unwrap();
+        prop_assert_eq!(decoded.kind(), event.kind());
+        prop_assert_eq!(decoded.token(), event.token());
+    }
+}